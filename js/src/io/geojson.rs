@@ -41,6 +41,6 @@ pub fn write_geojson(table: Table) -> WasmResult<Vec<u8>> {
     let (schema, batches) = table.into_inner();
     let rust_table = geoarrow::table::Table::try_new(batches, schema)?;
     let mut output_file: Vec<u8> = vec![];
-    _write_geojson(rust_table, &mut output_file)?;
+    _write_geojson(rust_table, &mut output_file, Default::default())?;
     Ok(output_file)
 }
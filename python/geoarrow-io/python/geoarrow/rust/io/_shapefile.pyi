@@ -3,7 +3,7 @@ from __future__ import annotations
 from pathlib import Path
 from typing import Union
 
-from arro3.core import Table
+from arro3.core import RecordBatchReader
 from geoarrow.rust.core.enums import CoordType
 from geoarrow.rust.core.types import CoordTypeT
 
@@ -12,17 +12,17 @@ def read_shapefile(
     *,
     batch_size: int = 65536,
     coord_type: CoordType | CoordTypeT | None = None,
-) -> Table:
+) -> RecordBatchReader:
     """
-    Read a Shapefile into an Arrow Table.
+    Read a Shapefile as a stream of Arrow record batches.
 
-    The returned Arrow table will have geometry information in native GeoArrow encoding.
+    The returned batches will have geometry information in native GeoArrow encoding.
 
     Args:
         shp_file: the path to the `.shp` file
 
     Other args:
-        batch_size: the number of rows to include in each internal batch of the table.
+        batch_size: the number of rows to include in each batch.
         coord_type: The coordinate type. Defaults to None.
 
     """
@@ -56,6 +56,7 @@ pub fn read_csv(
         quote,
         terminator,
         comment,
+        ..Default::default()
     };
     let reader = CSVReader::try_new(file, options)?;
 
@@ -78,6 +79,6 @@ pub fn read_csv(
 #[pyfunction]
 #[pyo3(signature = (table, file))]
 pub fn write_csv(table: AnyRecordBatch, file: FileWriter) -> PyGeoArrowResult<()> {
-    csv::write_csv(table.into_reader()?, file)?;
+    csv::write_csv(table.into_reader()?, file, Default::default())?;
     Ok(())
 }
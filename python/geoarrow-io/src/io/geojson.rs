@@ -17,6 +17,6 @@ pub fn read_geojson(mut file: FileReader, batch_size: usize) -> PyGeoArrowResult
 
 #[pyfunction]
 pub fn write_geojson(table: PyRecordBatchReader, file: FileWriter) -> PyGeoArrowResult<()> {
-    _write_geojson(table.into_reader()?, file)?;
+    _write_geojson(table.into_reader()?, file, Default::default())?;
     Ok(())
 }
@@ -17,6 +17,6 @@ pub fn read_geojson_lines(mut file: FileReader, batch_size: usize) -> PyGeoArrow
 
 #[pyfunction]
 pub fn write_geojson_lines(table: AnyRecordBatch, file: FileWriter) -> PyGeoArrowResult<()> {
-    _write_geojson_lines(table.into_reader()?, file)?;
+    _write_geojson_lines(table.into_reader()?, file, Default::default())?;
     Ok(())
 }
@@ -340,7 +340,7 @@ impl ParquetDataset {
             .to_stream_builders(
                 |path| ParquetObjectReader::new(self.store.clone(), path.into()),
                 geo_options,
-            )
+            )?
             .into_iter()
             .map(|builder| builder.build())
             .collect()
@@ -169,6 +169,7 @@ pub fn write_parquet(
         encoding: encoding.into(),
         crs_transform: Some(Box::new(PyprojCRSTransform::new())),
         writer_properties: Some(props.build()),
+        ..Default::default()
     };
     _write_geoparquet(table.into_reader()?, file, &options)?;
     Ok(())
@@ -1,12 +1,10 @@
-use std::fs::File;
-use std::io::BufReader;
 use std::path::PathBuf;
 
 use crate::error::PyGeoArrowResult;
-use crate::util::to_arro3_table;
-use geoarrow::io::shapefile::{ShapefileReaderOptions, read_shapefile as _read_shapefile};
+use geoarrow::io::shapefile::{ShapefileReader, ShapefileReaderOptions};
 use pyo3::prelude::*;
-use pyo3_arrow::export::Arro3Table;
+use pyo3_arrow::PyRecordBatchReader;
+use pyo3_arrow::export::Arro3RecordBatchReader;
 use pyo3_geoarrow::PyCoordType;
 
 #[pyfunction]
@@ -23,30 +21,17 @@ pub fn read_shapefile(
     shp_path: PathBuf,
     batch_size: usize,
     coord_type: PyCoordType,
-) -> PyGeoArrowResult<Arro3Table> {
+) -> PyGeoArrowResult<Arro3RecordBatchReader> {
     let shp_path = shp_path.canonicalize()?;
-    let mut dbf_path = shp_path.clone();
-    dbf_path.set_extension("dbf");
-
-    let mut prj_path = shp_path.clone();
-    prj_path.set_extension("prj");
-
-    let mut crs: Option<String> = None;
-    if let Ok(content) = std::fs::read(&prj_path) {
-        if let Ok(content) = String::from_utf8(content) {
-            crs = Some(content);
-        }
-    }
 
+    // The CRS and encoding are read from the .prj and .cpg files next to the .shp
     let options = ShapefileReaderOptions {
-        crs,
         batch_size: Some(batch_size),
         coord_type: coord_type.into(),
+        ..Default::default()
     };
 
-    let shp_file = BufReader::new(File::open(shp_path)?);
-    let dbf_file = BufReader::new(File::open(dbf_path)?);
-
-    let table = _read_shapefile(shp_file, dbf_file, options)?;
-    Ok(to_arro3_table(table))
+    let reader = ShapefileReader::open(shp_path, options)?;
+    let batch_reader = PyRecordBatchReader::new(Box::new(reader.batches()));
+    Ok(Arro3RecordBatchReader::from(batch_reader))
 }
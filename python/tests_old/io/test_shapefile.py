@@ -7,7 +7,7 @@ from pyproj import CRS
 def test_read_shapefile():
     shp_path = geodatasets.get_path("ny.bb")
 
-    table = read_shapefile(shp_path)
+    table = read_shapefile(shp_path).read_all()
     crs = get_crs(table)
     assert crs is not None
 
@@ -19,7 +19,7 @@ use crate::trait_::{ArrayAccessor, GeometryArrayBuilder};
 pub struct MultiPointBuilder {
     data_type: MultiPointType,
 
-    coords: CoordBufferBuilder,
+    pub(crate) coords: CoordBufferBuilder,
 
     geom_offsets: OffsetsBuilder<i32>,
 
@@ -1,11 +1,13 @@
 //! Helper functions for downcasting [`dyn GeoArrowArray`][GeoArrowArray] to concrete types.
 
+use std::fmt::Write;
 use std::sync::Arc;
 
-use arrow_array::OffsetSizeTrait;
-use arrow_array::builder::GenericStringBuilder;
+use arrow_array::builder::{GenericBinaryBuilder, GenericStringBuilder};
 use arrow_array::cast::AsArray;
-use geoarrow_schema::WkbType;
+use arrow_array::{GenericStringArray, OffsetSizeTrait};
+use geoarrow_schema::{Crs, Metadata, WkbType};
+pub use wkb::Endianness;
 
 use crate::array::*;
 use crate::builder::{
@@ -16,6 +18,12 @@ use crate::error::{GeoArrowError, Result};
 use crate::trait_::GeoArrowArray;
 use crate::{ArrayAccessor, GeoArrowType};
 
+mod polyline;
+mod twkb;
+
+pub use polyline::{from_polyline, to_polyline};
+pub use twkb::{TwkbEncodeOptions, from_twkb, to_twkb};
+
 /// Helpers for downcasting a [`GeoArrowArray`] to a concrete implementation.
 ///
 /// ```
@@ -531,6 +539,361 @@ pub fn from_wkt<O: OffsetSizeTrait>(
     Ok(result)
 }
 
+/// The dialect of WKB written by [`to_wkb_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WkbDialect {
+    /// ISO WKB, where Z and M dimensions add 1000, 2000 or 3000 to the geometry type code.
+    Iso,
+
+    /// Extended WKB (EWKB) as used by PostGIS, where Z, M and SRID flags are set in the high bits
+    /// of the geometry type code, and an SRID may follow the geometry type.
+    Extended,
+}
+
+/// Options for writing WKB with [`to_wkb_with_options`].
+#[derive(Debug, Clone, Copy)]
+pub struct WkbEncodeOptions {
+    /// The WKB dialect to write.
+    ///
+    /// Defaults to [`WkbDialect::Iso`].
+    pub dialect: WkbDialect,
+
+    /// The byte order to write.
+    ///
+    /// Defaults to [`Endianness::LittleEndian`].
+    pub endianness: Endianness,
+
+    /// The SRID to embed in each EWKB geometry.
+    ///
+    /// If `None`, the SRID is taken from the array's CRS when it is an SRID or an `EPSG`
+    /// authority code, then from the SRID embedded in each input EWKB geometry, and otherwise
+    /// omitted. This is ignored when writing ISO WKB.
+    pub srid: Option<i32>,
+}
+
+impl Default for WkbEncodeOptions {
+    fn default() -> Self {
+        Self {
+            dialect: WkbDialect::Iso,
+            endianness: Endianness::LittleEndian,
+            srid: None,
+        }
+    }
+}
+
+/// Convert a [GeoArrowArray] to a [WkbArray] with the given WKB dialect and byte order.
+///
+/// Input WKB arrays, whether ISO WKB or EWKB, are transcoded without parsing their geometries.
+pub fn to_wkb_with_options<O: OffsetSizeTrait>(
+    arr: &dyn GeoArrowArray,
+    options: &WkbEncodeOptions,
+) -> Result<WkbArray<O>> {
+    let wkb_arr = to_wkb::<O>(arr)?;
+    let srid = match options.dialect {
+        WkbDialect::Iso => None,
+        WkbDialect::Extended => options
+            .srid
+            .or_else(|| arr.data_type().metadata().crs().srid()),
+    };
+
+    let mut builder = GenericBinaryBuilder::<O>::with_capacity(wkb_arr.len(), 0);
+    let mut buf = vec![];
+    for value in wkb_arr.array.iter() {
+        if let Some(value) = value {
+            buf.clear();
+            // Carry through the SRID of input EWKB when none is given
+            let srid = match options.dialect {
+                WkbDialect::Iso => None,
+                WkbDialect::Extended => srid.or(WkbHeader::try_new(value)?.srid),
+            };
+            transcode_wkb(value, &mut buf, options, srid)?;
+            builder.append_value(&buf);
+        } else {
+            builder.append_null();
+        }
+    }
+    Ok(WkbArray {
+        data_type: wkb_arr.data_type,
+        array: builder.finish(),
+    })
+}
+
+/// The SRID embedded in the EWKB geometries of a [WkbArray], if any.
+///
+/// Geometries without an embedded SRID are ignored. Returns an error if geometries have different
+/// SRIDs.
+pub fn ewkb_srid<O: OffsetSizeTrait>(arr: &WkbArray<O>) -> Result<Option<i32>> {
+    let mut srid = None;
+    for value in arr.array.iter().flatten() {
+        if let Some(value_srid) = WkbHeader::try_new(value)?.srid {
+            match srid {
+                Some(srid) if srid != value_srid => {
+                    return Err(GeoArrowError::General(format!(
+                        "Mixed SRIDs {} and {} in EWKB array",
+                        srid, value_srid
+                    )));
+                }
+                _ => srid = Some(value_srid),
+            }
+        }
+    }
+    Ok(srid)
+}
+
+/// Parse a [WkbArray] of ISO WKB or EWKB to a [GeoArrowArray] with the designated
+/// [GeoArrowType].
+///
+/// If the geometries embed an SRID, it is stored as the CRS of the new array, and an error is
+/// returned if geometries have different SRIDs. Otherwise the GeoArrow metadata on the new array
+/// is taken from `to_type`, as in [`from_wkb`].
+pub fn from_ewkb<O: OffsetSizeTrait>(
+    arr: &WkbArray<O>,
+    to_type: GeoArrowType,
+) -> Result<Arc<dyn GeoArrowArray>> {
+    let to_type = match ewkb_srid(arr)? {
+        Some(srid) => {
+            let metadata =
+                Metadata::new(Crs::from_srid(srid.to_string()), to_type.metadata().edges());
+            to_type.with_metadata(Arc::new(metadata))
+        }
+        None => to_type,
+    };
+
+    let mut is_extended = false;
+    for value in arr.array.iter().flatten() {
+        is_extended |= WkbHeader::try_new(value)?.extended;
+    }
+    if is_extended {
+        let iso_arr = to_wkb_with_options::<O>(arr, &Default::default())?;
+        from_wkb(&iso_arr, to_type)
+    } else {
+        from_wkb(arr, to_type)
+    }
+}
+
+/// Encode a [WkbArray] as upper-case hex strings, as used by PostGIS and many text formats.
+pub fn wkb_to_hex<O: OffsetSizeTrait>(arr: &WkbArray<O>) -> GenericStringArray<O> {
+    let mut builder = GenericStringBuilder::<O>::with_capacity(
+        arr.len(),
+        arr.buffer_lengths().buffer_capacity() * 2,
+    );
+    for value in arr.array.iter() {
+        if let Some(value) = value {
+            for byte in value {
+                write!(builder, "{:02X}", byte).unwrap();
+            }
+            builder.append_value("");
+        } else {
+            builder.append_null();
+        }
+    }
+    builder.finish()
+}
+
+/// Decode hex strings of ISO WKB or EWKB to a [WkbArray].
+///
+/// Upper and lower-case hex digits are accepted, and surrounding whitespace is ignored.
+pub fn wkb_from_hex<O: OffsetSizeTrait>(
+    arr: &GenericStringArray<O>,
+    typ: WkbType,
+) -> Result<WkbArray<O>> {
+    let mut builder = GenericBinaryBuilder::<O>::with_capacity(arr.len(), arr.values().len() / 2);
+    let mut buf = vec![];
+    for value in arr.iter() {
+        if let Some(value) = value {
+            buf.clear();
+            decode_hex(value, &mut buf)?;
+            builder.append_value(&buf);
+        } else {
+            builder.append_null();
+        }
+    }
+    Ok(WkbArray {
+        data_type: typ,
+        array: builder.finish(),
+    })
+}
+
+fn decode_hex(value: &str, buf: &mut Vec<u8>) -> Result<()> {
+    let value = value.trim();
+    let invalid = || GeoArrowError::General(format!("Invalid hex WKB: '{}'", value));
+    if value.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    for i in (0..value.len()).step_by(2) {
+        let byte = value
+            .get(i..i + 2)
+            .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+            .ok_or_else(invalid)?;
+        buf.push(byte);
+    }
+    Ok(())
+}
+
+const EWKB_Z_FLAG: u32 = 0x8000_0000;
+const EWKB_M_FLAG: u32 = 0x4000_0000;
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+/// The header of an ISO WKB or EWKB geometry.
+#[derive(Debug, Clone, Copy)]
+struct WkbHeader {
+    little_endian: bool,
+    /// The geometry type code without dimension flags, e.g. `1` for a point.
+    geometry_type: u32,
+    has_z: bool,
+    has_m: bool,
+    /// Whether the geometry type uses EWKB flags.
+    extended: bool,
+    srid: Option<i32>,
+    /// The length of the header in bytes.
+    len: usize,
+}
+
+impl WkbHeader {
+    fn try_new(buf: &[u8]) -> Result<Self> {
+        let little_endian = match buf.first() {
+            Some(0) => false,
+            Some(1) => true,
+            _ => return Err(GeoArrowError::General("Invalid WKB byte order".to_string())),
+        };
+        let type_code = read_u32(buf, 1, little_endian)?;
+        let extended = type_code & (EWKB_Z_FLAG | EWKB_M_FLAG | EWKB_SRID_FLAG) != 0;
+        let iso_code = type_code & 0x0FFF_FFFF;
+        let srid = if type_code & EWKB_SRID_FLAG != 0 {
+            Some(read_u32(buf, 5, little_endian)? as i32)
+        } else {
+            None
+        };
+        Ok(Self {
+            little_endian,
+            geometry_type: iso_code % 1000,
+            has_z: type_code & EWKB_Z_FLAG != 0 || matches!(iso_code / 1000, 1 | 3),
+            has_m: type_code & EWKB_M_FLAG != 0 || matches!(iso_code / 1000, 2 | 3),
+            extended,
+            srid,
+            len: if srid.is_some() { 9 } else { 5 },
+        })
+    }
+}
+
+fn read_u32(buf: &[u8], offset: usize, little_endian: bool) -> Result<u32> {
+    let bytes: [u8; 4] = buf
+        .get(offset..offset + 4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| GeoArrowError::General("Unexpected end of WKB buffer".to_string()))?;
+    Ok(if little_endian {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+fn write_u32(out: &mut Vec<u8>, value: u32, endianness: Endianness) {
+    match endianness {
+        Endianness::LittleEndian => out.extend_from_slice(&value.to_le_bytes()),
+        Endianness::BigEndian => out.extend_from_slice(&value.to_be_bytes()),
+    }
+}
+
+/// Transcode one WKB geometry to the dialect and byte order of `options`, returning the number of
+/// bytes read from `buf`.
+///
+/// The SRID is only written to the outermost geometry.
+fn transcode_wkb(
+    buf: &[u8],
+    out: &mut Vec<u8>,
+    options: &WkbEncodeOptions,
+    srid: Option<i32>,
+) -> Result<usize> {
+    let header = WkbHeader::try_new(buf)?;
+    let little_endian = header.little_endian;
+
+    out.push(match options.endianness {
+        Endianness::BigEndian => 0,
+        Endianness::LittleEndian => 1,
+    });
+    let type_code = match options.dialect {
+        WkbDialect::Iso => {
+            header.geometry_type
+                + match (header.has_z, header.has_m) {
+                    (false, false) => 0,
+                    (true, false) => 1000,
+                    (false, true) => 2000,
+                    (true, true) => 3000,
+                }
+        }
+        WkbDialect::Extended => {
+            let mut type_code = header.geometry_type;
+            if header.has_z {
+                type_code |= EWKB_Z_FLAG;
+            }
+            if header.has_m {
+                type_code |= EWKB_M_FLAG;
+            }
+            if srid.is_some() {
+                type_code |= EWKB_SRID_FLAG;
+            }
+            type_code
+        }
+    };
+    write_u32(out, type_code, options.endianness);
+    if let Some(srid) = srid {
+        write_u32(out, srid as u32, options.endianness);
+    }
+
+    let mut offset = header.len;
+    // Copy a number of 8-byte coordinate values, swapping their byte order if needed
+    let copy_coords = |offset: &mut usize, num_coords: usize, out: &mut Vec<u8>| {
+        let num_values = num_coords * (2 + header.has_z as usize + header.has_m as usize);
+        let values = buf
+            .get(*offset..*offset + num_values * 8)
+            .ok_or_else(|| GeoArrowError::General("Unexpected end of WKB buffer".to_string()))?;
+        if little_endian == (options.endianness == Endianness::LittleEndian) {
+            out.extend_from_slice(values);
+        } else {
+            for value in values.chunks_exact(8) {
+                out.extend(value.iter().rev());
+            }
+        }
+        *offset += num_values * 8;
+        Ok::<_, GeoArrowError>(())
+    };
+    let copy_count = |offset: &mut usize, out: &mut Vec<u8>| {
+        let count = read_u32(buf, *offset, little_endian)?;
+        write_u32(out, count, options.endianness);
+        *offset += 4;
+        Ok::<_, GeoArrowError>(count as usize)
+    };
+
+    match header.geometry_type {
+        1 => copy_coords(&mut offset, 1, out)?,
+        2 => {
+            let num_coords = copy_count(&mut offset, out)?;
+            copy_coords(&mut offset, num_coords, out)?;
+        }
+        3 => {
+            let num_rings = copy_count(&mut offset, out)?;
+            for _ in 0..num_rings {
+                let num_coords = copy_count(&mut offset, out)?;
+                copy_coords(&mut offset, num_coords, out)?;
+            }
+        }
+        4..=7 => {
+            let num_geometries = copy_count(&mut offset, out)?;
+            for _ in 0..num_geometries {
+                offset += transcode_wkb(&buf[offset..], out, options, None)?;
+            }
+        }
+        geometry_type => {
+            return Err(GeoArrowError::General(format!(
+                "Unsupported WKB geometry type {}",
+                geometry_type
+            )));
+        }
+    }
+    Ok(offset)
+}
+
 /// Re-export symbols needed for downcast macros
 ///
 /// Name follows `serde` convention
@@ -860,7 +1223,116 @@ mod test {
         }
     }
 
+    // Start EWKB and hex WKB tests
+    #[test]
+    fn test_round_trip_ewkb() {
+        let options = WkbEncodeOptions {
+            dialect: WkbDialect::Extended,
+            endianness: Endianness::BigEndian,
+            srid: Some(4326),
+        };
+        for dim in [
+            Dimension::XY,
+            Dimension::XYZ,
+            Dimension::XYM,
+            Dimension::XYZM,
+        ] {
+            let arr = test::geometrycollection::array(CoordType::Interleaved, dim, false);
+
+            let ewkb_arr = to_wkb_with_options::<i32>(&arr, &options).unwrap();
+            assert_eq!(ewkb_srid(&ewkb_arr).unwrap(), Some(4326));
+
+            let iso_arr = to_wkb_with_options::<i32>(&ewkb_arr, &Default::default()).unwrap();
+            assert_eq!(iso_arr, to_wkb::<i32>(&arr).unwrap());
+
+            let arr2 = from_ewkb(&ewkb_arr, arr.data_type().clone()).unwrap();
+            assert_eq!(
+                arr2.data_type().metadata().crs(),
+                &Crs::from_srid("4326".to_string())
+            );
+            assert_eq!(&arr, arr2.as_geometry_collection());
+        }
+    }
+
+    #[test]
+    fn test_ewkb_srid_from_crs() {
+        let metadata = Arc::new(Metadata::new(
+            Crs::from_authority_code("EPSG:3857".to_string()),
+            None,
+        ));
+        let arr = test::point::array(CoordType::Interleaved, Dimension::XY);
+        let arr = arr.with_metadata(metadata);
+        let options = WkbEncodeOptions {
+            dialect: WkbDialect::Extended,
+            ..Default::default()
+        };
+        let ewkb_arr = to_wkb_with_options::<i32>(&arr, &options).unwrap();
+        assert_eq!(ewkb_srid(&ewkb_arr).unwrap(), Some(3857));
+
+        let wkb_arr = to_wkb_with_options::<i32>(&arr, &Default::default()).unwrap();
+        assert_eq!(ewkb_srid(&wkb_arr).unwrap(), None);
+    }
+
+    #[test]
+    fn test_ewkb_srid_carried_through() {
+        let options = WkbEncodeOptions {
+            dialect: WkbDialect::Extended,
+            srid: Some(4326),
+            ..Default::default()
+        };
+        let arr = test::point::array(CoordType::Interleaved, Dimension::XY);
+        let ewkb_arr = to_wkb_with_options::<i32>(&arr, &options).unwrap();
+
+        let options = WkbEncodeOptions {
+            dialect: WkbDialect::Extended,
+            endianness: Endianness::BigEndian,
+            srid: None,
+        };
+        let ewkb_arr2 = to_wkb_with_options::<i32>(&ewkb_arr, &options).unwrap();
+        assert_eq!(ewkb_srid(&ewkb_arr2).unwrap(), Some(4326));
+    }
+
+    #[test]
+    fn test_ewkb_mixed_srid() {
+        let hex = GenericStringArray::<i32>::from(vec![
+            "0101000020E6100000000000000000F03F0000000000000040",
+            "0101000020110F0000000000000000F03F0000000000000040",
+        ]);
+        let wkb_arr = wkb_from_hex(&hex, Default::default()).unwrap();
+        assert!(ewkb_srid(&wkb_arr).is_err());
+        assert!(from_ewkb(&wkb_arr, wkb_arr.data_type().clone()).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_hex_wkb() {
+        let arr = test::polygon::array(CoordType::Interleaved, Dimension::XYZ);
+        let wkb_arr = to_wkb::<i32>(&arr).unwrap();
+
+        let hex = wkb_to_hex(&wkb_arr);
+        assert!(
+            hex.iter()
+                .flatten()
+                .all(|value| !value.contains(char::is_lowercase))
+        );
+        let wkb_arr2 = wkb_from_hex(&hex, wkb_arr.data_type().clone()).unwrap();
+        assert_eq!(wkb_arr, wkb_arr2);
+
+        let lower =
+            GenericStringArray::<i32>::from(vec!["0101000000000000000000f03f0000000000000040"]);
+        assert!(wkb_from_hex(&lower, Default::default()).is_ok());
+
+        let padded =
+            GenericStringArray::<i32>::from(vec![" 0101000000000000000000F03F0000000000000040\n"]);
+        assert!(wkb_from_hex(&padded, Default::default()).is_ok());
+
+        for invalid in ["01010", "0g"] {
+            let invalid = GenericStringArray::<i32>::from(vec![invalid]);
+            assert!(wkb_from_hex(&invalid, Default::default()).is_err());
+        }
+    }
+
     // Start WKT round trip tests
+
     #[test]
     fn test_round_trip_wkt_point() {
         for coord_type in [CoordType::Interleaved, CoordType::Separated] {
@@ -0,0 +1,185 @@
+//! Encoding and decoding of [encoded polylines](https://developers.google.com/maps/documentation/utilities/polylinealgorithm).
+
+use std::fmt::Write;
+
+use arrow_array::builder::GenericStringBuilder;
+use arrow_array::{GenericStringArray, OffsetSizeTrait};
+use geo_traits::{CoordTrait, LineStringTrait};
+use geoarrow_schema::{Dimension, LineStringType};
+
+use crate::ArrayAccessor;
+use crate::array::LineStringArray;
+use crate::builder::LineStringBuilder;
+use crate::error::{GeoArrowError, Result};
+use crate::trait_::GeoArrowArray;
+
+/// Encode a [LineStringArray] as a string array of [encoded polylines](https://developers.google.com/maps/documentation/utilities/polylinealgorithm).
+///
+/// `precision` is the number of decimal digits kept for each coordinate, usually 5 as used by
+/// Google or 6 as used by OSRM and Valhalla. Each coordinate is encoded as a latitude (Y) and
+/// longitude (X) pair; the line strings must be 2-dimensional.
+pub fn to_polyline<O: OffsetSizeTrait>(
+    arr: &LineStringArray,
+    precision: u32,
+) -> Result<GenericStringArray<O>> {
+    if arr.data_type.dimension() != Dimension::XY {
+        return Err(GeoArrowError::General(format!(
+            "Encoded polylines only support XY line strings, got {:?}",
+            arr.data_type.dimension()
+        )));
+    }
+    let scale = scale(precision)?;
+
+    let mut builder = GenericStringBuilder::<O>::with_capacity(arr.len(), 0);
+    let mut encoded = String::new();
+    for maybe_line_string in arr.iter() {
+        if let Some(line_string) = maybe_line_string {
+            encoded.clear();
+            let mut prev = [0; 2];
+            for coord in line_string?.coords() {
+                for (i, value) in [coord.y(), coord.x()].into_iter().enumerate() {
+                    let value = (value * scale).round() as i64;
+                    encode_value(&mut encoded, value - prev[i]);
+                    prev[i] = value;
+                }
+            }
+            builder.append_value(&encoded);
+        } else {
+            builder.append_null();
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Decode a string array of [encoded polylines](https://developers.google.com/maps/documentation/utilities/polylinealgorithm)
+/// to a [LineStringArray].
+///
+/// `precision` must match the precision the polylines were encoded with. `typ` must be
+/// 2-dimensional.
+pub fn from_polyline<O: OffsetSizeTrait>(
+    arr: &GenericStringArray<O>,
+    precision: u32,
+    typ: LineStringType,
+) -> Result<LineStringArray> {
+    if typ.dimension() != Dimension::XY {
+        return Err(GeoArrowError::General(format!(
+            "Encoded polylines only support XY line strings, got {:?}",
+            typ.dimension()
+        )));
+    }
+    let scale = scale(precision)?;
+
+    let mut builder = LineStringBuilder::new(typ);
+    for value in arr.iter() {
+        if let Some(value) = value {
+            let mut bytes = value.bytes();
+            let mut prev = [0; 2];
+            let mut num_coords = 0;
+            while let Some(lat) = decode_value(&mut bytes, value)? {
+                let lng = decode_value(&mut bytes, value)?.ok_or_else(|| invalid(value))?;
+                prev[0] += lat;
+                prev[1] += lng;
+                builder.push_coord(&(prev[1] as f64 / scale, prev[0] as f64 / scale))?;
+                num_coords += 1;
+            }
+            builder.try_push_length(num_coords)?;
+        } else {
+            builder.push_null();
+        }
+    }
+    Ok(builder.finish())
+}
+
+fn scale(precision: u32) -> Result<f64> {
+    if precision > 10 {
+        return Err(GeoArrowError::General(format!(
+            "Polyline precision must be at most 10, got {}",
+            precision
+        )));
+    }
+    Ok(10f64.powi(precision as i32))
+}
+
+fn encode_value(out: &mut String, value: i64) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+    while value >= 0x20 {
+        out.write_char(char::from(((0x20 | (value & 0x1F)) + 63) as u8))
+            .unwrap();
+        value >>= 5;
+    }
+    out.write_char(char::from((value + 63) as u8)).unwrap();
+}
+
+/// Decode the next value of a polyline, or `None` at the end of the polyline.
+fn decode_value(bytes: &mut impl Iterator<Item = u8>, polyline: &str) -> Result<Option<i64>> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    let mut started = false;
+    loop {
+        let Some(byte) = bytes.next() else {
+            if started {
+                return Err(invalid(polyline));
+            }
+            return Ok(None);
+        };
+        started = true;
+        let chunk = byte
+            .checked_sub(63)
+            .filter(|chunk| *chunk < 0x40 && shift < 64)
+            .ok_or_else(|| invalid(polyline))? as u64;
+        value |= (chunk & 0x1F) << shift;
+        if chunk < 0x20 {
+            return Ok(Some(((value >> 1) as i64) ^ -((value & 1) as i64)));
+        }
+        shift += 5;
+    }
+}
+
+fn invalid(polyline: &str) -> GeoArrowError {
+    GeoArrowError::General(format!("Invalid encoded polyline: '{}'", polyline))
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::StringArray;
+    use geoarrow_schema::CoordType;
+
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn encode() {
+        let typ = LineStringType::new(CoordType::Interleaved, Dimension::XY, Default::default());
+        let mut builder = LineStringBuilder::new(typ.clone());
+        for (x, y) in [(-120.2, 38.5), (-120.95, 40.7), (-126.453, 43.252)] {
+            builder.push_coord(&(x, y)).unwrap();
+        }
+        builder.try_push_length(3).unwrap();
+        builder.push_null();
+        let arr = builder.finish();
+
+        let polylines = to_polyline::<i32>(&arr, 5).unwrap();
+        assert_eq!(polylines.value(0), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+        assert!(polylines.is_null(1));
+
+        let arr2 = from_polyline(&polylines, 5, typ).unwrap();
+        assert_eq!(arr, arr2);
+    }
+
+    #[test]
+    fn round_trip() {
+        let arr = test::linestring::array(CoordType::Separated, Dimension::XY);
+        let polylines = to_polyline::<i64>(&arr, 6).unwrap();
+        let arr2 = from_polyline(&polylines, 6, arr.data_type.clone()).unwrap();
+        assert_eq!(arr, arr2);
+    }
+
+    #[test]
+    fn invalid_polyline() {
+        let typ = LineStringType::new(CoordType::Interleaved, Dimension::XY, Default::default());
+        let polylines = StringArray::from(vec!["_p~iF~ps|U_"]);
+        assert!(from_polyline(&polylines, 5, typ.clone()).is_err());
+        let polylines = StringArray::from(vec!["_p~iF"]);
+        assert!(from_polyline(&polylines, 5, typ).is_err());
+    }
+}
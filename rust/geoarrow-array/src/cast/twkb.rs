@@ -0,0 +1,1662 @@
+//! Encoding and decoding of [TWKB](https://github.com/TWKB/Specification/blob/master/twkb.md).
+
+use std::sync::Arc;
+
+use arrow_array::builder::{GenericBinaryBuilder, GenericStringBuilder};
+use arrow_array::{GenericBinaryArray, GenericStringArray, OffsetSizeTrait};
+use arrow_buffer::OffsetBuffer;
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait,
+    LineTrait, MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+    RectTrait, TriangleTrait, UnimplementedLine, UnimplementedRect, UnimplementedTriangle,
+};
+use geoarrow_schema::Dimension;
+
+use crate::array::{CoordBuffer, WktArray};
+use crate::builder::{
+    CoordBufferBuilder, GeometryBuilder, GeometryCollectionBuilder, LineStringBuilder,
+    MultiLineStringBuilder, MultiPointBuilder, MultiPolygonBuilder, OffsetsBuilder, PointBuilder,
+    PolygonBuilder, WkbBuilder,
+};
+use crate::cast::AsGeoArrowArray;
+use crate::error::{GeoArrowError, Result};
+use crate::trait_::{GeoArrowArray, GeometryArrayBuilder};
+use crate::util::OffsetBufferUtils;
+use crate::{ArrayAccessor, GeoArrowType};
+
+const BBOX_FLAG: u8 = 0x01;
+const SIZE_FLAG: u8 = 0x02;
+const ID_LIST_FLAG: u8 = 0x04;
+const EXTENDED_PRECISION_FLAG: u8 = 0x08;
+const EMPTY_FLAG: u8 = 0x10;
+
+/// Options for encoding TWKB with [`to_twkb`].
+#[derive(Debug, Clone, Copy)]
+pub struct TwkbEncodeOptions {
+    /// The number of decimal digits to keep for X and Y coordinates, between -8 and 7.
+    ///
+    /// Negative values round coordinates to tens, hundreds, and so on. Defaults to 6.
+    pub xy_precision: i8,
+
+    /// The number of decimal digits to keep for Z values, between 0 and 7.
+    ///
+    /// Defaults to 0.
+    pub z_precision: u8,
+
+    /// The number of decimal digits to keep for M values, between 0 and 7.
+    ///
+    /// Defaults to 0.
+    pub m_precision: u8,
+
+    /// Whether to write the bounding box of each geometry in its header.
+    ///
+    /// Defaults to `false`.
+    pub include_bbox: bool,
+
+    /// Whether to write the size in bytes of each geometry in its header, which lets readers skip
+    /// geometries without decoding them.
+    ///
+    /// Defaults to `false`.
+    pub include_size: bool,
+}
+
+impl Default for TwkbEncodeOptions {
+    fn default() -> Self {
+        Self {
+            xy_precision: 6,
+            z_precision: 0,
+            m_precision: 0,
+            include_bbox: false,
+            include_size: false,
+        }
+    }
+}
+
+impl TwkbEncodeOptions {
+    fn validate(&self) -> Result<()> {
+        if !(-8..=7).contains(&self.xy_precision) {
+            return Err(GeoArrowError::General(format!(
+                "TWKB XY precision must be between -8 and 7, got {}",
+                self.xy_precision
+            )));
+        }
+        if self.z_precision > 7 || self.m_precision > 7 {
+            return Err(GeoArrowError::General(format!(
+                "TWKB Z and M precision must be between 0 and 7, got {} and {}",
+                self.z_precision, self.m_precision
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Convert a [GeoArrowArray] to a binary array of [TWKB](https://github.com/TWKB/Specification/blob/master/twkb.md).
+///
+/// Coordinates are rounded to the precision given in `options`, so the encoding is lossy unless
+/// the input coordinates have no more decimal digits than that. Single-type native arrays are
+/// encoded straight from their coordinate buffers and offsets.
+pub fn to_twkb<O: OffsetSizeTrait>(
+    arr: &dyn GeoArrowArray,
+    options: &TwkbEncodeOptions,
+) -> Result<GenericBinaryArray<O>> {
+    options.validate()?;
+
+    use GeoArrowType::*;
+    match arr.data_type() {
+        Point(typ) => {
+            let point_arr = arr.as_point();
+            native_to_twkb(arr, typ.dimension(), 1, options, |encoder, i| {
+                let values = coord_values(&point_arr.coords, i);
+                if !(values[0].is_nan() && values[1].is_nan()) {
+                    encoder.write_values(values)?;
+                }
+                Ok(())
+            })
+        }
+        LineString(typ) => {
+            let line_string_arr = arr.as_line_string();
+            native_to_twkb(arr, typ.dimension(), 2, options, |encoder, i| {
+                let (start, end) = line_string_arr.geom_offsets.start_end(i);
+                if start < end {
+                    encoder.write_coords(&line_string_arr.coords, start, end)?;
+                }
+                Ok(())
+            })
+        }
+        Polygon(typ) => {
+            let polygon_arr = arr.as_polygon();
+            native_to_twkb(arr, typ.dimension(), 3, options, |encoder, i| {
+                let (start, end) = polygon_arr.geom_offsets.start_end(i);
+                if start < end {
+                    encoder.write_rings(
+                        &polygon_arr.coords,
+                        &polygon_arr.ring_offsets,
+                        start,
+                        end,
+                    )?;
+                }
+                Ok(())
+            })
+        }
+        MultiPoint(typ) => {
+            let multi_point_arr = arr.as_multi_point();
+            native_to_twkb(arr, typ.dimension(), 4, options, |encoder, i| {
+                let (start, end) = multi_point_arr.geom_offsets.start_end(i);
+                if start < end {
+                    encoder.write_count(end - start);
+                    for coord_idx in start..end {
+                        let values = coord_values(&multi_point_arr.coords, coord_idx);
+                        if values[0].is_nan() && values[1].is_nan() {
+                            return Err(empty_point_in_multi_point());
+                        }
+                        encoder.write_values(values)?;
+                    }
+                }
+                Ok(())
+            })
+        }
+        MultiLineString(typ) => {
+            let multi_line_string_arr = arr.as_multi_line_string();
+            native_to_twkb(arr, typ.dimension(), 5, options, |encoder, i| {
+                let (start, end) = multi_line_string_arr.geom_offsets.start_end(i);
+                if start < end {
+                    encoder.write_count(end - start);
+                    for line_string_idx in start..end {
+                        let (coord_start, coord_end) = multi_line_string_arr
+                            .ring_offsets
+                            .start_end(line_string_idx);
+                        encoder.write_coords(
+                            &multi_line_string_arr.coords,
+                            coord_start,
+                            coord_end,
+                        )?;
+                    }
+                }
+                Ok(())
+            })
+        }
+        MultiPolygon(typ) => {
+            let multi_polygon_arr = arr.as_multi_polygon();
+            native_to_twkb(arr, typ.dimension(), 6, options, |encoder, i| {
+                let (start, end) = multi_polygon_arr.geom_offsets.start_end(i);
+                if start < end {
+                    encoder.write_count(end - start);
+                    for polygon_idx in start..end {
+                        let (ring_start, ring_end) =
+                            multi_polygon_arr.polygon_offsets.start_end(polygon_idx);
+                        encoder.write_rings(
+                            &multi_polygon_arr.coords,
+                            &multi_polygon_arr.ring_offsets,
+                            ring_start,
+                            ring_end,
+                        )?;
+                    }
+                }
+                Ok(())
+            })
+        }
+        Geometry(_) => impl_to_twkb(arr.as_geometry(), options),
+        GeometryCollection(_) => impl_to_twkb(arr.as_geometry_collection(), options),
+        Rect(_) => impl_to_twkb(arr.as_rect(), options),
+        Wkb(_) => impl_to_twkb(arr.as_wkb::<i32>(), options),
+        LargeWkb(_) => impl_to_twkb(arr.as_wkb::<i64>(), options),
+        Wkt(_) => impl_to_twkb(arr.as_wkt::<i32>(), options),
+        LargeWkt(_) => impl_to_twkb(arr.as_wkt::<i64>(), options),
+    }
+}
+
+/// Encode a single-type native array, writing the body of each valid geometry with `write_body`.
+fn native_to_twkb<O: OffsetSizeTrait>(
+    arr: &dyn GeoArrowArray,
+    dim: Dimension,
+    geometry_type: u8,
+    options: &TwkbEncodeOptions,
+    mut write_body: impl FnMut(&mut TwkbEncoder, usize) -> Result<()>,
+) -> Result<GenericBinaryArray<O>> {
+    let (has_z, has_m) = match dim {
+        Dimension::XY => (false, false),
+        Dimension::XYZ => (true, false),
+        Dimension::XYM => (false, true),
+        Dimension::XYZM => (true, true),
+    };
+    let mut builder = GenericBinaryBuilder::<O>::with_capacity(arr.len(), 0);
+    let mut buf = vec![];
+    for i in 0..arr.len() {
+        if arr.is_null(i) {
+            builder.append_null();
+        } else {
+            buf.clear();
+            let mut encoder = TwkbEncoder::new(has_z, has_m, options);
+            write_body(&mut encoder, i)?;
+            encoder.finish(&mut buf, geometry_type, options);
+            builder.append_value(&buf);
+        }
+    }
+    Ok(builder.finish())
+}
+
+fn impl_to_twkb<'a, O: OffsetSizeTrait>(
+    geo_arr: &'a impl ArrayAccessor<'a>,
+    options: &TwkbEncodeOptions,
+) -> Result<GenericBinaryArray<O>> {
+    let mut builder = GenericBinaryBuilder::<O>::with_capacity(geo_arr.len(), 0);
+    let mut buf = vec![];
+    for maybe_geom in geo_arr.iter() {
+        if let Some(geom) = maybe_geom {
+            buf.clear();
+            write_geometry(&mut buf, &geom?, options)?;
+            builder.append_value(&buf);
+        } else {
+            builder.append_null();
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Parse a binary array of [TWKB](https://github.com/TWKB/Specification/blob/master/twkb.md) to
+/// a [GeoArrowArray] with the designated [GeoArrowType].
+///
+/// Single-type native arrays are decoded straight into the coordinate and offset buffers of their
+/// builder. For the other types, each geometry is decoded on its own and pushed into the builder
+/// for `to_type`. Note that the GeoArrow metadata on the new array is taken from `to_type`.
+///
+/// Decoding to a `Rect` array is not supported: TWKB has no box type, and converting polygons to
+/// their bounding boxes would silently change any polygon that isn't an axis-aligned rectangle.
+pub fn from_twkb<O: OffsetSizeTrait>(
+    arr: &GenericBinaryArray<O>,
+    to_type: GeoArrowType,
+) -> Result<Arc<dyn GeoArrowArray>> {
+    use GeoArrowType::*;
+    let result: Arc<dyn GeoArrowArray> = match to_type {
+        Point(typ) => {
+            Arc::new(decode_native(arr, PointBuilder::with_capacity(typ, arr.len()))?.finish())
+        }
+        LineString(typ) => Arc::new(decode_native(arr, LineStringBuilder::new(typ))?.finish()),
+        Polygon(typ) => Arc::new(decode_native(arr, PolygonBuilder::new(typ))?.finish()),
+        MultiPoint(typ) => Arc::new(decode_native(arr, MultiPointBuilder::new(typ))?.finish()),
+        MultiLineString(typ) => {
+            Arc::new(decode_native(arr, MultiLineStringBuilder::new(typ))?.finish())
+        }
+        MultiPolygon(typ) => Arc::new(decode_native(arr, MultiPolygonBuilder::new(typ))?.finish()),
+        GeometryCollection(typ) => {
+            let mut builder = GeometryCollectionBuilder::new(typ);
+            decode_each(arr, |geom| builder.push_geometry(geom))?;
+            Arc::new(builder.finish())
+        }
+        Rect(_) => {
+            return Err(GeoArrowError::General(
+                "Cannot decode TWKB to a Rect array, since TWKB has no box type".to_string(),
+            ));
+        }
+        Geometry(typ) => {
+            let mut builder = GeometryBuilder::new(typ);
+            decode_each(arr, |geom| builder.push_geometry(geom))?;
+            Arc::new(builder.finish())
+        }
+        Wkb(typ) => {
+            let mut builder = WkbBuilder::<i32>::new(typ);
+            decode_each(arr, |geom| {
+                builder.push_geometry(geom);
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        LargeWkb(typ) => {
+            let mut builder = WkbBuilder::<i64>::new(typ);
+            decode_each(arr, |geom| {
+                builder.push_geometry(geom);
+                Ok(())
+            })?;
+            Arc::new(builder.finish())
+        }
+        Wkt(typ) => Arc::new(WktArray::new(
+            decode_wkt::<_, i32>(arr)?,
+            typ.metadata().clone(),
+        )),
+        LargeWkt(typ) => Arc::new(WktArray::new(
+            decode_wkt::<_, i64>(arr)?,
+            typ.metadata().clone(),
+        )),
+    };
+    Ok(result)
+}
+
+/// Decode each geometry straight into the buffers of a single-type native builder.
+fn decode_native<O: OffsetSizeTrait, B: TwkbBuilder>(
+    arr: &GenericBinaryArray<O>,
+    mut builder: B,
+) -> Result<B> {
+    for value in arr.iter() {
+        if let Some(buf) = value {
+            let mut reader = TwkbReader { buf, pos: 0 };
+            let header = reader.read_header()?;
+            builder.push_twkb(&mut reader, header)?;
+        } else {
+            builder.push_null();
+        }
+    }
+    Ok(builder)
+}
+
+/// Decode each geometry on its own and pass it to `push`, which gets `None` for null values.
+fn decode_each<O: OffsetSizeTrait>(
+    arr: &GenericBinaryArray<O>,
+    mut push: impl FnMut(Option<&TwkbGeometry>) -> Result<()>,
+) -> Result<()> {
+    for value in arr.iter() {
+        let geom = value
+            .map(|buf| TwkbReader { buf, pos: 0 }.read_geometry())
+            .transpose()?;
+        push(geom.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Decode each geometry and write it as WKT.
+fn decode_wkt<O: OffsetSizeTrait, O2: OffsetSizeTrait>(
+    arr: &GenericBinaryArray<O>,
+) -> Result<GenericStringArray<O2>> {
+    let mut builder = GenericStringBuilder::<O2>::with_capacity(arr.len(), 0);
+    decode_each(arr, |geom| {
+        if let Some(geom) = geom {
+            wkt::to_wkt::write_geometry(&mut builder, geom)?;
+            builder.append_value("");
+        } else {
+            builder.append_null();
+        }
+        Ok(())
+    })?;
+    Ok(builder.finish())
+}
+
+fn empty_point_in_multi_point() -> GeoArrowError {
+    GeoArrowError::General("TWKB cannot encode empty points within a MultiPoint".to_string())
+}
+
+/// The values of the `i`th coordinate of a [CoordBuffer], in `x, y[, z][, m]` order.
+fn coord_values(coords: &CoordBuffer, i: usize) -> [f64; 4] {
+    let mut values = [0.0; 4];
+    match coords {
+        CoordBuffer::Interleaved(coords) => {
+            let size = coords.dim.size();
+            values[..size].copy_from_slice(&coords.coords[i * size..(i + 1) * size]);
+        }
+        CoordBuffer::Separated(coords) => {
+            for (n, value) in values.iter_mut().enumerate().take(coords.dim.size()) {
+                *value = coords.buffers[n][i];
+            }
+        }
+    }
+    values
+}
+
+fn write_uvarint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_varint(out: &mut Vec<u8>, value: i64) {
+    write_uvarint(out, ((value << 1) ^ (value >> 63)) as u64)
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// The minimum and maximum scaled values of each dimension.
+type Bbox = ([i64; 4], [i64; 4]);
+
+/// Writes the delta-encoded body of a single TWKB geometry.
+struct TwkbEncoder {
+    has_z: bool,
+    has_m: bool,
+    dims: usize,
+    scales: [f64; 4],
+    prev: [i64; 4],
+    bbox: Option<Bbox>,
+    body: Vec<u8>,
+}
+
+impl TwkbEncoder {
+    fn new(has_z: bool, has_m: bool, options: &TwkbEncodeOptions) -> Self {
+        let xy_scale = 10f64.powi(options.xy_precision as i32);
+        let z_scale = 10f64.powi(options.z_precision as i32);
+        let m_scale = 10f64.powi(options.m_precision as i32);
+        Self {
+            has_z,
+            has_m,
+            dims: 2 + has_z as usize + has_m as usize,
+            scales: [
+                xy_scale,
+                xy_scale,
+                if has_z { z_scale } else { m_scale },
+                m_scale,
+            ],
+            prev: [0; 4],
+            bbox: None,
+            body: vec![],
+        }
+    }
+
+    /// Write the scaled values of a coordinate as deltas from the previous coordinate.
+    ///
+    /// Returns an error if a scaled value doesn't fit in an `i64`, which a cast would saturate.
+    fn write_values(&mut self, values: [f64; 4]) -> Result<()> {
+        let (mins, maxs) = self.bbox.get_or_insert(([i64::MAX; 4], [i64::MIN; 4]));
+        for i in 0..self.dims {
+            let scaled = (values[i] * self.scales[i]).round();
+            if !(i64::MIN as f64..i64::MAX as f64).contains(&scaled) {
+                return Err(GeoArrowError::General(format!(
+                    "Coordinate value {} is out of range for TWKB with a scale of {}",
+                    values[i], self.scales[i]
+                )));
+            }
+            let value = scaled as i64;
+            // Deltas wrap like the decoder's running sums, so any pair of values round trips
+            write_varint(&mut self.body, value.wrapping_sub(self.prev[i]));
+            self.prev[i] = value;
+            mins[i] = mins[i].min(value);
+            maxs[i] = maxs[i].max(value);
+        }
+        Ok(())
+    }
+
+    fn write_coord(&mut self, coord: &impl CoordTrait<T = f64>) -> Result<()> {
+        let mut values = [0.0; 4];
+        for (i, value) in values.iter_mut().enumerate().take(self.dims) {
+            *value = coord.nth_or_panic(i);
+        }
+        self.write_values(values)
+    }
+
+    fn write_count(&mut self, count: usize) {
+        write_uvarint(&mut self.body, count as u64);
+    }
+
+    /// Write the count and values of the coordinates from `start` to `end` of a [CoordBuffer].
+    fn write_coords(&mut self, coords: &CoordBuffer, start: usize, end: usize) -> Result<()> {
+        self.write_count(end - start);
+        for i in start..end {
+            self.write_values(coord_values(coords, i))?;
+        }
+        Ok(())
+    }
+
+    /// Write the rings from `start` to `end` of a polygon, given the offsets of their coordinates.
+    fn write_rings(
+        &mut self,
+        coords: &CoordBuffer,
+        ring_offsets: &OffsetBuffer<i32>,
+        start: usize,
+        end: usize,
+    ) -> Result<()> {
+        self.write_count(end - start);
+        for ring_idx in start..end {
+            let (coord_start, coord_end) = ring_offsets.start_end(ring_idx);
+            self.write_coords(coords, coord_start, coord_end)?;
+        }
+        Ok(())
+    }
+
+    fn write_line_string(&mut self, line_string: &impl LineStringTrait<T = f64>) -> Result<()> {
+        self.write_count(line_string.num_coords());
+        for coord in line_string.coords() {
+            self.write_coord(&coord)?;
+        }
+        Ok(())
+    }
+
+    fn write_polygon(&mut self, polygon: &impl PolygonTrait<T = f64>) -> Result<()> {
+        if let Some(exterior) = polygon.exterior() {
+            self.write_count(1 + polygon.num_interiors());
+            self.write_line_string(&exterior)?;
+            for interior in polygon.interiors() {
+                self.write_line_string(&interior)?;
+            }
+        } else {
+            self.write_count(0);
+        }
+        Ok(())
+    }
+
+    fn write_point(&mut self, point: &impl PointTrait<T = f64>) -> Result<()> {
+        let coord = point.coord().ok_or_else(empty_point_in_multi_point)?;
+        self.write_coord(&coord)
+    }
+
+    fn merge_bbox(&mut self, (other_mins, other_maxs): Bbox) {
+        let (mins, maxs) = self.bbox.get_or_insert(([i64::MAX; 4], [i64::MIN; 4]));
+        for i in 0..self.dims {
+            mins[i] = mins[i].min(other_mins[i]);
+            maxs[i] = maxs[i].max(other_maxs[i]);
+        }
+    }
+
+    /// Write the header and body of the geometry, returning its bounding box if it isn't empty.
+    fn finish(
+        self,
+        out: &mut Vec<u8>,
+        geometry_type: u8,
+        options: &TwkbEncodeOptions,
+    ) -> Option<Bbox> {
+        let is_empty = self.bbox.is_none();
+        let xy_precision = options.xy_precision as i64;
+        out.push(geometry_type | ((((xy_precision << 1) ^ (xy_precision >> 63)) as u8) << 4));
+
+        let mut metadata = 0;
+        if options.include_bbox && !is_empty {
+            metadata |= BBOX_FLAG;
+        }
+        if options.include_size {
+            metadata |= SIZE_FLAG;
+        }
+        if self.has_z || self.has_m {
+            metadata |= EXTENDED_PRECISION_FLAG;
+        }
+        if is_empty {
+            metadata |= EMPTY_FLAG;
+        }
+        out.push(metadata);
+        if self.has_z || self.has_m {
+            out.push(
+                (self.has_z as u8)
+                    | ((self.has_m as u8) << 1)
+                    | (options.z_precision << 2)
+                    | (options.m_precision << 5),
+            );
+        }
+
+        let mut rest = vec![];
+        if let (true, Some((mins, maxs))) = (options.include_bbox, self.bbox) {
+            for i in 0..self.dims {
+                write_varint(&mut rest, mins[i]);
+                write_varint(&mut rest, maxs[i].wrapping_sub(mins[i]));
+            }
+        }
+        if !is_empty {
+            rest.extend_from_slice(&self.body);
+        }
+        if options.include_size {
+            write_uvarint(out, rest.len() as u64);
+        }
+        out.extend_from_slice(&rest);
+
+        self.bbox
+    }
+}
+
+/// Write one TWKB geometry with its header, returning its bounding box if it isn't empty.
+fn write_geometry(
+    out: &mut Vec<u8>,
+    geom: &impl GeometryTrait<T = f64>,
+    options: &TwkbEncodeOptions,
+) -> Result<Option<Bbox>> {
+    let (has_z, has_m) = match geom.dim() {
+        Dimensions::Xy | Dimensions::Unknown(2) => (false, false),
+        Dimensions::Xyz | Dimensions::Unknown(3) => (true, false),
+        Dimensions::Xym => (false, true),
+        Dimensions::Xyzm | Dimensions::Unknown(4) => (true, true),
+        Dimensions::Unknown(n) => {
+            return Err(GeoArrowError::General(format!(
+                "Unsupported number of dimensions for TWKB: {}",
+                n
+            )));
+        }
+    };
+    let mut encoder = TwkbEncoder::new(has_z, has_m, options);
+
+    let geometry_type = match geom.as_type() {
+        GeometryType::Point(point) => {
+            if let Some(coord) = point.coord() {
+                if !(coord.x().is_nan() && coord.y().is_nan()) {
+                    encoder.write_coord(&coord)?;
+                }
+            }
+            1
+        }
+        GeometryType::LineString(line_string) => {
+            if line_string.num_coords() > 0 {
+                encoder.write_line_string(line_string)?;
+            }
+            2
+        }
+        GeometryType::Polygon(polygon) => {
+            if polygon.exterior().is_some() {
+                encoder.write_polygon(polygon)?;
+            }
+            3
+        }
+        GeometryType::MultiPoint(multi_point) => {
+            if multi_point.num_points() > 0 {
+                encoder.write_count(multi_point.num_points());
+                for point in multi_point.points() {
+                    encoder.write_point(&point)?;
+                }
+            }
+            4
+        }
+        GeometryType::MultiLineString(multi_line_string) => {
+            if multi_line_string.num_line_strings() > 0 {
+                encoder.write_count(multi_line_string.num_line_strings());
+                for line_string in multi_line_string.line_strings() {
+                    encoder.write_line_string(&line_string)?;
+                }
+            }
+            5
+        }
+        GeometryType::MultiPolygon(multi_polygon) => {
+            if multi_polygon.num_polygons() > 0 {
+                encoder.write_count(multi_polygon.num_polygons());
+                for polygon in multi_polygon.polygons() {
+                    encoder.write_polygon(&polygon)?;
+                }
+            }
+            6
+        }
+        GeometryType::GeometryCollection(geometry_collection) => {
+            if geometry_collection.num_geometries() > 0 {
+                encoder.write_count(geometry_collection.num_geometries());
+                for child in geometry_collection.geometries() {
+                    // Each member of a collection is a complete TWKB geometry with its own header
+                    if let Some(bbox) = write_geometry(&mut encoder.body, &child, options)? {
+                        encoder.merge_bbox(bbox);
+                    }
+                }
+            }
+            7
+        }
+        GeometryType::Rect(rect) => {
+            let mut min = [0.0; 4];
+            let mut max = [0.0; 4];
+            for i in 0..encoder.dims {
+                min[i] = rect.min().nth_or_panic(i);
+                max[i] = rect.max().nth_or_panic(i);
+            }
+            // A single ring through the corners of the rect
+            let corner = |x: &[f64; 4], y: &[f64; 4]| [x[0], y[1], y[2], y[3]];
+            encoder.write_count(1);
+            encoder.write_count(5);
+            for values in [
+                corner(&min, &min),
+                corner(&max, &min),
+                corner(&max, &max),
+                corner(&min, &max),
+                corner(&min, &min),
+            ] {
+                encoder.write_values(values)?;
+            }
+            3
+        }
+        GeometryType::Triangle(triangle) => {
+            encoder.write_count(1);
+            encoder.write_count(4);
+            encoder.write_coord(&triangle.first())?;
+            encoder.write_coord(&triangle.second())?;
+            encoder.write_coord(&triangle.third())?;
+            encoder.write_coord(&triangle.first())?;
+            3
+        }
+        GeometryType::Line(line) => {
+            encoder.write_count(2);
+            encoder.write_coord(&line.start())?;
+            encoder.write_coord(&line.end())?;
+            2
+        }
+    };
+
+    Ok(encoder.finish(out, geometry_type, options))
+}
+
+/// The precision and running values used to decode the coordinates of one TWKB geometry.
+struct CoordDecoder {
+    dim: Dimensions,
+    dims: usize,
+    scales: [f64; 4],
+    prev: [i64; 4],
+}
+
+/// Decodes TWKB geometries.
+struct TwkbReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl TwkbReader<'_> {
+    fn read_u8(&mut self) -> Result<u8> {
+        let value = *self.buf.get(self.pos).ok_or(GeoArrowError::General(
+            "Unexpected end of TWKB buffer".to_string(),
+        ))?;
+        self.pos += 1;
+        Ok(value)
+    }
+
+    fn read_uvarint(&mut self) -> Result<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift >= 64 {
+                return Err(GeoArrowError::General("Invalid TWKB varint".to_string()));
+            }
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_varint(&mut self) -> Result<i64> {
+        Ok(unzigzag(self.read_uvarint()?))
+    }
+
+    fn read_count(&mut self) -> Result<usize> {
+        let count = u32::try_from(self.read_uvarint()?)
+            .map_err(|_| GeoArrowError::General("Invalid TWKB count".to_string()))?;
+        Ok(count as usize)
+    }
+
+    fn read_coord(&mut self, decoder: &mut CoordDecoder) -> Result<TwkbCoord> {
+        let mut values = [0.0; 4];
+        for (i, value) in values.iter_mut().enumerate().take(decoder.dims) {
+            decoder.prev[i] = decoder.prev[i].wrapping_add(self.read_varint()?);
+            *value = decoder.prev[i] as f64 / decoder.scales[i];
+        }
+        Ok(TwkbCoord {
+            values,
+            dim: decoder.dim,
+        })
+    }
+
+    fn read_line_string(&mut self, decoder: &mut CoordDecoder) -> Result<TwkbLineString> {
+        let num_coords = self.read_count()?;
+        let coords = (0..num_coords)
+            .map(|_| self.read_coord(decoder))
+            .collect::<Result<_>>()?;
+        Ok(TwkbLineString {
+            coords,
+            dim: decoder.dim,
+        })
+    }
+
+    fn read_polygon(&mut self, decoder: &mut CoordDecoder) -> Result<TwkbPolygon> {
+        let num_rings = self.read_count()?;
+        let rings = (0..num_rings)
+            .map(|_| self.read_line_string(decoder))
+            .collect::<Result<_>>()?;
+        Ok(TwkbPolygon {
+            rings,
+            dim: decoder.dim,
+        })
+    }
+
+    /// Read the header of a geometry, up to the start of its body.
+    fn read_header(&mut self) -> Result<TwkbHeader> {
+        let type_and_precision = self.read_u8()?;
+        let geometry_type = type_and_precision & 0x0F;
+        let xy_precision = unzigzag((type_and_precision >> 4) as u64) as i32;
+        let metadata = self.read_u8()?;
+        let (has_z, has_m, z_precision, m_precision) = if metadata & EXTENDED_PRECISION_FLAG != 0 {
+            let extended = self.read_u8()?;
+            (
+                extended & 0x01 != 0,
+                extended & 0x02 != 0,
+                ((extended >> 2) & 0x07) as i32,
+                ((extended >> 5) & 0x07) as i32,
+            )
+        } else {
+            (false, false, 0, 0)
+        };
+        let dims = 2 + has_z as usize + has_m as usize;
+
+        if metadata & SIZE_FLAG != 0 {
+            self.read_uvarint()?;
+        }
+        if metadata & BBOX_FLAG != 0 {
+            for _ in 0..dims * 2 {
+                self.read_varint()?;
+            }
+        }
+
+        let xy_scale = 10f64.powi(xy_precision);
+        let z_scale = 10f64.powi(z_precision);
+        let m_scale = 10f64.powi(m_precision);
+        let dim = match (has_z, has_m) {
+            (false, false) => Dimensions::Xy,
+            (true, false) => Dimensions::Xyz,
+            (false, true) => Dimensions::Xym,
+            (true, true) => Dimensions::Xyzm,
+        };
+        let decoder = CoordDecoder {
+            dim,
+            dims,
+            scales: [
+                xy_scale,
+                xy_scale,
+                if has_z { z_scale } else { m_scale },
+                m_scale,
+            ],
+            prev: [0; 4],
+        };
+
+        if !(1..=7).contains(&geometry_type) {
+            return Err(GeoArrowError::General(format!(
+                "Unsupported TWKB geometry type {}",
+                geometry_type
+            )));
+        }
+
+        Ok(TwkbHeader {
+            geometry_type,
+            is_empty: metadata & EMPTY_FLAG != 0,
+            has_id_list: metadata & ID_LIST_FLAG != 0,
+            decoder,
+        })
+    }
+
+    /// Read the number of parts of a multi geometry or collection, skipping its ID list.
+    fn read_num_parts(&mut self, header: &TwkbHeader) -> Result<usize> {
+        let num_parts = if header.is_empty {
+            0
+        } else {
+            self.read_count()?
+        };
+        if header.has_id_list {
+            for _ in 0..num_parts {
+                self.read_varint()?;
+            }
+        }
+        Ok(num_parts)
+    }
+
+    fn read_geometry(&mut self) -> Result<TwkbGeometry> {
+        let mut header = self.read_header()?;
+        let dim = header.decoder.dim;
+        let is_empty = header.is_empty;
+        let decoder = &mut header.decoder;
+        let geometry = match header.geometry_type {
+            1 => TwkbGeometry::Point(TwkbPoint {
+                coord: if is_empty {
+                    None
+                } else {
+                    Some(self.read_coord(decoder)?)
+                },
+                dim,
+            }),
+            2 if is_empty => TwkbGeometry::LineString(TwkbLineString {
+                coords: vec![],
+                dim,
+            }),
+            2 => TwkbGeometry::LineString(self.read_line_string(decoder)?),
+            3 if is_empty => TwkbGeometry::Polygon(TwkbPolygon { rings: vec![], dim }),
+            3 => TwkbGeometry::Polygon(self.read_polygon(decoder)?),
+            geometry_type => {
+                let num_parts = self.read_num_parts(&header)?;
+                let decoder = &mut header.decoder;
+                match geometry_type {
+                    4 => TwkbGeometry::MultiPoint(TwkbMultiPoint {
+                        points: (0..num_parts)
+                            .map(|_| {
+                                Ok(TwkbPoint {
+                                    coord: Some(self.read_coord(decoder)?),
+                                    dim,
+                                })
+                            })
+                            .collect::<Result<_>>()?,
+                        dim,
+                    }),
+                    5 => TwkbGeometry::MultiLineString(TwkbMultiLineString {
+                        line_strings: (0..num_parts)
+                            .map(|_| self.read_line_string(decoder))
+                            .collect::<Result<_>>()?,
+                        dim,
+                    }),
+                    6 => TwkbGeometry::MultiPolygon(TwkbMultiPolygon {
+                        polygons: (0..num_parts)
+                            .map(|_| self.read_polygon(decoder))
+                            .collect::<Result<_>>()?,
+                        dim,
+                    }),
+                    _ => TwkbGeometry::GeometryCollection(TwkbGeometryCollection {
+                        geometries: (0..num_parts)
+                            .map(|_| self.read_geometry())
+                            .collect::<Result<_>>()?,
+                        dim,
+                    }),
+                }
+            }
+        };
+        Ok(geometry)
+    }
+
+    /// Push `num_coords` coordinates onto a coordinate buffer.
+    fn push_coords(
+        &mut self,
+        decoder: &mut CoordDecoder,
+        coords: &mut CoordBufferBuilder,
+        num_coords: usize,
+    ) -> Result<()> {
+        for _ in 0..num_coords {
+            coords.try_push_coord(&self.read_coord(decoder)?)?;
+        }
+        Ok(())
+    }
+
+    /// Push the coordinates of a line string or ring, returning their number.
+    fn push_line_string(
+        &mut self,
+        decoder: &mut CoordDecoder,
+        coords: &mut CoordBufferBuilder,
+    ) -> Result<usize> {
+        let num_coords = self.read_count()?;
+        self.push_coords(decoder, coords, num_coords)?;
+        Ok(num_coords)
+    }
+
+    /// Push the rings of a polygon and their offsets, returning the number of rings.
+    fn push_rings(
+        &mut self,
+        decoder: &mut CoordDecoder,
+        coords: &mut CoordBufferBuilder,
+        ring_offsets: &mut OffsetsBuilder<i32>,
+    ) -> Result<usize> {
+        let num_rings = self.read_count()?;
+        for _ in 0..num_rings {
+            let num_coords = self.push_line_string(decoder, coords)?;
+            ring_offsets.try_push_usize(num_coords)?;
+        }
+        Ok(num_rings)
+    }
+}
+
+/// The header of a TWKB geometry, with the decoder for the coordinates in its body.
+struct TwkbHeader {
+    geometry_type: u8,
+    is_empty: bool,
+    has_id_list: bool,
+    decoder: CoordDecoder,
+}
+
+impl TwkbHeader {
+    /// The number of parts of a geometry with the given single and multi geometry types, which
+    /// is at most one for the single type.
+    fn num_parts(
+        &self,
+        reader: &mut TwkbReader,
+        single_type: u8,
+        multi_type: u8,
+        to_type: &str,
+    ) -> Result<usize> {
+        if self.geometry_type == single_type {
+            Ok(!self.is_empty as usize)
+        } else if self.geometry_type == multi_type {
+            reader.read_num_parts(self)
+        } else {
+            Err(GeoArrowError::General(format!(
+                "Cannot decode TWKB geometry type {} to a {} array",
+                self.geometry_type, to_type
+            )))
+        }
+    }
+}
+
+/// A single-type native builder that TWKB geometries are decoded into directly.
+///
+/// Single geometries are pushed to builders of the matching multi type, and multi geometries with
+/// at most one part to builders of the matching single type, as with `push_geometry`.
+trait TwkbBuilder: GeometryArrayBuilder {
+    /// Decode the body of a geometry with the given header onto the end of this builder.
+    fn push_twkb(&mut self, reader: &mut TwkbReader, header: TwkbHeader) -> Result<()>;
+}
+
+/// The error for a multi geometry with more than one part, pushed to a single-type builder.
+fn too_many_parts(num_parts: usize, to_type: &str) -> GeoArrowError {
+    GeoArrowError::General(format!(
+        "Cannot decode a TWKB geometry with {} parts to a {} array",
+        num_parts, to_type
+    ))
+}
+
+impl TwkbBuilder for PointBuilder {
+    fn push_twkb(&mut self, reader: &mut TwkbReader, mut header: TwkbHeader) -> Result<()> {
+        match header.num_parts(reader, 1, 4, "Point")? {
+            0 => self.push_empty(),
+            1 => self.try_push_coord(Some(&reader.read_coord(&mut header.decoder)?))?,
+            n => return Err(too_many_parts(n, "Point")),
+        }
+        Ok(())
+    }
+}
+
+impl TwkbBuilder for LineStringBuilder {
+    fn push_twkb(&mut self, reader: &mut TwkbReader, mut header: TwkbHeader) -> Result<()> {
+        match header.num_parts(reader, 2, 5, "LineString")? {
+            0 => self.push_empty(),
+            1 => {
+                let num_coords = reader.push_line_string(&mut header.decoder, &mut self.coords)?;
+                self.try_push_length(num_coords)?;
+            }
+            n => return Err(too_many_parts(n, "LineString")),
+        }
+        Ok(())
+    }
+}
+
+impl TwkbBuilder for PolygonBuilder {
+    fn push_twkb(&mut self, reader: &mut TwkbReader, mut header: TwkbHeader) -> Result<()> {
+        match header.num_parts(reader, 3, 6, "Polygon")? {
+            0 => self.push_empty(),
+            1 => {
+                let num_rings = reader.push_rings(
+                    &mut header.decoder,
+                    &mut self.coords,
+                    &mut self.ring_offsets,
+                )?;
+                self.try_push_geom_offset(num_rings)?;
+            }
+            n => return Err(too_many_parts(n, "Polygon")),
+        }
+        Ok(())
+    }
+}
+
+impl TwkbBuilder for MultiPointBuilder {
+    fn push_twkb(&mut self, reader: &mut TwkbReader, mut header: TwkbHeader) -> Result<()> {
+        let num_points = header.num_parts(reader, 1, 4, "MultiPoint")?;
+        reader.push_coords(&mut header.decoder, &mut self.coords, num_points)?;
+        self.try_push_length(num_points)
+    }
+}
+
+impl TwkbBuilder for MultiLineStringBuilder {
+    fn push_twkb(&mut self, reader: &mut TwkbReader, mut header: TwkbHeader) -> Result<()> {
+        let num_line_strings = header.num_parts(reader, 2, 5, "MultiLineString")?;
+        for _ in 0..num_line_strings {
+            let num_coords = reader.push_line_string(&mut header.decoder, &mut self.coords)?;
+            self.try_push_ring_offset(num_coords)?;
+        }
+        self.try_push_geom_offset(num_line_strings)
+    }
+}
+
+impl TwkbBuilder for MultiPolygonBuilder {
+    fn push_twkb(&mut self, reader: &mut TwkbReader, mut header: TwkbHeader) -> Result<()> {
+        let num_polygons = header.num_parts(reader, 3, 6, "MultiPolygon")?;
+        for _ in 0..num_polygons {
+            let num_rings = reader.push_rings(
+                &mut header.decoder,
+                &mut self.coords,
+                &mut self.ring_offsets,
+            )?;
+            self.try_push_polygon_offset(num_rings)?;
+        }
+        self.try_push_geom_offset(num_polygons)
+    }
+}
+
+/// A coordinate decoded from TWKB, with its values in `x, y[, z][, m]` order.
+#[derive(Debug, Clone, Copy)]
+struct TwkbCoord {
+    values: [f64; 4],
+    dim: Dimensions,
+}
+
+impl CoordTrait for TwkbCoord {
+    type T = f64;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn nth_or_panic(&self, n: usize) -> Self::T {
+        self.values[n]
+    }
+
+    fn x(&self) -> Self::T {
+        self.values[0]
+    }
+
+    fn y(&self) -> Self::T {
+        self.values[1]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TwkbPoint {
+    coord: Option<TwkbCoord>,
+    dim: Dimensions,
+}
+
+impl PointTrait for TwkbPoint {
+    type T = f64;
+    type CoordType<'b> = TwkbCoord;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn coord(&self) -> Option<Self::CoordType<'_>> {
+        self.coord
+    }
+}
+
+#[derive(Debug)]
+struct TwkbLineString {
+    coords: Vec<TwkbCoord>,
+    dim: Dimensions,
+}
+
+impl LineStringTrait for TwkbLineString {
+    type T = f64;
+    type CoordType<'b> = TwkbCoord;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn num_coords(&self) -> usize {
+        self.coords.len()
+    }
+
+    unsafe fn coord_unchecked(&self, i: usize) -> Self::CoordType<'_> {
+        self.coords[i]
+    }
+}
+
+impl LineStringTrait for &TwkbLineString {
+    type T = f64;
+    type CoordType<'b>
+        = TwkbCoord
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn num_coords(&self) -> usize {
+        self.coords.len()
+    }
+
+    unsafe fn coord_unchecked(&self, i: usize) -> Self::CoordType<'_> {
+        self.coords[i]
+    }
+}
+
+#[derive(Debug)]
+struct TwkbPolygon {
+    rings: Vec<TwkbLineString>,
+    dim: Dimensions,
+}
+
+impl PolygonTrait for TwkbPolygon {
+    type T = f64;
+    type RingType<'b> = &'b TwkbLineString;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn exterior(&self) -> Option<Self::RingType<'_>> {
+        self.rings.first()
+    }
+
+    fn num_interiors(&self) -> usize {
+        self.rings.len().saturating_sub(1)
+    }
+
+    unsafe fn interior_unchecked(&self, i: usize) -> Self::RingType<'_> {
+        &self.rings[i + 1]
+    }
+}
+
+impl PolygonTrait for &TwkbPolygon {
+    type T = f64;
+    type RingType<'b>
+        = &'b TwkbLineString
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn exterior(&self) -> Option<Self::RingType<'_>> {
+        self.rings.first()
+    }
+
+    fn num_interiors(&self) -> usize {
+        self.rings.len().saturating_sub(1)
+    }
+
+    unsafe fn interior_unchecked(&self, i: usize) -> Self::RingType<'_> {
+        &self.rings[i + 1]
+    }
+}
+
+#[derive(Debug)]
+struct TwkbMultiPoint {
+    points: Vec<TwkbPoint>,
+    dim: Dimensions,
+}
+
+impl MultiPointTrait for TwkbMultiPoint {
+    type T = f64;
+    type PointType<'b> = TwkbPoint;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn num_points(&self) -> usize {
+        self.points.len()
+    }
+
+    unsafe fn point_unchecked(&self, i: usize) -> Self::PointType<'_> {
+        self.points[i]
+    }
+}
+
+#[derive(Debug)]
+struct TwkbMultiLineString {
+    line_strings: Vec<TwkbLineString>,
+    dim: Dimensions,
+}
+
+impl MultiLineStringTrait for TwkbMultiLineString {
+    type T = f64;
+    type LineStringType<'b> = &'b TwkbLineString;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn num_line_strings(&self) -> usize {
+        self.line_strings.len()
+    }
+
+    unsafe fn line_string_unchecked(&self, i: usize) -> Self::LineStringType<'_> {
+        &self.line_strings[i]
+    }
+}
+
+#[derive(Debug)]
+struct TwkbMultiPolygon {
+    polygons: Vec<TwkbPolygon>,
+    dim: Dimensions,
+}
+
+impl MultiPolygonTrait for TwkbMultiPolygon {
+    type T = f64;
+    type PolygonType<'b> = &'b TwkbPolygon;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn num_polygons(&self) -> usize {
+        self.polygons.len()
+    }
+
+    unsafe fn polygon_unchecked(&self, i: usize) -> Self::PolygonType<'_> {
+        &self.polygons[i]
+    }
+}
+
+#[derive(Debug)]
+struct TwkbGeometryCollection {
+    geometries: Vec<TwkbGeometry>,
+    dim: Dimensions,
+}
+
+impl GeometryCollectionTrait for TwkbGeometryCollection {
+    type T = f64;
+    type GeometryType<'b> = &'b TwkbGeometry;
+
+    fn dim(&self) -> Dimensions {
+        self.dim
+    }
+
+    fn num_geometries(&self) -> usize {
+        self.geometries.len()
+    }
+
+    unsafe fn geometry_unchecked(&self, i: usize) -> Self::GeometryType<'_> {
+        &self.geometries[i]
+    }
+}
+
+/// A geometry decoded from TWKB, which is pushed into the builder of the target array.
+#[derive(Debug)]
+enum TwkbGeometry {
+    Point(TwkbPoint),
+    LineString(TwkbLineString),
+    Polygon(TwkbPolygon),
+    MultiPoint(TwkbMultiPoint),
+    MultiLineString(TwkbMultiLineString),
+    MultiPolygon(TwkbMultiPolygon),
+    GeometryCollection(TwkbGeometryCollection),
+}
+
+impl GeometryTrait for TwkbGeometry {
+    type T = f64;
+    type PointType<'b> = TwkbPoint;
+    type LineStringType<'b> = TwkbLineString;
+    type PolygonType<'b> = TwkbPolygon;
+    type MultiPointType<'b> = TwkbMultiPoint;
+    type MultiLineStringType<'b> = TwkbMultiLineString;
+    type MultiPolygonType<'b> = TwkbMultiPolygon;
+    type GeometryCollectionType<'b> = TwkbGeometryCollection;
+    type RectType<'b> = UnimplementedRect<f64>;
+    type TriangleType<'b> = UnimplementedTriangle<f64>;
+    type LineType<'b> = UnimplementedLine<f64>;
+
+    fn dim(&self) -> Dimensions {
+        match self {
+            TwkbGeometry::Point(g) => g.dim,
+            TwkbGeometry::LineString(g) => g.dim,
+            TwkbGeometry::Polygon(g) => g.dim,
+            TwkbGeometry::MultiPoint(g) => g.dim,
+            TwkbGeometry::MultiLineString(g) => g.dim,
+            TwkbGeometry::MultiPolygon(g) => g.dim,
+            TwkbGeometry::GeometryCollection(g) => g.dim,
+        }
+    }
+
+    fn as_type(
+        &self,
+    ) -> GeometryType<
+        '_,
+        TwkbPoint,
+        TwkbLineString,
+        TwkbPolygon,
+        TwkbMultiPoint,
+        TwkbMultiLineString,
+        TwkbMultiPolygon,
+        TwkbGeometryCollection,
+        UnimplementedRect<f64>,
+        UnimplementedTriangle<f64>,
+        UnimplementedLine<f64>,
+    > {
+        match self {
+            TwkbGeometry::Point(g) => GeometryType::Point(g),
+            TwkbGeometry::LineString(g) => GeometryType::LineString(g),
+            TwkbGeometry::Polygon(g) => GeometryType::Polygon(g),
+            TwkbGeometry::MultiPoint(g) => GeometryType::MultiPoint(g),
+            TwkbGeometry::MultiLineString(g) => GeometryType::MultiLineString(g),
+            TwkbGeometry::MultiPolygon(g) => GeometryType::MultiPolygon(g),
+            TwkbGeometry::GeometryCollection(g) => GeometryType::GeometryCollection(g),
+        }
+    }
+}
+
+impl GeometryTrait for &TwkbGeometry {
+    type T = f64;
+    type PointType<'b>
+        = TwkbPoint
+    where
+        Self: 'b;
+    type LineStringType<'b>
+        = TwkbLineString
+    where
+        Self: 'b;
+    type PolygonType<'b>
+        = TwkbPolygon
+    where
+        Self: 'b;
+    type MultiPointType<'b>
+        = TwkbMultiPoint
+    where
+        Self: 'b;
+    type MultiLineStringType<'b>
+        = TwkbMultiLineString
+    where
+        Self: 'b;
+    type MultiPolygonType<'b>
+        = TwkbMultiPolygon
+    where
+        Self: 'b;
+    type GeometryCollectionType<'b>
+        = TwkbGeometryCollection
+    where
+        Self: 'b;
+    type RectType<'b>
+        = UnimplementedRect<f64>
+    where
+        Self: 'b;
+    type TriangleType<'b>
+        = UnimplementedTriangle<f64>
+    where
+        Self: 'b;
+    type LineType<'b>
+        = UnimplementedLine<f64>
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        match self {
+            TwkbGeometry::Point(g) => g.dim,
+            TwkbGeometry::LineString(g) => g.dim,
+            TwkbGeometry::Polygon(g) => g.dim,
+            TwkbGeometry::MultiPoint(g) => g.dim,
+            TwkbGeometry::MultiLineString(g) => g.dim,
+            TwkbGeometry::MultiPolygon(g) => g.dim,
+            TwkbGeometry::GeometryCollection(g) => g.dim,
+        }
+    }
+
+    fn as_type(
+        &self,
+    ) -> GeometryType<
+        '_,
+        TwkbPoint,
+        TwkbLineString,
+        TwkbPolygon,
+        TwkbMultiPoint,
+        TwkbMultiLineString,
+        TwkbMultiPolygon,
+        TwkbGeometryCollection,
+        UnimplementedRect<f64>,
+        UnimplementedTriangle<f64>,
+        UnimplementedLine<f64>,
+    > {
+        match self {
+            TwkbGeometry::Point(g) => GeometryType::Point(g),
+            TwkbGeometry::LineString(g) => GeometryType::LineString(g),
+            TwkbGeometry::Polygon(g) => GeometryType::Polygon(g),
+            TwkbGeometry::MultiPoint(g) => GeometryType::MultiPoint(g),
+            TwkbGeometry::MultiLineString(g) => GeometryType::MultiLineString(g),
+            TwkbGeometry::MultiPolygon(g) => GeometryType::MultiPolygon(g),
+            TwkbGeometry::GeometryCollection(g) => GeometryType::GeometryCollection(g),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::BinaryArray;
+    use geoarrow_schema::{BoxType, CoordType, Dimension, GeometryType, MultiPolygonType};
+
+    use super::*;
+    use crate::cast::{from_wkb, from_wkt, to_wkb};
+    use crate::test;
+
+    fn options() -> TwkbEncodeOptions {
+        TwkbEncodeOptions {
+            xy_precision: 7,
+            z_precision: 7,
+            m_precision: 7,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn encode() {
+        let arr = from_wkt(
+            &WktArray::<i32>::new(
+                vec!["LINESTRING(1 1,5 5)", "POINT(1 2)"].into(),
+                Default::default(),
+            ),
+            GeometryType::new(CoordType::Interleaved, Default::default()).into(),
+        )
+        .unwrap();
+        let options = TwkbEncodeOptions {
+            xy_precision: 0,
+            ..Default::default()
+        };
+        let twkb = to_twkb::<i32>(arr.as_ref(), &options).unwrap();
+        assert_eq!(twkb.value(0), [0x02, 0x00, 0x02, 0x02, 0x02, 0x08, 0x08]);
+        assert_eq!(twkb.value(1), [0x01, 0x00, 0x02, 0x04]);
+    }
+
+    #[test]
+    fn round_trip_polygon() {
+        for dim in [
+            Dimension::XY,
+            Dimension::XYZ,
+            Dimension::XYM,
+            Dimension::XYZM,
+        ] {
+            let arr = test::polygon::array(CoordType::Interleaved, dim);
+            let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+            let arr2 = from_twkb(&twkb, arr.data_type().clone()).unwrap();
+            assert_eq!(&arr, arr2.as_polygon());
+        }
+    }
+
+    #[test]
+    fn round_trip_geometry_collection() {
+        let options = TwkbEncodeOptions {
+            include_bbox: true,
+            include_size: true,
+            ..options()
+        };
+        for dim in [
+            Dimension::XY,
+            Dimension::XYZ,
+            Dimension::XYM,
+            Dimension::XYZM,
+        ] {
+            let arr = test::geometrycollection::array(CoordType::Interleaved, dim, false);
+            let twkb = to_twkb::<i64>(&arr, &options).unwrap();
+            let arr2 = from_twkb(&twkb, arr.data_type().clone()).unwrap();
+            assert_eq!(&arr, arr2.as_geometry_collection());
+        }
+    }
+
+    #[test]
+    fn round_trip_native() {
+        for coord_type in [CoordType::Interleaved, CoordType::Separated] {
+            for dim in [
+                Dimension::XY,
+                Dimension::XYZ,
+                Dimension::XYM,
+                Dimension::XYZM,
+            ] {
+                let arr = test::point::array(coord_type, dim);
+                let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+                let arr2 = from_twkb(&twkb, arr.data_type().clone()).unwrap();
+                assert_eq!(&arr, arr2.as_point());
+
+                let arr = test::linestring::array(coord_type, dim);
+                let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+                let arr2 = from_twkb(&twkb, arr.data_type().clone()).unwrap();
+                assert_eq!(&arr, arr2.as_line_string());
+
+                let arr = test::multipoint::array(coord_type, dim);
+                let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+                let arr2 = from_twkb(&twkb, arr.data_type().clone()).unwrap();
+                assert_eq!(&arr, arr2.as_multi_point());
+
+                let arr = test::multilinestring::array(coord_type, dim);
+                let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+                let arr2 = from_twkb(&twkb, arr.data_type().clone()).unwrap();
+                assert_eq!(&arr, arr2.as_multi_line_string());
+
+                let arr = test::multipolygon::array(coord_type, dim);
+                let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+                let arr2 = from_twkb(&twkb, arr.data_type().clone()).unwrap();
+                assert_eq!(&arr, arr2.as_multi_polygon());
+            }
+        }
+    }
+
+    #[test]
+    fn native_matches_geometry_encoding() {
+        let arr = test::multipolygon::array(CoordType::Separated, Dimension::XYZ);
+        let geometry_arr = from_twkb(
+            &to_twkb::<i32>(&arr, &options()).unwrap(),
+            GeometryType::new(CoordType::Interleaved, Default::default()).into(),
+        )
+        .unwrap();
+        assert_eq!(
+            to_twkb::<i32>(&arr, &options()).unwrap(),
+            to_twkb::<i32>(geometry_arr.as_ref(), &options()).unwrap()
+        );
+    }
+
+    #[test]
+    fn decode_geometry() {
+        let arr = test::geometry::array(CoordType::Interleaved, false);
+        let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+        let arr2 = from_twkb(&twkb, arr.data_type().clone()).unwrap();
+        assert_eq!(&arr, arr2.as_geometry());
+    }
+
+    #[test]
+    fn decode_wkb() {
+        let arr = test::polygon::array(CoordType::Interleaved, Dimension::XYZ);
+        let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+        let wkb_arr = from_twkb(&twkb, GeoArrowType::Wkb(Default::default())).unwrap();
+        let arr2 = from_wkb(wkb_arr.as_wkb::<i32>(), arr.data_type().clone()).unwrap();
+        assert_eq!(&arr, arr2.as_polygon());
+    }
+
+    #[test]
+    fn decode_promote_to_multi() {
+        let arr = test::polygon::array(CoordType::Separated, Dimension::XYZ);
+        let typ = MultiPolygonType::new(CoordType::Separated, Dimension::XYZ, Default::default());
+        let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+        let arr2 = from_twkb(&twkb, typ.clone().into()).unwrap();
+        let expected = from_wkb(&to_wkb::<i32>(&arr).unwrap(), typ.into()).unwrap();
+        assert_eq!(expected.as_multi_polygon(), arr2.as_multi_polygon());
+    }
+
+    #[test]
+    fn decode_rect() {
+        let arr = test::polygon::array(CoordType::Interleaved, Dimension::XY);
+        let twkb = to_twkb::<i32>(&arr, &options()).unwrap();
+        let typ = BoxType::new(Dimension::XY, Default::default());
+        assert!(from_twkb(&twkb, typ.into()).is_err());
+    }
+
+    #[test]
+    fn out_of_range() {
+        let arr = from_wkt(
+            &WktArray::<i32>::new(vec!["POINT(1e300 0)"].into(), Default::default()),
+            GeometryType::new(CoordType::Interleaved, Default::default()).into(),
+        )
+        .unwrap();
+        assert!(to_twkb::<i32>(arr.as_ref(), &options()).is_err());
+    }
+
+    #[test]
+    fn invalid_precision() {
+        let arr = test::point::array(CoordType::Interleaved, Dimension::XY);
+        let options = TwkbEncodeOptions {
+            xy_precision: 8,
+            ..Default::default()
+        };
+        assert!(to_twkb::<i32>(&arr, &options).is_err());
+    }
+
+    #[test]
+    fn truncated() {
+        let arr = test::point::array(CoordType::Interleaved, Dimension::XY);
+        let twkb = BinaryArray::from(vec![&[0x02, 0x00, 0x02, 0x02][..]]);
+        assert!(from_twkb(&twkb, arr.data_type().clone()).is_err());
+    }
+}
@@ -9,6 +9,8 @@ use arrow_array::cast::AsArray;
 use arrow_array::timezone::Tz;
 use arrow_array::types::*;
 use arrow_array::{Array, RecordBatch};
+use arrow_cast::cast;
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
 use arrow_json::writer::make_encoder;
 use arrow_schema::{DataType, Schema, TimeUnit};
 use geozero::error::GeozeroError;
@@ -43,9 +45,10 @@ impl GeozeroDatasource for GeozeroRecordBatchReader {
         let mut overall_row_idx = 0;
         for batch in reader.into_iter() {
             let batch = batch.map_err(|err| GeozeroError::Dataset(err.to_string()))?;
+            let batch = unpack_dictionaries(&batch)?;
             process_batch(
                 &batch,
-                &schema,
+                batch.schema_ref(),
                 geometry_column_index,
                 overall_row_idx,
                 processor,
@@ -59,6 +62,41 @@ impl GeozeroDatasource for GeozeroRecordBatchReader {
     }
 }
 
+/// Cast any dictionary-encoded columns to their value type, so that each value can be passed to
+/// the processor directly.
+fn unpack_dictionaries(batch: &RecordBatch) -> Result<RecordBatch, GeozeroError> {
+    let schema = batch.schema_ref();
+    if !schema
+        .fields()
+        .iter()
+        .any(|field| matches!(field.data_type(), DataType::Dictionary(_, _)))
+    {
+        return Ok(batch.clone());
+    }
+
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (field, array) in schema.fields().iter().zip(batch.columns()) {
+        if let DataType::Dictionary(_, value_type) = field.data_type() {
+            let values =
+                cast(array, value_type).map_err(|err| GeozeroError::Properties(err.to_string()))?;
+            fields.push(Arc::new(
+                field
+                    .as_ref()
+                    .clone()
+                    .with_data_type(value_type.as_ref().clone()),
+            ));
+            columns.push(values);
+        } else {
+            fields.push(field.clone());
+            columns.push(array.clone());
+        }
+    }
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|err| GeozeroError::Properties(err.to_string()))
+}
+
 fn process_batch<P: FeatureProcessor>(
     batch: &RecordBatch,
     schema: &Schema,
@@ -113,8 +151,10 @@ fn process_properties<P: PropertyProcessor>(
         }
         let name = field.name();
 
-        // Don't pass null properties to geozero
-        if array.is_null(within_batch_row_idx) {
+        // Don't pass null properties to geozero, but still count them so that the property
+        // index matches the column's position
+        if array.is_null(within_batch_row_idx) || array.data_type() == &DataType::Null {
+            property_idx += 1;
             continue;
         }
 
@@ -231,6 +271,14 @@ fn process_properties<P: PropertyProcessor>(
                     &ColumnValue::String(arr.value(within_batch_row_idx)),
                 )?;
             }
+            DataType::Utf8View => {
+                let arr = array.as_string_view();
+                processor.property(
+                    property_idx,
+                    name,
+                    &ColumnValue::String(arr.value(within_batch_row_idx)),
+                )?;
+            }
             DataType::Binary => {
                 let arr = array.as_binary::<i32>();
                 processor.property(
@@ -247,9 +295,38 @@ fn process_properties<P: PropertyProcessor>(
                     &ColumnValue::Binary(arr.value(within_batch_row_idx)),
                 )?;
             }
+            DataType::BinaryView => {
+                let arr = array.as_binary_view();
+                processor.property(
+                    property_idx,
+                    name,
+                    &ColumnValue::Binary(arr.value(within_batch_row_idx)),
+                )?;
+            }
+            DataType::FixedSizeBinary(_) => {
+                let arr = array.as_fixed_size_binary();
+                processor.property(
+                    property_idx,
+                    name,
+                    &ColumnValue::Binary(arr.value(within_batch_row_idx)),
+                )?;
+            }
+            DataType::Decimal128(_, _)
+            | DataType::Decimal256(_, _)
+            | DataType::Time32(_)
+            | DataType::Time64(_)
+            | DataType::Duration(_)
+            | DataType::Interval(_) => {
+                let options = FormatOptions::default();
+                let formatter = ArrayFormatter::try_new(array, &options)
+                    .map_err(|err| GeozeroError::Property(err.to_string()))?;
+                let value = formatter.value(within_batch_row_idx).to_string();
+                processor.property(property_idx, name, &ColumnValue::String(&value))?;
+            }
             DataType::Struct(_)
             | DataType::List(_)
             | DataType::LargeList(_)
+            | DataType::FixedSizeList(_, _)
             | DataType::Map(_, _) => {
                 // TODO(Perf): refactor so that we don't make a new encoder on every row
                 let options = Default::default();
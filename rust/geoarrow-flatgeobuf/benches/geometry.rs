@@ -0,0 +1,142 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use flatbuffers::FlatBufferBuilder;
+use flatgeobuf::{
+    FallibleStreamingIterator, Feature, FeatureArgs, FgbReader, FgbWriter, FgbWriterOptions,
+    Geometry, GeometryType,
+};
+use geoarrow_array::array::MultiPolygonArray;
+use geoarrow_array::builder::MultiPolygonBuilder;
+use geoarrow_array::cast::AsGeoArrowArray;
+use geoarrow_array::{ArrayAccessor, GeoArrowArray, GeoArrowType};
+use geoarrow_flatgeobuf::geometry::{FlatGeobufGeometryDecoder, FlatGeobufGeometryEncoder};
+use geoarrow_schema::{CoordType, Dimension, MultiPolygonType};
+
+fn multi_polygon_type() -> MultiPolygonType {
+    MultiPolygonType::new(CoordType::Interleaved, Dimension::XY, Default::default())
+}
+
+/// Read the countries fixture into a multi polygon array.
+fn load_array() -> Arc<dyn GeoArrowArray> {
+    let data = std::fs::read("../../fixtures/flatgeobuf/countries.fgb").unwrap();
+    let mut selection = FgbReader::open(Cursor::new(data))
+        .unwrap()
+        .select_all()
+        .unwrap();
+    let mut decoder =
+        FlatGeobufGeometryDecoder::try_new(GeoArrowType::MultiPolygon(multi_polygon_type()), 0)
+            .unwrap();
+    while let Some(feature) = selection.next().unwrap() {
+        decoder.push_geometry(feature.geometry()).unwrap();
+    }
+    decoder.finish().unwrap()
+}
+
+/// Encode each geometry of `array` as a standalone FlatGeobuf geometry table.
+fn encode_geometries(array: &dyn GeoArrowArray) -> Vec<Vec<u8>> {
+    let encoder = FlatGeobufGeometryEncoder::try_new(array).unwrap();
+    let mut fbb = FlatBufferBuilder::new();
+    (0..array.len())
+        .map(|i| {
+            fbb.reset();
+            let geometry = encoder.encode(&mut fbb, i).unwrap();
+            fbb.finish_minimal(geometry);
+            fbb.finished_data().to_vec()
+        })
+        .collect()
+}
+
+fn decode_direct(geometries: &[Geometry]) -> Arc<dyn GeoArrowArray> {
+    let mut decoder = FlatGeobufGeometryDecoder::try_new(
+        GeoArrowType::MultiPolygon(multi_polygon_type()),
+        geometries.len(),
+    )
+    .unwrap();
+    for geometry in geometries {
+        decoder.push_geometry(Some(*geometry)).unwrap();
+    }
+    decoder.finish().unwrap()
+}
+
+fn decode_geozero(geometries: &[Geometry]) -> MultiPolygonArray {
+    let mut builder = MultiPolygonBuilder::new(multi_polygon_type());
+    for geometry in geometries {
+        geometry
+            .process(&mut builder, GeometryType::MultiPolygon)
+            .unwrap();
+    }
+    builder.finish()
+}
+
+/// Encode each geometry as a size-prefixed FlatGeobuf feature, as the writer does.
+fn encode_direct(array: &dyn GeoArrowArray) -> usize {
+    let encoder = FlatGeobufGeometryEncoder::try_new(array).unwrap();
+    let mut fbb = FlatBufferBuilder::new();
+    let mut len = 0;
+    for i in 0..array.len() {
+        fbb.reset();
+        let geometry = encoder.encode(&mut fbb, i);
+        let feature = Feature::create(
+            &mut fbb,
+            &FeatureArgs {
+                geometry,
+                ..Default::default()
+            },
+        );
+        fbb.finish_size_prefixed(feature, None);
+        len += fbb.finished_data().len();
+    }
+    len
+}
+
+/// Encode each geometry as a FlatGeobuf feature through geozero's `GeomProcessor` callbacks.
+///
+/// [`FgbWriter`] also appends each feature to its temporary feature file, but nothing else is
+/// written.
+fn encode_geozero(array: &MultiPolygonArray) -> FgbWriter<'static> {
+    let options = FgbWriterOptions {
+        write_index: false,
+        ..Default::default()
+    };
+    let mut fgb =
+        FgbWriter::create_with_options("countries", GeometryType::MultiPolygon, options).unwrap();
+    for i in 0..array.len() {
+        fgb.add_feature_geom(array.value(i).unwrap(), |_| {})
+            .unwrap();
+    }
+    fgb
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let array = load_array();
+    let buffers = encode_geometries(array.as_ref());
+    let geometries = buffers
+        .iter()
+        .map(|buf| flatbuffers::root::<Geometry>(buf).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        decode_direct(&geometries).as_multi_polygon(),
+        &decode_geozero(&geometries)
+    );
+
+    c.bench_function("decode countries direct", |bencher| {
+        bencher.iter(|| criterion::black_box(decode_direct(criterion::black_box(&geometries))));
+    });
+    c.bench_function("decode countries geozero", |bencher| {
+        bencher.iter(|| criterion::black_box(decode_geozero(criterion::black_box(&geometries))));
+    });
+    c.bench_function("encode countries direct", |bencher| {
+        bencher.iter(|| criterion::black_box(encode_direct(criterion::black_box(array.as_ref()))));
+    });
+    let multi_polygon_array = array.as_multi_polygon();
+    c.bench_function("encode countries geozero", |bencher| {
+        bencher.iter(|| {
+            criterion::black_box(encode_geozero(criterion::black_box(multi_polygon_array)))
+        });
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,354 @@
+//! The mapping between Arrow fields and FlatGeobuf columns.
+//!
+//! When writing, each Arrow property column is stored as the FlatGeobuf column type below:
+//!
+//! | Arrow type                                               | FlatGeobuf column type |
+//! |----------------------------------------------------------|------------------------|
+//! | `Boolean`                                                | `Bool`                 |
+//! | `Int8`, `UInt8`, `Int16`, `UInt16`                       | `Byte`, `UByte`, `Short`, `UShort` |
+//! | `Int32`, `UInt32`, `Int64`, `UInt64`                     | `Int`, `UInt`, `Long`, `ULong` |
+//! | `Float16`, `Float32`                                     | `Float`                |
+//! | `Float64`                                                | `Double`               |
+//! | `Utf8`, `LargeUtf8`, `Utf8View`                          | `String`, or `Json` for the `arrow.json` extension type |
+//! | `Binary`, `LargeBinary`, `BinaryView`, `FixedSizeBinary` | `Binary`               |
+//! | `Timestamp`, `Date32`, `Date64`                          | `DateTime`, as an ISO 8601 string |
+//! | `Decimal128`, `Decimal256`                               | `String`, with the column's `precision` and `scale` set |
+//! | `Time32`, `Time64`, `Duration`, `Interval`               | `String`               |
+//! | `List`, `LargeList`, `FixedSizeList`, `Struct`, `Map`    | `Json`                 |
+//! | `Dictionary`                                             | The mapping of the dictionary's value type |
+//!
+//! When reading, each FlatGeobuf column type is read as the matching Arrow type in the first
+//! column. `DateTime` columns are read as `Timestamp(Microsecond, None)`, `Json` columns as `Utf8`
+//! with the `arrow.json` extension type, and `String` columns with a `precision` and `scale` as
+//! `Decimal128` (or `Decimal256` if the precision is larger than 38).
+//!
+//! If that isn't the Arrow type the column was written from, e.g. for a `Dictionary`, a
+//! `Timestamp` with a time zone or a `List`, the writer also stores the original Arrow type in the
+//! column's free-form metadata, as a JSON object with an `arrow:data_type` key. The reader then
+//! restores the original type. If the column's metadata is set through [`METADATA_KEY`] to a
+//! string that isn't a JSON object, the original type can't be stored alongside it and the column
+//! is read with the default mapping above.
+//!
+//! Column metadata is round-tripped through Arrow field metadata under the keys defined in this
+//! module. A column's `nullable` flag maps to [`Field::is_nullable`].
+
+use std::collections::HashMap;
+
+use arrow_schema::extension::EXTENSION_TYPE_NAME_KEY;
+use arrow_schema::{DECIMAL128_MAX_PRECISION, DataType, Field, TimeUnit};
+use flatbuffers::FlatBufferBuilder;
+use flatgeobuf::{Column, ColumnArgs, ColumnType};
+use geoarrow_array::error::{GeoArrowError, Result};
+use serde_json::{Map, Value};
+
+/// Field metadata key for the column's title.
+pub const TITLE_KEY: &str = "flatgeobuf:title";
+/// Field metadata key for the column's description.
+pub const DESCRIPTION_KEY: &str = "flatgeobuf:description";
+/// Field metadata key for the column's width, such as the maximum length of a string.
+pub const WIDTH_KEY: &str = "flatgeobuf:width";
+/// Field metadata key for the column's precision. This is set from the precision of decimal
+/// types when writing.
+pub const PRECISION_KEY: &str = "flatgeobuf:precision";
+/// Field metadata key for the column's scale. This is set from the scale of decimal types when
+/// writing.
+pub const SCALE_KEY: &str = "flatgeobuf:scale";
+/// Field metadata key for whether the column's values are unique, as `"true"` or `"false"`.
+pub const UNIQUE_KEY: &str = "flatgeobuf:unique";
+/// Field metadata key for whether the column is the primary key, as `"true"` or `"false"`.
+pub const PRIMARY_KEY_KEY: &str = "flatgeobuf:primary_key";
+/// Field metadata key for the column's free-form metadata string.
+pub const METADATA_KEY: &str = "flatgeobuf:metadata";
+
+/// The key of the original Arrow type in the JSON object stored as a column's metadata.
+const ARROW_DATA_TYPE_KEY: &str = "arrow:data_type";
+
+/// The FlatGeobuf column type used to store an Arrow field.
+pub(crate) fn column_type(field: &Field) -> Result<ColumnType> {
+    data_type_column_type(field, field.data_type())
+}
+
+fn data_type_column_type(field: &Field, data_type: &DataType) -> Result<ColumnType> {
+    let column_type = match data_type {
+        DataType::Boolean => ColumnType::Bool,
+        DataType::Int8 => ColumnType::Byte,
+        DataType::UInt8 => ColumnType::UByte,
+        DataType::Int16 => ColumnType::Short,
+        DataType::UInt16 => ColumnType::UShort,
+        DataType::Int32 => ColumnType::Int,
+        DataType::UInt32 => ColumnType::UInt,
+        DataType::Int64 => ColumnType::Long,
+        DataType::UInt64 => ColumnType::ULong,
+        DataType::Float16 | DataType::Float32 => ColumnType::Float,
+        DataType::Float64 => ColumnType::Double,
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            if field.extension_type_name() == Some("arrow.json") {
+                ColumnType::Json
+            } else {
+                ColumnType::String
+            }
+        }
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => ColumnType::Binary,
+        DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => ColumnType::DateTime,
+        DataType::Decimal128(_, _)
+        | DataType::Decimal256(_, _)
+        | DataType::Time32(_)
+        | DataType::Time64(_)
+        | DataType::Duration(_)
+        | DataType::Interval(_) => ColumnType::String,
+        DataType::List(_)
+        | DataType::LargeList(_)
+        | DataType::FixedSizeList(_, _)
+        | DataType::Struct(_)
+        | DataType::Map(_, _) => ColumnType::Json,
+        DataType::Dictionary(_, value_type) => data_type_column_type(field, value_type)?,
+        data_type => {
+            return Err(GeoArrowError::NotYetImplemented(format!(
+                "Writing {} column {} to FlatGeobuf",
+                data_type,
+                field.name()
+            )));
+        }
+    };
+    Ok(column_type)
+}
+
+/// Create the FlatGeobuf column for an Arrow field.
+pub(crate) fn create_column<'fbb>(
+    fbb: &mut FlatBufferBuilder<'fbb>,
+    field: &Field,
+) -> Result<flatbuffers::WIPOffset<Column<'fbb>>> {
+    let name = fbb.create_string(field.name());
+    let mut column = ColumnArgs {
+        name: Some(name),
+        type_: column_type(field)?,
+        ..Default::default()
+    };
+    apply_field_metadata(fbb, field, &mut column)?;
+    Ok(Column::create(fbb, &column))
+}
+
+/// Set the nullability, decimal precision and scale, and any metadata stored under the keys of
+/// this module on a FlatGeobuf column.
+pub(crate) fn apply_field_metadata<'fbb>(
+    fbb: &mut FlatBufferBuilder<'fbb>,
+    field: &Field,
+    column: &mut ColumnArgs<'fbb>,
+) -> Result<()> {
+    column.nullable = field.is_nullable();
+
+    let metadata = field.metadata();
+    let parse_int = |key: &str| -> Result<Option<i32>> {
+        metadata
+            .get(key)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    GeoArrowError::General(format!(
+                        "Invalid {} metadata {} for column {}",
+                        key,
+                        value,
+                        field.name()
+                    ))
+                })
+            })
+            .transpose()
+    };
+    column.width = parse_int(WIDTH_KEY)?.unwrap_or(column.width);
+    column.precision = parse_int(PRECISION_KEY)?.unwrap_or(column.precision);
+    column.scale = parse_int(SCALE_KEY)?.unwrap_or(column.scale);
+    column.unique = metadata.get(UNIQUE_KEY).is_some_and(|v| v == "true");
+    column.primary_key = metadata.get(PRIMARY_KEY_KEY).is_some_and(|v| v == "true");
+    column.title = metadata.get(TITLE_KEY).map(|v| fbb.create_string(v));
+    column.description = metadata.get(DESCRIPTION_KEY).map(|v| fbb.create_string(v));
+
+    let value_type = match field.data_type() {
+        DataType::Dictionary(_, value_type) => value_type.as_ref(),
+        data_type => data_type,
+    };
+    match value_type {
+        DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale) => {
+            column.precision = *precision as i32;
+            column.scale = *scale as i32;
+        }
+        _ => {}
+    }
+
+    let column_metadata = metadata.get(METADATA_KEY).cloned();
+    let read_type = read_data_type(column.type_, column.precision, column.scale)?;
+    let column_metadata = if &read_type == field.data_type() {
+        column_metadata
+    } else {
+        store_data_type(column_metadata, field.data_type())?
+    };
+    column.metadata = column_metadata.map(|v| fbb.create_string(&v));
+    Ok(())
+}
+
+/// Add `data_type` to a column's free-form metadata.
+///
+/// The metadata is left unchanged if it is set to something other than a JSON object.
+fn store_data_type(
+    column_metadata: Option<String>,
+    data_type: &DataType,
+) -> Result<Option<String>> {
+    let mut object = match column_metadata {
+        Some(column_metadata) => match serde_json::from_str::<Value>(&column_metadata) {
+            Ok(Value::Object(object)) => object,
+            _ => return Ok(Some(column_metadata)),
+        },
+        None => Map::new(),
+    };
+    let data_type =
+        serde_json::to_value(data_type).map_err(|err| GeoArrowError::External(Box::new(err)))?;
+    object.insert(ARROW_DATA_TYPE_KEY.to_string(), data_type);
+    Ok(Some(Value::Object(object).to_string()))
+}
+
+/// Split a column's free-form metadata into the Arrow type stored by [`store_data_type`], if any,
+/// and the remaining metadata.
+fn load_data_type(column_metadata: &str) -> Result<(Option<DataType>, Option<String>)> {
+    let mut object = match serde_json::from_str::<Value>(column_metadata) {
+        Ok(Value::Object(object)) if object.contains_key(ARROW_DATA_TYPE_KEY) => object,
+        _ => return Ok((None, Some(column_metadata.to_string()))),
+    };
+    let data_type = object.remove(ARROW_DATA_TYPE_KEY).unwrap();
+    let data_type = serde_json::from_value(data_type).map_err(|err| {
+        GeoArrowError::General(format!(
+            "Invalid Arrow type in FlatGeobuf column metadata: {err}"
+        ))
+    })?;
+    let column_metadata = (!object.is_empty()).then(|| Value::Object(object).to_string());
+    Ok((Some(data_type), column_metadata))
+}
+
+/// The Arrow type that a FlatGeobuf column is read as when no original Arrow type is stored.
+fn read_data_type(column_type: ColumnType, precision: i32, scale: i32) -> Result<DataType> {
+    let data_type = match column_type {
+        ColumnType::Bool => DataType::Boolean,
+        ColumnType::Byte => DataType::Int8,
+        ColumnType::UByte => DataType::UInt8,
+        ColumnType::Short => DataType::Int16,
+        ColumnType::UShort => DataType::UInt16,
+        ColumnType::Int => DataType::Int32,
+        ColumnType::UInt => DataType::UInt32,
+        ColumnType::Long => DataType::Int64,
+        ColumnType::ULong => DataType::UInt64,
+        ColumnType::Float => DataType::Float32,
+        ColumnType::Double => DataType::Float64,
+        ColumnType::String => match (precision, scale) {
+            (precision @ 1..=76, scale @ 0..=76) if scale <= precision => {
+                if precision as u8 <= DECIMAL128_MAX_PRECISION {
+                    DataType::Decimal128(precision as u8, scale as i8)
+                } else {
+                    DataType::Decimal256(precision as u8, scale as i8)
+                }
+            }
+            _ => DataType::Utf8,
+        },
+        ColumnType::Json => DataType::Utf8,
+        ColumnType::DateTime => DataType::Timestamp(TimeUnit::Microsecond, None),
+        ColumnType::Binary => DataType::Binary,
+        // ColumnType is a struct wrapping the raw value read from the file, so a corrupt file or
+        // one written with a newer version of the spec can hold any other value
+        column_type => {
+            return Err(GeoArrowError::General(format!(
+                "Unsupported FlatGeobuf column type {}",
+                column_type.0
+            )));
+        }
+    };
+    Ok(data_type)
+}
+
+/// The Arrow type that the values of a column of `data_type` are decoded to, before being
+/// converted to `data_type` itself.
+///
+/// This follows the mapping of the module documentation, except that timestamps keep their unit.
+pub(crate) fn storage_type(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::Float16 => DataType::Float32,
+        DataType::LargeUtf8
+        | DataType::Utf8View
+        | DataType::Time32(_)
+        | DataType::Time64(_)
+        | DataType::Duration(_)
+        | DataType::Interval(_)
+        | DataType::List(_)
+        | DataType::LargeList(_)
+        | DataType::FixedSizeList(_, _)
+        | DataType::Struct(_)
+        | DataType::Map(_, _) => DataType::Utf8,
+        DataType::LargeBinary | DataType::BinaryView | DataType::FixedSizeBinary(_) => {
+            DataType::Binary
+        }
+        DataType::Timestamp(unit, _) => DataType::Timestamp(*unit, None),
+        DataType::Date32 | DataType::Date64 => DataType::Timestamp(TimeUnit::Millisecond, None),
+        DataType::Dictionary(_, value_type) => storage_type(value_type),
+        data_type => data_type.clone(),
+    }
+}
+
+/// The Arrow field used to read a FlatGeobuf column.
+pub(crate) fn column_field(column: &Column<'_>) -> Result<Field> {
+    let name = column.name();
+    let nullable = column.nullable();
+    let mut metadata = HashMap::new();
+
+    let column_type = column.type_();
+    let read_type = read_data_type(column_type, column.precision(), column.scale())
+        .map_err(|err| GeoArrowError::General(format!("{err} for column {name}")))?;
+    let (stored_type, column_metadata) = match column.metadata() {
+        Some(column_metadata) => load_data_type(column_metadata)?,
+        None => (None, None),
+    };
+    let data_type = stored_type.unwrap_or(read_type);
+
+    // Strings are stored in `Json` columns if they have the `arrow.json` extension type
+    let field = Field::new(name, data_type.clone(), nullable);
+    if column_type == ColumnType::Json
+        && data_type_column_type(&field, &data_type)? == ColumnType::String
+    {
+        metadata.insert(
+            EXTENSION_TYPE_NAME_KEY.to_string(),
+            "arrow.json".to_string(),
+        );
+    }
+    let field = field.with_metadata(metadata.clone());
+    if data_type_column_type(&field, &data_type)? != column_type {
+        return Err(GeoArrowError::General(format!(
+            "Arrow type {data_type} in the metadata of FlatGeobuf column {name} doesn't match \
+             its column type"
+        )));
+    }
+
+    if let Some(title) = column.title() {
+        metadata.insert(TITLE_KEY.to_string(), title.to_string());
+    }
+    if let Some(description) = column.description() {
+        metadata.insert(DESCRIPTION_KEY.to_string(), description.to_string());
+    }
+    // -1 is the FlatGeobuf default for width, precision and scale
+    for (key, value) in [
+        (WIDTH_KEY, column.width()),
+        (PRECISION_KEY, column.precision()),
+        (SCALE_KEY, column.scale()),
+    ] {
+        if value != -1 {
+            metadata.insert(key.to_string(), value.to_string());
+        }
+    }
+    if column.unique() {
+        metadata.insert(UNIQUE_KEY.to_string(), "true".to_string());
+    }
+    if column.primary_key() {
+        metadata.insert(PRIMARY_KEY_KEY.to_string(), "true".to_string());
+    }
+    if let Some(column_metadata) = column_metadata {
+        metadata.insert(METADATA_KEY.to_string(), column_metadata);
+    }
+
+    Ok(Field::new(name, data_type, nullable).with_metadata(metadata))
+}
@@ -0,0 +1,350 @@
+use std::sync::Arc;
+
+use arrow_buffer::{NullBufferBuilder, OffsetBuffer, ScalarBuffer};
+use flatbuffers::Vector;
+use flatgeobuf::Geometry;
+use geoarrow_array::array::{
+    CoordBuffer, InterleavedCoordBuffer, LineStringArray, MultiLineStringArray, MultiPointArray,
+    MultiPolygonArray, PointArray, PolygonArray, SeparatedCoordBuffer,
+};
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geoarrow_schema::{CoordType, Dimension};
+
+/// A builder that decodes FlatGeobuf [`Geometry`] tables straight into the coordinate and offset
+/// buffers of a native GeoArrow array.
+///
+/// Only the single-geometry-type native types (`Point`, `LineString`, `Polygon`, `MultiPoint`,
+/// `MultiLineString` and `MultiPolygon`) are supported.
+#[derive(Debug)]
+pub struct FlatGeobufGeometryDecoder {
+    data_type: GeoArrowType,
+    coords: CoordsDecoder,
+    geom_offsets: Vec<i32>,
+    polygon_offsets: Vec<i32>,
+    ring_offsets: Vec<i32>,
+    validity: NullBufferBuilder,
+}
+
+impl FlatGeobufGeometryDecoder {
+    /// Create a new decoder for the given GeoArrow type, with space for `capacity` geometries.
+    pub fn try_new(data_type: GeoArrowType, capacity: usize) -> Result<Self> {
+        use GeoArrowType::*;
+
+        let (coord_type, dim) = match &data_type {
+            Point(t) => (t.coord_type(), t.dimension()),
+            LineString(t) => (t.coord_type(), t.dimension()),
+            Polygon(t) => (t.coord_type(), t.dimension()),
+            MultiPoint(t) => (t.coord_type(), t.dimension()),
+            MultiLineString(t) => (t.coord_type(), t.dimension()),
+            MultiPolygon(t) => (t.coord_type(), t.dimension()),
+            _ => {
+                return Err(GeoArrowError::IncorrectType(
+                    format!(
+                        "Direct FlatGeobuf decoding is not supported for {:?}",
+                        data_type
+                    )
+                    .into(),
+                ));
+            }
+        };
+
+        let mut geom_offsets = Vec::with_capacity(capacity + 1);
+        geom_offsets.push(0);
+        Ok(Self {
+            data_type,
+            coords: CoordsDecoder::new(coord_type, dim),
+            geom_offsets,
+            polygon_offsets: vec![0],
+            ring_offsets: vec![0],
+            validity: NullBufferBuilder::new(capacity),
+        })
+    }
+
+    /// The number of geometries added so far.
+    pub fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    /// Whether no geometries have been added yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add a FlatGeobuf geometry, or a null if `None`.
+    pub fn push_geometry(&mut self, geom: Option<Geometry<'_>>) -> Result<()> {
+        let Some(geom) = geom else {
+            self.push_null();
+            return Ok(());
+        };
+
+        self.reserve(&geom);
+        match &self.data_type {
+            GeoArrowType::Point(_) => {
+                if self.coords.push_coords(&geom)? == 0 {
+                    // An empty point is stored as a point with NaN coordinates
+                    self.coords.push_nan();
+                }
+            }
+            GeoArrowType::LineString(_) | GeoArrowType::MultiPoint(_) => {
+                self.coords.push_coords(&geom)?;
+                self.geom_offsets.push(offset(self.coords.len())?);
+            }
+            GeoArrowType::Polygon(_) | GeoArrowType::MultiLineString(_) => {
+                self.push_rings(&geom)?;
+                self.geom_offsets.push(offset(self.ring_offsets.len() - 1)?);
+            }
+            GeoArrowType::MultiPolygon(_) => {
+                if let Some(parts) = geom.parts() {
+                    for part in parts.iter() {
+                        self.push_rings(&part)?;
+                        self.polygon_offsets
+                            .push(offset(self.ring_offsets.len() - 1)?);
+                    }
+                } else if geom.xy().is_some() {
+                    // Some writers encode a single-part multi polygon without parts
+                    self.push_rings(&geom)?;
+                    self.polygon_offsets
+                        .push(offset(self.ring_offsets.len() - 1)?);
+                }
+                self.geom_offsets
+                    .push(offset(self.polygon_offsets.len() - 1)?);
+            }
+            _ => unreachable!(),
+        }
+        self.validity.append_non_null();
+        Ok(())
+    }
+
+    /// Reserve space for the coordinates and offsets of `geom`, from the lengths of its `xy`,
+    /// `ends` and `parts` vectors, so that they are not grown one value at a time.
+    fn reserve(&mut self, geom: &Geometry<'_>) {
+        let (num_coords, num_rings) = geometry_size(geom);
+        self.coords.reserve(num_coords);
+        match &self.data_type {
+            GeoArrowType::Polygon(_) | GeoArrowType::MultiLineString(_) => {
+                self.ring_offsets.reserve(num_rings)
+            }
+            GeoArrowType::MultiPolygon(_) => {
+                self.ring_offsets.reserve(num_rings);
+                self.polygon_offsets
+                    .reserve(geom.parts().map_or(1, |parts| parts.len()));
+            }
+            _ => {}
+        }
+    }
+
+    /// Add a null geometry.
+    pub fn push_null(&mut self) {
+        match &self.data_type {
+            GeoArrowType::Point(_) => self.coords.push_nan(),
+            _ => {
+                let last = *self.geom_offsets.last().unwrap();
+                self.geom_offsets.push(last);
+            }
+        }
+        self.validity.append_null();
+    }
+
+    /// Push the coordinates of a geometry whose parts are delimited by `ends`, such as a polygon's
+    /// rings or a multi line string's line strings.
+    fn push_rings(&mut self, geom: &Geometry<'_>) -> Result<()> {
+        let start = self.coords.len();
+        let num_coords = self.coords.push_coords(geom)?;
+        if let Some(ends) = geom.ends() {
+            for end in ends.iter() {
+                self.ring_offsets.push(offset(start + end as usize)?);
+            }
+        } else if num_coords > 0 {
+            self.ring_offsets.push(offset(start + num_coords)?);
+        }
+        Ok(())
+    }
+
+    /// Finish this builder into a GeoArrow array.
+    pub fn finish(mut self) -> Result<Arc<dyn GeoArrowArray>> {
+        let coords = self.coords.finish()?;
+        let nulls = self.validity.finish();
+        let metadata = self.data_type.metadata().clone();
+        let geom_offsets = OffsetBuffer::new(ScalarBuffer::from(self.geom_offsets));
+        let polygon_offsets = OffsetBuffer::new(ScalarBuffer::from(self.polygon_offsets));
+        let ring_offsets = OffsetBuffer::new(ScalarBuffer::from(self.ring_offsets));
+
+        let array: Arc<dyn GeoArrowArray> = match self.data_type {
+            GeoArrowType::Point(_) => Arc::new(PointArray::try_new(coords, nulls, metadata)?),
+            GeoArrowType::LineString(_) => Arc::new(LineStringArray::try_new(
+                coords,
+                geom_offsets,
+                nulls,
+                metadata,
+            )?),
+            GeoArrowType::Polygon(_) => Arc::new(PolygonArray::try_new(
+                coords,
+                geom_offsets,
+                ring_offsets,
+                nulls,
+                metadata,
+            )?),
+            GeoArrowType::MultiPoint(_) => Arc::new(MultiPointArray::try_new(
+                coords,
+                geom_offsets,
+                nulls,
+                metadata,
+            )?),
+            GeoArrowType::MultiLineString(_) => Arc::new(MultiLineStringArray::try_new(
+                coords,
+                geom_offsets,
+                ring_offsets,
+                nulls,
+                metadata,
+            )?),
+            GeoArrowType::MultiPolygon(_) => Arc::new(MultiPolygonArray::try_new(
+                coords,
+                geom_offsets,
+                polygon_offsets,
+                ring_offsets,
+                nulls,
+                metadata,
+            )?),
+            _ => unreachable!(),
+        };
+        Ok(array)
+    }
+}
+
+/// The number of coordinates and of rings (or line strings) in `geom` and its parts.
+fn geometry_size(geom: &Geometry<'_>) -> (usize, usize) {
+    let num_coords = geom.xy().map_or(0, |xy| xy.len() / 2);
+    let num_rings = match geom.ends() {
+        Some(ends) => ends.len(),
+        None => usize::from(num_coords > 0),
+    };
+    let parts = geom.parts().into_iter().flat_map(|parts| parts.iter());
+    parts.fold((num_coords, num_rings), |(coords, rings), part| {
+        let (part_coords, part_rings) = geometry_size(&part);
+        (coords + part_coords, rings + part_rings)
+    })
+}
+
+fn offset(value: usize) -> Result<i32> {
+    value.try_into().map_err(|_| GeoArrowError::Overflow)
+}
+
+/// Coordinate storage for [FlatGeobufGeometryDecoder].
+#[derive(Debug)]
+enum CoordsDecoder {
+    Interleaved(Vec<f64>, Dimension),
+    Separated([Vec<f64>; 4], Dimension),
+}
+
+impl CoordsDecoder {
+    fn new(coord_type: CoordType, dim: Dimension) -> Self {
+        match coord_type {
+            CoordType::Interleaved => Self::Interleaved(vec![], dim),
+            CoordType::Separated => Self::Separated(Default::default(), dim),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Interleaved(coords, dim) => coords.len() / dim.size(),
+            Self::Separated(buffers, _) => buffers[0].len(),
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        match self {
+            Self::Interleaved(coords, dim) => coords.reserve(additional * dim.size()),
+            Self::Separated(buffers, dim) => buffers
+                .iter_mut()
+                .take(dim.size())
+                .for_each(|buffer| buffer.reserve(additional)),
+        }
+    }
+
+    fn push_nan(&mut self) {
+        match self {
+            Self::Interleaved(coords, dim) => {
+                coords.extend(std::iter::repeat_n(f64::NAN, dim.size()))
+            }
+            Self::Separated(buffers, dim) => buffers
+                .iter_mut()
+                .take(dim.size())
+                .for_each(|buffer| buffer.push(f64::NAN)),
+        }
+    }
+
+    /// Append all coordinates of `geom` (ignoring its parts), returning the number of coordinates
+    /// added.
+    ///
+    /// Coordinates missing an ordinate that the output dimension requires are filled with NaN.
+    fn push_coords(&mut self, geom: &Geometry<'_>) -> Result<usize> {
+        let Some(xy) = geom.xy() else {
+            return Ok(0);
+        };
+        let num_coords = xy.len() / 2;
+
+        let dim = match self {
+            Self::Interleaved(_, dim) | Self::Separated(_, dim) => *dim,
+        };
+        let (third, fourth) = match dim {
+            Dimension::XY => (None, None),
+            Dimension::XYZ => (geom.z(), None),
+            Dimension::XYM => (geom.m(), None),
+            Dimension::XYZM => (geom.z(), geom.m()),
+        };
+        for ordinates in [third, fourth].iter().flatten() {
+            if ordinates.len() != num_coords {
+                return Err(GeoArrowError::General(format!(
+                    "FlatGeobuf geometry has {} coordinates but {} values in its z or m vector",
+                    num_coords,
+                    ordinates.len()
+                )));
+            }
+        }
+
+        match self {
+            Self::Interleaved(coords, dim) => {
+                for i in 0..num_coords {
+                    coords.push(xy.get(2 * i));
+                    coords.push(xy.get(2 * i + 1));
+                    if dim.size() >= 3 {
+                        coords.push(ordinate(&third, i));
+                    }
+                    if dim.size() == 4 {
+                        coords.push(ordinate(&fourth, i));
+                    }
+                }
+            }
+            Self::Separated(buffers, dim) => {
+                let [xs, ys, thirds, fourths] = buffers;
+                xs.extend((0..num_coords).map(|i| xy.get(2 * i)));
+                ys.extend((0..num_coords).map(|i| xy.get(2 * i + 1)));
+                if dim.size() >= 3 {
+                    thirds.extend((0..num_coords).map(|i| ordinate(&third, i)));
+                }
+                if dim.size() == 4 {
+                    fourths.extend((0..num_coords).map(|i| ordinate(&fourth, i)));
+                }
+            }
+        }
+
+        Ok(num_coords)
+    }
+
+    fn finish(self) -> Result<CoordBuffer> {
+        match self {
+            Self::Interleaved(coords, dim) => Ok(CoordBuffer::Interleaved(
+                InterleavedCoordBuffer::try_new(coords.into(), dim)?,
+            )),
+            Self::Separated(buffers, dim) => Ok(CoordBuffer::Separated(
+                SeparatedCoordBuffer::from_array(buffers.map(ScalarBuffer::from), dim)?,
+            )),
+        }
+    }
+}
+
+#[inline]
+fn ordinate(values: &Option<Vector<'_, f64>>, i: usize) -> f64 {
+    values.as_ref().map_or(f64::NAN, |values| values.get(i))
+}
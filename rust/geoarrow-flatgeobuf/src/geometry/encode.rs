@@ -0,0 +1,486 @@
+use std::ops::Range;
+
+use flatbuffers::{FlatBufferBuilder, ForwardsUOffset, Vector, WIPOffset};
+use flatgeobuf::{Geometry, GeometryArgs, GeometryType};
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait,
+    GeometryType as GeometryTraitType, LineStringTrait, LineTrait, MultiLineStringTrait,
+    MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait, RectTrait, TriangleTrait,
+};
+use geoarrow_array::array::{
+    CoordBuffer, LineStringArray, MultiLineStringArray, MultiPointArray, MultiPolygonArray,
+    PointArray, PolygonArray,
+};
+use geoarrow_array::cast::AsGeoArrowArray;
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geoarrow_schema::Dimension;
+
+/// Encodes geometries of a native GeoArrow array as FlatGeobuf [`Geometry`] tables, reading
+/// coordinates straight from the array's [`CoordBuffer`].
+///
+/// Only the single-geometry-type native arrays (`Point`, `LineString`, `Polygon`, `MultiPoint`,
+/// `MultiLineString` and `MultiPolygon`) are supported.
+#[derive(Debug, Clone, Copy)]
+pub struct FlatGeobufGeometryEncoder<'a> {
+    array: NativeArrayRef<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NativeArrayRef<'a> {
+    Point(&'a PointArray),
+    LineString(&'a LineStringArray),
+    Polygon(&'a PolygonArray),
+    MultiPoint(&'a MultiPointArray),
+    MultiLineString(&'a MultiLineStringArray),
+    MultiPolygon(&'a MultiPolygonArray),
+}
+
+impl<'a> FlatGeobufGeometryEncoder<'a> {
+    /// Create a new encoder for the given array.
+    pub fn try_new(array: &'a dyn GeoArrowArray) -> Result<Self> {
+        let array = match array.data_type() {
+            GeoArrowType::Point(_) => NativeArrayRef::Point(array.as_point()),
+            GeoArrowType::LineString(_) => NativeArrayRef::LineString(array.as_line_string()),
+            GeoArrowType::Polygon(_) => NativeArrayRef::Polygon(array.as_polygon()),
+            GeoArrowType::MultiPoint(_) => NativeArrayRef::MultiPoint(array.as_multi_point()),
+            GeoArrowType::MultiLineString(_) => {
+                NativeArrayRef::MultiLineString(array.as_multi_line_string())
+            }
+            GeoArrowType::MultiPolygon(_) => NativeArrayRef::MultiPolygon(array.as_multi_polygon()),
+            data_type => {
+                return Err(GeoArrowError::IncorrectType(
+                    format!(
+                        "Direct FlatGeobuf encoding is not supported for {:?}",
+                        data_type
+                    )
+                    .into(),
+                ));
+            }
+        };
+        Ok(Self { array })
+    }
+
+    /// The FlatGeobuf geometry type of the encoded geometries.
+    pub fn geometry_type(&self) -> GeometryType {
+        match self.array {
+            NativeArrayRef::Point(_) => GeometryType::Point,
+            NativeArrayRef::LineString(_) => GeometryType::LineString,
+            NativeArrayRef::Polygon(_) => GeometryType::Polygon,
+            NativeArrayRef::MultiPoint(_) => GeometryType::MultiPoint,
+            NativeArrayRef::MultiLineString(_) => GeometryType::MultiLineString,
+            NativeArrayRef::MultiPolygon(_) => GeometryType::MultiPolygon,
+        }
+    }
+
+    /// Encode the geometry at index `i`, returning `None` if it is null.
+    pub fn encode<'fbb>(
+        &self,
+        fbb: &mut FlatBufferBuilder<'fbb>,
+        i: usize,
+    ) -> Option<WIPOffset<Geometry<'fbb>>> {
+        let type_ = self.geometry_type();
+        let geom = match self.array {
+            NativeArrayRef::Point(arr) => {
+                if arr.is_null(i) {
+                    return None;
+                }
+                let coords = arr.coords();
+                if is_empty_point(coords, i) {
+                    create_geometry(fbb, type_, None, None, None)
+                } else {
+                    let coords = create_coords(fbb, coords, i..i + 1);
+                    create_geometry(fbb, type_, Some(coords), None, None)
+                }
+            }
+            NativeArrayRef::LineString(arr) => {
+                if arr.is_null(i) {
+                    return None;
+                }
+                let coords = create_coords(fbb, arr.coords(), range(arr.geom_offsets(), i));
+                create_geometry(fbb, type_, Some(coords), None, None)
+            }
+            NativeArrayRef::MultiPoint(arr) => {
+                if arr.is_null(i) {
+                    return None;
+                }
+                let coords = create_coords(fbb, arr.coords(), range(arr.geom_offsets(), i));
+                create_geometry(fbb, type_, Some(coords), None, None)
+            }
+            NativeArrayRef::Polygon(arr) => {
+                if arr.is_null(i) {
+                    return None;
+                }
+                let rings = range(arr.geom_offsets(), i);
+                create_ringed(fbb, type_, arr.coords(), arr.ring_offsets(), rings)
+            }
+            NativeArrayRef::MultiLineString(arr) => {
+                if arr.is_null(i) {
+                    return None;
+                }
+                let line_strings = range(arr.geom_offsets(), i);
+                create_ringed(fbb, type_, arr.coords(), arr.ring_offsets(), line_strings)
+            }
+            NativeArrayRef::MultiPolygon(arr) => {
+                if arr.is_null(i) {
+                    return None;
+                }
+                let parts = range(arr.geom_offsets(), i)
+                    .map(|polygon_idx| {
+                        let rings = range(arr.polygon_offsets(), polygon_idx);
+                        create_ringed(
+                            fbb,
+                            GeometryType::Polygon,
+                            arr.coords(),
+                            arr.ring_offsets(),
+                            rings,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let parts = fbb.create_vector(&parts);
+                create_geometry(fbb, type_, None, None, Some(parts))
+            }
+        };
+        Some(geom)
+    }
+}
+
+/// The range of child indices of `i` given by `offsets`.
+#[inline]
+fn range(offsets: &[i32], i: usize) -> Range<usize> {
+    offsets[i] as usize..offsets[i + 1] as usize
+}
+
+fn is_empty_point(coords: &CoordBuffer, i: usize) -> bool {
+    match coords {
+        CoordBuffer::Interleaved(c) => {
+            let size = c.dim().size();
+            c.coords()[i * size..(i + 1) * size]
+                .iter()
+                .all(|v| v.is_nan())
+        }
+        CoordBuffer::Separated(c) => c.raw_buffers()[..c.dim().size()]
+            .iter()
+            .all(|buffer| buffer[i].is_nan()),
+    }
+}
+
+struct CoordOffsets<'fbb> {
+    xy: WIPOffset<Vector<'fbb, f64>>,
+    z: Option<WIPOffset<Vector<'fbb, f64>>>,
+    m: Option<WIPOffset<Vector<'fbb, f64>>>,
+}
+
+/// Write the coordinates in `range` as FlatGeobuf `xy`, `z` and `m` vectors.
+///
+/// Interleaved XY coordinates and separated Z and M coordinates are copied into the builder as
+/// contiguous slices.
+fn create_coords<'fbb>(
+    fbb: &mut FlatBufferBuilder<'fbb>,
+    coords: &CoordBuffer,
+    range: Range<usize>,
+) -> CoordOffsets<'fbb> {
+    let dim = coords.dim();
+    let (z_idx, m_idx) = match dim {
+        Dimension::XY => (None, None),
+        Dimension::XYZ => (Some(2), None),
+        Dimension::XYM => (None, Some(2)),
+        Dimension::XYZM => (Some(2), Some(3)),
+    };
+
+    match coords {
+        CoordBuffer::Interleaved(c) => {
+            let size = dim.size();
+            let values = &c.coords()[range.start * size..range.end * size];
+            if dim == Dimension::XY {
+                return CoordOffsets {
+                    xy: fbb.create_vector(values),
+                    z: None,
+                    m: None,
+                };
+            }
+
+            let xy = values
+                .chunks_exact(size)
+                .flat_map(|coord| [coord[0], coord[1]])
+                .collect::<Vec<_>>();
+            let ordinate = |fbb: &mut FlatBufferBuilder<'fbb>, idx: Option<usize>| {
+                idx.map(|idx| {
+                    let values = values
+                        .chunks_exact(size)
+                        .map(|coord| coord[idx])
+                        .collect::<Vec<_>>();
+                    fbb.create_vector(&values)
+                })
+            };
+            let z = ordinate(fbb, z_idx);
+            let m = ordinate(fbb, m_idx);
+            CoordOffsets {
+                xy: fbb.create_vector(&xy),
+                z,
+                m,
+            }
+        }
+        CoordBuffer::Separated(c) => {
+            let buffers = c.raw_buffers();
+            let xy = buffers[0][range.clone()]
+                .iter()
+                .zip(&buffers[1][range.clone()])
+                .flat_map(|(x, y)| [*x, *y])
+                .collect::<Vec<_>>();
+            let z = z_idx.map(|idx| fbb.create_vector(&buffers[idx][range.clone()]));
+            let m = m_idx.map(|idx| fbb.create_vector(&buffers[idx][range.clone()]));
+            CoordOffsets {
+                xy: fbb.create_vector(&xy),
+                z,
+                m,
+            }
+        }
+    }
+}
+
+/// Create a geometry made of one or more coordinate sequences, such as a polygon's rings or a
+/// multi line string's line strings.
+///
+/// `parts` are indices into `part_offsets`. As in the FlatGeobuf reference implementation, `ends`
+/// is only written when there is more than one part.
+fn create_ringed<'fbb>(
+    fbb: &mut FlatBufferBuilder<'fbb>,
+    type_: GeometryType,
+    coords: &CoordBuffer,
+    part_offsets: &[i32],
+    parts: Range<usize>,
+) -> WIPOffset<Geometry<'fbb>> {
+    if parts.is_empty() {
+        return create_geometry(fbb, type_, None, None, None);
+    }
+
+    let coord_start = part_offsets[parts.start] as usize;
+    let coord_end = part_offsets[parts.end] as usize;
+    let ends = if parts.len() > 1 {
+        let ends = part_offsets[parts.start + 1..=parts.end]
+            .iter()
+            .map(|end| (*end as usize - coord_start) as u32)
+            .collect::<Vec<_>>();
+        Some(fbb.create_vector(&ends))
+    } else {
+        None
+    };
+    let coords = create_coords(fbb, coords, coord_start..coord_end);
+    create_geometry(fbb, type_, Some(coords), ends, None)
+}
+
+fn create_geometry<'fbb>(
+    fbb: &mut FlatBufferBuilder<'fbb>,
+    type_: GeometryType,
+    coords: Option<CoordOffsets<'fbb>>,
+    ends: Option<WIPOffset<Vector<'fbb, u32>>>,
+    parts: Option<WIPOffset<Vector<'fbb, ForwardsUOffset<Geometry<'fbb>>>>>,
+) -> WIPOffset<Geometry<'fbb>> {
+    let (xy, z, m) = match coords {
+        Some(coords) => (Some(coords.xy), coords.z, coords.m),
+        None => (None, None, None),
+    };
+    Geometry::create(
+        fbb,
+        &GeometryArgs {
+            ends,
+            xy,
+            z,
+            m,
+            type_,
+            parts,
+            ..Default::default()
+        },
+    )
+}
+
+/// Encode any geometry implementing [GeometryTrait] as a FlatGeobuf geometry.
+///
+/// This is the slow path for arrays that [FlatGeobufGeometryEncoder] does not support, such as
+/// `Geometry` and WKB arrays, where each geometry may have a different type and dimension.
+///
+/// If `promote_to_multi` is set, single geometries are written as multi geometries with one part.
+/// Rects and triangles are written as polygons, and lines as line strings.
+pub(crate) fn encode_geometry_trait<'fbb>(
+    fbb: &mut FlatBufferBuilder<'fbb>,
+    geom: &impl GeometryTrait<T = f64>,
+    promote_to_multi: bool,
+) -> WIPOffset<Geometry<'fbb>> {
+    let line_string_type = if promote_to_multi {
+        GeometryType::MultiLineString
+    } else {
+        GeometryType::LineString
+    };
+
+    let mut writer = CoordsWriter::default();
+    match geom.as_type() {
+        GeometryTraitType::Point(point) => {
+            if let Some(coord) = point.coord() {
+                writer.push_coord(&coord);
+            }
+            let type_ = if promote_to_multi {
+                GeometryType::MultiPoint
+            } else {
+                GeometryType::Point
+            };
+            writer.finish(fbb, type_)
+        }
+        GeometryTraitType::LineString(line_string) => {
+            line_string
+                .coords()
+                .for_each(|coord| writer.push_coord(&coord));
+            writer.finish(fbb, line_string_type)
+        }
+        GeometryTraitType::Polygon(polygon) => {
+            let polygon = encode_polygon(fbb, polygon);
+            promote_polygon(fbb, polygon, promote_to_multi)
+        }
+        GeometryTraitType::MultiPoint(multi_point) => {
+            for point in multi_point.points() {
+                if let Some(coord) = point.coord() {
+                    writer.push_coord(&coord);
+                }
+            }
+            writer.finish(fbb, GeometryType::MultiPoint)
+        }
+        GeometryTraitType::MultiLineString(multi_line_string) => {
+            for line_string in multi_line_string.line_strings() {
+                line_string
+                    .coords()
+                    .for_each(|coord| writer.push_coord(&coord));
+                writer.end_part();
+            }
+            writer.finish(fbb, GeometryType::MultiLineString)
+        }
+        GeometryTraitType::MultiPolygon(multi_polygon) => {
+            let parts = multi_polygon
+                .polygons()
+                .map(|polygon| encode_polygon(fbb, &polygon))
+                .collect::<Vec<_>>();
+            let parts = fbb.create_vector(&parts);
+            create_geometry(fbb, GeometryType::MultiPolygon, None, None, Some(parts))
+        }
+        GeometryTraitType::GeometryCollection(geometry_collection) => {
+            let parts = geometry_collection
+                .geometries()
+                .map(|geom| encode_geometry_trait(fbb, &geom, false))
+                .collect::<Vec<_>>();
+            let parts = fbb.create_vector(&parts);
+            create_geometry(
+                fbb,
+                GeometryType::GeometryCollection,
+                None,
+                None,
+                Some(parts),
+            )
+        }
+        GeometryTraitType::Rect(rect) => {
+            let (min, max) = (rect.min(), rect.max());
+            for (x, y) in [
+                (min.x(), min.y()),
+                (max.x(), min.y()),
+                (max.x(), max.y()),
+                (min.x(), max.y()),
+                (min.x(), min.y()),
+            ] {
+                writer.xy.extend([x, y]);
+            }
+            let polygon = writer.finish(fbb, GeometryType::Polygon);
+            promote_polygon(fbb, polygon, promote_to_multi)
+        }
+        GeometryTraitType::Triangle(triangle) => {
+            for coord in [
+                triangle.first(),
+                triangle.second(),
+                triangle.third(),
+                triangle.first(),
+            ] {
+                writer.push_coord(&coord);
+            }
+            let polygon = writer.finish(fbb, GeometryType::Polygon);
+            promote_polygon(fbb, polygon, promote_to_multi)
+        }
+        GeometryTraitType::Line(line) => {
+            writer.push_coord(&line.start());
+            writer.push_coord(&line.end());
+            writer.finish(fbb, line_string_type)
+        }
+    }
+}
+
+/// Wrap an encoded polygon in a multi polygon with a single part, if `promote_to_multi` is set.
+fn promote_polygon<'fbb>(
+    fbb: &mut FlatBufferBuilder<'fbb>,
+    polygon: WIPOffset<Geometry<'fbb>>,
+    promote_to_multi: bool,
+) -> WIPOffset<Geometry<'fbb>> {
+    if !promote_to_multi {
+        return polygon;
+    }
+    let parts = fbb.create_vector(&[polygon]);
+    create_geometry(fbb, GeometryType::MultiPolygon, None, None, Some(parts))
+}
+
+fn encode_polygon<'fbb>(
+    fbb: &mut FlatBufferBuilder<'fbb>,
+    polygon: &impl PolygonTrait<T = f64>,
+) -> WIPOffset<Geometry<'fbb>> {
+    let mut writer = CoordsWriter::default();
+    for ring in polygon.exterior().into_iter().chain(polygon.interiors()) {
+        ring.coords().for_each(|coord| writer.push_coord(&coord));
+        writer.end_part();
+    }
+    writer.finish(fbb, GeometryType::Polygon)
+}
+
+/// Collects the coordinates of a geometry whose coordinates may not be contiguous in memory.
+#[derive(Debug, Default)]
+struct CoordsWriter {
+    xy: Vec<f64>,
+    z: Vec<f64>,
+    m: Vec<f64>,
+    ends: Vec<u32>,
+}
+
+impl CoordsWriter {
+    fn push_coord(&mut self, coord: &impl CoordTrait<T = f64>) {
+        self.xy.extend([coord.x(), coord.y()]);
+        match coord.dim() {
+            Dimensions::Xyz => self.z.push(coord.nth_or_panic(2)),
+            Dimensions::Xym => self.m.push(coord.nth_or_panic(2)),
+            Dimensions::Xyzm => {
+                self.z.push(coord.nth_or_panic(2));
+                self.m.push(coord.nth_or_panic(3));
+            }
+            Dimensions::Xy | Dimensions::Unknown(_) => {}
+        }
+    }
+
+    /// Mark the end of a ring or line string.
+    fn end_part(&mut self) {
+        self.ends.push((self.xy.len() / 2) as u32);
+    }
+
+    fn finish<'fbb>(
+        self,
+        fbb: &mut FlatBufferBuilder<'fbb>,
+        type_: GeometryType,
+    ) -> WIPOffset<Geometry<'fbb>> {
+        if self.xy.is_empty() {
+            return create_geometry(fbb, type_, None, None, None);
+        }
+
+        // As in the FlatGeobuf reference implementation, `ends` is only written when there is
+        // more than one part.
+        let ends = (self.ends.len() > 1).then(|| fbb.create_vector(&self.ends));
+        let z = (!self.z.is_empty()).then(|| fbb.create_vector(&self.z));
+        let m = (!self.m.is_empty()).then(|| fbb.create_vector(&self.m));
+        let coords = CoordOffsets {
+            xy: fbb.create_vector(&self.xy),
+            z,
+            m,
+        };
+        create_geometry(fbb, type_, Some(coords), ends, None)
+    }
+}
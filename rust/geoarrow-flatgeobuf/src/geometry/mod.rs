@@ -0,0 +1,85 @@
+//! Convert between FlatGeobuf geometries and GeoArrow arrays without going through geozero.
+//!
+//! geozero's [`GeomProcessor`][geozero::GeomProcessor] is callback based and costs a virtual call
+//! per coordinate. The codecs here instead read the flatbuffer [`Geometry`][flatgeobuf::Geometry]
+//! tables straight into GeoArrow coordinate and offset buffers, and write FlatGeobuf geometries
+//! straight from [`CoordBuffer`][geoarrow_array::array::CoordBuffer] slices.
+
+mod decode;
+mod encode;
+
+pub use decode::FlatGeobufGeometryDecoder;
+pub use encode::FlatGeobufGeometryEncoder;
+pub(crate) use encode::encode_geometry_trait;
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use flatbuffers::FlatBufferBuilder;
+    use geoarrow_array::GeoArrowArray;
+    use geoarrow_array::cast::AsGeoArrowArray;
+    use geoarrow_array::test::{
+        geometry, linestring, multilinestring, multipoint, multipolygon, point, polygon,
+    };
+    use geoarrow_schema::{CoordType, Dimension};
+
+    use super::*;
+
+    /// Encode every geometry of `array` to a FlatGeobuf geometry and decode it back.
+    fn round_trip(array: &dyn GeoArrowArray) -> Arc<dyn GeoArrowArray> {
+        let encoder = FlatGeobufGeometryEncoder::try_new(array).unwrap();
+        let mut decoder = FlatGeobufGeometryDecoder::try_new(array.data_type(), 0).unwrap();
+        let mut fbb = FlatBufferBuilder::new();
+        for i in 0..array.len() {
+            fbb.reset();
+            match encoder.encode(&mut fbb, i) {
+                Some(geom) => {
+                    fbb.finish_minimal(geom);
+                    let geom =
+                        flatbuffers::root::<flatgeobuf::Geometry>(fbb.finished_data()).unwrap();
+                    decoder.push_geometry(Some(geom)).unwrap();
+                }
+                None => decoder.push_geometry(None).unwrap(),
+            }
+        }
+        decoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for coord_type in [CoordType::Interleaved, CoordType::Separated] {
+            for dim in [
+                Dimension::XY,
+                Dimension::XYZ,
+                Dimension::XYM,
+                Dimension::XYZM,
+            ] {
+                let arr = point::array(coord_type, dim);
+                assert_eq!(&arr, round_trip(&arr).as_point());
+
+                let arr = linestring::array(coord_type, dim);
+                assert_eq!(&arr, round_trip(&arr).as_line_string());
+
+                let arr = polygon::array(coord_type, dim);
+                assert_eq!(&arr, round_trip(&arr).as_polygon());
+
+                let arr = multipoint::array(coord_type, dim);
+                assert_eq!(&arr, round_trip(&arr).as_multi_point());
+
+                let arr = multilinestring::array(coord_type, dim);
+                assert_eq!(&arr, round_trip(&arr).as_multi_line_string());
+
+                let arr = multipolygon::array(coord_type, dim);
+                assert_eq!(&arr, round_trip(&arr).as_multi_polygon());
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsupported_type() {
+        let arr = geometry::array(CoordType::Interleaved, false);
+        assert!(FlatGeobufGeometryEncoder::try_new(&arr).is_err());
+        assert!(FlatGeobufGeometryDecoder::try_new(arr.data_type(), 0).is_err());
+    }
+}
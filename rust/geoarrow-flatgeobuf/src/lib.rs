@@ -1,3 +1,6 @@
 #![cfg_attr(not(test), deny(unused_crate_dependencies))]
 
+pub mod column;
+pub mod geometry;
+pub mod reader;
 pub mod writer;
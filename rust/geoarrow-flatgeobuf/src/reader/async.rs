@@ -0,0 +1,218 @@
+use std::pin::Pin;
+
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use flatgeobuf::{AsyncFeatureIter, HttpFgbReader};
+use futures::future::BoxFuture;
+use futures::task::{Context, Poll};
+use futures::{FutureExt, Stream, ready};
+use geoarrow_array::error::{GeoArrowError, Result};
+use http_range_client::{AsyncBufferedHttpRangeClient, AsyncHttpRangeClient};
+
+use crate::reader::common::{FlatGeobufReaderOptions, FlatGeobufSchema};
+
+/// A builder for [FlatGeobufStream]
+pub struct FlatGeobufStreamBuilder<T: AsyncHttpRangeClient> {
+    reader: HttpFgbReader<T>,
+}
+
+impl<T: AsyncHttpRangeClient> FlatGeobufStreamBuilder<T> {
+    /// Create a new [FlatGeobufStreamBuilder] from an [AsyncBufferedHttpRangeClient]
+    pub async fn new(reader: AsyncBufferedHttpRangeClient<T>) -> Result<Self> {
+        let reader = HttpFgbReader::new(reader)
+            .await
+            .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+        Ok(Self { reader })
+    }
+
+    /// Create a new [FlatGeobufStreamBuilder] directly from a client.
+    pub async fn new_from_client(reader: T, url: &str) -> Result<Self> {
+        let client = AsyncBufferedHttpRangeClient::with(reader, url);
+        Self::new(client).await
+    }
+
+    /// The Arrow schema of the record batches that will be read with these options.
+    pub fn schema(&self, options: &FlatGeobufReaderOptions) -> Result<SchemaRef> {
+        Ok(FlatGeobufSchema::try_new(self.reader.header(), options)?.schema())
+    }
+
+    /// Read from the FlatGeobuf file
+    pub async fn read(self, options: FlatGeobufReaderOptions) -> Result<FlatGeobufStream<T>> {
+        let fgb_schema = FlatGeobufSchema::try_new(self.reader.header(), &options)?;
+        let selection = if let Some((min_x, min_y, max_x, max_y)) = options.bbox {
+            self.reader.select_bbox(min_x, min_y, max_x, max_y).await
+        } else {
+            self.reader.select_all().await
+        }
+        .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+        Ok(FlatGeobufStream::new(selection, fgb_schema, &options))
+    }
+}
+
+#[cfg(feature = "object_store")]
+impl FlatGeobufStreamBuilder<crate::reader::ObjectStoreWrapper> {
+    /// Create a [FlatGeobufStreamBuilder] from an [ObjectStore][object_store::ObjectStore]
+    /// instance.
+    pub async fn new_from_store(
+        store: std::sync::Arc<dyn object_store::ObjectStore>,
+        location: object_store::path::Path,
+    ) -> Result<Self> {
+        let head = store
+            .head(&location)
+            .await
+            .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+        let object_store_wrapper = crate::reader::ObjectStoreWrapper {
+            reader: store,
+            location,
+            size: head.size,
+        };
+        let async_client = AsyncBufferedHttpRangeClient::with(object_store_wrapper, "");
+        Self::new(async_client).await
+    }
+}
+
+enum StreamState<T: AsyncHttpRangeClient> {
+    Init(Box<Option<FlatGeobufStreamReader<T>>>),
+    Reading(BoxFuture<'static, Result<(FlatGeobufStreamReader<T>, Option<RecordBatch>)>>),
+}
+
+struct FlatGeobufStreamReader<T: AsyncHttpRangeClient> {
+    selection: AsyncFeatureIter<T>,
+    fgb_schema: FlatGeobufSchema,
+}
+
+impl<T> FlatGeobufStreamReader<T>
+where
+    T: AsyncHttpRangeClient,
+{
+    async fn next_batch(mut self, batch_size: usize) -> Result<(Self, Option<RecordBatch>)> {
+        let mut builder = self.fgb_schema.batch_builder(batch_size)?;
+        while builder.len() < batch_size {
+            match self
+                .selection
+                .next()
+                .await
+                .map_err(|err| GeoArrowError::External(Box::new(err)))?
+            {
+                Some(feature) => builder.push_feature(feature)?,
+                None => break,
+            }
+        }
+
+        if builder.len() == 0 {
+            Ok((self, None))
+        } else {
+            let batch = builder.finish()?;
+            Ok((self, Some(batch)))
+        }
+    }
+}
+
+/// A stream of record batches from a FlatGeobuf file.
+pub struct FlatGeobufStream<T: AsyncHttpRangeClient> {
+    schema: SchemaRef,
+    batch_size: usize,
+    num_rows_remaining: Option<usize>,
+    state: StreamState<T>,
+}
+
+impl<T: AsyncHttpRangeClient> FlatGeobufStream<T> {
+    fn new(
+        selection: AsyncFeatureIter<T>,
+        fgb_schema: FlatGeobufSchema,
+        options: &FlatGeobufReaderOptions,
+    ) -> Self {
+        Self {
+            schema: fgb_schema.schema(),
+            batch_size: options.batch_size.unwrap_or(65_536),
+            num_rows_remaining: selection.features_count(),
+            state: StreamState::Init(Box::new(Some(FlatGeobufStreamReader {
+                selection,
+                fgb_schema,
+            }))),
+        }
+    }
+
+    /// Access the schema of the batches emitted from this stream.
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl<T> Stream for FlatGeobufStream<T>
+where
+    T: AsyncHttpRangeClient + Unpin + Send + 'static,
+{
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match &mut self.state {
+            StreamState::Init(reader) => {
+                let reader = reader.take().expect("lost reader");
+                let mut batch_size = self.batch_size;
+                if let Some(num_rows) = self.num_rows_remaining {
+                    if num_rows == 0 {
+                        return Poll::Ready(None);
+                    }
+                    batch_size = batch_size.min(num_rows);
+                }
+                let fut = reader.next_batch(batch_size).boxed();
+                self.state = StreamState::Reading(fut);
+                self.poll_next(cx)
+            }
+            StreamState::Reading(f) => match ready!(f.poll_unpin(cx)) {
+                Ok((reader, Some(batch))) => {
+                    if let Some(num_rows) = self.num_rows_remaining {
+                        self.num_rows_remaining = Some(num_rows - batch.num_rows());
+                    }
+                    self.state = StreamState::Init(Box::new(Some(reader)));
+                    Poll::Ready(Some(Ok(batch)))
+                }
+                // no more record batches
+                Ok((_reader, None)) => Poll::Ready(None),
+                Err(err) => Poll::Ready(Some(Err(err))),
+            },
+        }
+    }
+}
+
+#[cfg(all(test, feature = "object_store"))]
+mod test {
+    use std::env::current_dir;
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+    use object_store::local::LocalFileSystem;
+    use object_store::path::Path;
+
+    use super::*;
+
+    async fn read_countries(options: FlatGeobufReaderOptions) -> Vec<RecordBatch> {
+        let prefix = current_dir().unwrap().join("../../fixtures/flatgeobuf");
+        let store = Arc::new(LocalFileSystem::new_with_prefix(prefix).unwrap());
+        let builder = FlatGeobufStreamBuilder::new_from_store(store, Path::from("countries.fgb"))
+            .await
+            .unwrap();
+        let reader = builder.read(options).await.unwrap();
+        reader.try_collect::<Vec<_>>().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_countries() {
+        let batches = read_countries(Default::default()).await;
+        let num_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(num_rows, 179);
+    }
+
+    #[tokio::test]
+    async fn test_countries_bbox() {
+        let options = FlatGeobufReaderOptions {
+            bbox: Some((0., -90., 180., 90.)),
+            batch_size: Some(100),
+            ..Default::default()
+        };
+        let batches = read_countries(options).await;
+        let batch_lengths = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+        assert_eq!(batch_lengths, vec![100, 33]);
+    }
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use arrow_schema::{Schema, SchemaBuilder, SchemaRef};
+use flatgeobuf::{Crs, GeometryType, Header};
+use geoarrow_array::GeoArrowType;
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_schema::{
+    CoordType, Dimension, GeometryCollectionType, LineStringType, Metadata, MultiLineStringType,
+    MultiPointType, MultiPolygonType, PointType, PolygonType,
+};
+
+use crate::column::column_field;
+use crate::reader::table_builder::RecordBatchBuilder;
+
+/// Options for the FlatGeobuf reader
+#[derive(Debug, Clone)]
+pub struct FlatGeobufReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+
+    /// A spatial filter for reading rows.
+    ///
+    /// This is evaluated against the packed Hilbert R-tree index stored in the file, so only
+    /// features whose bounding box intersects `(min_x, min_y, max_x, max_y)` are read. Files
+    /// without an index are not supported when a bbox is set.
+    ///
+    /// If set to `None`, no spatial filtering will be performed.
+    pub bbox: Option<(f64, f64, f64, f64)>,
+
+    /// The names of the property columns to read.
+    ///
+    /// Columns are emitted in the order given here. The geometry column is always included. If
+    /// set to `None`, all columns declared in the file header will be read.
+    pub columns: Option<Vec<String>>,
+}
+
+impl Default for FlatGeobufReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+            bbox: None,
+            columns: None,
+        }
+    }
+}
+
+/// The Arrow representation of a FlatGeobuf file, inferred from its header.
+#[derive(Debug, Clone)]
+pub(super) struct FlatGeobufSchema {
+    /// The GeoArrow type of the geometry column.
+    pub(super) data_type: GeoArrowType,
+    /// The schema of the selected property columns.
+    pub(super) properties_schema: SchemaRef,
+    /// For each column in the file header, its position in `properties_schema`, or `None` if the
+    /// column was not selected.
+    pub(super) projection: Arc<[Option<usize>]>,
+}
+
+impl FlatGeobufSchema {
+    pub(super) fn try_new(header: Header<'_>, options: &FlatGeobufReaderOptions) -> Result<Self> {
+        let data_type = infer_data_type(header, options.coord_type)?;
+        let (properties_schema, projection) = infer_schema(header, options.columns.as_deref())?;
+        Ok(Self {
+            data_type,
+            properties_schema,
+            projection,
+        })
+    }
+
+    /// The schema of the record batches emitted by the reader.
+    ///
+    /// Property columns come first, followed by a single `geometry` column.
+    pub(super) fn schema(&self) -> SchemaRef {
+        let geom_field = self.data_type.to_field("geometry", true);
+        let mut fields = self.properties_schema.fields().to_vec();
+        fields.push(Arc::new(geom_field));
+        Arc::new(Schema::new_with_metadata(
+            fields,
+            self.properties_schema.metadata().clone(),
+        ))
+    }
+
+    pub(super) fn batch_builder(&self, capacity: usize) -> Result<RecordBatchBuilder> {
+        RecordBatchBuilder::try_new(self, capacity)
+    }
+}
+
+/// Infer the Arrow schema of the property columns, restricted to `columns` if provided.
+fn infer_schema(
+    header: Header<'_>,
+    columns: Option<&[String]>,
+) -> Result<(SchemaRef, Arc<[Option<usize>]>)> {
+    let header_columns = header
+        .columns()
+        .map(|cols| cols.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let mut projection = vec![None; header_columns.len()];
+
+    let schema = if let Some(columns) = columns {
+        let mut schema = SchemaBuilder::with_capacity(columns.len());
+        for name in columns {
+            let col_idx = header_columns
+                .iter()
+                .position(|col| col.name() == name)
+                .ok_or_else(|| {
+                    GeoArrowError::General(format!(
+                        "Column {} does not exist in FlatGeobuf file",
+                        name
+                    ))
+                })?;
+            projection[col_idx] = Some(schema.fields().len());
+            let col = header_columns[col_idx];
+            schema.push(column_field(&col)?);
+        }
+        schema.finish()
+    } else {
+        let mut schema = SchemaBuilder::with_capacity(header_columns.len());
+        for (col_idx, col) in header_columns.iter().enumerate() {
+            projection[col_idx] = Some(col_idx);
+            schema.push(column_field(col)?);
+        }
+        schema.finish()
+    };
+
+    Ok((Arc::new(schema), projection.into()))
+}
+
+/// Parse CRS information provided by FlatGeobuf into a [Metadata].
+///
+/// WKT is preferred if it exists. Otherwise, authority code will be used as a fallback.
+fn parse_crs(crs: Option<Crs<'_>>) -> Arc<Metadata> {
+    if let Some(crs) = crs {
+        if let Some(wkt) = crs.wkt() {
+            // We use unknown CRS because we don't know for sure it's WKT 2019
+            let crs = geoarrow_schema::Crs::from_unknown_crs_type(wkt.to_string());
+            return Arc::new(Metadata::new(crs, None));
+        }
+
+        // FlatGeobuf defaults the organization to EPSG when only a code is given
+        let org = crs.org().unwrap_or("EPSG");
+        let code = crs.code();
+        if code != 0 {
+            let crs = geoarrow_schema::Crs::from_authority_code(format!("{org}:{code}"));
+            return Arc::new(Metadata::new(crs, None));
+        }
+
+        if let Some(code) = crs.code_string() {
+            let crs = geoarrow_schema::Crs::from_authority_code(format!("{org}:{code}"));
+            return Arc::new(Metadata::new(crs, None));
+        }
+    };
+
+    Default::default()
+}
+
+fn infer_data_type(header: Header<'_>, coord_type: CoordType) -> Result<GeoArrowType> {
+    if header.has_t() | header.has_tm() {
+        return Err(GeoArrowError::General(
+            "T and TM dimensions are not supported".to_string(),
+        ));
+    }
+
+    let dim = match (header.has_z(), header.has_m()) {
+        (false, false) => Dimension::XY,
+        (true, false) => Dimension::XYZ,
+        (false, true) => Dimension::XYM,
+        (true, true) => Dimension::XYZM,
+    };
+    let metadata = parse_crs(header.crs());
+
+    let data_type = match header.geometry_type() {
+        GeometryType::Point => GeoArrowType::Point(PointType::new(coord_type, dim, metadata)),
+        GeometryType::LineString => {
+            GeoArrowType::LineString(LineStringType::new(coord_type, dim, metadata))
+        }
+        GeometryType::Polygon => GeoArrowType::Polygon(PolygonType::new(coord_type, dim, metadata)),
+        GeometryType::MultiPoint => {
+            GeoArrowType::MultiPoint(MultiPointType::new(coord_type, dim, metadata))
+        }
+        GeometryType::MultiLineString => {
+            GeoArrowType::MultiLineString(MultiLineStringType::new(coord_type, dim, metadata))
+        }
+        GeometryType::MultiPolygon => {
+            GeoArrowType::MultiPolygon(MultiPolygonType::new(coord_type, dim, metadata))
+        }
+        GeometryType::GeometryCollection => {
+            GeoArrowType::GeometryCollection(GeometryCollectionType::new(coord_type, dim, metadata))
+        }
+        GeometryType::Unknown => {
+            GeoArrowType::Geometry(geoarrow_schema::GeometryType::new(coord_type, metadata))
+        }
+        geom_type => {
+            return Err(GeoArrowError::NotYetImplemented(format!(
+                "Parsing FlatGeobuf from {:?} geometry type not yet supported",
+                geom_type
+            )));
+        }
+    };
+    Ok(data_type)
+}
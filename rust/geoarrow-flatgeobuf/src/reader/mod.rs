@@ -0,0 +1,21 @@
+//! Read FlatGeobuf files into GeoArrow record batches.
+//!
+//! The Arrow schema is inferred from the file header: each property column maps to an Arrow
+//! column and the geometry column maps to the GeoArrow type matching the header's geometry type,
+//! dimension and CRS. Files with an `Unknown` geometry type are read into a
+//! [`GeometryArray`][geoarrow_array::array::GeometryArray].
+
+#[cfg(feature = "async")]
+mod r#async;
+mod common;
+#[cfg(feature = "object_store")]
+mod object_store_reader;
+mod sync;
+mod table_builder;
+
+#[cfg(feature = "async")]
+pub use r#async::{FlatGeobufStream, FlatGeobufStreamBuilder};
+pub use common::FlatGeobufReaderOptions;
+#[cfg(feature = "object_store")]
+pub use object_store_reader::ObjectStoreWrapper;
+pub use sync::{FlatGeobufReader, FlatGeobufReaderBuilder};
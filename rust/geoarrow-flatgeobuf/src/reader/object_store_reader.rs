@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http_range_client::{AsyncHttpRangeClient, HttpError, Result as HTTPRangeClientResult};
+use object_store::ObjectStore;
+use object_store::path::Path;
+
+/// An [AsyncHttpRangeClient] that fetches byte ranges from an [ObjectStore].
+pub struct ObjectStoreWrapper {
+    /// The path of the FlatGeobuf file within the store.
+    pub location: Path,
+    /// The store to read from.
+    pub reader: Arc<dyn ObjectStore>,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+#[async_trait]
+impl AsyncHttpRangeClient for ObjectStoreWrapper {
+    /// Send a GET range request
+    async fn get_range(&self, _url: &str, range: &str) -> HTTPRangeClientResult<Bytes> {
+        let (start_range, end_range) = range
+            .strip_prefix("bytes=")
+            .and_then(|range| range.split_once('-'))
+            .and_then(|(start, end)| Some((start.parse::<u64>().ok()?, end.parse::<u64>().ok()?)))
+            .ok_or_else(|| HttpError::HttpError(format!("Invalid range header {range}")))?;
+
+        // Add one to the range because HTTP range strings are end-inclusive
+        let end_range = end_range + 1;
+
+        // Flatgeobuf will sometimes overfetch, but not all object store backends support
+        // overfetches (e.g. this errors on a LocalFileSystem)
+        // See https://github.com/flatgeobuf/flatgeobuf/issues/338
+        let end_range = end_range.min(self.size);
+
+        self.reader
+            .get_range(&self.location, start_range..end_range)
+            .await
+            .map_err(|err| HttpError::HttpError(err.to_string()))
+    }
+
+    /// Send a HEAD request and return response header value
+    async fn head_response_header(
+        &self,
+        _url: &str,
+        header: &str,
+    ) -> HTTPRangeClientResult<Option<String>> {
+        // This is a massive hack to align APIs
+        if header == "content-length" {
+            Ok(Some(self.size.to_string()))
+        } else {
+            Ok(None)
+        }
+    }
+}
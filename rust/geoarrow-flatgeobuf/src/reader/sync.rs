@@ -0,0 +1,271 @@
+use std::io::{Read, Seek};
+
+use arrow_array::{RecordBatch, RecordBatchReader};
+use arrow_schema::{ArrowError, SchemaRef};
+use flatgeobuf::{
+    FallibleStreamingIterator, FeatureIter, FgbFeature, FgbReader, NotSeekable, Seekable,
+};
+use geoarrow_array::error::{GeoArrowError, Result};
+
+use crate::reader::common::{FlatGeobufReaderOptions, FlatGeobufSchema};
+
+/// A builder for [FlatGeobufReader]
+pub struct FlatGeobufReaderBuilder<R> {
+    reader: FgbReader<R>,
+}
+
+impl<R: Read> FlatGeobufReaderBuilder<R> {
+    /// Open a new FlatGeobuf reader
+    pub fn open(reader: R) -> Result<Self> {
+        let reader =
+            FgbReader::open(reader).map_err(|err| GeoArrowError::External(Box::new(err)))?;
+        Ok(Self { reader })
+    }
+
+    /// The Arrow schema of the record batches that will be read with these options.
+    pub fn schema(&self, options: &FlatGeobufReaderOptions) -> Result<SchemaRef> {
+        Ok(FlatGeobufSchema::try_new(self.reader.header(), options)?.schema())
+    }
+
+    /// Read features sequentially, without using `Seek`
+    pub fn read_seq(
+        self,
+        options: FlatGeobufReaderOptions,
+    ) -> Result<FlatGeobufReader<R, NotSeekable>> {
+        let fgb_schema = FlatGeobufSchema::try_new(self.reader.header(), &options)?;
+        let selection = if let Some((min_x, min_y, max_x, max_y)) = options.bbox {
+            self.reader.select_bbox_seq(min_x, min_y, max_x, max_y)
+        } else {
+            self.reader.select_all_seq()
+        }
+        .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+        Ok(FlatGeobufReader::new(selection, fgb_schema, &options))
+    }
+}
+
+impl<R: Read + Seek> FlatGeobufReaderBuilder<R> {
+    /// Read features
+    pub fn read(self, options: FlatGeobufReaderOptions) -> Result<FlatGeobufReader<R, Seekable>> {
+        let fgb_schema = FlatGeobufSchema::try_new(self.reader.header(), &options)?;
+        let selection = if let Some((min_x, min_y, max_x, max_y)) = options.bbox {
+            self.reader.select_bbox(min_x, min_y, max_x, max_y)
+        } else {
+            self.reader.select_all()
+        }
+        .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+        Ok(FlatGeobufReader::new(selection, fgb_schema, &options))
+    }
+}
+
+/// An iterator over record batches from a FlatGeobuf file.
+///
+/// This implements [arrow_array::RecordBatchReader], which you can use to access data.
+pub struct FlatGeobufReader<R, S> {
+    selection: FeatureIter<R, S>,
+    fgb_schema: FlatGeobufSchema,
+    schema: SchemaRef,
+    batch_size: usize,
+    num_rows_remaining: Option<usize>,
+}
+
+impl<R, S> FlatGeobufReader<R, S> {
+    fn new(
+        selection: FeatureIter<R, S>,
+        fgb_schema: FlatGeobufSchema,
+        options: &FlatGeobufReaderOptions,
+    ) -> Self {
+        let num_rows_remaining = selection.features_count();
+        Self {
+            selection,
+            schema: fgb_schema.schema(),
+            fgb_schema,
+            batch_size: options.batch_size.unwrap_or(65_536),
+            num_rows_remaining,
+        }
+    }
+}
+
+impl<R, S> FlatGeobufReader<R, S>
+where
+    FeatureIter<R, S>: FallibleStreamingIterator<Item = FgbFeature, Error = flatgeobuf::Error>,
+{
+    fn process_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let mut batch_size = self.batch_size;
+        if let Some(num_rows_remaining) = self.num_rows_remaining {
+            batch_size = batch_size.min(num_rows_remaining);
+        }
+
+        let mut builder = self.fgb_schema.batch_builder(batch_size)?;
+        while builder.len() < batch_size {
+            match self
+                .selection
+                .next()
+                .map_err(|err| GeoArrowError::External(Box::new(err)))?
+            {
+                Some(feature) => builder.push_feature(feature)?,
+                None => break,
+            }
+        }
+
+        if builder.len() == 0 {
+            return Ok(None);
+        }
+
+        if let Some(num_rows_remaining) = self.num_rows_remaining.as_mut() {
+            *num_rows_remaining -= builder.len();
+        }
+        Ok(Some(builder.finish()?))
+    }
+}
+
+impl<R, S> Iterator for FlatGeobufReader<R, S>
+where
+    FeatureIter<R, S>: FallibleStreamingIterator<Item = FgbFeature, Error = flatgeobuf::Error>,
+{
+    type Item = std::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.process_batch()
+            .map_err(|err| ArrowError::ExternalError(Box::new(err)))
+            .transpose()
+    }
+}
+
+impl<R, S> RecordBatchReader for FlatGeobufReader<R, S>
+where
+    FeatureIter<R, S>: FallibleStreamingIterator<Item = FgbFeature, Error = flatgeobuf::Error>,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Float64Type;
+    use arrow_schema::DataType;
+    use geoarrow_array::GeoArrowType;
+    use geoarrow_schema::CoordType;
+
+    use super::*;
+
+    fn read_all(path: &str, options: FlatGeobufReaderOptions) -> (SchemaRef, Vec<RecordBatch>) {
+        let file = BufReader::new(File::open(path).unwrap());
+        let reader = FlatGeobufReaderBuilder::open(file)
+            .unwrap()
+            .read(options)
+            .unwrap();
+        let schema = reader.schema();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        (schema, batches)
+    }
+
+    fn num_rows(batches: &[RecordBatch]) -> usize {
+        batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    #[test]
+    fn test_countries() {
+        let (schema, batches) = read_all(
+            "../../fixtures/flatgeobuf/countries.fgb",
+            Default::default(),
+        );
+        assert_eq!(num_rows(&batches), 179);
+
+        let geo_field = schema.field_with_name("geometry").unwrap();
+        let geo_type = GeoArrowType::try_from(geo_field).unwrap();
+        assert!(matches!(geo_type, GeoArrowType::MultiPolygon(_)));
+        // The header carries WKT, which takes precedence over the EPSG code
+        let crs_value = geo_type.metadata().crs().crs_value().unwrap();
+        assert!(
+            crs_value
+                .as_str()
+                .unwrap()
+                .starts_with("GEOGCRS[\"WGS 84\"")
+        );
+    }
+
+    #[test]
+    fn test_countries_bbox() {
+        let options = FlatGeobufReaderOptions {
+            bbox: Some((0., -90., 180., 90.)),
+            ..Default::default()
+        };
+        let (_schema, batches) = read_all("../../fixtures/flatgeobuf/countries.fgb", options);
+        assert_eq!(num_rows(&batches), 133);
+    }
+
+    #[test]
+    fn test_read_seq() {
+        let file = File::open("../../fixtures/flatgeobuf/countries.fgb").unwrap();
+        let reader = FlatGeobufReaderBuilder::open(file)
+            .unwrap()
+            .read_seq(Default::default())
+            .unwrap();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(num_rows(&batches), 179);
+    }
+
+    #[test]
+    fn test_batch_size() {
+        let options = FlatGeobufReaderOptions {
+            batch_size: Some(50),
+            coord_type: CoordType::Separated,
+            ..Default::default()
+        };
+        let (schema, batches) = read_all("../../fixtures/flatgeobuf/countries.fgb", options);
+        let batch_lengths = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+        assert_eq!(batch_lengths, vec![50, 50, 50, 29]);
+
+        let geo_field = schema.field_with_name("geometry").unwrap();
+        let geo_type = GeoArrowType::try_from(geo_field).unwrap();
+        assert_eq!(geo_type.coord_type(), Some(CoordType::Separated));
+    }
+
+    #[test]
+    fn test_poly_projection() {
+        let options = FlatGeobufReaderOptions {
+            columns: Some(vec!["EAS_ID".to_string(), "AREA".to_string()]),
+            ..Default::default()
+        };
+        let (schema, batches) = read_all("../../fixtures/flatgeobuf/poly00.fgb", options);
+        assert_eq!(num_rows(&batches), 10);
+
+        let field_names = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(field_names, vec!["EAS_ID", "AREA", "geometry"]);
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+
+        let area = batches[0].column(1).as_primitive::<Float64Type>();
+        assert_eq!(area.len(), 10);
+        assert_eq!(area.null_count(), 0);
+    }
+
+    #[test]
+    fn test_projection_missing_column() {
+        let file = File::open("../../fixtures/flatgeobuf/poly00.fgb").unwrap();
+        let options = FlatGeobufReaderOptions {
+            columns: Some(vec!["missing".to_string()]),
+            ..Default::default()
+        };
+        let result = FlatGeobufReaderBuilder::open(file).unwrap().read(options);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_nz_buildings() {
+        let (_schema, batches) = read_all(
+            "../../fixtures/flatgeobuf/nz-building-outlines-small.fgb",
+            Default::default(),
+        );
+        assert!(num_rows(&batches) > 0);
+    }
+}
@@ -0,0 +1,469 @@
+//! Builders for converting a stream of FlatGeobuf features into Arrow record batches.
+
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    ArrayBuilder, BinaryBuilder, BooleanBuilder, Decimal128Builder, Decimal256Builder,
+    Float32Builder, Float64Builder, Int8Builder, Int16Builder, Int32Builder, Int64Builder,
+    StringBuilder, UInt8Builder, UInt16Builder, UInt32Builder, UInt64Builder,
+};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Decimal128Type, Decimal256Type, TimestampMicrosecondType, TimestampMillisecondType,
+    TimestampNanosecondType, TimestampSecondType,
+};
+use arrow_array::{Array, ArrayRef, Int64Array, RecordBatch, make_array, new_empty_array};
+use arrow_cast::cast;
+use arrow_cast::parse::{parse_decimal, string_to_timestamp_nanos};
+use arrow_json::ReaderBuilder;
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+use flatgeobuf::FgbFeature;
+use geo_traits::GeometryTrait;
+use geoarrow_array::builder::{GeometryBuilder, GeometryCollectionBuilder};
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geozero::error::GeozeroError;
+use geozero::{ColumnValue, FeatureProperties, PropertyProcessor};
+
+use crate::column::storage_type;
+use crate::geometry::FlatGeobufGeometryDecoder;
+use crate::reader::common::FlatGeobufSchema;
+
+/// A builder for a single [RecordBatch] of FlatGeobuf features.
+pub(super) struct RecordBatchBuilder {
+    schema: SchemaRef,
+    properties: PropertiesBuilder,
+    geometry: GeometryColumnBuilder,
+}
+
+impl RecordBatchBuilder {
+    pub(super) fn try_new(fgb_schema: &FlatGeobufSchema, capacity: usize) -> Result<Self> {
+        Ok(Self {
+            schema: fgb_schema.schema(),
+            properties: PropertiesBuilder::try_new(fgb_schema, capacity)?,
+            geometry: GeometryColumnBuilder::try_new(fgb_schema.data_type.clone(), capacity)?,
+        })
+    }
+
+    /// The number of features added so far.
+    pub(super) fn len(&self) -> usize {
+        self.properties.row_counter
+    }
+
+    /// Add a single feature's properties and geometry.
+    pub(super) fn push_feature(&mut self, feature: &FgbFeature) -> Result<()> {
+        feature.process_properties(&mut self.properties)?;
+        self.properties.properties_end();
+        self.geometry.push_feature(feature)
+    }
+
+    pub(super) fn finish(self) -> Result<RecordBatch> {
+        let mut columns = self.properties.finish()?;
+        columns.push(self.geometry.finish()?.to_array_ref());
+        Ok(RecordBatch::try_new(self.schema, columns)?)
+    }
+}
+
+/// A builder for the geometry column, chosen from the header's geometry type.
+enum GeometryColumnBuilder {
+    /// Single geometry types are decoded directly from the FlatGeobuf geometry tables.
+    Native(FlatGeobufGeometryDecoder),
+    GeometryCollection(GeometryCollectionBuilder),
+    Geometry(GeometryBuilder),
+}
+
+impl GeometryColumnBuilder {
+    fn try_new(data_type: GeoArrowType, capacity: usize) -> Result<Self> {
+        match data_type {
+            GeoArrowType::GeometryCollection(t) => {
+                Ok(Self::GeometryCollection(GeometryCollectionBuilder::new(t)))
+            }
+            GeoArrowType::Geometry(t) => Ok(Self::Geometry(GeometryBuilder::new(t))),
+            data_type => Ok(Self::Native(FlatGeobufGeometryDecoder::try_new(
+                data_type, capacity,
+            )?)),
+        }
+    }
+
+    fn push_feature(&mut self, feature: &FgbFeature) -> Result<()> {
+        match self {
+            Self::Native(decoder) => decoder.push_geometry(feature.geometry()),
+            Self::GeometryCollection(builder) => {
+                builder.push_geometry(geometry_trait(feature)?.as_ref())
+            }
+            Self::Geometry(builder) => builder.push_geometry(geometry_trait(feature)?.as_ref()),
+        }
+    }
+
+    fn finish(self) -> Result<Arc<dyn GeoArrowArray>> {
+        match self {
+            Self::Native(decoder) => decoder.finish(),
+            Self::GeometryCollection(builder) => Ok(Arc::new(builder.finish())),
+            Self::Geometry(builder) => Ok(Arc::new(builder.finish())),
+        }
+    }
+}
+
+fn geometry_trait(feature: &FgbFeature) -> Result<Option<impl GeometryTrait<T = f64> + '_>> {
+    feature
+        .geometry_trait()
+        .map_err(|err| GeoArrowError::External(Box::new(err)))
+}
+
+/// A builder for the property columns, driven by geozero's [PropertyProcessor].
+struct PropertiesBuilder {
+    fields: Fields,
+    columns: Vec<PropertyBuilder>,
+    /// For each column in the file header, its position in `columns`, or `None` if the column
+    /// was not selected.
+    projection: Arc<[Option<usize>]>,
+    /// The number of rows that have been added, excluding the current row.
+    row_counter: usize,
+}
+
+impl PropertiesBuilder {
+    fn try_new(fgb_schema: &FlatGeobufSchema, capacity: usize) -> Result<Self> {
+        let fields = fgb_schema.properties_schema.fields().clone();
+        let columns = fields
+            .iter()
+            .map(|field| PropertyBuilder::from_field_with_capacity(field, capacity))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            fields,
+            columns,
+            projection: fgb_schema.projection.clone(),
+            row_counter: 0,
+        })
+    }
+
+    /// Fill nulls for any column that wasn't visited in the current feature and move on to the
+    /// next row.
+    ///
+    /// FlatGeobuf omits null values from a feature's properties buffer entirely.
+    fn properties_end(&mut self) {
+        for col in self.columns.iter_mut() {
+            if col.len() == self.row_counter {
+                col.append_null();
+            }
+        }
+        self.row_counter += 1;
+    }
+
+    fn finish(self) -> Result<Vec<ArrayRef>> {
+        self.columns
+            .into_iter()
+            .zip(self.fields.iter())
+            .map(|(col, field)| restore_type(col.finish(), field))
+            .collect()
+    }
+}
+
+impl PropertyProcessor for PropertiesBuilder {
+    fn property(
+        &mut self,
+        idx: usize,
+        name: &str,
+        value: &ColumnValue,
+    ) -> geozero::error::Result<bool> {
+        // idx is the position of the column in the file header
+        if let Some(Some(col_idx)) = self.projection.get(idx) {
+            self.columns[*col_idx].add_value(name, value)?;
+        }
+        Ok(false)
+    }
+}
+
+/// Builder for a single property column.
+#[derive(Debug)]
+enum PropertyBuilder {
+    Bool(BooleanBuilder),
+    Int8(Int8Builder),
+    UInt8(UInt8Builder),
+    Int16(Int16Builder),
+    UInt16(UInt16Builder),
+    Int32(Int32Builder),
+    UInt32(UInt32Builder),
+    Int64(Int64Builder),
+    UInt64(UInt64Builder),
+    Float32(Float32Builder),
+    Float64(Float64Builder),
+    String(StringBuilder),
+    /// A string column with a precision and scale.
+    Decimal128(Decimal128Builder, u8, i8),
+    Decimal256(Decimal256Builder, u8, i8),
+    /// Timestamps in the given unit, without a time zone.
+    DateTime(Int64Builder, TimeUnit),
+    Binary(BinaryBuilder),
+}
+
+impl PropertyBuilder {
+    /// Create the builder for the values of `field`, which decodes them to their
+    /// [`storage_type`].
+    fn from_field_with_capacity(field: &Field, capacity: usize) -> Result<Self> {
+        use PropertyBuilder::*;
+
+        let builder = match &storage_type(field.data_type()) {
+            DataType::Boolean => Bool(BooleanBuilder::with_capacity(capacity)),
+            DataType::Int8 => Int8(Int8Builder::with_capacity(capacity)),
+            DataType::UInt8 => UInt8(UInt8Builder::with_capacity(capacity)),
+            DataType::Int16 => Int16(Int16Builder::with_capacity(capacity)),
+            DataType::UInt16 => UInt16(UInt16Builder::with_capacity(capacity)),
+            DataType::Int32 => Int32(Int32Builder::with_capacity(capacity)),
+            DataType::UInt32 => UInt32(UInt32Builder::with_capacity(capacity)),
+            DataType::Int64 => Int64(Int64Builder::with_capacity(capacity)),
+            DataType::UInt64 => UInt64(UInt64Builder::with_capacity(capacity)),
+            DataType::Float32 => Float32(Float32Builder::with_capacity(capacity)),
+            DataType::Float64 => Float64(Float64Builder::with_capacity(capacity)),
+            DataType::Utf8 => String(StringBuilder::with_capacity(capacity, 0)),
+            DataType::Decimal128(precision, scale) => Decimal128(
+                Decimal128Builder::with_capacity(capacity)
+                    .with_precision_and_scale(*precision, *scale)?,
+                *precision,
+                *scale,
+            ),
+            DataType::Decimal256(precision, scale) => Decimal256(
+                Decimal256Builder::with_capacity(capacity)
+                    .with_precision_and_scale(*precision, *scale)?,
+                *precision,
+                *scale,
+            ),
+            DataType::Timestamp(unit, None) => {
+                DateTime(Int64Builder::with_capacity(capacity), *unit)
+            }
+            DataType::Binary => Binary(BinaryBuilder::with_capacity(capacity, 0)),
+            data_type => {
+                return Err(GeoArrowError::General(format!(
+                    "Unsupported data type {data_type} for FlatGeobuf column {}",
+                    field.name()
+                )));
+            }
+        };
+        Ok(builder)
+    }
+
+    /// Add a geozero [ColumnValue]. The type of the value must match the type of the builder.
+    fn add_value(&mut self, name: &str, value: &ColumnValue) -> geozero::error::Result<()> {
+        match (self, value) {
+            (Self::Bool(builder), ColumnValue::Bool(v)) => builder.append_value(*v),
+            (Self::Int8(builder), ColumnValue::Byte(v)) => builder.append_value(*v),
+            (Self::UInt8(builder), ColumnValue::UByte(v)) => builder.append_value(*v),
+            (Self::Int16(builder), ColumnValue::Short(v)) => builder.append_value(*v),
+            (Self::UInt16(builder), ColumnValue::UShort(v)) => builder.append_value(*v),
+            (Self::Int32(builder), ColumnValue::Int(v)) => builder.append_value(*v),
+            (Self::UInt32(builder), ColumnValue::UInt(v)) => builder.append_value(*v),
+            (Self::Int64(builder), ColumnValue::Long(v)) => builder.append_value(*v),
+            (Self::UInt64(builder), ColumnValue::ULong(v)) => builder.append_value(*v),
+            (Self::Float32(builder), ColumnValue::Float(v)) => builder.append_value(*v),
+            (Self::Float64(builder), ColumnValue::Double(v)) => builder.append_value(*v),
+            (Self::String(builder), ColumnValue::String(v) | ColumnValue::Json(v)) => {
+                builder.append_value(v)
+            }
+            (Self::Decimal128(builder, precision, scale), ColumnValue::String(v)) => {
+                let value = parse_decimal::<Decimal128Type>(v, *precision, *scale)
+                    .map_err(|err| GeozeroError::Property(err.to_string()))?;
+                builder.append_value(value);
+            }
+            (Self::Decimal256(builder, precision, scale), ColumnValue::String(v)) => {
+                let value = parse_decimal::<Decimal256Type>(v, *precision, *scale)
+                    .map_err(|err| GeozeroError::Property(err.to_string()))?;
+                builder.append_value(value);
+            }
+            (Self::DateTime(builder, unit), ColumnValue::DateTime(v)) => {
+                let nanos = string_to_timestamp_nanos(v)
+                    .map_err(|err| GeozeroError::Property(err.to_string()))?;
+                let value = match unit {
+                    TimeUnit::Second => nanos.div_euclid(1_000_000_000),
+                    TimeUnit::Millisecond => nanos.div_euclid(1_000_000),
+                    TimeUnit::Microsecond => nanos.div_euclid(1_000),
+                    TimeUnit::Nanosecond => nanos,
+                };
+                builder.append_value(value);
+            }
+            (Self::Binary(builder), ColumnValue::Binary(v)) => builder.append_value(v),
+            (builder, value) => {
+                return Err(GeozeroError::Property(format!(
+                    "Unexpected value {:?} for column {} of type {:?}",
+                    value, name, builder
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn append_null(&mut self) {
+        match self {
+            Self::Bool(builder) => builder.append_null(),
+            Self::Int8(builder) => builder.append_null(),
+            Self::UInt8(builder) => builder.append_null(),
+            Self::Int16(builder) => builder.append_null(),
+            Self::UInt16(builder) => builder.append_null(),
+            Self::Int32(builder) => builder.append_null(),
+            Self::UInt32(builder) => builder.append_null(),
+            Self::Int64(builder) => builder.append_null(),
+            Self::UInt64(builder) => builder.append_null(),
+            Self::Float32(builder) => builder.append_null(),
+            Self::Float64(builder) => builder.append_null(),
+            Self::String(builder) => builder.append_null(),
+            Self::Decimal128(builder, _, _) => builder.append_null(),
+            Self::Decimal256(builder, _, _) => builder.append_null(),
+            Self::DateTime(builder, _) => builder.append_null(),
+            Self::Binary(builder) => builder.append_null(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Bool(builder) => builder.len(),
+            Self::Int8(builder) => builder.len(),
+            Self::UInt8(builder) => builder.len(),
+            Self::Int16(builder) => builder.len(),
+            Self::UInt16(builder) => builder.len(),
+            Self::Int32(builder) => builder.len(),
+            Self::UInt32(builder) => builder.len(),
+            Self::Int64(builder) => builder.len(),
+            Self::UInt64(builder) => builder.len(),
+            Self::Float32(builder) => builder.len(),
+            Self::Float64(builder) => builder.len(),
+            Self::String(builder) => builder.len(),
+            Self::Decimal128(builder, _, _) => builder.len(),
+            Self::Decimal256(builder, _, _) => builder.len(),
+            Self::DateTime(builder, _) => builder.len(),
+            Self::Binary(builder) => builder.len(),
+        }
+    }
+
+    fn finish(self) -> ArrayRef {
+        match self {
+            Self::Bool(mut builder) => Arc::new(builder.finish()),
+            Self::Int8(mut builder) => Arc::new(builder.finish()),
+            Self::UInt8(mut builder) => Arc::new(builder.finish()),
+            Self::Int16(mut builder) => Arc::new(builder.finish()),
+            Self::UInt16(mut builder) => Arc::new(builder.finish()),
+            Self::Int32(mut builder) => Arc::new(builder.finish()),
+            Self::UInt32(mut builder) => Arc::new(builder.finish()),
+            Self::Int64(mut builder) => Arc::new(builder.finish()),
+            Self::UInt64(mut builder) => Arc::new(builder.finish()),
+            Self::Float32(mut builder) => Arc::new(builder.finish()),
+            Self::Float64(mut builder) => Arc::new(builder.finish()),
+            Self::String(mut builder) => Arc::new(builder.finish()),
+            Self::Decimal128(mut builder, _, _) => Arc::new(builder.finish()),
+            Self::Decimal256(mut builder, _, _) => Arc::new(builder.finish()),
+            Self::DateTime(mut builder, unit) => {
+                let values = builder.finish();
+                match unit {
+                    TimeUnit::Second => Arc::new(values.reinterpret_cast::<TimestampSecondType>()),
+                    TimeUnit::Millisecond => {
+                        Arc::new(values.reinterpret_cast::<TimestampMillisecondType>())
+                    }
+                    TimeUnit::Microsecond => {
+                        Arc::new(values.reinterpret_cast::<TimestampMicrosecondType>())
+                    }
+                    TimeUnit::Nanosecond => {
+                        Arc::new(values.reinterpret_cast::<TimestampNanosecondType>())
+                    }
+                }
+            }
+            Self::Binary(mut builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/// Convert a column decoded to its [`storage_type`] to the type of `field`.
+fn restore_type(array: ArrayRef, field: &Field) -> Result<ArrayRef> {
+    let data_type = field.data_type();
+    if array.data_type() == data_type {
+        return Ok(array);
+    }
+    let array = match data_type {
+        // Timestamps are decoded as UTC, which is also how Arrow stores timestamps with a time zone
+        DataType::Timestamp(_, Some(_)) => reinterpret(array, data_type)?,
+        DataType::Duration(unit) => {
+            let values = array
+                .as_string::<i32>()
+                .iter()
+                .map(|value| value.map(|value| parse_duration(value, *unit)).transpose())
+                .collect::<Result<Vec<_>>>()?;
+            reinterpret(Arc::new(Int64Array::from(values)), data_type)?
+        }
+        DataType::List(_)
+        | DataType::LargeList(_)
+        | DataType::FixedSizeList(_, _)
+        | DataType::Struct(_)
+        | DataType::Map(_, _) => decode_json(array.as_ref(), data_type)?,
+        DataType::Dictionary(_, value_type) => {
+            let value_field = field.clone().with_data_type(value_type.as_ref().clone());
+            cast(&restore_type(array, &value_field)?, data_type)?
+        }
+        _ => cast(&array, data_type)?,
+    };
+    Ok(array)
+}
+
+/// Change the type of an array to another type with the same memory layout.
+fn reinterpret(array: ArrayRef, data_type: &DataType) -> Result<ArrayRef> {
+    let data = array
+        .into_data()
+        .into_builder()
+        .data_type(data_type.clone())
+        .build()?;
+    Ok(make_array(data))
+}
+
+/// Parse an ISO 8601 duration as formatted by Arrow, such as `PT1.5S`, `-PT3S` or `P1DT2S`.
+fn parse_duration(value: &str, unit: TimeUnit) -> Result<i64> {
+    let invalid = || GeoArrowError::General(format!("Invalid ISO 8601 duration {value}"));
+
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value),
+    };
+    let rest = rest.strip_prefix('P').ok_or_else(invalid)?;
+    let (days, rest) = match rest.split_once('D') {
+        Some((days, rest)) => (days.parse::<i128>().map_err(|_| invalid())?, rest),
+        None => (0, rest),
+    };
+    let nanos = match rest.strip_prefix('T') {
+        Some(time) => {
+            let seconds = time.strip_suffix('S').ok_or_else(invalid)?;
+            let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+            if fraction.len() > 9 {
+                return Err(invalid());
+            }
+            let whole = whole.parse::<i128>().map_err(|_| invalid())?;
+            let fraction = format!("{fraction:0<9}")
+                .parse::<i128>()
+                .map_err(|_| invalid())?;
+            whole * 1_000_000_000 + fraction
+        }
+        None if rest.is_empty() => 0,
+        None => return Err(invalid()),
+    };
+    let nanos = sign * (days * 86_400_000_000_000 + nanos);
+    let value = match unit {
+        TimeUnit::Second => nanos / 1_000_000_000,
+        TimeUnit::Millisecond => nanos / 1_000_000,
+        TimeUnit::Microsecond => nanos / 1_000,
+        TimeUnit::Nanosecond => nanos,
+    };
+    i64::try_from(value).map_err(|_| invalid())
+}
+
+/// Decode a string column of JSON values, as written for nested types, to `data_type`.
+fn decode_json(array: &dyn Array, data_type: &DataType) -> Result<ArrayRef> {
+    let mut json = String::new();
+    for value in array.as_string::<i32>().iter() {
+        json.push_str("{\"value\":");
+        json.push_str(value.unwrap_or("null"));
+        json.push_str("}\n");
+    }
+
+    let field = Field::new("value", data_type.clone(), true);
+    let mut decoder = ReaderBuilder::new(Arc::new(Schema::new(vec![field])))
+        .with_batch_size(array.len().max(1))
+        .build_decoder()?;
+    decoder.decode(json.as_bytes())?;
+    match decoder.flush()? {
+        Some(batch) => Ok(batch.column(0).clone()),
+        None => Ok(new_empty_array(data_type)),
+    }
+}
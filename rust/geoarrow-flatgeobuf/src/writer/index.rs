@@ -0,0 +1,461 @@
+//! Building the FlatGeobuf packed Hilbert R-tree with bounded memory.
+//!
+//! The upstream writer holds one index node per feature in memory and sorts them at once. Here,
+//! index entries are instead spilled to temporary files, sorted with an external merge sort, and
+//! the tree is built one level at a time, so memory use only depends on the sort buffer size.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::Path;
+
+/// The maximum number of sorted runs merged at once, which bounds the number of open files.
+const MAX_MERGE_FAN_IN: usize = 64;
+
+const HILBERT_MAX: u32 = (1 << 16) - 1;
+
+/// A bounding box with an offset, as stored in the FlatGeobuf index.
+///
+/// In a leaf node, `offset` is the byte offset of the feature in the features section. In a parent
+/// node, it is the index of the node's first child.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct NodeItem {
+    pub(super) min_x: f64,
+    pub(super) min_y: f64,
+    pub(super) max_x: f64,
+    pub(super) max_y: f64,
+    pub(super) offset: u64,
+}
+
+impl NodeItem {
+    /// The serialized size of a node in the index.
+    const SIZE: usize = 40;
+
+    /// An empty bounding box, which is the identity for [`Self::expand`].
+    ///
+    /// This is also the bounding box of a feature with a null or empty geometry. Its minimum is
+    /// larger than its maximum, so it doesn't intersect any search box and such features are only
+    /// read when the index isn't used.
+    pub(super) fn empty(offset: u64) -> Self {
+        Self {
+            min_x: f64::INFINITY,
+            min_y: f64::INFINITY,
+            max_x: f64::NEG_INFINITY,
+            max_y: f64::NEG_INFINITY,
+            offset,
+        }
+    }
+
+    /// Whether this bounding box doesn't contain any point.
+    pub(super) fn is_empty(&self) -> bool {
+        self.min_x > self.max_x || self.min_y > self.max_y
+    }
+
+    pub(super) fn expand(&mut self, other: &NodeItem) {
+        self.expand_xy(other.min_x, other.min_y);
+        self.expand_xy(other.max_x, other.max_y);
+    }
+
+    pub(super) fn expand_xy(&mut self, x: f64, y: f64) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    fn width(&self) -> f64 {
+        self.max_x - self.min_x
+    }
+
+    fn height(&self) -> f64 {
+        self.max_y - self.min_y
+    }
+
+    fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&self.min_x.to_le_bytes())?;
+        writer.write_all(&self.min_y.to_le_bytes())?;
+        writer.write_all(&self.max_x.to_le_bytes())?;
+        writer.write_all(&self.max_y.to_le_bytes())?;
+        writer.write_all(&self.offset.to_le_bytes())
+    }
+
+    fn read(buf: &[u8]) -> Self {
+        let f64_at = |i: usize| f64::from_le_bytes(buf[i..i + 8].try_into().unwrap());
+        Self {
+            min_x: f64_at(0),
+            min_y: f64_at(8),
+            max_x: f64_at(16),
+            max_y: f64_at(24),
+            offset: u64::from_le_bytes(buf[32..40].try_into().unwrap()),
+        }
+    }
+}
+
+/// A feature spilled to a temporary file, waiting to be sorted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct SpilledFeature {
+    /// The bounding box of the feature, with `offset` the feature's position in the spill file.
+    pub(super) bbox: NodeItem,
+    /// The size of the feature in bytes, including its size prefix.
+    pub(super) len: u64,
+}
+
+impl SpilledFeature {
+    const SIZE: usize = NodeItem::SIZE + 8;
+
+    pub(super) fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.bbox.write(writer)?;
+        writer.write_all(&self.len.to_le_bytes())
+    }
+
+    /// Read the next entry, returning `None` at the end of the input.
+    pub(super) fn read(reader: &mut impl Read) -> io::Result<Option<Self>> {
+        let mut buf = [0; Self::SIZE];
+        match reader.read_exact(&mut buf) {
+            Ok(()) => Ok(Some(Self {
+                bbox: NodeItem::read(&buf),
+                len: u64::from_le_bytes(buf[NodeItem::SIZE..].try_into().unwrap()),
+            })),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+pub(super) fn temp_file(temp_dir: Option<&Path>) -> io::Result<File> {
+    match temp_dir {
+        Some(dir) => tempfile::tempfile_in(dir),
+        None => tempfile::tempfile(),
+    }
+}
+
+/// Sort spilled features by the Hilbert value of their bounding box center within `extent`.
+///
+/// As in the FlatGeobuf reference implementation, features are sorted in descending Hilbert
+/// order. At most `buffer_size` features are held in memory: each buffer is sorted and written to
+/// a temporary file, and the sorted runs are then merged.
+pub(super) fn hilbert_sort(
+    features: File,
+    extent: &NodeItem,
+    buffer_size: usize,
+    temp_dir: Option<&Path>,
+) -> io::Result<File> {
+    let mut reader = BufReader::new(rewind(features)?);
+    let buffer_size = buffer_size.max(1);
+
+    let mut runs = vec![];
+    // The buffer size may be unbounded, for sorting in memory
+    let mut buffer = Vec::with_capacity(buffer_size.min(1 << 16));
+    loop {
+        let feature = SpilledFeature::read(&mut reader)?;
+        if let Some(feature) = feature {
+            buffer.push((hilbert_bbox(&feature.bbox, extent), feature));
+        }
+        if buffer.len() == buffer_size || (feature.is_none() && !buffer.is_empty()) {
+            // A stable sort keeps features with equal Hilbert values in input order
+            buffer.sort_by_key(|(hilbert, _)| Reverse(*hilbert));
+            let mut run = BufWriter::new(temp_file(temp_dir)?);
+            for (_, feature) in buffer.drain(..) {
+                feature.write(&mut run)?;
+            }
+            runs.push(run.into_inner()?);
+        }
+        if feature.is_none() {
+            break;
+        }
+    }
+
+    while runs.len() > 1 {
+        let mut merged = Vec::with_capacity(runs.len().div_ceil(MAX_MERGE_FAN_IN));
+        let mut runs_iter = runs.into_iter().peekable();
+        while runs_iter.peek().is_some() {
+            let chunk = runs_iter.by_ref().take(MAX_MERGE_FAN_IN).collect();
+            merged.push(merge_runs(chunk, extent, temp_dir)?);
+        }
+        runs = merged;
+    }
+
+    match runs.pop() {
+        Some(run) => rewind(run),
+        None => temp_file(temp_dir),
+    }
+}
+
+/// Merge sorted runs into a single sorted run.
+fn merge_runs(runs: Vec<File>, extent: &NodeItem, temp_dir: Option<&Path>) -> io::Result<File> {
+    let mut readers = runs
+        .into_iter()
+        .map(|run| Ok(BufReader::new(rewind(run)?)))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // The heap is ordered by the largest Hilbert value, then by the earliest run, so that the
+    // merge is stable.
+    let mut heads = Vec::with_capacity(readers.len());
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (run_idx, reader) in readers.iter_mut().enumerate() {
+        let head = SpilledFeature::read(reader)?;
+        if let Some(feature) = &head {
+            heap.push((hilbert_bbox(&feature.bbox, extent), Reverse(run_idx)));
+        }
+        heads.push(head);
+    }
+
+    let mut output = BufWriter::new(temp_file(temp_dir)?);
+    while let Some((_, Reverse(run_idx))) = heap.pop() {
+        let feature = heads[run_idx].take().unwrap();
+        feature.write(&mut output)?;
+
+        let head = SpilledFeature::read(&mut readers[run_idx])?;
+        if let Some(feature) = &head {
+            heap.push((hilbert_bbox(&feature.bbox, extent), Reverse(run_idx)));
+        }
+        heads[run_idx] = head;
+    }
+    Ok(output.into_inner()?)
+}
+
+fn rewind(mut file: File) -> io::Result<File> {
+    file.seek(SeekFrom::Start(0))?;
+    Ok(file)
+}
+
+/// The range of node indices of each level of the tree, from the leaves up to the root.
+///
+/// Nodes are stored root first, so the leaves occupy the end of the index.
+fn level_bounds(num_items: usize, node_size: u16) -> Vec<Range<usize>> {
+    let node_size = node_size as usize;
+
+    // Number of nodes per level, bottom up
+    let mut level_num_nodes = vec![num_items];
+    let mut n = num_items;
+    let mut num_nodes = n;
+    loop {
+        n = n.div_ceil(node_size);
+        num_nodes += n;
+        level_num_nodes.push(n);
+        if n == 1 {
+            break;
+        }
+    }
+
+    let mut level_bounds = Vec::with_capacity(level_num_nodes.len());
+    let mut end = num_nodes;
+    for level_size in level_num_nodes {
+        level_bounds.push(end - level_size..end);
+        end -= level_size;
+    }
+    level_bounds
+}
+
+/// Write the packed R-tree for features sorted by [hilbert_sort].
+///
+/// The leaf level is built by assigning each feature its offset in the output features section.
+/// Each parent level is then computed from the level below and spilled to its own temporary file,
+/// before all levels are copied to `writer`, root first.
+pub(super) fn write_index(
+    sorted: &mut File,
+    num_items: usize,
+    node_size: u16,
+    temp_dir: Option<&Path>,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let bounds = level_bounds(num_items, node_size);
+
+    let mut reader = BufReader::new(&mut *sorted);
+    reader.seek(SeekFrom::Start(0))?;
+    let mut leaves = BufWriter::new(temp_file(temp_dir)?);
+    let mut feature_offset = 0;
+    while let Some(feature) = SpilledFeature::read(&mut reader)? {
+        let node = NodeItem {
+            offset: feature_offset,
+            ..feature.bbox
+        };
+        node.write(&mut leaves)?;
+        feature_offset += feature.len;
+    }
+
+    let mut levels = vec![leaves.into_inner()?];
+    for level in 1..bounds.len() {
+        let children = rewind(levels.last_mut().unwrap().try_clone()?)?;
+        let mut children = BufReader::new(children);
+        let mut parents = BufWriter::new(temp_file(temp_dir)?);
+
+        let mut buf = [0; NodeItem::SIZE];
+        let mut child_idx = bounds[level - 1].start;
+        while child_idx < bounds[level - 1].end {
+            let mut parent = NodeItem::empty(child_idx as u64);
+            for _ in 0..node_size {
+                if child_idx >= bounds[level - 1].end {
+                    break;
+                }
+                children.read_exact(&mut buf)?;
+                parent.expand(&NodeItem::read(&buf));
+                child_idx += 1;
+            }
+            parent.write(&mut parents)?;
+        }
+        levels.push(parents.into_inner()?);
+    }
+
+    for level in levels.into_iter().rev() {
+        io::copy(&mut rewind(level)?, writer)?;
+    }
+    Ok(())
+}
+
+/// The Hilbert value of the center of `node` within `extent`.
+///
+/// Empty bounding boxes have no center and get the lowest value, so that features with null or
+/// empty geometries are sorted last.
+fn hilbert_bbox(node: &NodeItem, extent: &NodeItem) -> u32 {
+    if node.is_empty() {
+        return 0;
+    }
+    let scale = |value: f64, min: f64, size: f64| {
+        if size == 0.0 {
+            0
+        } else {
+            (HILBERT_MAX as f64 * (value - min) / size).floor() as u32
+        }
+    };
+    let x = scale(
+        (node.min_x + node.max_x) / 2.0,
+        extent.min_x,
+        extent.width(),
+    );
+    let y = scale(
+        (node.min_y + node.max_y) / 2.0,
+        extent.min_y,
+        extent.height(),
+    );
+    hilbert(x, y)
+}
+
+/// Fast Hilbert curve algorithm by <http://threadlocalmutex.com/>, as used by the FlatGeobuf
+/// reference implementation.
+fn hilbert(x: u32, y: u32) -> u32 {
+    let mut a = x ^ y;
+    let mut b = 0xFFFF ^ a;
+    let mut c = 0xFFFF ^ (x | y);
+    let mut d = x & (y ^ 0xFFFF);
+
+    let mut aa = a | (b >> 1);
+    let mut bb = (a >> 1) ^ a;
+    let mut cc = ((c >> 1) ^ (b & (d >> 1))) ^ c;
+    let mut dd = ((a & (c >> 1)) ^ (d >> 1)) ^ d;
+
+    a = aa;
+    b = bb;
+    c = cc;
+    d = dd;
+    aa = (a & (a >> 2)) ^ (b & (b >> 2));
+    bb = (a & (b >> 2)) ^ (b & ((a ^ b) >> 2));
+    cc ^= (a & (c >> 2)) ^ (b & (d >> 2));
+    dd ^= (b & (c >> 2)) ^ ((a ^ b) & (d >> 2));
+
+    a = aa;
+    b = bb;
+    c = cc;
+    d = dd;
+    aa = (a & (a >> 4)) ^ (b & (b >> 4));
+    bb = (a & (b >> 4)) ^ (b & ((a ^ b) >> 4));
+    cc ^= (a & (c >> 4)) ^ (b & (d >> 4));
+    dd ^= (b & (c >> 4)) ^ ((a ^ b) & (d >> 4));
+
+    a = aa;
+    b = bb;
+    c = cc;
+    d = dd;
+    cc ^= (a & (c >> 8)) ^ (b & (d >> 8));
+    dd ^= (b & (c >> 8)) ^ ((a ^ b) & (d >> 8));
+
+    a = cc ^ (cc >> 1);
+    b = dd ^ (dd >> 1);
+
+    let mut i0 = x ^ y;
+    let mut i1 = b | (0xFFFF ^ (i0 | a));
+
+    i0 = (i0 | (i0 << 8)) & 0x00FF00FF;
+    i0 = (i0 | (i0 << 4)) & 0x0F0F0F0F;
+    i0 = (i0 | (i0 << 2)) & 0x33333333;
+    i0 = (i0 | (i0 << 1)) & 0x55555555;
+
+    i1 = (i1 | (i1 << 8)) & 0x00FF00FF;
+    i1 = (i1 | (i1 << 4)) & 0x0F0F0F0F;
+    i1 = (i1 | (i1 << 2)) & 0x33333333;
+    i1 = (i1 | (i1 << 1)) & 0x55555555;
+
+    (i1 << 1) | i0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_level_bounds() {
+        assert_eq!(level_bounds(1, 16), vec![1..2, 0..1]);
+        // 100 leaves, 7 level-one nodes and a root
+        assert_eq!(level_bounds(100, 16), vec![8..108, 1..8, 0..1]);
+    }
+
+    #[test]
+    fn test_empty_bbox() {
+        let empty = NodeItem::empty(0);
+        assert!(empty.is_empty());
+        let extent = NodeItem {
+            min_x: 0.,
+            min_y: 0.,
+            max_x: 10.,
+            max_y: 10.,
+            offset: 0,
+        };
+        assert_eq!(hilbert_bbox(&empty, &extent), 0);
+
+        // Empty children don't change the bounding box of their parent
+        let mut parent = NodeItem::empty(0);
+        parent.expand(&extent);
+        parent.expand(&empty);
+        assert_eq!(parent, extent);
+    }
+
+    #[test]
+    fn test_external_sort_matches_in_memory_sort() {
+        let features = (0..1000)
+            .map(|i| {
+                let x = ((i * 7919) % 1000) as f64;
+                let y = ((i * 104729) % 1000) as f64;
+                SpilledFeature {
+                    bbox: NodeItem {
+                        min_x: x,
+                        min_y: y,
+                        max_x: x + 1.0,
+                        max_y: y + 1.0,
+                        offset: i,
+                    },
+                    len: 1,
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut extent = NodeItem::empty(0);
+        features.iter().for_each(|f| extent.expand(&f.bbox));
+
+        let mut expected = features.clone();
+        expected.sort_by_key(|f| Reverse(hilbert_bbox(&f.bbox, &extent)));
+
+        let mut spill = BufWriter::new(tempfile::tempfile().unwrap());
+        features.iter().for_each(|f| f.write(&mut spill).unwrap());
+        let spill = spill.into_inner().unwrap();
+
+        // Small buffers force several sorted runs and merge passes
+        let sorted = hilbert_sort(spill, &extent, 7, None).unwrap();
+        let mut reader = BufReader::new(sorted);
+        let mut actual = vec![];
+        while let Some(feature) = SpilledFeature::read(&mut reader).unwrap() {
+            actual.push(feature);
+        }
+        assert_eq!(actual, expected);
+    }
+}
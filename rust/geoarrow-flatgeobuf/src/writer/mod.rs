@@ -0,0 +1,866 @@
+mod index;
+mod properties;
+mod streaming;
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arrow_array::{RecordBatch, RecordBatchReader};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use geoarrow_array::array::from_arrow_array;
+use geoarrow_array::cast::to_wkb;
+use geoarrow_array::crs::{CRSTransform, DefaultCRSTransform};
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::geozero::export::GeozeroRecordBatchReader;
+use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geoarrow_schema::Metadata;
+
+/// Options for the FlatGeobuf writer
+#[derive(Debug)]
+pub struct FlatGeobufWriterOptions {
+    /// Write index and sort features accordingly.
+    pub write_index: bool,
+    /// Detect geometry type when `geometry_type` is Unknown.
+    pub detect_type: bool,
+    /// Convert single to multi geometries, if `geometry_type` is multi type or Unknown
+    pub promote_to_multi: bool,
+    /// Dataset title
+    pub title: Option<String>,
+    /// Dataset description (intended for free form long text)
+    pub description: Option<String>,
+    /// Dataset metadata (intended to be application specific and
+    pub metadata: Option<String>,
+    /// A method for transforming CRS to WKT
+    ///
+    /// This is implemented as an external trait so that external libraries can inject the method
+    /// for CRS conversions. For example, the Python API uses the `pyproj` Python library to
+    /// perform the conversion rather than linking into PROJ from Rust.
+    pub crs_transform: Option<Box<dyn CRSTransform>>,
+    /// The name of the column to write as the FlatGeobuf geometry.
+    ///
+    /// FlatGeobuf stores a single geometry per feature, so any other geometry columns are written
+    /// as WKB binary properties. If `None`, the first geometry column in the schema is used.
+    pub geometry_column: Option<String>,
+    /// Build the spatial index with bounded memory, by spilling index entries to temporary files.
+    ///
+    /// Features are always staged in a temporary file, since the header can only be written once
+    /// every feature has been seen. By default the index entries (one bounding box per feature)
+    /// are then sorted in memory at once. When this is set, they are instead sorted with an
+    /// external merge sort of at most `sort_buffer_size` entries at a time. This only has an effect
+    /// when `write_index` is set.
+    pub spill_to_disk: bool,
+    /// The maximum number of features sorted in memory at once when `spill_to_disk` is set.
+    pub sort_buffer_size: usize,
+    /// The directory for temporary files. If `None`, the system's temporary directory is used.
+    pub temp_dir: Option<PathBuf>,
+}
+
+impl Default for FlatGeobufWriterOptions {
+    fn default() -> Self {
+        Self {
+            write_index: true,
+            detect_type: true,
+            promote_to_multi: true,
+            crs_transform: Some(Box::new(DefaultCRSTransform::default())),
+            title: None,
+            description: None,
+            metadata: None,
+            geometry_column: None,
+            spill_to_disk: false,
+            sort_buffer_size: 1 << 20,
+            temp_dir: None,
+        }
+    }
+}
+
+impl FlatGeobufWriterOptions {
+    /// Create a WKT CRS from whatever CRS exists in the [Metadata].
+    ///
+    /// This uses the [CRSTransform] supplied in the [FlatGeobufWriterOptions].
+    ///
+    /// If no CRS exists in the Metadata, None will be returned here.
+    fn create_wkt_crs(&self, array_meta: &Metadata) -> Result<Option<String>> {
+        if let Some(crs_transform) = &self.crs_transform {
+            crs_transform.extract_wkt(array_meta.crs())
+        } else {
+            DefaultCRSTransform::default().extract_wkt(array_meta.crs())
+        }
+    }
+}
+
+/// Write an iterator of GeoArrow RecordBatches to a FlatGeobuf file.
+///
+/// `name` is written as the dataset name in the header and is what OGR observes as the layer name
+/// of the file.
+pub fn write_flatgeobuf<W: Write, S: Into<GeozeroRecordBatchReader>>(
+    stream: S,
+    writer: W,
+    name: &str,
+) -> Result<()> {
+    write_flatgeobuf_with_options(stream, writer, name, Default::default())
+}
+
+/// Write a Table to a FlatGeobuf file with specific writer options.
+///
+/// `name` is written as the dataset name in the header and is what OGR observes as the layer name
+/// of the file.
+///
+/// Property columns are stored as described in [`crate::column`], with their field metadata
+/// written to the column definitions in the header.
+///
+/// Native geometry columns are encoded straight from their coordinate buffers, and other
+/// geometry columns through [geo_traits]. Features are staged in a temporary file, so that the
+/// header can record the feature count, extent, detected geometry type and dimension.
+///
+/// If the geometry column's type carries no dimension, as for
+/// [`GeometryArray`][geoarrow_array::array::GeometryArray], WKB and WKT, the header has Z (or M)
+/// if any geometry has Z (or M) values. This is detected while the features are staged, so the
+/// input is never buffered in memory.
+pub fn write_flatgeobuf_with_options<W: Write, S: Into<GeozeroRecordBatchReader>>(
+    stream: S,
+    writer: W,
+    name: &str,
+    options: FlatGeobufWriterOptions,
+) -> Result<()> {
+    let stream: GeozeroRecordBatchReader = stream.into();
+    let mut reader = stream.into_inner();
+
+    let schema = reader.schema();
+    let geom_col_idxs = geometry_columns(schema.as_ref());
+    let geometry_column_index =
+        select_geometry_column(&schema, &geom_col_idxs, options.geometry_column.as_deref())?;
+    let geometry_field = schema.field(geometry_column_index);
+    let geo_data_type = GeoArrowType::try_from(geometry_field)?;
+
+    if geom_col_idxs.len() > 1 {
+        let wkb_columns = geom_col_idxs
+            .into_iter()
+            .filter(|idx| *idx != geometry_column_index)
+            .collect();
+        reader = Box::new(WkbPropertiesReader::new(reader, wkb_columns));
+    }
+
+    let wkt_crs_str = options.create_wkt_crs(geo_data_type.metadata())?;
+    streaming::write_streaming(
+        reader,
+        writer,
+        name,
+        &options,
+        geometry_column_index,
+        &geo_data_type,
+        wkt_crs_str.as_deref(),
+    )
+}
+
+/// Find the index of the geometry column to write as the FlatGeobuf geometry.
+fn select_geometry_column(
+    schema: &Schema,
+    geom_col_idxs: &[usize],
+    name: Option<&str>,
+) -> Result<usize> {
+    if let Some(name) = name {
+        geom_col_idxs
+            .iter()
+            .copied()
+            .find(|idx| schema.field(*idx).name() == name)
+            .ok_or_else(|| {
+                GeoArrowError::General(format!("Geometry column {} does not exist", name))
+            })
+    } else {
+        geom_col_idxs.first().copied().ok_or_else(|| {
+            GeoArrowError::General("No geometry column found in FlatGeobuf writer".to_string())
+        })
+    }
+}
+
+fn infer_flatgeobuf_geometry_type(geo_data_type: &GeoArrowType) -> flatgeobuf::GeometryType {
+    use GeoArrowType::*;
+    match geo_data_type {
+        Point(_) => flatgeobuf::GeometryType::Point,
+        LineString(_) => flatgeobuf::GeometryType::LineString,
+        Polygon(_) => flatgeobuf::GeometryType::Polygon,
+        MultiPoint(_) => flatgeobuf::GeometryType::MultiPoint,
+        MultiLineString(_) => flatgeobuf::GeometryType::MultiLineString,
+        MultiPolygon(_) => flatgeobuf::GeometryType::MultiPolygon,
+        Rect(_) | Geometry(_) | Wkb(_) | LargeWkb(_) | Wkt(_) | LargeWkt(_) => {
+            flatgeobuf::GeometryType::Unknown
+        }
+        GeometryCollection(_) => flatgeobuf::GeometryType::GeometryCollection,
+    }
+}
+
+// Note: this is duplicated from the `geoarrow-array` crate.
+fn geometry_columns(schema: &Schema) -> Vec<usize> {
+    let mut geom_indices = vec![];
+    for (field_idx, field) in schema.fields().iter().enumerate() {
+        // We first check that an extension type name is set and then check that we can coerce to a
+        // GeoArrowType so that we don't accept columns that are _compatible_ with geoarrow storage
+        // but aren't set as geoarrow extension types.
+        if let Some(_ext_name) = field.extension_type_name() {
+            if let Ok(_geoarrow_type) = GeoArrowType::try_from(field.as_ref()) {
+                geom_indices.push(field_idx);
+            }
+        }
+    }
+    geom_indices
+}
+
+/// A [RecordBatchReader] that converts the given geometry columns to plain WKB binary columns, so
+/// that they are written as FlatGeobuf properties.
+struct WkbPropertiesReader {
+    reader: Box<dyn RecordBatchReader>,
+    schema: SchemaRef,
+    wkb_columns: Vec<usize>,
+}
+
+impl WkbPropertiesReader {
+    fn new(reader: Box<dyn RecordBatchReader>, wkb_columns: Vec<usize>) -> Self {
+        let input_schema = reader.schema();
+        let fields = input_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                if wkb_columns.contains(&idx) {
+                    // Drop the extension metadata so that this is no longer a geometry column
+                    Arc::new(Field::new(
+                        field.name(),
+                        DataType::Binary,
+                        field.is_nullable(),
+                    ))
+                } else {
+                    field.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        ));
+        Self {
+            reader,
+            schema,
+            wkb_columns,
+        }
+    }
+
+    fn convert_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let input_schema = batch.schema();
+        let mut columns = batch.columns().to_vec();
+        for idx in &self.wkb_columns {
+            let array = from_arrow_array(columns[*idx].as_ref(), input_schema.field(*idx))?;
+            columns[*idx] = to_wkb::<i32>(array.as_ref())?.to_array_ref();
+        }
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+impl Iterator for WkbPropertiesReader {
+    type Item = std::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader
+            .next()
+            .map(|batch| Ok(self.convert_batch(batch?)?))
+    }
+}
+
+impl RecordBatchReader for WkbPropertiesReader {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::io::{BufWriter, Cursor};
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Int32Type, UInt8Type};
+    use arrow_array::{
+        ArrayRef, Date32Array, Decimal128Array, DictionaryArray, DurationMillisecondArray,
+        ListArray, RecordBatchIterator, TimestampMillisecondArray, create_array,
+    };
+    use arrow_cast::cast;
+    use flatgeobuf::{FallibleStreamingIterator, FgbReader};
+    use geoarrow_array::array::PointArray;
+    use geoarrow_array::builder::{GeometryBuilder, PointBuilder};
+    use geoarrow_schema::{CoordType, Dimension, GeometryType, PointType};
+    use wkt::{Wkt, wkt};
+
+    use super::*;
+    use crate::column;
+    use crate::reader::FlatGeobufReaderBuilder;
+
+    // FlatGeobuf, or at least the FlatGeobuf rust library, doesn't support writing null or empty
+    // points.
+    fn non_empty_point_array() -> PointArray {
+        let geoms = vec![
+            Some(wkt! { POINT (30. 10.) }),
+            Some(wkt! { POINT (40. 20.) }),
+            Some(wkt! { POINT (1. 2.) }),
+            Some(wkt! { POINT (1. 2.) }),
+        ];
+        let typ = PointType::new(CoordType::Interleaved, Dimension::XY, Default::default());
+        PointBuilder::from_nullable_points(geoms.iter().map(|x| x.as_ref()), typ).finish()
+    }
+
+    fn table() -> (Vec<RecordBatch>, Arc<Schema>) {
+        let point_array = non_empty_point_array();
+        let u8_array = create_array!(UInt8, [1, 2, 3, 4]);
+        let string_array = create_array!(Utf8, ["1", "2", "3", "4"]);
+
+        let fields = vec![
+            Arc::new(Field::new("u8", DataType::UInt8, true)),
+            Arc::new(Field::new("string", DataType::Utf8, true)),
+            Arc::new(point_array.data_type().to_field("geometry", true)),
+        ];
+        let schema = Arc::new(Schema::new(fields));
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![u8_array, string_array, point_array.into_array_ref()],
+        )
+        .unwrap();
+
+        (vec![batch], schema)
+    }
+
+    #[test]
+    fn test_write() {
+        let (batches, schema) = table();
+        let reader = Box::new(RecordBatchIterator::new(
+            batches.into_iter().map(Ok),
+            schema,
+        ));
+        let geozero_reader = GeozeroRecordBatchReader::new(Box::new(reader));
+
+        let mut output_buffer = Vec::new();
+        let writer = BufWriter::new(&mut output_buffer);
+        write_flatgeobuf(geozero_reader, writer, "name").unwrap();
+
+        // let reader = Cursor::new(output_buffer);
+        // let reader_builder = FlatGeobufReaderBuilder::open(reader).unwrap();
+        // let record_batch_reader = reader_builder.read(Default::default()).unwrap();
+        // let new_table = Table::try_from(
+        //     Box::new(record_batch_reader) as Box<dyn arrow_array::RecordBatchReader>
+        // )
+        // .unwrap();
+
+        // // Note: backwards row order is due to the reordering during the spatial index
+        // let batch = &new_table.batches()[0];
+        // let arr = batch.column(0);
+        // dbg!(arr);
+        // dbg!(new_table);
+        // dbg!(output_buffer);
+    }
+
+    #[test]
+    fn test_write_no_index() {
+        let (batches, schema) = table();
+        let reader = Box::new(RecordBatchIterator::new(
+            batches.into_iter().map(Ok),
+            schema,
+        ));
+        let geozero_reader = GeozeroRecordBatchReader::new(Box::new(reader));
+
+        let mut output_buffer = Vec::new();
+        let writer = BufWriter::new(&mut output_buffer);
+        let options = FlatGeobufWriterOptions {
+            write_index: false,
+            ..Default::default()
+        };
+        write_flatgeobuf_with_options(geozero_reader, writer, "name", options).unwrap();
+
+        // let reader = Cursor::new(output_buffer);
+        // let reader_builder = FlatGeobufReaderBuilder::open(reader).unwrap();
+        // let record_batch_reader = reader_builder.read(Default::default()).unwrap();
+        // let new_table = Table::try_from(
+        //     Box::new(record_batch_reader) as Box<dyn arrow_array::RecordBatchReader>
+        // )
+        // .unwrap();
+        // assert_eq!(table, new_table);
+    }
+
+    fn write(batches: Vec<RecordBatch>, options: FlatGeobufWriterOptions) -> Result<Vec<u8>> {
+        let schema = batches[0].schema();
+        let reader = Box::new(RecordBatchIterator::new(
+            batches.into_iter().map(Ok),
+            schema,
+        ));
+        let mut output_buffer = Vec::new();
+        write_flatgeobuf_with_options(
+            reader as Box<dyn RecordBatchReader>,
+            &mut output_buffer,
+            "name",
+            options,
+        )?;
+        Ok(output_buffer)
+    }
+
+    #[test]
+    fn test_write_geometry_array_z() {
+        let geoms = ["POINT Z (1 2 3)", "LINESTRING (1 2, 3 4)"]
+            .iter()
+            .map(|s| Some(s.parse::<Wkt<f64>>().unwrap()))
+            .collect::<Vec<_>>();
+        let typ = GeometryType::new(CoordType::Interleaved, Default::default());
+        let geometry_array = GeometryBuilder::from_nullable_geometries(&geoms, typ)
+            .unwrap()
+            .finish();
+        let schema = Arc::new(Schema::new(vec![
+            geometry_array.data_type().to_field("geometry", true),
+        ]));
+        let batch = RecordBatch::try_new(schema, vec![geometry_array.into_array_ref()]).unwrap();
+
+        let output_buffer = write(vec![batch], Default::default()).unwrap();
+        let reader = FgbReader::open(Cursor::new(output_buffer)).unwrap();
+        assert!(reader.header().has_z());
+        assert!(!reader.header().has_m());
+    }
+
+    #[test]
+    fn test_write_wkb_z() {
+        let geoms = ["POINT Z (1 2 3)", "POINT Z (4 5 6)"]
+            .iter()
+            .map(|s| Some(s.parse::<Wkt<f64>>().unwrap()))
+            .collect::<Vec<_>>();
+        let typ = GeometryType::new(CoordType::Interleaved, Default::default());
+        let geometry_array = GeometryBuilder::from_nullable_geometries(&geoms, typ)
+            .unwrap()
+            .finish();
+        let wkb_array = to_wkb::<i32>(&geometry_array).unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            wkb_array.data_type().to_field("geometry", true),
+        ]));
+        let batch = RecordBatch::try_new(schema, vec![wkb_array.into_array_ref()]).unwrap();
+
+        let options = FlatGeobufWriterOptions {
+            write_index: false,
+            ..Default::default()
+        };
+        let output_buffer = write(vec![batch], options).unwrap();
+        let mut reader = FgbReader::open(Cursor::new(output_buffer))
+            .unwrap()
+            .select_all()
+            .unwrap();
+        assert!(reader.header().has_z());
+        assert!(!reader.header().has_m());
+
+        let mut z = vec![];
+        while let Some(feature) = reader.next().unwrap() {
+            let geometry = feature.geometry().unwrap();
+            z.extend(geometry.z().unwrap().iter());
+        }
+        assert_eq!(z, vec![3., 6.]);
+    }
+
+    #[test]
+    fn test_write_promote_to_multi() {
+        let geoms = [
+            "POLYGON ((0 0, 1 0, 1 1, 0 0))",
+            "MULTIPOLYGON (((2 2, 3 2, 3 3, 2 2)), ((4 4, 5 4, 5 5, 4 4)))",
+        ]
+        .iter()
+        .map(|s| Some(s.parse::<Wkt<f64>>().unwrap()))
+        .collect::<Vec<_>>();
+        let typ = GeometryType::new(CoordType::Interleaved, Default::default());
+        let geometry_array = GeometryBuilder::from_nullable_geometries(&geoms, typ)
+            .unwrap()
+            .finish();
+        let schema = Arc::new(Schema::new(vec![
+            geometry_array.data_type().to_field("geometry", true),
+        ]));
+        let batch = RecordBatch::try_new(schema, vec![geometry_array.into_array_ref()]).unwrap();
+
+        let output_buffer = write(vec![batch.clone()], Default::default()).unwrap();
+        let reader = FgbReader::open(Cursor::new(output_buffer)).unwrap();
+        assert_eq!(
+            reader.header().geometry_type(),
+            flatgeobuf::GeometryType::MultiPolygon
+        );
+
+        let options = FlatGeobufWriterOptions {
+            promote_to_multi: false,
+            ..Default::default()
+        };
+        let output_buffer = write(vec![batch], options).unwrap();
+        let reader = FgbReader::open(Cursor::new(output_buffer)).unwrap();
+        assert_eq!(
+            reader.header().geometry_type(),
+            flatgeobuf::GeometryType::Unknown
+        );
+    }
+
+    #[test]
+    fn test_write_multiple_geometry_columns() {
+        let point_array = non_empty_point_array();
+        let fields = vec![
+            Arc::new(Field::new("u8", DataType::UInt8, true)),
+            Arc::new(point_array.data_type().to_field("centroid", true)),
+            Arc::new(point_array.data_type().to_field("geometry", true)),
+        ];
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            vec![
+                create_array!(UInt8, [1, 2, 3, 4]),
+                point_array.to_array_ref(),
+                point_array.into_array_ref(),
+            ],
+        )
+        .unwrap();
+
+        let options = FlatGeobufWriterOptions {
+            geometry_column: Some("geometry".to_string()),
+            ..Default::default()
+        };
+        let output_buffer = write(vec![batch], options).unwrap();
+
+        let reader = FlatGeobufReaderBuilder::open(Cursor::new(output_buffer)).unwrap();
+        let schema = reader.schema(&Default::default()).unwrap();
+        let field_names = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(field_names, vec!["u8", "centroid", "geometry"]);
+        // The other geometry column is written as a WKB property
+        assert_eq!(schema.field(1).data_type(), &DataType::Binary);
+    }
+
+    #[test]
+    fn test_write_missing_geometry_column() {
+        let (batches, _schema) = table();
+        let options = FlatGeobufWriterOptions {
+            geometry_column: Some("missing".to_string()),
+            ..Default::default()
+        };
+        assert!(write(batches, options).is_err());
+    }
+
+    #[test]
+    fn test_write_spill_to_disk() {
+        let (batches, _schema) = table();
+        let options = FlatGeobufWriterOptions {
+            spill_to_disk: true,
+            sort_buffer_size: 3,
+            ..Default::default()
+        };
+        let output_buffer = write(batches, options).unwrap();
+
+        let reader = FgbReader::open(Cursor::new(&output_buffer)).unwrap();
+        assert_eq!(reader.header().features_count(), 4);
+        assert_eq!(reader.header().index_node_size(), 16);
+
+        let options = crate::reader::FlatGeobufReaderOptions {
+            bbox: Some((0., 0., 10., 10.)),
+            ..Default::default()
+        };
+        let batches = FlatGeobufReaderBuilder::open(Cursor::new(output_buffer))
+            .unwrap()
+            .read(options)
+            .unwrap()
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        let num_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(num_rows, 2);
+    }
+
+    #[test]
+    fn test_write_spill_to_disk_null_geometry() {
+        let geoms = vec![
+            Some(wkt! { POINT (30. 10.) }),
+            None,
+            Some(wkt! { POINT (1. 2.) }),
+        ];
+        let typ = PointType::new(CoordType::Interleaved, Dimension::XY, Default::default());
+        let point_array =
+            PointBuilder::from_nullable_points(geoms.iter().map(|x| x.as_ref()), typ).finish();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("u8", DataType::UInt8, true),
+            point_array.data_type().to_field("geometry", true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                create_array!(UInt8, [1, 2, 3]),
+                point_array.into_array_ref(),
+            ],
+        )
+        .unwrap();
+        let options = FlatGeobufWriterOptions {
+            spill_to_disk: true,
+            ..Default::default()
+        };
+        let output_buffer = write(vec![batch], options).unwrap();
+
+        // The null geometry doesn't contribute to the extent
+        let reader = FgbReader::open(Cursor::new(&output_buffer)).unwrap();
+        let envelope = reader.header().envelope().unwrap();
+        assert_eq!(envelope.iter().collect::<Vec<_>>(), vec![1., 2., 30., 10.]);
+
+        let read_u8 = |bbox| {
+            let options = crate::reader::FlatGeobufReaderOptions {
+                bbox,
+                ..Default::default()
+            };
+            let batches = FlatGeobufReaderBuilder::open(Cursor::new(&output_buffer))
+                .unwrap()
+                .read(options)
+                .unwrap()
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .unwrap();
+            let mut values = batches
+                .iter()
+                .flat_map(|batch| {
+                    batch
+                        .column(0)
+                        .as_primitive::<UInt8Type>()
+                        .values()
+                        .to_vec()
+                })
+                .collect::<Vec<_>>();
+            values.sort();
+            values
+        };
+        // The index search skips the null geometry, even for a search box covering the extent
+        assert_eq!(read_u8(Some((-180., -90., 180., 90.))), vec![1, 3]);
+        assert_eq!(read_u8(None), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_write_spill_to_disk_countries() {
+        let file = std::fs::File::open("../../fixtures/flatgeobuf/countries.fgb").unwrap();
+        let reader = FlatGeobufReaderBuilder::open(file)
+            .unwrap()
+            .read(Default::default())
+            .unwrap();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        let options = FlatGeobufWriterOptions {
+            spill_to_disk: true,
+            sort_buffer_size: 50,
+            ..Default::default()
+        };
+        let output_buffer = write(batches, options).unwrap();
+
+        // The index must select the same features as the index of the original file
+        let options = crate::reader::FlatGeobufReaderOptions {
+            bbox: Some((0., -90., 180., 90.)),
+            ..Default::default()
+        };
+        let batches = FlatGeobufReaderBuilder::open(Cursor::new(output_buffer))
+            .unwrap()
+            .read(options)
+            .unwrap()
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        let num_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(num_rows, 133);
+    }
+
+    fn column_types_batch() -> RecordBatch {
+        let point_array = non_empty_point_array();
+        let decimal_array = Arc::new(
+            Decimal128Array::from(vec![Some(123), None, Some(456), Some(-789)])
+                .with_precision_and_scale(10, 2)
+                .unwrap(),
+        );
+        let dict_array = Arc::new(
+            vec![Some("a"), Some("b"), Some("a"), None]
+                .into_iter()
+                .collect::<DictionaryArray<Int32Type>>(),
+        );
+        let list_array = Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2)]),
+            Some(vec![]),
+            None,
+            Some(vec![Some(3)]),
+        ]));
+        let timestamp_array = Arc::new(
+            TimestampMillisecondArray::from(vec![Some(1_000_001), None, Some(0), Some(-1_000_000)])
+                .with_timezone("+01:00"),
+        );
+        let date_array = Arc::new(Date32Array::from(vec![
+            Some(18262),
+            None,
+            Some(0),
+            Some(-1),
+        ]));
+        let float16_array = cast(
+            &create_array!(Float32, [Some(1.5), None, Some(-2.0), Some(0.25)]),
+            &DataType::Float16,
+        )
+        .unwrap();
+        let duration_array = Arc::new(DurationMillisecondArray::from(vec![
+            Some(90_061_500),
+            None,
+            Some(-3),
+            Some(0),
+        ]));
+
+        let mut metadata = HashMap::new();
+        metadata.insert(column::TITLE_KEY.to_string(), "Price".to_string());
+        metadata.insert(column::UNIQUE_KEY.to_string(), "true".to_string());
+        let columns: Vec<ArrayRef> = vec![
+            decimal_array,
+            dict_array,
+            list_array,
+            timestamp_array,
+            date_array,
+            float16_array,
+            duration_array,
+        ];
+        let mut fields = [
+            "decimal",
+            "dict",
+            "list",
+            "timestamp",
+            "date",
+            "float16",
+            "duration",
+        ]
+        .iter()
+        .zip(&columns)
+        .map(|(name, array)| Field::new(*name, array.data_type().clone(), true))
+        .collect::<Vec<_>>();
+        fields[0] = fields[0].clone().with_metadata(metadata);
+        fields.push(point_array.data_type().to_field("geometry", true));
+
+        let mut columns = columns;
+        columns.push(point_array.into_array_ref());
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap()
+    }
+
+    /// The property columns are read back with the exact types they were written with.
+    fn assert_column_types(schema: &Schema) {
+        let expected = column_types_batch().schema();
+        for (field, expected) in schema.fields().iter().zip(expected.fields()).take(7) {
+            assert_eq!(field.name(), expected.name());
+            assert_eq!(field.data_type(), expected.data_type());
+        }
+
+        let decimal = schema.field(0);
+        assert_eq!(
+            decimal
+                .metadata()
+                .get(column::TITLE_KEY)
+                .map(String::as_str),
+            Some("Price")
+        );
+        assert_eq!(
+            decimal
+                .metadata()
+                .get(column::UNIQUE_KEY)
+                .map(String::as_str),
+            Some("true")
+        );
+        // The stored Arrow type isn't exposed as column metadata
+        assert!(
+            schema
+                .fields()
+                .iter()
+                .all(|field| !field.metadata().contains_key(column::METADATA_KEY))
+        );
+    }
+
+    #[test]
+    fn test_write_column_types() {
+        let options = FlatGeobufWriterOptions {
+            write_index: false,
+            ..Default::default()
+        };
+        let expected = column_types_batch();
+        let output_buffer = write(vec![expected.clone()], options).unwrap();
+
+        let batches = FlatGeobufReaderBuilder::open(Cursor::new(output_buffer))
+            .unwrap()
+            .read(Default::default())
+            .unwrap()
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        let batch = &batches[0];
+        assert_column_types(batch.schema_ref());
+        for i in 0..7 {
+            assert_eq!(batch.column(i).as_ref(), expected.column(i).as_ref());
+        }
+    }
+
+    #[test]
+    fn test_write_column_metadata_with_stored_type() {
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            column::METADATA_KEY.to_string(),
+            r#"{"source":"survey"}"#.to_string(),
+        );
+        let point_array = non_empty_point_array();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("large", DataType::LargeUtf8, true).with_metadata(metadata),
+            point_array.data_type().to_field("geometry", true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                create_array!(LargeUtf8, ["a", "b", "c", "d"]),
+                point_array.into_array_ref(),
+            ],
+        )
+        .unwrap();
+        let output_buffer = write(vec![batch], Default::default()).unwrap();
+
+        let schema = FlatGeobufReaderBuilder::open(Cursor::new(output_buffer))
+            .unwrap()
+            .schema(&Default::default())
+            .unwrap();
+        let field = schema.field(0);
+        assert_eq!(field.data_type(), &DataType::LargeUtf8);
+        assert_eq!(
+            field
+                .metadata()
+                .get(column::METADATA_KEY)
+                .map(String::as_str),
+            Some(r#"{"source":"survey"}"#)
+        );
+    }
+
+    #[test]
+    fn test_write_column_types_spill_to_disk() {
+        let options = FlatGeobufWriterOptions {
+            spill_to_disk: true,
+            ..Default::default()
+        };
+        let output_buffer = write(vec![column_types_batch()], options).unwrap();
+
+        let reader = FlatGeobufReaderBuilder::open(Cursor::new(output_buffer)).unwrap();
+        assert_column_types(&reader.schema(&Default::default()).unwrap());
+        let batches = reader
+            .read(Default::default())
+            .unwrap()
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        let num_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(num_rows, 4);
+    }
+
+    // #[test]
+    // fn test_write_z() {
+    //     let table = point::table_z();
+
+    //     let mut output_buffer = Vec::new();
+    //     let writer = BufWriter::new(&mut output_buffer);
+    //     write_flatgeobuf(&table, writer, "name").unwrap();
+
+    //     let reader = Cursor::new(output_buffer);
+    //     let reader_builder = FlatGeobufReaderBuilder::open(reader).unwrap();
+    //     let record_batch_reader = reader_builder.read(Default::default()).unwrap();
+    //     let new_table = Table::try_from(
+    //         Box::new(record_batch_reader) as Box<dyn arrow_array::RecordBatchReader>
+    //     )
+    //     .unwrap();
+
+    //     // Note: backwards row order is due to the reordering during the spatial index
+    //     let batch = &new_table.batches()[0];
+    //     let _arr = batch.column(0);
+    // }
+}
@@ -0,0 +1,174 @@
+//! Encoding Arrow property columns as FlatGeobuf feature properties.
+
+use std::sync::Arc;
+
+use arrow_array::builder::StringBuilder;
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_cast::cast;
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_json::writer::make_encoder;
+use arrow_schema::{DataType, Field, SchemaRef};
+use flatgeobuf::ColumnType;
+use geoarrow_array::error::{GeoArrowError, Result};
+
+use crate::column;
+
+/// The FlatGeobuf column type of each property column, keyed by its index in the schema.
+pub(super) fn property_columns(
+    schema: &SchemaRef,
+    geometry_column_index: usize,
+) -> Result<Vec<(usize, ColumnType)>> {
+    schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != geometry_column_index)
+        .map(|(idx, field)| Ok((idx, column::column_type(field)?)))
+        .collect()
+}
+
+/// Encodes the property columns of a record batch, one feature at a time.
+///
+/// Each column is first converted to an array whose values map directly to the FlatGeobuf
+/// encoding of its column type, following the mapping in [crate::column].
+pub(super) struct PropertyEncoder {
+    arrays: Vec<(ArrayRef, ColumnType)>,
+}
+
+impl PropertyEncoder {
+    /// Prepare the property `columns` of `batch`, as returned by [property_columns].
+    pub(super) fn try_new(
+        schema: &SchemaRef,
+        batch: &RecordBatch,
+        columns: &[(usize, ColumnType)],
+    ) -> Result<Self> {
+        let arrays = columns
+            .iter()
+            .map(|(idx, column_type)| {
+                let array = prepare_property(schema.field(*idx), batch.column(*idx), *column_type)?;
+                Ok((array, *column_type))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { arrays })
+    }
+
+    /// Append the FlatGeobuf properties of `row` to `buf`.
+    ///
+    /// Each non-null value is written as its column index followed by its value. Null values are
+    /// omitted.
+    pub(super) fn encode(&self, row: usize, buf: &mut Vec<u8>) -> Result<()> {
+        for (column_idx, (array, column_type)) in self.arrays.iter().enumerate() {
+            if array.is_null(row) {
+                continue;
+            }
+            buf.extend_from_slice(&(column_idx as u16).to_le_bytes());
+            push_property(buf, array, *column_type, row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Convert a property column to an array that [push_property] can write directly.
+///
+/// Dictionaries are unpacked, DateTime values are formatted as ISO 8601 strings, nested values
+/// are encoded as JSON and other types without a FlatGeobuf equivalent are cast.
+fn prepare_property(field: &Field, array: &ArrayRef, column_type: ColumnType) -> Result<ArrayRef> {
+    if let DataType::Dictionary(_, value_type) = array.data_type() {
+        return prepare_property(field, &cast(array, value_type)?, column_type);
+    }
+    if column_type == ColumnType::DateTime {
+        let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default())?;
+        let mut builder = StringBuilder::with_capacity(array.len(), 0);
+        for row in 0..array.len() {
+            if array.is_null(row) {
+                builder.append_null();
+            } else {
+                builder.append_value(formatter.value(row).to_string());
+            }
+        }
+        return Ok(Arc::new(builder.finish()));
+    }
+
+    let array = match array.data_type() {
+        DataType::Float16 => cast(array, &DataType::Float32)?,
+        DataType::Decimal128(_, _)
+        | DataType::Decimal256(_, _)
+        | DataType::Time32(_)
+        | DataType::Time64(_)
+        | DataType::Duration(_)
+        | DataType::Interval(_) => cast(array, &DataType::Utf8)?,
+        DataType::FixedSizeBinary(_) => cast(array, &DataType::Binary)?,
+        DataType::List(_)
+        | DataType::LargeList(_)
+        | DataType::FixedSizeList(_, _)
+        | DataType::Struct(_)
+        | DataType::Map(_, _) => {
+            let field = Arc::new(field.clone().with_data_type(array.data_type().clone()));
+            let options = Default::default();
+            let mut encoder = make_encoder(&field, array.as_ref(), &options)?;
+            let mut builder = StringBuilder::with_capacity(array.len(), 0);
+            let mut buf = vec![];
+            for row in 0..array.len() {
+                if array.is_null(row) {
+                    builder.append_null();
+                    continue;
+                }
+                buf.clear();
+                encoder.encode(row, &mut buf);
+                let json = std::str::from_utf8(&buf)
+                    .map_err(|err| GeoArrowError::General(err.to_string()))?;
+                builder.append_value(json);
+            }
+            Arc::new(builder.finish())
+        }
+        _ => array.clone(),
+    };
+    Ok(array)
+}
+
+/// Append the FlatGeobuf encoding of a property value to `buf`.
+fn push_property(
+    buf: &mut Vec<u8>,
+    array: &ArrayRef,
+    column_type: ColumnType,
+    row: usize,
+) -> Result<()> {
+    match array.data_type() {
+        DataType::Boolean => buf.push(array.as_boolean().value(row) as u8),
+        DataType::Int8 => buf.extend(array.as_primitive::<Int8Type>().value(row).to_le_bytes()),
+        DataType::UInt8 => buf.extend(array.as_primitive::<UInt8Type>().value(row).to_le_bytes()),
+        DataType::Int16 => buf.extend(array.as_primitive::<Int16Type>().value(row).to_le_bytes()),
+        DataType::UInt16 => buf.extend(array.as_primitive::<UInt16Type>().value(row).to_le_bytes()),
+        DataType::Int32 => buf.extend(array.as_primitive::<Int32Type>().value(row).to_le_bytes()),
+        DataType::UInt32 => buf.extend(array.as_primitive::<UInt32Type>().value(row).to_le_bytes()),
+        DataType::Int64 => buf.extend(array.as_primitive::<Int64Type>().value(row).to_le_bytes()),
+        DataType::UInt64 => buf.extend(array.as_primitive::<UInt64Type>().value(row).to_le_bytes()),
+        DataType::Float32 => {
+            buf.extend(array.as_primitive::<Float32Type>().value(row).to_le_bytes())
+        }
+        DataType::Float64 => {
+            buf.extend(array.as_primitive::<Float64Type>().value(row).to_le_bytes())
+        }
+        DataType::Utf8 => push_bytes(buf, array.as_string::<i32>().value(row).as_bytes()),
+        DataType::LargeUtf8 => push_bytes(buf, array.as_string::<i64>().value(row).as_bytes()),
+        DataType::Utf8View => push_bytes(buf, array.as_string_view().value(row).as_bytes()),
+        DataType::Binary => push_bytes(buf, array.as_binary::<i32>().value(row)),
+        DataType::LargeBinary => push_bytes(buf, array.as_binary::<i64>().value(row)),
+        DataType::BinaryView => push_bytes(buf, array.as_binary_view().value(row)),
+        data_type => {
+            return Err(GeoArrowError::General(format!(
+                "Unexpected {} column for FlatGeobuf {:?} property",
+                data_type, column_type
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Strings, JSON and binary values are prefixed by their length in bytes.
+fn push_bytes(buf: &mut Vec<u8>, value: &[u8]) {
+    buf.extend((value.len() as u32).to_le_bytes());
+    buf.extend_from_slice(value);
+}
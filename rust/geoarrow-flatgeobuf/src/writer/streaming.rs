@@ -0,0 +1,347 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+
+use arrow_array::RecordBatchReader;
+use arrow_schema::SchemaRef;
+use flatbuffers::{FlatBufferBuilder, WIPOffset};
+use flatgeobuf::{
+    ColumnType, Crs, CrsArgs, Feature, FeatureArgs, Geometry, GeometryType, Header, HeaderArgs,
+};
+use geoarrow_array::array::{WkbArray, from_arrow_array};
+use geoarrow_array::cast::to_wkb;
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::{ArrayAccessor, GeoArrowArray, GeoArrowType};
+use geoarrow_schema::Dimension;
+
+use crate::column;
+use crate::geometry::{FlatGeobufGeometryEncoder, encode_geometry_trait};
+use crate::writer::index::{NodeItem, SpilledFeature, hilbert_sort, temp_file, write_index};
+use crate::writer::properties::{PropertyEncoder, property_columns};
+use crate::writer::{FlatGeobufWriterOptions, infer_flatgeobuf_geometry_type};
+
+/// The FlatGeobuf magic bytes, for format version 3.
+const MAGIC_BYTES: [u8; 8] = [b'f', b'g', b'b', 3, b'f', b'g', b'b', 0];
+
+/// Write a FlatGeobuf file, staging the encoded features in temporary files.
+///
+/// In the first pass every feature is encoded and appended to a temporary file, while its bounding
+/// box is appended to another. The header, which needs the feature count, extent and detected
+/// geometry type, is written once the input is exhausted. If an index is written, the bounding
+/// boxes are then sorted in Hilbert order, in memory or, with
+/// [`spill_to_disk`][FlatGeobufWriterOptions::spill_to_disk], with bounded memory, and the index
+/// and sorted features are written in a second pass. Otherwise the features are copied in input
+/// order.
+pub(super) fn write_streaming<W: Write>(
+    reader: Box<dyn RecordBatchReader>,
+    writer: W,
+    name: &str,
+    options: &FlatGeobufWriterOptions,
+    geometry_column_index: usize,
+    geo_data_type: &GeoArrowType,
+    wkt_crs: Option<&str>,
+) -> Result<()> {
+    let schema = reader.schema();
+    let columns = property_columns(&schema, geometry_column_index)?;
+    let temp_dir = options.temp_dir.as_deref();
+    let geometry_field = schema.field(geometry_column_index);
+
+    let mut features = BufWriter::new(temp_file(temp_dir)?);
+    let mut spilled = BufWriter::new(temp_file(temp_dir)?);
+    let mut stats = FeatureStats::new(geo_data_type);
+    let declared_type = infer_flatgeobuf_geometry_type(geo_data_type);
+    // Native arrays already have a single declared type, so only geometries of an unknown type are
+    // promoted
+    let promote_to_multi = options.promote_to_multi && declared_type == GeometryType::Unknown;
+
+    let mut fbb = FlatBufferBuilder::new();
+    let mut property_buf = vec![];
+    let mut feature_offset = 0;
+    for batch in reader {
+        let batch = batch?;
+        let geometry_array =
+            from_arrow_array(batch.column(geometry_column_index).as_ref(), geometry_field)?;
+        let geometries = BatchGeometries::try_new(geometry_array.as_ref())?;
+        let property_encoder = PropertyEncoder::try_new(&schema, &batch, &columns)?;
+
+        for row in 0..batch.num_rows() {
+            property_buf.clear();
+            property_encoder.encode(row, &mut property_buf)?;
+
+            fbb.reset();
+            let geometry = geometries.encode(&mut fbb, row, promote_to_multi)?;
+            let properties = (!property_buf.is_empty()).then(|| fbb.create_vector(&property_buf));
+            let feature = Feature::create(
+                &mut fbb,
+                &FeatureArgs {
+                    geometry,
+                    properties,
+                    ..Default::default()
+                },
+            );
+            fbb.finish_size_prefixed(feature, None);
+            let data = fbb.finished_data();
+
+            let mut bbox = NodeItem::empty(feature_offset);
+            let feature = flatbuffers::size_prefixed_root::<Feature>(data)
+                .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+            if let Some(geometry) = feature.geometry() {
+                stats.add_geometry(&geometry, &mut bbox);
+            }
+            stats.count += 1;
+            features.write_all(data)?;
+            SpilledFeature {
+                bbox,
+                len: data.len() as u64,
+            }
+            .write(&mut spilled)?;
+            feature_offset += data.len() as u64;
+        }
+    }
+
+    let mut features = features.into_inner()?;
+    let spilled = spilled.into_inner()?;
+
+    let mut writer = BufWriter::new(writer);
+    writer.write_all(&MAGIC_BYTES)?;
+    let geometry_type = stats.header_geometry_type(declared_type, options);
+    let index_node_size = if options.write_index && stats.count > 0 {
+        INDEX_NODE_SIZE
+    } else {
+        0
+    };
+    write_header(
+        &mut writer,
+        name,
+        geometry_type,
+        index_node_size,
+        options,
+        &schema,
+        &columns,
+        &stats,
+        wkt_crs,
+    )?;
+    if index_node_size > 0 {
+        let sort_buffer_size = if options.spill_to_disk {
+            options.sort_buffer_size
+        } else {
+            usize::MAX
+        };
+        let mut sorted = hilbert_sort(spilled, &stats.extent, sort_buffer_size, temp_dir)?;
+        write_index(
+            &mut sorted,
+            stats.count,
+            index_node_size,
+            temp_dir,
+            &mut writer,
+        )?;
+        copy_sorted_features(&mut sorted, &mut features, &mut writer)?;
+    } else {
+        features.seek(SeekFrom::Start(0))?;
+        io::copy(&mut features, &mut writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+const INDEX_NODE_SIZE: u16 = 16;
+
+/// Copy the spilled features to `writer` in sorted order.
+///
+/// Features that follow each other in the spill file are read sequentially, and other jumps keep
+/// the read buffer when the target is still within it.
+fn copy_sorted_features(
+    sorted: &mut File,
+    features: &mut File,
+    writer: &mut impl Write,
+) -> Result<()> {
+    sorted.seek(SeekFrom::Start(0))?;
+    features.seek(SeekFrom::Start(0))?;
+    let mut sorted = BufReader::new(sorted);
+    let mut features = BufReader::new(features);
+    let mut position = 0;
+    let mut buf = vec![];
+    while let Some(feature) = SpilledFeature::read(&mut sorted)? {
+        let offset = feature.bbox.offset;
+        if offset != position {
+            features.seek_relative(offset as i64 - position as i64)?;
+        }
+        buf.resize(feature.len as usize, 0);
+        features.read_exact(&mut buf)?;
+        writer.write_all(&buf)?;
+        position = offset + feature.len;
+    }
+    Ok(())
+}
+
+/// The geometry column of a record batch, ready to be encoded.
+enum BatchGeometries<'a> {
+    /// Native arrays are encoded directly from their coordinate buffers.
+    Native(FlatGeobufGeometryEncoder<'a>),
+    /// Other arrays are converted to WKB and encoded through geo-traits.
+    Wkb(WkbArray<i32>),
+}
+
+impl<'a> BatchGeometries<'a> {
+    fn try_new(array: &'a dyn GeoArrowArray) -> Result<Self> {
+        match FlatGeobufGeometryEncoder::try_new(array) {
+            Ok(encoder) => Ok(Self::Native(encoder)),
+            Err(_) => Ok(Self::Wkb(to_wkb(array)?)),
+        }
+    }
+
+    fn encode<'fbb>(
+        &self,
+        fbb: &mut FlatBufferBuilder<'fbb>,
+        i: usize,
+        promote_to_multi: bool,
+    ) -> Result<Option<WIPOffset<Geometry<'fbb>>>> {
+        match self {
+            Self::Native(encoder) => Ok(encoder.encode(fbb, i)),
+            Self::Wkb(array) => match array.get(i) {
+                Some(geom) => Ok(Some(encode_geometry_trait(fbb, &geom?, promote_to_multi))),
+                None => Ok(None),
+            },
+        }
+    }
+}
+
+/// Statistics about the written features that are needed for the header.
+struct FeatureStats {
+    count: usize,
+    extent: NodeItem,
+    has_z: bool,
+    has_m: bool,
+    /// Whether Z and M are detected from the encoded geometries, for geometry columns whose type
+    /// carries no dimension. The Z and M flags of WKB and WKT geometries come from their headers.
+    detect_dimension: bool,
+    /// The geometry type shared by all features, if any.
+    geometry_type: Option<GeometryType>,
+    mixed_geometry_types: bool,
+}
+
+impl FeatureStats {
+    fn new(geo_data_type: &GeoArrowType) -> Self {
+        let (has_z, has_m) = match geo_data_type.dimension() {
+            Some(Dimension::XY) | None => (false, false),
+            Some(Dimension::XYZ) => (true, false),
+            Some(Dimension::XYM) => (false, true),
+            Some(Dimension::XYZM) => (true, true),
+        };
+        Self {
+            count: 0,
+            extent: NodeItem::empty(0),
+            has_z,
+            has_m,
+            detect_dimension: geo_data_type.dimension().is_none(),
+            geometry_type: None,
+            mixed_geometry_types: false,
+        }
+    }
+
+    /// Record a feature's geometry, expanding `bbox` to cover it.
+    fn add_geometry(&mut self, geometry: &Geometry<'_>, bbox: &mut NodeItem) {
+        match self.geometry_type {
+            None => self.geometry_type = Some(geometry.type_()),
+            Some(geometry_type) if geometry_type != geometry.type_() => {
+                self.mixed_geometry_types = true
+            }
+            _ => {}
+        }
+        self.expand(geometry, bbox);
+        self.extent.expand(bbox);
+    }
+
+    fn expand(&mut self, geometry: &Geometry<'_>, bbox: &mut NodeItem) {
+        if let Some(xy) = geometry.xy() {
+            for i in 0..xy.len() / 2 {
+                bbox.expand_xy(xy.get(2 * i), xy.get(2 * i + 1));
+            }
+        }
+        if self.detect_dimension {
+            self.has_z |= geometry.z().is_some();
+            self.has_m |= geometry.m().is_some();
+        }
+        if let Some(parts) = geometry.parts() {
+            for part in parts.iter() {
+                self.expand(&part, bbox);
+            }
+        }
+    }
+
+    /// The geometry type to declare in the header.
+    fn header_geometry_type(
+        &self,
+        declared_type: GeometryType,
+        options: &FlatGeobufWriterOptions,
+    ) -> GeometryType {
+        match (declared_type, self.geometry_type) {
+            (GeometryType::Unknown, Some(geometry_type))
+                if options.detect_type && !self.mixed_geometry_types =>
+            {
+                geometry_type
+            }
+            _ => declared_type,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_header(
+    writer: &mut impl Write,
+    name: &str,
+    geometry_type: GeometryType,
+    index_node_size: u16,
+    options: &FlatGeobufWriterOptions,
+    schema: &SchemaRef,
+    columns: &[(usize, ColumnType)],
+    stats: &FeatureStats,
+    wkt_crs: Option<&str>,
+) -> Result<()> {
+    let mut fbb = FlatBufferBuilder::new();
+
+    let name = fbb.create_string(name);
+    let envelope = (!stats.extent.is_empty()).then(|| {
+        let extent = &stats.extent;
+        fbb.create_vector(&[extent.min_x, extent.min_y, extent.max_x, extent.max_y])
+    });
+    let columns = columns
+        .iter()
+        .map(|(idx, _)| column::create_column(&mut fbb, schema.field(*idx)))
+        .collect::<Result<Vec<_>>>()?;
+    let columns = (!columns.is_empty()).then(|| fbb.create_vector(&columns));
+    let crs = wkt_crs.map(|wkt| {
+        let wkt = fbb.create_string(wkt);
+        Crs::create(
+            &mut fbb,
+            &CrsArgs {
+                wkt: Some(wkt),
+                ..Default::default()
+            },
+        )
+    });
+    let title = options.title.as_deref().map(|s| fbb.create_string(s));
+    let description = options.description.as_deref().map(|s| fbb.create_string(s));
+    let metadata = options.metadata.as_deref().map(|s| fbb.create_string(s));
+
+    let header = Header::create(
+        &mut fbb,
+        &HeaderArgs {
+            name: Some(name),
+            envelope,
+            geometry_type,
+            has_z: stats.has_z,
+            has_m: stats.has_m,
+            columns,
+            features_count: stats.count as u64,
+            index_node_size,
+            crs,
+            title,
+            description,
+            metadata,
+            ..Default::default()
+        },
+    );
+    fbb.finish_size_prefixed(header, None);
+    writer.write_all(fbb.finished_data())?;
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use gdal::spatial_ref::SpatialRef;
+use geoarrow_array::error::Result;
+use geoarrow_schema::{Crs, CrsType};
+use serde_json::Value;
+
+use crate::gdal_error;
+
+/// The CRS of an OGR spatial reference, as PROJJSON where possible.
+pub(crate) fn spatial_ref_crs(spatial_ref: &SpatialRef) -> Crs {
+    if let Some(projjson) = spatial_ref
+        .to_projjson()
+        .ok()
+        .and_then(|projjson| serde_json::from_str(&projjson).ok())
+    {
+        return Crs::from_projjson(projjson);
+    }
+    if let (Ok(authority), Ok(code)) = (spatial_ref.auth_name(), spatial_ref.auth_code()) {
+        return Crs::from_authority_code(format!("{authority}:{code}"));
+    }
+    spatial_ref
+        .to_wkt()
+        .map(Crs::from_unknown_crs_type)
+        .unwrap_or_default()
+}
+
+/// The OGR spatial reference of a CRS, or `None` if the CRS is not set.
+pub(crate) fn crs_spatial_ref(crs: &Crs) -> Result<Option<SpatialRef>> {
+    let definition = match (crs.crs_type(), crs.crs_value()) {
+        (_, None) => return Ok(None),
+        (Some(CrsType::Srid), Some(Value::String(srid))) => format!("EPSG:{srid}"),
+        (_, Some(Value::String(definition))) => definition.clone(),
+        // OGR reads PROJJSON as a user input definition
+        (_, Some(value)) => value.to_string(),
+    };
+    Ok(Some(
+        SpatialRef::from_definition(&definition).map_err(gdal_error)?,
+    ))
+}
@@ -0,0 +1,23 @@
+//! Read and write GeoArrow data with [GDAL](https://gdal.org/) vector drivers.
+//!
+//! Layers are read through OGR's Arrow C stream interface (`OGR_L_GetArrowStream`), and written
+//! with `OGR_L_WriteArrowBatch`, so that attribute columns are exchanged without a copy. Geometry
+//! columns are mapped to GeoArrow arrays with the CRS of the layer's spatial reference.
+//!
+//! This requires GDAL 3.8 or later.
+
+#![warn(missing_docs)]
+#![cfg_attr(not(test), deny(unused_crate_dependencies))]
+
+mod crs;
+mod reader;
+mod writer;
+
+use geoarrow_array::error::GeoArrowError;
+
+pub use reader::{GdalReader, GdalReaderOptions, LayerSelection};
+pub use writer::{GdalWriterOptions, write_gdal};
+
+fn gdal_error(err: gdal::errors::GdalError) -> GeoArrowError {
+    GeoArrowError::External(Box::new(err))
+}
@@ -0,0 +1,468 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use arrow_array::{RecordBatch, RecordBatchReader};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use gdal::Dataset;
+use gdal::cpl::CslStringList;
+use gdal::vector::{LayerAccess, OwnedLayer};
+use gdal_sys::OGRwkbGeometryType;
+use geoarrow_array::array::WkbArray;
+use geoarrow_array::cast::from_wkb;
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geoarrow_schema::{
+    CoordType, Dimension, GeometryCollectionType, GeometryType, LineStringType, Metadata,
+    MultiLineStringType, MultiPointType, MultiPolygonType, PointType, PolygonType, WkbType,
+};
+
+use crate::crs::spatial_ref_crs;
+use crate::gdal_error;
+
+/// The layer of a dataset to read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerSelection {
+    /// The layer at an index of the dataset.
+    Index(usize),
+
+    /// The layer with a name.
+    Name(String),
+}
+
+impl Default for LayerSelection {
+    fn default() -> Self {
+        Self::Index(0)
+    }
+}
+
+impl From<usize> for LayerSelection {
+    fn from(value: usize) -> Self {
+        Self::Index(value)
+    }
+}
+
+impl From<&str> for LayerSelection {
+    fn from(value: &str) -> Self {
+        Self::Name(value.to_string())
+    }
+}
+
+impl From<String> for LayerSelection {
+    fn from(value: String) -> Self {
+        Self::Name(value)
+    }
+}
+
+/// Options for reading an OGR layer.
+#[derive(Debug, Clone)]
+pub struct GdalReaderOptions {
+    /// The maximum number of rows in each batch.
+    ///
+    /// If `None`, GDAL's default of 65,536 is used.
+    pub batch_size: Option<usize>,
+
+    /// The GeoArrow coordinate type of the geometry columns.
+    ///
+    /// Geometry columns are parsed to the native GeoArrow type matching the OGR geometry type
+    /// of the field, or to the mixed geometry type if the field has no single type. The ESRI
+    /// Shapefile driver declares line string and polygon layers that may hold multi-part
+    /// features, so their fields are read as multi line strings and multi polygons when the
+    /// layer is opened with [`GdalReader::open`]. If `None`, geometry columns are kept as WKB.
+    pub coord_type: Option<CoordType>,
+
+    /// An OGR SQL `WHERE` clause that features must match, such as `"population > 1000"`.
+    pub attribute_filter: Option<String>,
+
+    /// A bounding box `(min_x, min_y, max_x, max_y)` that the geometries of features must
+    /// intersect, in the CRS of the layer.
+    pub bbox: Option<(f64, f64, f64, f64)>,
+
+    /// Whether to include the feature ids as a column.
+    pub include_fid: bool,
+}
+
+impl Default for GdalReaderOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: None,
+            coord_type: Some(CoordType::Interleaved),
+            attribute_filter: None,
+            bbox: None,
+            include_fid: true,
+        }
+    }
+}
+
+/// A geometry column of the stream, and the GeoArrow type it is read as.
+#[derive(Debug, Clone)]
+struct GeometryColumn {
+    index: usize,
+    data_type: GeoArrowType,
+}
+
+/// A reader of record batches from an OGR layer.
+///
+/// Batches are streamed from GDAL through the Arrow C stream interface. The reader owns the
+/// layer, which must outlive the stream.
+pub struct GdalReader<L: LayerAccess = OwnedLayer> {
+    // Declared before `layer` so that the stream is released first
+    stream: ArrowArrayStreamReader,
+    schema: SchemaRef,
+    geometry_columns: Vec<GeometryColumn>,
+    layer: L,
+}
+
+impl GdalReader<OwnedLayer> {
+    /// Open a layer of a dataset that GDAL can read.
+    pub fn open(
+        path: impl AsRef<Path>,
+        layer: impl Into<LayerSelection>,
+        options: GdalReaderOptions,
+    ) -> Result<Self> {
+        let dataset = Dataset::open(path).map_err(gdal_error)?;
+        let promote_to_multi = dataset.driver().short_name() == "ESRI Shapefile";
+        let layer = match layer.into() {
+            LayerSelection::Index(idx) => dataset.into_layer(idx),
+            LayerSelection::Name(name) => dataset.into_layer_by_name(&name),
+        }
+        .map_err(gdal_error)?;
+        Self::try_new_impl(layer, options, promote_to_multi)
+    }
+}
+
+impl<L: LayerAccess> GdalReader<L> {
+    /// Create a new reader of a layer.
+    ///
+    /// The attribute and spatial filters of the options replace any that were set on the layer.
+    /// Geometry fields are read as their declared OGR geometry type, see
+    /// [`GdalReaderOptions::coord_type`].
+    pub fn try_new(layer: L, options: GdalReaderOptions) -> Result<Self> {
+        Self::try_new_impl(layer, options, false)
+    }
+
+    /// Create a new reader of a layer, reading line string and polygon fields as multi geometry
+    /// types if `promote_to_multi` is set.
+    fn try_new_impl(
+        mut layer: L,
+        options: GdalReaderOptions,
+        promote_to_multi: bool,
+    ) -> Result<Self> {
+        match &options.attribute_filter {
+            Some(attribute_filter) => layer
+                .set_attribute_filter(attribute_filter)
+                .map_err(gdal_error)?,
+            None => layer.clear_attribute_filter(),
+        }
+        match options.bbox {
+            Some((min_x, min_y, max_x, max_y)) => {
+                layer.set_spatial_filter_rect(min_x, min_y, max_x, max_y)
+            }
+            None => layer.clear_spatial_filter(),
+        }
+
+        let mut stream_options = CslStringList::new();
+        if let Some(batch_size) = options.batch_size {
+            stream_options
+                .set_name_value("MAX_FEATURES_IN_BATCH", &batch_size.to_string())
+                .map_err(gdal_error)?;
+        }
+        let include_fid = if options.include_fid { "YES" } else { "NO" };
+        stream_options
+            .set_name_value("INCLUDE_FID", include_fid)
+            .map_err(gdal_error)?;
+        stream_options
+            .set_name_value("GEOMETRY_ENCODING", "WKB")
+            .map_err(gdal_error)?;
+
+        let mut ffi_stream = FFI_ArrowArrayStream::empty();
+        // GDAL declares its own copy of the ArrowArrayStream struct, with the same layout
+        let ffi_stream_ptr: *mut gdal::ArrowArrayStream =
+            (&mut ffi_stream as *mut FFI_ArrowArrayStream).cast();
+        unsafe { layer.read_arrow_stream(ffi_stream_ptr, &stream_options) }.map_err(gdal_error)?;
+        let stream = ArrowArrayStreamReader::try_new(ffi_stream)?;
+
+        // OGR writes geometry fields as WKB columns after the attribute columns, in the order of
+        // the layer's geometry fields
+        let input_schema = stream.schema();
+        let mut fields = input_schema.fields().to_vec();
+        let mut geometry_columns = vec![];
+        let wkb_columns = input_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| is_wkb_field(field));
+        for ((index, field), geometry_field) in wkb_columns.zip(layer.defn().geom_fields()) {
+            let crs = geometry_field
+                .spatial_ref()
+                .map(|spatial_ref| spatial_ref_crs(&spatial_ref))
+                .unwrap_or_default();
+            let metadata = Arc::new(Metadata::new(crs, None));
+            let data_type = match options.coord_type {
+                Some(coord_type) => geometry_type(
+                    geometry_field.field_type(),
+                    coord_type,
+                    metadata,
+                    promote_to_multi,
+                ),
+                None if field.data_type() == &DataType::LargeBinary => {
+                    GeoArrowType::LargeWkb(WkbType::new(metadata))
+                }
+                None => GeoArrowType::Wkb(WkbType::new(metadata)),
+            };
+            fields[index] = Arc::new(data_type.to_field(field.name(), field.is_nullable()));
+            geometry_columns.push(GeometryColumn { index, data_type });
+        }
+
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        ));
+        Ok(Self {
+            stream,
+            schema,
+            geometry_columns,
+            layer,
+        })
+    }
+
+    /// The layer that this reader reads from.
+    pub fn layer(&self) -> &L {
+        &self.layer
+    }
+
+    fn convert(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let mut columns = batch.columns().to_vec();
+        for column in &self.geometry_columns {
+            let array = batch.column(column.index).as_ref();
+            let wkb_type = WkbType::new(column.data_type.metadata().clone());
+            let geometry_array = match array.data_type() {
+                DataType::Binary => from_wkb(
+                    &WkbArray::<i32>::try_from((array, wkb_type))?,
+                    column.data_type.clone(),
+                )?,
+                DataType::LargeBinary => from_wkb(
+                    &WkbArray::<i64>::try_from((array, wkb_type))?,
+                    column.data_type.clone(),
+                )?,
+                data_type => {
+                    return Err(GeoArrowError::General(format!(
+                        "Unexpected OGR geometry column type {data_type}"
+                    )));
+                }
+            };
+            columns[column.index] = geometry_array.to_array_ref();
+        }
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+impl<L: LayerAccess> Iterator for GdalReader<L> {
+    type Item = std::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.stream.next()?;
+        Some(batch.and_then(|batch| Ok(self.convert(batch)?)))
+    }
+}
+
+impl<L: LayerAccess> RecordBatchReader for GdalReader<L> {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+fn is_wkb_field(field: &Field) -> bool {
+    field
+        .extension_type_name()
+        .is_some_and(|name| matches!(name, "ogc.wkb" | "geoarrow.wkb"))
+}
+
+/// The GeoArrow type of an OGR geometry type, with line strings and polygons promoted to the
+/// matching multi geometry type if `promote_to_multi` is set.
+fn geometry_type(
+    ogr_type: OGRwkbGeometryType::Type,
+    coord_type: CoordType,
+    metadata: Arc<Metadata>,
+    promote_to_multi: bool,
+) -> GeoArrowType {
+    let (has_z, has_m) = unsafe {
+        (
+            gdal_sys::OGR_GT_HasZ(ogr_type) != 0,
+            gdal_sys::OGR_GT_HasM(ogr_type) != 0,
+        )
+    };
+    let dim = match (has_z, has_m) {
+        (false, false) => Dimension::XY,
+        (true, false) => Dimension::XYZ,
+        (false, true) => Dimension::XYM,
+        (true, true) => Dimension::XYZM,
+    };
+    match unsafe { gdal_sys::OGR_GT_Flatten(ogr_type) } {
+        OGRwkbGeometryType::wkbPoint => PointType::new(coord_type, dim, metadata).into(),
+        OGRwkbGeometryType::wkbLineString if !promote_to_multi => {
+            LineStringType::new(coord_type, dim, metadata).into()
+        }
+        OGRwkbGeometryType::wkbPolygon if !promote_to_multi => {
+            PolygonType::new(coord_type, dim, metadata).into()
+        }
+        OGRwkbGeometryType::wkbMultiPoint => MultiPointType::new(coord_type, dim, metadata).into(),
+        OGRwkbGeometryType::wkbLineString | OGRwkbGeometryType::wkbMultiLineString => {
+            MultiLineStringType::new(coord_type, dim, metadata).into()
+        }
+        OGRwkbGeometryType::wkbPolygon | OGRwkbGeometryType::wkbMultiPolygon => {
+            MultiPolygonType::new(coord_type, dim, metadata).into()
+        }
+        OGRwkbGeometryType::wkbGeometryCollection => {
+            GeometryCollectionType::new(coord_type, dim, metadata).into()
+        }
+        _ => GeometryType::new(coord_type, metadata).into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gdal::DriverManager;
+    use gdal::vector::{Geometry, LayerOptions};
+    use geoarrow_schema::CrsType;
+
+    use super::*;
+
+    const COUNTRIES: &str = "../../fixtures/flatgeobuf/countries.fgb";
+
+    fn read_all<L: LayerAccess>(reader: GdalReader<L>) -> Vec<RecordBatch> {
+        reader.collect::<std::result::Result<Vec<_>, _>>().unwrap()
+    }
+
+    fn num_rows(batches: &[RecordBatch]) -> usize {
+        batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    #[test]
+    fn read() {
+        let options = GdalReaderOptions {
+            batch_size: Some(50),
+            ..Default::default()
+        };
+        let reader = GdalReader::open(COUNTRIES, LayerSelection::default(), options).unwrap();
+        let schema = reader.schema();
+        let geometry_column = reader.geometry_columns[0].clone();
+        let batches = read_all(reader);
+        assert_eq!(num_rows(&batches), 179);
+        assert_eq!(batches[0].num_rows(), 50);
+        assert_eq!(batches[0].schema(), schema);
+
+        let field = schema.field(geometry_column.index);
+        let data_type = GeoArrowType::try_from(field).unwrap();
+        assert!(matches!(data_type, GeoArrowType::MultiPolygon(_)));
+        let crs = data_type.metadata().crs();
+        assert_eq!(crs.crs_type(), Some(CrsType::Projjson));
+        assert_eq!(crs.crs_value().unwrap()["id"]["code"], 4326);
+    }
+
+    #[test]
+    fn layer_selection() {
+        let reader = GdalReader::open(COUNTRIES, "countries", Default::default()).unwrap();
+        assert_eq!(reader.layer().name(), "countries");
+        assert!(GdalReader::open(COUNTRIES, "missing", Default::default()).is_err());
+        assert!(GdalReader::open(COUNTRIES, LayerSelection::Index(1), Default::default()).is_err());
+    }
+
+    #[test]
+    fn filters() {
+        let options = GdalReaderOptions {
+            attribute_filter: Some("name = 'Canada'".to_string()),
+            include_fid: false,
+            ..Default::default()
+        };
+        let batches =
+            read_all(GdalReader::open(COUNTRIES, LayerSelection::default(), options).unwrap());
+        assert_eq!(num_rows(&batches), 1);
+
+        let options = GdalReaderOptions {
+            bbox: Some((0., -90., 180., 90.)),
+            coord_type: None,
+            ..Default::default()
+        };
+        let reader = GdalReader::open(COUNTRIES, LayerSelection::default(), options).unwrap();
+        let index = reader.geometry_columns[0].index;
+        let batches = read_all(reader);
+        let num_rows = num_rows(&batches);
+        assert!(num_rows > 0 && num_rows < 179);
+        let data_type = GeoArrowType::try_from(batches[0].schema().field(index)).unwrap();
+        assert!(matches!(data_type, GeoArrowType::Wkb(_)));
+    }
+
+    #[test]
+    fn multi_part_shapefile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lines.shp");
+        let mut dataset = DriverManager::get_driver_by_name("ESRI Shapefile")
+            .unwrap()
+            .create_vector_only(&path)
+            .unwrap();
+        let mut layer = dataset
+            .create_layer(LayerOptions {
+                name: "lines",
+                ty: OGRwkbGeometryType::wkbLineString,
+                ..Default::default()
+            })
+            .unwrap();
+        for wkt in [
+            "LINESTRING (0 0, 1 1)",
+            "MULTILINESTRING ((0 0, 1 1), (2 2, 3 3))",
+        ] {
+            layer
+                .create_feature(Geometry::from_wkt(wkt).unwrap())
+                .unwrap();
+        }
+        drop(layer);
+        drop(dataset);
+
+        let reader =
+            GdalReader::open(&path, LayerSelection::default(), Default::default()).unwrap();
+        // The driver declares the layer as line strings
+        let geometry_field = reader.layer().defn().geom_fields().next().unwrap();
+        assert_eq!(
+            geometry_field.field_type(),
+            OGRwkbGeometryType::wkbLineString
+        );
+        let schema = reader.schema();
+        let geometry_column = reader.geometry_columns[0].clone();
+        let batches = read_all(reader);
+        assert_eq!(num_rows(&batches), 2);
+        let data_type = GeoArrowType::try_from(schema.field(geometry_column.index)).unwrap();
+        assert!(matches!(data_type, GeoArrowType::MultiLineString(_)));
+    }
+
+    #[test]
+    fn single_geometry_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lines.gpkg");
+        let mut dataset = DriverManager::get_driver_by_name("GPKG")
+            .unwrap()
+            .create_vector_only(&path)
+            .unwrap();
+        let mut layer = dataset
+            .create_layer(LayerOptions {
+                name: "lines",
+                ty: OGRwkbGeometryType::wkbLineString,
+                ..Default::default()
+            })
+            .unwrap();
+        layer
+            .create_feature(Geometry::from_wkt("LINESTRING (0 0, 1 1)").unwrap())
+            .unwrap();
+        drop(layer);
+        drop(dataset);
+
+        // Only shapefiles have their line strings promoted to multi line strings
+        let reader =
+            GdalReader::open(&path, LayerSelection::default(), Default::default()).unwrap();
+        let schema = reader.schema();
+        let geometry_column = reader.geometry_columns[0].clone();
+        assert_eq!(num_rows(&read_all(reader)), 1);
+        let data_type = GeoArrowType::try_from(schema.field(geometry_column.index)).unwrap();
+        assert!(matches!(data_type, GeoArrowType::LineString(_)));
+    }
+}
@@ -0,0 +1,258 @@
+use std::ffi::CStr;
+use std::sync::Arc;
+
+use arrow_array::ffi::{FFI_ArrowArray, FFI_ArrowSchema};
+use arrow_array::{Array, RecordBatch, RecordBatchReader, StructArray};
+use arrow_schema::Schema;
+use gdal::Dataset;
+use gdal::cpl::CslStringList;
+use gdal::vector::{LayerAccess, LayerOptions};
+use gdal_sys::OGRwkbGeometryType;
+use geoarrow_array::array::from_arrow_array;
+use geoarrow_array::cast::to_wkb;
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geoarrow_schema::{Dimension, WkbType};
+
+use crate::crs::crs_spatial_ref;
+use crate::gdal_error;
+
+/// Options for writing an OGR layer.
+#[derive(Debug, Clone, Default)]
+pub struct GdalWriterOptions {
+    /// Driver-specific layer creation options, such as `"GEOMETRY_NAME=geom"`.
+    pub layer_options: Vec<String>,
+
+    /// The name of an integer column whose values are written as the feature ids.
+    ///
+    /// If `None`, feature ids are assigned by the driver.
+    pub fid_column: Option<String>,
+}
+
+/// Write record batches to a new layer of a dataset.
+///
+/// The batches may contain at most one GeoArrow geometry column, which is written as the
+/// layer's geometry field with the spatial reference of its CRS. The other columns are created as
+/// layer fields from their Arrow types, so any OGR driver that can create layers can be written
+/// to.
+///
+/// This requires GDAL 3.8 or later.
+pub fn write_gdal(
+    dataset: &mut Dataset,
+    layer_name: &str,
+    reader: impl RecordBatchReader,
+    options: GdalWriterOptions,
+) -> Result<()> {
+    let schema = reader.schema();
+    let geometry_columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| {
+            field
+                .extension_type_name()
+                .is_some_and(|name| name.starts_with("geoarrow."))
+        })
+        .map(|(index, field)| Ok((index, GeoArrowType::try_from(field.as_ref())?)))
+        .collect::<Result<Vec<_>>>()?;
+    let geometry_column = match geometry_columns.as_slice() {
+        [] => None,
+        [column] => Some(column.clone()),
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Writing with GDAL supports at most one geometry column, found {}",
+                geometry_columns.len()
+            )));
+        }
+    };
+
+    let (ty, srs) = match &geometry_column {
+        Some((_, data_type)) => (
+            ogr_geometry_type(data_type),
+            crs_spatial_ref(data_type.metadata().crs())?,
+        ),
+        None => (OGRwkbGeometryType::wkbNone, None),
+    };
+    let layer_options = options
+        .layer_options
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    let layer = dataset
+        .create_layer(LayerOptions {
+            name: layer_name,
+            srs: srs.as_ref(),
+            ty,
+            options: Some(&layer_options),
+        })
+        .map_err(gdal_error)?;
+
+    for (index, field) in schema.fields().iter().enumerate() {
+        let is_geometry = geometry_column
+            .as_ref()
+            .is_some_and(|(geometry_index, _)| *geometry_index == index);
+        if is_geometry || options.fid_column.as_ref() == Some(field.name()) {
+            continue;
+        }
+        let ffi_schema = FFI_ArrowSchema::try_from(field.as_ref())?;
+        let created = unsafe {
+            gdal_sys::OGR_L_CreateFieldFromArrowSchema(
+                layer.c_layer(),
+                (&ffi_schema as *const FFI_ArrowSchema).cast(),
+                std::ptr::null_mut(),
+            )
+        };
+        if !created {
+            return Err(last_error(&format!(
+                "Failed to create field '{}'",
+                field.name()
+            )));
+        }
+    }
+
+    let mut write_options = CslStringList::new();
+    if let Some((index, _)) = &geometry_column {
+        write_options
+            .set_name_value("GEOMETRY_NAME", schema.field(*index).name())
+            .map_err(gdal_error)?;
+    }
+    if let Some(fid_column) = &options.fid_column {
+        write_options
+            .set_name_value("FID", fid_column)
+            .map_err(gdal_error)?;
+    }
+
+    for batch in reader {
+        let batch = wkb_batch(batch?, geometry_column.as_ref())?;
+        let data = StructArray::from(batch).into_data();
+        let (mut ffi_array, ffi_schema) = arrow_array::ffi::to_ffi(&data)?;
+        let written = unsafe {
+            gdal_sys::OGR_L_WriteArrowBatch(
+                layer.c_layer(),
+                (&ffi_schema as *const FFI_ArrowSchema).cast(),
+                (&mut ffi_array as *mut FFI_ArrowArray).cast(),
+                write_options.as_ptr(),
+            )
+        };
+        if !written {
+            return Err(last_error("Failed to write batch"));
+        }
+    }
+    Ok(())
+}
+
+/// Replace the geometry column of a batch with WKB, which is the only encoding OGR can write.
+fn wkb_batch(
+    batch: RecordBatch,
+    geometry_column: Option<&(usize, GeoArrowType)>,
+) -> Result<RecordBatch> {
+    let Some((index, data_type)) = geometry_column else {
+        return Ok(batch);
+    };
+    let schema = batch.schema();
+    let field = schema.field(*index);
+    let geometry_array = from_arrow_array(batch.column(*index).as_ref(), field)?;
+    let wkb_array = to_wkb::<i32>(geometry_array.as_ref())?;
+
+    let mut fields = schema.fields().to_vec();
+    let wkb_type = WkbType::new(data_type.metadata().clone());
+    fields[*index] = Arc::new(wkb_type.to_field(field.name(), field.is_nullable()));
+    let mut columns = batch.columns().to_vec();
+    columns[*index] = wkb_array.to_array_ref();
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )?)
+}
+
+/// The OGR geometry type of a GeoArrow type.
+fn ogr_geometry_type(data_type: &GeoArrowType) -> OGRwkbGeometryType::Type {
+    use GeoArrowType::*;
+
+    let (ty, dim) = match data_type {
+        Point(t) => (OGRwkbGeometryType::wkbPoint, t.dimension()),
+        LineString(t) => (OGRwkbGeometryType::wkbLineString, t.dimension()),
+        Polygon(t) => (OGRwkbGeometryType::wkbPolygon, t.dimension()),
+        MultiPoint(t) => (OGRwkbGeometryType::wkbMultiPoint, t.dimension()),
+        MultiLineString(t) => (OGRwkbGeometryType::wkbMultiLineString, t.dimension()),
+        MultiPolygon(t) => (OGRwkbGeometryType::wkbMultiPolygon, t.dimension()),
+        GeometryCollection(t) => (OGRwkbGeometryType::wkbGeometryCollection, t.dimension()),
+        Rect(t) => (OGRwkbGeometryType::wkbPolygon, t.dimension()),
+        _ => return OGRwkbGeometryType::wkbUnknown,
+    };
+    let (has_z, has_m) = match dim {
+        Dimension::XY => (0, 0),
+        Dimension::XYZ => (1, 0),
+        Dimension::XYM => (0, 1),
+        Dimension::XYZM => (1, 1),
+    };
+    unsafe { gdal_sys::OGR_GT_SetModifier(ty, has_z, has_m) }
+}
+
+fn last_error(context: &str) -> GeoArrowError {
+    let message = unsafe { CStr::from_ptr(gdal_sys::CPLGetLastErrorMsg()) };
+    GeoArrowError::General(format!("{context}: {}", message.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod test {
+    use gdal::DriverManager;
+
+    use super::*;
+    use crate::{GdalReader, GdalReaderOptions, LayerSelection};
+
+    const COUNTRIES: &str = "../../fixtures/flatgeobuf/countries.fgb";
+
+    #[test]
+    fn round_trip() {
+        let options = GdalReaderOptions {
+            include_fid: false,
+            ..Default::default()
+        };
+        let reader = GdalReader::open(COUNTRIES, LayerSelection::default(), options).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("countries.gpkg");
+        let mut dataset = DriverManager::get_driver_by_name("GPKG")
+            .unwrap()
+            .create_vector_only(&path)
+            .unwrap();
+        write_gdal(&mut dataset, "countries", reader, Default::default()).unwrap();
+        drop(dataset);
+
+        let options = GdalReaderOptions {
+            attribute_filter: Some("name = 'Canada'".to_string()),
+            ..Default::default()
+        };
+        let reader = GdalReader::open(&path, "countries", options).unwrap();
+        let schema = reader.schema();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+
+        let geometry_field = schema
+            .fields()
+            .iter()
+            .find(|field| field.extension_type_name().is_some())
+            .unwrap();
+        let data_type = GeoArrowType::try_from(geometry_field.as_ref()).unwrap();
+        assert!(matches!(data_type, GeoArrowType::MultiPolygon(_)));
+        let crs = crs_spatial_ref(data_type.metadata().crs())
+            .unwrap()
+            .unwrap();
+        assert_eq!(crs.auth_code().unwrap(), 4326);
+    }
+
+    #[test]
+    fn multiple_geometry_columns() {
+        let schema = Arc::new(Schema::new(vec![
+            WkbType::default().to_field("a", true),
+            WkbType::default().to_field("b", true),
+        ]));
+        let reader = arrow_array::RecordBatchIterator::new(vec![], schema);
+        let dir = tempfile::tempdir().unwrap();
+        let mut dataset = DriverManager::get_driver_by_name("GPKG")
+            .unwrap()
+            .create_vector_only(dir.path().join("out.gpkg"))
+            .unwrap();
+        assert!(write_gdal(&mut dataset, "out", reader, Default::default()).is_err());
+    }
+}
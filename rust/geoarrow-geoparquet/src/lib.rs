@@ -79,13 +79,16 @@ mod total_bounds;
 mod writer;
 
 pub use reader::{
-    GeoParquetDatasetMetadata, GeoParquetReaderMetadata, GeoParquetReaderOptions,
-    GeoParquetRecordBatchReader, GeoParquetRecordBatchReaderBuilder,
+    GeoParquetDatasetMetadata, GeoParquetMergeReport, GeoParquetReaderMetadata,
+    GeoParquetReaderOptions, GeoParquetRecordBatchReader, GeoParquetRecordBatchReaderBuilder,
 };
+#[cfg(feature = "object_store")]
+pub use reader::{GeoParquetListingOptions, GeoParquetObjectStoreDataset};
 #[cfg(feature = "async")]
 pub use reader::{GeoParquetRecordBatchStream, GeoParquetRecordBatchStreamBuilder};
 pub use writer::{
-    GeoParquetWriter, GeoParquetWriterEncoding, GeoParquetWriterOptions, write_geoparquet,
+    GeoParquetTypeInference, GeoParquetWriter, GeoParquetWriterEncoding, GeoParquetWriterOptions,
+    write_geoparquet,
 };
 #[cfg(feature = "async")]
 pub use writer::{GeoParquetWriterAsync, write_geoparquet_async};
@@ -596,6 +596,111 @@ impl GeoParquetMetadata {
         Ok(())
     }
 
+    /// Merge another file's metadata into this one, widening where the files disagree.
+    ///
+    /// Unlike [`Self::try_update`], this does not require both files to have the same set of
+    /// geometry columns or the same encodings:
+    ///
+    /// - Geometry columns are unioned by name.
+    /// - The geometry types of a column are unioned, so that e.g. `Point` and `MultiPoint` resolve
+    ///   to `MultiPoint` and differing dimensions resolve to `Geometry`.
+    /// - If one file stores a column with a native encoding and another as WKB, the geometry types
+    ///   are cleared, so that the column resolves to `Geometry`.
+    /// - If files store a column with different native encodings, the merged column is encoded as
+    ///   WKB, keeping the union of the geometry types.
+    /// - Bounding boxes are expanded. Coverings that differ between files are dropped.
+    ///
+    /// Columns whose CRS or edges differ can't be merged; these are returned as conflicts, leaving
+    /// the existing column metadata untouched.
+    pub fn try_merge(
+        &mut self,
+        other: &GeoParquetMetadata,
+    ) -> std::result::Result<(), Vec<GeoParquetColumnConflict>> {
+        let mut conflicts = vec![];
+        for (column_name, other_column_meta) in other.columns.iter() {
+            let Some(column_meta) = self.columns.get_mut(column_name) else {
+                self.columns
+                    .insert(column_name.clone(), other_column_meta.clone());
+                continue;
+            };
+
+            if column_meta.crs != other_column_meta.crs {
+                conflicts.push(GeoParquetColumnConflict::new(
+                    column_name,
+                    "CRS does not match the CRS of previous files",
+                ));
+                continue;
+            }
+
+            if column_meta.edges.as_deref().unwrap_or("planar")
+                != other_column_meta.edges.as_deref().unwrap_or("planar")
+            {
+                conflicts.push(GeoParquetColumnConflict::new(
+                    column_name,
+                    format!(
+                        "edges {:?} does not match edges {:?} of previous files",
+                        other_column_meta.edges, column_meta.edges
+                    ),
+                ));
+                continue;
+            }
+
+            let is_wkb = column_meta.encoding == GeoParquetColumnEncoding::WKB;
+            let other_is_wkb = other_column_meta.encoding == GeoParquetColumnEncoding::WKB;
+            if is_wkb != other_is_wkb {
+                // Mixing native and serialized encodings
+                column_meta.encoding = GeoParquetColumnEncoding::WKB;
+                column_meta.geometry_types.clear();
+            } else if column_meta.geometry_types.is_empty()
+                || other_column_meta.geometry_types.is_empty()
+            {
+                // An empty set means the geometry types are unknown
+                column_meta.geometry_types.clear();
+            } else {
+                column_meta
+                    .geometry_types
+                    .extend(other_column_meta.geometry_types.iter().copied());
+            }
+
+            // A native encoding can only hold one geometry type, so files with different native
+            // encodings are merged as WKB
+            if column_meta.encoding != other_column_meta.encoding {
+                column_meta.encoding = GeoParquetColumnEncoding::WKB;
+            }
+
+            column_meta.bbox = match (column_meta.bbox.take(), &other_column_meta.bbox) {
+                (Some(bbox), Some(other_bbox)) => Some(merge_bbox(&bbox, other_bbox)),
+                // If either file has no bbox, the bbox of the merged column is unknown
+                _ => None,
+            };
+
+            if column_meta.orientation != other_column_meta.orientation {
+                column_meta.orientation = None;
+            }
+
+            if column_meta.epoch != other_column_meta.epoch {
+                column_meta.epoch = None;
+            }
+
+            let same_covering = match (&column_meta.covering, &other_column_meta.covering) {
+                (Some(left), Some(right)) => {
+                    serde_json::to_value(left).ok() == serde_json::to_value(right).ok()
+                }
+                (None, None) => true,
+                _ => false,
+            };
+            if !same_covering {
+                column_meta.covering = None;
+            }
+        }
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(conflicts)
+        }
+    }
+
     /// Check if this metadata is compatible with another metadata instance, swallowing the error
     /// message if not compatible.
     pub fn is_compatible_with(&self, other: &GeoParquetMetadata) -> bool {
@@ -696,6 +801,55 @@ impl GeoParquetMetadata {
     }
 }
 
+/// A column that could not be merged across the files of a GeoParquet dataset.
+///
+/// See [`GeoParquetMetadata::try_merge`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoParquetColumnConflict {
+    /// The name of the column.
+    pub column: String,
+
+    /// A description of why the column could not be merged.
+    pub reason: String,
+}
+
+impl GeoParquetColumnConflict {
+    pub(crate) fn new(column: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for GeoParquetColumnConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column {}: {}", self.column, self.reason)
+    }
+}
+
+/// Expand a bounding box, formatted according to RFC 7946, section 5, to include another.
+///
+/// If one of the boxes is 2D and the other is 3D, the result is 2D.
+fn merge_bbox(left: &[f64], right: &[f64]) -> Vec<f64> {
+    // Split into (min, max) halves
+    let (left_min, left_max) = left.split_at(left.len() / 2);
+    let (right_min, right_max) = right.split_at(right.len() / 2);
+    let dim = left_min.len().min(right_min.len());
+
+    let mins = left_min
+        .iter()
+        .zip(right_min)
+        .take(dim)
+        .map(|(l, r)| l.min(*r));
+    let maxs = left_max
+        .iter()
+        .zip(right_max)
+        .take(dim)
+        .map(|(l, r)| l.max(*r));
+    mins.chain(maxs).collect()
+}
+
 impl From<GeoParquetColumnMetadata> for Metadata {
     fn from(value: GeoParquetColumnMetadata) -> Self {
         let edges = if let Some(edges) = value.edges {
@@ -858,4 +1012,88 @@ mod test {
 
         dbg!(&meta);
     }
+
+    fn column_meta(encoding: &str, geometry_types: &[&str]) -> GeoParquetColumnMetadata {
+        let geometry_types = serde_json::to_string(geometry_types).unwrap();
+        let s = format!(
+            r#"{{"encoding": "{encoding}", "geometry_types": {geometry_types}, "bbox": [0, 0, 1, 1]}}"#
+        );
+        serde_json::from_str(&s).unwrap()
+    }
+
+    fn file_meta(columns: Vec<(&str, GeoParquetColumnMetadata)>) -> GeoParquetMetadata {
+        GeoParquetMetadata {
+            version: "1.1.0".to_string(),
+            primary_column: columns[0].0.to_string(),
+            columns: columns
+                .into_iter()
+                .map(|(name, meta)| (name.to_string(), meta))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn merge_widens_geometry_types() {
+        let mut left = file_meta(vec![("geometry", column_meta("point", &["Point"]))]);
+        let mut right = file_meta(vec![
+            ("geometry", column_meta("multipoint", &["MultiPoint"])),
+            ("other", column_meta("WKB", &["Polygon"])),
+        ]);
+        right.columns.get_mut("geometry").unwrap().bbox = Some(vec![-1., 0., 0.5, 2.]);
+        left.try_merge(&right).unwrap();
+
+        let geometry = &left.columns["geometry"];
+        assert_eq!(geometry.geometry_types.len(), 2);
+        assert_eq!(geometry.bbox, Some(vec![-1., 0., 1., 2.]));
+        assert!(left.columns.contains_key("other"));
+        let data_type = infer_geo_data_type(
+            &geometry.geometry_types,
+            CoordType::Separated,
+            Default::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(matches!(data_type, GeoArrowType::MultiPoint(_)));
+    }
+
+    #[test]
+    fn merge_native_and_wkb() {
+        let mut left = file_meta(vec![("geometry", column_meta("polygon", &["Polygon"]))]);
+        let right = file_meta(vec![("geometry", column_meta("WKB", &["Polygon"]))]);
+        left.try_merge(&right).unwrap();
+
+        let geometry = &left.columns["geometry"];
+        assert_eq!(geometry.encoding, GeoParquetColumnEncoding::WKB);
+        assert!(geometry.geometry_types.is_empty());
+    }
+
+    #[test]
+    fn merge_different_native_encodings() {
+        let mut left = file_meta(vec![("geometry", column_meta("point", &["Point"]))]);
+        let right = file_meta(vec![("geometry", column_meta("polygon", &["Polygon"]))]);
+        left.try_merge(&right).unwrap();
+
+        let geometry = &left.columns["geometry"];
+        assert_eq!(geometry.encoding, GeoParquetColumnEncoding::WKB);
+        assert_eq!(geometry.geometry_types.len(), 2);
+        let data_type = infer_geo_data_type(
+            &geometry.geometry_types,
+            CoordType::Separated,
+            Default::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(matches!(data_type, GeoArrowType::Geometry(_)));
+    }
+
+    #[test]
+    fn merge_conflicting_crs() {
+        let mut left = file_meta(vec![("geometry", column_meta("WKB", &["Point"]))]);
+        let mut right = left.clone();
+        right.columns.get_mut("geometry").unwrap().crs =
+            Some(serde_json::json!({"id": {"authority": "EPSG", "code": 3857}}));
+        let conflicts = left.try_merge(&right).unwrap_err();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].column, "geometry");
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use arrow_array::RecordBatch;
 use arrow_schema::{ArrowError, SchemaRef};
 use async_stream::try_stream;
@@ -12,8 +14,8 @@ use parquet::arrow::async_reader::{
 use crate::metadata::GeoParquetMetadata;
 use crate::reader::builder::GeoParquetReaderBuilder;
 use crate::reader::metadata::GeoParquetReaderMetadata;
-use crate::reader::options::GeoParquetReaderOptions;
-use crate::reader::parse::{infer_target_schema, parse_record_batch};
+use crate::reader::options::{GeoParquetReaderOptions, prune_with_bloom_filters};
+use crate::reader::parse::{infer_target_schema, parse_and_cast_record_batch};
 
 /// A builder used to construct a [`GeoParquetRecordBatchStream`] for async reading of a GeoParquet
 /// file.
@@ -75,8 +77,9 @@ impl<T: AsyncFileReader + Send + 'static> GeoParquetRecordBatchStreamBuilder<T>
             input,
             metadata.arrow_metadata().clone(),
         );
-        let geo_meta =
-            GeoParquetMetadata::from_parquet_meta(builder.metadata().file_metadata()).ok();
+        let geo_meta = metadata
+            .geo_metadata()
+            .map(|geo_meta| geo_meta.as_ref().clone());
         Self {
             builder,
             geo_meta,
@@ -85,17 +88,35 @@ impl<T: AsyncFileReader + Send + 'static> GeoParquetRecordBatchStreamBuilder<T>
     }
 
     /// Consume this builder, returning a [`GeoParquetRecordBatchStream`]
+    ///
+    /// If [bloom filters][GeoParquetReaderOptions::with_bloom_filter] are used, they are fetched
+    /// when the stream is started.
     pub fn build(self) -> Result<GeoParquetRecordBatchStream<T>> {
         let output_schema = self.output_schema()?;
-        let builder = self
+        let target_schema = self.options.target_schema.clone();
+        let leaves = self
             .options
-            .apply_to_builder(self.builder, self.geo_meta.as_ref())?;
-        let stream = builder
-            .build()
-            .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+            .bloom_filter_leaves(self.builder.parquet_schema())?;
+        let source = if leaves.is_empty() {
+            let builder = self
+                .options
+                .apply_to_builder(self.builder, self.geo_meta.as_ref())?;
+            let stream = builder
+                .build()
+                .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+            StreamSource::Stream(stream)
+        } else {
+            StreamSource::Pending(PendingStream {
+                builder: self.builder,
+                geo_meta: self.geo_meta,
+                options: self.options,
+                leaves,
+            })
+        };
         Ok(GeoParquetRecordBatchStream {
-            stream,
+            source,
             output_schema,
+            target_schema,
         })
     }
 }
@@ -118,12 +139,14 @@ impl<T: AsyncFileReader + Send + 'static> GeoParquetReaderBuilder
     for GeoParquetRecordBatchStreamBuilder<T>
 {
     fn output_schema(&self) -> Result<SchemaRef> {
-        if let Some(geo_meta) = &self.geo_meta {
-            infer_target_schema(self.builder.schema(), geo_meta, self.options.coord_type)
+        let schema = if let Some(geo_meta) = &self.geo_meta {
+            infer_target_schema(self.builder.schema(), geo_meta, self.options.coord_type)?
         } else {
             // If non-geospatial, return the same schema as output
-            Ok(self.builder.schema().clone())
-        }
+            self.builder.schema().clone()
+        };
+        self.options
+            .project_schema(schema, self.builder.parquet_schema())
     }
 
     fn with_options(self, options: GeoParquetReaderOptions) -> Self {
@@ -137,8 +160,58 @@ impl<T: AsyncFileReader + Send + 'static> GeoParquetReaderBuilder
 ///
 /// This will parse any geometries to their native representation.
 pub struct GeoParquetRecordBatchStream<T: AsyncFileReader + Send + 'static> {
-    stream: ParquetRecordBatchStream<T>,
+    source: StreamSource<T>,
     output_schema: SchemaRef,
+    target_schema: Option<SchemaRef>,
+}
+
+/// The Parquet stream of a [`GeoParquetRecordBatchStream`].
+enum StreamSource<T: AsyncFileReader + Send + 'static> {
+    Stream(ParquetRecordBatchStream<T>),
+    /// The row groups still have to be pruned with bloom filters, which are fetched
+    /// asynchronously.
+    Pending(PendingStream<T>),
+}
+
+struct PendingStream<T: AsyncFileReader + Send + 'static> {
+    builder: ParquetRecordBatchStreamBuilder<T>,
+    geo_meta: Option<GeoParquetMetadata>,
+    options: GeoParquetReaderOptions,
+    /// Pairs of leaf column index and plain-encoded value to check the bloom filters for.
+    leaves: Vec<(usize, Vec<u8>)>,
+}
+
+impl<T: AsyncFileReader + Send + 'static> PendingStream<T> {
+    /// Fetch the bloom filters of the candidate row groups, then build the stream of the row
+    /// groups that may contain every value.
+    async fn build(mut self) -> Result<ParquetRecordBatchStream<T>> {
+        let candidates = self
+            .options
+            .row_group_candidates(self.builder.metadata().num_row_groups());
+        let mut bloom_filters = HashMap::new();
+        for row_group_idx in candidates.iter() {
+            for (leaf_idx, _) in self.leaves.iter() {
+                let bloom_filter = self
+                    .builder
+                    .get_row_group_column_bloom_filter(*row_group_idx, *leaf_idx)
+                    .await
+                    .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+                bloom_filters.insert((*row_group_idx, *leaf_idx), bloom_filter);
+            }
+        }
+        let row_groups =
+            prune_with_bloom_filters(candidates, &self.leaves, |row_group_idx, leaf_idx| {
+                Ok(bloom_filters.remove(&(row_group_idx, leaf_idx)).flatten())
+            })?;
+
+        let builder = self
+            .options
+            .with_row_groups(row_groups)
+            .apply_to_builder(self.builder, self.geo_meta.as_ref())?;
+        builder
+            .build()
+            .map_err(|err| GeoArrowError::External(Box::new(err)))
+    }
 }
 
 impl<T: AsyncFileReader + Unpin + Send + 'static> GeoParquetRecordBatchStream<T> {
@@ -150,15 +223,22 @@ impl<T: AsyncFileReader + Unpin + Send + 'static> GeoParquetRecordBatchStream<T>
         self,
     ) -> impl Stream<Item = std::result::Result<RecordBatch, ArrowError>> + 'static {
         try_stream! {
-            for await batch in self.stream {
-                yield parse_record_batch(batch?, self.output_schema.clone()).map_err(|err| ArrowError::CastError(err.to_string()))?
+            let stream = match self.source {
+                StreamSource::Stream(stream) => stream,
+                StreamSource::Pending(pending) => pending.build().await?,
+            };
+            for await batch in stream {
+                yield parse_and_cast_record_batch(batch?, self.output_schema.clone(), self.target_schema.clone()).map_err(|err| ArrowError::CastError(err.to_string()))?
             }
         }
     }
 
     /// Collect all batches into an in-memory table.
     pub async fn read_table(self) -> Result<(Vec<RecordBatch>, SchemaRef)> {
-        let output_schema = self.output_schema.clone();
+        let output_schema = self
+            .target_schema
+            .clone()
+            .unwrap_or_else(|| self.output_schema.clone());
         let batches = self.read_stream().try_collect::<_>().await?;
         Ok((batches, output_schema))
     }
@@ -166,11 +246,13 @@ impl<T: AsyncFileReader + Unpin + Send + 'static> GeoParquetRecordBatchStream<T>
 
 #[cfg(all(test, feature = "compression"))]
 mod test {
-    use super::*;
+    use arrow_array::cast::AsArray;
+    use parquet::file::properties::WriterProperties;
     use tokio::fs::File;
 
+    use super::*;
     use crate::metadata::GeoParquetBboxCovering;
-    use crate::test::fixture_dir;
+    use crate::test::{fixture_dir, write_sorted_points};
 
     #[tokio::test]
     async fn nybb() -> Result<()> {
@@ -292,4 +374,62 @@ mod test {
         let (batches, _schema) = reader.read_table().await.unwrap();
         assert_eq!(batches.iter().fold(0, |acc, x| acc + x.num_rows()), 53);
     }
+
+    #[tokio::test]
+    async fn page_index_bbox_filter() {
+        // 100 points sorted by x, written to a single row group of 10 pages
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("page_index.parquet");
+        let writer_properties = WriterProperties::builder()
+            .set_data_page_row_count_limit(10)
+            .set_write_batch_size(10)
+            .build();
+        write_sorted_points(&path, writer_properties);
+
+        let bbox = geo_types::Rect::new(
+            geo_types::coord! { x: 12., y: -1. },
+            geo_types::coord! { x: 15., y: 1. },
+        );
+        let reader = GeoParquetRecordBatchStreamBuilder::try_new_with_options(
+            File::open(&path).await.unwrap(),
+            ArrowReaderOptions::new().with_page_index(true),
+            GeoParquetReaderOptions::default().with_bbox(bbox, None),
+        )
+        .await
+        .unwrap()
+        .build()
+        .unwrap();
+        let (batches, _schema) = reader.read_table().await.unwrap();
+        assert_eq!(batches.iter().fold(0, |acc, x| acc + x.num_rows()), 4);
+    }
+
+    #[tokio::test]
+    async fn bloom_filter() {
+        // 100 points, written to 10 row groups with a bloom filter on the id column
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bloom_filter.parquet");
+        let writer_properties = WriterProperties::builder()
+            .set_max_row_group_size(10)
+            .set_bloom_filter_enabled(true)
+            .set_bloom_filter_fpp(0.001)
+            .build();
+        write_sorted_points(&path, writer_properties);
+
+        let reader = GeoParquetRecordBatchStreamBuilder::try_new_with_options(
+            File::open(&path).await.unwrap(),
+            Default::default(),
+            GeoParquetReaderOptions::default().with_bloom_filter("id", "42"),
+        )
+        .await
+        .unwrap()
+        .build()
+        .unwrap();
+        let (batches, _schema) = reader.read_table().await.unwrap();
+        let ids = batches
+            .iter()
+            .flat_map(|batch| batch.column(0).as_string::<i32>().iter().flatten())
+            .collect::<Vec<_>>();
+        assert!(ids.contains(&"42"));
+        assert!(ids.len() < 100);
+    }
 }
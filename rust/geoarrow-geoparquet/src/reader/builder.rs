@@ -9,8 +9,8 @@ use parquet::file::reader::ChunkReader;
 
 use crate::metadata::GeoParquetMetadata;
 use crate::reader::metadata::GeoParquetReaderMetadata;
-use crate::reader::options::GeoParquetReaderOptions;
-use crate::reader::parse::{infer_target_schema, parse_record_batch};
+use crate::reader::options::{GeoParquetReaderOptions, prune_with_bloom_filters};
+use crate::reader::parse::{infer_target_schema, parse_and_cast_record_batch};
 
 pub trait GeoParquetReaderBuilder: Sized {
     fn output_schema(&self) -> Result<SchemaRef>;
@@ -73,7 +73,15 @@ impl<T: ChunkReader + 'static> GeoParquetRecordBatchReaderBuilder<T> {
             input,
             metadata.arrow_metadata().clone(),
         );
-        Self::from(builder).with_options(geo_options)
+        let geo_meta = metadata
+            .geo_metadata()
+            .map(|geo_meta| geo_meta.as_ref().clone());
+        Self {
+            builder,
+            geo_meta,
+            options: Default::default(),
+        }
+        .with_options(geo_options)
     }
 
     /// Returns a reference to the geo metadata.
@@ -82,8 +90,10 @@ impl<T: ChunkReader + 'static> GeoParquetRecordBatchReaderBuilder<T> {
     }
 
     /// Consume this builder, returning a [`GeoParquetRecordBatchReader`]
-    pub fn build(self) -> Result<GeoParquetRecordBatchReader> {
+    pub fn build(mut self) -> Result<GeoParquetRecordBatchReader> {
+        self.apply_bloom_filters()?;
         let output_schema = self.output_schema()?;
+        let target_schema = self.options.target_schema.clone();
         let builder = self
             .options
             .apply_to_builder(self.builder, self.geo_meta.as_ref())?;
@@ -93,8 +103,32 @@ impl<T: ChunkReader + 'static> GeoParquetRecordBatchReaderBuilder<T> {
         Ok(GeoParquetRecordBatchReader {
             reader,
             output_schema,
+            target_schema,
         })
     }
+
+    /// Restrict the row groups to read to those whose bloom filters may contain the values of
+    /// [`GeoParquetReaderOptions::with_bloom_filter`].
+    fn apply_bloom_filters(&mut self) -> Result<()> {
+        let leaves = self
+            .options
+            .bloom_filter_leaves(self.builder.parquet_schema())?;
+        if leaves.is_empty() {
+            return Ok(());
+        }
+
+        let candidates = self
+            .options
+            .row_group_candidates(self.builder.metadata().num_row_groups());
+        let row_groups =
+            prune_with_bloom_filters(candidates, &leaves, |row_group_idx, leaf_idx| {
+                self.builder
+                    .get_row_group_column_bloom_filter(row_group_idx, leaf_idx)
+                    .map_err(|err| GeoArrowError::External(Box::new(err)))
+            })?;
+        self.options = std::mem::take(&mut self.options).with_row_groups(row_groups);
+        Ok(())
+    }
 }
 
 impl<T: ChunkReader + 'static> From<ParquetRecordBatchReaderBuilder<T>>
@@ -113,12 +147,14 @@ impl<T: ChunkReader + 'static> From<ParquetRecordBatchReaderBuilder<T>>
 
 impl<T: ChunkReader + 'static> GeoParquetReaderBuilder for GeoParquetRecordBatchReaderBuilder<T> {
     fn output_schema(&self) -> Result<SchemaRef> {
-        if let Some(geo_meta) = &self.geo_meta {
-            infer_target_schema(self.builder.schema(), geo_meta, self.options.coord_type)
+        let schema = if let Some(geo_meta) = &self.geo_meta {
+            infer_target_schema(self.builder.schema(), geo_meta, self.options.coord_type)?
         } else {
             // If non-geospatial, return the same schema as output
-            Ok(self.builder.schema().clone())
-        }
+            self.builder.schema().clone()
+        };
+        self.options
+            .project_schema(schema, self.builder.parquet_schema())
     }
 
     fn with_options(self, options: GeoParquetReaderOptions) -> Self {
@@ -132,6 +168,7 @@ impl<T: ChunkReader + 'static> GeoParquetReaderBuilder for GeoParquetRecordBatch
 pub struct GeoParquetRecordBatchReader {
     reader: ParquetRecordBatchReader,
     output_schema: SchemaRef,
+    target_schema: Option<SchemaRef>,
 }
 
 impl GeoParquetRecordBatchReader {
@@ -149,8 +186,12 @@ impl Iterator for GeoParquetRecordBatchReader {
         if let Some(batch) = self.reader.next() {
             match batch {
                 Ok(batch) => Some(
-                    parse_record_batch(batch, self.output_schema.clone())
-                        .map_err(|err| ArrowError::CastError(err.to_string())),
+                    parse_and_cast_record_batch(
+                        batch,
+                        self.output_schema.clone(),
+                        self.target_schema.clone(),
+                    )
+                    .map_err(|err| ArrowError::CastError(err.to_string())),
                 ),
                 Err(err) => Some(Err(err)),
             }
@@ -162,19 +203,23 @@ impl Iterator for GeoParquetRecordBatchReader {
 
 impl RecordBatchReader for GeoParquetRecordBatchReader {
     fn schema(&self) -> arrow_schema::SchemaRef {
-        self.output_schema.clone()
+        self.target_schema
+            .clone()
+            .unwrap_or_else(|| self.output_schema.clone())
     }
 }
 
 #[cfg(all(test, feature = "compression"))]
 mod test {
+    use std::fs::File;
+
     use arrow_array::cast::AsArray;
+    use parquet::file::properties::WriterProperties;
 
     use super::*;
-    use std::fs::File;
-
     use crate::metadata::GeoParquetBboxCovering;
-    use crate::test::fixture_dir;
+    use crate::reader::spatial_filter::{ParquetBboxStatistics, bbox_page_selection};
+    use crate::test::{fixture_dir, write_sorted_points};
 
     #[test]
     fn nybb() {
@@ -341,4 +386,103 @@ mod test {
             .unwrap();
         assert_eq!(batches.iter().fold(0, |acc, x| acc + x.num_rows()), 53);
     }
+
+    #[test]
+    fn page_index_bbox_filter() {
+        // 100 points sorted by x, written to a single row group of 10 pages
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("page_index.parquet");
+        let writer_properties = WriterProperties::builder()
+            .set_data_page_row_count_limit(10)
+            .set_write_batch_size(10)
+            .build();
+        write_sorted_points(&path, writer_properties);
+
+        let bbox = geo_types::Rect::new(
+            geo_types::coord! { x: 12., y: -1. },
+            geo_types::coord! { x: 15., y: 1. },
+        );
+        let arrow_options = ArrowReaderOptions::new().with_page_index(true);
+
+        // Only the second page can intersect the bbox
+        let meta =
+            ArrowReaderMetadata::load(&File::open(&path).unwrap(), arrow_options.clone()).unwrap();
+        let geo_meta =
+            GeoParquetMetadata::from_parquet_meta(meta.metadata().file_metadata()).unwrap();
+        let bbox_paths = geo_meta.bbox_covering(None).unwrap().unwrap();
+        let bbox_cols = ParquetBboxStatistics::try_new(meta.parquet_schema(), &bbox_paths).unwrap();
+        let selection = bbox_page_selection(meta.metadata(), &bbox_cols, &bbox, &[0]).unwrap();
+        assert_eq!(selection.row_count(), 10);
+
+        let reader = GeoParquetRecordBatchReaderBuilder::try_new_with_options(
+            File::open(&path).unwrap(),
+            arrow_options,
+            GeoParquetReaderOptions::default().with_bbox(bbox, None),
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let batches = reader
+            .collect::<std::result::Result<Vec<_>, ArrowError>>()
+            .unwrap();
+        assert_eq!(batches.iter().fold(0, |acc, x| acc + x.num_rows()), 4);
+    }
+
+    #[test]
+    fn bloom_filter() {
+        // 100 points, written to 10 row groups with a bloom filter on the id column
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bloom_filter.parquet");
+        let writer_properties = WriterProperties::builder()
+            .set_max_row_group_size(10)
+            .set_bloom_filter_enabled(true)
+            .set_bloom_filter_fpp(0.001)
+            .build();
+        write_sorted_points(&path, writer_properties);
+
+        let options = GeoParquetReaderOptions::default().with_bloom_filter("id", "42");
+        let reader = GeoParquetRecordBatchReaderBuilder::try_new_with_options(
+            File::open(&path).unwrap(),
+            Default::default(),
+            options,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let batches = reader
+            .collect::<std::result::Result<Vec<_>, ArrowError>>()
+            .unwrap();
+        let ids = batches
+            .iter()
+            .flat_map(|batch| batch.column(0).as_string::<i32>().iter().flatten())
+            .collect::<Vec<_>>();
+        assert!(ids.contains(&"42"));
+        assert!(ids.len() < 100);
+
+        // Every bloom filter must allow a value for its row group to be read
+        let options = GeoParquetReaderOptions::default()
+            .with_bloom_filter("id", "42")
+            .with_bloom_filter("id", "55");
+        let builder = GeoParquetRecordBatchReaderBuilder::try_new_with_options(
+            File::open(&path).unwrap(),
+            Default::default(),
+            options,
+        )
+        .unwrap();
+        let num_rows = builder
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum::<usize>();
+        assert_eq!(num_rows, 0);
+
+        let options = GeoParquetReaderOptions::default().with_bloom_filter("missing", "42");
+        let builder = GeoParquetRecordBatchReaderBuilder::try_new_with_options(
+            File::open(&path).unwrap(),
+            Default::default(),
+            options,
+        )
+        .unwrap();
+        assert!(builder.build().is_err());
+    }
 }
@@ -0,0 +1,613 @@
+//! Read a GeoParquet dataset consisting of one or more files stored in an [ObjectStore].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, SchemaRef};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_schema::CoordType;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::arrow::async_reader::ParquetObjectReader;
+
+use crate::reader::r#async::GeoParquetRecordBatchStreamBuilder;
+use crate::reader::metadata::{GeoParquetDatasetMetadata, GeoParquetReaderMetadata};
+use crate::reader::options::GeoParquetReaderOptions;
+use crate::reader::spatial_filter::file_bbox_intersects;
+
+/// Options for discovering the files of a GeoParquet dataset in an [ObjectStore].
+#[derive(Debug, Clone)]
+pub struct GeoParquetListingOptions {
+    /// The maximum number of Parquet footers to fetch concurrently.
+    metadata_concurrency: usize,
+
+    /// The maximum number of files to read concurrently in
+    /// [`GeoParquetObjectStoreDataset::read_stream`].
+    read_concurrency: usize,
+
+    /// Only files whose path ends with this suffix are included in the dataset.
+    ///
+    /// Defaults to `.parquet`. If set to `None`, all listed files are included.
+    file_extension: Option<String>,
+
+    /// The options used when loading the [ArrowReaderMetadata] of each file.
+    arrow_options: ArrowReaderOptions,
+
+    /// Whether to union the schemas of all files by name instead of requiring them to be equal.
+    merge_schemas: bool,
+}
+
+impl Default for GeoParquetListingOptions {
+    fn default() -> Self {
+        Self {
+            metadata_concurrency: 16,
+            read_concurrency: 4,
+            file_extension: Some(".parquet".to_string()),
+            arrow_options: Default::default(),
+            merge_schemas: false,
+        }
+    }
+}
+
+impl GeoParquetListingOptions {
+    /// Set the maximum number of Parquet footers to fetch concurrently.
+    ///
+    /// Defaults to 16.
+    pub fn with_metadata_concurrency(self, metadata_concurrency: usize) -> Self {
+        Self {
+            metadata_concurrency: metadata_concurrency.max(1),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of files to read concurrently when streaming the dataset.
+    ///
+    /// The batches of each file being read are buffered in memory until all batches of the
+    /// preceding files have been yielded, so memory use grows with this number.
+    ///
+    /// Defaults to 4.
+    pub fn with_read_concurrency(self, read_concurrency: usize) -> Self {
+        Self {
+            read_concurrency: read_concurrency.max(1),
+            ..self
+        }
+    }
+
+    /// Only include files whose path ends with the provided suffix.
+    ///
+    /// Defaults to `.parquet`. Pass `None` to include every listed file.
+    pub fn with_file_extension(self, file_extension: Option<String>) -> Self {
+        Self {
+            file_extension,
+            ..self
+        }
+    }
+
+    /// Set the [ArrowReaderOptions] used when loading the metadata of each file.
+    pub fn with_arrow_options(self, arrow_options: ArrowReaderOptions) -> Self {
+        Self {
+            arrow_options,
+            ..self
+        }
+    }
+
+    /// Allow the files of the dataset to have different schemas.
+    ///
+    /// When enabled, the schemas of all files are unioned by name and each file is cast to the
+    /// merged schema when read; see [`GeoParquetDatasetMetadata::from_files_merged`]. Otherwise,
+    /// all files must have identical schemas.
+    ///
+    /// Defaults to `false`.
+    pub fn with_merge_schemas(self, merge_schemas: bool) -> Self {
+        Self {
+            merge_schemas,
+            ..self
+        }
+    }
+}
+
+/// A GeoParquet dataset consisting of one or more files stored in an [ObjectStore].
+///
+/// Constructing a dataset lists the files under a prefix or matching a glob and fetches all of
+/// their Parquet footers concurrently. Reading the dataset yields a single stream of
+/// [RecordBatch]es across all files, skipping whole files whose GeoParquet `bbox` does not
+/// intersect the spatial filter before any data is fetched from them.
+///
+/// ```notest
+/// use object_store::local::LocalFileSystem;
+///
+/// let store = Arc::new(LocalFileSystem::new_with_prefix("data")?);
+/// let dataset =
+///     GeoParquetObjectStoreDataset::try_new_from_glob(store, "buildings/**/*.parquet", Default::default())
+///         .await?;
+/// let stream = dataset.read_stream(GeoParquetReaderOptions::default().with_bbox(bbox, None))?;
+/// ```
+pub struct GeoParquetObjectStoreDataset {
+    store: Arc<dyn ObjectStore>,
+    /// The objects in this dataset, sorted by location.
+    objects: Vec<ObjectMeta>,
+    metadata: GeoParquetDatasetMetadata,
+    read_concurrency: usize,
+}
+
+impl GeoParquetObjectStoreDataset {
+    /// Construct a dataset from all files under the given prefix.
+    ///
+    /// If `prefix` is `None`, all files in the store are listed.
+    pub async fn try_new_from_prefix(
+        store: Arc<dyn ObjectStore>,
+        prefix: Option<&Path>,
+        options: GeoParquetListingOptions,
+    ) -> Result<Self> {
+        let objects = list_objects(store.as_ref(), prefix, |_| true).await?;
+        Self::try_new_from_objects(store, objects, options).await
+    }
+
+    /// Construct a dataset from all files whose path matches the given glob pattern.
+    ///
+    /// Within the pattern, `*` matches any sequence of characters within a single path segment,
+    /// `?` matches any single character other than `/` and `**` matches any number of path
+    /// segments. For example, `year=*/**/*.parquet`.
+    ///
+    /// Only the portion of the store under the longest literal prefix of the pattern is listed.
+    pub async fn try_new_from_glob(
+        store: Arc<dyn ObjectStore>,
+        pattern: &str,
+        options: GeoParquetListingOptions,
+    ) -> Result<Self> {
+        let prefix = glob_prefix(pattern).map(Path::from);
+        let objects = list_objects(store.as_ref(), prefix.as_ref(), |location| {
+            glob_match(pattern.as_bytes(), location.as_ref().as_bytes())
+        })
+        .await?;
+        Self::try_new_from_objects(store, objects, options).await
+    }
+
+    /// Construct a dataset from an explicit collection of objects.
+    ///
+    /// The Parquet footers of the objects are fetched concurrently, with at most
+    /// `metadata_concurrency` requests in flight at a time.
+    pub async fn try_new_from_objects(
+        store: Arc<dyn ObjectStore>,
+        objects: Vec<ObjectMeta>,
+        options: GeoParquetListingOptions,
+    ) -> Result<Self> {
+        let mut objects = objects
+            .into_iter()
+            .filter(|object| {
+                options
+                    .file_extension
+                    .as_ref()
+                    .is_none_or(|ext| object.location.as_ref().ends_with(ext.as_str()))
+            })
+            .collect::<Vec<_>>();
+        objects.sort_by(|a, b| a.location.cmp(&b.location));
+
+        let metas = stream::iter(objects.iter().cloned())
+            .map(|object| {
+                let store = store.clone();
+                let arrow_options = options.arrow_options.clone();
+                async move {
+                    let mut reader = ParquetObjectReader::new(store, object.location.clone())
+                        .with_file_size(object.size);
+                    let arrow_meta = ArrowReaderMetadata::load_async(&mut reader, arrow_options)
+                        .await
+                        .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+                    Ok::<_, GeoArrowError>((object.location.to_string(), arrow_meta))
+                }
+            })
+            .buffered(options.metadata_concurrency)
+            .try_collect::<HashMap<_, _>>()
+            .await?;
+
+        let metadata = if options.merge_schemas {
+            GeoParquetDatasetMetadata::from_files_merged(metas)?
+        } else {
+            GeoParquetDatasetMetadata::from_files(metas)?
+        };
+        Ok(Self {
+            store,
+            objects,
+            metadata,
+            read_concurrency: options.read_concurrency,
+        })
+    }
+
+    /// Access the metadata of this dataset.
+    pub fn metadata(&self) -> &GeoParquetDatasetMetadata {
+        &self.metadata
+    }
+
+    /// The objects contained in this dataset, sorted by location.
+    pub fn objects(&self) -> &[ObjectMeta] {
+        &self.objects
+    }
+
+    /// Construct an _output_ Arrow schema based on the provided `CoordType`.
+    ///
+    /// Every batch yielded by [`Self::read_stream`] without a projection has this schema.
+    pub fn resolved_schema(&self, coord_type: CoordType) -> Result<SchemaRef> {
+        self.metadata.resolved_schema(coord_type)
+    }
+
+    /// The objects that may contain rows matching the spatial filter of `geo_options`.
+    ///
+    /// A file is skipped when its GeoParquet metadata contains a `bbox` for the primary geometry
+    /// column that does not intersect the filter. Files without a `bbox` are always kept.
+    pub fn pruned_objects(
+        &self,
+        geo_options: &GeoParquetReaderOptions,
+    ) -> Result<Vec<&ObjectMeta>> {
+        let Some(bbox_query) = geo_options.bbox() else {
+            return Ok(self.objects.iter().collect());
+        };
+
+        let mut pruned = Vec::with_capacity(self.objects.len());
+        for object in self.objects.iter() {
+            let arrow_meta = &self.metadata.files()[object.location.as_ref()];
+            let file_meta = GeoParquetReaderMetadata::new(arrow_meta.clone());
+            match file_meta.file_bbox(None)? {
+                Some(file_bbox) if !file_bbox_intersects(file_bbox, bbox_query) => {}
+                _ => pruned.push(object),
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Read the dataset as a single stream of [RecordBatch]es.
+    ///
+    /// Batches are yielded in order of the location of their file. Up to `read_concurrency` files
+    /// (see [`GeoParquetListingOptions::with_read_concurrency`]) are fetched concurrently. Files
+    /// that cannot contain rows matching the spatial filter of `geo_options` are never opened;
+    /// see [`Self::pruned_objects`].
+    ///
+    /// Every batch has the [resolved schema][Self::resolved_schema] of the dataset, projected to
+    /// the columns selected by the projection mask of `geo_options`, if any.
+    pub fn read_stream(
+        &self,
+        geo_options: GeoParquetReaderOptions,
+    ) -> Result<impl Stream<Item = std::result::Result<RecordBatch, ArrowError>> + 'static> {
+        let output_schema = self.metadata.output_schema(&geo_options)?;
+
+        let mut file_streams = vec![];
+        for object in self.pruned_objects(&geo_options)? {
+            let file_meta = self
+                .metadata
+                .file_metadata(object.location.as_ref())
+                .unwrap();
+            let reader = ParquetObjectReader::new(self.store.clone(), object.location.clone())
+                .with_file_size(object.size);
+            let file_stream = GeoParquetRecordBatchStreamBuilder::new_with_metadata_and_options(
+                reader,
+                file_meta,
+                self.metadata
+                    .file_options(object.location.as_ref(), &geo_options)?,
+            )
+            .build()?;
+            file_streams.push(file_stream.read_stream());
+        }
+
+        // The schema-level metadata (e.g. the `geo` key) and the nullability of fields differ
+        // between files, so we replace the schema with that of the dataset.
+        let stream = stream::iter(file_streams)
+            .map(|file_stream| file_stream.try_collect::<Vec<_>>())
+            .buffered(self.read_concurrency)
+            .map_ok(|batches| stream::iter(batches).map(Ok::<_, ArrowError>))
+            .try_flatten()
+            .map(move |batch| {
+                let batch = batch?;
+                RecordBatch::try_new(output_schema.clone(), batch.columns().to_vec())
+            });
+        Ok(stream)
+    }
+}
+
+/// List all objects under `prefix` whose location satisfies `predicate`.
+async fn list_objects(
+    store: &dyn ObjectStore,
+    prefix: Option<&Path>,
+    predicate: impl Fn(&Path) -> bool,
+) -> Result<Vec<ObjectMeta>> {
+    let objects = store
+        .list(prefix)
+        .try_filter(|object| futures::future::ready(predicate(&object.location)))
+        .try_collect::<Vec<_>>()
+        .await
+        .map_err(|err| GeoArrowError::External(Box::new(err)))?;
+    Ok(objects)
+}
+
+/// The directory portion of a glob pattern that precedes its first wildcard.
+fn glob_prefix(pattern: &str) -> Option<&str> {
+    let wildcard_idx = pattern.find(['*', '?']).unwrap_or(pattern.len());
+    let literal = &pattern[..wildcard_idx];
+    let prefix = &literal[..literal.rfind('/').unwrap_or(0)];
+    if prefix.is_empty() {
+        None
+    } else {
+        Some(prefix)
+    }
+}
+
+/// A single element of a glob pattern.
+enum GlobToken {
+    /// A literal byte.
+    Byte(u8),
+    /// `?`, any single byte other than `/`.
+    Any,
+    /// `*`, any sequence of bytes within a single path segment.
+    Star,
+    /// `**` not followed by `/`, any sequence of bytes.
+    GlobStar,
+    /// `**/`, zero or more whole path segments.
+    GlobStarSlash,
+}
+
+fn glob_tokens(pattern: &[u8]) -> Vec<GlobToken> {
+    let mut tokens = vec![];
+    let mut idx = 0;
+    while idx < pattern.len() {
+        let token = match &pattern[idx..] {
+            [b'*', b'*', b'/', ..] => GlobToken::GlobStarSlash,
+            [b'*', b'*', ..] => GlobToken::GlobStar,
+            [b'*', ..] => GlobToken::Star,
+            [b'?', ..] => GlobToken::Any,
+            [c, ..] => GlobToken::Byte(*c),
+            [] => unreachable!(),
+        };
+        idx += match token {
+            GlobToken::GlobStarSlash => 3,
+            GlobToken::GlobStar => 2,
+            _ => 1,
+        };
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Check whether a path matches a glob pattern.
+///
+/// This tracks the set of path offsets reachable after each token of the pattern, so it takes
+/// time proportional to the length of the pattern times the length of the path.
+///
+/// Note that matching is performed on bytes, so `?` matches a single byte of a multi-byte
+/// character.
+fn glob_match(pattern: &[u8], path: &[u8]) -> bool {
+    // reachable[i] is whether the pattern so far matches path[..i]
+    let mut reachable = vec![false; path.len() + 1];
+    reachable[0] = true;
+    for token in glob_tokens(pattern) {
+        let mut next = vec![false; path.len() + 1];
+        match token {
+            GlobToken::Byte(c) => {
+                for idx in 0..path.len() {
+                    next[idx + 1] = reachable[idx] && path[idx] == c;
+                }
+            }
+            GlobToken::Any => {
+                for idx in 0..path.len() {
+                    next[idx + 1] = reachable[idx] && path[idx] != b'/';
+                }
+            }
+            GlobToken::Star => {
+                let mut open = false;
+                for idx in 0..=path.len() {
+                    open |= reachable[idx];
+                    next[idx] = open;
+                    if path.get(idx) == Some(&b'/') {
+                        open = false;
+                    }
+                }
+            }
+            GlobToken::GlobStar => {
+                let mut open = false;
+                for idx in 0..=path.len() {
+                    open |= reachable[idx];
+                    next[idx] = open;
+                }
+            }
+            GlobToken::GlobStarSlash => {
+                let mut open = false;
+                for idx in 0..=path.len() {
+                    next[idx] = reachable[idx] || (open && path[idx - 1] == b'/');
+                    open |= reachable[idx];
+                }
+            }
+        }
+        reachable = next;
+    }
+    reachable[path.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(glob_match(b"*.parquet", b"a.parquet"));
+        assert!(!glob_match(b"*.parquet", b"dir/a.parquet"));
+        assert!(glob_match(b"dir/*.parquet", b"dir/a.parquet"));
+        assert!(glob_match(b"**/*.parquet", b"a.parquet"));
+        assert!(glob_match(b"**/*.parquet", b"x/y/a.parquet"));
+        assert!(glob_match(b"x/**/a.parquet", b"x/a.parquet"));
+        assert!(glob_match(b"x/**/a.parquet", b"x/y/z/a.parquet"));
+        assert!(glob_match(b"year=202?/*.parquet", b"year=2024/a.parquet"));
+        assert!(!glob_match(b"year=202?/*.parquet", b"year=2024/a.json"));
+        assert!(!glob_match(b"x/**/a.parquet", b"xa.parquet"));
+        assert!(glob_match(b"**", b"x/y/a.parquet"));
+
+        // Backtracking on every `*` would take exponential time here
+        let pattern = "a*".repeat(50) + "b";
+        assert!(!glob_match(pattern.as_bytes(), "a".repeat(100).as_bytes()));
+        assert!(glob_match(
+            pattern.as_bytes(),
+            ("a".repeat(100) + "b").as_bytes()
+        ));
+
+        assert_eq!(glob_prefix("x/y/*.parquet"), Some("x/y"));
+        assert_eq!(glob_prefix("x/y*/a.parquet"), Some("x"));
+        assert_eq!(glob_prefix("*.parquet"), None);
+        assert_eq!(glob_prefix("a.parquet"), None);
+    }
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod test_object_store {
+    use super::*;
+    use geoarrow_schema::GeoArrowType;
+    use object_store::PutPayload;
+    use object_store::local::LocalFileSystem;
+    use object_store::memory::InMemory;
+    use parquet::arrow::ProjectionMask;
+
+    use crate::test::fixture_dir;
+
+    async fn nybb_store() -> Arc<dyn ObjectStore> {
+        let store = InMemory::new();
+        let payload = PutPayload::from(
+            std::fs::read(fixture_dir().join("geoparquet/nybb_geoarrow.parquet")).unwrap(),
+        );
+        for path in ["a/part-0.parquet", "a/part-1.parquet", "b/part-0.parquet"] {
+            store.put(&Path::from(path), payload.clone()).await.unwrap();
+        }
+        Arc::new(store)
+    }
+
+    #[tokio::test]
+    async fn read_in_memory_prefix() {
+        let store = nybb_store().await;
+        let dataset = GeoParquetObjectStoreDataset::try_new_from_prefix(
+            store,
+            Some(&Path::from("a")),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(dataset.objects().len(), 2);
+
+        let schema = dataset.resolved_schema(Default::default()).unwrap();
+        let batches = dataset
+            .read_stream(Default::default())
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 10);
+        assert!(batches.iter().all(|b| b.schema() == schema));
+    }
+
+    #[tokio::test]
+    async fn read_in_memory_glob_bbox_pruning() {
+        let store = nybb_store().await;
+        let dataset = GeoParquetObjectStoreDataset::try_new_from_glob(
+            store,
+            "**/part-0.parquet",
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(dataset.objects().len(), 2);
+
+        // Far away from New York
+        let bbox = geo_types::Rect::new(
+            geo_types::coord! { x: 0., y: 0. },
+            geo_types::coord! { x: 1., y: 1. },
+        );
+        let options = GeoParquetReaderOptions::default().with_bbox(bbox, None);
+        assert!(dataset.pruned_objects(&options).unwrap().is_empty());
+        let batches = dataset
+            .read_stream(options)
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert!(batches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_in_memory_merged_schemas() {
+        let store = InMemory::new();
+        for (path, fixture) in [
+            ("native.parquet", "geoparquet/nybb_geoarrow.parquet"),
+            ("wkb.parquet", "geoparquet/nybb_wkb.parquet"),
+        ] {
+            let payload = PutPayload::from(std::fs::read(fixture_dir().join(fixture)).unwrap());
+            store.put(&Path::from(path), payload).await.unwrap();
+        }
+        let store: Arc<dyn ObjectStore> = Arc::new(store);
+
+        // Native and WKB encodings can't be read as one dataset without merging
+        assert!(
+            GeoParquetObjectStoreDataset::try_new_from_prefix(
+                store.clone(),
+                None,
+                Default::default(),
+            )
+            .await
+            .is_err()
+        );
+
+        let dataset = GeoParquetObjectStoreDataset::try_new_from_prefix(
+            store,
+            None,
+            GeoParquetListingOptions::default().with_merge_schemas(true),
+        )
+        .await
+        .unwrap();
+
+        let schema = dataset.resolved_schema(Default::default()).unwrap();
+        let geometry_field = schema.field_with_name("geometry").unwrap();
+        assert!(matches!(
+            GeoArrowType::try_from(geometry_field).unwrap(),
+            GeoArrowType::Geometry(_)
+        ));
+
+        let batches = dataset
+            .read_stream(Default::default())
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 10);
+        assert!(batches.iter().all(|b| b.schema() == schema));
+
+        // Projected-out columns are not added back as nulls
+        let geometry_idx = schema.index_of("geometry").unwrap();
+        let mask = ProjectionMask::roots(
+            &dataset.metadata().parquet_schema().unwrap(),
+            [0, geometry_idx],
+        );
+        let batches = dataset
+            .read_stream(GeoParquetReaderOptions::default().with_projection(mask))
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let projected_schema = Arc::new(schema.project(&[0, geometry_idx]).unwrap());
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 10);
+        assert!(batches.iter().all(|b| b.schema() == projected_schema));
+    }
+
+    #[tokio::test]
+    async fn read_local_file_system() {
+        let store = Arc::new(LocalFileSystem::new_with_prefix(fixture_dir()).unwrap());
+        let dataset = GeoParquetObjectStoreDataset::try_new_from_glob(
+            store,
+            "geoparquet/nybb_geoarrow.parquet",
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        let batches = dataset
+            .read_stream(Default::default())
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 5);
+    }
+}
@@ -1,13 +1,15 @@
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::sync::Arc;
 
-use arrow_schema::SchemaRef;
+use arrow_schema::{Field, Schema, SchemaRef};
 use geoarrow_array::array::RectArray;
 use geoarrow_array::builder::RectBuilder;
 use geoarrow_schema::{BoxType, CoordType, Dimension};
 use parquet::arrow::arrow_reader::ArrowReaderMetadata;
 #[cfg(feature = "async")]
 use parquet::arrow::async_reader::AsyncFileReader;
+use parquet::arrow::{ArrowSchemaConverter, ProjectionMask};
 use parquet::file::metadata::ParquetMetaData;
 use parquet::file::reader::ChunkReader;
 use parquet::schema::types::SchemaDescriptor;
@@ -15,7 +17,8 @@ use serde_json::Value;
 
 #[cfg(feature = "async")]
 use crate::GeoParquetRecordBatchStreamBuilder;
-use crate::metadata::{GeoParquetBboxCovering, GeoParquetMetadata};
+use crate::metadata::{GeoParquetBboxCovering, GeoParquetColumnConflict, GeoParquetMetadata};
+use crate::reader::options::projected_roots;
 use crate::reader::parse::infer_target_schema;
 use crate::reader::spatial_filter::ParquetBboxStatistics;
 use crate::{GeoParquetReaderOptions, GeoParquetRecordBatchReaderBuilder};
@@ -251,6 +254,9 @@ pub struct GeoParquetDatasetMetadata {
     files: HashMap<String, ArrowReaderMetadata>,
     geo_meta: Option<Arc<GeoParquetMetadata>>,
     schema: SchemaRef,
+    /// Whether the files were merged with [`Self::from_files_merged`], in which case each file
+    /// must be cast to the resolved schema of the dataset.
+    merged: bool,
 }
 
 impl GeoParquetDatasetMetadata {
@@ -282,6 +288,109 @@ impl GeoParquetDatasetMetadata {
             files: metas,
             schema: schema.unwrap(),
             geo_meta: geo_meta.map(Arc::new),
+            merged: false,
+        })
+    }
+
+    /// Construct dataset metadata from files whose schemas may differ.
+    ///
+    /// In contrast to [`Self::from_files`], columns are unioned by name across all files:
+    ///
+    /// - Columns missing from some files are filled with nulls when read.
+    /// - Geometry types are widened across files as described in
+    ///   [`GeoParquetMetadata::try_merge`], e.g. `Point` and `MultiPoint` become `MultiPoint` and
+    ///   a mix of native and WKB encodings becomes `Geometry`.
+    ///
+    /// If the files can't be merged, e.g. because a column has different data types or a geometry
+    /// column has different CRS in different files, an error wrapping a [`GeoParquetMergeReport`]
+    /// listing every incompatibility is returned.
+    pub fn from_files_merged(metas: HashMap<String, ArrowReaderMetadata>) -> Result<Self> {
+        if metas.is_empty() {
+            return Err(GeoArrowError::General("No files provided".to_string()));
+        }
+
+        // Merge in a deterministic order, so that the first file defines the column order
+        let mut paths = metas.keys().cloned().collect::<Vec<_>>();
+        paths.sort();
+
+        let mut report = GeoParquetMergeReport::default();
+
+        let mut geo_meta: Option<GeoParquetMetadata> = None;
+        for path in paths.iter() {
+            let file_geo_meta =
+                GeoParquetMetadata::from_parquet_meta(metas[path].metadata().file_metadata())
+                    .map_err(|err| {
+                        GeoArrowError::General(format!(
+                            "Invalid GeoParquet metadata in file {path}: {err}"
+                        ))
+                    })?;
+            if let Some(geo_meta) = geo_meta.as_mut() {
+                if let Err(conflicts) = geo_meta.try_merge(&file_geo_meta) {
+                    report.extend(path, conflicts);
+                }
+            } else {
+                geo_meta = Some(file_geo_meta);
+            }
+        }
+
+        let mut fields: Vec<Field> = vec![];
+        let mut field_counts: Vec<usize> = vec![];
+        for path in paths.iter() {
+            for file_field in metas[path].schema().fields() {
+                let is_geometry_column = geo_meta
+                    .as_ref()
+                    .is_some_and(|geo_meta| geo_meta.columns.contains_key(file_field.name()));
+                let existing_idx = fields
+                    .iter()
+                    .position(|field| field.name() == file_field.name());
+                if let Some(existing_idx) = existing_idx {
+                    field_counts[existing_idx] += 1;
+                    let existing_field = &mut fields[existing_idx];
+                    // The storage type of geometry columns is allowed to differ between files, as
+                    // it's resolved from the GeoParquet metadata
+                    if !is_geometry_column && existing_field.data_type() != file_field.data_type() {
+                        report.push(
+                            path,
+                            GeoParquetColumnConflict::new(
+                                file_field.name(),
+                                format!(
+                                    "data type {} does not match data type {} of previous files",
+                                    file_field.data_type(),
+                                    existing_field.data_type()
+                                ),
+                            ),
+                        );
+                    }
+                    existing_field
+                        .set_nullable(existing_field.is_nullable() || file_field.is_nullable());
+                } else {
+                    fields.push(file_field.as_ref().clone());
+                    field_counts.push(1);
+                }
+            }
+        }
+
+        if !report.conflicts.is_empty() {
+            return Err(GeoArrowError::External(Box::new(report)));
+        }
+
+        // Columns that are not present in every file will be filled with nulls
+        for (field, count) in fields.iter_mut().zip(field_counts) {
+            if count < metas.len() {
+                field.set_nullable(true);
+            }
+        }
+
+        let mut schema_metadata = metas[&paths[0]].schema().metadata().clone();
+        if let Some(geo_meta) = geo_meta.as_ref() {
+            schema_metadata.insert("geo".to_string(), serde_json::to_string(geo_meta)?);
+        }
+
+        Ok(Self {
+            files: metas,
+            schema: Arc::new(Schema::new_with_metadata(fields, schema_metadata)),
+            geo_meta: geo_meta.map(Arc::new),
+            merged: true,
         })
     }
 
@@ -290,6 +399,84 @@ impl GeoParquetDatasetMetadata {
         self.geo_meta.as_ref()
     }
 
+    /// Access the [ArrowReaderMetadata] of each file in this dataset, keyed by path.
+    pub(crate) fn files(&self) -> &HashMap<String, ArrowReaderMetadata> {
+        &self.files
+    }
+
+    /// Construct the [GeoParquetReaderMetadata] of a single file in this dataset.
+    ///
+    /// Unless the dataset was merged, the returned metadata carries the geo metadata of the
+    /// _dataset_ rather than that of the individual file, so that every file resolves to the same
+    /// output schema. Files of merged datasets are parsed according to their own geo metadata and
+    /// then cast to the resolved schema; see [`Self::file_options`].
+    pub(crate) fn file_metadata(&self, path: &str) -> Option<GeoParquetReaderMetadata> {
+        self.files.get(path).map(|arrow_meta| {
+            if self.merged {
+                GeoParquetReaderMetadata::new(arrow_meta.clone())
+            } else {
+                GeoParquetReaderMetadata {
+                    meta: arrow_meta.clone(),
+                    geo_meta: self.geo_meta.clone(),
+                }
+            }
+        })
+    }
+
+    /// The resolved schema, projected to the columns selected by the projection mask of
+    /// `geo_options`.
+    pub(crate) fn output_schema(&self, geo_options: &GeoParquetReaderOptions) -> Result<SchemaRef> {
+        let schema = self.resolved_schema(geo_options.coord_type)?;
+        let Some(mask) = geo_options.projection() else {
+            return Ok(schema);
+        };
+        let roots = projected_roots(mask, &self.parquet_schema()?);
+        Ok(Arc::new(schema.project(&roots)?))
+    }
+
+    /// The Parquet schema of this dataset, to which projection masks refer.
+    ///
+    /// For merged datasets, this is converted from the merged Arrow schema of all files.
+    pub fn parquet_schema(&self) -> Result<SchemaDescriptor> {
+        ArrowSchemaConverter::new()
+            .convert(&self.schema)
+            .map_err(|err| GeoArrowError::External(Box::new(err)))
+    }
+
+    /// The reader options to use for the file at `path` in this dataset.
+    ///
+    /// For merged datasets, this casts each file to the resolved schema of the dataset. The
+    /// projection mask, which refers to the merged schema, is translated to the columns of the
+    /// file by name, and the resolved schema is projected to the same columns.
+    pub(crate) fn file_options(
+        &self,
+        path: &str,
+        geo_options: &GeoParquetReaderOptions,
+    ) -> Result<GeoParquetReaderOptions> {
+        if !self.merged {
+            return Ok(geo_options.clone());
+        }
+
+        let target_schema = self.output_schema(geo_options)?;
+        if geo_options.projection().is_none() {
+            return Ok(geo_options.clone().with_target_schema(target_schema));
+        }
+
+        let file_meta = &self.files[path];
+        let file_roots = file_meta
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| target_schema.field_with_name(field.name()).is_ok())
+            .map(|(idx, _)| idx);
+        let file_mask = ProjectionMask::roots(file_meta.parquet_schema(), file_roots);
+        Ok(geo_options
+            .clone()
+            .with_projection(file_mask)
+            .with_target_schema(target_schema))
+    }
+
     /// The total number of rows across all files.
     pub fn num_rows(&self) -> usize {
         self.files
@@ -367,22 +554,21 @@ impl GeoParquetDatasetMetadata {
         &self,
         reader_cb: F,
         geo_options: GeoParquetReaderOptions,
-    ) -> Vec<GeoParquetRecordBatchStreamBuilder<T>>
+    ) -> Result<Vec<GeoParquetRecordBatchStreamBuilder<T>>>
     where
         F: Fn(&str) -> T,
     {
         self.files
-            .iter()
-            .map(|(path, arrow_meta)| {
+            .keys()
+            .map(|path| {
                 let reader = reader_cb(path);
-                let file_metadata = GeoParquetReaderMetadata {
-                    meta: arrow_meta.clone(),
-                    geo_meta: self.geo_meta.clone(),
-                };
-                GeoParquetRecordBatchStreamBuilder::new_with_metadata_and_options(
-                    reader,
-                    file_metadata,
-                    geo_options.clone(),
+                let file_metadata = self.file_metadata(path).unwrap();
+                Ok(
+                    GeoParquetRecordBatchStreamBuilder::new_with_metadata_and_options(
+                        reader,
+                        file_metadata,
+                        self.file_options(path, &geo_options)?,
+                    ),
                 )
             })
             .collect()
@@ -394,24 +580,57 @@ impl GeoParquetDatasetMetadata {
         &self,
         reader_cb: F,
         geo_options: GeoParquetReaderOptions,
-    ) -> Vec<GeoParquetRecordBatchReaderBuilder<T>>
+    ) -> Result<Vec<GeoParquetRecordBatchReaderBuilder<T>>>
     where
         F: Fn(&str) -> T,
     {
         self.files
-            .iter()
-            .map(|(path, arrow_meta)| {
+            .keys()
+            .map(|path| {
                 let reader = reader_cb(path);
-                let file_metadata = GeoParquetReaderMetadata {
-                    meta: arrow_meta.clone(),
-                    geo_meta: self.geo_meta.clone(),
-                };
-                GeoParquetRecordBatchReaderBuilder::new_with_metadata_and_options(
-                    reader,
-                    file_metadata,
-                    geo_options.clone(),
+                let file_metadata = self.file_metadata(path).unwrap();
+                Ok(
+                    GeoParquetRecordBatchReaderBuilder::new_with_metadata_and_options(
+                        reader,
+                        file_metadata,
+                        self.file_options(path, &geo_options)?,
+                    ),
                 )
             })
             .collect()
     }
 }
+
+/// The reasons why a collection of GeoParquet files could not be merged into one dataset.
+///
+/// This is returned, wrapped in [`GeoArrowError::External`], by
+/// [`GeoParquetDatasetMetadata::from_files_merged`].
+#[derive(Debug, Clone, Default)]
+pub struct GeoParquetMergeReport {
+    /// Each incompatibility, together with the path of the file in which it was found.
+    pub conflicts: Vec<(String, GeoParquetColumnConflict)>,
+}
+
+impl GeoParquetMergeReport {
+    fn push(&mut self, path: &str, conflict: GeoParquetColumnConflict) {
+        self.conflicts.push((path.to_string(), conflict));
+    }
+
+    fn extend(&mut self, path: &str, conflicts: Vec<GeoParquetColumnConflict>) {
+        for conflict in conflicts {
+            self.push(path, conflict);
+        }
+    }
+}
+
+impl Display for GeoParquetMergeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Unable to merge GeoParquet files:")?;
+        for (path, conflict) in self.conflicts.iter() {
+            writeln!(f, "  {path}: {conflict}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for GeoParquetMergeReport {}
@@ -1,6 +1,8 @@
 #[cfg(feature = "async")]
 mod r#async;
 mod builder;
+#[cfg(feature = "object_store")]
+mod dataset;
 mod metadata;
 mod options;
 mod parse;
@@ -9,5 +11,7 @@ mod spatial_filter;
 #[cfg(feature = "async")]
 pub use r#async::{GeoParquetRecordBatchStream, GeoParquetRecordBatchStreamBuilder};
 pub use builder::{GeoParquetRecordBatchReader, GeoParquetRecordBatchReaderBuilder};
-pub use metadata::{GeoParquetDatasetMetadata, GeoParquetReaderMetadata};
+#[cfg(feature = "object_store")]
+pub use dataset::{GeoParquetListingOptions, GeoParquetObjectStoreDataset};
+pub use metadata::{GeoParquetDatasetMetadata, GeoParquetMergeReport, GeoParquetReaderMetadata};
 pub use options::GeoParquetReaderOptions;
@@ -1,8 +1,14 @@
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef;
 use geo_types::Rect;
 use geoarrow_array::error::{GeoArrowError, Result};
 use geoarrow_schema::CoordType;
 use parquet::arrow::ProjectionMask;
 use parquet::arrow::arrow_reader::ArrowReaderBuilder;
+use parquet::bloom_filter::Sbbf;
+use parquet::data_type::AsBytes;
+use parquet::schema::types::SchemaDescriptor;
 
 use crate::metadata::{GeoParquetBboxCovering, GeoParquetMetadata};
 use crate::reader::spatial_filter::{
@@ -41,6 +47,15 @@ pub struct GeoParquetReaderOptions {
     /// The paths in the Parquet schema to the bounding box columns. This will not be necessary as
     /// of GeoParquet 1.1.
     bbox_paths: Option<GeoParquetBboxCovering>,
+
+    /// If set, each parsed batch is cast to this schema, matching columns by name.
+    ///
+    /// This is used when reading a dataset whose files have different schemas.
+    pub(crate) target_schema: Option<SchemaRef>,
+
+    /// Pairs of column path and plain-encoded value that row groups must be able to contain,
+    /// according to their bloom filters.
+    bloom_filters: Vec<(String, Vec<u8>)>,
 }
 
 impl Default for GeoParquetReaderOptions {
@@ -54,6 +69,8 @@ impl Default for GeoParquetReaderOptions {
             coord_type: CoordType::Separated,
             bbox: None,
             bbox_paths: None,
+            target_schema: None,
+            bloom_filters: vec![],
         }
     }
 }
@@ -80,6 +97,9 @@ impl GeoParquetReaderOptions {
     }
 
     /// Only read data from the provided column indexes
+    ///
+    /// When reading a dataset whose files have different schemas, the mask refers to the leaves
+    /// of the merged schema of the dataset, and every column with a selected leaf is read in full.
     pub fn with_projection(self, mask: ProjectionMask) -> Self {
         Self {
             mask: Some(mask),
@@ -122,6 +142,13 @@ impl GeoParquetReaderOptions {
 
     /// Set the bounding box for reading with a spatial filter
     ///
+    /// Row groups whose statistics don't intersect the bounding box are skipped entirely. If the
+    /// Parquet page index was loaded (see [`ArrowReaderOptions::with_page_index`]), individual
+    /// pages that don't intersect the bounding box are skipped as well, so that files with few,
+    /// large row groups that are spatially sorted can still be pruned at a finer granularity.
+    /// Remaining rows are then filtered exactly by their bounding box.
+    ///
+    /// [`ArrowReaderOptions::with_page_index`]: parquet::arrow::arrow_reader::ArrowReaderOptions::with_page_index
     pub fn with_bbox(
         self,
         bbox: geo_types::Rect,
@@ -134,6 +161,94 @@ impl GeoParquetReaderOptions {
         }
     }
 
+    /// Skip row groups whose Parquet bloom filter for `column` rules out `value`.
+    ///
+    /// `column` is the dot-separated path of a leaf column, e.g. `"id"` or `"bbox.xmin"`, and
+    /// `value` must have the physical type of that column, e.g. `&str` for a string column or
+    /// `i64` for an `Int64` column. Row groups without a bloom filter for the column are always
+    /// read. This can be called multiple times, in which case a row group is skipped if any of
+    /// the bloom filters rules out its value.
+    ///
+    /// Note that bloom filters only prune whole row groups; rows that don't match `value` are
+    /// not filtered out.
+    pub fn with_bloom_filter<V: AsBytes + ?Sized>(
+        mut self,
+        column: impl Into<String>,
+        value: &V,
+    ) -> Self {
+        self.bloom_filters
+            .push((column.into(), value.as_bytes().to_vec()));
+        self
+    }
+
+    /// Cast each batch to the provided schema, matching columns by name.
+    ///
+    /// Columns missing from the file are filled with nulls, and geometry columns are cast to the
+    /// geometry type of the target field, e.g. `Point` to `MultiPoint`. This is set automatically
+    /// for datasets constructed with [`GeoParquetDatasetMetadata::from_files_merged`].
+    ///
+    /// [`GeoParquetDatasetMetadata::from_files_merged`]: crate::GeoParquetDatasetMetadata::from_files_merged
+    pub fn with_target_schema(self, target_schema: SchemaRef) -> Self {
+        Self {
+            target_schema: Some(target_schema),
+            ..self
+        }
+    }
+
+    /// The bounding box used for spatial filtering, if any.
+    pub(crate) fn bbox(&self) -> Option<&Rect> {
+        self.bbox.as_ref()
+    }
+
+    /// The projection mask, if any.
+    pub(crate) fn projection(&self) -> Option<&ProjectionMask> {
+        self.mask.as_ref()
+    }
+
+    /// The bloom filter values to check, as pairs of leaf column index and plain-encoded value.
+    pub(crate) fn bloom_filter_leaves(
+        &self,
+        parquet_schema: &SchemaDescriptor,
+    ) -> Result<Vec<(usize, Vec<u8>)>> {
+        self.bloom_filters
+            .iter()
+            .map(|(column, value)| {
+                let leaf_idx = parquet_schema
+                    .columns()
+                    .iter()
+                    .position(|leaf| leaf.path().string() == *column)
+                    .ok_or_else(|| {
+                        GeoArrowError::General(format!(
+                            "Bloom filter column {column} not found in Parquet schema"
+                        ))
+                    })?;
+                Ok((leaf_idx, value.clone()))
+            })
+            .collect()
+    }
+
+    /// The row groups to read, before any pruning.
+    pub(crate) fn row_group_candidates(&self, num_row_groups: usize) -> Vec<usize> {
+        self.row_groups
+            .clone()
+            .unwrap_or_else(|| (0..num_row_groups).collect())
+    }
+
+    /// Project `schema`, whose fields are the root columns of `parquet_schema`, to the columns
+    /// selected by the projection mask.
+    pub(crate) fn project_schema(
+        &self,
+        schema: SchemaRef,
+        parquet_schema: &SchemaDescriptor,
+    ) -> Result<SchemaRef> {
+        match &self.mask {
+            Some(mask) => Ok(Arc::new(
+                schema.project(&projected_roots(mask, parquet_schema))?,
+            )),
+            None => Ok(schema),
+        }
+    }
+
     /// Apply these settings to an [ArrowReaderBuilder]
     pub(crate) fn apply_to_builder<T>(
         self,
@@ -144,10 +259,6 @@ impl GeoParquetReaderOptions {
             builder = builder.with_batch_size(batch_size);
         }
 
-        if let Some(row_groups) = self.row_groups {
-            builder = builder.with_row_groups(row_groups);
-        }
-
         if let Some(limit) = self.limit {
             builder = builder.with_limit(limit);
         }
@@ -173,10 +284,47 @@ impl GeoParquetReaderOptions {
             };
 
             let bbox_cols = ParquetBboxStatistics::try_new(builder.parquet_schema(), &bbox_paths)?;
-            builder = apply_bbox_row_groups(builder, &bbox_cols, bbox)?;
+            builder = apply_bbox_row_groups(builder, &bbox_cols, bbox, self.row_groups)?;
             builder = apply_bbox_row_filter(builder, bbox_cols, bbox)?;
+        } else if let Some(row_groups) = self.row_groups {
+            builder = builder.with_row_groups(row_groups);
         }
 
         Ok(builder)
     }
 }
+
+/// The indices of the root columns of `parquet_schema` with at least one leaf selected by `mask`.
+pub(crate) fn projected_roots(
+    mask: &ProjectionMask,
+    parquet_schema: &SchemaDescriptor,
+) -> Vec<usize> {
+    let mut roots = (0..parquet_schema.num_columns())
+        .filter(|leaf_idx| mask.leaf_included(*leaf_idx))
+        .map(|leaf_idx| parquet_schema.get_column_root_idx(leaf_idx))
+        .collect::<Vec<_>>();
+    roots.dedup();
+    roots
+}
+
+/// Keep the row groups whose bloom filters may contain every value.
+///
+/// `bloom_filter` returns the bloom filter of a leaf column in a row group, if any.
+pub(crate) fn prune_with_bloom_filters(
+    row_groups: Vec<usize>,
+    leaves: &[(usize, Vec<u8>)],
+    mut bloom_filter: impl FnMut(usize, usize) -> Result<Option<Sbbf>>,
+) -> Result<Vec<usize>> {
+    let mut pruned = Vec::with_capacity(row_groups.len());
+    'row_groups: for row_group_idx in row_groups {
+        for (leaf_idx, value) in leaves {
+            if bloom_filter(row_group_idx, *leaf_idx)?
+                .is_some_and(|bloom_filter| !bloom_filter.check(value.as_slice()))
+            {
+                continue 'row_groups;
+            }
+        }
+        pruned.push(row_group_idx);
+    }
+    Ok(pruned)
+}
@@ -3,14 +3,15 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
-use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_array::{Array, ArrayRef, RecordBatch, new_null_array};
 use arrow_schema::{DataType, Field, FieldRef, Schema, SchemaRef};
 use geoarrow_array::array::{
     LineStringArray, MultiLineStringArray, MultiPointArray, MultiPolygonArray, PointArray,
-    PolygonArray, WkbArray,
+    PolygonArray, WkbArray, from_arrow_array,
 };
 use geoarrow_array::cast::from_wkb;
 use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geoarrow_cast::cast::cast;
 use geoarrow_schema::{
     CoordType, GeometryType, LineStringType, Metadata, MultiLineStringType, MultiPointType,
     MultiPolygonType, PointType, PolygonType, WkbType,
@@ -56,11 +57,12 @@ fn infer_target_field(
             infer_target_wkb_type(&column_meta.geometry_types, coord_type, metadata)?
         }
         // For native encodings there should only be one geometry type
-        _ => {
-            assert_eq!(column_meta.geometry_types.len(), 1);
+        _ if column_meta.geometry_types.len() == 1 => {
             let gpq_type = column_meta.geometry_types.iter().next().unwrap();
             gpq_type.to_data_type(coord_type, metadata)
         }
+        // Unless the geometry types are unknown
+        _ => infer_target_wkb_type(&column_meta.geometry_types, coord_type, metadata)?,
     };
 
     Ok(Arc::new(target_geo_data_type.to_field(
@@ -110,6 +112,60 @@ pub fn parse_record_batch(batch: RecordBatch, target_schema: SchemaRef) -> Resul
     Ok(RecordBatch::try_new(target_schema, output_columns)?)
 }
 
+/// Parse a record batch to a GeoArrow record batch, then cast it to the target schema if
+/// provided.
+pub fn parse_and_cast_record_batch(
+    batch: RecordBatch,
+    output_schema: SchemaRef,
+    target_schema: Option<SchemaRef>,
+) -> Result<RecordBatch> {
+    let batch = parse_record_batch(batch, output_schema)?;
+    if let Some(target_schema) = target_schema {
+        cast_record_batch(&batch, target_schema)
+    } else {
+        Ok(batch)
+    }
+}
+
+/// Cast a parsed GeoArrow record batch to a target schema, matching columns by name.
+///
+/// This is used when reading a dataset whose files have different schemas. Columns of the target
+/// schema that are missing from `batch` are filled with nulls, and geometry columns are cast to
+/// the geometry type of the target field, e.g. `Point` to `MultiPoint`.
+pub fn cast_record_batch(batch: &RecordBatch, target_schema: SchemaRef) -> Result<RecordBatch> {
+    let mut output_columns = Vec::with_capacity(target_schema.fields().len());
+    for target_field in target_schema.fields() {
+        let Some((column_idx, field)) = batch.schema_ref().column_with_name(target_field.name())
+        else {
+            output_columns.push(new_null_array(target_field.data_type(), batch.num_rows()));
+            continue;
+        };
+
+        let column = batch.column(column_idx);
+        if field.data_type() == target_field.data_type()
+            && field.metadata() == target_field.metadata()
+        {
+            output_columns.push(column.clone());
+        } else if target_field
+            .extension_type_name()
+            .is_some_and(|name| name.starts_with("geoarrow"))
+        {
+            let target_type = GeoArrowType::try_from(target_field.as_ref())?;
+            let geo_arr = from_arrow_array(column, field)?;
+            output_columns.push(cast(geo_arr.as_ref(), &target_type)?.to_array_ref());
+        } else {
+            return Err(GeoArrowError::General(format!(
+                "Cannot cast column {} from {} to {}",
+                field.name(),
+                field.data_type(),
+                target_field.data_type()
+            )));
+        }
+    }
+
+    Ok(RecordBatch::try_new(target_schema, output_columns)?)
+}
+
 /// Parse a single column based on provided GeoParquet metadata and target field
 fn parse_array(array: ArrayRef, orig_field: &Field, target_field: &Field) -> Result<ArrayRef> {
     let target_type = GeoArrowType::try_from(target_field)?;
@@ -15,9 +15,11 @@ use geoarrow_array::error::{GeoArrowError, Result};
 use geoarrow_schema::{BoxType, Dimension};
 use parquet::arrow::ProjectionMask;
 use parquet::arrow::arrow_reader::{
-    ArrowPredicate, ArrowPredicateFn, ArrowReaderBuilder, RowFilter,
+    ArrowPredicate, ArrowPredicateFn, ArrowReaderBuilder, RowFilter, RowSelection, RowSelector,
 };
-use parquet::file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData, RowGroupMetaData};
+use parquet::file::page_index::index::Index;
+use parquet::file::page_index::offset_index::OffsetIndexMetaData;
 use parquet::file::statistics::Statistics;
 use parquet::schema::types::{ColumnPath, SchemaDescriptor};
 
@@ -164,19 +166,152 @@ pub(crate) fn apply_bbox_row_groups<T>(
     builder: ArrowReaderBuilder<T>,
     bbox_cols: &ParquetBboxStatistics,
     bbox_query: Rect,
+    row_groups: Option<Vec<usize>>,
 ) -> Result<ArrowReaderBuilder<T>> {
-    let row_groups = builder.metadata().row_groups();
-    let row_groups_bounds = bbox_cols.get_bboxes(row_groups)?;
+    let metadata = builder.metadata().clone();
+    let row_groups_bounds = bbox_cols.get_bboxes(metadata.row_groups())?;
     let mut intersects_row_groups_idxs = vec![];
     for (row_group_idx, row_group_bounds) in row_groups_bounds.iter_values().enumerate() {
+        if row_groups
+            .as_ref()
+            .is_some_and(|row_groups| !row_groups.contains(&row_group_idx))
+        {
+            continue;
+        }
+
         if rect_intersects(&row_group_bounds?, &bbox_query) {
             intersects_row_groups_idxs.push(row_group_idx);
         }
     }
 
+    let mut builder = builder;
+    if let Some(selection) = bbox_page_selection(
+        &metadata,
+        bbox_cols,
+        &bbox_query,
+        &intersects_row_groups_idxs,
+    ) {
+        builder = builder.with_row_selection(selection);
+    }
+
     Ok(builder.with_row_groups(intersects_row_groups_idxs))
 }
 
+/// Construct a [RowSelection] that skips the pages of the given row groups whose statistics in
+/// the page index don't intersect the query bounding box.
+///
+/// Returns `None` if the page index was not loaded; see
+/// [`ArrowReaderOptions::with_page_index`][parquet::arrow::arrow_reader::ArrowReaderOptions::with_page_index].
+pub(crate) fn bbox_page_selection(
+    metadata: &ParquetMetaData,
+    bbox_cols: &ParquetBboxStatistics,
+    bbox_query: &Rect,
+    row_groups: &[usize],
+) -> Option<RowSelection> {
+    let column_index = metadata.column_index()?;
+    let offset_index = metadata.offset_index()?;
+
+    let mut selectors: Vec<RowSelector> = vec![];
+    for row_group_idx in row_groups {
+        let num_rows = metadata.row_group(*row_group_idx).num_rows() as usize;
+        let column_index = &column_index[*row_group_idx];
+        let offset_index = &offset_index[*row_group_idx];
+
+        // Pages can't be pruned if any of the columns in this row group lacks a page index
+        if column_index.is_empty() || offset_index.is_empty() {
+            selectors.push(RowSelector::select(num_rows));
+            continue;
+        }
+
+        // The pages of each column are aligned differently, so we compute a selection per column
+        // and intersect them. In the native encoding case the same column is used for both the
+        // min and the max, which is equivalent to checking both bounds per page.
+        let column_selections = [
+            column_page_selection(
+                &column_index[bbox_cols.minx_col],
+                &offset_index[bbox_cols.minx_col],
+                num_rows,
+                |page_min, _| page_min <= bbox_query.max().x,
+            ),
+            column_page_selection(
+                &column_index[bbox_cols.miny_col],
+                &offset_index[bbox_cols.miny_col],
+                num_rows,
+                |page_min, _| page_min <= bbox_query.max().y,
+            ),
+            column_page_selection(
+                &column_index[bbox_cols.maxx_col],
+                &offset_index[bbox_cols.maxx_col],
+                num_rows,
+                |_, page_max| page_max >= bbox_query.min().x,
+            ),
+            column_page_selection(
+                &column_index[bbox_cols.maxy_col],
+                &offset_index[bbox_cols.maxy_col],
+                num_rows,
+                |_, page_max| page_max >= bbox_query.min().y,
+            ),
+        ];
+        let row_group_selection = column_selections
+            .into_iter()
+            .reduce(|left, right| left.intersection(&right))
+            .unwrap();
+        selectors.extend(Vec::<RowSelector>::from(row_group_selection));
+    }
+
+    Some(RowSelection::from(selectors))
+}
+
+/// Construct a [RowSelection] over a single column chunk, selecting the rows of every page for
+/// which `page_may_match` returns `true` given the page's min and max values.
+///
+/// Pages without statistics are always selected.
+fn column_page_selection(
+    index: &Index,
+    offset_index: &OffsetIndexMetaData,
+    num_rows: usize,
+    page_may_match: impl Fn(f64, f64) -> bool,
+) -> RowSelection {
+    let page_bounds = match index {
+        Index::DOUBLE(native_index) => native_index
+            .indexes
+            .iter()
+            .map(|page| page.min.zip(page.max))
+            .collect::<Vec<_>>(),
+        Index::FLOAT(native_index) => native_index
+            .indexes
+            .iter()
+            .map(|page| {
+                page.min
+                    .zip(page.max)
+                    .map(|(min, max)| (min as f64, max as f64))
+            })
+            .collect::<Vec<_>>(),
+        _ => return RowSelection::from(vec![RowSelector::select(num_rows)]),
+    };
+
+    let page_locations = offset_index.page_locations();
+    if page_locations.len() != page_bounds.len() {
+        return RowSelection::from(vec![RowSelector::select(num_rows)]);
+    }
+
+    let ranges = page_locations
+        .iter()
+        .enumerate()
+        .filter(|(page_idx, _)| {
+            page_bounds[*page_idx].is_none_or(|(min, max)| page_may_match(min, max))
+        })
+        .map(|(page_idx, page_location)| {
+            let start = page_location.first_row_index as usize;
+            let end = page_locations
+                .get(page_idx + 1)
+                .map(|next_page| next_page.first_row_index as usize)
+                .unwrap_or(num_rows);
+            start..end
+        });
+    RowSelection::from_consecutive_ranges(ranges, num_rows)
+}
+
 pub(crate) fn apply_bbox_row_filter<T>(
     builder: ArrowReaderBuilder<T>,
     bbox_cols: ParquetBboxStatistics,
@@ -418,6 +553,25 @@ fn parse_statistics_f64(column_meta: &ColumnChunkMetaData) -> Result<(f64, f64)>
     }
 }
 
+/// Check whether a GeoParquet file-level `bbox`, formatted according to RFC 7946, section 5,
+/// intersects the query bounding box.
+///
+/// Returns `true` for bounding boxes of unexpected length, so that the file is not pruned.
+pub(crate) fn file_bbox_intersects(file_bbox: &[f64], bbox_query: &Rect) -> bool {
+    let file_rect = match file_bbox.len() {
+        4 => Rect::new(
+            coord! { x: file_bbox[0], y: file_bbox[1] },
+            coord! { x: file_bbox[2], y: file_bbox[3] },
+        ),
+        6 => Rect::new(
+            coord! { x: file_bbox[0], y: file_bbox[1] },
+            coord! { x: file_bbox[3], y: file_bbox[4] },
+        ),
+        _ => return true,
+    };
+    rect_intersects(&file_rect, bbox_query)
+}
+
 /// Check whether two [RectTrait] intersect.
 fn rect_intersects<T: CoordNum>(a: &impl RectTrait<T = T>, b: &impl RectTrait<T = T>) -> bool {
     if a.max().x() < b.min().x() {
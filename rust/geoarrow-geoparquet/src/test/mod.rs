@@ -16,3 +16,43 @@ pub(crate) fn geoarrow_data_example_files() -> PathBuf {
 pub(crate) fn geoarrow_data_example_crs_files() -> PathBuf {
     fixture_dir().join("geoarrow-data/example-crs/files")
 }
+
+/// Write 100 points sorted by x, with a string `id` column, to a native GeoParquet file.
+#[cfg(feature = "compression")]
+pub(crate) fn write_sorted_points(
+    path: &std::path::Path,
+    writer_properties: parquet::file::properties::WriterProperties,
+) {
+    use std::sync::Arc;
+
+    use arrow_array::{RecordBatch, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use geoarrow_array::GeoArrowArray;
+    use geoarrow_array::builder::PointBuilder;
+    use geoarrow_schema::{CoordType, Dimension, PointType};
+
+    use crate::{GeoParquetWriter, GeoParquetWriterEncoding, GeoParquetWriterOptions};
+
+    let points = (0..100)
+        .map(|i| geo_types::point!(x: i as f64, y: 0.))
+        .collect::<Vec<_>>();
+    let typ = PointType::new(CoordType::Separated, Dimension::XY, Default::default());
+    let array = PointBuilder::from_points(points.iter(), typ).finish();
+    let ids = StringArray::from_iter_values((0..100).map(|i| i.to_string()));
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        array.data_type().to_field("geometry", true),
+    ]);
+    let batch =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(ids), array.to_array_ref()]).unwrap();
+
+    let options = GeoParquetWriterOptions {
+        encoding: GeoParquetWriterEncoding::Native,
+        writer_properties: Some(writer_properties),
+        ..Default::default()
+    };
+    let file = std::fs::File::create(path).unwrap();
+    let mut writer = GeoParquetWriter::try_new(file, &batch.schema(), &options).unwrap();
+    writer.write_batch(&batch).unwrap();
+    writer.finish().unwrap();
+}
@@ -1,3 +1,4 @@
+use crate::writer::buffer::InferenceBuffer;
 use crate::writer::encode::encode_record_batch;
 use crate::writer::metadata::GeoParquetMetadataBuilder;
 use crate::writer::options::GeoParquetWriterOptions;
@@ -7,6 +8,7 @@ use geoarrow_array::error::{GeoArrowError, Result};
 use parquet::arrow::AsyncArrowWriter;
 use parquet::arrow::async_writer::AsyncFileWriter;
 use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
 
 /// Write a [RecordBatchReader] to GeoParquet.
 pub async fn write_geoparquet_async<W: AsyncFileWriter>(
@@ -26,8 +28,12 @@ pub async fn write_geoparquet_async<W: AsyncFileWriter>(
 
 /// An asynchronous GeoParquet file writer
 pub struct GeoParquetWriterAsync<W: AsyncFileWriter> {
-    writer: AsyncArrowWriter<W>,
+    writer: Option<AsyncArrowWriter<W>>,
     metadata_builder: GeoParquetMetadataBuilder,
+    /// The output and the buffered batches, while the native types of geometry columns are
+    /// still being inferred.
+    pending: Option<(W, InferenceBuffer)>,
+    writer_properties: Option<WriterProperties>,
 }
 
 impl<W: AsyncFileWriter> GeoParquetWriterAsync<W> {
@@ -35,6 +41,15 @@ impl<W: AsyncFileWriter> GeoParquetWriterAsync<W> {
     pub fn try_new(writer: W, schema: &Schema, options: &GeoParquetWriterOptions) -> Result<Self> {
         let metadata_builder = GeoParquetMetadataBuilder::try_new(schema, options)?;
 
+        if metadata_builder.pending_inference() {
+            return Ok(Self {
+                writer: None,
+                metadata_builder,
+                pending: Some((writer, InferenceBuffer::new(options.type_inference))),
+                writer_properties: options.writer_properties.clone(),
+            });
+        }
+
         let writer = AsyncArrowWriter::try_new(
             writer,
             metadata_builder.output_schema.clone(),
@@ -43,15 +58,58 @@ impl<W: AsyncFileWriter> GeoParquetWriterAsync<W> {
         .map_err(|err| GeoArrowError::External(Box::new(err)))?;
 
         Ok(Self {
-            writer,
+            writer: Some(writer),
             metadata_builder,
+            pending: None,
+            writer_properties: options.writer_properties.clone(),
         })
     }
 
     /// Write a batch to an output file
     pub async fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        if let Some((_, buffer)) = self.pending.as_mut() {
+            let Some(remaining) = buffer.push(batch.clone()) else {
+                return Ok(());
+            };
+            self.flush_pending().await?;
+            if remaining.num_rows() == 0 {
+                return Ok(());
+            }
+            return self.write_encoded_batch(&remaining).await;
+        }
+
+        self.write_encoded_batch(batch).await
+    }
+
+    /// Infer the native types of geometry columns from the buffered batches, then create the
+    /// Parquet writer and write the buffered batches.
+    async fn flush_pending(&mut self) -> Result<()> {
+        let Some((writer, buffer)) = self.pending.take() else {
+            return Ok(());
+        };
+
+        let batches = buffer.into_batches();
+        self.metadata_builder.infer_types(&batches)?;
+        self.writer = Some(
+            AsyncArrowWriter::try_new(
+                writer,
+                self.metadata_builder.output_schema.clone(),
+                self.writer_properties.clone(),
+            )
+            .map_err(|err| GeoArrowError::External(Box::new(err)))?,
+        );
+
+        for batch in batches.iter() {
+            self.write_encoded_batch(batch).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_encoded_batch(&mut self, batch: &RecordBatch) -> Result<()> {
         let encoded_batch = encode_record_batch(batch, &mut self.metadata_builder)?;
         self.writer
+            .as_mut()
+            .unwrap()
             .write(&encoded_batch)
             .await
             .map_err(|err| GeoArrowError::External(Box::new(err)))?;
@@ -59,8 +117,16 @@ impl<W: AsyncFileWriter> GeoParquetWriterAsync<W> {
     }
 
     /// Access the underlying writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics while batches are buffered to infer the native types of geometry columns, as the
+    /// underlying writer is only created once the output schema is known; see
+    /// [GeoParquetTypeInference][crate::GeoParquetTypeInference].
     pub fn writer(&self) -> &AsyncArrowWriter<W> {
-        &self.writer
+        self.writer
+            .as_ref()
+            .expect("The writer is created once geometry types have been inferred")
     }
 
     /// Close and finalize the writer.
@@ -69,12 +135,15 @@ impl<W: AsyncFileWriter> GeoParquetWriterAsync<W> {
     ///
     /// All the data in the inner buffer will be force flushed.
     pub async fn finish(mut self) -> Result<()> {
+        self.flush_pending().await?;
+        let mut writer = self.writer.take().unwrap();
+
         if let Some(geo_meta) = self.metadata_builder.finish() {
             let kv_metadata = KeyValue::new("geo".to_string(), serde_json::to_string(&geo_meta)?);
-            self.writer.append_key_value_metadata(kv_metadata);
+            writer.append_key_value_metadata(kv_metadata);
         }
 
-        self.writer
+        writer
             .close()
             .await
             .map_err(|err| GeoArrowError::External(Box::new(err)))?;
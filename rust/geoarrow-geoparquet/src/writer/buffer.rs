@@ -0,0 +1,51 @@
+use arrow_array::RecordBatch;
+
+use crate::writer::options::GeoParquetTypeInference;
+
+/// Batches that are held back until the native types of all geometry columns have been inferred.
+pub(super) struct InferenceBuffer {
+    batches: Vec<RecordBatch>,
+    num_rows: usize,
+    /// The number of rows after which the types are inferred, or `None` to buffer all input.
+    max_rows: Option<usize>,
+}
+
+impl InferenceBuffer {
+    pub(super) fn new(type_inference: GeoParquetTypeInference) -> Self {
+        let max_rows = match type_inference {
+            GeoParquetTypeInference::Buffered(max_rows) => Some(max_rows),
+            GeoParquetTypeInference::Disabled | GeoParquetTypeInference::Full => None,
+        };
+        Self {
+            batches: vec![],
+            num_rows: 0,
+            max_rows,
+        }
+    }
+
+    /// Add a batch to the buffer, holding at most `max_rows` rows.
+    ///
+    /// Returns `None` while more rows are needed to infer types. Otherwise, returns the rows of
+    /// `batch` that were not buffered, which may be empty.
+    pub(super) fn push(&mut self, batch: RecordBatch) -> Option<RecordBatch> {
+        let Some(max_rows) = self.max_rows else {
+            self.num_rows += batch.num_rows();
+            self.batches.push(batch);
+            return None;
+        };
+
+        let num_buffered = max_rows.saturating_sub(self.num_rows).min(batch.num_rows());
+        if num_buffered > 0 {
+            self.num_rows += num_buffered;
+            self.batches.push(batch.slice(0, num_buffered));
+        }
+        if self.num_rows < max_rows {
+            return None;
+        }
+        Some(batch.slice(num_buffered, batch.num_rows() - num_buffered))
+    }
+
+    pub(super) fn into_batches(self) -> Vec<RecordBatch> {
+        self.batches
+    }
+}
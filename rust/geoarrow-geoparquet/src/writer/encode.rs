@@ -1,12 +1,11 @@
 use arrow_array::{Array, ArrayRef, RecordBatch};
 use arrow_schema::Field;
 use geoarrow_array::array::from_arrow_array;
-use geoarrow_array::cast::{AsGeoArrowArray, to_wkb};
-use geoarrow_array::error::Result;
+use geoarrow_array::cast::to_wkb;
+use geoarrow_array::error::{GeoArrowError, Result};
 use geoarrow_array::{GeoArrowArray, GeoArrowType};
-use geoarrow_schema::CoordType;
+use geoarrow_cast::cast::cast;
 
-use crate::metadata::GeoParquetColumnEncoding;
 use crate::total_bounds::{BoundingRect, total_bounds};
 use crate::writer::metadata::{ColumnInfo, GeoParquetMetadataBuilder};
 
@@ -18,7 +17,6 @@ pub(super) fn encode_record_batch(
     for (column_idx, column_info) in metadata_builder.columns.iter_mut() {
         let array = batch.column(*column_idx);
         let field = batch.schema_ref().field(*column_idx);
-        column_info.update_geometry_types(array, field)?;
 
         let (encoded_column, array_bounds) = encode_column(array, field, column_info)?;
         new_columns[*column_idx] = encoded_column;
@@ -38,10 +36,13 @@ fn encode_column(
     column_info: &mut ColumnInfo,
 ) -> Result<(ArrayRef, BoundingRect)> {
     let geo_arr = from_arrow_array(array, field)?;
+    column_info.validate(geo_arr.data_type())?;
+    column_info.update_geometry_types(geo_arr.data_type());
+
     let array_bounds = total_bounds(geo_arr.as_ref())?;
-    let encoded_array = match column_info.encoding {
-        GeoParquetColumnEncoding::WKB => encode_wkb_column(geo_arr.as_ref())?,
-        _ => encode_native_column(geo_arr.as_ref()),
+    let encoded_array = match &column_info.target_type {
+        Some(target_type) => encode_native_column(geo_arr.as_ref(), target_type, column_info)?,
+        None => encode_wkb_column(geo_arr.as_ref())?,
     };
     Ok((encoded_array, array_bounds))
 }
@@ -51,28 +52,26 @@ fn encode_wkb_column(geo_arr: &dyn GeoArrowArray) -> Result<ArrayRef> {
     Ok(to_wkb::<i32>(geo_arr)?.to_array_ref())
 }
 
-/// Encode column as GeoArrow.
+/// Encode column as GeoArrow, casting to the output type of the column.
 ///
-/// Note that the GeoParquet specification requires separated coord type!
-fn encode_native_column(geo_arr: &dyn GeoArrowArray) -> ArrayRef {
-    macro_rules! impl_into_coord_type {
-        ($cast_func:ident) => {
-            geo_arr
-                .$cast_func()
-                .clone()
-                .into_coord_type(CoordType::Separated)
-                .to_array_ref()
+/// This also converts interleaved coords to separated coords, as required by the GeoParquet
+/// specification.
+fn encode_native_column(
+    geo_arr: &dyn GeoArrowArray,
+    target_type: &GeoArrowType,
+    column_info: &ColumnInfo,
+) -> Result<ArrayRef> {
+    let casted = cast(geo_arr, target_type).map_err(|err| {
+        let hint = if column_info.inferred {
+            ". The type was inferred from the first rows written; infer it from the full input \
+             or write the column as WKB instead"
+        } else {
+            ""
         };
-    }
-    match geo_arr.data_type() {
-        GeoArrowType::Point(_) => impl_into_coord_type!(as_point),
-        GeoArrowType::LineString(_) => impl_into_coord_type!(as_line_string),
-        GeoArrowType::Polygon(_) => impl_into_coord_type!(as_polygon),
-        GeoArrowType::MultiPoint(_) => impl_into_coord_type!(as_multi_point),
-        GeoArrowType::MultiLineString(_) => impl_into_coord_type!(as_multi_line_string),
-        GeoArrowType::MultiPolygon(_) => impl_into_coord_type!(as_multi_polygon),
-        GeoArrowType::Geometry(_) => impl_into_coord_type!(as_geometry),
-        GeoArrowType::GeometryCollection(_) => impl_into_coord_type!(as_geometry_collection),
-        _ => geo_arr.to_array_ref(),
-    }
+        GeoArrowError::General(format!(
+            "Geometry column {} can't be cast to its native output type {:?}: {}{}",
+            column_info.name, target_type, err, hint
+        ))
+    })?;
+    Ok(casted.to_array_ref())
 }
@@ -1,14 +1,18 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use arrow_array::ArrayRef;
+use arrow_array::RecordBatch;
 use arrow_schema::extension::{EXTENSION_TYPE_METADATA_KEY, EXTENSION_TYPE_NAME_KEY};
 use arrow_schema::{DataType, Field, Schema, SchemaRef};
-use geoarrow_array::GeoArrowType;
 use geoarrow_array::array::from_arrow_array;
 use geoarrow_array::crs::{CRSTransform, DefaultCRSTransform};
-use geoarrow_array::error::Result;
-use geoarrow_schema::{CoordType, Edges, Metadata, WkbType};
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geoarrow_cast::downcast::{NativeType, infer_downcast_type};
+use geoarrow_schema::{
+    CoordType, Dimension, Edges, LineStringType, Metadata, MultiLineStringType, MultiPointType,
+    MultiPolygonType, PointType, PolygonType, WkbType,
+};
 use serde_json::Value;
 
 use crate::metadata::{
@@ -16,7 +20,9 @@ use crate::metadata::{
     GeoParquetGeometryTypeAndDimension, GeoParquetMetadata,
 };
 use crate::total_bounds::BoundingRect;
-use crate::writer::options::{GeoParquetWriterEncoding, GeoParquetWriterOptions};
+use crate::writer::options::{
+    GeoParquetTypeInference, GeoParquetWriterEncoding, GeoParquetWriterOptions,
+};
 
 /// Information for one geometry column being written to Parquet
 pub struct ColumnInfo {
@@ -38,6 +44,26 @@ pub struct ColumnInfo {
     /// If present, instructs consumers that edges follow a spherical path rather than a planar
     /// one. If this value is omitted, edges will be interpreted as planar.
     pub edges: Option<Edges>,
+
+    /// The GeoArrow type of this column in the schema passed to the writer.
+    ///
+    /// Every batch is validated against this type.
+    pub declared_type: GeoArrowType,
+
+    /// Whether `geometry_types` can't be known from the data types of the written arrays, in
+    /// which case an empty list of geometry types is written.
+    pub geometry_types_unknown: bool,
+
+    /// Whether the native type of this column still has to be inferred from the data.
+    pub pending_inference: bool,
+
+    /// The native type that each array of this column is cast to before encoding, or `None` if
+    /// the column is encoded as WKB.
+    pub target_type: Option<GeoArrowType>,
+
+    /// Whether `target_type` was inferred from the data, in which case later arrays may contain
+    /// geometries that it can't represent.
+    pub inferred: bool,
 }
 
 impl ColumnInfo {
@@ -48,9 +74,31 @@ impl ColumnInfo {
         data_type: &GeoArrowType,
         array_meta: Metadata,
         crs_transform: Option<&Box<dyn CRSTransform>>,
+        type_inference: GeoParquetTypeInference,
     ) -> Result<Self> {
-        let encoding = GeoParquetColumnEncoding::try_new(writer_encoding, data_type)?;
+        let pending_inference = matches!(writer_encoding, GeoParquetWriterEncoding::Native)
+            && type_inference != GeoParquetTypeInference::Disabled
+            && matches!(
+                data_type,
+                GeoArrowType::Geometry(_)
+                    | GeoArrowType::Wkb(_)
+                    | GeoArrowType::LargeWkb(_)
+                    | GeoArrowType::Wkt(_)
+                    | GeoArrowType::LargeWkt(_)
+            );
+        // Until the native type has been inferred, the column is treated as WKB
+        let encoding = if pending_inference {
+            GeoParquetColumnEncoding::WKB
+        } else {
+            GeoParquetColumnEncoding::try_new(writer_encoding, data_type)?
+        };
         let geometry_types = get_geometry_types(data_type);
+        let target_type = if encoding == GeoParquetColumnEncoding::WKB {
+            None
+        } else {
+            // The GeoParquet specification requires separated coords
+            Some(data_type.clone().with_coord_type(CoordType::Separated))
+        };
 
         let crs = if let Some(crs_transform) = crs_transform {
             crs_transform.extract_projjson(array_meta.crs())?
@@ -62,13 +110,48 @@ impl ColumnInfo {
         Ok(Self {
             name,
             encoding,
-            geometry_types,
             bbox: None,
             crs,
             edges,
+            declared_type: data_type.clone(),
+            geometry_types_unknown: geometry_types.is_empty(),
+            geometry_types,
+            pending_inference,
+            target_type,
+            inferred: false,
         })
     }
 
+    /// Infer the narrowest native type of this column from the given arrays.
+    ///
+    /// If no single native type can represent every geometry, the column falls back to WKB.
+    fn infer_type(&mut self, arrays: &[Arc<dyn GeoArrowArray>]) -> Result<()> {
+        self.pending_inference = false;
+
+        // With no non-null geometries there's nothing to infer from
+        if arrays
+            .iter()
+            .all(|array| array.logical_null_count() == array.len())
+        {
+            return Ok(());
+        }
+
+        let inferred = infer_downcast_type(arrays.iter().map(|array| array.as_ref()))?;
+        let metadata = arrays[0].data_type().metadata().clone();
+        if let Some(target_type) =
+            inferred.and_then(|(native_type, dim)| native_data_type(native_type, dim, metadata))
+        {
+            self.encoding =
+                GeoParquetColumnEncoding::try_new(GeoParquetWriterEncoding::Native, &target_type)?;
+            self.geometry_types = get_geometry_types(&target_type);
+            self.geometry_types_unknown = false;
+            self.target_type = Some(target_type);
+            self.inferred = true;
+        }
+
+        Ok(())
+    }
+
     pub fn update_bbox(&mut self, new_bounds: &BoundingRect) {
         if let Some(existing_bounds) = self.bbox.as_mut() {
             existing_bounds.update(new_bounds)
@@ -77,6 +160,46 @@ impl ColumnInfo {
         }
     }
 
+    /// Check that an array written to this column is consistent with the declared schema.
+    ///
+    /// Arrays may differ from the declared type in geometry type or coord type, as long as they
+    /// can be cast to the output type of the column, but their CRS, edges and dimension must
+    /// match.
+    pub fn validate(&self, data_type: &GeoArrowType) -> Result<()> {
+        let declared_metadata = self.declared_type.metadata();
+        let metadata = data_type.metadata();
+        if metadata.crs() != declared_metadata.crs() {
+            return Err(GeoArrowError::General(format!(
+                "CRS of geometry column {} ({:?}) does not match the CRS declared in the writer schema ({:?})",
+                self.name,
+                metadata.crs(),
+                declared_metadata.crs()
+            )));
+        }
+
+        if metadata.edges() != declared_metadata.edges() {
+            return Err(GeoArrowError::General(format!(
+                "Edges of geometry column {} ({:?}) do not match the edges declared in the writer schema ({:?})",
+                self.name,
+                metadata.edges(),
+                declared_metadata.edges()
+            )));
+        }
+
+        if let (Some(dim), Some(declared_dim)) =
+            (data_type.dimension(), self.declared_type.dimension())
+        {
+            if dim != declared_dim {
+                return Err(GeoArrowError::General(format!(
+                    "Dimension of geometry column {} ({:?}) does not match the dimension declared in the writer schema ({:?})",
+                    self.name, dim, declared_dim
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Update the geometry types in the encoder for mixed arrays
     // TODO: for multi columns, should we do a check to see if there are non-multi geometries in
     // the file? E.g. check if the diff in geom_offsets is 1 for any row, in which case we should
@@ -85,45 +208,23 @@ impl ColumnInfo {
     // Note: for these multi columns, we should first check the geometry_types HashSet, because we
     // shouldn't compute that for every array if we see in the first that the data is both multi
     // and single polygons.
-    pub fn update_geometry_types(&mut self, array: &ArrayRef, field: &Field) -> Result<()> {
-        let array = from_arrow_array(array, field)?;
-        let array_ref = array.as_ref();
+    pub fn update_geometry_types(&mut self, data_type: &GeoArrowType) {
+        // Native columns are cast to a single type, so their geometry types are statically known
+        if self.target_type.is_some() || self.geometry_types_unknown {
+            return;
+        }
 
-        // We only have to do this for geometry arrays because other arrays are statically known
-        if let GeoArrowType::Geometry(_) = array_ref.data_type() {
-            // TODO: restore writing `geometry_types`.
+        let array_geometry_types = get_geometry_types(data_type);
+        if array_geometry_types.is_empty() {
+            // TODO: restore writing `geometry_types` for geometry arrays.
             // The spec says "The geometry types of all geometries, or an empty array if they are
             // not known.". So it's valid for us to write an empty array, but in the future we
             // should restore writing known types.
-
-            // let arr = array_ref.as_geometry();
-            // if arr.has_points(Dimension::XY) || arr.has_points(Dimension::XYZ) {
-            //     self.geometry_types.insert(GeoParquetGeometryType::Point);
-            // }
-            // if arr.has_line_strings(Dimension::XY) || arr.has_line_strings(Dimension::XYZ) {
-            //     self.geometry_types
-            //         .insert(GeoParquetGeometryType::LineString);
-            // }
-            // if arr.has_polygons(Dimension::XY) || arr.has_polygons(Dimension::XYZ) {
-            //     self.geometry_types.insert(GeoParquetGeometryType::Polygon);
-            // }
-            // if arr.has_multi_points(Dimension::XY) || arr.has_multi_points(Dimension::XYZ) {
-            //     self.geometry_types
-            //         .insert(GeoParquetGeometryType::MultiPoint);
-            // }
-            // if arr.has_multi_line_strings(Dimension::XY)
-            //     || arr.has_multi_line_strings(Dimension::XYZ)
-            // {
-            //     self.geometry_types
-            //         .insert(GeoParquetGeometryType::MultiLineString);
-            // }
-            // if arr.has_multi_polygons(Dimension::XY) || arr.has_multi_polygons(Dimension::XYZ) {
-            //     self.geometry_types
-            //         .insert(GeoParquetGeometryType::MultiPolygon);
-            // }
+            self.geometry_types_unknown = true;
+            self.geometry_types.clear();
+        } else {
+            self.geometry_types.extend(array_geometry_types);
         }
-
-        Ok(())
     }
 
     /// Returns (column_name, column_metadata)
@@ -163,6 +264,7 @@ impl ColumnInfo {
 }
 
 pub struct GeoParquetMetadataBuilder {
+    pub input_schema: SchemaRef,
     pub output_schema: SchemaRef,
     pub primary_column: Option<String>,
     pub columns: HashMap<usize, ColumnInfo>,
@@ -195,6 +297,7 @@ impl GeoParquetMetadataBuilder {
                     &geo_data_type,
                     array_meta,
                     options.crs_transform.as_ref(),
+                    options.type_inference,
                 )?;
 
                 columns.insert(col_idx, column_info);
@@ -203,12 +306,41 @@ impl GeoParquetMetadataBuilder {
 
         let output_schema = create_output_schema(schema, &columns);
         Ok(Self {
+            input_schema: Arc::new(schema.clone()),
             primary_column: None,
             columns,
             output_schema,
         })
     }
 
+    /// Whether any column still needs its native type to be inferred before the output schema is
+    /// known.
+    pub fn pending_inference(&self) -> bool {
+        self.columns
+            .values()
+            .any(|column_info| column_info.pending_inference)
+    }
+
+    /// Infer the native types of all pending columns from the given batches, updating the
+    /// output schema.
+    pub fn infer_types(&mut self, batches: &[RecordBatch]) -> Result<()> {
+        for (column_idx, column_info) in self.columns.iter_mut() {
+            if !column_info.pending_inference {
+                continue;
+            }
+
+            let field = self.input_schema.field(*column_idx);
+            let arrays = batches
+                .iter()
+                .map(|batch| from_arrow_array(batch.column(*column_idx), field))
+                .collect::<Result<Vec<_>>>()?;
+            column_info.infer_type(&arrays)?;
+        }
+
+        self.output_schema = create_output_schema(&self.input_schema, &self.columns);
+        Ok(())
+    }
+
     #[allow(dead_code)]
     fn update_bounds(&mut self, bounds: &HashMap<usize, BoundingRect>) {
         for (column_idx, column_bounds) in bounds.iter() {
@@ -313,6 +445,13 @@ fn create_output_schema(input_schema: &Schema, columns: &HashMap<usize, ColumnIn
 fn create_output_field(column_info: &ColumnInfo, name: String, nullable: bool) -> Field {
     use GeoParquetColumnEncoding as Encoding;
 
+    if let Some(target_type) = &column_info.target_type {
+        return target_type
+            .clone()
+            .with_metadata(Default::default())
+            .to_field(name, nullable);
+    }
+
     match column_info.encoding {
         Encoding::WKB => Field::new(name, DataType::Binary, nullable)
             .with_extension_type(WkbType::new(Default::default())),
@@ -325,3 +464,32 @@ fn create_output_field(column_info: &ColumnInfo, name: String, nullable: bool) -
         }
     }
 }
+
+/// The GeoParquet-compatible native type for an inferred geometry type, if any.
+///
+/// Geometry collections and rectangles have no native GeoParquet encoding.
+fn native_data_type(
+    native_type: NativeType,
+    dim: Dimension,
+    metadata: Arc<Metadata>,
+) -> Option<GeoArrowType> {
+    let coord_type = CoordType::Separated;
+    let data_type = match native_type {
+        NativeType::Point => GeoArrowType::Point(PointType::new(coord_type, dim, metadata)),
+        NativeType::LineString => {
+            GeoArrowType::LineString(LineStringType::new(coord_type, dim, metadata))
+        }
+        NativeType::Polygon => GeoArrowType::Polygon(PolygonType::new(coord_type, dim, metadata)),
+        NativeType::MultiPoint => {
+            GeoArrowType::MultiPoint(MultiPointType::new(coord_type, dim, metadata))
+        }
+        NativeType::MultiLineString => {
+            GeoArrowType::MultiLineString(MultiLineStringType::new(coord_type, dim, metadata))
+        }
+        NativeType::MultiPolygon => {
+            GeoArrowType::MultiPolygon(MultiPolygonType::new(coord_type, dim, metadata))
+        }
+        NativeType::GeometryCollection | NativeType::Rect => return None,
+    };
+    Some(data_type)
+}
@@ -1,5 +1,6 @@
 #[cfg(feature = "async")]
 mod r#async;
+mod buffer;
 mod encode;
 mod metadata;
 mod options;
@@ -7,5 +8,5 @@ mod sync;
 
 #[cfg(feature = "async")]
 pub use r#async::{GeoParquetWriterAsync, write_geoparquet_async};
-pub use options::{GeoParquetWriterEncoding, GeoParquetWriterOptions};
+pub use options::{GeoParquetTypeInference, GeoParquetWriterEncoding, GeoParquetWriterOptions};
 pub use sync::{GeoParquetWriter, write_geoparquet};
@@ -16,6 +16,37 @@ pub enum GeoParquetWriterEncoding {
     Native,
 }
 
+/// How to choose a native encoding for geometry columns that don't have a single native type.
+///
+/// This applies to `Geometry`, WKB and WKT input columns when writing with
+/// [GeoParquetWriterEncoding::Native]. Such columns are scanned to infer the narrowest native
+/// type that can represent all of their geometries, e.g. `MultiPolygon` with a fixed dimension,
+/// and each batch is then cast to that type. If no single native type exists, e.g. because the
+/// column contains both points and polygons or mixes dimensions, the column is written as WKB
+/// instead.
+///
+/// Since the Parquet schema has to be known before the first row group is written, batches are
+/// buffered in memory until the type has been inferred.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum GeoParquetTypeInference {
+    /// Don't infer a native type. Writing a column without a single native type with
+    /// [GeoParquetWriterEncoding::Native] is an error.
+    #[default]
+    Disabled,
+
+    /// Infer the native type from the first `n` rows.
+    ///
+    /// Only these rows are held in memory. Rows written after the type has been inferred are
+    /// cast to it, and writing fails if one of them contains a geometry that can't be
+    /// represented by the inferred type. Use [Self::Full] or [GeoParquetWriterEncoding::WKB] if
+    /// the first rows may not be representative.
+    Buffered(usize),
+
+    /// Buffer the entire input until the writer is finished, so that the inferred type is always
+    /// valid for every batch.
+    Full,
+}
+
 /// Options for writing GeoParquet
 #[derive(Default)]
 pub struct GeoParquetWriterOptions {
@@ -27,4 +58,9 @@ pub struct GeoParquetWriterOptions {
 
     /// A transformer for converting CRS from the GeoArrow representation to PROJJSON.
     pub crs_transform: Option<Box<dyn CRSTransform>>,
+
+    /// How to choose a native encoding for geometry columns that don't have a single native type.
+    ///
+    /// Only used with [GeoParquetWriterEncoding::Native].
+    pub type_inference: GeoParquetTypeInference,
 }
@@ -1,5 +1,6 @@
 use std::io::Write;
 
+use crate::writer::buffer::InferenceBuffer;
 use crate::writer::encode::encode_record_batch;
 use crate::writer::metadata::GeoParquetMetadataBuilder;
 use crate::writer::options::GeoParquetWriterOptions;
@@ -8,6 +9,7 @@ use arrow_schema::Schema;
 use geoarrow_array::error::{GeoArrowError, Result};
 use parquet::arrow::ArrowWriter;
 use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
 
 /// Write a [RecordBatchReader] to GeoParquet.
 pub fn write_geoparquet<W: Write + Send>(
@@ -27,8 +29,12 @@ pub fn write_geoparquet<W: Write + Send>(
 
 /// A synchronous GeoParquet file writer
 pub struct GeoParquetWriter<W: Write + Send> {
-    writer: ArrowWriter<W>,
+    writer: Option<ArrowWriter<W>>,
     metadata_builder: GeoParquetMetadataBuilder,
+    /// The output and the buffered batches, while the native types of geometry columns are
+    /// still being inferred.
+    pending: Option<(W, InferenceBuffer)>,
+    writer_properties: Option<WriterProperties>,
 }
 
 impl<W: Write + Send> GeoParquetWriter<W> {
@@ -36,6 +42,15 @@ impl<W: Write + Send> GeoParquetWriter<W> {
     pub fn try_new(writer: W, schema: &Schema, options: &GeoParquetWriterOptions) -> Result<Self> {
         let metadata_builder = GeoParquetMetadataBuilder::try_new(schema, options)?;
 
+        if metadata_builder.pending_inference() {
+            return Ok(Self {
+                writer: None,
+                metadata_builder,
+                pending: Some((writer, InferenceBuffer::new(options.type_inference))),
+                writer_properties: options.writer_properties.clone(),
+            });
+        }
+
         let writer = ArrowWriter::try_new(
             writer,
             metadata_builder.output_schema.clone(),
@@ -44,23 +59,74 @@ impl<W: Write + Send> GeoParquetWriter<W> {
         .map_err(|err| GeoArrowError::External(Box::new(err)))?;
 
         Ok(Self {
-            writer,
+            writer: Some(writer),
             metadata_builder,
+            pending: None,
+            writer_properties: options.writer_properties.clone(),
         })
     }
 
     /// Write a batch to an output file
     pub fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        if let Some((_, buffer)) = self.pending.as_mut() {
+            let Some(remaining) = buffer.push(batch.clone()) else {
+                return Ok(());
+            };
+            self.flush_pending()?;
+            if remaining.num_rows() == 0 {
+                return Ok(());
+            }
+            return self.write_encoded_batch(&remaining);
+        }
+
+        self.write_encoded_batch(batch)
+    }
+
+    /// Infer the native types of geometry columns from the buffered batches, then create the
+    /// Parquet writer and write the buffered batches.
+    fn flush_pending(&mut self) -> Result<()> {
+        let Some((writer, buffer)) = self.pending.take() else {
+            return Ok(());
+        };
+
+        let batches = buffer.into_batches();
+        self.metadata_builder.infer_types(&batches)?;
+        self.writer = Some(
+            ArrowWriter::try_new(
+                writer,
+                self.metadata_builder.output_schema.clone(),
+                self.writer_properties.clone(),
+            )
+            .map_err(|err| GeoArrowError::External(Box::new(err)))?,
+        );
+
+        for batch in batches.iter() {
+            self.write_encoded_batch(batch)?;
+        }
+        Ok(())
+    }
+
+    fn write_encoded_batch(&mut self, batch: &RecordBatch) -> Result<()> {
         let encoded_batch = encode_record_batch(batch, &mut self.metadata_builder)?;
         self.writer
+            .as_mut()
+            .unwrap()
             .write(&encoded_batch)
             .map_err(|err| GeoArrowError::External(Box::new(err)))?;
         Ok(())
     }
 
     /// Access the underlying writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics while batches are buffered to infer the native types of geometry columns, as the
+    /// underlying writer is only created once the output schema is known; see
+    /// [GeoParquetTypeInference][crate::GeoParquetTypeInference].
     pub fn writer(&self) -> &ArrowWriter<W> {
-        &self.writer
+        self.writer
+            .as_ref()
+            .expect("The writer is created once geometry types have been inferred")
     }
 
     /// Close and finalize the writer.
@@ -69,14 +135,169 @@ impl<W: Write + Send> GeoParquetWriter<W> {
     ///
     /// All the data in the inner buffer will be force flushed.
     pub fn finish(mut self) -> Result<()> {
+        self.flush_pending()?;
+        let mut writer = self.writer.take().unwrap();
+
         if let Some(geo_meta) = self.metadata_builder.finish() {
             let kv_metadata = KeyValue::new("geo".to_string(), serde_json::to_string(&geo_meta)?);
-            self.writer.append_key_value_metadata(kv_metadata);
+            writer.append_key_value_metadata(kv_metadata);
         }
 
-        self.writer
+        writer
             .close()
             .map_err(|err| GeoArrowError::External(Box::new(err)))?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::sync::Arc;
+
+    use arrow_schema::ArrowError;
+    use geoarrow_array::array::from_arrow_array;
+    use geoarrow_array::builder::{PointBuilder, WkbBuilder};
+    use geoarrow_array::{GeoArrowArray, GeoArrowType};
+    use geoarrow_schema::{CoordType, Crs, Dimension, Metadata, PointType, WkbType};
+
+    use super::*;
+    use crate::GeoParquetRecordBatchReaderBuilder;
+    use crate::metadata::GeoParquetColumnEncoding;
+    use crate::writer::options::{GeoParquetTypeInference, GeoParquetWriterEncoding};
+
+    fn wkb_batch(geoms: &[geo_types::Geometry]) -> RecordBatch {
+        let geoms = geoms.iter().cloned().map(Some).collect::<Vec<_>>();
+        let array =
+            WkbBuilder::<i32>::from_nullable_geometries(&geoms, WkbType::new(Default::default()))
+                .finish();
+        let field = array.data_type().to_field("geometry", true);
+        let schema = Arc::new(Schema::new(vec![field]));
+        RecordBatch::try_new(schema, vec![array.to_array_ref()]).unwrap()
+    }
+
+    /// Write batches with native encoding and type inference, then read them back.
+    fn round_trip(
+        name: &str,
+        batches: &[RecordBatch],
+        type_inference: GeoParquetTypeInference,
+    ) -> (GeoParquetColumnEncoding, Arc<dyn GeoArrowArray>) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("{name}.parquet"));
+        let options = GeoParquetWriterOptions {
+            encoding: GeoParquetWriterEncoding::Native,
+            type_inference,
+            ..Default::default()
+        };
+        let mut writer =
+            GeoParquetWriter::try_new(File::create(&path).unwrap(), &batches[0].schema(), &options)
+                .unwrap();
+        for batch in batches {
+            writer.write_batch(batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let builder =
+            GeoParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        let encoding = builder.geo_meta().unwrap().columns["geometry"].encoding;
+        let reader = builder.build().unwrap();
+        let schema = reader.schema();
+        let batches = reader
+            .collect::<std::result::Result<Vec<_>, ArrowError>>()
+            .unwrap();
+
+        let array = from_arrow_array(batches[0].column(0), schema.field(0)).unwrap();
+        (encoding, array)
+    }
+
+    #[test]
+    fn infer_narrowest_native_type() {
+        let batches = [
+            wkb_batch(&[geo_types::point!(x: 0., y: 0.).into()]),
+            wkb_batch(&[geo_types::MultiPoint::from(vec![(1., 1.), (2., 2.)]).into()]),
+        ];
+        let (encoding, array) =
+            round_trip("infer_multi_point", &batches, GeoParquetTypeInference::Full);
+        assert_eq!(encoding, GeoParquetColumnEncoding::MultiPoint);
+        assert!(matches!(array.data_type(), GeoArrowType::MultiPoint(_)));
+        assert_eq!(array.len(), 2);
+    }
+
+    #[test]
+    fn infer_falls_back_to_wkb() {
+        let batches = [
+            wkb_batch(&[geo_types::point!(x: 0., y: 0.).into()]),
+            wkb_batch(&[geo_types::line_string![(x: 0., y: 0.), (x: 1., y: 1.)].into()]),
+        ];
+        let (encoding, _) = round_trip("infer_mixed", &batches, GeoParquetTypeInference::Full);
+        assert_eq!(encoding, GeoParquetColumnEncoding::WKB);
+    }
+
+    #[test]
+    fn infer_from_first_rows() {
+        let points = wkb_batch(&[
+            geo_types::point!(x: 0., y: 0.).into(),
+            geo_types::point!(x: 1., y: 1.).into(),
+        ]);
+        let line = wkb_batch(&[geo_types::line_string![(x: 0., y: 0.), (x: 1., y: 1.)].into()]);
+        let options = GeoParquetWriterOptions {
+            encoding: GeoParquetWriterEncoding::Native,
+            type_inference: GeoParquetTypeInference::Buffered(1),
+            ..Default::default()
+        };
+
+        // The type is inferred from the first row, and the rest of the batch is cast to it
+        let mut writer = GeoParquetWriter::try_new(vec![], &points.schema(), &options).unwrap();
+        writer.write_batch(&points).unwrap();
+        assert_eq!(writer.writer().flushed_row_groups().len(), 0);
+
+        // Rows that don't fit the inferred type are an error
+        let err = writer.write_batch(&line).unwrap_err();
+        assert!(err.to_string().contains("inferred from the first rows"));
+
+        let (encoding, array) = round_trip(
+            "infer_from_first_rows",
+            &[points],
+            GeoParquetTypeInference::Buffered(1),
+        );
+        assert_eq!(encoding, GeoParquetColumnEncoding::Point);
+        assert_eq!(array.len(), 2);
+    }
+
+    fn point_batch(coord_type: CoordType, metadata: Metadata) -> RecordBatch {
+        let typ = PointType::new(coord_type, Dimension::XY, Arc::new(metadata));
+        let points = [geo_types::point!(x: 1., y: 2.)];
+        let array = PointBuilder::from_points(points.iter(), typ).finish();
+        let field = array.data_type().to_field("geometry", true);
+        let schema = Arc::new(Schema::new(vec![field]));
+        RecordBatch::try_new(schema, vec![array.to_array_ref()]).unwrap()
+    }
+
+    #[test]
+    fn coerce_coord_type() {
+        let separated = point_batch(CoordType::Separated, Default::default());
+        let interleaved = point_batch(CoordType::Interleaved, Default::default());
+        let options = GeoParquetWriterOptions {
+            encoding: GeoParquetWriterEncoding::Native,
+            ..Default::default()
+        };
+        let mut writer = GeoParquetWriter::try_new(vec![], &separated.schema(), &options).unwrap();
+        writer.write_batch(&separated).unwrap();
+        writer.write_batch(&interleaved).unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn reject_mismatched_crs() {
+        let batch = point_batch(CoordType::Separated, Default::default());
+        let other_crs = point_batch(
+            CoordType::Separated,
+            Metadata::new(Crs::from_authority_code("EPSG:4326".to_string()), None),
+        );
+        let mut writer =
+            GeoParquetWriter::try_new(vec![], &batch.schema(), &Default::default()).unwrap();
+        writer.write_batch(&batch).unwrap();
+        let err = writer.write_batch(&other_crs).unwrap_err();
+        assert!(err.to_string().contains("CRS of geometry column geometry"));
+    }
+}
@@ -0,0 +1,21 @@
+//! Read and write GeoArrow data in the [Arrow IPC](https://arrow.apache.org/docs/format/Columnar.html#format-ipc)
+//! file and stream formats.
+//!
+//! Geometry columns are identified by their `geoarrow.*` extension type. Their extension metadata
+//! is validated when reading and before writing, so that malformed data is rejected at the
+//! boundary rather than surfacing later as a confusing error.
+//!
+//! Buffer compression with LZ4 or ZSTD requires the `ipc_compression` feature.
+
+#![warn(missing_docs)]
+#![cfg_attr(not(test), deny(unused_crate_dependencies))]
+
+mod reader;
+mod schema;
+mod writer;
+
+pub use reader::{
+    GeoArrowIpcFileReader, GeoArrowIpcReader, GeoArrowIpcStreamReader, IpcReaderOptions,
+};
+pub use schema::{GeometryColumn, geometry_columns};
+pub use writer::{CompressionType, IpcWriterOptions, write_ipc_file, write_ipc_stream};
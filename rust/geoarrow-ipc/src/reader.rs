@@ -0,0 +1,222 @@
+use std::io::{Read, Seek};
+use std::sync::Arc;
+
+use arrow_array::{RecordBatch, RecordBatchReader};
+use arrow_ipc::reader::{FileReader, StreamReader};
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+use geoarrow_array::array::from_arrow_array;
+use geoarrow_array::error::Result;
+use geoarrow_cast::cast::cast;
+use geoarrow_schema::CoordType;
+
+use crate::schema::{GeometryColumn, geometry_columns};
+
+/// Options for reading GeoArrow data from Arrow IPC.
+#[derive(Debug, Clone, Default)]
+pub struct IpcReaderOptions {
+    /// The GeoArrow coordinate type to convert native geometry columns to.
+    ///
+    /// If `None`, geometry columns are read with the coordinate type they were written with.
+    pub coord_type: Option<CoordType>,
+}
+
+/// A reader of record batches from Arrow IPC, with validated GeoArrow geometry columns.
+///
+/// The geometry columns of the schema are validated when the reader is created, so that
+/// malformed `geoarrow.*` extension metadata is rejected before any batch is read.
+pub struct GeoArrowIpcReader<R> {
+    inner: R,
+    schema: SchemaRef,
+    geometry_columns: Vec<GeometryColumn>,
+    /// The geometry columns converted to a different coordinate type.
+    converted_columns: Vec<GeometryColumn>,
+}
+
+/// A reader of an Arrow IPC file.
+pub type GeoArrowIpcFileReader<R> = GeoArrowIpcReader<FileReader<R>>;
+
+/// A reader of an Arrow IPC stream.
+pub type GeoArrowIpcStreamReader<R> = GeoArrowIpcReader<StreamReader<R>>;
+
+impl<R: Read + Seek> GeoArrowIpcReader<FileReader<R>> {
+    /// Create a new reader of an Arrow IPC file.
+    pub fn try_new_file(reader: R, options: IpcReaderOptions) -> Result<Self> {
+        Self::try_new(FileReader::try_new(reader, None)?, options)
+    }
+}
+
+impl<R: Read> GeoArrowIpcReader<StreamReader<R>> {
+    /// Create a new reader of an Arrow IPC stream.
+    pub fn try_new_stream(reader: R, options: IpcReaderOptions) -> Result<Self> {
+        Self::try_new(StreamReader::try_new(reader, None)?, options)
+    }
+}
+
+impl<R: RecordBatchReader> GeoArrowIpcReader<R> {
+    fn try_new(inner: R, options: IpcReaderOptions) -> Result<Self> {
+        let input_schema = inner.schema();
+        let mut geometry_columns = geometry_columns(&input_schema)?;
+
+        let mut fields = input_schema.fields().to_vec();
+        let mut converted_columns = vec![];
+        if let Some(coord_type) = options.coord_type {
+            for column in geometry_columns.iter_mut() {
+                let data_type = column.data_type.clone().with_coord_type(coord_type);
+                if data_type == column.data_type {
+                    continue;
+                }
+                let field = input_schema.field(column.index);
+                fields[column.index] =
+                    Arc::new(data_type.to_field(field.name(), field.is_nullable()));
+                column.data_type = data_type;
+                converted_columns.push(column.clone());
+            }
+        }
+
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        ));
+        Ok(Self {
+            inner,
+            schema,
+            geometry_columns,
+            converted_columns,
+        })
+    }
+
+    /// The geometry columns of the record batches emitted by this reader.
+    ///
+    /// Use [`GeometryColumn::array`] to access a geometry column of a batch as a typed GeoArrow
+    /// array.
+    pub fn geometry_columns(&self) -> &[GeometryColumn] {
+        &self.geometry_columns
+    }
+
+    /// Consume this reader, returning the underlying Arrow IPC reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn convert(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        if self.converted_columns.is_empty() {
+            return Ok(batch);
+        }
+
+        let input_schema = batch.schema();
+        let mut columns = batch.columns().to_vec();
+        for column in &self.converted_columns {
+            let array = from_arrow_array(
+                columns[column.index].as_ref(),
+                input_schema.field(column.index),
+            )?;
+            columns[column.index] = cast(array.as_ref(), &column.data_type)?.to_array_ref();
+        }
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+impl<R: RecordBatchReader> Iterator for GeoArrowIpcReader<R> {
+    type Item = std::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.inner.next()?;
+        Some(batch.and_then(|batch| Ok(self.convert(batch)?)))
+    }
+}
+
+impl<R: RecordBatchReader> RecordBatchReader for GeoArrowIpcReader<R> {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use arrow_array::Int64Array;
+    use arrow_array::RecordBatchIterator;
+    use arrow_schema::{DataType, Field};
+    use geoarrow_array::GeoArrowArray;
+    use geoarrow_schema::Dimension;
+
+    use super::*;
+    use crate::writer::{IpcWriterOptions, write_ipc_file, write_ipc_stream};
+
+    fn batch() -> RecordBatch {
+        let geometry = geoarrow_array::test::point::array(CoordType::Interleaved, Dimension::XY);
+        let ids = Int64Array::from_iter_values(0..geometry.len() as i64);
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            geometry.data_type().to_field("geometry", true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(ids), geometry.to_array_ref()],
+        )
+        .unwrap()
+    }
+
+    fn batch_reader() -> impl RecordBatchReader {
+        let batch = batch();
+        RecordBatchIterator::new(vec![Ok(batch.clone())], batch.schema())
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let mut buffer = vec![];
+        write_ipc_file(batch_reader(), &mut buffer, IpcWriterOptions::default()).unwrap();
+
+        let reader =
+            GeoArrowIpcFileReader::try_new_file(Cursor::new(buffer), Default::default()).unwrap();
+        let columns = reader.geometry_columns().to_vec();
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0].index, 1);
+
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches, vec![batch()]);
+        let array = columns[0].array(&batches[0]).unwrap();
+        assert_eq!(array.data_type().coord_type(), Some(CoordType::Interleaved));
+    }
+
+    #[test]
+    fn stream_convert_coord_type() {
+        let mut buffer = vec![];
+        write_ipc_stream(batch_reader(), &mut buffer, IpcWriterOptions::default()).unwrap();
+
+        let options = IpcReaderOptions {
+            coord_type: Some(CoordType::Separated),
+        };
+        let reader = GeoArrowIpcStreamReader::try_new_stream(buffer.as_slice(), options).unwrap();
+        let schema = reader.schema();
+        let column = reader.geometry_columns()[0].clone();
+        assert_eq!(column.data_type.coord_type(), Some(CoordType::Separated));
+
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches[0].schema(), schema);
+        let array = column.array(&batches[0]).unwrap();
+        assert_eq!(array.data_type().coord_type(), Some(CoordType::Separated));
+        assert_eq!(array.len(), batch().num_rows());
+    }
+
+    #[cfg(feature = "ipc_compression")]
+    #[test]
+    fn compression() {
+        use crate::writer::CompressionType;
+
+        for compression in [CompressionType::LZ4_FRAME, CompressionType::ZSTD] {
+            let mut buffer = vec![];
+            let options = IpcWriterOptions {
+                compression: Some(compression),
+            };
+            write_ipc_file(batch_reader(), &mut buffer, options).unwrap();
+
+            let reader =
+                GeoArrowIpcFileReader::try_new_file(Cursor::new(buffer), Default::default())
+                    .unwrap();
+            let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+            assert_eq!(batches, vec![batch()]);
+        }
+    }
+}
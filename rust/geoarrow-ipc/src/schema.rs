@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_schema::{Field, Schema};
+use geoarrow_array::array::from_arrow_array;
+use geoarrow_array::error::{GeoArrowError, Result};
+use geoarrow_array::{GeoArrowArray, GeoArrowType};
+use geoarrow_schema::{Crs, CrsType};
+use serde_json::Value;
+
+/// A GeoArrow geometry column of a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeometryColumn {
+    /// The index of the column in the schema.
+    pub index: usize,
+
+    /// The GeoArrow type of the column.
+    pub data_type: GeoArrowType,
+}
+
+impl GeometryColumn {
+    /// Access this column of a record batch as a GeoArrow array.
+    pub fn array(&self, batch: &RecordBatch) -> Result<Arc<dyn GeoArrowArray>> {
+        from_arrow_array(
+            batch.column(self.index).as_ref(),
+            batch.schema_ref().field(self.index),
+        )
+    }
+}
+
+/// Find the GeoArrow geometry columns of a schema.
+///
+/// Geometry columns are identified by a `geoarrow.*` extension name. This returns an error if a
+/// geometry column has an unsupported storage type for its extension type, or if its extension
+/// metadata or CRS is malformed.
+pub fn geometry_columns(schema: &Schema) -> Result<Vec<GeometryColumn>> {
+    let mut columns = vec![];
+    for (index, field) in schema.fields().iter().enumerate() {
+        if !is_geoarrow_extension(field) {
+            continue;
+        }
+        let data_type = GeoArrowType::try_from(field.as_ref()).map_err(|err| {
+            GeoArrowError::General(format!("Invalid geometry column '{}': {err}", field.name()))
+        })?;
+        validate_crs(data_type.metadata().crs()).map_err(|err| {
+            GeoArrowError::General(format!("Invalid geometry column '{}': {err}", field.name()))
+        })?;
+        columns.push(GeometryColumn { index, data_type });
+    }
+    Ok(columns)
+}
+
+fn is_geoarrow_extension(field: &Field) -> bool {
+    field
+        .extension_type_name()
+        .is_some_and(|name| name.starts_with("geoarrow.") || name == "ogc.wkb")
+}
+
+/// Check that the CRS value matches the representation given by its CRS type.
+fn validate_crs(crs: &Crs) -> Result<()> {
+    match (crs.crs_type(), crs.crs_value()) {
+        (None, None) => Ok(()),
+        (Some(crs_type), None) => Err(GeoArrowError::General(format!(
+            "crs_type is {crs_type:?} but no crs is set"
+        ))),
+        (None, Some(Value::String(_) | Value::Object(_))) => Ok(()),
+        (Some(CrsType::Projjson), Some(Value::Object(_))) => Ok(()),
+        (Some(CrsType::Wkt2_2019 | CrsType::Srid), Some(Value::String(_))) => Ok(()),
+        (Some(CrsType::AuthorityCode), Some(Value::String(code)))
+            if code
+                .split_once(':')
+                .is_some_and(|(authority, code)| !authority.is_empty() && !code.is_empty()) =>
+        {
+            Ok(())
+        }
+        (crs_type, Some(value)) => Err(GeoArrowError::General(format!(
+            "crs {value} is not a valid value for crs_type {crs_type:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use arrow_schema::DataType;
+    use geoarrow_schema::{CoordType, Dimension};
+
+    use super::*;
+
+    fn point_field(metadata: &str) -> Field {
+        let array = geoarrow_array::test::point::array(CoordType::Interleaved, Dimension::XY);
+        let field = array.data_type().to_field("geometry", true);
+        Field::new("geometry", field.data_type().clone(), true).with_metadata(HashMap::from([
+            (
+                "ARROW:extension:name".to_string(),
+                "geoarrow.point".to_string(),
+            ),
+            ("ARROW:extension:metadata".to_string(), metadata.to_string()),
+        ]))
+    }
+
+    #[test]
+    fn discover() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            point_field(r#"{"crs": "EPSG:4326", "crs_type": "authority_code"}"#),
+        ]);
+        let columns = geometry_columns(&schema).unwrap();
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0].index, 1);
+    }
+
+    #[test]
+    fn malformed_crs() {
+        for metadata in [
+            r#"{"crs": 4326}"#,
+            r#"{"crs": "EPSG:4326", "crs_type": "projjson"}"#,
+            r#"{"crs": "4326", "crs_type": "authority_code"}"#,
+            r#"{"crs_type": "wkt2:2019"}"#,
+            "not json",
+        ] {
+            let schema = Schema::new(vec![point_field(metadata)]);
+            assert!(geometry_columns(&schema).is_err(), "{metadata}");
+        }
+    }
+
+    #[test]
+    fn unsupported_storage() {
+        let field = Field::new("geometry", DataType::Int64, true).with_metadata(HashMap::from([(
+            "ARROW:extension:name".to_string(),
+            "geoarrow.point".to_string(),
+        )]));
+        assert!(geometry_columns(&Schema::new(vec![field])).is_err());
+    }
+}
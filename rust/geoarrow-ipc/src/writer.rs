@@ -0,0 +1,64 @@
+use std::io::Write;
+
+use arrow_array::RecordBatchReader;
+pub use arrow_ipc::CompressionType;
+use arrow_ipc::writer::{FileWriter, IpcWriteOptions, StreamWriter};
+use geoarrow_array::error::Result;
+
+use crate::schema::geometry_columns;
+
+/// Options for writing GeoArrow data to Arrow IPC.
+#[derive(Debug, Clone, Default)]
+pub struct IpcWriterOptions {
+    /// The codec used to compress record batch buffers.
+    ///
+    /// Writing compressed buffers requires the `ipc_compression` feature. If `None`, buffers are
+    /// not compressed.
+    pub compression: Option<CompressionType>,
+}
+
+impl IpcWriterOptions {
+    fn ipc_write_options(&self) -> Result<IpcWriteOptions> {
+        Ok(IpcWriteOptions::default().try_with_compression(self.compression)?)
+    }
+}
+
+/// Write record batches to an Arrow IPC file.
+///
+/// The geometry columns of the schema are validated before anything is written.
+pub fn write_ipc_file<W: Write>(
+    reader: impl RecordBatchReader,
+    writer: W,
+    options: IpcWriterOptions,
+) -> Result<()> {
+    let schema = reader.schema();
+    geometry_columns(&schema)?;
+
+    let mut writer =
+        FileWriter::try_new_with_options(writer, &schema, options.ipc_write_options()?)?;
+    for batch in reader {
+        writer.write(&batch?)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Write record batches to an Arrow IPC stream.
+///
+/// The geometry columns of the schema are validated before anything is written.
+pub fn write_ipc_stream<W: Write>(
+    reader: impl RecordBatchReader,
+    writer: W,
+    options: IpcWriterOptions,
+) -> Result<()> {
+    let schema = reader.schema();
+    geometry_columns(&schema)?;
+
+    let mut writer =
+        StreamWriter::try_new_with_options(writer, &schema, options.ipc_write_options()?)?;
+    for batch in reader {
+        writer.write(&batch?)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
@@ -118,6 +118,29 @@ impl Crs {
         self.crs.as_ref()
     }
 
+    /// The SRID of this CRS, when it is an SRID or an `EPSG` authority code, including the `id`
+    /// member of PROJJSON.
+    ///
+    /// PostGIS SRIDs generally match EPSG codes, so this is the SRID to use in EWKB.
+    pub fn srid(&self) -> Option<i32> {
+        match (self.crs_type, self.crs.as_ref()) {
+            (Some(CrsType::Projjson), Some(Value::Object(projjson))) => projjson
+                .get("id")
+                .filter(|id| {
+                    id.get("authority")
+                        .and_then(|authority| authority.as_str())
+                        .is_some_and(|authority| authority.eq_ignore_ascii_case("EPSG"))
+                })
+                .and_then(|id| id.get("code")?.as_i64()?.try_into().ok()),
+            (Some(CrsType::Srid), Some(Value::String(value))) => value.parse().ok(),
+            (_, Some(Value::String(value))) => value
+                .split_once(':')
+                .filter(|(authority, _)| authority.eq_ignore_ascii_case("EPSG"))
+                .and_then(|(_, code)| code.parse().ok()),
+            _ => None,
+        }
+    }
+
     /// Return `true` if we should include a CRS key in the GeoArrow metadata
     pub(crate) fn should_serialize(&self) -> bool {
         self.crs.is_some()
@@ -222,6 +245,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn srid() {
+        assert_eq!(Crs::default().srid(), None);
+        assert_eq!(Crs::from_srid("3857".to_string()).srid(), Some(3857));
+        assert_eq!(
+            Crs::from_authority_code("EPSG:4326".to_string()).srid(),
+            Some(4326)
+        );
+        assert_eq!(
+            Crs::from_authority_code("ESRI:102003".to_string()).srid(),
+            None
+        );
+        assert_eq!(
+            Crs::from_projjson(json!({
+                "id": {"authority": "EPSG", "code": 32615}
+            }))
+            .srid(),
+            Some(32615)
+        );
+    }
+
     #[test]
     fn crs_unknown() {
         let crs = Crs::from_unknown_crs_type("1234".to_string());
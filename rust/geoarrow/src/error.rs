@@ -69,10 +69,33 @@ pub enum GeoArrowError {
     #[error(transparent)]
     FlatgeobufError(#[from] flatgeobuf::Error),
 
+    /// [prost::DecodeError]
+    #[cfg(any(feature = "geobuf", feature = "osm"))]
+    #[error(transparent)]
+    GeobufError(#[from] prost::DecodeError),
+
     /// [std::io::Error]
     #[error(transparent)]
     IOError(#[from] std::io::Error),
 
+    /// [quick_xml::Error]
+    #[cfg(any(feature = "gml", feature = "gpx", feature = "kml"))]
+    #[error(transparent)]
+    QuickXmlError(#[from] quick_xml::Error),
+
+    /// [rusqlite::Error]
+    #[cfg(feature = "geopackage")]
+    #[error(transparent)]
+    RusqliteError(#[from] rusqlite::Error),
+
+    /// [dbase::Error]
+    #[error(transparent)]
+    DbaseError(#[from] dbase::Error),
+
+    /// [shapefile::Error]
+    #[error(transparent)]
+    ShapefileError(#[from] shapefile::Error),
+
     /// [serde_json::Error]
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
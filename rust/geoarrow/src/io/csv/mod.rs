@@ -36,9 +36,26 @@
 //! // it to a geoarrow Table
 //! let table = Table::try_from(Box::new(reader) as Box<dyn arrow_array::RecordBatchReader>).unwrap();
 //! ```
+//!
+//! Points can also be constructed from numeric coordinate columns:
+//!
+//! ```ignore
+//! use std::io::Cursor;
+//!
+//! use geoarrow::io::csv::{CSVPointColumns, CSVReader, CSVReaderOptions};
+//!
+//! let s = "name,lon,lat\nSeattle,-122.33,47.61\nPortland,-122.68,45.52";
+//!
+//! let options = CSVReaderOptions {
+//!     point_columns: Some(CSVPointColumns::new("lon", "lat")),
+//!     drop_point_columns: true,
+//!     ..Default::default()
+//! };
+//! let reader = CSVReader::try_new(Cursor::new(s), options).unwrap();
+//! ```
 
-pub use reader::{CSVReader, CSVReaderOptions};
-pub use writer::write_csv;
+pub use reader::{CSVGeometryEncoding, CSVPointColumns, CSVReader, CSVReaderOptions};
+pub use writer::{CSVWriterOptions, write_csv};
 
 mod reader;
 mod writer;
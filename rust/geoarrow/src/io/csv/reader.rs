@@ -1,16 +1,58 @@
 use arrow::array::AsArray;
-use arrow_array::RecordBatch;
+use arrow::datatypes::Float64Type;
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_buffer::{NullBuffer, ScalarBuffer};
 use arrow_csv::ReaderBuilder;
 use arrow_csv::reader::Format;
-use arrow_schema::{ArrowError, Schema, SchemaRef};
-use geoarrow_schema::{CoordType, GeometryType};
+use arrow_schema::{ArrowError, DataType, Schema, SchemaRef};
+use geoarrow_array::IntoArrow;
+use geoarrow_array::cast::wkb_from_hex;
+use geoarrow_schema::{CoordType, Crs, Dimension, GeometryType, Metadata, PointType};
 use std::io::{Read, Seek};
 use std::sync::Arc;
 
-use crate::array::WKTArray;
+use crate::ArrayBase;
+use crate::array::{CoordBuffer, PointArray, SeparatedCoordBuffer, WKBArray, WKTArray};
+use crate::datatypes::NativeType;
 use crate::error::{GeoArrowError, Result};
+use crate::io::wkb::from_wkb;
 use crate::io::wkt::read_wkt;
 
+/// The encoding of the geometry column of a CSV file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CSVGeometryEncoding {
+    /// Geometries are encoded as WKT strings.
+    #[default]
+    WKT,
+
+    /// Geometries are encoded as hex strings of ISO WKB or EWKB.
+    HexWKB,
+}
+
+/// The names of the columns that hold the coordinates of points in a CSV file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CSVPointColumns {
+    /// The name of the column of x coordinates, such as `"lon"`.
+    pub x: String,
+
+    /// The name of the column of y coordinates, such as `"lat"`.
+    pub y: String,
+
+    /// The name of the column of z coordinates, if any.
+    pub z: Option<String>,
+}
+
+impl CSVPointColumns {
+    /// Create new point columns from the names of the x and y columns.
+    pub fn new(x: impl Into<String>, y: impl Into<String>) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+            z: None,
+        }
+    }
+}
+
 /// Options for the CSV reader.
 #[derive(Debug, Clone)]
 pub struct CSVReaderOptions {
@@ -25,6 +67,21 @@ pub struct CSVReaderOptions {
     /// Defaults to `"geometry"`
     pub geometry_column_name: Option<String>,
 
+    /// The encoding of the geometry column, defaults to WKT.
+    pub geometry_encoding: CSVGeometryEncoding,
+
+    /// Construct points from numeric coordinate columns instead of parsing a geometry column.
+    ///
+    /// The points are added as a new column named `"geometry"` at the end of the schema.
+    pub point_columns: Option<CSVPointColumns>,
+
+    /// Whether to remove the coordinate columns from the output when constructing points from
+    /// [`point_columns`][Self::point_columns], defaults to `false`.
+    pub drop_point_columns: bool,
+
+    /// The CRS to attach to the geometry column.
+    pub crs: Option<Crs>,
+
     /// Specify whether the CSV file has a header, defaults to `true`
     ///
     /// When `true`, the first row of the CSV file is treated as a header row
@@ -89,6 +146,10 @@ impl Default for CSVReaderOptions {
             coord_type: CoordType::Interleaved,
             batch_size: 65_536,
             geometry_column_name: Default::default(),
+            geometry_encoding: Default::default(),
+            point_columns: Default::default(),
+            drop_point_columns: false,
+            crs: Default::default(),
             has_header: Default::default(),
             max_records: Default::default(),
             delimiter: Default::default(),
@@ -100,26 +161,40 @@ impl Default for CSVReaderOptions {
     }
 }
 
-/// Returns (Schema, records_read, geometry column name)
+/// Returns (Schema, records_read)
 ///
 /// Note that the geometry column in the Schema is still left as a String.
-fn infer_csv_schema(
-    reader: impl Read,
-    options: &CSVReaderOptions,
-) -> Result<(SchemaRef, usize, String)> {
+fn infer_csv_schema(reader: impl Read, options: &CSVReaderOptions) -> Result<(SchemaRef, usize)> {
     let format = options.to_format();
     let (schema, records_read) = format.infer_schema(reader, options.max_records)?;
+    Ok((Arc::new(schema), records_read))
+}
 
-    let geometry_col_name = find_geometry_column(&schema, options.geometry_column_name.as_deref())?;
-
-    Ok((Arc::new(schema), records_read, geometry_col_name))
+/// Where the geometries of a CSV file are read from.
+#[derive(Debug, Clone, Copy)]
+enum GeometrySource {
+    /// A string column of geometries, which is replaced with the parsed geometries.
+    Column {
+        index: usize,
+        encoding: CSVGeometryEncoding,
+    },
+    /// Numeric coordinate columns, which are used to construct a new point column.
+    Points {
+        x: usize,
+        y: usize,
+        z: Option<usize>,
+    },
 }
 
-/// A CSV reader that parses a WKT-encoded geometry column
+/// A CSV reader that parses a WKT or WKB-encoded geometry column, or constructs points from
+/// coordinate columns.
 pub struct CSVReader<R> {
     reader: arrow_csv::Reader<R>,
     output_schema: SchemaRef,
-    geometry_column_index: usize,
+    source: GeometrySource,
+    geometry_type: NativeType,
+    /// The indices of the input columns retained in the output.
+    retained_columns: Vec<usize>,
     coord_type: CoordType,
 }
 
@@ -137,8 +212,7 @@ impl<R: Read + Seek> CSVReader<R> {
     /// schema. If your data is large, you can limit the number of records scanned
     /// with the [CSVReaderOptions].
     pub fn try_new(mut reader: R, options: CSVReaderOptions) -> Result<Self> {
-        let (schema, _read_records, _geometry_column_name) =
-            infer_csv_schema(&mut reader, &options)?;
+        let (schema, _read_records) = infer_csv_schema(&mut reader, &options)?;
         reader.rewind()?;
 
         Self::try_new_with_schema(reader, schema, options)
@@ -148,7 +222,8 @@ impl<R: Read + Seek> CSVReader<R> {
 impl<R: Read> CSVReader<R> {
     /// Read a CSV file to a [RecordBatchReader].
     ///
-    /// This expects a geometry to be encoded as WKT within one column.
+    /// This expects a geometry to be encoded as WKT or hex WKB within one column, or coordinates to
+    /// be stored in numeric columns given by [`CSVReaderOptions::point_columns`].
     ///
     /// Note that the input required here is [`Read`] and not [`Read`] + [`Seek`][std::io::Seek]. This
     /// means that you must infer the schema yourself before calling this function. This allows using
@@ -162,30 +237,85 @@ impl<R: Read> CSVReader<R> {
         schema: SchemaRef,
         options: CSVReaderOptions,
     ) -> Result<Self> {
-        let geometry_column_name =
-            find_geometry_column(schema.as_ref(), options.geometry_column_name.as_deref())?;
-        let geometry_column_index = schema.index_of(&geometry_column_name)?;
+        let metadata = Arc::new(Metadata::new(options.crs.clone().unwrap_or_default(), None));
+        let mut input_fields = schema.fields().to_vec();
+        let mut output_fields = schema.fields().to_vec();
+        let mut retained_columns = (0..input_fields.len()).collect::<Vec<_>>();
+
+        let (source, geometry_type) = if let Some(point_columns) = &options.point_columns {
+            let x = find_column(schema.as_ref(), &point_columns.x)?;
+            let y = find_column(schema.as_ref(), &point_columns.y)?;
+            let z = point_columns
+                .z
+                .as_deref()
+                .map(|z| find_column(schema.as_ref(), z))
+                .transpose()?;
+            // Parse coordinates as floats, even if they were inferred as integers
+            for index in [Some(x), Some(y), z].into_iter().flatten() {
+                input_fields[index] = Arc::new(
+                    input_fields[index]
+                        .as_ref()
+                        .clone()
+                        .with_data_type(DataType::Float64),
+                );
+                output_fields[index] = input_fields[index].clone();
+            }
+            if options.drop_point_columns {
+                retained_columns.retain(|index| ![Some(x), Some(y), z].contains(&Some(*index)));
+            }
+
+            let dim = if z.is_some() {
+                Dimension::XYZ
+            } else {
+                Dimension::XY
+            };
+            output_fields = retained_columns
+                .iter()
+                .map(|index| output_fields[*index].clone())
+                .collect();
+            let geometry_type =
+                NativeType::Point(PointType::new(options.coord_type, dim, metadata));
+            output_fields.push(geometry_type.to_field("geometry", true).into());
+            (GeometrySource::Points { x, y, z }, geometry_type)
+        } else {
+            let geometry_column_name =
+                find_geometry_column(schema.as_ref(), options.geometry_column_name.as_deref())?;
+            let index = schema.index_of(&geometry_column_name)?;
+            // Geometries are always strings, even if they look like numbers
+            input_fields[index] = Arc::new(
+                input_fields[index]
+                    .as_ref()
+                    .clone()
+                    .with_data_type(DataType::Utf8),
+            );
+            let geometry_type =
+                NativeType::Geometry(GeometryType::new(options.coord_type, metadata));
+            output_fields[index] = geometry_type.to_field("geometry", true).into();
+            let source = GeometrySource::Column {
+                index,
+                encoding: options.geometry_encoding,
+            };
+            (source, geometry_type)
+        };
 
         // Transform to output schema
-        let mut output_fields = schema.fields().to_vec();
-        output_fields[geometry_column_index] =
-            GeometryType::new(options.coord_type, Default::default())
-                .to_field("geometry", true)
-                .into();
+        let input_schema =
+            Arc::new(Schema::new(input_fields).with_metadata(schema.metadata().clone()));
         let output_schema =
             Arc::new(Schema::new(output_fields).with_metadata(schema.metadata().clone()));
-        let output_schema2 = output_schema.clone();
 
         // Create builder
-        let builder = ReaderBuilder::new(schema)
+        let builder = ReaderBuilder::new(input_schema)
             .with_format(options.to_format())
             .with_batch_size(options.batch_size);
 
         let reader = builder.build(reader)?;
         Ok(Self {
             reader,
-            output_schema: output_schema2,
-            geometry_column_index,
+            output_schema,
+            source,
+            geometry_type,
+            retained_columns,
             coord_type: options.coord_type,
         })
     }
@@ -195,15 +325,11 @@ impl<R: Read> Iterator for CSVReader<R> {
     type Item = std::result::Result<RecordBatch, ArrowError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let x = &mut self.reader;
-        x.next().map(move |batch| {
-            parse_batch(
-                batch,
-                self.output_schema.clone(),
-                self.geometry_column_index,
-                self.coord_type,
-            )
-        })
+        let batch = self.reader.next()?;
+        Some(batch.and_then(|batch| {
+            self.parse_batch(batch)
+                .map_err(|err| ArrowError::from_external_error(Box::new(err)))
+        }))
     }
 }
 
@@ -213,24 +339,93 @@ impl<R: Read> arrow_array::RecordBatchReader for CSVReader<R> {
     }
 }
 
-fn parse_batch(
-    batch: std::result::Result<RecordBatch, ArrowError>,
-    output_schema: SchemaRef,
-    geometry_column_index: usize,
-    coord_type: CoordType,
-) -> std::result::Result<RecordBatch, ArrowError> {
-    let batch = batch?;
-    let column = batch.column(geometry_column_index);
-    let str_col = column.as_string::<i32>();
-    let wkt_arr = WKTArray::new(str_col.clone(), Default::default());
-    let geom_arr = read_wkt(&wkt_arr, coord_type, true)
-        .map_err(|err| ArrowError::from_external_error(Box::new(err)))?;
-
-    // Replace column in record batch
-    let mut columns = batch.columns().to_vec();
-    columns[geometry_column_index] = geom_arr.to_array_ref();
-
-    RecordBatch::try_new(output_schema, columns)
+impl<R> CSVReader<R> {
+    fn parse_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let metadata = self.geometry_type.metadata().clone();
+
+        match self.source {
+            GeometrySource::Column { index, encoding } => {
+                let str_col = batch.column(index).as_string::<i32>();
+                let geom_arr = match encoding {
+                    CSVGeometryEncoding::WKT => {
+                        let wkt_arr = WKTArray::new(str_col.clone(), metadata);
+                        read_wkt(&wkt_arr, self.coord_type, true)?
+                    }
+                    CSVGeometryEncoding::HexWKB => {
+                        let binary_arr = wkb_from_hex(str_col, Default::default())
+                            .map_err(|err| GeoArrowError::General(err.to_string()))?
+                            .into_arrow();
+                        let wkb_arr = WKBArray::new(binary_arr, metadata);
+                        from_wkb(&wkb_arr, self.geometry_type.clone(), true)?
+                    }
+                };
+
+                // Replace column in record batch
+                let mut columns = batch.columns().to_vec();
+                columns[index] = geom_arr.to_array_ref();
+                Ok(RecordBatch::try_new(self.output_schema.clone(), columns)?)
+            }
+            GeometrySource::Points { x, y, z } => {
+                let mut columns = self
+                    .retained_columns
+                    .iter()
+                    .map(|index| batch.column(*index).clone())
+                    .collect::<Vec<_>>();
+                let points = build_points(
+                    batch.column(x),
+                    batch.column(y),
+                    z.map(|z| batch.column(z)),
+                    metadata,
+                )?;
+                columns.push(points.into_coord_type(self.coord_type).into_array_ref());
+                Ok(RecordBatch::try_new(self.output_schema.clone(), columns)?)
+            }
+        }
+    }
+}
+
+/// Construct points from Float64 coordinate columns. A point is null if any of its coordinates
+/// are null.
+fn build_points(
+    x: &ArrayRef,
+    y: &ArrayRef,
+    z: Option<&ArrayRef>,
+    metadata: Arc<Metadata>,
+) -> Result<PointArray> {
+    let x = x.as_primitive::<Float64Type>();
+    let y = y.as_primitive::<Float64Type>();
+    let z = z.map(|z| z.as_primitive::<Float64Type>());
+
+    let mut validity = NullBuffer::union(x.nulls(), y.nulls());
+    let mut buffers = [
+        x.values().clone(),
+        y.values().clone(),
+        ScalarBuffer::from(vec![]),
+        ScalarBuffer::from(vec![]),
+    ];
+    let dim = if let Some(z) = z {
+        validity = NullBuffer::union(validity.as_ref(), z.nulls());
+        buffers[2] = z.values().clone();
+        Dimension::XYZ
+    } else {
+        Dimension::XY
+    };
+
+    let coords = SeparatedCoordBuffer::try_new(buffers, dim)?;
+    Ok(PointArray::new(
+        CoordBuffer::Separated(coords),
+        validity,
+        metadata,
+    ))
+}
+
+fn find_column(schema: &Schema, name: &str) -> Result<usize> {
+    schema.index_of(name).map_err(|_| {
+        GeoArrowError::General(format!(
+            "CSV coordinate column specified to have name '{}' but no such column found",
+            name
+        ))
+    })
 }
 
 fn find_geometry_column(schema: &Schema, geometry_column_name: Option<&str>) -> Result<String> {
@@ -259,3 +454,76 @@ fn find_geometry_column(schema: &Schema, geometry_column_name: Option<&str>) ->
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use arrow_array::cast::AsArray;
+
+    use super::*;
+    use crate::array::{AsNativeArray, NativeArrayDyn};
+    use crate::trait_::{ArrayAccessor, NativeArray};
+    use geo_traits::{CoordTrait, PointTrait};
+
+    #[test]
+    fn point_columns() {
+        let s = "name,lon,lat,elevation\nSeattle,-122.5,47.5,50\nPortland,,45.5,15";
+        let options = CSVReaderOptions {
+            point_columns: Some(CSVPointColumns {
+                z: Some("elevation".to_string()),
+                ..CSVPointColumns::new("lon", "lat")
+            }),
+            drop_point_columns: true,
+            crs: Some(Crs::from_authority_code("EPSG:4326".to_string())),
+            ..Default::default()
+        };
+        let reader = CSVReader::try_new(Cursor::new(s), options).unwrap();
+        let schema = reader.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["name", "geometry"]);
+
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        let array =
+            NativeArrayDyn::from_arrow_array(batches[0].column(1), schema.field(1)).unwrap();
+        assert_eq!(
+            array.as_ref().data_type().metadata().crs(),
+            &Crs::from_authority_code("EPSG:4326".to_string())
+        );
+        let points = array.as_ref().as_point();
+        let coord = points.value(0).coord().unwrap();
+        assert_eq!(
+            (coord.x(), coord.y(), coord.nth_or_panic(2)),
+            (-122.5, 47.5, 50.)
+        );
+        assert!(points.get(1).is_none());
+    }
+
+    #[test]
+    fn hex_wkb() {
+        // POINT(1 2) as ISO WKB, and with SRID 4326 as EWKB
+        let s = "id,geometry\n1,0101000000000000000000F03F0000000000000040\n2,0101000020E6100000000000000000F03F0000000000000040\n3,";
+        let options = CSVReaderOptions {
+            geometry_encoding: CSVGeometryEncoding::HexWKB,
+            ..Default::default()
+        };
+        let reader = CSVReader::try_new(Cursor::new(s), options).unwrap();
+        let schema = reader.schema();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        let array =
+            NativeArrayDyn::from_arrow_array(batches[0].column(1), schema.field(1)).unwrap();
+        assert_eq!(array.as_ref().len(), 3);
+        assert_eq!(batches[0].column(1).null_count(), 1);
+        assert_eq!(
+            batches[0]
+                .column(0)
+                .as_primitive::<arrow::datatypes::Int64Type>()
+                .values(),
+            &[1, 2, 3]
+        );
+    }
+}
@@ -1,29 +1,47 @@
-use crate::array::NativeArrayDyn;
-use crate::error::Result;
+use crate::array::{AsNativeArray, NativeArrayDyn};
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::csv::CSVPointColumns;
 use crate::io::stream::RecordBatchReader;
 use crate::io::wkt::ToWKT;
+use crate::trait_::ArrayAccessor;
 use crate::{ArrayBase, NativeArray};
-use arrow_array::RecordBatch;
-use arrow_schema::Schema;
+use arrow_array::{ArrayRef, Float64Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+use geo_traits::{CoordTrait, PointTrait};
+use geoarrow_schema::Dimension;
 use std::io::Write;
 use std::sync::Arc;
 
-// TODO: add CSV writer options
+/// Options for the CSV writer.
+#[derive(Debug, Clone, Default)]
+pub struct CSVWriterOptions {
+    /// Write point geometries as separate coordinate columns with these names, rather than as
+    /// WKT.
+    ///
+    /// Geometry columns must then contain points. The z column is only written for points with a
+    /// Z dimension, and must be named in that case.
+    pub point_columns: Option<CSVPointColumns>,
+}
 
 /// Write a Table to CSV
-pub fn write_csv<W: Write, S: Into<RecordBatchReader>>(stream: S, writer: W) -> Result<()> {
+pub fn write_csv<W: Write, S: Into<RecordBatchReader>>(
+    stream: S,
+    writer: W,
+    options: CSVWriterOptions,
+) -> Result<()> {
     let stream: RecordBatchReader = stream.into();
     let reader = stream.into_inner();
 
     let mut csv_writer = arrow_csv::Writer::new(writer);
     for batch in reader {
-        csv_writer.write(&encode_batch(batch?)?)?;
+        csv_writer.write(&encode_batch(batch?, &options)?)?;
     }
 
     Ok(())
 }
 
-fn encode_batch(batch: RecordBatch) -> Result<RecordBatch> {
+fn encode_batch(batch: RecordBatch, options: &CSVWriterOptions) -> Result<RecordBatch> {
     let schema = batch.schema();
     let fields = schema.fields();
 
@@ -32,9 +50,16 @@ fn encode_batch(batch: RecordBatch) -> Result<RecordBatch> {
 
     for (field, column) in schema.fields().iter().zip(batch.columns()) {
         if let Ok(arr) = NativeArrayDyn::from_arrow_array(&column, field) {
-            let wkt_arr = arr.as_ref().to_wkt::<i32>()?;
-            new_fields.push(wkt_arr.extension_field());
-            new_columns.push(wkt_arr.into_array_ref());
+            if let Some(point_columns) = &options.point_columns {
+                for (field, column) in encode_point_columns(arr.as_ref(), point_columns)? {
+                    new_fields.push(field);
+                    new_columns.push(column);
+                }
+            } else {
+                let wkt_arr = arr.as_ref().to_wkt::<i32>()?;
+                new_fields.push(wkt_arr.extension_field());
+                new_columns.push(wkt_arr.into_array_ref());
+            }
         } else {
             new_fields.push(field.clone());
             new_columns.push(column.clone());
@@ -47,6 +72,49 @@ fn encode_batch(batch: RecordBatch) -> Result<RecordBatch> {
     )?)
 }
 
+/// Split a point array into its coordinate columns.
+fn encode_point_columns(
+    arr: &dyn NativeArray,
+    point_columns: &CSVPointColumns,
+) -> Result<Vec<(Arc<Field>, ArrayRef)>> {
+    let NativeType::Point(point_type) = arr.data_type() else {
+        return Err(GeoArrowError::General(format!(
+            "Writing coordinate columns to CSV requires point geometries, found {:?}",
+            arr.data_type()
+        )));
+    };
+    let mut names = vec![&point_columns.x, &point_columns.y];
+    match (point_type.dimension(), &point_columns.z) {
+        (Dimension::XY, _) => (),
+        (Dimension::XYZ, Some(z)) => names.push(z),
+        (Dimension::XYZ, None) => {
+            return Err(GeoArrowError::General(
+                "Writing points with Z values to CSV requires a z column name".to_string(),
+            ));
+        }
+        (dim, _) => {
+            return Err(GeoArrowError::NotYetImplemented(format!(
+                "Writing {:?} points to CSV coordinate columns",
+                dim
+            )));
+        }
+    }
+
+    let point_arr = arr.as_point();
+    Ok(names
+        .into_iter()
+        .enumerate()
+        .map(|(n, name)| {
+            let values = point_arr
+                .iter()
+                .map(|point| point.and_then(|point| Some(point.coord()?.nth_or_panic(n))))
+                .collect::<Float64Array>();
+            let field = Arc::new(Field::new(name, DataType::Float64, true));
+            (field, Arc::new(values) as ArrayRef)
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -59,8 +127,22 @@ mod test {
 
         let mut output_buffer = Vec::new();
         let writer = BufWriter::new(&mut output_buffer);
-        write_csv(&table, writer).unwrap();
+        write_csv(&table, writer, Default::default()).unwrap();
         let output_string = String::from_utf8(output_buffer).unwrap();
         println!("{}", output_string);
     }
+
+    #[test]
+    fn test_write_point_columns() {
+        let table = point::table();
+
+        let mut output_buffer = Vec::new();
+        let options = CSVWriterOptions {
+            point_columns: Some(CSVPointColumns::new("lon", "lat")),
+        };
+        write_csv(&table, &mut output_buffer, options).unwrap();
+        let output_string = String::from_utf8(output_buffer).unwrap();
+        assert_eq!(output_string.lines().next(), Some("u8,string,lon,lat"));
+        assert_eq!(output_string.lines().count(), 4);
+    }
 }
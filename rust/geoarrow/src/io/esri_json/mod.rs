@@ -0,0 +1,21 @@
+//! Read from and write to [Esri JSON](https://developers.arcgis.com/rest/services-reference/enterprise/featureset-object/)
+//! feature sets, as returned by ArcGIS REST services.
+
+mod reader;
+mod writer;
+
+pub use reader::{EsriJsonReaderOptions, read_esri_json};
+pub use writer::{EsriJsonWriterOptions, write_esri_json};
+
+use crate::io::parsed::Coord;
+
+/// Whether a ring is clockwise, as Esri exterior rings are.
+fn is_clockwise(ring: &[Coord]) -> bool {
+    // The sum is twice the ring's signed area, positive for clockwise rings
+    let area = ring
+        .iter()
+        .zip(ring.iter().cycle().skip(1))
+        .map(|(a, b)| (b.x - a.x) * (b.y + a.y))
+        .sum::<f64>();
+    area > 0.0
+}
@@ -0,0 +1,438 @@
+use std::io::Read;
+use std::sync::Arc;
+
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use geo::{Area, Contains, LineString, Polygon};
+use geoarrow_schema::{
+    CoordType, Crs, Dimension, Metadata, MultiLineStringType, MultiPointType, MultiPolygonType,
+    PointType, PolygonType,
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::esri_json::is_clockwise;
+use crate::io::parsed::{
+    Coord, KeyOrder, ParsedGeometry, feature_batch, infer_geometry_type, infer_properties_schema,
+    property_columns,
+};
+use crate::io::stream::{RecordBatchReader, record_batches};
+
+/// Options for the Esri JSON reader.
+#[derive(Debug, Clone)]
+pub struct EsriJsonReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+}
+
+impl Default for EsriJsonReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+        }
+    }
+}
+
+/// The members of an Esri JSON feature set that are read.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FeatureSet {
+    #[serde(default)]
+    geometry_type: Option<String>,
+    #[serde(default)]
+    spatial_reference: Option<SpatialReference>,
+    #[serde(default)]
+    has_z: bool,
+    #[serde(default)]
+    fields: Option<Vec<EsriField>>,
+    #[serde(default)]
+    features: Vec<Feature>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpatialReference {
+    #[serde(default)]
+    wkid: Option<i64>,
+    #[serde(default)]
+    latest_wkid: Option<i64>,
+    #[serde(default)]
+    wkt: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EsriField {
+    name: String,
+    #[serde(rename = "type")]
+    field_type: String,
+}
+
+#[derive(Deserialize)]
+struct Feature {
+    #[serde(default)]
+    attributes: Option<Map<String, Value>>,
+    #[serde(default)]
+    geometry: Option<Value>,
+}
+
+/// Read an Esri JSON feature set.
+///
+/// The output schema has a column for each of the feature set's `fields`, followed by a
+/// `geometry` column. Integer fields are read as `Int64`, floating point fields as `Float64`,
+/// dates as UTC timestamps, and other fields as strings. If the feature set has no `fields`,
+/// the schema is inferred from the attributes as by the GeoJSON reader.
+///
+/// Points, multipoints, polylines and polygons are read as points, multi points, multi line
+/// strings and multi polygons, and envelopes as polygons. Clockwise rings are read as exteriors
+/// and counterclockwise rings as holes of the smallest exterior containing them. The CRS is taken
+/// from the `spatialReference`, with a `wkid` below 100000 read as an EPSG code and any other as
+/// an ESRI code. M values are dropped.
+pub fn read_esri_json<R: Read>(
+    mut reader: R,
+    options: EsriJsonReaderOptions,
+) -> Result<RecordBatchReader> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let feature_set: FeatureSet = serde_json::from_str(&text)?;
+
+    let mut geometries = vec![];
+    let mut rows = vec![];
+    for feature in feature_set.features {
+        let geometry = match &feature.geometry {
+            Some(geometry) => parse_geometry(geometry, feature_set.has_z)?,
+            None => None,
+        };
+        geometries.push(geometry);
+        rows.push(Value::Object(feature.attributes.unwrap_or_default()));
+    }
+
+    let properties_schema = match &feature_set.fields {
+        Some(fields) => Arc::new(Schema::new(
+            fields
+                .iter()
+                .filter_map(|field| {
+                    let data_type = field_type(&field.field_type)?;
+                    Some(Field::new(&field.name, data_type, true))
+                })
+                .collect::<Vec<_>>(),
+        )),
+        None => {
+            let key_order: KeyOrder = serde_json::from_str(&text)?;
+            let attributes_order = key_order
+                .get("features")
+                .and_then(|features| features.get("attributes"))
+                .cloned()
+                .unwrap_or_default();
+            infer_properties_schema(&rows, &attributes_order)?
+        }
+    };
+    let columns = property_columns(properties_schema.clone(), &rows)?;
+
+    let crs = feature_set
+        .spatial_reference
+        .map(|spatial_reference| spatial_reference_crs(&spatial_reference))
+        .unwrap_or_default();
+    let metadata = Arc::new(Metadata::new(crs, None));
+    let coord_type = options.coord_type;
+    let dim = if feature_set.has_z {
+        Dimension::XYZ
+    } else {
+        Dimension::XY
+    };
+    let geometry_type = match feature_set.geometry_type.as_deref() {
+        Some("esriGeometryPoint") => NativeType::Point(PointType::new(coord_type, dim, metadata)),
+        Some("esriGeometryMultipoint") => {
+            NativeType::MultiPoint(MultiPointType::new(coord_type, dim, metadata))
+        }
+        Some("esriGeometryPolyline") => {
+            NativeType::MultiLineString(MultiLineStringType::new(coord_type, dim, metadata))
+        }
+        Some("esriGeometryPolygon") => {
+            NativeType::MultiPolygon(MultiPolygonType::new(coord_type, dim, metadata))
+        }
+        Some("esriGeometryEnvelope") => {
+            NativeType::Polygon(PolygonType::new(coord_type, dim, metadata))
+        }
+        _ => infer_geometry_type(geometries.iter().flatten(), coord_type, metadata),
+    };
+
+    let batch = feature_batch(&properties_schema, columns, &geometries, geometry_type)?;
+    Ok(record_batches(batch, options.batch_size.unwrap_or(65_536)))
+}
+
+/// The Arrow type of an Esri field type, or `None` for fields that aren't read.
+fn field_type(esri_type: &str) -> Option<DataType> {
+    let data_type = match esri_type {
+        "esriFieldTypeGeometry" | "esriFieldTypeRaster" | "esriFieldTypeBlob" => return None,
+        "esriFieldTypeOID"
+        | "esriFieldTypeSmallInteger"
+        | "esriFieldTypeInteger"
+        | "esriFieldTypeBigInteger" => DataType::Int64,
+        "esriFieldTypeSingle" | "esriFieldTypeDouble" => DataType::Float64,
+        "esriFieldTypeDate" => DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        _ => DataType::Utf8,
+    };
+    Some(data_type)
+}
+
+fn spatial_reference_crs(spatial_reference: &SpatialReference) -> Crs {
+    match (
+        spatial_reference.latest_wkid.or(spatial_reference.wkid),
+        &spatial_reference.wkt,
+    ) {
+        (Some(wkid), _) if wkid < 100_000 => Crs::from_authority_code(format!("EPSG:{}", wkid)),
+        (Some(wkid), _) => Crs::from_authority_code(format!("ESRI:{}", wkid)),
+        (None, Some(wkt)) => Crs::from_unknown_crs_type(wkt.clone()),
+        (None, None) => Crs::default(),
+    }
+}
+
+/// Parse an Esri JSON geometry, or `None` if it is empty.
+fn parse_geometry(value: &Value, has_z: bool) -> Result<Option<ParsedGeometry>> {
+    let invalid = || GeoArrowError::General(format!("Invalid Esri JSON geometry: {}", value));
+    let object = match value {
+        Value::Object(object) => object,
+        Value::Null => return Ok(None),
+        _ => return Err(invalid()),
+    };
+    // Positions are `[x, y, z, m]`, `[x, y, z]` or `[x, y, m]`, and Z values are only present if
+    // the feature set has `hasZ`
+    let position = |value: &Value| -> Result<Coord> {
+        let values = value.as_array().ok_or_else(invalid)?;
+        let number = |idx: usize| values.get(idx).and_then(Value::as_f64);
+        Ok(Coord {
+            x: number(0).ok_or_else(invalid)?,
+            y: number(1).ok_or_else(invalid)?,
+            z: if has_z { number(2) } else { None },
+        })
+    };
+    let positions = |value: &Value| -> Result<Vec<Coord>> {
+        value
+            .as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(position)
+            .collect()
+    };
+    let lines = |value: &Value| -> Result<Vec<Vec<Coord>>> {
+        value
+            .as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(positions)
+            .collect()
+    };
+    let number = |key: &str| object.get(key).and_then(Value::as_f64);
+
+    if object.contains_key("x") {
+        // Empty points have a null or NaN x
+        let (Some(x), Some(y)) = (number("x"), number("y")) else {
+            return Ok(None);
+        };
+        if x.is_nan() {
+            return Ok(None);
+        }
+        let z = number("z");
+        return Ok(Some(ParsedGeometry::Point(Coord { x, y, z })));
+    }
+    if let Some(points) = object.get("points") {
+        return Ok(Some(ParsedGeometry::MultiPoint(positions(points)?)));
+    }
+    if let Some(paths) = object.get("paths") {
+        let mut paths = lines(paths)?;
+        let geometry = if paths.len() == 1 {
+            ParsedGeometry::LineString(paths.remove(0))
+        } else {
+            ParsedGeometry::MultiLineString(paths)
+        };
+        return Ok(Some(geometry));
+    }
+    if let Some(rings) = object.get("rings") {
+        return Ok(Some(polygons(lines(rings)?)));
+    }
+    if object.contains_key("xmin") {
+        let (Some(xmin), Some(ymin), Some(xmax), Some(ymax)) = (
+            number("xmin"),
+            number("ymin"),
+            number("xmax"),
+            number("ymax"),
+        ) else {
+            return Ok(None);
+        };
+        let coord = |x, y| Coord { x, y, z: None };
+        return Ok(Some(ParsedGeometry::Polygon(vec![vec![
+            coord(xmin, ymin),
+            coord(xmin, ymax),
+            coord(xmax, ymax),
+            coord(xmax, ymin),
+            coord(xmin, ymin),
+        ]])));
+    }
+    if object.contains_key("curvePaths") || object.contains_key("curveRings") {
+        return Err(GeoArrowError::General(
+            "Esri JSON curves are not supported".to_string(),
+        ));
+    }
+    Err(invalid())
+}
+
+/// Group rings into polygons by their orientation.
+///
+/// Each clockwise ring is an exterior, and each counterclockwise ring a hole of the smallest
+/// exterior containing it. Holes outside of every exterior, or rings that are all
+/// counterclockwise, are read as exteriors.
+fn polygons(rings: Vec<Vec<Coord>>) -> ParsedGeometry {
+    let (exteriors, mut holes): (Vec<_>, Vec<_>) =
+        rings.into_iter().partition(|ring| is_clockwise(ring));
+    let line_string = |ring: &[Coord]| {
+        LineString::from(
+            ring.iter()
+                .map(|coord| (coord.x, coord.y))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    let shapes = exteriors
+        .iter()
+        .map(|ring| Polygon::new(line_string(ring), vec![]))
+        .collect::<Vec<_>>();
+    let areas = shapes
+        .iter()
+        .map(|shape| shape.unsigned_area())
+        .collect::<Vec<_>>();
+    let mut polygons = exteriors
+        .into_iter()
+        .map(|ring| vec![ring])
+        .collect::<Vec<_>>();
+    if polygons.is_empty() {
+        polygons = holes.drain(..).map(|ring| vec![ring]).collect();
+    }
+    for hole in holes {
+        let hole_line_string = line_string(&hole);
+        let exterior = (0..shapes.len())
+            .filter(|idx| shapes[*idx].contains(&hole_line_string))
+            .min_by(|a, b| areas[*a].total_cmp(&areas[*b]));
+        match exterior {
+            Some(idx) => polygons[idx].push(hole),
+            None => polygons.push(vec![hole]),
+        }
+    }
+
+    if polygons.len() == 1 {
+        ParsedGeometry::Polygon(polygons.remove(0))
+    } else {
+        ParsedGeometry::MultiPolygon(polygons)
+    }
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use arrow_array::Array;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Int64Type, TimestampMillisecondType};
+    use geo_traits::{GeometryTrait, GeometryType, MultiPolygonTrait, PolygonTrait};
+
+    use super::*;
+    use crate::array::NativeArrayDyn;
+    use crate::io::wkb::to_wkb;
+    use crate::table::Table;
+    use crate::trait_::ArrayAccessor;
+
+    pub(in crate::io::esri_json) const FEATURE_SET: &str = r#"{
+  "geometryType": "esriGeometryPolygon",
+  "spatialReference": {"wkid": 102100, "latestWkid": 3857},
+  "fields": [
+    {"name": "OBJECTID", "type": "esriFieldTypeOID", "alias": "OBJECTID"},
+    {"name": "NAME", "type": "esriFieldTypeString", "alias": "Name"},
+    {"name": "AREA", "type": "esriFieldTypeDouble", "alias": "Area"},
+    {"name": "UPDATED", "type": "esriFieldTypeDate", "alias": "Updated"},
+    {"name": "SHAPE", "type": "esriFieldTypeGeometry", "alias": "Shape"}
+  ],
+  "features": [
+    {
+      "attributes": {"OBJECTID": 1, "NAME": "Lake", "AREA": 12.5, "UPDATED": 1700000000000},
+      "geometry": {"rings": [
+        [[0, 0], [0, 10], [10, 10], [10, 0], [0, 0]],
+        [[2, 2], [4, 2], [4, 4], [2, 4], [2, 2]],
+        [[20, 0], [20, 5], [25, 5], [25, 0], [20, 0]]
+      ]}
+    },
+    {
+      "attributes": {"OBJECTID": 2, "NAME": null, "AREA": null, "UPDATED": null},
+      "geometry": null
+    }
+  ]
+}"#;
+
+    #[test]
+    fn read() {
+        let table: Table = read_esri_json(FEATURE_SET.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table.batches()[0];
+        let schema = batch.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["OBJECTID", "NAME", "AREA", "UPDATED", "geometry"]);
+        assert_eq!(
+            batch.column(0).as_primitive::<Int64Type>().values(),
+            &[1, 2]
+        );
+        assert_eq!(
+            batch
+                .column(3)
+                .as_primitive::<TimestampMillisecondType>()
+                .value(0),
+            1_700_000_000_000
+        );
+        assert!(batch.column(1).is_null(1));
+
+        let geometry_type = NativeType::try_from(schema.field(4)).unwrap();
+        assert!(matches!(geometry_type, NativeType::MultiPolygon(_)));
+        assert_eq!(
+            geometry_type.metadata().crs().crs_value(),
+            Some(&Value::String("EPSG:3857".to_string()))
+        );
+
+        let geometry = NativeArrayDyn::from_arrow_array(batch.column(4).as_ref(), schema.field(4))
+            .unwrap()
+            .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry.as_ref());
+        assert!(wkb_array.get(1).is_none());
+        let geometry = wkb_array.value(0).parse().unwrap();
+        let GeometryType::MultiPolygon(multi_polygon) = geometry.as_type() else {
+            panic!("expected a multi polygon");
+        };
+        assert_eq!(multi_polygon.num_polygons(), 2);
+        assert_eq!(multi_polygon.polygon(0).unwrap().num_interiors(), 1);
+        assert_eq!(multi_polygon.polygon(1).unwrap().num_interiors(), 0);
+    }
+
+    #[test]
+    fn infer_fields() {
+        let input = r#"{"features": [
+            {"attributes": {"id": 1, "kind": "a"}, "geometry": {"x": 1.5, "y": 2.5}},
+            {"attributes": {"id": 2, "kind": "b"}, "geometry": {"x": "NaN", "y": "NaN"}}
+        ]}"#;
+        let table: Table = read_esri_json(input.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table.batches()[0];
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Int64);
+        assert!(matches!(
+            NativeType::try_from(batch.schema().field(2)).unwrap(),
+            NativeType::Point(_)
+        ));
+        assert!(batch.column(2).is_null(1));
+    }
+}
@@ -0,0 +1,490 @@
+use std::io::Write;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type, TimestampMillisecondType};
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_schema::{DataType, Schema, TimeUnit};
+use geo_traits::{
+    CoordTrait, Dimensions, LineStringTrait, MultiLineStringTrait, MultiPointTrait,
+    MultiPolygonTrait, PointTrait, PolygonTrait, RectTrait,
+};
+use geoarrow_schema::{Crs, CrsType, Dimension};
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{Map, Number, Value, json};
+
+use crate::NativeArray;
+use crate::array::{AsNativeArray, NativeArrayDyn};
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::esri_json::is_clockwise;
+use crate::io::parsed::Coord;
+use crate::io::stream::RecordBatchReader;
+use crate::schema::GeoSchemaExt;
+use crate::trait_::ArrayAccessor;
+
+/// Options for the Esri JSON writer.
+#[derive(Debug, Clone, Default)]
+pub struct EsriJsonWriterOptions {
+    /// The `wkid` of the feature set's spatial reference.
+    ///
+    /// If `None`, it is taken from the CRS of the geometry column when that is an EPSG or ESRI
+    /// code, and otherwise no spatial reference is written.
+    pub wkid: Option<i64>,
+}
+
+/// Write a table to an Esri JSON feature set.
+///
+/// The table must contain exactly one geometry column, of point, multi point, line string, multi
+/// line string, polygon, multi polygon or rect type. Line strings are written as polylines, and
+/// rects as polygons. Exterior rings are written clockwise and holes counterclockwise, as Esri
+/// JSON requires.
+///
+/// Integer and boolean columns are written as integer fields, floating point columns as double
+/// fields, date and timestamp columns as date fields in milliseconds since the epoch, and all
+/// other columns as string fields.
+pub fn write_esri_json<W: Write, S: Into<RecordBatchReader>>(
+    stream: S,
+    mut writer: W,
+    options: EsriJsonWriterOptions,
+) -> Result<()> {
+    let reader = stream.into().into_inner();
+    let schema = reader.schema();
+    let geometry_columns = schema.geometry_columns();
+    let geometry_column_idx = match geometry_columns.as_slice() {
+        [idx] => *idx,
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Writing Esri JSON requires exactly one geometry column, found {}",
+                geometry_columns.len()
+            )));
+        }
+    };
+    let geometry_type = NativeType::try_from(schema.field(geometry_column_idx))?;
+    let esri_geometry_type = match geometry_type {
+        NativeType::Point(_) => "esriGeometryPoint",
+        NativeType::MultiPoint(_) => "esriGeometryMultipoint",
+        NativeType::LineString(_) | NativeType::MultiLineString(_) => "esriGeometryPolyline",
+        NativeType::Polygon(_) | NativeType::MultiPolygon(_) | NativeType::Rect(_) => {
+            "esriGeometryPolygon"
+        }
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Esri JSON requires a single geometry type, got {:?}",
+                geometry_type
+            )));
+        }
+    };
+    let has_z = matches!(
+        geometry_type.dimension(),
+        Some(Dimension::XYZ | Dimension::XYZM)
+    );
+
+    let mut header = Map::new();
+    header.insert("geometryType".to_string(), esri_geometry_type.into());
+    header.insert("hasZ".to_string(), has_z.into());
+    if let Some(wkid) = options
+        .wkid
+        .or_else(|| crs_wkid(geometry_type.metadata().crs()))
+    {
+        header.insert("spatialReference".to_string(), json!({ "wkid": wkid }));
+    }
+    let fields = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != geometry_column_idx)
+        .map(|(_, field)| {
+            json!({
+                "name": field.name(),
+                "type": esri_field_type(field.data_type()),
+                "alias": field.name(),
+            })
+        })
+        .collect::<Vec<_>>();
+    header.insert("fields".to_string(), fields.into());
+
+    // Write the header without its closing brace, and stream the features after it
+    let header = serde_json::to_string(&header)?;
+    writer.write_all(header[..header.len() - 1].as_bytes())?;
+    writer.write_all(br#","features":["#)?;
+
+    let mut first = true;
+    for batch in reader {
+        let batch = batch?;
+        let attributes = Attributes::try_new(&batch, &schema, geometry_column_idx)?;
+        let geometry_array = NativeArrayDyn::from_arrow_array(
+            batch.column(geometry_column_idx).as_ref(),
+            schema.field(geometry_column_idx),
+        )?
+        .into_inner();
+        let geometries = geometries(geometry_array.as_ref(), has_z)?;
+
+        for (row, geometry) in geometries.into_iter().enumerate() {
+            let feature = Feature {
+                attributes: attributes.row(row),
+                geometry,
+            };
+            if !first {
+                writer.write_all(b",")?;
+            }
+            first = false;
+            serde_json::to_writer(&mut writer, &feature)?;
+        }
+    }
+
+    writer.write_all(b"]}")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// The `wkid` of a CRS that is an EPSG or ESRI code.
+fn crs_wkid(crs: &Crs) -> Option<i64> {
+    let authority_code = |authority: &str, code: Option<i64>| {
+        (authority.eq_ignore_ascii_case("EPSG") || authority.eq_ignore_ascii_case("ESRI"))
+            .then_some(code)
+            .flatten()
+    };
+    match (crs.crs_type(), crs.crs_value()) {
+        (Some(CrsType::Projjson), Some(Value::Object(projjson))) => {
+            let id = projjson.get("id")?;
+            authority_code(id.get("authority")?.as_str()?, id.get("code")?.as_i64())
+        }
+        (Some(CrsType::Srid), Some(Value::String(value))) => value.parse().ok(),
+        (_, Some(Value::String(value))) if value.eq_ignore_ascii_case("OGC:CRS84") => Some(4326),
+        (_, Some(Value::String(value))) => {
+            let (authority, code) = value.split_once(':')?;
+            authority_code(authority, code.parse().ok())
+        }
+        _ => None,
+    }
+}
+
+fn esri_field_type(data_type: &DataType) -> &'static str {
+    match data_type {
+        DataType::Boolean | DataType::Int8 | DataType::Int16 | DataType::UInt8 => {
+            "esriFieldTypeSmallInteger"
+        }
+        DataType::Int32 | DataType::UInt16 => "esriFieldTypeInteger",
+        DataType::Int64 | DataType::UInt32 | DataType::UInt64 => "esriFieldTypeBigInteger",
+        DataType::Float16 | DataType::Float32 => "esriFieldTypeSingle",
+        DataType::Float64 | DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+            "esriFieldTypeDouble"
+        }
+        DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _) => "esriFieldTypeDate",
+        _ => "esriFieldTypeString",
+    }
+}
+
+/// A feature, without a geometry if it is null.
+#[derive(Serialize)]
+struct Feature<'a> {
+    attributes: IndexMap<&'a str, Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    geometry: Option<Value>,
+}
+
+/// The attribute columns of a batch, cast to the types written for their Esri field types.
+struct Attributes<'a> {
+    names: Vec<&'a str>,
+    columns: Vec<AttributeColumn<'a>>,
+}
+
+enum AttributeColumn<'a> {
+    Integer(ArrayRef),
+    Double(ArrayRef),
+    Date(ArrayRef),
+    String(&'a dyn Array, ArrayFormatter<'a>),
+}
+
+impl<'a> Attributes<'a> {
+    fn try_new(
+        batch: &'a RecordBatch,
+        schema: &'a Schema,
+        geometry_column_idx: usize,
+    ) -> Result<Self> {
+        let mut names = vec![];
+        let mut columns = vec![];
+        for (idx, field) in schema.fields().iter().enumerate() {
+            if idx == geometry_column_idx {
+                continue;
+            }
+            let column = batch.column(idx);
+            let attribute_column = match esri_field_type(field.data_type()) {
+                "esriFieldTypeSmallInteger"
+                | "esriFieldTypeInteger"
+                | "esriFieldTypeBigInteger" => {
+                    AttributeColumn::Integer(arrow_cast::cast(column, &DataType::Int64)?)
+                }
+                "esriFieldTypeSingle" | "esriFieldTypeDouble" => {
+                    AttributeColumn::Double(arrow_cast::cast(column, &DataType::Float64)?)
+                }
+                "esriFieldTypeDate" => AttributeColumn::Date(arrow_cast::cast(
+                    column,
+                    &DataType::Timestamp(TimeUnit::Millisecond, None),
+                )?),
+                _ => AttributeColumn::String(
+                    column.as_ref(),
+                    ArrayFormatter::try_new(column.as_ref(), &FormatOptions::default())?,
+                ),
+            };
+            names.push(field.name().as_str());
+            columns.push(attribute_column);
+        }
+        Ok(Self { names, columns })
+    }
+
+    /// The attributes of a row, in the order of their columns.
+    fn row(&self, row: usize) -> IndexMap<&'a str, Value> {
+        let mut attributes = IndexMap::new();
+        for (name, column) in self.names.iter().zip(&self.columns) {
+            let value = match column {
+                AttributeColumn::Integer(array) => array
+                    .is_valid(row)
+                    .then(|| array.as_primitive::<Int64Type>().value(row).into()),
+                AttributeColumn::Double(array) => array
+                    .is_valid(row)
+                    .then(|| number(array.as_primitive::<Float64Type>().value(row))),
+                AttributeColumn::Date(array) => array.is_valid(row).then(|| {
+                    array
+                        .as_primitive::<TimestampMillisecondType>()
+                        .value(row)
+                        .into()
+                }),
+                AttributeColumn::String(array, formatter) => array
+                    .is_valid(row)
+                    .then(|| formatter.value(row).to_string().into()),
+            };
+            attributes.insert(*name, value.unwrap_or(Value::Null));
+        }
+        attributes
+    }
+}
+
+/// A JSON number, or null for NaN and infinite values.
+fn number(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+fn coord(coord: &impl CoordTrait<T = f64>) -> Coord {
+    let z = match coord.dim() {
+        Dimensions::Xyz | Dimensions::Xyzm => Some(coord.nth_or_panic(2)),
+        _ => None,
+    };
+    Coord {
+        x: coord.x(),
+        y: coord.y(),
+        z,
+    }
+}
+
+fn position(coord: &Coord, has_z: bool) -> Value {
+    let mut position = vec![number(coord.x), number(coord.y)];
+    if has_z {
+        position.push(coord.z.map_or(Value::Null, number));
+    }
+    Value::Array(position)
+}
+
+fn positions(coords: &[Coord], has_z: bool) -> Value {
+    Value::Array(coords.iter().map(|coord| position(coord, has_z)).collect())
+}
+
+/// Add the rings of a polygon, with the exterior clockwise and holes counterclockwise.
+fn push_rings(rings: &mut Vec<Value>, polygon: &impl PolygonTrait<T = f64>, has_z: bool) {
+    if let Some(exterior) = polygon.exterior() {
+        let mut exterior = exterior.coords().map(|c| coord(&c)).collect::<Vec<_>>();
+        if !is_clockwise(&exterior) {
+            exterior.reverse();
+        }
+        rings.push(positions(&exterior, has_z));
+    }
+    for interior in polygon.interiors() {
+        let mut interior = interior.coords().map(|c| coord(&c)).collect::<Vec<_>>();
+        if is_clockwise(&interior) {
+            interior.reverse();
+        }
+        rings.push(positions(&interior, has_z));
+    }
+}
+
+/// The Esri JSON geometry of each row of a native array, or `None` for nulls.
+fn geometries(array: &dyn NativeArray, has_z: bool) -> Result<Vec<Option<Value>>> {
+    use NativeType::*;
+
+    let geometries = match array.data_type() {
+        Point(_) => rows(array.as_point(), |point| point_geometry(&point, has_z)),
+        MultiPoint(_) => rows(array.as_multi_point(), |multi_point| {
+            multi_point_geometry(&multi_point, has_z)
+        }),
+        LineString(_) => rows(
+            array.as_line_string(),
+            |line_string| json!({ "paths": [path(&line_string, has_z)] }),
+        ),
+        MultiLineString(_) => rows(array.as_multi_line_string(), |multi_line_string| {
+            let paths = multi_line_string
+                .line_strings()
+                .map(|line_string| path(&line_string, has_z))
+                .collect::<Vec<_>>();
+            json!({ "paths": paths })
+        }),
+        Polygon(_) => rows(array.as_polygon(), |polygon| {
+            let mut rings = vec![];
+            push_rings(&mut rings, &polygon, has_z);
+            json!({ "rings": rings })
+        }),
+        MultiPolygon(_) => rows(array.as_multi_polygon(), |multi_polygon| {
+            let mut rings = vec![];
+            for polygon in multi_polygon.polygons() {
+                push_rings(&mut rings, &polygon, has_z);
+            }
+            json!({ "rings": rings })
+        }),
+        Rect(_) => rows(array.as_rect(), |rect| rect_geometry(&rect, has_z)),
+        GeometryCollection(_) | Geometry(_) => {
+            return Err(GeoArrowError::General(
+                "Esri JSON can't represent geometry collections, triangles or lines".to_string(),
+            ));
+        }
+    };
+    Ok(geometries)
+}
+
+/// Map each row of an array with `f`, or to `None` for nulls.
+fn rows<'a, A: ArrayAccessor<'a>>(
+    array: &'a A,
+    f: impl Fn(A::Item) -> Value,
+) -> Vec<Option<Value>> {
+    (0..array.len()).map(|row| array.get(row).map(&f)).collect()
+}
+
+fn point_geometry(point: &impl PointTrait<T = f64>, has_z: bool) -> Value {
+    match point.coord() {
+        Some(point) => {
+            let point = coord(&point);
+            let mut object = json!({ "x": number(point.x), "y": number(point.y) });
+            if has_z {
+                object["z"] = point.z.map_or(Value::Null, number);
+            }
+            object
+        }
+        None => json!({ "x": null, "y": null }),
+    }
+}
+
+fn multi_point_geometry(multi_point: &impl MultiPointTrait<T = f64>, has_z: bool) -> Value {
+    let points = multi_point
+        .points()
+        .filter_map(|point| point.coord().map(|c| coord(&c)))
+        .collect::<Vec<_>>();
+    json!({ "points": positions(&points, has_z) })
+}
+
+fn path(line_string: &impl LineStringTrait<T = f64>, has_z: bool) -> Value {
+    let path = line_string.coords().map(|c| coord(&c)).collect::<Vec<_>>();
+    positions(&path, has_z)
+}
+
+fn rect_geometry(rect: &impl RectTrait<T = f64>, has_z: bool) -> Value {
+    let (min, max) = (coord(&rect.min()), coord(&rect.max()));
+    let corner = |x, y| Coord { x, y, z: min.z };
+    let ring = [
+        corner(min.x, min.y),
+        corner(min.x, max.y),
+        corner(max.x, max.y),
+        corner(max.x, min.y),
+        corner(min.x, min.y),
+    ];
+    json!({ "rings": [positions(&ring, has_z)] })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow_schema::Schema;
+
+    use super::*;
+    use crate::ArrayBase;
+    use crate::io::esri_json::read_esri_json;
+    use crate::table::Table;
+    use crate::test::{point, polygon};
+
+    #[test]
+    fn round_trip() {
+        let input = super::super::reader::test::FEATURE_SET;
+        let table: Table = read_esri_json(input.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let mut output = vec![];
+        write_esri_json(&table, &mut output, Default::default()).unwrap();
+        let json: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["spatialReference"], json!({ "wkid": 3857 }));
+        assert_eq!(json["fields"][1]["type"], "esriFieldTypeString");
+
+        let table2: Table = read_esri_json(output.as_slice(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(table.batches(), table2.batches());
+    }
+
+    #[test]
+    fn ring_orientation() {
+        let geometry = polygon::p_array();
+        let schema = Arc::new(Schema::new(vec![geometry.extension_field()]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![geometry.into_array_ref()]).unwrap();
+        let table = Table::try_new(vec![batch], schema).unwrap();
+        let mut output = vec![];
+        let options = EsriJsonWriterOptions { wkid: Some(4326) };
+        write_esri_json(&table, &mut output, options).unwrap();
+
+        let json: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["geometryType"], "esriGeometryPolygon");
+        assert_eq!(json["spatialReference"]["wkid"], 4326);
+        let features = json["features"].as_array().unwrap();
+        for feature in features {
+            let rings = feature["geometry"]["rings"].as_array().unwrap();
+            for (idx, ring) in rings.iter().enumerate() {
+                let ring = ring
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|position| Coord {
+                        x: position[0].as_f64().unwrap(),
+                        y: position[1].as_f64().unwrap(),
+                        z: None,
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(is_clockwise(&ring), idx == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn crs() {
+        assert_eq!(
+            crs_wkid(&Crs::from_authority_code("OGC:CRS84".to_string())),
+            Some(4326)
+        );
+        assert_eq!(
+            crs_wkid(&Crs::from_authority_code("ESRI:102100".to_string())),
+            Some(102100)
+        );
+        assert_eq!(
+            crs_wkid(&Crs::from_authority_code("IAU:30100".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn attribute_order() {
+        let mut output = vec![];
+        write_esri_json(&point::table(), &mut output, Default::default()).unwrap();
+        // Attributes keep the order of their columns rather than being sorted
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(r#""attributes":{"u8":"#));
+    }
+}
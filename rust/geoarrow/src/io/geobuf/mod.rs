@@ -0,0 +1,9 @@
+//! Read from and write to [Geobuf](https://github.com/mapbox/geobuf) files, a compact protobuf
+//! encoding of GeoJSON.
+
+mod proto;
+mod reader;
+mod writer;
+
+pub use reader::read_geobuf;
+pub use writer::{GeobufWriterOptions, write_geobuf};
@@ -0,0 +1,112 @@
+//! Protobuf messages of the [Geobuf schema](https://github.com/mapbox/geobuf/blob/master/geobuf.proto).
+
+/// The top-level Geobuf message.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct Data {
+    /// The property keys of all features.
+    #[prost(string, repeated, tag = "1")]
+    pub keys: Vec<String>,
+    /// The number of dimensions of each coordinate, 2 if unset.
+    #[prost(uint32, optional, tag = "2")]
+    pub dimensions: Option<u32>,
+    /// The number of decimal digits of each coordinate, 6 if unset.
+    #[prost(uint32, optional, tag = "3")]
+    pub precision: Option<u32>,
+    #[prost(oneof = "DataType", tags = "4, 5, 6")]
+    pub data_type: Option<DataType>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub(super) enum DataType {
+    #[prost(message, tag = "4")]
+    FeatureCollection(FeatureCollection),
+    #[prost(message, tag = "5")]
+    Feature(Feature),
+    #[prost(message, tag = "6")]
+    Geometry(Geometry),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct FeatureCollection {
+    #[prost(message, repeated, tag = "1")]
+    pub features: Vec<Feature>,
+    #[prost(message, repeated, tag = "13")]
+    pub values: Vec<Value>,
+    #[prost(uint32, repeated, tag = "15")]
+    pub custom_properties: Vec<u32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct Feature {
+    #[prost(message, optional, tag = "1")]
+    pub geometry: Option<Geometry>,
+    #[prost(oneof = "IdType", tags = "11, 12")]
+    pub id_type: Option<IdType>,
+    /// The property values of this feature.
+    #[prost(message, repeated, tag = "13")]
+    pub values: Vec<Value>,
+    /// Pairs of indices into the keys of [Data] and the values of this feature.
+    #[prost(uint32, repeated, tag = "14")]
+    pub properties: Vec<u32>,
+    #[prost(uint32, repeated, tag = "15")]
+    pub custom_properties: Vec<u32>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub(super) enum IdType {
+    #[prost(string, tag = "11")]
+    Id(String),
+    #[prost(sint64, tag = "12")]
+    IntId(i64),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct Geometry {
+    /// One of the `*_TYPE` constants.
+    #[prost(uint32, required, tag = "1")]
+    pub r#type: u32,
+    /// The number of coordinates of each line or ring, for geometries with more than one of them.
+    #[prost(uint32, repeated, tag = "2")]
+    pub lengths: Vec<u32>,
+    /// Scaled coordinates, delta-encoded within each line or ring.
+    #[prost(sint64, repeated, tag = "3")]
+    pub coords: Vec<i64>,
+    #[prost(message, repeated, tag = "4")]
+    pub geometries: Vec<Geometry>,
+    #[prost(message, repeated, tag = "13")]
+    pub values: Vec<Value>,
+    #[prost(uint32, repeated, tag = "15")]
+    pub custom_properties: Vec<u32>,
+}
+
+pub(super) const POINT_TYPE: u32 = 0;
+pub(super) const MULTI_POINT_TYPE: u32 = 1;
+pub(super) const LINE_STRING_TYPE: u32 = 2;
+pub(super) const MULTI_LINE_STRING_TYPE: u32 = 3;
+pub(super) const POLYGON_TYPE: u32 = 4;
+pub(super) const MULTI_POLYGON_TYPE: u32 = 5;
+pub(super) const GEOMETRY_COLLECTION_TYPE: u32 = 6;
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct Value {
+    #[prost(oneof = "ValueType", tags = "1, 2, 3, 4, 5, 6")]
+    pub value_type: Option<ValueType>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub(super) enum ValueType {
+    #[prost(string, tag = "1")]
+    String(String),
+    #[prost(double, tag = "2")]
+    Double(f64),
+    #[prost(uint64, tag = "3")]
+    PosInt(u64),
+    /// The absolute value of a negative integer.
+    #[prost(uint64, tag = "4")]
+    NegInt(u64),
+    #[prost(bool, tag = "5")]
+    Bool(bool),
+    /// Any other JSON value, serialized.
+    #[prost(string, tag = "6")]
+    Json(String),
+}
@@ -0,0 +1,394 @@
+use std::io::Read;
+use std::sync::Arc;
+
+use arrow_array::{RecordBatch, RecordBatchIterator};
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+use geoarrow_schema::GeometryType;
+use prost::Message;
+use serde_json::{Map, Number, Value};
+
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::geobuf::proto;
+use crate::io::geojson::GeoJsonReaderOptions;
+use crate::io::parsed::{
+    Coord, KeyOrder, ParsedGeometry, geometry_array, infer_properties_schema, property_columns,
+};
+use crate::io::stream::RecordBatchReader;
+
+/// Read a [Geobuf](https://github.com/mapbox/geobuf) file to a stream of record batches.
+///
+/// The file may hold a FeatureCollection, a single Feature or a single Geometry. Properties and
+/// ids are read as by the GeoJSON reader, following `options`, and the output schema is the same
+/// as [`GeoJsonRecordBatchReader`](crate::io::geojson::GeoJsonRecordBatchReader)'s. Geometries
+/// keep their Z values when the file has 3 or more dimensions.
+///
+/// A Geobuf file is a single protobuf message, so it is read and decoded as a whole up front.
+/// Features are converted to record batches as the stream is consumed.
+pub fn read_geobuf<R: Read>(
+    mut reader: R,
+    options: GeoJsonReaderOptions,
+) -> Result<RecordBatchReader> {
+    let mut buf = vec![];
+    reader.read_to_end(&mut buf)?;
+    let data = proto::Data::decode(buf.as_slice())?;
+
+    let decoder = GeometryDecoder {
+        dims: data.dimensions.unwrap_or(2) as usize,
+        scale: 10f64.powi(data.precision.unwrap_or(6) as i32),
+    };
+    if decoder.dims < 2 {
+        return Err(GeoArrowError::General(format!(
+            "Invalid Geobuf dimensions: {}",
+            decoder.dims
+        )));
+    }
+
+    let features = match data.data_type {
+        Some(proto::DataType::FeatureCollection(collection)) => collection.features,
+        Some(proto::DataType::Feature(feature)) => vec![feature],
+        Some(proto::DataType::Geometry(geometry)) => vec![proto::Feature {
+            geometry: Some(geometry),
+            ..Default::default()
+        }],
+        None => vec![],
+    };
+    let keys = data.keys;
+    let id_column = options.id_column;
+
+    let properties_schema = match options.schema {
+        Some(schema) => schema,
+        None => {
+            let mut key_order = KeyOrder::default();
+            if let Some(id_column) = &id_column {
+                key_order.insert(id_column);
+            }
+            let rows = features
+                .iter()
+                .take(options.infer_schema_length)
+                .map(|feature| {
+                    properties(feature, &keys, id_column.as_deref(), Some(&mut key_order))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            infer_properties_schema(&rows, &key_order)?
+        }
+    };
+
+    let geometry_type =
+        NativeType::Geometry(GeometryType::new(options.coord_type, Default::default()));
+    let mut fields = properties_schema.fields().to_vec();
+    fields.push(Arc::new(geometry_type.to_field("geometry", true)));
+    let schema: SchemaRef = Arc::new(Schema::new_with_metadata(
+        fields,
+        properties_schema.metadata().clone(),
+    ));
+
+    let batch_size = options.batch_size.unwrap_or(65_536).max(1);
+    let mut features = features.into_iter().peekable();
+    let batch_schema = schema.clone();
+    let batches = std::iter::from_fn(move || {
+        features.peek()?;
+        let batch = (|| -> Result<RecordBatch> {
+            let mut rows = vec![];
+            let mut geometries = vec![];
+            for feature in features.by_ref().take(batch_size) {
+                rows.push(properties(&feature, &keys, id_column.as_deref(), None)?);
+                geometries.push(
+                    feature
+                        .geometry
+                        .as_ref()
+                        .map(|geometry| decoder.decode(geometry))
+                        .transpose()?,
+                );
+            }
+            let mut columns = property_columns(properties_schema.clone(), &rows)?;
+            columns.push(geometry_array(&geometries, geometry_type.clone())?);
+            Ok(RecordBatch::try_new(batch_schema.clone(), columns)?)
+        })();
+        Some(batch.map_err(|err| ArrowError::ExternalError(Box::new(err))))
+    });
+    Ok(RecordBatchReader::new(Box::new(RecordBatchIterator::new(
+        batches, schema,
+    ))))
+}
+
+/// The properties of a feature as a JSON object, with its id under `id_column` unless a property
+/// has that name. The order of the property keys is added to `key_order` if given.
+fn properties(
+    feature: &proto::Feature,
+    keys: &[String],
+    id_column: Option<&str>,
+    mut key_order: Option<&mut KeyOrder>,
+) -> Result<Value> {
+    let invalid = || GeoArrowError::General("Invalid Geobuf property index".to_string());
+    let mut properties = Map::new();
+    for pair in feature.properties.chunks(2) {
+        let [key, value] = pair else {
+            return Err(invalid());
+        };
+        let key = keys.get(*key as usize).ok_or_else(invalid)?;
+        let value = feature.values.get(*value as usize).ok_or_else(invalid)?;
+        let value = match &value.value_type {
+            Some(proto::ValueType::String(value)) => Value::String(value.clone()),
+            Some(proto::ValueType::Double(value)) => {
+                Number::from_f64(*value).map_or(Value::Null, Value::Number)
+            }
+            Some(proto::ValueType::PosInt(value)) => Value::from(*value),
+            Some(proto::ValueType::NegInt(value)) => {
+                Value::from(0i64.checked_sub_unsigned(*value).ok_or_else(invalid)?)
+            }
+            Some(proto::ValueType::Bool(value)) => Value::Bool(*value),
+            Some(proto::ValueType::Json(value)) => {
+                if let Some(key_order) = key_order.as_deref_mut() {
+                    key_order.insert_nested(key, serde_json::from_str(value)?);
+                }
+                serde_json::from_str(value)?
+            }
+            None => Value::Null,
+        };
+        if let Some(key_order) = key_order.as_deref_mut() {
+            key_order.insert(key);
+        }
+        properties.insert(key.clone(), value);
+    }
+
+    if let (Some(id_column), Some(id)) = (id_column, &feature.id_type) {
+        let id = match id {
+            proto::IdType::Id(id) => Value::from(id.clone()),
+            proto::IdType::IntId(id) => Value::from(*id),
+        };
+        properties.entry(id_column).or_insert(id);
+    }
+    Ok(Value::Object(properties))
+}
+
+/// Decodes Geobuf geometries, keeping the third dimension as Z values.
+struct GeometryDecoder {
+    dims: usize,
+    scale: f64,
+}
+
+impl GeometryDecoder {
+    fn position(&self, coord: &[i64]) -> Coord {
+        Coord {
+            x: coord[0] as f64 / self.scale,
+            y: coord[1] as f64 / self.scale,
+            z: (self.dims > 2).then(|| coord[2] as f64 / self.scale),
+        }
+    }
+
+    /// Decode the delta-encoded coordinates of a line or ring, repeating the first position at the
+    /// end if `closed`.
+    fn line(&self, coords: &[i64], closed: bool) -> Result<Vec<Coord>> {
+        if coords.len() % self.dims != 0 {
+            return Err(GeoArrowError::General(format!(
+                "Geobuf geometry has {} coordinate values, which is not a multiple of its {} \
+                 dimensions",
+                coords.len(),
+                self.dims
+            )));
+        }
+        let mut sum = vec![0i64; self.dims];
+        let mut positions = coords
+            .chunks_exact(self.dims)
+            .map(|coord| {
+                for (sum, delta) in sum.iter_mut().zip(coord) {
+                    *sum = sum.wrapping_add(*delta);
+                }
+                self.position(&sum)
+            })
+            .collect::<Vec<_>>();
+        if closed && !positions.is_empty() {
+            positions.push(positions[0]);
+        }
+        Ok(positions)
+    }
+
+    /// Split coordinates into lines with the given numbers of positions.
+    fn lines(
+        &self,
+        coords: &[i64],
+        lengths: impl IntoIterator<Item = u32>,
+        closed: bool,
+    ) -> Result<Vec<Vec<Coord>>> {
+        let invalid =
+            || GeoArrowError::General("Geobuf geometry lengths exceed its coordinates".to_string());
+        let mut offset = 0usize;
+        let mut lines = vec![];
+        for length in lengths {
+            let end = (length as usize)
+                .checked_mul(self.dims)
+                .and_then(|len| offset.checked_add(len))
+                .ok_or_else(invalid)?;
+            let line = coords.get(offset..end).ok_or_else(invalid)?;
+            lines.push(self.line(line, closed)?);
+            offset = end;
+        }
+        Ok(lines)
+    }
+
+    fn decode(&self, geometry: &proto::Geometry) -> Result<ParsedGeometry> {
+        let coords = geometry.coords.as_slice();
+        let lengths = geometry.lengths.as_slice();
+        let geometry = match geometry.r#type {
+            // An empty point is read with NaN coordinates, as GeoArrow stores empty points
+            proto::POINT_TYPE if coords.len() < self.dims => ParsedGeometry::Point(Coord {
+                x: f64::NAN,
+                y: f64::NAN,
+                z: None,
+            }),
+            proto::POINT_TYPE => ParsedGeometry::Point(self.position(coords)),
+            proto::MULTI_POINT_TYPE => ParsedGeometry::MultiPoint(self.line(coords, false)?),
+            proto::LINE_STRING_TYPE => ParsedGeometry::LineString(self.line(coords, false)?),
+            proto::MULTI_LINE_STRING_TYPE | proto::POLYGON_TYPE => {
+                let closed = geometry.r#type == proto::POLYGON_TYPE;
+                let lines = if lengths.is_empty() {
+                    if coords.is_empty() {
+                        vec![]
+                    } else {
+                        vec![self.line(coords, closed)?]
+                    }
+                } else {
+                    self.lines(coords, lengths.iter().copied(), closed)?
+                };
+                if closed {
+                    ParsedGeometry::Polygon(lines)
+                } else {
+                    ParsedGeometry::MultiLineString(lines)
+                }
+            }
+            proto::MULTI_POLYGON_TYPE => {
+                if lengths.is_empty() {
+                    if coords.is_empty() {
+                        ParsedGeometry::MultiPolygon(vec![])
+                    } else {
+                        ParsedGeometry::MultiPolygon(vec![vec![self.line(coords, true)?]])
+                    }
+                } else {
+                    let invalid = || {
+                        GeoArrowError::General("Invalid Geobuf MultiPolygon lengths".to_string())
+                    };
+                    let mut lengths = lengths.iter().copied();
+                    let num_polygons = lengths.next().ok_or_else(invalid)?;
+                    let mut polygons = vec![];
+                    let mut offset = 0;
+                    for _ in 0..num_polygons {
+                        let num_rings = lengths.next().ok_or_else(invalid)?;
+                        let ring_lengths = lengths
+                            .by_ref()
+                            .take(num_rings as usize)
+                            .collect::<Vec<_>>();
+                        if ring_lengths.len() != num_rings as usize {
+                            return Err(invalid());
+                        }
+                        let num_coords = ring_lengths
+                            .iter()
+                            .try_fold(0u32, |sum, length| sum.checked_add(*length))
+                            .and_then(|sum| (sum as usize).checked_mul(self.dims))
+                            .ok_or_else(invalid)?;
+                        let polygon_coords = offset
+                            .checked_add(num_coords)
+                            .and_then(|end| coords.get(offset..end))
+                            .ok_or_else(invalid)?;
+                        polygons.push(self.lines(polygon_coords, ring_lengths, true)?);
+                        offset += num_coords;
+                    }
+                    ParsedGeometry::MultiPolygon(polygons)
+                }
+            }
+            proto::GEOMETRY_COLLECTION_TYPE => ParsedGeometry::GeometryCollection(
+                geometry
+                    .geometries
+                    .iter()
+                    .map(|geometry| self.decode(geometry))
+                    .collect::<Result<_>>()?,
+            ),
+            geometry_type => {
+                return Err(GeoArrowError::General(format!(
+                    "Unknown Geobuf geometry type {}",
+                    geometry_type
+                )));
+            }
+        };
+        Ok(geometry)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decoder() -> GeometryDecoder {
+        GeometryDecoder {
+            dims: 2,
+            scale: 1.0,
+        }
+    }
+
+    fn polygon(geometry: ParsedGeometry) -> Vec<Vec<(f64, f64)>> {
+        let ParsedGeometry::Polygon(rings) = geometry else {
+            panic!("expected a polygon");
+        };
+        rings
+            .iter()
+            .map(|ring| ring.iter().map(|coord| (coord.x, coord.y)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn closes_rings() {
+        let geometry = proto::Geometry {
+            r#type: proto::POLYGON_TYPE,
+            coords: vec![0, 0, 2, 0, 0, 2],
+            ..Default::default()
+        };
+        assert_eq!(
+            polygon(decoder().decode(&geometry).unwrap()),
+            [[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 0.0)]]
+        );
+    }
+
+    #[test]
+    fn empty_point() {
+        let geometry = proto::Geometry {
+            r#type: proto::POINT_TYPE,
+            ..Default::default()
+        };
+        let ParsedGeometry::Point(coord) = decoder().decode(&geometry).unwrap() else {
+            panic!("expected a point");
+        };
+        assert!(coord.x.is_nan() && coord.y.is_nan());
+    }
+
+    #[test]
+    fn partial_coordinate() {
+        let geometry = proto::Geometry {
+            r#type: proto::LINE_STRING_TYPE,
+            coords: vec![0, 0, 1],
+            ..Default::default()
+        };
+        assert!(decoder().decode(&geometry).is_err());
+    }
+
+    #[test]
+    fn lengths_exceed_coordinates() {
+        let geometry = proto::Geometry {
+            r#type: proto::MULTI_LINE_STRING_TYPE,
+            lengths: vec![1, 2],
+            coords: vec![0, 0, 1, 1],
+            ..Default::default()
+        };
+        assert!(decoder().decode(&geometry).is_err());
+    }
+
+    #[test]
+    fn overflowing_ring_lengths() {
+        let geometry = proto::Geometry {
+            r#type: proto::MULTI_POLYGON_TYPE,
+            lengths: vec![1, 2, u32::MAX, 2],
+            coords: vec![0, 0, 1, 1],
+            ..Default::default()
+        };
+        assert!(decoder().decode(&geometry).is_err());
+    }
+}
@@ -0,0 +1,584 @@
+use std::io::Write;
+
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait,
+    MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+};
+use geoarrow_schema::Dimension;
+use indexmap::{IndexMap, IndexSet};
+use prost::Message;
+use prost::encoding::{WireType, encode_key, encode_varint};
+use serde_json::Value;
+use serde_json::value::RawValue;
+
+use crate::NativeArray;
+use crate::array::{AsNativeArray, NativeArrayDyn};
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::geobuf::proto;
+use crate::io::geojson::{FeatureEncoder, GeoJsonWriterOptions};
+use crate::io::stream::RecordBatchReader;
+use crate::trait_::ArrayAccessor;
+
+/// Options for the Geobuf writer.
+#[derive(Debug, Clone)]
+pub struct GeobufWriterOptions {
+    /// The number of decimal digits kept for each coordinate.
+    ///
+    /// Defaults to 6.
+    pub precision: u32,
+
+    /// The name of the column to write as each feature's id, rather than as a property.
+    ///
+    /// Integer columns are written as integer ids, and other columns as string ids.
+    pub id_column: Option<String>,
+}
+
+impl Default for GeobufWriterOptions {
+    fn default() -> Self {
+        Self {
+            precision: 6,
+            id_column: None,
+        }
+    }
+}
+
+/// Write a table to a [Geobuf](https://github.com/mapbox/geobuf) FeatureCollection.
+///
+/// The table must contain exactly one geometry column. Properties are encoded as by the GeoJSON
+/// writer, and written as integer, floating point, boolean, string or JSON values, keeping their
+/// column order. Null values are skipped. Coordinates have 3 dimensions if the geometry column has
+/// Z values.
+///
+/// Since Geobuf stores the property keys and the size of the FeatureCollection ahead of the
+/// features, the encoded features are held in memory until the file is written. For a mixed
+/// geometry column, coordinates are encoded with Z values until the last feature shows whether
+/// any geometry has them.
+pub fn write_geobuf<W: Write, S: Into<RecordBatchReader>>(
+    stream: S,
+    mut writer: W,
+    options: GeobufWriterOptions,
+) -> Result<()> {
+    if options.precision > 15 {
+        return Err(GeoArrowError::General(format!(
+            "Geobuf precision must be at most 15, got {}",
+            options.precision
+        )));
+    }
+
+    let reader = stream.into().into_inner();
+    let schema = reader.schema();
+    let geojson_options = GeoJsonWriterOptions {
+        id_column: options.id_column.clone(),
+        ..Default::default()
+    };
+    let feature_encoder = FeatureEncoder::try_new(&schema, &geojson_options)?;
+    let geometry_column_idx = feature_encoder.geometry_column_idx;
+    let id_column_idx = options
+        .id_column
+        .as_ref()
+        .map(|name| schema.index_of(name))
+        .transpose()?;
+    let keys = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != geometry_column_idx && Some(*idx) != id_column_idx)
+        .map(|(_, field)| field.name().clone())
+        .collect::<IndexSet<_>>();
+
+    let geometry_type = NativeType::try_from(schema.field(geometry_column_idx))?;
+    // Without a declared dimension, Z values are kept until all geometries have been seen
+    let detect_z = geometry_type.dimension().is_none();
+    let mut encoder = GeometryEncoder {
+        dims: match geometry_type.dimension() {
+            Some(Dimension::XYZ | Dimension::XYZM) | None => 3,
+            Some(_) => 2,
+        },
+        scale: 10f64.powi(options.precision as i32),
+        has_z: false,
+    };
+
+    // The `features` field of the FeatureCollection message
+    let mut features = vec![];
+    let mut staged = vec![];
+    for batch in reader {
+        let batch = batch?;
+        let geometry_array = NativeArrayDyn::from_arrow_array(
+            batch.column(geometry_column_idx).as_ref(),
+            schema.field(geometry_column_idx),
+        )?
+        .into_inner();
+        let geometries = encoder.encode_array(geometry_array.as_ref())?;
+        let ids = feature_encoder.encode_ids(&batch)?;
+        let properties = feature_encoder.encode_properties(&batch)?;
+        let mut properties = properties
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty());
+
+        for (geometry, id) in geometries.into_iter().zip(ids) {
+            let mut feature = proto::Feature {
+                geometry,
+                id_type: id.map(id_type),
+                ..Default::default()
+            };
+            if let Some(line) = properties.next() {
+                // Values are kept as raw JSON so that keys stay in their original order
+                let object: IndexMap<String, Box<RawValue>> = serde_json::from_slice(line)?;
+                for (key, value) in object {
+                    let (Some(key_idx), Some(value_type)) =
+                        (keys.get_index_of(&key), value_type(&value)?)
+                    else {
+                        continue;
+                    };
+                    feature.properties.push(key_idx as u32);
+                    feature.properties.push(feature.values.len() as u32);
+                    feature.values.push(proto::Value {
+                        value_type: Some(value_type),
+                    });
+                }
+            }
+            if detect_z {
+                staged.push(feature);
+            } else {
+                prost::encoding::message::encode(1, &feature, &mut features);
+            }
+        }
+    }
+
+    if detect_z && !encoder.has_z {
+        encoder.dims = 2;
+        for feature in staged.iter_mut() {
+            if let Some(geometry) = &mut feature.geometry {
+                drop_z(geometry);
+            }
+        }
+    }
+    for feature in &staged {
+        prost::encoding::message::encode(1, feature, &mut features);
+    }
+
+    let mut output = proto::Data {
+        keys: keys.into_iter().collect(),
+        dimensions: Some(encoder.dims as u32),
+        precision: Some(options.precision),
+        data_type: None,
+    }
+    .encode_to_vec();
+    // The FeatureCollection, as the `data_type` field of the Data message
+    encode_key(4, WireType::LengthDelimited, &mut output);
+    encode_varint(features.len() as u64, &mut output);
+    writer.write_all(&output)?;
+    writer.write_all(&features)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// A feature id, as an integer id if it is an integer and a string id otherwise.
+fn id_type(id: Value) -> proto::IdType {
+    match id {
+        Value::Number(number) if number.is_i64() => proto::IdType::IntId(number.as_i64().unwrap()),
+        Value::String(id) => proto::IdType::Id(id),
+        id => proto::IdType::Id(id.to_string()),
+    }
+}
+
+/// The Geobuf value of a property, or `None` if it is null.
+///
+/// Objects and arrays are written as JSON values.
+fn value_type(value: &RawValue) -> Result<Option<proto::ValueType>> {
+    let json = value.get();
+    if json.starts_with(['{', '[']) {
+        return Ok(Some(proto::ValueType::Json(json.to_string())));
+    }
+    let value_type = match serde_json::from_str(json)? {
+        Value::Null => return Ok(None),
+        Value::Bool(value) => proto::ValueType::Bool(value),
+        Value::Number(number) => {
+            if let Some(value) = number.as_u64() {
+                proto::ValueType::PosInt(value)
+            } else if let Some(value) = number.as_i64() {
+                proto::ValueType::NegInt(value.unsigned_abs())
+            } else {
+                proto::ValueType::Double(number.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        Value::String(value) => proto::ValueType::String(value),
+        value => proto::ValueType::Json(value.to_string()),
+    };
+    Ok(Some(value_type))
+}
+
+/// Drop the Z values of a geometry that was encoded with 3 dimensions.
+///
+/// The X and Y deltas don't depend on Z, so the remaining values are still valid.
+fn drop_z(geometry: &mut proto::Geometry) {
+    geometry.coords = geometry
+        .coords
+        .chunks_exact(3)
+        .flat_map(|coord| [coord[0], coord[1]])
+        .collect();
+    geometry.geometries.iter_mut().for_each(drop_z);
+}
+
+/// Encode each geometry of an array with `encode`, or `None` for nulls.
+fn encode_rows<'a, A: ArrayAccessor<'a>>(
+    array: &'a A,
+    mut encode: impl FnMut(A::Item) -> Result<proto::Geometry>,
+) -> Result<Vec<Option<proto::Geometry>>> {
+    (0..array.len())
+        .map(|row| array.get(row).map(&mut encode).transpose())
+        .collect()
+}
+
+/// Encodes geometries as Geobuf geometries.
+struct GeometryEncoder {
+    dims: usize,
+    scale: f64,
+    /// Whether any encoded coordinate has a Z value.
+    has_z: bool,
+}
+
+impl GeometryEncoder {
+    /// Encode the geometries of a native array, walking its coordinates directly.
+    fn encode_array(&mut self, array: &dyn NativeArray) -> Result<Vec<Option<proto::Geometry>>> {
+        use NativeType::*;
+
+        match array.data_type() {
+            Point(_) => encode_rows(array.as_point(), |point| Ok(self.encode_point(&point))),
+            LineString(_) => encode_rows(array.as_line_string(), |line_string| {
+                Ok(self.encode_line_string(&line_string))
+            }),
+            Polygon(_) => encode_rows(array.as_polygon(), |polygon| {
+                Ok(self.encode_polygon(&polygon))
+            }),
+            MultiPoint(_) => encode_rows(array.as_multi_point(), |multi_point| {
+                Ok(self.encode_multi_point(&multi_point))
+            }),
+            MultiLineString(_) => encode_rows(array.as_multi_line_string(), |multi_line_string| {
+                Ok(self.encode_multi_line_string(&multi_line_string))
+            }),
+            MultiPolygon(_) => encode_rows(array.as_multi_polygon(), |multi_polygon| {
+                Ok(self.encode_multi_polygon(&multi_polygon))
+            }),
+            GeometryCollection(_) => encode_rows(array.as_geometry_collection(), |collection| {
+                self.encode_geometry_collection(&collection)
+            }),
+            Geometry(_) => encode_rows(array.as_geometry(), |geometry| self.encode(&geometry)),
+            Rect(_) => Err(unsupported()),
+        }
+    }
+
+    /// The scaled values of a coordinate, with a Z value of 0 if it has none.
+    fn values(&mut self, coord: &impl CoordTrait<T = f64>) -> [i64; 3] {
+        let z = match coord.dim() {
+            Dimensions::Xyz | Dimensions::Xyzm => {
+                self.has_z = true;
+                coord.nth_or_panic(2)
+            }
+            _ => 0.0,
+        };
+        [coord.x(), coord.y(), z].map(|value| (value * self.scale).round() as i64)
+    }
+
+    /// Append delta-encoded coordinates, returning how many were written.
+    fn push_coords<C: CoordTrait<T = f64>>(
+        &mut self,
+        coords: &mut Vec<i64>,
+        line: impl IntoIterator<Item = C>,
+    ) -> u32 {
+        let mut previous = [0; 3];
+        let mut num_coords = 0;
+        for coord in line {
+            let values = self.values(&coord);
+            for (value, previous) in values.iter().zip(&previous).take(self.dims) {
+                coords.push(value - previous);
+            }
+            previous = values;
+            num_coords += 1;
+        }
+        num_coords
+    }
+
+    /// Append the delta-encoded coordinates of a line or ring, skipping the closing position of a
+    /// ring.
+    fn push_line(
+        &mut self,
+        coords: &mut Vec<i64>,
+        line: &impl LineStringTrait<T = f64>,
+        closed: bool,
+    ) -> u32 {
+        let num_coords = line.num_coords().saturating_sub(closed as usize);
+        self.push_coords(coords, line.coords().take(num_coords))
+    }
+
+    /// Append the rings of a polygon, returning the number of positions written for each.
+    fn push_rings(
+        &mut self,
+        coords: &mut Vec<i64>,
+        polygon: &impl PolygonTrait<T = f64>,
+    ) -> Vec<u32> {
+        let mut lengths = vec![];
+        if let Some(exterior) = polygon.exterior() {
+            lengths.push(self.push_line(coords, &exterior, true));
+        }
+        for interior in polygon.interiors() {
+            lengths.push(self.push_line(coords, &interior, true));
+        }
+        lengths
+    }
+
+    fn encode_point(&mut self, point: &impl PointTrait<T = f64>) -> proto::Geometry {
+        let mut encoded = proto::Geometry {
+            r#type: proto::POINT_TYPE,
+            ..Default::default()
+        };
+        let coord = point
+            .coord()
+            .filter(|coord| !(coord.x().is_nan() && coord.y().is_nan()));
+        self.push_coords(&mut encoded.coords, coord);
+        encoded
+    }
+
+    fn encode_multi_point(
+        &mut self,
+        multi_point: &impl MultiPointTrait<T = f64>,
+    ) -> proto::Geometry {
+        let mut encoded = proto::Geometry {
+            r#type: proto::MULTI_POINT_TYPE,
+            ..Default::default()
+        };
+        self.push_coords(
+            &mut encoded.coords,
+            multi_point.points().filter_map(|point| point.coord()),
+        );
+        encoded
+    }
+
+    fn encode_line_string(
+        &mut self,
+        line_string: &impl LineStringTrait<T = f64>,
+    ) -> proto::Geometry {
+        let mut encoded = proto::Geometry {
+            r#type: proto::LINE_STRING_TYPE,
+            ..Default::default()
+        };
+        self.push_line(&mut encoded.coords, line_string, false);
+        encoded
+    }
+
+    fn encode_multi_line_string(
+        &mut self,
+        multi_line_string: &impl MultiLineStringTrait<T = f64>,
+    ) -> proto::Geometry {
+        let mut encoded = proto::Geometry {
+            r#type: proto::MULTI_LINE_STRING_TYPE,
+            ..Default::default()
+        };
+        let lengths = multi_line_string
+            .line_strings()
+            .map(|line_string| self.push_line(&mut encoded.coords, &line_string, false))
+            .collect::<Vec<_>>();
+        if lengths.len() != 1 {
+            encoded.lengths = lengths;
+        }
+        encoded
+    }
+
+    fn encode_polygon(&mut self, polygon: &impl PolygonTrait<T = f64>) -> proto::Geometry {
+        let mut encoded = proto::Geometry {
+            r#type: proto::POLYGON_TYPE,
+            ..Default::default()
+        };
+        let lengths = self.push_rings(&mut encoded.coords, polygon);
+        if lengths.len() != 1 {
+            encoded.lengths = lengths;
+        }
+        encoded
+    }
+
+    fn encode_multi_polygon(
+        &mut self,
+        multi_polygon: &impl MultiPolygonTrait<T = f64>,
+    ) -> proto::Geometry {
+        let mut encoded = proto::Geometry {
+            r#type: proto::MULTI_POLYGON_TYPE,
+            ..Default::default()
+        };
+        let mut lengths = vec![multi_polygon.num_polygons() as u32];
+        for polygon in multi_polygon.polygons() {
+            let ring_lengths = self.push_rings(&mut encoded.coords, &polygon);
+            lengths.push(ring_lengths.len() as u32);
+            lengths.extend(ring_lengths);
+        }
+        if lengths.get(..2) != Some(&[1, 1]) {
+            encoded.lengths = lengths;
+        }
+        encoded
+    }
+
+    fn encode_geometry_collection(
+        &mut self,
+        collection: &impl GeometryCollectionTrait<T = f64>,
+    ) -> Result<proto::Geometry> {
+        Ok(proto::Geometry {
+            r#type: proto::GEOMETRY_COLLECTION_TYPE,
+            geometries: collection
+                .geometries()
+                .map(|geometry| self.encode(&geometry))
+                .collect::<Result<_>>()?,
+            ..Default::default()
+        })
+    }
+
+    fn encode(&mut self, geometry: &impl GeometryTrait<T = f64>) -> Result<proto::Geometry> {
+        let encoded = match geometry.as_type() {
+            GeometryType::Point(point) => self.encode_point(point),
+            GeometryType::MultiPoint(multi_point) => self.encode_multi_point(multi_point),
+            GeometryType::LineString(line_string) => self.encode_line_string(line_string),
+            GeometryType::MultiLineString(multi_line_string) => {
+                self.encode_multi_line_string(multi_line_string)
+            }
+            GeometryType::Polygon(polygon) => self.encode_polygon(polygon),
+            GeometryType::MultiPolygon(multi_polygon) => self.encode_multi_polygon(multi_polygon),
+            GeometryType::GeometryCollection(collection) => {
+                self.encode_geometry_collection(collection)?
+            }
+            _ => return Err(unsupported()),
+        };
+        Ok(encoded)
+    }
+}
+
+fn unsupported() -> GeoArrowError {
+    GeoArrowError::General("Geobuf can't represent rects, triangles or lines".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::io::geobuf::read_geobuf;
+    use crate::io::geojson::{GeoJsonReaderOptions, GeoJsonRecordBatchReader};
+    use crate::table::Table;
+    use crate::test::point;
+
+    const COLLECTION: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "id": 1,
+                "geometry": {"type": "Point", "coordinates": [0.5, -1.25]},
+                "properties": {"name": "a", "value": -3, "nested": {"x": 1.5}}
+            },
+            {
+                "type": "Feature",
+                "id": 2,
+                "geometry": null,
+                "properties": {"name": "b", "value": 4}
+            },
+            {
+                "type": "Feature",
+                "id": 3,
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [
+                        [[[0, 0], [1, 0], [1, 1], [0, 0]]],
+                        [[[2, 2], [3, 2], [3, 3], [2, 2]], [[2.1, 2.1], [2.2, 2.1], [2.2, 2.2], [2.1, 2.1]]]
+                    ]
+                },
+                "properties": {"name": "c", "value": 0.5}
+            }
+        ]
+    }"#;
+
+    fn id_options() -> GeoJsonReaderOptions {
+        GeoJsonReaderOptions {
+            id_column: Some("id".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn read_geojson(collection: &str) -> Table {
+        let reader =
+            GeoJsonRecordBatchReader::try_new(collection.as_bytes(), id_options()).unwrap();
+        let schema = reader.schema();
+        let batches = reader.collect::<std::result::Result<_, _>>().unwrap();
+        Table::try_new(batches, schema).unwrap()
+    }
+
+    fn geojson(table: &Table) -> Value {
+        let mut output = vec![];
+        crate::io::geojson::write_geojson(table, &mut output, Default::default()).unwrap();
+        serde_json::from_slice(&output).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let table = read_geojson(COLLECTION);
+        let mut output = vec![];
+        write_geobuf(
+            &table,
+            &mut output,
+            GeobufWriterOptions {
+                id_column: Some("id".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let reader = read_geobuf(output.as_slice(), id_options()).unwrap();
+        let table2: Table = reader.try_into().unwrap();
+        let batch = &table2.batches()[0];
+        assert_eq!(
+            batch.column(0).as_primitive::<Int64Type>().values(),
+            &[1, 2, 3]
+        );
+        assert_eq!(batch.column(1).as_string::<i32>().value(2), "c");
+        assert_eq!(
+            batch.column(2).as_primitive::<Float64Type>().values(),
+            &[-3.0, 4.0, 0.5]
+        );
+        assert_eq!(geojson(&table), geojson(&table2));
+    }
+
+    #[test]
+    fn z_dimension() {
+        let mut encoder = GeometryEncoder {
+            dims: 3,
+            scale: 1.0,
+            has_z: false,
+        };
+        let line_string = wkt::Wkt::<f64>::from_str("LINESTRING Z (0 0 1, 1 1 3)").unwrap();
+        let point = wkt::Wkt::<f64>::from_str("POINT (0 0)").unwrap();
+        assert_eq!(
+            encoder.encode(&line_string).unwrap().coords,
+            [0, 0, 1, 1, 1, 2]
+        );
+        assert_eq!(encoder.encode(&point).unwrap().coords, [0, 0, 0]);
+    }
+
+    #[test]
+    fn z_round_trip() {
+        let geometry = point::point_z_array();
+        let field = geometry.extension_field();
+        let schema = Arc::new(Schema::new(vec![field]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![geometry.into_array_ref()]).unwrap();
+        let table = Table::try_new(vec![batch], schema).unwrap();
+
+        let mut output = vec![];
+        write_geobuf(&table, &mut output, Default::default()).unwrap();
+        let data = proto::Data::decode(output.as_slice()).unwrap();
+        assert_eq!(data.dimensions, Some(3));
+
+        let reader = read_geobuf(output.as_slice(), Default::default()).unwrap();
+        let table2: Table = reader.try_into().unwrap();
+        let mut output2 = vec![];
+        write_geobuf(&table2, &mut output2, Default::default()).unwrap();
+        assert_eq!(output, output2);
+    }
+}
@@ -0,0 +1,611 @@
+//! GeoJSON geometries, and the transformations applied to them when writing RFC 7946 compliant
+//! GeoJSON.
+
+use std::io::Write;
+
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait,
+    LineTrait, MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+    RectTrait, TriangleTrait,
+};
+
+use crate::error::Result;
+
+/// A GeoJSON position. M values are not part of GeoJSON and are dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Position {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) z: Option<f64>,
+}
+
+impl Position {
+    fn new(coord: &impl CoordTrait<T = f64>) -> Self {
+        let z = match coord.dim() {
+            Dimensions::Xyz | Dimensions::Xyzm => coord.nth(2),
+            _ => None,
+        };
+        Self {
+            x: coord.x(),
+            y: coord.y(),
+            z,
+        }
+    }
+
+    /// The position where the segment from `self` to `other` crosses the meridian at `x`.
+    fn interpolate(&self, other: &Self, x: f64) -> Self {
+        let t = (x - self.x) / (other.x - self.x);
+        Self {
+            x,
+            y: self.y + t * (other.y - self.y),
+            z: match (self.z, other.z) {
+                (Some(z1), Some(z2)) => Some(z1 + t * (z2 - z1)),
+                _ => None,
+            },
+        }
+    }
+
+    fn shift(self, dx: f64) -> Self {
+        Self {
+            x: self.x + dx,
+            ..self
+        }
+    }
+}
+
+/// A GeoJSON geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Geometry {
+    Point(Option<Position>),
+    LineString(Vec<Position>),
+    Polygon(Vec<Vec<Position>>),
+    MultiPoint(Vec<Position>),
+    MultiLineString(Vec<Vec<Position>>),
+    MultiPolygon(Vec<Vec<Vec<Position>>>),
+    GeometryCollection(Vec<Geometry>),
+}
+
+impl Geometry {
+    pub(crate) fn new(geometry: &impl GeometryTrait<T = f64>) -> Self {
+        match geometry.as_type() {
+            GeometryType::Point(point) => Self::Point(point.coord().map(|c| Position::new(&c))),
+            GeometryType::LineString(line_string) => Self::LineString(line_positions(line_string)),
+            GeometryType::Polygon(polygon) => Self::Polygon(polygon_positions(polygon)),
+            GeometryType::MultiPoint(multi_point) => Self::MultiPoint(
+                multi_point
+                    .points()
+                    .filter_map(|point| point.coord().map(|c| Position::new(&c)))
+                    .collect(),
+            ),
+            GeometryType::MultiLineString(multi_line_string) => Self::MultiLineString(
+                multi_line_string
+                    .line_strings()
+                    .map(|line_string| line_positions(&line_string))
+                    .collect(),
+            ),
+            GeometryType::MultiPolygon(multi_polygon) => Self::MultiPolygon(
+                multi_polygon
+                    .polygons()
+                    .map(|polygon| polygon_positions(&polygon))
+                    .collect(),
+            ),
+            GeometryType::GeometryCollection(collection) => Self::GeometryCollection(
+                collection
+                    .geometries()
+                    .map(|geometry| Self::new(&geometry))
+                    .collect(),
+            ),
+            GeometryType::Rect(rect) => {
+                let (min, max) = (Position::new(&rect.min()), Position::new(&rect.max()));
+                let corner = |x, y| Position { x, y, z: min.z };
+                Self::Polygon(vec![vec![
+                    corner(min.x, min.y),
+                    corner(max.x, min.y),
+                    corner(max.x, max.y),
+                    corner(min.x, max.y),
+                    corner(min.x, min.y),
+                ]])
+            }
+            GeometryType::Triangle(triangle) => {
+                let mut ring = triangle
+                    .coords()
+                    .iter()
+                    .map(Position::new)
+                    .collect::<Vec<_>>();
+                ring.push(ring[0]);
+                Self::Polygon(vec![ring])
+            }
+            GeometryType::Line(line) => Self::LineString(vec![
+                Position::new(&line.start()),
+                Position::new(&line.end()),
+            ]),
+        }
+    }
+
+    /// Apply a function to every position of the geometry.
+    pub(crate) fn try_for_each_position(
+        &mut self,
+        f: &mut impl FnMut(&mut Position) -> Result<()>,
+    ) -> Result<()> {
+        match self {
+            Self::Point(position) => position.iter_mut().try_for_each(f),
+            Self::LineString(positions) | Self::MultiPoint(positions) => {
+                positions.iter_mut().try_for_each(f)
+            }
+            Self::Polygon(lines) | Self::MultiLineString(lines) => {
+                lines.iter_mut().flatten().try_for_each(f)
+            }
+            Self::MultiPolygon(polygons) => polygons.iter_mut().flatten().flatten().try_for_each(f),
+            Self::GeometryCollection(geometries) => geometries
+                .iter_mut()
+                .try_for_each(|geometry| geometry.try_for_each_position(f)),
+        }
+    }
+
+    /// Orient polygon rings following the right-hand rule: exterior rings counterclockwise and
+    /// interior rings clockwise.
+    pub(crate) fn orient(&mut self) {
+        match self {
+            Self::Polygon(rings) => orient_polygon(rings),
+            Self::MultiPolygon(polygons) => polygons.iter_mut().for_each(orient_polygon),
+            Self::GeometryCollection(geometries) => geometries.iter_mut().for_each(Self::orient),
+            _ => {}
+        }
+    }
+
+    /// Split line strings and polygons that cross the antimeridian into parts on either side of
+    /// it.
+    pub(crate) fn split_antimeridian(self) -> Self {
+        match self {
+            Self::LineString(line) => {
+                let mut lines = split_line(line);
+                if lines.len() == 1 {
+                    Self::LineString(lines.remove(0))
+                } else {
+                    Self::MultiLineString(lines)
+                }
+            }
+            Self::MultiLineString(lines) => {
+                Self::MultiLineString(lines.into_iter().flat_map(split_line).collect())
+            }
+            Self::Polygon(polygon) => {
+                let mut polygons = split_polygon(polygon);
+                if polygons.len() == 1 {
+                    Self::Polygon(polygons.remove(0))
+                } else {
+                    Self::MultiPolygon(polygons)
+                }
+            }
+            Self::MultiPolygon(polygons) => {
+                Self::MultiPolygon(polygons.into_iter().flat_map(split_polygon).collect())
+            }
+            Self::GeometryCollection(geometries) => Self::GeometryCollection(
+                geometries
+                    .into_iter()
+                    .map(Self::split_antimeridian)
+                    .collect(),
+            ),
+            geometry => geometry,
+        }
+    }
+
+    /// Write the geometry as a GeoJSON geometry object.
+    pub(crate) fn write<W: Write>(&self, writer: &mut W, precision: Option<usize>) -> Result<()> {
+        let type_name = match self {
+            Self::Point(_) => "Point",
+            Self::LineString(_) => "LineString",
+            Self::Polygon(_) => "Polygon",
+            Self::MultiPoint(_) => "MultiPoint",
+            Self::MultiLineString(_) => "MultiLineString",
+            Self::MultiPolygon(_) => "MultiPolygon",
+            Self::GeometryCollection(geometries) => {
+                writer.write_all(br#"{"type":"GeometryCollection","geometries":["#)?;
+                for (i, geometry) in geometries.iter().enumerate() {
+                    if i > 0 {
+                        writer.write_all(b",")?;
+                    }
+                    geometry.write(writer, precision)?;
+                }
+                writer.write_all(b"]}")?;
+                return Ok(());
+            }
+        };
+        write!(writer, r#"{{"type":"{type_name}","coordinates":"#)?;
+        match self {
+            Self::Point(Some(position)) => write_position(writer, position, precision)?,
+            Self::Point(None) => writer.write_all(b"[]")?,
+            Self::LineString(positions) | Self::MultiPoint(positions) => {
+                write_positions(writer, positions, precision)?
+            }
+            Self::Polygon(lines) | Self::MultiLineString(lines) => {
+                write_list(writer, lines, |writer, line| {
+                    write_positions(writer, line, precision)
+                })?
+            }
+            Self::MultiPolygon(polygons) => write_list(writer, polygons, |writer, polygon| {
+                write_list(writer, polygon, |writer, ring| {
+                    write_positions(writer, ring, precision)
+                })
+            })?,
+            Self::GeometryCollection(_) => unreachable!(),
+        }
+        writer.write_all(b"}")?;
+        Ok(())
+    }
+}
+
+fn line_positions(line_string: &impl LineStringTrait<T = f64>) -> Vec<Position> {
+    line_string.coords().map(|c| Position::new(&c)).collect()
+}
+
+fn polygon_positions(polygon: &impl PolygonTrait<T = f64>) -> Vec<Vec<Position>> {
+    polygon
+        .exterior()
+        .into_iter()
+        .map(|ring| line_positions(&ring))
+        .chain(polygon.interiors().map(|ring| line_positions(&ring)))
+        .collect()
+}
+
+/// The bounding box of a set of positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct BoundingBox {
+    min: Option<Position>,
+    max: Option<Position>,
+}
+
+impl BoundingBox {
+    pub(crate) fn add_position(&mut self, position: &Position) {
+        let (min, max) = match (self.min, self.max) {
+            (Some(min), Some(max)) => (min, max),
+            _ => (*position, *position),
+        };
+        let z = |a: Option<f64>, b: Option<f64>, f: fn(f64, f64) -> f64| match (a, b) {
+            (Some(a), Some(b)) => Some(f(a, b)),
+            (a, b) => a.or(b),
+        };
+        self.min = Some(Position {
+            x: min.x.min(position.x),
+            y: min.y.min(position.y),
+            z: z(min.z, position.z, f64::min),
+        });
+        self.max = Some(Position {
+            x: max.x.max(position.x),
+            y: max.y.max(position.y),
+            z: z(max.z, position.z, f64::max),
+        });
+    }
+
+    pub(crate) fn add_geometry(&mut self, geometry: &mut Geometry) -> Result<()> {
+        geometry.try_for_each_position(&mut |position| {
+            self.add_position(position);
+            Ok(())
+        })
+    }
+
+    pub(crate) fn add_bbox(&mut self, other: &Self) {
+        if let (Some(min), Some(max)) = (other.min, other.max) {
+            self.add_position(&min);
+            self.add_position(&max);
+        }
+    }
+
+    /// Write the bounding box as a GeoJSON `bbox` array, or nothing if it is empty.
+    pub(crate) fn write<W: Write>(&self, writer: &mut W, precision: Option<usize>) -> Result<()> {
+        let (Some(min), Some(max)) = (self.min, self.max) else {
+            return Ok(());
+        };
+        let mut values = vec![min.x, min.y];
+        values.extend(min.z);
+        values.extend([max.x, max.y]);
+        values.extend(max.z);
+        writer.write_all(b"[")?;
+        for (i, value) in values.into_iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            write_number(writer, value, precision)?;
+        }
+        writer.write_all(b"]")?;
+        Ok(())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.min.is_none()
+    }
+}
+
+fn write_list<W: Write, T>(
+    writer: &mut W,
+    items: &[T],
+    mut write_item: impl FnMut(&mut W, &T) -> Result<()>,
+) -> Result<()> {
+    writer.write_all(b"[")?;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        write_item(writer, item)?;
+    }
+    writer.write_all(b"]")?;
+    Ok(())
+}
+
+fn write_positions<W: Write>(
+    writer: &mut W,
+    positions: &[Position],
+    precision: Option<usize>,
+) -> Result<()> {
+    write_list(writer, positions, |writer, position| {
+        write_position(writer, position, precision)
+    })
+}
+
+fn write_position<W: Write>(
+    writer: &mut W,
+    position: &Position,
+    precision: Option<usize>,
+) -> Result<()> {
+    writer.write_all(b"[")?;
+    write_number(writer, position.x, precision)?;
+    writer.write_all(b",")?;
+    write_number(writer, position.y, precision)?;
+    if let Some(z) = position.z {
+        writer.write_all(b",")?;
+        write_number(writer, z, precision)?;
+    }
+    writer.write_all(b"]")?;
+    Ok(())
+}
+
+/// Write a number with at most `precision` decimal places, without trailing zeros.
+fn write_number<W: Write>(writer: &mut W, value: f64, precision: Option<usize>) -> Result<()> {
+    if !value.is_finite() {
+        writer.write_all(b"null")?;
+        return Ok(());
+    }
+    match precision {
+        Some(precision) => {
+            let formatted = format!("{value:.precision$}");
+            let trimmed = if formatted.contains('.') {
+                formatted.trim_end_matches('0').trim_end_matches('.')
+            } else {
+                &formatted
+            };
+            match trimmed {
+                "-0" => writer.write_all(b"0")?,
+                trimmed => writer.write_all(trimmed.as_bytes())?,
+            }
+        }
+        None => write!(writer, "{value}")?,
+    }
+    Ok(())
+}
+
+/// Twice the signed area of a ring, positive if the ring is counterclockwise.
+fn signed_area(ring: &[Position]) -> f64 {
+    ring.windows(2)
+        .map(|pair| pair[0].x * pair[1].y - pair[1].x * pair[0].y)
+        .sum()
+}
+
+fn orient_polygon(rings: &mut Vec<Vec<Position>>) {
+    for (i, ring) in rings.iter_mut().enumerate() {
+        let counterclockwise = signed_area(ring) > 0.;
+        // The first ring is the exterior
+        if counterclockwise != (i == 0) {
+            ring.reverse();
+        }
+    }
+}
+
+/// Whether any segment of a line crosses the antimeridian, taken as a longitude jump of more than
+/// 180 degrees.
+fn crosses_antimeridian(line: &[Position]) -> bool {
+    line.windows(2)
+        .any(|pair| (pair[1].x - pair[0].x).abs() > 180.)
+}
+
+fn split_line(line: Vec<Position>) -> Vec<Vec<Position>> {
+    if !crosses_antimeridian(&line) {
+        return vec![line];
+    }
+
+    let mut lines = vec![];
+    let mut current = vec![line[0]];
+    for pair in line.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let dx = end.x - start.x;
+        if dx.abs() > 180. {
+            // Cross at +180 when moving east, and at -180 when moving west
+            let meridian = if dx < 0. { 180. } else { -180. };
+            let unwrapped_end = end.shift(2. * meridian);
+            let crossing = start.interpolate(&unwrapped_end, meridian);
+            current.push(crossing);
+            lines.push(std::mem::take(&mut current));
+            current.push(crossing.shift(-2. * meridian));
+        }
+        current.push(end);
+    }
+    lines.push(current);
+    lines
+}
+
+/// Make the longitudes of a ring continuous, by shifting each position by a multiple of 360
+/// degrees so that no segment is longer than 180 degrees.
+fn unwrap_ring(ring: &mut [Position]) {
+    for i in 1..ring.len() {
+        let previous = ring[i - 1].x;
+        let position = &mut ring[i];
+        position.x -= ((position.x - previous) / 360.).round() * 360.;
+    }
+}
+
+fn split_polygon(mut polygon: Vec<Vec<Position>>) -> Vec<Vec<Vec<Position>>> {
+    if !polygon.iter().any(|ring| crosses_antimeridian(ring)) {
+        return vec![polygon];
+    }
+
+    for ring in polygon.iter_mut() {
+        unwrap_ring(ring);
+    }
+    // A ring that doesn't close once unwrapped encircles a pole, and can't be split
+    if polygon
+        .iter()
+        .any(|ring| ring.first().map(|p| p.x) != ring.last().map(|p| p.x))
+    {
+        return vec![polygon];
+    }
+
+    // Shift rings so the exterior extends east of the antimeridian, and the interiors lie within
+    // the exterior's longitudes
+    let (min_x, max_x) = polygon[0]
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| {
+            (min.min(p.x), max.max(p.x))
+        });
+    let exterior_shift = if min_x < -180. { 360. } else { 0. };
+    let (min_x, max_x) = (min_x + exterior_shift, max_x + exterior_shift);
+    for (i, ring) in polygon.iter_mut().enumerate() {
+        let shift = if i == 0 {
+            exterior_shift
+        } else {
+            let first = ring[0].x;
+            let mut shift = 0.;
+            while first + shift < min_x {
+                shift += 360.;
+            }
+            while first + shift > max_x {
+                shift -= 360.;
+            }
+            shift
+        };
+        for position in ring.iter_mut() {
+            *position = position.shift(shift);
+        }
+    }
+
+    [false, true]
+        .into_iter()
+        .filter_map(|east| {
+            let mut rings = polygon.iter().map(|ring| clip_ring(ring, east));
+            let exterior = rings.next().filter(|ring| ring.len() >= 4)?;
+            Some(
+                std::iter::once(exterior)
+                    .chain(rings.filter(|ring| ring.len() >= 4))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Clip a ring to the part west or east of the antimeridian at longitude 180.
+///
+/// The eastern part is shifted back to longitudes from -180. Clipping a concave ring can produce
+/// edges along the antimeridian which connect its separate parts.
+fn clip_ring(ring: &[Position], east: bool) -> Vec<Position> {
+    let inside = |position: &Position| {
+        if east {
+            position.x >= 180.
+        } else {
+            position.x <= 180.
+        }
+    };
+
+    let mut clipped = vec![];
+    for pair in ring.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        match (inside(&start), inside(&end)) {
+            (true, true) => clipped.push(end),
+            (true, false) => clipped.push(start.interpolate(&end, 180.)),
+            (false, true) => {
+                clipped.push(start.interpolate(&end, 180.));
+                clipped.push(end);
+            }
+            (false, false) => {}
+        }
+    }
+    if clipped.first() != clipped.last() {
+        clipped.push(clipped[0]);
+    }
+
+    if east {
+        clipped
+            .iter()
+            .map(|position| position.shift(-360.))
+            .collect()
+    } else {
+        clipped
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn xy(x: f64, y: f64) -> Position {
+        Position { x, y, z: None }
+    }
+
+    #[test]
+    fn split_line_string() {
+        let line = Geometry::LineString(vec![xy(170., 0.), xy(-170., 10.)]);
+        let expected = Geometry::MultiLineString(vec![
+            vec![xy(170., 0.), xy(180., 5.)],
+            vec![xy(-180., 5.), xy(-170., 10.)],
+        ]);
+        assert_eq!(line.split_antimeridian(), expected);
+    }
+
+    #[test]
+    fn split_polygon() {
+        let polygon = Geometry::Polygon(vec![vec![
+            xy(170., 0.),
+            xy(-170., 0.),
+            xy(-170., 10.),
+            xy(170., 10.),
+            xy(170., 0.),
+        ]]);
+        let Geometry::MultiPolygon(polygons) = polygon.split_antimeridian() else {
+            panic!("expected a MultiPolygon");
+        };
+        assert_eq!(polygons.len(), 2);
+        assert!(polygons[0][0].iter().all(|p| (170. ..=180.).contains(&p.x)));
+        assert!(
+            polygons[1][0]
+                .iter()
+                .all(|p| (-180. ..=-170.).contains(&p.x))
+        );
+    }
+
+    #[test]
+    fn orient() {
+        let mut polygon = Geometry::Polygon(vec![
+            vec![
+                xy(0., 0.),
+                xy(0., 10.),
+                xy(10., 10.),
+                xy(10., 0.),
+                xy(0., 0.),
+            ],
+            vec![xy(2., 2.), xy(4., 2.), xy(4., 4.), xy(2., 2.)],
+        ]);
+        polygon.orient();
+        let Geometry::Polygon(rings) = polygon else {
+            unreachable!()
+        };
+        assert!(signed_area(&rings[0]) > 0.);
+        assert!(signed_area(&rings[1]) < 0.);
+    }
+
+    #[test]
+    fn precision() {
+        let mut output = vec![];
+        let point = Geometry::Point(Some(xy(1.23456789, -0.0000001)));
+        point.write(&mut output, Some(3)).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"{"type":"Point","coordinates":[1.235,0]}"#
+        );
+    }
+}
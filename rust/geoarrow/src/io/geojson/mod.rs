@@ -1,7 +1,13 @@
 //! Read from and write to [GeoJSON](https://geojson.org/) files.
 
 pub use reader::read_geojson;
-pub use writer::write_geojson;
+pub use stream::{GeoJsonReaderOptions, GeoJsonRecordBatchReader};
+pub use writer::{GeoJsonWriterOptions, write_geojson};
 
+pub(crate) use geometry::{Geometry, Position};
+pub(crate) use writer::FeatureEncoder;
+
+mod geometry;
 mod reader;
+mod stream;
 mod writer;
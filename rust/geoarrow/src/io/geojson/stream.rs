@@ -0,0 +1,589 @@
+//! Streaming reader of GeoJSON FeatureCollections to Arrow record batches.
+
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::sync::Arc;
+
+use arrow_array::{RecordBatch, RecordBatchOptions, RecordBatchReader};
+use arrow_json::reader::{Decoder, ReaderBuilder, infer_json_schema_from_iterator};
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+use geoarrow_schema::{CoordType, GeometryType, Metadata};
+use geozero::GeozeroGeometry;
+use geozero::geojson::GeoJson;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use serde_json::{Map, Value};
+
+use crate::ArrayBase;
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::geozero::array::GeometryStreamBuilder;
+use crate::io::parsed::KeyOrder;
+
+/// Options for the streaming GeoJSON reader.
+#[derive(Debug, Clone)]
+pub struct GeoJsonReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+
+    /// The Arrow schema of the non-geometry columns, including the id column if `id_column` is
+    /// set.
+    ///
+    /// Feature properties not present in this schema are skipped. If `None`, the schema is
+    /// inferred from the first `infer_schema_length` features. Nested objects are inferred as
+    /// `Struct` columns and arrays as `List` columns.
+    pub schema: Option<SchemaRef>,
+
+    /// The maximum number of features to read when inferring the schema.
+    pub infer_schema_length: usize,
+
+    /// The name of the column that each feature's top-level `id` member is stored in.
+    ///
+    /// If a feature has a property of the same name, the property is read instead. If `None`,
+    /// feature ids are not read.
+    pub id_column: Option<String>,
+}
+
+impl Default for GeoJsonReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+            schema: None,
+            infer_schema_length: 1000,
+            id_column: None,
+        }
+    }
+}
+
+/// A streaming reader of the features in a GeoJSON FeatureCollection.
+///
+/// Features are read from the underlying reader one at a time, so the input is never held in
+/// memory as a whole. Only the features used for schema inference are buffered up front.
+///
+/// The output schema consists of the non-geometry columns followed by a `geometry` column of
+/// GeoArrow geometry type.
+pub struct GeoJsonRecordBatchReader<R> {
+    features: FeatureScanner<R>,
+    /// Features read ahead of time during schema inference.
+    buffered: VecDeque<Feature>,
+    /// The decoder of property columns, or `None` if there are none.
+    decoder: Option<Decoder>,
+    schema: SchemaRef,
+    coord_type: CoordType,
+    metadata: Arc<Metadata>,
+    batch_size: usize,
+    id_column: Option<String>,
+}
+
+impl<R: BufRead> GeoJsonRecordBatchReader<R> {
+    /// Create a new reader over a GeoJSON FeatureCollection.
+    ///
+    /// If no schema is provided, this reads up to `infer_schema_length` features to infer one.
+    pub fn try_new(reader: R, options: GeoJsonReaderOptions) -> Result<Self> {
+        let batch_size = options.batch_size.unwrap_or(65_536);
+        let mut features = FeatureScanner::new(reader);
+        let mut buffered = VecDeque::new();
+
+        let properties_schema = match options.schema {
+            Some(schema) => schema,
+            None => {
+                let mut key_order = KeyOrder::default();
+                if let Some(id_column) = &options.id_column {
+                    key_order.insert(id_column);
+                }
+                while buffered.len() < options.infer_schema_length {
+                    match features.next_feature()? {
+                        Some(bytes) => buffered.push_back(Feature::try_new(
+                            &bytes,
+                            options.id_column.as_deref(),
+                            Some(&mut key_order),
+                        )?),
+                        None => break,
+                    }
+                }
+                let schema = infer_json_schema_from_iterator(
+                    buffered.iter().map(|feature| Ok(&feature.properties)),
+                )?;
+                Arc::new(key_order.sort_schema(&schema))
+            }
+        };
+
+        let decoder = if properties_schema.fields().is_empty() {
+            None
+        } else {
+            Some(
+                ReaderBuilder::new(properties_schema.clone())
+                    .with_batch_size(batch_size)
+                    .with_coerce_primitive(true)
+                    .build_decoder()?,
+            )
+        };
+
+        let metadata = Arc::new(Metadata::default());
+        let geometry_type =
+            NativeType::Geometry(GeometryType::new(options.coord_type, metadata.clone()));
+        let mut fields = properties_schema.fields().to_vec();
+        fields.push(Arc::new(geometry_type.to_field("geometry", true)));
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            properties_schema.metadata().clone(),
+        ));
+
+        Ok(Self {
+            features,
+            buffered,
+            decoder,
+            schema,
+            coord_type: options.coord_type,
+            metadata,
+            batch_size,
+            id_column: options.id_column,
+        })
+    }
+
+    fn next_feature(&mut self) -> Result<Option<Feature>> {
+        if let Some(feature) = self.buffered.pop_front() {
+            return Ok(Some(feature));
+        }
+        self.features
+            .next_feature()?
+            .map(|bytes| Feature::try_new(&bytes, self.id_column.as_deref(), None))
+            .transpose()
+    }
+
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let mut geometries =
+            GeometryStreamBuilder::new_with_options(self.coord_type, self.metadata.clone(), true);
+        let mut rows = Vec::new();
+        while rows.len() < self.batch_size {
+            let Some(feature) = self.next_feature()? else {
+                break;
+            };
+            match &feature.geometry {
+                Some(geometry) => GeoJson(geometry.get()).process_geom(&mut geometries)?,
+                None => geometries.push_null(),
+            }
+            rows.push(feature.properties);
+        }
+
+        if rows.is_empty() {
+            return Ok(None);
+        }
+
+        let mut columns = match &mut self.decoder {
+            Some(decoder) => {
+                decoder.serialize(&rows)?;
+                match decoder.flush()? {
+                    Some(batch) => batch.columns().to_vec(),
+                    None => vec![],
+                }
+            }
+            None => vec![],
+        };
+        columns.push(geometries.finish().into_array_ref());
+
+        let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+        Ok(Some(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &options,
+        )?))
+    }
+}
+
+impl<R: BufRead> Iterator for GeoJsonRecordBatchReader<R> {
+    type Item = std::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch()
+            .map_err(|err| ArrowError::ExternalError(Box::new(err)))
+            .transpose()
+    }
+}
+
+impl<R: BufRead> RecordBatchReader for GeoJsonRecordBatchReader<R> {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// The members of a GeoJSON feature that are read.
+#[derive(Deserialize)]
+struct RawFeature {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(default)]
+    geometry: Option<Box<RawValue>>,
+    #[serde(default)]
+    properties: Option<Box<RawValue>>,
+}
+
+/// A parsed GeoJSON feature.
+struct Feature {
+    /// The unparsed GeoJSON geometry, or `None` if the geometry is null.
+    geometry: Option<Box<RawValue>>,
+    /// A JSON object holding the feature's properties and its id.
+    properties: Value,
+}
+
+impl Feature {
+    /// Parse a feature, adding the order of its property keys to `key_order` if given.
+    fn try_new(
+        bytes: &[u8],
+        id_column: Option<&str>,
+        key_order: Option<&mut KeyOrder>,
+    ) -> Result<Self> {
+        let feature: RawFeature = serde_json::from_slice(bytes)?;
+        let mut properties = match &feature.properties {
+            Some(properties) => {
+                serde_json::from_str::<Option<Map<String, Value>>>(properties.get())?
+                    .unwrap_or_default()
+            }
+            None => Map::new(),
+        };
+        if let (Some(id_column), Some(id)) = (id_column, feature.id) {
+            properties.entry(id_column).or_insert(id);
+        }
+        if let (Some(key_order), Some(properties)) = (key_order, &feature.properties) {
+            key_order.merge(serde_json::from_str(properties.get())?);
+        }
+        Ok(Self {
+            geometry: feature.geometry,
+            properties: Value::Object(properties),
+        })
+    }
+}
+
+/// Scans the bytes of a GeoJSON FeatureCollection, yielding the raw JSON of each element of its
+/// top-level `features` array.
+///
+/// This only tracks enough of the JSON structure (strings and nesting) to find the boundaries of
+/// each feature. Each feature is validated when it is parsed.
+struct FeatureScanner<R> {
+    reader: R,
+    state: ScannerState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScannerState {
+    /// The start of the top-level object has not been read yet.
+    Start,
+    /// Inside the `features` array.
+    Features,
+    /// The end of the `features` array, or of the top-level object, has been reached.
+    Done,
+}
+
+impl<R: BufRead> FeatureScanner<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            state: ScannerState::Start,
+        }
+    }
+
+    fn next_feature(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.state == ScannerState::Start {
+            self.find_features()?;
+        }
+        if self.state == ScannerState::Done {
+            return Ok(None);
+        }
+
+        match self.next_token()? {
+            b']' => {
+                self.state = ScannerState::Done;
+                return Ok(None);
+            }
+            b',' => {
+                self.next_token()?;
+            }
+            _ => {}
+        }
+        if self.peek()? != Some(b'{') {
+            return Err(invalid("expected a feature object in the features array"));
+        }
+        let mut feature = Vec::new();
+        self.read_value(Some(&mut feature))?;
+        Ok(Some(feature))
+    }
+
+    /// Advance to the first element of the top-level `features` array.
+    fn find_features(&mut self) -> Result<()> {
+        if self.next_token()? != b'{' {
+            return Err(invalid("expected a JSON object"));
+        }
+        self.reader.consume(1);
+        loop {
+            match self.next_token()? {
+                b'}' => {
+                    self.state = ScannerState::Done;
+                    return Ok(());
+                }
+                b',' => {
+                    self.reader.consume(1);
+                    self.next_token()?;
+                }
+                _ => {}
+            }
+
+            let mut key = Vec::new();
+            self.read_string(Some(&mut key))?;
+            if self.next_token()? != b':' {
+                return Err(invalid("expected ':' after object key"));
+            }
+            self.reader.consume(1);
+
+            if key == b"\"features\"" {
+                if self.next_token()? != b'[' {
+                    return Err(invalid("expected the features member to be an array"));
+                }
+                self.reader.consume(1);
+                self.state = ScannerState::Features;
+                return Ok(());
+            }
+            self.next_token()?;
+            self.read_value(None)?;
+        }
+    }
+
+    /// Skip whitespace and return the next byte without consuming it.
+    ///
+    /// The separators `,` and `]` are consumed when returned from within the features array.
+    fn next_token(&mut self) -> Result<u8> {
+        loop {
+            let Some(byte) = self.peek()? else {
+                return Err(invalid("unexpected end of input"));
+            };
+            if byte.is_ascii_whitespace() {
+                self.reader.consume(1);
+                continue;
+            }
+            if self.state == ScannerState::Features && matches!(byte, b',' | b']') {
+                self.reader.consume(1);
+            }
+            return Ok(byte);
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn next_byte(&mut self, sink: &mut Option<&mut Vec<u8>>) -> Result<u8> {
+        let byte = self
+            .peek()?
+            .ok_or_else(|| invalid("unexpected end of input"))?;
+        self.reader.consume(1);
+        if let Some(sink) = sink {
+            sink.push(byte);
+        }
+        Ok(byte)
+    }
+
+    /// Read a string, including its quotes, into `sink`.
+    fn read_string(&mut self, mut sink: Option<&mut Vec<u8>>) -> Result<()> {
+        if self.next_byte(&mut sink)? != b'"' {
+            return Err(invalid("expected a string"));
+        }
+        loop {
+            match self.next_byte(&mut sink)? {
+                b'"' => return Ok(()),
+                b'\\' => {
+                    self.next_byte(&mut sink)?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Read any JSON value into `sink`.
+    fn read_value(&mut self, mut sink: Option<&mut Vec<u8>>) -> Result<()> {
+        match self.peek()? {
+            Some(b'"') => self.read_string(sink),
+            Some(b'{' | b'[') => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        Some(b'"') => self.read_string(sink.as_deref_mut())?,
+                        _ => match self.next_byte(&mut sink)? {
+                            b'{' | b'[' => depth += 1,
+                            b'}' | b']' => {
+                                depth -= 1;
+                                if depth == 0 {
+                                    return Ok(());
+                                }
+                            }
+                            _ => {}
+                        },
+                    }
+                }
+            }
+            _ => {
+                // A number, boolean or null, which ends at the next delimiter
+                while let Some(byte) = self.peek()? {
+                    if matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace() {
+                        break;
+                    }
+                    self.next_byte(&mut sink)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn invalid(message: &str) -> GeoArrowError {
+    GeoArrowError::General(format!("Invalid GeoJSON FeatureCollection: {message}"))
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::Array;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, Int64Type};
+    use arrow_schema::{DataType, Field};
+
+    use super::*;
+
+    const COLLECTION: &str = r#"{
+        "type": "FeatureCollection",
+        "name": "test, with \"escapes\" ]}",
+        "features": [
+            {
+                "type": "Feature",
+                "id": 1,
+                "geometry": {"type": "Point", "coordinates": [0.0, 1.0]},
+                "properties": {"name": "a", "nested": {"x": 1.5, "tags": ["u", "v"]}}
+            },
+            {
+                "type": "Feature",
+                "id": 2,
+                "geometry": null,
+                "properties": {"name": "b]}", "nested": {"x": 2.5, "tags": []}}
+            },
+            {
+                "type": "Feature",
+                "id": 3,
+                "geometry": {"type": "LineString", "coordinates": [[0.0, 0.0], [1.0, 1.0]]},
+                "properties": null
+            }
+        ],
+        "bbox": [0.0, 0.0, 1.0, 1.0]
+    }"#;
+
+    #[test]
+    fn infer_schema() {
+        let options = GeoJsonReaderOptions {
+            batch_size: Some(2),
+            id_column: Some("id".to_string()),
+            ..Default::default()
+        };
+        let reader = GeoJsonRecordBatchReader::try_new(COLLECTION.as_bytes(), options).unwrap();
+        let schema = reader.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["id", "name", "nested", "geometry"]);
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert!(matches!(schema.field(2).data_type(), DataType::Struct(_)));
+
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].num_rows(), 2);
+        assert_eq!(batches[1].num_rows(), 1);
+
+        let ids = batches[0].column(0).as_primitive::<Int64Type>();
+        assert_eq!(ids.values(), &[1, 2]);
+        let names = batches[0].column(1).as_string::<i32>();
+        assert_eq!(names.value(1), "b]}");
+        let nested = batches[0].column(2).as_struct();
+        let x = nested
+            .column_by_name("x")
+            .unwrap()
+            .as_primitive::<Float64Type>();
+        assert_eq!(x.values(), &[1.5, 2.5]);
+        let tags = nested.column_by_name("tags").unwrap().as_list::<i32>();
+        assert_eq!(tags.value_length(0), 2);
+        assert_eq!(tags.value_length(1), 0);
+
+        assert_eq!(batches[0].column(3).len(), 2);
+        assert!(batches[1].column(1).is_null(0));
+    }
+
+    #[test]
+    fn explicit_schema() {
+        let schema = Schema::new(vec![
+            Field::new("fid", DataType::Utf8, true),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        let options = GeoJsonReaderOptions {
+            schema: Some(Arc::new(schema)),
+            id_column: Some("fid".to_string()),
+            ..Default::default()
+        };
+        let reader = GeoJsonRecordBatchReader::try_new(COLLECTION.as_bytes(), options).unwrap();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_columns(), 3);
+        let ids = batch.column(0).as_string::<i32>();
+        assert_eq!(ids.value(2), "3");
+    }
+
+    #[test]
+    fn property_order() {
+        let collection = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "geometry": null, "properties": {"z": 1, "b": {"y": 1, "a": 2}}},
+            {"type": "Feature", "geometry": null, "properties": {"m": "x", "z": 2}}
+        ]}"#;
+        let reader =
+            GeoJsonRecordBatchReader::try_new(collection.as_bytes(), Default::default()).unwrap();
+        let schema = reader.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["z", "b", "m", "geometry"]);
+        let DataType::Struct(nested) = schema.field(1).data_type() else {
+            panic!("expected a struct column");
+        };
+        let nested_names = nested
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(nested_names, ["y", "a"]);
+    }
+
+    #[test]
+    fn id_property() {
+        let collection = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "id": 1, "geometry": null, "properties": {"id": 2}},
+            {"type": "Feature", "id": 3, "geometry": null, "properties": {"name": "c"}}
+        ]}"#;
+
+        // Feature ids aren't read by default
+        let reader =
+            GeoJsonRecordBatchReader::try_new(collection.as_bytes(), Default::default()).unwrap();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        let ids = batches[0].column(0).as_primitive::<Int64Type>();
+        assert_eq!(ids.iter().collect::<Vec<_>>(), [Some(2), None]);
+
+        // A property with the name of the id column takes precedence over the feature id
+        let options = GeoJsonReaderOptions {
+            id_column: Some("id".to_string()),
+            ..Default::default()
+        };
+        let reader = GeoJsonRecordBatchReader::try_new(collection.as_bytes(), options).unwrap();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        let ids = batches[0].column(0).as_primitive::<Int64Type>();
+        assert_eq!(ids.values(), &[2, 3]);
+    }
+}
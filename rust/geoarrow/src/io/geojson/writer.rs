@@ -1,32 +1,491 @@
-use crate::error::Result;
-use crate::io::stream::RecordBatchReader;
-use geozero::GeozeroDatasource;
-use geozero::geojson::GeoJsonWriter;
 use std::io::Write;
 
-/// Write a Table to GeoJSON
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type};
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_json::WriterBuilder;
+use arrow_json::writer::LineDelimited;
+use arrow_schema::{DataType, Schema};
+use geoarrow_schema::{Crs, CrsType};
+use indexmap::IndexMap;
+use serde_json::Value;
+use serde_json::value::RawValue;
+
+use crate::array::NativeArrayDyn;
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::geojson::geometry::{BoundingBox, Geometry, Position};
+use crate::io::stream::RecordBatchReader;
+use crate::io::wkb::to_wkb;
+use crate::schema::GeoSchemaExt;
+use crate::trait_::ArrayAccessor;
+
+/// CRS identifiers that are equivalent to the WGS84 longitude/latitude coordinates required by
+/// RFC 7946.
+const WGS84_IDENTIFIERS: [&str; 4] = [
+    "OGC:CRS84",
+    "EPSG:4326",
+    "URN:OGC:DEF:CRS:OGC:1.3:CRS84",
+    "URN:OGC:DEF:CRS:EPSG::4326",
+];
+
+/// Options for the GeoJSON and GeoJSON Lines writers.
+#[derive(Debug, Clone)]
+pub struct GeoJsonWriterOptions {
+    /// The maximum number of decimal places written for each coordinate.
+    ///
+    /// If `None`, coordinates are written with full precision.
+    pub coordinate_precision: Option<usize>,
+
+    /// Write GeoJSON that complies with [RFC 7946](https://datatracker.ietf.org/doc/html/rfc7946).
+    ///
+    /// When enabled:
+    ///
+    /// - Geometries whose CRS is not WGS84 are reprojected to WGS84. This requires the `proj`
+    ///   feature; without it, writing fails.
+    /// - Polygon rings are oriented following the right-hand rule, with exterior rings
+    ///   counterclockwise and interior rings clockwise.
+    /// - Line strings and polygons that cross the antimeridian are split into parts on either
+    ///   side of it.
+    pub rfc7946: bool,
+
+    /// Write a `bbox` member on each feature.
+    pub feature_bbox: bool,
+
+    /// Write a `bbox` member on the FeatureCollection, covering all features.
+    ///
+    /// This is ignored when writing GeoJSON Lines, which has no enclosing collection.
+    pub collection_bbox: bool,
+
+    /// The name of the column to write as each feature's `id` member, rather than as a property.
+    ///
+    /// Integer and floating point columns are written as JSON numbers, and other columns as
+    /// strings.
+    pub id_column: Option<String>,
+
+    /// Write `Struct`, `List` and `Map` properties as nested JSON objects and arrays.
+    ///
+    /// If `false`, they are written as strings containing their JSON representation.
+    pub nested_properties: bool,
+}
+
+impl Default for GeoJsonWriterOptions {
+    fn default() -> Self {
+        Self {
+            coordinate_precision: None,
+            rfc7946: false,
+            feature_bbox: false,
+            collection_bbox: false,
+            id_column: None,
+            nested_properties: true,
+        }
+    }
+}
+
+/// Write a table to a GeoJSON FeatureCollection.
 ///
-/// Note: Does not reproject to WGS84 for you
-pub fn write_geojson<W: Write, S: Into<RecordBatchReader>>(stream: S, writer: W) -> Result<()> {
-    let mut geojson = GeoJsonWriter::new(writer);
-    stream.into().process(&mut geojson)?;
+/// The table must contain exactly one geometry column. Geometries are not reprojected to WGS84
+/// unless [`GeoJsonWriterOptions::rfc7946`] is set.
+pub fn write_geojson<W: Write, S: Into<RecordBatchReader>>(
+    stream: S,
+    mut writer: W,
+    options: GeoJsonWriterOptions,
+) -> Result<()> {
+    let reader = stream.into().into_inner();
+    let mut encoder = FeatureEncoder::try_new(&reader.schema(), &options)?;
+
+    writer.write_all(br#"{"type":"FeatureCollection","features":["#)?;
+    let mut num_features = 0;
+    for batch in reader {
+        encoder.encode_batch(&batch?, |feature| {
+            if num_features > 0 {
+                writer.write_all(b",")?;
+            }
+            writer.write_all(b"\n")?;
+            writer.write_all(feature)?;
+            num_features += 1;
+            Ok(())
+        })?;
+    }
+    writer.write_all(b"\n]")?;
+    if options.collection_bbox && !encoder.bbox.is_empty() {
+        writer.write_all(br#","bbox":"#)?;
+        encoder
+            .bbox
+            .write(&mut writer, options.coordinate_precision)?;
+    }
+    writer.write_all(b"}")?;
+    writer.flush()?;
     Ok(())
 }
 
+/// Encodes the rows of record batches as GeoJSON features.
+pub(crate) struct FeatureEncoder {
+    pub(crate) geometry_column_idx: usize,
+    id_column_idx: Option<usize>,
+    property_indices: Vec<usize>,
+    /// The names of nested properties that are written as strings.
+    stringified_properties: Vec<String>,
+    reprojection: Option<Reprojection>,
+    precision: Option<usize>,
+    rfc7946: bool,
+    feature_bbox: bool,
+    /// The bounding box of all features encoded so far.
+    pub(crate) bbox: BoundingBox,
+}
+
+impl FeatureEncoder {
+    pub(crate) fn try_new(schema: &Schema, options: &GeoJsonWriterOptions) -> Result<Self> {
+        let geometry_columns = schema.geometry_columns();
+        let geometry_column_idx = match geometry_columns.as_slice() {
+            [idx] => *idx,
+            _ => {
+                return Err(GeoArrowError::General(format!(
+                    "Writing GeoJSON requires exactly one geometry column, found {}",
+                    geometry_columns.len()
+                )));
+            }
+        };
+        let geometry_type = NativeType::try_from(schema.field(geometry_column_idx))?;
+
+        let id_column_idx = options
+            .id_column
+            .as_ref()
+            .map(|name| schema.index_of(name))
+            .transpose()?;
+
+        let mut property_indices = vec![];
+        let mut stringified_properties = vec![];
+        for (column_idx, field) in schema.fields().iter().enumerate() {
+            if column_idx == geometry_column_idx || Some(column_idx) == id_column_idx {
+                continue;
+            }
+            property_indices.push(column_idx);
+            if !options.nested_properties && field.data_type().is_nested() {
+                stringified_properties.push(field.name().clone());
+            }
+        }
+
+        let reprojection = if options.rfc7946 {
+            Reprojection::try_new(geometry_type.metadata().crs())?
+        } else {
+            None
+        };
+
+        Ok(Self {
+            geometry_column_idx,
+            id_column_idx,
+            property_indices,
+            stringified_properties,
+            reprojection,
+            precision: options.coordinate_precision,
+            rfc7946: options.rfc7946,
+            feature_bbox: options.feature_bbox,
+            bbox: BoundingBox::default(),
+        })
+    }
+
+    /// Encode each row of a batch as a GeoJSON feature, passing the JSON of each feature to
+    /// `write_feature`.
+    pub(crate) fn encode_batch(
+        &mut self,
+        batch: &RecordBatch,
+        mut write_feature: impl FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let schema = batch.schema();
+        let geometry_array = NativeArrayDyn::from_arrow_array(
+            batch.column(self.geometry_column_idx).as_ref(),
+            schema.field(self.geometry_column_idx),
+        )?
+        .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry_array.as_ref());
+        let ids = self.encode_ids(batch)?;
+        let properties = self.encode_properties(batch)?;
+        let mut properties = properties
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty());
+
+        let mut feature = vec![];
+        for row in 0..batch.num_rows() {
+            feature.clear();
+            feature.extend_from_slice(br#"{"type":"Feature""#);
+
+            if let Some(id) = &ids[row] {
+                feature.extend_from_slice(br#","id":"#);
+                serde_json::to_writer(&mut feature, &id)?;
+            }
+
+            let geometry = wkb_array
+                .get(row)
+                .map(|wkb| -> Result<_> { self.transform(Geometry::new(&wkb.parse()?)) })
+                .transpose()?;
+            if let Some(mut geometry) = geometry {
+                let mut bbox = BoundingBox::default();
+                bbox.add_geometry(&mut geometry)?;
+                self.bbox.add_bbox(&bbox);
+                if self.feature_bbox && !bbox.is_empty() {
+                    feature.extend_from_slice(br#","bbox":"#);
+                    bbox.write(&mut feature, self.precision)?;
+                }
+                feature.extend_from_slice(br#","geometry":"#);
+                geometry.write(&mut feature, self.precision)?;
+            } else {
+                feature.extend_from_slice(br#","geometry":null"#);
+            }
+
+            feature.extend_from_slice(br#","properties":"#);
+            match properties.next() {
+                Some(line) => feature.extend_from_slice(line),
+                None => feature.extend_from_slice(b"{}"),
+            }
+            feature.push(b'}');
+            write_feature(&feature)?;
+        }
+        Ok(())
+    }
+
+    /// The id of each row of a batch, or `None` for all rows if there is no id column.
+    pub(crate) fn encode_ids(&self, batch: &RecordBatch) -> Result<Vec<Option<Value>>> {
+        let Some(idx) = self.id_column_idx else {
+            return Ok(vec![None; batch.num_rows()]);
+        };
+        let ids = cast_ids(batch.column(idx))?;
+        Ok((0..batch.num_rows())
+            .map(|row| id_value(&ids, row))
+            .collect())
+    }
+
+    /// Encode the properties of each row of a batch as a line of JSON.
+    ///
+    /// The output is empty if there are no property columns.
+    pub(crate) fn encode_properties(&self, batch: &RecordBatch) -> Result<Vec<u8>> {
+        if self.property_indices.is_empty() {
+            return Ok(vec![]);
+        }
+        let properties = batch.project(&self.property_indices)?;
+        let mut writer = WriterBuilder::new()
+            .with_explicit_nulls(true)
+            .build::<_, LineDelimited>(vec![]);
+        writer.write(&properties)?;
+        writer.finish()?;
+        let encoded = writer.into_inner();
+        if self.stringified_properties.is_empty() {
+            return Ok(encoded);
+        }
+
+        let mut output = vec![];
+        for line in encoded.split(|byte| *byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+            // Values are kept as raw JSON so that keys stay in their original order
+            let mut object: IndexMap<String, Box<RawValue>> = serde_json::from_slice(line)?;
+            for name in &self.stringified_properties {
+                if let Some(value) = object.get_mut(name).filter(|value| value.get() != "null") {
+                    *value = RawValue::from_string(serde_json::to_string(value.get())?)?;
+                }
+            }
+            serde_json::to_writer(&mut output, &object)?;
+            output.push(b'\n');
+        }
+        Ok(output)
+    }
+
+    fn transform(&self, mut geometry: Geometry) -> Result<Geometry> {
+        if let Some(reprojection) = &self.reprojection {
+            geometry.try_for_each_position(&mut |position| reprojection.apply(position))?;
+        }
+        if self.rfc7946 {
+            geometry = geometry.split_antimeridian();
+            geometry.orient();
+        }
+        Ok(geometry)
+    }
+}
+
+/// Cast an id column to `Int64`, `Float64` or `Utf8`.
+fn cast_ids(array: &ArrayRef) -> Result<ArrayRef> {
+    let data_type = match array.data_type() {
+        data_type if data_type.is_integer() => DataType::Int64,
+        data_type if data_type.is_floating() => DataType::Float64,
+        _ => DataType::Utf8,
+    };
+    Ok(arrow_cast::cast(array, &data_type)?)
+}
+
+fn id_value(ids: &ArrayRef, row: usize) -> Option<Value> {
+    if ids.is_null(row) {
+        return None;
+    }
+    let value = match ids.data_type() {
+        DataType::Int64 => ids.as_primitive::<Int64Type>().value(row).into(),
+        DataType::Float64 => ids.as_primitive::<Float64Type>().value(row).into(),
+        _ => ids.as_string::<i32>().value(row).into(),
+    };
+    Some(value)
+}
+
+/// Whether a CRS is equivalent to WGS84 longitude/latitude. A missing CRS is assumed to be.
+fn is_wgs84(crs: &Crs) -> bool {
+    let identifier = match (crs.crs_type(), crs.crs_value()) {
+        (_, None) => return true,
+        (Some(CrsType::Srid), Some(Value::String(srid))) => format!("EPSG:{srid}"),
+        (_, Some(Value::String(identifier))) => identifier.clone(),
+        (_, Some(Value::Object(projjson))) => {
+            let Some(id) = projjson.get("id") else {
+                return false;
+            };
+            let code = match id.get("code") {
+                Some(Value::String(code)) => code.clone(),
+                Some(code) => code.to_string(),
+                None => return false,
+            };
+            match id.get("authority") {
+                Some(Value::String(authority)) => format!("{authority}:{code}"),
+                _ => return false,
+            }
+        }
+        _ => return false,
+    };
+    WGS84_IDENTIFIERS.contains(&identifier.trim().to_uppercase().as_str())
+}
+
+/// Reprojects positions to WGS84 longitude/latitude.
+#[cfg(feature = "proj")]
+struct Reprojection(proj::Proj);
+
+/// Reprojection is unavailable without the `proj` feature.
+#[cfg(not(feature = "proj"))]
+enum Reprojection {}
+
+impl Reprojection {
+    /// Create a reprojection from a CRS, or `None` if it is already WGS84.
+    fn try_new(crs: &Crs) -> Result<Option<Self>> {
+        if is_wgs84(crs) {
+            return Ok(None);
+        }
+
+        #[cfg(feature = "proj")]
+        {
+            let definition = match (crs.crs_type(), crs.crs_value()) {
+                (Some(CrsType::Srid), Some(Value::String(srid))) => format!("EPSG:{srid}"),
+                (_, Some(Value::String(definition))) => definition.clone(),
+                (_, Some(value)) => value.to_string(),
+                (_, None) => unreachable!("a missing CRS is WGS84"),
+            };
+            let proj = proj::Proj::new_known_crs(&definition, "OGC:CRS84", None)
+                .map_err(|err| GeoArrowError::General(err.to_string()))?;
+            Ok(Some(Self(proj)))
+        }
+
+        #[cfg(not(feature = "proj"))]
+        Err(GeoArrowError::General(
+            "RFC 7946 GeoJSON requires WGS84 coordinates; enable the `proj` feature to reproject \
+             other CRSes"
+                .to_string(),
+        ))
+    }
+
+    #[cfg(feature = "proj")]
+    fn apply(&self, position: &mut Position) -> Result<()> {
+        use proj::Transform;
+        let (x, y) = self.0.convert((position.x, position.y))?;
+        position.x = x;
+        position.y = y;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "proj"))]
+    fn apply(&self, _position: &mut Position) -> Result<()> {
+        match *self {}
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, StringArray, StructArray};
+    use arrow_schema::Field;
+
     use super::*;
+    use crate::ArrayBase;
+    use crate::table::Table;
     use crate::test::point;
-    use std::io::BufWriter;
+
+    fn write(table: &Table, options: GeoJsonWriterOptions) -> Value {
+        let mut output = vec![];
+        write_geojson(table, &mut output, options).unwrap();
+        serde_json::from_slice(&output).unwrap()
+    }
 
     #[test]
     fn test_write() {
+        let value = write(&point::table(), Default::default());
+        let features = value["features"].as_array().unwrap();
+        assert_eq!(features.len(), 3);
+        assert_eq!(features[0]["geometry"]["type"], "Point");
+        assert_eq!(features[0]["properties"]["string"], "foo");
+        assert!(value.get("bbox").is_none());
+    }
+
+    #[test]
+    fn options() {
         let table = point::table();
+        let value = write(
+            &table,
+            GeoJsonWriterOptions {
+                coordinate_precision: Some(0),
+                feature_bbox: true,
+                collection_bbox: true,
+                id_column: Some("u8".to_string()),
+                ..Default::default()
+            },
+        );
+        let features = value["features"].as_array().unwrap();
+        assert_eq!(features[1]["id"], 2);
+        assert!(features[1]["properties"].get("u8").is_none());
+        assert_eq!(features[1]["bbox"].as_array().unwrap().len(), 4);
+        assert_eq!(value["bbox"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn nested_properties() {
+        let geometry = point::point_array();
+        let nested = StructArray::from(vec![
+            (
+                Arc::new(Field::new("a", DataType::Int64, true)),
+                Arc::new(Int64Array::from(vec![1, 2, 3])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("b", DataType::Utf8, true)),
+                Arc::new(StringArray::from(vec!["x", "y", "z"])) as ArrayRef,
+            ),
+        ]);
+        let schema = Arc::new(Schema::new(vec![
+            Arc::new(Field::new("nested", nested.data_type().clone(), true)),
+            geometry.extension_field(),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(nested), geometry.into_array_ref()],
+        )
+        .unwrap();
+        let table = Table::try_new(vec![batch], schema).unwrap();
+
+        let value = write(&table, Default::default());
+        assert_eq!(value["features"][0]["properties"]["nested"]["b"], "x");
 
-        let mut output_buffer = Vec::new();
-        let writer = BufWriter::new(&mut output_buffer);
-        write_geojson(&table, writer).unwrap();
-        let output_string = String::from_utf8(output_buffer).unwrap();
-        println!("{}", output_string);
+        let value = write(
+            &table,
+            GeoJsonWriterOptions {
+                nested_properties: false,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            value["features"][0]["properties"]["nested"],
+            r#"{"a":1,"b":"x"}"#
+        );
     }
 }
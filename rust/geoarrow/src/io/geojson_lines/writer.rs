@@ -1,16 +1,48 @@
-use geozero::GeozeroDatasource;
-use geozero::geojson::GeoJsonLineWriter;
 use std::io::Write;
 
 use crate::error::Result;
+use crate::io::geojson::{FeatureEncoder, GeoJsonWriterOptions};
 use crate::io::stream::RecordBatchReader;
 
 /// Write a table to newline-delimited GeoJSON
+///
+/// Each feature is written on its own line. [`GeoJsonWriterOptions::collection_bbox`] is ignored,
+/// as there is no enclosing FeatureCollection.
 pub fn write_geojson_lines<W: Write, S: Into<RecordBatchReader>>(
     stream: S,
-    writer: W,
+    mut writer: W,
+    options: GeoJsonWriterOptions,
 ) -> Result<()> {
-    let mut geojson_writer = GeoJsonLineWriter::new(writer);
-    stream.into().process(&mut geojson_writer)?;
+    let reader = stream.into().into_inner();
+    let mut encoder = FeatureEncoder::try_new(&reader.schema(), &options)?;
+    for batch in reader {
+        encoder.encode_batch(&batch?, |feature| {
+            writer.write_all(feature)?;
+            writer.write_all(b"\n")?;
+            Ok(())
+        })?;
+    }
+    writer.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::point;
+
+    #[test]
+    fn test_write() {
+        let mut output = vec![];
+        let options = GeoJsonWriterOptions {
+            id_column: Some("u8".to_string()),
+            ..Default::default()
+        };
+        write_geojson_lines(&point::table(), &mut output, options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        let feature: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(feature["id"], 1);
+    }
+}
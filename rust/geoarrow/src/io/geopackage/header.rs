@@ -0,0 +1,145 @@
+//! Parse and encode the [GeoPackage binary
+//! header](https://www.geopackage.org/spec140/index.html#gpb_format) that precedes the WKB of each
+//! geometry.
+
+use crate::error::{GeoArrowError, Result};
+
+/// The magic bytes at the start of every GeoPackage geometry.
+const MAGIC: [u8; 2] = [b'G', b'P'];
+
+/// Bit 0 of the flags byte: the header is little endian.
+const LITTLE_ENDIAN_FLAG: u8 = 0b0000_0001;
+
+/// Bit 4 of the flags byte: the geometry is empty.
+const EMPTY_FLAG: u8 = 0b0001_0000;
+
+/// Bit 5 of the flags byte: the geometry is an extended GeoPackage geometry.
+const EXTENDED_FLAG: u8 = 0b0010_0000;
+
+/// A geometry stored in a GeoPackage feature table.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GeoPackageGeometry<'a> {
+    /// The 2D envelope as `[min_x, max_x, min_y, max_y]`, in the order used by the header.
+    pub(crate) envelope: Option<[f64; 4]>,
+    /// Whether the geometry is empty.
+    pub(crate) empty: bool,
+    /// The WKB-encoded geometry following the header.
+    pub(crate) wkb: &'a [u8],
+}
+
+impl<'a> GeoPackageGeometry<'a> {
+    /// Parse a GeoPackage geometry blob.
+    pub(crate) fn parse(buf: &'a [u8]) -> Result<Self> {
+        if buf.len() < 8 || buf[0..2] != MAGIC {
+            return Err(GeoArrowError::General(
+                "Invalid GeoPackage geometry header".to_string(),
+            ));
+        }
+        let flags = buf[3];
+        if flags & EXTENDED_FLAG != 0 {
+            return Err(GeoArrowError::NotYetImplemented(
+                "Extended GeoPackage geometries are not supported".to_string(),
+            ));
+        }
+        let little_endian = flags & LITTLE_ENDIAN_FLAG != 0;
+        let read_f64 = |bytes: &[u8]| {
+            let bytes = bytes.try_into().unwrap();
+            if little_endian {
+                f64::from_le_bytes(bytes)
+            } else {
+                f64::from_be_bytes(bytes)
+            }
+        };
+
+        // The envelope contents indicator is stored in bits 1-3
+        let envelope_len = match (flags >> 1) & 0b111 {
+            0 => 0,
+            1 => 32,
+            2 | 3 => 48,
+            4 => 64,
+            indicator => {
+                return Err(GeoArrowError::General(format!(
+                    "Invalid GeoPackage envelope contents indicator {}",
+                    indicator
+                )));
+            }
+        };
+        if buf.len() < 8 + envelope_len {
+            return Err(GeoArrowError::General(
+                "GeoPackage geometry is shorter than its header".to_string(),
+            ));
+        }
+        // Z and M ranges, if any, follow the XY range
+        let envelope = (envelope_len > 0).then(|| {
+            [
+                read_f64(&buf[8..16]),
+                read_f64(&buf[16..24]),
+                read_f64(&buf[24..32]),
+                read_f64(&buf[32..40]),
+            ]
+        });
+
+        // Bytes 4..8 hold the srs_id, which always matches gpkg_geometry_columns
+        Ok(Self {
+            envelope,
+            empty: flags & EMPTY_FLAG != 0,
+            wkb: &buf[8 + envelope_len..],
+        })
+    }
+}
+
+/// Write a little-endian GeoPackage geometry blob with an optional 2D envelope, given as
+/// `[min_x, max_x, min_y, max_y]`.
+pub(crate) fn write_geometry(
+    buf: &mut Vec<u8>,
+    srs_id: i32,
+    envelope: Option<[f64; 4]>,
+    wkb: &[u8],
+) {
+    let mut flags = LITTLE_ENDIAN_FLAG;
+    match envelope {
+        Some(_) => flags |= 1 << 1,
+        None => flags |= EMPTY_FLAG,
+    }
+
+    buf.extend_from_slice(&MAGIC);
+    // Version 1 of the format is stored as 0
+    buf.push(0);
+    buf.push(flags);
+    buf.extend_from_slice(&srs_id.to_le_bytes());
+    if let Some(envelope) = envelope {
+        for value in envelope {
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    buf.extend_from_slice(wkb);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let wkb = [1, 1, 0, 0, 0];
+        let mut buf = vec![];
+        write_geometry(&mut buf, 4326, Some([0., 1., 2., 3.]), &wkb);
+
+        let geometry = GeoPackageGeometry::parse(&buf).unwrap();
+        assert_eq!(geometry.envelope, Some([0., 1., 2., 3.]));
+        assert!(!geometry.empty);
+        assert_eq!(geometry.wkb, wkb);
+
+        buf.clear();
+        write_geometry(&mut buf, -1, None, &wkb);
+        let geometry = GeoPackageGeometry::parse(&buf).unwrap();
+        assert_eq!(geometry.envelope, None);
+        assert!(geometry.empty);
+        assert_eq!(geometry.wkb, wkb);
+    }
+
+    #[test]
+    fn invalid_magic() {
+        assert!(GeoPackageGeometry::parse(&[0; 8]).is_err());
+    }
+}
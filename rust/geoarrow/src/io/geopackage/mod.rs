@@ -0,0 +1,328 @@
+//! Read from and write to [GeoPackage](https://www.geopackage.org/) files.
+//!
+//! GeoPackages are SQLite databases, accessed here through [`rusqlite`]. Feature tables are read
+//! and written with their R-tree spatial index when present.
+
+mod header;
+mod reader;
+mod writer;
+
+pub use reader::{
+    GeoPackageReader, GeoPackageReaderOptions, GeoPackageRecordBatchReader, GeoPackageTable,
+};
+pub use writer::{GeoPackageWriterOptions, write_geopackage};
+
+/// Quote an SQLite identifier such as a table or column name.
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// The name of the R-tree spatial index on a geometry column, as defined by the GeoPackage R-tree
+/// spatial index extension.
+pub(crate) fn rtree_table_name(table_name: &str, column_name: &str) -> String {
+    format!("rtree_{table_name}_{column_name}")
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, Int16Type, Int64Type};
+    use arrow_array::{Date64Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use geoarrow_schema::{CoordType, Crs, Dimension, Metadata};
+    use rusqlite::Connection;
+
+    use super::*;
+    use crate::array::PointBuilder;
+    use crate::datatypes::NativeType;
+    use crate::table::Table;
+    use crate::test::point;
+    use crate::{ArrayBase, NativeArray};
+
+    /// The points table, with `crs` on the geometry column.
+    fn table_with_crs(crs: Crs) -> Table {
+        let table = point::table();
+        let point_array = PointBuilder::from_points(
+            [point::p0(), point::p1(), point::p2()].iter(),
+            Dimension::XY,
+            CoordType::default_interleaved(),
+            Arc::new(Metadata::new(crs, None)),
+        )
+        .finish();
+        let schema = Arc::new(Schema::new(vec![
+            table.schema().field(0).clone(),
+            table.schema().field(1).clone(),
+            point_array.extension_field().as_ref().clone(),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                table.batches()[0].column(0).clone(),
+                table.batches()[0].column(1).clone(),
+                point_array.into_array_ref(),
+            ],
+        )
+        .unwrap();
+        Table::try_new(vec![batch], schema).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.gpkg");
+        write_geopackage(
+            point::table(),
+            &path,
+            "points",
+            GeoPackageWriterOptions {
+                description: Some("Test points".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let reader = GeoPackageReader::open(&path).unwrap();
+        let tables = reader.tables().unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].table_name, "points");
+        assert_eq!(tables[0].description.as_deref(), Some("Test points"));
+        assert_eq!(tables[0].bbox, Some((0., 1., 2., 3.)));
+
+        let batches = reader
+            .read_table("points", Default::default())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 3);
+
+        let schema = batch.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["u8", "string", "geometry"]);
+        assert_eq!(schema.field(0).data_type(), &DataType::Int16);
+        assert_eq!(
+            batch.column(0).as_primitive::<Int16Type>().values(),
+            &[1, 2, 3]
+        );
+        assert_eq!(
+            batch
+                .column(1)
+                .as_string::<i32>()
+                .iter()
+                .collect::<Vec<_>>(),
+            [Some("foo"), Some("bar"), Some("baz")]
+        );
+        assert_eq!(
+            NativeType::try_from(schema.field(2).as_ref()).unwrap(),
+            point::point_array().data_type()
+        );
+    }
+
+    #[test]
+    fn wkb_input() {
+        let table = point::table();
+        let wkb_array = crate::io::wkb::to_wkb::<i32>(&point::point_array());
+        let schema = Arc::new(Schema::new(vec![
+            table.schema().field(0).clone(),
+            table.schema().field(1).clone(),
+            wkb_array.extension_field().as_ref().clone(),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                table.batches()[0].column(0).clone(),
+                table.batches()[0].column(1).clone(),
+                wkb_array.into_array_ref(),
+            ],
+        )
+        .unwrap();
+        let table = Table::try_new(vec![batch], schema).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.gpkg");
+        write_geopackage(table, &path, "points", Default::default()).unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        let geometry_type_name: String = conn
+            .query_row(
+                "SELECT geometry_type_name FROM gpkg_geometry_columns",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(geometry_type_name, "GEOMETRY");
+
+        let reader = GeoPackageReader::open(&path).unwrap();
+        assert_eq!(reader.tables().unwrap()[0].bbox, Some((0., 1., 2., 3.)));
+        let num_rows = reader
+            .read_table("points", Default::default())
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum::<usize>();
+        assert_eq!(num_rows, 3);
+    }
+
+    #[test]
+    fn bbox_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.gpkg");
+        for spatial_index in [true, false] {
+            let table_name = format!("points_{spatial_index}");
+            write_geopackage(
+                point::table(),
+                &path,
+                &table_name,
+                GeoPackageWriterOptions {
+                    spatial_index,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            let options = GeoPackageReaderOptions {
+                bbox: Some((0.5, 1.5, 3., 3.)),
+                ..Default::default()
+            };
+            let reader = GeoPackageReader::open(&path)
+                .unwrap()
+                .read_table(&table_name, options)
+                .unwrap();
+            let num_rows = reader.map(|batch| batch.unwrap().num_rows()).sum::<usize>();
+            assert_eq!(num_rows, 2);
+        }
+    }
+
+    #[test]
+    fn duplicate_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.gpkg");
+        write_geopackage(point::table(), &path, "points", Default::default()).unwrap();
+        assert!(write_geopackage(point::table(), &path, "points", Default::default()).is_err());
+    }
+
+    #[test]
+    fn crs_mapping() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("crs.gpkg");
+        let wkt = r#"PROJCS["Custom",GEOGCS["WGS 84"]]"#;
+        let cases = [
+            ("no_crs", Crs::default(), -1),
+            (
+                "epsg_4326",
+                Crs::from_authority_code("EPSG:4326".to_string()),
+                4326,
+            ),
+            (
+                "epsg_32633",
+                Crs::from_authority_code("EPSG:32633".to_string()),
+                32633,
+            ),
+            ("wkt", Crs::from_unknown_crs_type(wkt.to_string()), 100000),
+        ];
+        for (table_name, crs, _) in &cases {
+            write_geopackage(
+                table_with_crs(crs.clone()),
+                &path,
+                table_name,
+                Default::default(),
+            )
+            .unwrap();
+        }
+
+        let reader = GeoPackageReader::open(&path).unwrap();
+        let srs_ids = reader
+            .tables()
+            .unwrap()
+            .into_iter()
+            .map(|table| (table.table_name, table.srs_id))
+            .collect::<std::collections::HashMap<_, _>>();
+        for (table_name, crs, srs_id) in cases {
+            assert_eq!(srs_ids[table_name], Some(srs_id));
+
+            let schema = reader.schema(table_name, &Default::default()).unwrap();
+            let geometry_type = NativeType::try_from(schema.field(2).as_ref()).unwrap();
+            // EPSG:4326 is predefined with its WKT definition, but the authority code is read
+            assert_eq!(geometry_type.metadata().crs(), &crs);
+        }
+    }
+
+    #[test]
+    fn columns_without_declared_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.gpkg");
+        // The R-tree triggers need spatial SQL functions that a plain connection doesn't have
+        let options = GeoPackageWriterOptions {
+            spatial_index: false,
+            ..Default::default()
+        };
+        write_geopackage(point::table(), &path, "points", options).unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "ALTER TABLE points ADD COLUMN integers;
+            ALTER TABLE points ADD COLUMN numbers NUMERIC;
+            ALTER TABLE points ADD COLUMN mixed;
+            UPDATE points SET integers = fid * 10, numbers = fid + 0.5, mixed = fid;
+            UPDATE points SET numbers = 7, mixed = 'text' WHERE fid = 1;",
+        )
+        .unwrap();
+        drop(conn);
+
+        let batches = GeoPackageReader::open(&path)
+            .unwrap()
+            .read_table("points", Default::default())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let batch = &batches[0];
+        let schema = batch.schema();
+        assert_eq!(schema.field(3).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(4).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(5).data_type(), &DataType::Utf8);
+        assert_eq!(
+            batch.column(3).as_primitive::<Int64Type>().values(),
+            &[10, 20, 30]
+        );
+        assert_eq!(
+            batch.column(4).as_primitive::<Float64Type>().values(),
+            &[7., 2.5, 3.5]
+        );
+        assert_eq!(
+            batch
+                .column(5)
+                .as_string::<i32>()
+                .iter()
+                .collect::<Vec<_>>(),
+            [Some("text"), Some("2"), Some("3")]
+        );
+    }
+
+    #[test]
+    fn out_of_range_date() {
+        let point_array = point::point_array();
+        let schema = Arc::new(Schema::new(vec![
+            Arc::new(Field::new("date", DataType::Date64, true)),
+            point_array.extension_field(),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Date64Array::from(vec![0, i64::MAX, 0])),
+                point_array.into_array_ref(),
+            ],
+        )
+        .unwrap();
+        let table = Table::try_new(vec![batch], schema).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dates.gpkg");
+        assert!(write_geopackage(table, &path, "dates", Default::default()).is_err());
+    }
+}
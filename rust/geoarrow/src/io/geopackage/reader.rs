@@ -0,0 +1,733 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    ArrayBuilder, BinaryBuilder, BooleanBuilder, Date32Builder, Float32Builder, Float64Builder,
+    Int8Builder, Int16Builder, Int32Builder, Int64Builder, StringBuilder,
+    TimestampMillisecondBuilder,
+};
+use arrow_array::types::Date32Type;
+use arrow_array::{ArrayRef, RecordBatch, RecordBatchReader};
+use arrow_cast::parse::{Parser, string_to_timestamp_nanos};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use geoarrow_schema::{
+    CoordType, Crs, Dimension, GeometryCollectionType, GeometryType, LineStringType, Metadata,
+    MultiLineStringType, MultiPointType, MultiPolygonType, PointType, PolygonType,
+};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params_from_iter};
+
+use crate::ArrayBase;
+use crate::array::WKBArray;
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::geopackage::header::GeoPackageGeometry;
+use crate::io::geopackage::{quote_identifier, rtree_table_name};
+use crate::io::wkb::from_wkb;
+
+/// Options for the GeoPackage reader
+#[derive(Debug, Clone)]
+pub struct GeoPackageReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+
+    /// A spatial filter for reading rows.
+    ///
+    /// If the table has an R-tree spatial index, the filter is evaluated against the index within
+    /// SQLite, so only features whose bounding box intersects `(min_x, min_y, max_x, max_y)` are
+    /// read. Otherwise the envelope stored in each geometry's header is checked instead.
+    ///
+    /// If set to `None`, no spatial filtering will be performed.
+    pub bbox: Option<(f64, f64, f64, f64)>,
+}
+
+impl Default for GeoPackageReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+            bbox: None,
+        }
+    }
+}
+
+/// A feature table listed in the `gpkg_contents` table of a GeoPackage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoPackageTable {
+    /// The name of the table.
+    pub table_name: String,
+    /// A human-readable identifier for the table.
+    pub identifier: Option<String>,
+    /// A human-readable description for the table.
+    pub description: Option<String>,
+    /// The bounding box of all features in the table as `(min_x, min_y, max_x, max_y)`, if
+    /// provided.
+    pub bbox: Option<(f64, f64, f64, f64)>,
+    /// The id of the table's spatial reference system in `gpkg_spatial_ref_sys`.
+    pub srs_id: Option<i64>,
+}
+
+/// A reader for the feature tables of a [GeoPackage](https://www.geopackage.org/) file.
+pub struct GeoPackageReader {
+    conn: Connection,
+}
+
+impl GeoPackageReader {
+    /// Open a GeoPackage file as read-only.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        Ok(Self { conn })
+    }
+
+    /// Create a reader from an existing SQLite connection to a GeoPackage.
+    pub fn from_connection(conn: Connection) -> Self {
+        Self { conn }
+    }
+
+    /// List the feature tables in this GeoPackage.
+    pub fn tables(&self) -> Result<Vec<GeoPackageTable>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT table_name, identifier, description, min_x, min_y, max_x, max_y, srs_id
+            FROM gpkg_contents WHERE data_type = 'features' ORDER BY table_name",
+        )?;
+        let tables = stmt
+            .query_map([], |row| {
+                let bounds: (Option<f64>, Option<f64>, Option<f64>, Option<f64>) =
+                    (row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?);
+                let bbox = match bounds {
+                    (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) => {
+                        Some((min_x, min_y, max_x, max_y))
+                    }
+                    _ => None,
+                };
+                Ok(GeoPackageTable {
+                    table_name: row.get(0)?,
+                    identifier: row.get(1)?,
+                    description: row.get(2)?,
+                    bbox,
+                    srs_id: row.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tables)
+    }
+
+    /// The Arrow schema of the record batches that will be read from a table with these options.
+    pub fn schema(&self, table_name: &str, options: &GeoPackageReaderOptions) -> Result<SchemaRef> {
+        Ok(TableInfo::try_new(&self.conn, table_name, options.coord_type)?.schema())
+    }
+
+    /// Read a feature table.
+    ///
+    /// Columns are emitted in the order of the table, except for the integer primary key, which
+    /// is omitted.
+    pub fn read_table(
+        self,
+        table_name: &str,
+        options: GeoPackageReaderOptions,
+    ) -> Result<GeoPackageRecordBatchReader> {
+        let table = TableInfo::try_new(&self.conn, table_name, options.coord_type)?;
+        GeoPackageRecordBatchReader::try_new(self.conn, table, options)
+    }
+}
+
+/// A column of a feature table, as described by `PRAGMA table_info`.
+#[derive(Debug, Clone)]
+enum ColumnKind {
+    Property(DataType),
+    Geometry,
+}
+
+#[derive(Debug, Clone)]
+struct ColumnInfo {
+    name: String,
+    nullable: bool,
+    kind: ColumnKind,
+}
+
+/// The layout of a feature table.
+#[derive(Debug, Clone)]
+struct TableInfo {
+    table_name: String,
+    /// The integer primary key, or `rowid` if the table has none.
+    fid_column: String,
+    /// The columns to read, excluding the primary key.
+    columns: Vec<ColumnInfo>,
+    /// The index of the geometry column in `columns`.
+    geometry_column_idx: usize,
+    geometry_type: NativeType,
+    /// The name of the R-tree spatial index on the geometry column, if it exists.
+    rtree: Option<String>,
+}
+
+impl TableInfo {
+    fn try_new(conn: &Connection, table_name: &str, coord_type: CoordType) -> Result<Self> {
+        let (geometry_column, geometry_type_name, srs_id, z, m) = conn
+            .query_row(
+                "SELECT column_name, geometry_type_name, srs_id, z, m
+                FROM gpkg_geometry_columns WHERE table_name = ?1",
+                [table_name],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                    ))
+                },
+            )
+            .optional()?
+            .ok_or_else(|| {
+                GeoArrowError::General(format!(
+                    "Table {} is not a feature table in this GeoPackage",
+                    table_name
+                ))
+            })?;
+
+        let metadata = Arc::new(Metadata::new(read_crs(conn, srs_id)?, None));
+        let geometry_type =
+            infer_geometry_type(&geometry_type_name, z, m, coord_type)?.with_metadata(metadata);
+
+        let mut fid_column = None;
+        let mut columns = vec![];
+        let mut geometry_column_idx = None;
+        let mut stmt = conn.prepare(&format!(
+            "PRAGMA table_info({})",
+            quote_identifier(table_name)
+        ))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let name: String = row.get(1)?;
+            let declared_type: String = row.get(2)?;
+            let not_null: bool = row.get(3)?;
+            let primary_key: i64 = row.get(5)?;
+
+            if primary_key == 1 && declared_type.eq_ignore_ascii_case("INTEGER") {
+                fid_column = Some(name);
+                continue;
+            }
+            let kind = if name == geometry_column {
+                geometry_column_idx = Some(columns.len());
+                ColumnKind::Geometry
+            } else {
+                let data_type = match sqlite_data_type(&declared_type) {
+                    Some(data_type) => data_type,
+                    None => infer_storage_type(conn, table_name, &name)?,
+                };
+                ColumnKind::Property(data_type)
+            };
+            columns.push(ColumnInfo {
+                name,
+                nullable: !not_null,
+                kind,
+            });
+        }
+
+        let geometry_column_idx = geometry_column_idx.ok_or_else(|| {
+            GeoArrowError::General(format!(
+                "Geometry column {} does not exist in table {}",
+                geometry_column, table_name
+            ))
+        })?;
+
+        let rtree_name = rtree_table_name(table_name, &geometry_column);
+        let rtree = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                [&rtree_name],
+                |_| Ok(()),
+            )
+            .optional()?
+            .map(|_| rtree_name);
+
+        Ok(Self {
+            table_name: table_name.to_string(),
+            fid_column: fid_column.unwrap_or_else(|| "rowid".to_string()),
+            columns,
+            geometry_column_idx,
+            geometry_type,
+            rtree,
+        })
+    }
+
+    fn schema(&self) -> SchemaRef {
+        let fields = self
+            .columns
+            .iter()
+            .map(|column| match &column.kind {
+                ColumnKind::Property(data_type) => {
+                    Field::new(&column.name, data_type.clone(), column.nullable)
+                }
+                ColumnKind::Geometry => self.geometry_type.to_field(&column.name, true),
+            })
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new(fields))
+    }
+
+    /// A query for the next `LIMIT` rows after a given primary key.
+    fn query(&self, use_rtree: bool) -> String {
+        let fid = quote_identifier(&self.fid_column);
+        let mut columns = vec![fid.clone()];
+        columns.extend(self.columns.iter().map(|c| quote_identifier(&c.name)));
+        let mut sql = format!(
+            "SELECT {} FROM {} WHERE {} > ?1",
+            columns.join(", "),
+            quote_identifier(&self.table_name),
+            fid
+        );
+        if use_rtree {
+            let rtree = self.rtree.as_ref().unwrap();
+            sql.push_str(&format!(
+                " AND {} IN (SELECT id FROM {} WHERE minx <= ?3 AND maxx >= ?4 AND miny <= ?5 AND maxy >= ?6)",
+                fid,
+                quote_identifier(rtree)
+            ));
+        }
+        sql.push_str(&format!(" ORDER BY {} LIMIT ?2", fid));
+        sql
+    }
+}
+
+/// Map a GeoPackage geometry type and Z/M flags to a [NativeType].
+///
+/// Z and M values of 2 mean the dimension is optional, in which case a mixed [GeometryType] is
+/// used so that each geometry keeps its own dimension.
+fn infer_geometry_type(
+    geometry_type_name: &str,
+    z: i64,
+    m: i64,
+    coord_type: CoordType,
+) -> Result<NativeType> {
+    let dim = match (z, m) {
+        (0, 0) => Dimension::XY,
+        (1, 0) => Dimension::XYZ,
+        (0, 1) => Dimension::XYM,
+        (1, 1) => Dimension::XYZM,
+        _ => {
+            return Ok(NativeType::Geometry(GeometryType::new(
+                coord_type,
+                Default::default(),
+            )));
+        }
+    };
+    let metadata = Default::default();
+    let geometry_type = match geometry_type_name.to_ascii_uppercase().as_str() {
+        "POINT" => NativeType::Point(PointType::new(coord_type, dim, metadata)),
+        "LINESTRING" => NativeType::LineString(LineStringType::new(coord_type, dim, metadata)),
+        "POLYGON" => NativeType::Polygon(PolygonType::new(coord_type, dim, metadata)),
+        "MULTIPOINT" => NativeType::MultiPoint(MultiPointType::new(coord_type, dim, metadata)),
+        "MULTILINESTRING" => {
+            NativeType::MultiLineString(MultiLineStringType::new(coord_type, dim, metadata))
+        }
+        "MULTIPOLYGON" => {
+            NativeType::MultiPolygon(MultiPolygonType::new(coord_type, dim, metadata))
+        }
+        "GEOMETRYCOLLECTION" => {
+            NativeType::GeometryCollection(GeometryCollectionType::new(coord_type, dim, metadata))
+        }
+        "GEOMETRY" => NativeType::Geometry(GeometryType::new(coord_type, metadata)),
+        other => {
+            return Err(GeoArrowError::NotYetImplemented(format!(
+                "GeoPackage geometry type {}",
+                other
+            )));
+        }
+    };
+    Ok(geometry_type)
+}
+
+/// Read a spatial reference system from `gpkg_spatial_ref_sys` into a [Crs].
+///
+/// The organization and code are preferred, as an authority code is unambiguous while the WKT
+/// definition may be either WKT 1 or WKT 2. The definition is used as a fallback for custom
+/// systems without an organization.
+fn read_crs(conn: &Connection, srs_id: i64) -> Result<Crs> {
+    let srs = conn
+        .query_row(
+            "SELECT organization, organization_coordsys_id, definition
+            FROM gpkg_spatial_ref_sys WHERE srs_id = ?1",
+            [srs_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((organization, code, definition)) = srs else {
+        return Ok(Default::default());
+    };
+
+    if !organization.eq_ignore_ascii_case("NONE") && code > 0 {
+        Ok(Crs::from_authority_code(format!("{organization}:{code}")))
+    } else if definition != "undefined" {
+        // We use unknown CRS because the definition may be either WKT 1 or WKT 2
+        Ok(Crs::from_unknown_crs_type(definition))
+    } else {
+        Ok(Default::default())
+    }
+}
+
+/// Map a column type declared in a GeoPackage feature table to an Arrow [DataType].
+///
+/// Returns `None` if the column has no declared type or a type that isn't defined by the
+/// GeoPackage specification.
+fn sqlite_data_type(declared_type: &str) -> Option<DataType> {
+    let declared_type = declared_type.to_ascii_uppercase();
+    // TEXT and BLOB may declare a maximum length, as in TEXT(255)
+    let base_type = declared_type
+        .split_once('(')
+        .map_or(declared_type.as_str(), |(base, _)| base)
+        .trim();
+    let data_type = match base_type {
+        "BOOLEAN" => DataType::Boolean,
+        "TINYINT" => DataType::Int8,
+        "SMALLINT" => DataType::Int16,
+        "MEDIUMINT" => DataType::Int32,
+        "INT" | "INTEGER" => DataType::Int64,
+        "FLOAT" => DataType::Float32,
+        "DOUBLE" | "REAL" => DataType::Float64,
+        "TEXT" => DataType::Utf8,
+        "BLOB" => DataType::Binary,
+        "DATE" => DataType::Date32,
+        "DATETIME" => DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        _ => return None,
+    };
+    Some(data_type)
+}
+
+/// Infer the Arrow [DataType] of a column from the SQLite storage classes of its values.
+///
+/// Integer columns are read as `Int64` and numeric columns with any real values as `Float64`.
+/// Columns that only hold blobs are read as `Binary`, and any other column as `Utf8`.
+fn infer_storage_type(conn: &Connection, table_name: &str, column_name: &str) -> Result<DataType> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT typeof({}) FROM {}",
+        quote_identifier(column_name),
+        quote_identifier(table_name)
+    ))?;
+    let storage_classes = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let has = |class: &str| storage_classes.iter().any(|c| c == class);
+
+    let data_type = if has("text") {
+        DataType::Utf8
+    } else if has("blob") {
+        if has("integer") || has("real") {
+            DataType::Utf8
+        } else {
+            DataType::Binary
+        }
+    } else if has("real") {
+        DataType::Float64
+    } else if has("integer") {
+        DataType::Int64
+    } else {
+        DataType::Utf8
+    };
+    Ok(data_type)
+}
+
+/// A [RecordBatchReader] over a GeoPackage feature table.
+///
+/// Rows are read in order of the table's primary key, `batch_size` rows at a time.
+pub struct GeoPackageRecordBatchReader {
+    conn: Connection,
+    table: TableInfo,
+    schema: SchemaRef,
+    sql: String,
+    batch_size: usize,
+    bbox: Option<(f64, f64, f64, f64)>,
+    /// Whether the bbox filter is evaluated within SQLite via the R-tree index.
+    use_rtree: bool,
+    /// The primary key of the last row read.
+    last_fid: i64,
+    done: bool,
+}
+
+impl GeoPackageRecordBatchReader {
+    fn try_new(
+        conn: Connection,
+        table: TableInfo,
+        options: GeoPackageReaderOptions,
+    ) -> Result<Self> {
+        let use_rtree = options.bbox.is_some() && table.rtree.is_some();
+        let sql = table.query(use_rtree);
+        // Validate the query up front, so that errors surface before iteration
+        conn.prepare_cached(&sql)?;
+        Ok(Self {
+            schema: table.schema(),
+            conn,
+            table,
+            sql,
+            batch_size: options.batch_size.unwrap_or(65_536),
+            bbox: options.bbox,
+            use_rtree,
+            last_fid: i64::MIN,
+            done: false,
+        })
+    }
+
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let mut builders = self
+            .table
+            .columns
+            .iter()
+            .map(|column| ColumnBuilder::new(&column.kind, self.batch_size))
+            .collect::<Vec<_>>();
+
+        let mut stmt = self.conn.prepare_cached(&self.sql)?;
+        while !self.done && builders[0].len() == 0 {
+            let mut params: Vec<rusqlite::types::Value> =
+                vec![self.last_fid.into(), (self.batch_size as i64).into()];
+            if self.use_rtree {
+                let (min_x, min_y, max_x, max_y) = self.bbox.unwrap();
+                params.extend([max_x.into(), min_x.into(), max_y.into(), min_y.into()]);
+            }
+
+            let mut rows = stmt.query(params_from_iter(params))?;
+            let mut num_rows = 0;
+            while let Some(row) = rows.next()? {
+                num_rows += 1;
+                self.last_fid = row.get(0)?;
+
+                if !self.use_rtree {
+                    if let Some(bbox) = self.bbox {
+                        let value = row.get_ref(self.table.geometry_column_idx + 1)?;
+                        if !intersects_envelope(value, bbox)? {
+                            continue;
+                        }
+                    }
+                }
+
+                for (column_idx, builder) in builders.iter_mut().enumerate() {
+                    let name = &self.table.columns[column_idx].name;
+                    builder.append_value(name, row.get_ref(column_idx + 1)?)?;
+                }
+            }
+            if num_rows < self.batch_size {
+                self.done = true;
+            }
+        }
+
+        if builders[0].len() == 0 {
+            return Ok(None);
+        }
+
+        let columns = builders
+            .into_iter()
+            .map(|builder| builder.finish(&self.table.geometry_type))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), columns)?))
+    }
+}
+
+/// Check the envelope in a geometry's header against a bbox. Geometries without an envelope are
+/// kept, unless they are empty.
+fn intersects_envelope(value: ValueRef, bbox: (f64, f64, f64, f64)) -> Result<bool> {
+    let ValueRef::Blob(buf) = value else {
+        return Ok(false);
+    };
+    let geometry = GeoPackageGeometry::parse(buf)?;
+    if geometry.empty {
+        return Ok(false);
+    }
+    let (min_x, min_y, max_x, max_y) = bbox;
+    Ok(match geometry.envelope {
+        Some([env_min_x, env_max_x, env_min_y, env_max_y]) => {
+            env_min_x <= max_x && env_max_x >= min_x && env_min_y <= max_y && env_max_y >= min_y
+        }
+        None => true,
+    })
+}
+
+impl Iterator for GeoPackageRecordBatchReader {
+    type Item = std::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch()
+            .map_err(|err| ArrowError::ExternalError(Box::new(err)))
+            .transpose()
+    }
+}
+
+impl RecordBatchReader for GeoPackageRecordBatchReader {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// A builder for a single column of a feature table.
+#[derive(Debug)]
+enum ColumnBuilder {
+    Bool(BooleanBuilder),
+    Int8(Int8Builder),
+    Int16(Int16Builder),
+    Int32(Int32Builder),
+    Int64(Int64Builder),
+    Float32(Float32Builder),
+    Float64(Float64Builder),
+    String(StringBuilder),
+    Binary(BinaryBuilder),
+    Date(Date32Builder),
+    DateTime(TimestampMillisecondBuilder),
+    /// The WKB of each geometry, with the GeoPackage header removed.
+    Geometry(BinaryBuilder),
+}
+
+impl ColumnBuilder {
+    fn new(kind: &ColumnKind, capacity: usize) -> Self {
+        let data_type = match kind {
+            ColumnKind::Property(data_type) => data_type,
+            ColumnKind::Geometry => {
+                return Self::Geometry(BinaryBuilder::with_capacity(capacity, 0));
+            }
+        };
+        match data_type {
+            DataType::Boolean => Self::Bool(BooleanBuilder::with_capacity(capacity)),
+            DataType::Int8 => Self::Int8(Int8Builder::with_capacity(capacity)),
+            DataType::Int16 => Self::Int16(Int16Builder::with_capacity(capacity)),
+            DataType::Int32 => Self::Int32(Int32Builder::with_capacity(capacity)),
+            DataType::Int64 => Self::Int64(Int64Builder::with_capacity(capacity)),
+            DataType::Float32 => Self::Float32(Float32Builder::with_capacity(capacity)),
+            DataType::Float64 => Self::Float64(Float64Builder::with_capacity(capacity)),
+            DataType::Utf8 => Self::String(StringBuilder::with_capacity(capacity, 0)),
+            DataType::Binary => Self::Binary(BinaryBuilder::with_capacity(capacity, 0)),
+            DataType::Date32 => Self::Date(Date32Builder::with_capacity(capacity)),
+            DataType::Timestamp(_, tz) => Self::DateTime(
+                TimestampMillisecondBuilder::with_capacity(capacity).with_timezone_opt(tz.clone()),
+            ),
+            // sqlite_data_type and infer_storage_type only produce the types above
+            _ => unreachable!(),
+        }
+    }
+
+    /// Append a SQLite value. SQLite columns are dynamically typed, so integer values are
+    /// accepted in floating point columns and text values in date columns.
+    fn append_value(&mut self, name: &str, value: ValueRef) -> Result<()> {
+        if let ValueRef::Null = value {
+            self.append_null();
+            return Ok(());
+        }
+
+        let invalid_value = || {
+            GeoArrowError::General(format!(
+                "Unexpected value {:?} in GeoPackage column {}",
+                value, name
+            ))
+        };
+        let integer = |value: ValueRef| match value {
+            ValueRef::Integer(v) => Ok(v),
+            _ => Err(invalid_value()),
+        };
+        let float = |value: ValueRef| match value {
+            ValueRef::Integer(v) => Ok(v as f64),
+            ValueRef::Real(v) => Ok(v),
+            _ => Err(invalid_value()),
+        };
+        let text = |value: ValueRef<'_>| match value {
+            ValueRef::Text(v) => std::str::from_utf8(v).map_err(|_| invalid_value()),
+            _ => Err(invalid_value()),
+        };
+
+        match self {
+            Self::Bool(builder) => builder.append_value(integer(value)? != 0),
+            Self::Int8(builder) => {
+                builder.append_value(integer(value)?.try_into().map_err(|_| invalid_value())?)
+            }
+            Self::Int16(builder) => {
+                builder.append_value(integer(value)?.try_into().map_err(|_| invalid_value())?)
+            }
+            Self::Int32(builder) => {
+                builder.append_value(integer(value)?.try_into().map_err(|_| invalid_value())?)
+            }
+            Self::Int64(builder) => builder.append_value(integer(value)?),
+            Self::Float32(builder) => builder.append_value(float(value)? as f32),
+            Self::Float64(builder) => builder.append_value(float(value)?),
+            // Columns without a declared type may mix text and numbers
+            Self::String(builder) => match value {
+                ValueRef::Integer(v) => builder.append_value(v.to_string()),
+                ValueRef::Real(v) => builder.append_value(v.to_string()),
+                value => builder.append_value(text(value)?),
+            },
+            Self::Binary(builder) => {
+                builder.append_value(value.as_blob().map_err(|_| invalid_value())?)
+            }
+            Self::Date(builder) => {
+                let date = Date32Type::parse(text(value)?).ok_or_else(invalid_value)?;
+                builder.append_value(date);
+            }
+            Self::DateTime(builder) => {
+                let nanos = string_to_timestamp_nanos(text(value)?)?;
+                builder.append_value(nanos.div_euclid(1_000_000));
+            }
+            Self::Geometry(builder) => {
+                let buf = value.as_blob().map_err(|_| invalid_value())?;
+                builder.append_value(GeoPackageGeometry::parse(buf)?.wkb);
+            }
+        }
+        Ok(())
+    }
+
+    fn append_null(&mut self) {
+        match self {
+            Self::Bool(builder) => builder.append_null(),
+            Self::Int8(builder) => builder.append_null(),
+            Self::Int16(builder) => builder.append_null(),
+            Self::Int32(builder) => builder.append_null(),
+            Self::Int64(builder) => builder.append_null(),
+            Self::Float32(builder) => builder.append_null(),
+            Self::Float64(builder) => builder.append_null(),
+            Self::String(builder) => builder.append_null(),
+            Self::Binary(builder) => builder.append_null(),
+            Self::Date(builder) => builder.append_null(),
+            Self::DateTime(builder) => builder.append_null(),
+            Self::Geometry(builder) => builder.append_null(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Bool(builder) => builder.len(),
+            Self::Int8(builder) => builder.len(),
+            Self::Int16(builder) => builder.len(),
+            Self::Int32(builder) => builder.len(),
+            Self::Int64(builder) => builder.len(),
+            Self::Float32(builder) => builder.len(),
+            Self::Float64(builder) => builder.len(),
+            Self::String(builder) => builder.len(),
+            Self::Binary(builder) => builder.len(),
+            Self::Date(builder) => builder.len(),
+            Self::DateTime(builder) => builder.len(),
+            Self::Geometry(builder) => builder.len(),
+        }
+    }
+
+    fn finish(self, geometry_type: &NativeType) -> Result<ArrayRef> {
+        let array: ArrayRef = match self {
+            Self::Bool(mut builder) => Arc::new(builder.finish()),
+            Self::Int8(mut builder) => Arc::new(builder.finish()),
+            Self::Int16(mut builder) => Arc::new(builder.finish()),
+            Self::Int32(mut builder) => Arc::new(builder.finish()),
+            Self::Int64(mut builder) => Arc::new(builder.finish()),
+            Self::Float32(mut builder) => Arc::new(builder.finish()),
+            Self::Float64(mut builder) => Arc::new(builder.finish()),
+            Self::String(mut builder) => Arc::new(builder.finish()),
+            Self::Binary(mut builder) => Arc::new(builder.finish()),
+            Self::Date(mut builder) => Arc::new(builder.finish()),
+            Self::DateTime(mut builder) => Arc::new(builder.finish()),
+            Self::Geometry(mut builder) => {
+                let wkb_array = WKBArray::new(builder.finish(), geometry_type.metadata().clone());
+                from_wkb(&wkb_array, geometry_type.clone(), false)?.to_array_ref()
+            }
+        };
+        Ok(array)
+    }
+}
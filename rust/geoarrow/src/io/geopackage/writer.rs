@@ -0,0 +1,647 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::{Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use geoarrow_schema::{CoordType, Crs, CrsType, Dimension, Metadata};
+use rusqlite::types::Value;
+use rusqlite::{Connection, OptionalExtension, Transaction, params, params_from_iter};
+use serde_json::Value as JsonValue;
+
+use crate::algorithm::native::bounding_rect::BoundingRect;
+use crate::array::{NativeArrayDyn, WKBArray, WKTArray};
+use crate::datatypes::{AnyType, NativeType, SerializedType};
+use crate::error::{GeoArrowError, Result};
+use crate::io::RecordBatchReader;
+use crate::io::geopackage::header::write_geometry;
+use crate::io::geopackage::{quote_identifier, rtree_table_name};
+use crate::io::wkb::to_wkb;
+use crate::io::wkt::read_wkt;
+use crate::schema::GeoSchemaExt;
+use crate::trait_::ArrayAccessor;
+
+/// The WKT definition of EPSG:4326 required by the GeoPackage specification.
+const WGS84_WKT: &str = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4326"]]"#;
+
+/// The tables every GeoPackage must contain, along with the `gpkg_extensions` table used to
+/// register the R-tree spatial index.
+const CREATE_CORE_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS gpkg_spatial_ref_sys (
+    srs_name TEXT NOT NULL,
+    srs_id INTEGER PRIMARY KEY,
+    organization TEXT NOT NULL,
+    organization_coordsys_id INTEGER NOT NULL,
+    definition TEXT NOT NULL,
+    description TEXT
+);
+CREATE TABLE IF NOT EXISTS gpkg_contents (
+    table_name TEXT NOT NULL PRIMARY KEY,
+    data_type TEXT NOT NULL,
+    identifier TEXT UNIQUE,
+    description TEXT DEFAULT '',
+    last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    min_x DOUBLE,
+    min_y DOUBLE,
+    max_x DOUBLE,
+    max_y DOUBLE,
+    srs_id INTEGER,
+    CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id)
+);
+CREATE TABLE IF NOT EXISTS gpkg_geometry_columns (
+    table_name TEXT NOT NULL,
+    column_name TEXT NOT NULL,
+    geometry_type_name TEXT NOT NULL,
+    srs_id INTEGER NOT NULL,
+    z TINYINT NOT NULL,
+    m TINYINT NOT NULL,
+    CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name),
+    CONSTRAINT uk_gc_table_name UNIQUE (table_name),
+    CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name),
+    CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys (srs_id)
+);
+CREATE TABLE IF NOT EXISTS gpkg_extensions (
+    table_name TEXT,
+    column_name TEXT,
+    extension_name TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    CONSTRAINT ge_tce UNIQUE (table_name, column_name, extension_name)
+);
+"#;
+
+/// The name of the integer primary key column created in each feature table.
+const FID_COLUMN: &str = "fid";
+
+/// Options for the GeoPackage writer
+#[derive(Debug, Clone)]
+pub struct GeoPackageWriterOptions {
+    /// Whether to create an R-tree spatial index on the geometry column.
+    ///
+    /// Defaults to `true`.
+    pub spatial_index: bool,
+
+    /// A human-readable identifier for the table, stored in `gpkg_contents`.
+    pub identifier: Option<String>,
+
+    /// A human-readable description for the table, stored in `gpkg_contents`.
+    pub description: Option<String>,
+}
+
+impl Default for GeoPackageWriterOptions {
+    fn default() -> Self {
+        Self {
+            spatial_index: true,
+            identifier: None,
+            description: None,
+        }
+    }
+}
+
+/// Write a table to a new feature table in a GeoPackage file.
+///
+/// The file is created if it doesn't exist. Otherwise the table is added to the existing
+/// GeoPackage, and an error is returned if a table with the same name already exists.
+///
+/// The table must contain exactly one geometry column, in a native GeoArrow encoding, WKB or WKT.
+/// Serialized geometries are declared with the `GEOMETRY` type, since each value may have its own
+/// type and dimension. An integer primary key named `fid` is added to the feature table.
+pub fn write_geopackage<S: Into<RecordBatchReader>>(
+    stream: S,
+    path: impl AsRef<Path>,
+    table_name: &str,
+    options: GeoPackageWriterOptions,
+) -> Result<()> {
+    let reader = stream.into().into_inner();
+    let schema = reader.schema();
+
+    let geometry_columns = schema.as_ref().geometry_columns();
+    let geometry_column_idx = match geometry_columns.as_slice() {
+        [idx] => *idx,
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Writing GeoPackage requires exactly one geometry column, found {}",
+                geometry_columns.len()
+            )));
+        }
+    };
+    let geometry_field = schema.field(geometry_column_idx);
+    let geometry_type = AnyType::try_from(geometry_field)?;
+    if let Some(field) = schema
+        .fields()
+        .iter()
+        .find(|field| field.name().eq_ignore_ascii_case(FID_COLUMN))
+    {
+        return Err(GeoArrowError::General(format!(
+            "Column {} conflicts with the GeoPackage primary key",
+            field.name()
+        )));
+    }
+
+    let mut conn = Connection::open(path)?;
+    conn.pragma_update(None, "application_id", 0x47504B47)?;
+    conn.pragma_update(None, "user_version", 10400)?;
+
+    let tx = conn.transaction()?;
+    tx.execute_batch(CREATE_CORE_TABLES)?;
+    tx.execute(
+        "INSERT OR IGNORE INTO gpkg_spatial_ref_sys VALUES
+            ('WGS 84 geodetic', 4326, 'EPSG', 4326, ?1, 'longitude/latitude coordinates in decimal degrees on the WGS 84 spheroid'),
+            ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', 'undefined cartesian coordinate reference system'),
+            ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', 'undefined geographic coordinate reference system')",
+        [WGS84_WKT],
+    )?;
+
+    let srs_id = insert_srs(&tx, geometry_metadata(&geometry_type).crs())?;
+    let geometry_type_name = geometry_type_name(&geometry_type);
+    create_feature_table(
+        &tx,
+        table_name,
+        &schema,
+        geometry_column_idx,
+        geometry_type_name,
+    )?;
+
+    let geometry_column = geometry_field.name();
+    let dimension = match &geometry_type {
+        AnyType::Native(geometry_type) => geometry_type.dimension(),
+        AnyType::Serialized(_) => None,
+    };
+    let (z, m) = match dimension {
+        Some(Dimension::XY) => (0, 0),
+        Some(Dimension::XYZ) => (1, 0),
+        Some(Dimension::XYM) => (0, 1),
+        Some(Dimension::XYZM) => (1, 1),
+        // Each geometry of a mixed array may have its own dimension
+        None => (2, 2),
+    };
+    tx.execute(
+        "INSERT INTO gpkg_contents (table_name, data_type, identifier, description, srs_id)
+        VALUES (?1, 'features', ?2, ?3, ?4)",
+        params![
+            table_name,
+            options.identifier.as_deref().unwrap_or(table_name),
+            options.description.as_deref().unwrap_or(""),
+            srs_id
+        ],
+    )?;
+    tx.execute(
+        "INSERT INTO gpkg_geometry_columns VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            table_name,
+            geometry_column,
+            geometry_type_name,
+            srs_id,
+            z,
+            m
+        ],
+    )?;
+
+    let rtree = rtree_table_name(table_name, geometry_column);
+    if options.spatial_index {
+        tx.execute_batch(&format!(
+            "CREATE VIRTUAL TABLE {} USING rtree(id, minx, maxx, miny, maxy)",
+            quote_identifier(&rtree)
+        ))?;
+    }
+
+    let mut extent = BoundingRect::new();
+    for batch in reader {
+        let batch = batch?;
+        insert_batch(
+            &tx,
+            table_name,
+            &batch,
+            geometry_column_idx,
+            srs_id,
+            options.spatial_index.then_some(rtree.as_str()),
+            &mut extent,
+        )?;
+    }
+
+    if extent.minx().is_finite() {
+        tx.execute(
+            "UPDATE gpkg_contents SET min_x = ?1, min_y = ?2, max_x = ?3, max_y = ?4
+            WHERE table_name = ?5",
+            params![
+                extent.minx(),
+                extent.miny(),
+                extent.maxx(),
+                extent.maxy(),
+                table_name
+            ],
+        )?;
+    }
+
+    if options.spatial_index {
+        // The triggers are created after the features are inserted, because they rely on the
+        // ST_* SQL functions that GeoPackage-aware clients like GDAL register.
+        create_rtree_triggers(&tx, table_name, geometry_column, &rtree)?;
+        tx.execute(
+            "INSERT INTO gpkg_extensions VALUES (?1, ?2, 'gpkg_rtree_index',
+            'http://www.geopackage.org/spec120/#extension_rtree', 'write-only')",
+            params![table_name, geometry_column],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Find or insert the spatial reference system for a [Crs], returning its `srs_id`.
+///
+/// Authority codes, including the `id` member of PROJJSON, are matched against existing rows and
+/// otherwise added without a definition. Other CRS strings are stored as the definition of a new
+/// row. Without a CRS, the undefined cartesian SRS of `-1` is used.
+fn insert_srs(tx: &Transaction, crs: &Crs) -> Result<i64> {
+    let authority_code = match (crs.crs_type(), crs.crs_value()) {
+        (Some(CrsType::Projjson), Some(JsonValue::Object(projjson))) => {
+            projjson.get("id").and_then(|id| {
+                let authority = id.get("authority")?.as_str()?;
+                let code = id.get("code")?.as_i64()?;
+                Some((authority.to_string(), code))
+            })
+        }
+        (_, Some(JsonValue::String(value))) => value
+            .split_once(':')
+            .and_then(|(authority, code)| Some((authority.to_string(), code.parse().ok()?))),
+        _ => None,
+    };
+
+    if let Some((organization, code)) = authority_code {
+        let existing = tx
+            .query_row(
+                "SELECT srs_id FROM gpkg_spatial_ref_sys
+                WHERE upper(organization) = upper(?1) AND organization_coordsys_id = ?2",
+                params![organization, code],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(srs_id) = existing {
+            return Ok(srs_id);
+        }
+
+        let code_is_free = tx
+            .query_row(
+                "SELECT 1 FROM gpkg_spatial_ref_sys WHERE srs_id = ?1",
+                [code],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_none();
+        let srs_id = if code_is_free { code } else { next_srs_id(tx)? };
+        tx.execute(
+            "INSERT INTO gpkg_spatial_ref_sys VALUES (?1, ?2, ?3, ?4, 'undefined', NULL)",
+            params![format!("{organization}:{code}"), srs_id, organization, code],
+        )?;
+        return Ok(srs_id);
+    }
+
+    let Some(JsonValue::String(definition)) = crs.crs_value() else {
+        return Ok(-1);
+    };
+    let existing = tx
+        .query_row(
+            "SELECT srs_id FROM gpkg_spatial_ref_sys WHERE definition = ?1",
+            [definition],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(srs_id) = existing {
+        return Ok(srs_id);
+    }
+    let srs_id = next_srs_id(tx)?;
+    tx.execute(
+        "INSERT INTO gpkg_spatial_ref_sys VALUES ('Unknown', ?1, 'NONE', ?1, ?2, NULL)",
+        params![srs_id, definition],
+    )?;
+    Ok(srs_id)
+}
+
+/// An `srs_id` outside of the range of EPSG codes.
+fn next_srs_id(tx: &Transaction) -> Result<i64> {
+    Ok(tx.query_row(
+        "SELECT max(max(srs_id) + 1, 100000) FROM gpkg_spatial_ref_sys",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// The metadata of a geometry column, holding its CRS.
+fn geometry_metadata(geometry_type: &AnyType) -> &Arc<Metadata> {
+    match geometry_type {
+        AnyType::Native(geometry_type) => geometry_type.metadata(),
+        AnyType::Serialized(SerializedType::WKB(t) | SerializedType::LargeWKB(t)) => t.metadata(),
+        AnyType::Serialized(SerializedType::WKT(t) | SerializedType::LargeWKT(t)) => t.metadata(),
+    }
+}
+
+fn geometry_type_name(geometry_type: &AnyType) -> &'static str {
+    use NativeType::*;
+    let AnyType::Native(geometry_type) = geometry_type else {
+        // Each serialized geometry may have its own type
+        return "GEOMETRY";
+    };
+    match geometry_type {
+        Point(_) => "POINT",
+        LineString(_) => "LINESTRING",
+        // Rects are written as polygons
+        Polygon(_) | Rect(_) => "POLYGON",
+        MultiPoint(_) => "MULTIPOINT",
+        MultiLineString(_) => "MULTILINESTRING",
+        MultiPolygon(_) => "MULTIPOLYGON",
+        GeometryCollection(_) => "GEOMETRYCOLLECTION",
+        Geometry(_) => "GEOMETRY",
+    }
+}
+
+fn create_feature_table(
+    tx: &Transaction,
+    table_name: &str,
+    schema: &Schema,
+    geometry_column_idx: usize,
+    geometry_type_name: &str,
+) -> Result<()> {
+    let mut columns = vec![format!(
+        "{} INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL",
+        quote_identifier(FID_COLUMN)
+    )];
+    for (idx, field) in schema.fields().iter().enumerate() {
+        let column_type = if idx == geometry_column_idx {
+            geometry_type_name
+        } else {
+            sqlite_type(field)?
+        };
+        let not_null = if field.is_nullable() { "" } else { " NOT NULL" };
+        columns.push(format!(
+            "{} {}{}",
+            quote_identifier(field.name()),
+            column_type,
+            not_null
+        ));
+    }
+    tx.execute_batch(&format!(
+        "CREATE TABLE {} ({})",
+        quote_identifier(table_name),
+        columns.join(", ")
+    ))?;
+    Ok(())
+}
+
+/// The GeoPackage column type used to store an Arrow field.
+fn sqlite_type(field: &Field) -> Result<&'static str> {
+    let column_type = match field.data_type() {
+        DataType::Boolean => "BOOLEAN",
+        DataType::Int8 => "TINYINT",
+        DataType::Int16 | DataType::UInt8 => "SMALLINT",
+        DataType::Int32 | DataType::UInt16 => "MEDIUMINT",
+        DataType::Int64 | DataType::UInt32 | DataType::UInt64 => "INTEGER",
+        DataType::Float16 | DataType::Float32 => "FLOAT",
+        DataType::Float64 => "DOUBLE",
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "TEXT",
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView => "BLOB",
+        DataType::Date32 | DataType::Date64 => "DATE",
+        DataType::Timestamp(_, _) => "DATETIME",
+        data_type => {
+            return Err(GeoArrowError::NotYetImplemented(format!(
+                "Writing {} column {} to GeoPackage",
+                data_type,
+                field.name()
+            )));
+        }
+    };
+    Ok(column_type)
+}
+
+fn insert_batch(
+    tx: &Transaction,
+    table_name: &str,
+    batch: &RecordBatch,
+    geometry_column_idx: usize,
+    srs_id: i64,
+    rtree: Option<&str>,
+    extent: &mut BoundingRect,
+) -> Result<()> {
+    let schema = batch.schema();
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| quote_identifier(field.name()))
+        .collect::<Vec<_>>();
+    let placeholders = (1..=columns.len())
+        .map(|i| format!("?{i}"))
+        .collect::<Vec<_>>();
+    let mut insert = tx.prepare_cached(&format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote_identifier(table_name),
+        columns.join(", "),
+        placeholders.join(", ")
+    ))?;
+    let mut insert_rtree = rtree
+        .map(|rtree| {
+            tx.prepare_cached(&format!(
+                "INSERT INTO {} VALUES (?1, ?2, ?3, ?4, ?5)",
+                quote_identifier(rtree)
+            ))
+        })
+        .transpose()?;
+
+    let wkb_array = wkb_column(
+        batch.column(geometry_column_idx).as_ref(),
+        schema.field(geometry_column_idx),
+    )?;
+
+    let mut geometry_buf = vec![];
+    for row in 0..batch.num_rows() {
+        let mut envelope = None;
+        let mut values = Vec::with_capacity(batch.num_columns());
+        for (column_idx, array) in batch.columns().iter().enumerate() {
+            if column_idx != geometry_column_idx {
+                values.push(property_value(array.as_ref(), row)?);
+                continue;
+            }
+
+            let Some(wkb) = wkb_array.get(row) else {
+                values.push(Value::Null);
+                continue;
+            };
+            let mut bbox = BoundingRect::new();
+            bbox.add_geometry(&wkb.parse()?);
+            if bbox.minx().is_finite() {
+                extent.update(&bbox);
+                envelope = Some([bbox.minx(), bbox.maxx(), bbox.miny(), bbox.maxy()]);
+            }
+            geometry_buf.clear();
+            write_geometry(&mut geometry_buf, srs_id as i32, envelope, wkb.as_slice());
+            values.push(Value::Blob(geometry_buf.clone()));
+        }
+
+        insert.execute(params_from_iter(values))?;
+        if let (Some(insert_rtree), Some([min_x, max_x, min_y, max_y])) =
+            (insert_rtree.as_mut(), envelope)
+        {
+            insert_rtree.execute(params![tx.last_insert_rowid(), min_x, max_x, min_y, max_y])?;
+        }
+    }
+    Ok(())
+}
+
+/// Convert a geometry column to WKB with `i32` offsets.
+///
+/// WKB columns are used as is, while native and WKT columns are encoded.
+fn wkb_column(array: &dyn Array, field: &Field) -> Result<WKBArray<i32>> {
+    match AnyType::try_from(field)? {
+        AnyType::Native(_) => {
+            let array = NativeArrayDyn::from_arrow_array(array, field)?.into_inner();
+            Ok(to_wkb(array.as_ref()))
+        }
+        AnyType::Serialized(SerializedType::WKB(_)) => WKBArray::<i32>::try_from((array, field)),
+        AnyType::Serialized(SerializedType::LargeWKB(_)) => {
+            WKBArray::<i64>::try_from((array, field))?.try_into()
+        }
+        AnyType::Serialized(SerializedType::WKT(_)) => {
+            let wkt_array = WKTArray::<i32>::try_from((array, field))?;
+            let array = read_wkt(&wkt_array, CoordType::Interleaved, false)?;
+            Ok(to_wkb(array.as_ref()))
+        }
+        AnyType::Serialized(SerializedType::LargeWKT(_)) => {
+            let wkt_array = WKTArray::<i64>::try_from((array, field))?;
+            let array = read_wkt(&wkt_array, CoordType::Interleaved, false)?;
+            Ok(to_wkb(array.as_ref()))
+        }
+    }
+}
+
+/// Convert a property value to a SQLite value.
+///
+/// Dates and timestamps are written as the ISO 8601 strings required by GeoPackage.
+fn property_value(array: &dyn Array, row: usize) -> Result<Value> {
+    if array.is_null(row) {
+        return Ok(Value::Null);
+    }
+
+    let out_of_range = || {
+        GeoArrowError::General(format!(
+            "{} value at row {} is out of range for a GeoPackage date",
+            array.data_type(),
+            row
+        ))
+    };
+    let value = match array.data_type() {
+        DataType::Boolean => Value::Integer(array.as_boolean().value(row) as i64),
+        DataType::Int8 => Value::Integer(array.as_primitive::<Int8Type>().value(row).into()),
+        DataType::UInt8 => Value::Integer(array.as_primitive::<UInt8Type>().value(row).into()),
+        DataType::Int16 => Value::Integer(array.as_primitive::<Int16Type>().value(row).into()),
+        DataType::UInt16 => Value::Integer(array.as_primitive::<UInt16Type>().value(row).into()),
+        DataType::Int32 => Value::Integer(array.as_primitive::<Int32Type>().value(row).into()),
+        DataType::UInt32 => Value::Integer(array.as_primitive::<UInt32Type>().value(row).into()),
+        DataType::Int64 => Value::Integer(array.as_primitive::<Int64Type>().value(row)),
+        DataType::UInt64 => {
+            let value = array.as_primitive::<UInt64Type>().value(row);
+            Value::Integer(value.try_into().map_err(|_| {
+                GeoArrowError::General(format!(
+                    "Value {} is too large for a GeoPackage integer",
+                    value
+                ))
+            })?)
+        }
+        DataType::Float16 => Value::Real(array.as_primitive::<Float16Type>().value(row).to_f64()),
+        DataType::Float32 => Value::Real(array.as_primitive::<Float32Type>().value(row).into()),
+        DataType::Float64 => Value::Real(array.as_primitive::<Float64Type>().value(row)),
+        DataType::Utf8 => Value::Text(array.as_string::<i32>().value(row).to_string()),
+        DataType::LargeUtf8 => Value::Text(array.as_string::<i64>().value(row).to_string()),
+        DataType::Utf8View => Value::Text(array.as_string_view().value(row).to_string()),
+        DataType::Binary => Value::Blob(array.as_binary::<i32>().value(row).to_vec()),
+        DataType::LargeBinary => Value::Blob(array.as_binary::<i64>().value(row).to_vec()),
+        DataType::BinaryView => Value::Blob(array.as_binary_view().value(row).to_vec()),
+        DataType::Date32 => {
+            let date = array.as_primitive::<Date32Type>().value_as_date(row);
+            Value::Text(
+                date.ok_or_else(out_of_range)?
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        }
+        DataType::Date64 => {
+            let date = array.as_primitive::<Date64Type>().value_as_date(row);
+            Value::Text(
+                date.ok_or_else(out_of_range)?
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        }
+        DataType::Timestamp(unit, _) => {
+            // Timestamps are stored relative to UTC regardless of their timezone
+            let datetime = match unit {
+                TimeUnit::Second => array
+                    .as_primitive::<TimestampSecondType>()
+                    .value_as_datetime(row),
+                TimeUnit::Millisecond => array
+                    .as_primitive::<TimestampMillisecondType>()
+                    .value_as_datetime(row),
+                TimeUnit::Microsecond => array
+                    .as_primitive::<TimestampMicrosecondType>()
+                    .value_as_datetime(row),
+                TimeUnit::Nanosecond => array
+                    .as_primitive::<TimestampNanosecondType>()
+                    .value_as_datetime(row),
+            };
+            Value::Text(
+                datetime
+                    .ok_or_else(out_of_range)?
+                    .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+                    .to_string(),
+            )
+        }
+        data_type => {
+            return Err(GeoArrowError::NotYetImplemented(format!(
+                "Writing {} values to GeoPackage",
+                data_type
+            )));
+        }
+    };
+    Ok(value)
+}
+
+/// Create the triggers that keep the R-tree index in sync with the feature table, as defined by
+/// the GeoPackage R-tree spatial index extension.
+fn create_rtree_triggers(
+    tx: &Transaction,
+    table_name: &str,
+    geometry_column: &str,
+    rtree: &str,
+) -> Result<()> {
+    let t = quote_identifier(table_name);
+    let c = quote_identifier(geometry_column);
+    let i = quote_identifier(FID_COLUMN);
+    let r = quote_identifier(rtree);
+    let trigger = |suffix: &str| quote_identifier(&format!("{rtree}_{suffix}"));
+    let insert_new = format!(
+        "INSERT OR REPLACE INTO {r} VALUES (NEW.{i}, ST_MinX(NEW.{c}), ST_MaxX(NEW.{c}), ST_MinY(NEW.{c}), ST_MaxY(NEW.{c}))"
+    );
+
+    tx.execute_batch(&format!(
+        "CREATE TRIGGER {insert} AFTER INSERT ON {t}
+            WHEN (NEW.{c} NOT NULL AND NOT ST_IsEmpty(NEW.{c}))
+            BEGIN {insert_new}; END;
+        CREATE TRIGGER {update6} AFTER UPDATE OF {c} ON {t}
+            WHEN OLD.{i} = NEW.{i} AND (NEW.{c} NOTNULL AND NOT ST_IsEmpty(NEW.{c}))
+            BEGIN {insert_new}; END;
+        CREATE TRIGGER {update7} AFTER UPDATE OF {c} ON {t}
+            WHEN OLD.{i} = NEW.{i} AND (NEW.{c} ISNULL OR ST_IsEmpty(NEW.{c}))
+            BEGIN DELETE FROM {r} WHERE id = OLD.{i}; END;
+        CREATE TRIGGER {update5} AFTER UPDATE ON {t}
+            WHEN OLD.{i} != NEW.{i} AND (NEW.{c} NOTNULL AND NOT ST_IsEmpty(NEW.{c}))
+            BEGIN DELETE FROM {r} WHERE id = OLD.{i}; {insert_new}; END;
+        CREATE TRIGGER {delete} AFTER DELETE ON {t}
+            WHEN OLD.{c} NOT NULL
+            BEGIN DELETE FROM {r} WHERE id = OLD.{i}; END;",
+        insert = trigger("insert"),
+        update5 = trigger("update5"),
+        update6 = trigger("update6"),
+        update7 = trigger("update7"),
+        delete = trigger("delete"),
+    ))?;
+    Ok(())
+}
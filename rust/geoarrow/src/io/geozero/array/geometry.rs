@@ -57,7 +57,6 @@ impl GeometryStreamBuilder {
         }
     }
 
-    #[allow(dead_code)]
     pub fn push_null(&mut self) {
         self.builder.push_null()
     }
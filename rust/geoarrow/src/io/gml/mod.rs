@@ -0,0 +1,8 @@
+//! Read from and write to [GML](https://www.ogc.org/standard/gml/) simple features, such as
+//! the responses of WFS `GetFeature` requests.
+
+mod reader;
+mod writer;
+
+pub use reader::{GmlReaderOptions, read_gml};
+pub use writer::{GmlWriterOptions, write_gml};
@@ -0,0 +1,1014 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::BufRead;
+use std::sync::Arc;
+
+use arrow_array::builder::StringBuilder;
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use geoarrow_schema::{CoordType, Crs, Dimension, Metadata};
+use indexmap::IndexSet;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::parsed::{Coord, ParsedGeometry, ParsedGeometryBuilder, infer_geometry_type};
+use crate::io::stream::RecordBatchReader;
+use crate::io::xml::attribute;
+
+/// Options for the GML reader.
+#[derive(Debug, Clone)]
+pub struct GmlReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+
+    /// The number of features read to infer the schema and geometry type.
+    ///
+    /// Set this to `usize::MAX` to read the whole document before the first batch.
+    pub infer_schema_length: usize,
+
+    /// Whether to swap the first two values of each position.
+    ///
+    /// GML 3 positions follow the axis order of their CRS, so geometries in a geographic CRS
+    /// such as `urn:ogc:def:crs:EPSG::4326` have latitude first. Set this to read them with
+    /// longitude first.
+    pub swap_xy: bool,
+}
+
+impl Default for GmlReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+            infer_schema_length: 1000,
+            swap_xy: false,
+        }
+    }
+}
+
+/// Read the features of a GML feature collection.
+///
+/// Features are the children of `featureMember`, `featureMembers` and WFS `member` elements, at
+/// any depth. The output schema has a `gml_id` column if any feature has a `gml:id`, followed by
+/// one column for each simple property, and a `geometry` column holding the first geometry
+/// property of each feature. Properties with child elements other than a geometry are skipped.
+///
+/// Property columns are `Int64` if all of their values are integers, `Float64` if all are
+/// numbers, `Boolean` if all are `true` or `false`, `Date32` if all are dates, `Timestamp` if all
+/// are date-times, and `Utf8` otherwise. Empty and `xsi:nil` values are null.
+///
+/// GML 2 and 3 points, curves, surfaces, envelopes and their multi geometries are read. The
+/// geometry type is the narrowest that holds all geometries, and the CRS is taken from the first
+/// `srsName`. Positions are read in the order they are written, whatever the axis order of the
+/// `srsName`: set `swap_xy` to read a document in a latitude first CRS, such as
+/// `urn:ogc:def:crs:EPSG::4326`, with longitude first.
+///
+/// The document is read as the batches are iterated. The schema and geometry type are inferred
+/// from the first `infer_schema_length` features. Values of later features that can't be read as
+/// their column's type are null, and a later feature with a new property, a geometry that doesn't
+/// fit the geometry type, or Z values in an XY geometry type is an error.
+pub fn read_gml<R: BufRead + 'static>(
+    reader: R,
+    options: GmlReaderOptions,
+) -> Result<RecordBatchReader> {
+    let mut reader = Reader::from_reader(reader);
+    reader.config_mut().trim_text(true);
+    let mut features = GmlFeatureReader {
+        reader,
+        buf: vec![],
+        path: vec![],
+        text: String::new(),
+        parser: GmlParser {
+            swap_xy: options.swap_xy,
+            ..Default::default()
+        },
+    };
+
+    let batch_size = options.batch_size.unwrap_or(65_536).max(1);
+    let mut pending = VecDeque::new();
+    while pending.len() < options.infer_schema_length {
+        let Some(feature) = features.next_feature()? else {
+            break;
+        };
+        pending.push_back(feature);
+    }
+
+    let mut fields = vec![];
+    let has_id = pending.iter().any(|feature| feature.id.is_some());
+    if has_id {
+        fields.push(Field::new("gml_id", DataType::Utf8, true));
+    }
+    let property_names = features
+        .parser
+        .property_names
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    for property_name in &property_names {
+        let values = StringArray::from_iter(
+            pending
+                .iter()
+                .map(|feature| feature.properties.get(property_name)),
+        );
+        fields.push(Field::new(property_name, inferred_type(&values), true));
+    }
+
+    let crs = features
+        .parser
+        .srs_name
+        .as_deref()
+        .map(srs_name_crs)
+        .unwrap_or_default();
+    let geometry_type = infer_geometry_type(
+        pending
+            .iter()
+            .filter_map(|feature| feature.geometry.as_ref()),
+        options.coord_type,
+        Arc::new(Metadata::new(crs, None)),
+    );
+    fields.push(geometry_type.to_field("geometry", true));
+
+    Ok(RecordBatchReader::new(Box::new(GmlRecordBatchReader {
+        features,
+        pending,
+        schema: Arc::new(Schema::new(fields)),
+        has_id,
+        property_names,
+        geometry_type,
+        batch_size,
+    })))
+}
+
+/// Reads the features of a GML document one at a time.
+struct GmlFeatureReader<R: BufRead> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    /// The local names of the open elements.
+    path: Vec<Vec<u8>>,
+    text: String,
+    parser: GmlParser,
+}
+
+impl<R: BufRead> GmlFeatureReader<R> {
+    /// Read the next feature of the document, if any.
+    fn next_feature(&mut self) -> Result<Option<Feature>> {
+        loop {
+            if let Some(feature) = self.parser.finished.take() {
+                return Ok(Some(feature));
+            }
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(element) => {
+                    let name = element.local_name().as_ref().to_vec();
+                    let parent = self.path.last().map(Vec::as_slice);
+                    self.parser
+                        .start(&name, parent, self.path.len(), &element)?;
+                    self.path.push(name);
+                    self.text.clear();
+                }
+                Event::Empty(element) => {
+                    let name = element.local_name().as_ref().to_vec();
+                    let parent = self.path.last().map(Vec::as_slice);
+                    self.parser
+                        .start(&name, parent, self.path.len(), &element)?;
+                    self.parser.end(&name, parent, self.path.len(), "")?;
+                }
+                Event::Text(value) => self.text.push_str(&value.unescape()?),
+                Event::CData(value) => self.text.push_str(&String::from_utf8_lossy(&value)),
+                Event::End(_) => {
+                    let name = self.path.pop().unwrap_or_default();
+                    let parent = self.path.last().map(Vec::as_slice);
+                    self.parser
+                        .end(&name, parent, self.path.len(), self.text.trim())?;
+                    self.text.clear();
+                }
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+            self.buf.clear();
+        }
+    }
+}
+
+/// Reads the features of a GML document as record batches.
+struct GmlRecordBatchReader<R: BufRead> {
+    features: GmlFeatureReader<R>,
+    /// The features read to infer the schema, which make up the first batches.
+    pending: VecDeque<Feature>,
+    schema: SchemaRef,
+    has_id: bool,
+    property_names: Vec<String>,
+    geometry_type: NativeType,
+    batch_size: usize,
+}
+
+impl<R: BufRead> GmlRecordBatchReader<R> {
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let mut ids = self.has_id.then(StringBuilder::new);
+        let mut properties = self
+            .property_names
+            .iter()
+            .map(|_| StringBuilder::new())
+            .collect::<Vec<_>>();
+        let mut geometries = ParsedGeometryBuilder::try_new(self.geometry_type.clone())?;
+
+        let mut num_rows = 0;
+        while num_rows < self.batch_size {
+            let feature = match self.pending.pop_front() {
+                Some(feature) => feature,
+                None => match self.features.next_feature()? {
+                    Some(feature) => {
+                        self.check_feature(&feature)?;
+                        feature
+                    }
+                    None => break,
+                },
+            };
+            if let Some(ids) = &mut ids {
+                ids.append_option(feature.id);
+            }
+            for (values, property_name) in properties.iter_mut().zip(&self.property_names) {
+                values.append_option(feature.properties.get(property_name));
+            }
+            geometries.push(feature.geometry.as_ref())?;
+            num_rows += 1;
+        }
+        if num_rows == 0 {
+            return Ok(None);
+        }
+
+        let mut columns: Vec<ArrayRef> = vec![];
+        columns.extend(ids.map(|mut ids| Arc::new(ids.finish()) as ArrayRef));
+        for (mut values, field) in properties
+            .into_iter()
+            .zip(&self.schema.fields()[usize::from(self.has_id)..])
+        {
+            columns.push(arrow_cast::cast(&values.finish(), field.data_type())?);
+        }
+        columns.push(geometries.finish());
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), columns)?))
+    }
+
+    /// Check that a feature read after schema inference fits the schema.
+    fn check_feature(&self, feature: &Feature) -> Result<()> {
+        let mismatch = |reason: String| {
+            GeoArrowError::General(format!(
+                "GML feature {}doesn't fit the schema inferred from the first features: {}. \
+                 Increase infer_schema_length to infer it from more features",
+                feature
+                    .id
+                    .as_ref()
+                    .map(|id| format!("'{}' ", id))
+                    .unwrap_or_default(),
+                reason
+            ))
+        };
+
+        // Property names are only ever added, so any beyond the schema's are new
+        if let Some(name) = self
+            .features
+            .parser
+            .property_names
+            .get_index(self.property_names.len())
+        {
+            return Err(mismatch(format!("it has a new property '{}'", name)));
+        }
+        if let Some(geometry) = &feature.geometry {
+            if self.geometry_type.dimension() == Some(Dimension::XY) && geometry.has_z() {
+                return Err(mismatch("it has Z values".to_string()));
+            }
+            if !fits(&self.geometry_type, geometry) {
+                return Err(mismatch(format!(
+                    "its geometry doesn't fit the {} type",
+                    self.geometry_type.extension_name()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether a geometry can be read as a geometry type.
+fn fits(geometry_type: &NativeType, geometry: &ParsedGeometry) -> bool {
+    matches!(
+        (geometry_type, geometry),
+        (NativeType::Geometry(_), _)
+            | (NativeType::Point(_), ParsedGeometry::Point(_))
+            | (NativeType::LineString(_), ParsedGeometry::LineString(_))
+            | (NativeType::Polygon(_), ParsedGeometry::Polygon(_))
+            | (
+                NativeType::MultiPoint(_),
+                ParsedGeometry::Point(_) | ParsedGeometry::MultiPoint(_)
+            )
+            | (
+                NativeType::MultiLineString(_),
+                ParsedGeometry::LineString(_) | ParsedGeometry::MultiLineString(_)
+            )
+            | (
+                NativeType::MultiPolygon(_),
+                ParsedGeometry::Polygon(_) | ParsedGeometry::MultiPolygon(_)
+            )
+    )
+}
+
+impl<R: BufRead> Iterator for GmlRecordBatchReader<R> {
+    type Item = std::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch().map_err(ArrowError::from).transpose()
+    }
+}
+
+impl<R: BufRead> arrow_array::RecordBatchReader for GmlRecordBatchReader<R> {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Feature {
+    id: Option<String>,
+    properties: HashMap<String, String>,
+    geometry: Option<ParsedGeometry>,
+}
+
+/// The property of a feature that is being read.
+#[derive(Debug)]
+struct Property {
+    name: String,
+    nil: bool,
+    /// Whether the property has child elements, such as a geometry.
+    complex: bool,
+}
+
+/// A geometry whose members are still being read.
+#[derive(Debug)]
+enum GeometryFrame {
+    /// A multi geometry, by its local name, or a `Surface` with one or more patches.
+    Multi(Vec<u8>, Vec<ParsedGeometry>),
+    Polygon(Vec<Vec<Coord>>),
+    /// The positions of the segments of a `Curve`.
+    Curve(Vec<Coord>),
+}
+
+/// Collects the features of a GML document from its elements.
+#[derive(Debug, Default)]
+struct GmlParser {
+    swap_xy: bool,
+    /// The last feature that was read in full.
+    finished: Option<Feature>,
+    feature: Option<Feature>,
+    /// The depth of the element of the feature being read.
+    feature_depth: usize,
+    property: Option<Property>,
+    /// The names of simple properties, in order of first appearance.
+    property_names: IndexSet<String>,
+    /// The first `srsName` of a geometry.
+    srs_name: Option<String>,
+    /// The depth of the geometry being read, if any.
+    geometry_depth: Option<usize>,
+    geometry_frames: Vec<GeometryFrame>,
+    /// The `srsDimension` of the geometry being read.
+    srs_dimension: Option<usize>,
+    /// The `srsDimension` of the `posList` being read, if it has its own.
+    list_dimension: Option<usize>,
+    coords: Vec<Coord>,
+}
+
+impl GmlParser {
+    fn start(
+        &mut self,
+        name: &[u8],
+        parent: Option<&[u8]>,
+        depth: usize,
+        element: &BytesStart,
+    ) -> Result<()> {
+        let Some(feature) = &mut self.feature else {
+            if matches!(
+                parent,
+                Some(b"featureMember" | b"featureMembers" | b"member")
+            ) && !matches!(name, b"FeatureCollection" | b"SimpleFeatureCollection")
+            {
+                self.feature = Some(Feature {
+                    id: attribute(element, "id")?,
+                    ..Default::default()
+                });
+                self.feature_depth = depth;
+            }
+            return Ok(());
+        };
+
+        if depth == self.feature_depth + 1 {
+            self.property = Some(Property {
+                name: String::from_utf8_lossy(name).into_owned(),
+                nil: attribute(element, "nil")?.is_some_and(|nil| nil == "true"),
+                complex: false,
+            });
+            return Ok(());
+        }
+        let Some(property) = &mut self.property else {
+            return Ok(());
+        };
+        property.complex = true;
+        if property.name == "boundedBy" {
+            return Ok(());
+        }
+
+        if self.geometry_depth.is_none() {
+            if feature.geometry.is_some() || !is_geometry(name) {
+                return Ok(());
+            }
+            self.geometry_depth = Some(depth);
+            self.geometry_frames.clear();
+            if self.srs_name.is_none() {
+                self.srs_name = attribute(element, "srsName")?;
+            }
+        }
+        if let Some(srs_dimension) = attribute(element, "srsDimension")? {
+            let srs_dimension = srs_dimension.parse().map_err(|_| {
+                GeoArrowError::General(format!("Invalid GML srsDimension: '{}'", srs_dimension))
+            })?;
+            if name == b"posList" {
+                self.list_dimension = Some(srs_dimension);
+            } else {
+                self.srs_dimension = Some(srs_dimension);
+            }
+        }
+
+        match name {
+            b"MultiPoint" | b"MultiCurve" | b"MultiLineString" | b"MultiSurface"
+            | b"MultiPolygon" | b"MultiGeometry" | b"Surface" => self
+                .geometry_frames
+                .push(GeometryFrame::Multi(name.to_vec(), vec![])),
+            b"Polygon" | b"PolygonPatch" => {
+                self.geometry_frames.push(GeometryFrame::Polygon(vec![]))
+            }
+            b"Curve" => self.geometry_frames.push(GeometryFrame::Curve(vec![])),
+            b"Point" | b"LineString" | b"LineStringSegment" | b"LinearRing" | b"Envelope" => {
+                self.coords.clear()
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, name: &[u8], parent: Option<&[u8]>, depth: usize, text: &str) -> Result<()> {
+        let Some(feature) = &mut self.feature else {
+            return Ok(());
+        };
+
+        if depth == self.feature_depth {
+            self.finished = self.feature.take();
+            return Ok(());
+        }
+        if depth == self.feature_depth + 1 {
+            if let Some(property) = self.property.take() {
+                if !property.complex && !property.nil && !text.is_empty() {
+                    self.property_names.insert(property.name.clone());
+                    feature.properties.insert(property.name, text.to_string());
+                }
+            }
+            return Ok(());
+        }
+        if self.geometry_depth.is_none() {
+            return Ok(());
+        }
+
+        match name {
+            b"pos" | b"lowerCorner" | b"upperCorner" => {
+                let values = parse_values(text)?;
+                self.coords.extend(self.positions(&values, values.len())?);
+            }
+            b"posList" => {
+                let values = parse_values(text)?;
+                let dim = self.list_dimension.take().or(self.srs_dimension);
+                let coords = self.positions(&values, dim.unwrap_or(2))?;
+                self.coords.extend(coords);
+            }
+            b"coordinates" => {
+                for tuple in text.split_whitespace() {
+                    let values = tuple
+                        .split(',')
+                        .map(|value| {
+                            value.parse::<f64>().map_err(|_| {
+                                GeoArrowError::General(format!(
+                                    "Invalid GML coordinates: '{}'",
+                                    tuple
+                                ))
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    self.coords.extend(self.positions(&values, values.len())?);
+                }
+            }
+            b"Point" => {
+                if let Some(coord) = self.coords.first().copied() {
+                    self.push_geometry(ParsedGeometry::Point(coord));
+                }
+            }
+            b"LineString" => {
+                let coords = std::mem::take(&mut self.coords);
+                self.push_geometry(ParsedGeometry::LineString(coords));
+            }
+            b"LineStringSegment" => {
+                if let Some(GeometryFrame::Curve(curve)) = self.geometry_frames.last_mut() {
+                    // Each segment starts where the previous one ended
+                    let skip =
+                        usize::from(curve.last() == self.coords.first() && !curve.is_empty());
+                    curve.extend(self.coords.drain(..).skip(skip));
+                }
+            }
+            b"Curve" => {
+                if let Some(GeometryFrame::Curve(coords)) = self.geometry_frames.pop() {
+                    self.push_geometry(ParsedGeometry::LineString(coords));
+                }
+            }
+            b"LinearRing" => {
+                let coords = std::mem::take(&mut self.coords);
+                match self.geometry_frames.last_mut() {
+                    Some(GeometryFrame::Polygon(rings)) => {
+                        // The exterior ring comes first, whatever order the boundaries are in
+                        if matches!(parent, Some(b"exterior" | b"outerBoundaryIs")) {
+                            rings.insert(0, coords);
+                        } else {
+                            rings.push(coords);
+                        }
+                    }
+                    _ => self.push_geometry(ParsedGeometry::LineString(coords)),
+                }
+            }
+            b"Polygon" | b"PolygonPatch" => {
+                if let Some(GeometryFrame::Polygon(rings)) = self.geometry_frames.pop() {
+                    self.push_geometry(ParsedGeometry::Polygon(rings));
+                }
+            }
+            b"Envelope" => {
+                if let [lower, upper] = self.coords[..] {
+                    let ring = [
+                        (lower.x, lower.y),
+                        (upper.x, lower.y),
+                        (upper.x, upper.y),
+                        (lower.x, upper.y),
+                        (lower.x, lower.y),
+                    ]
+                    .map(|(x, y)| Coord { x, y, z: None });
+                    self.push_geometry(ParsedGeometry::Polygon(vec![ring.to_vec()]));
+                }
+            }
+            b"MultiPoint" | b"MultiCurve" | b"MultiLineString" | b"MultiSurface"
+            | b"MultiPolygon" | b"MultiGeometry" | b"Surface" => {
+                if let Some(GeometryFrame::Multi(name, geometries)) = self.geometry_frames.pop() {
+                    self.push_geometry(multi_geometry(&name, geometries)?);
+                }
+            }
+            _ => {}
+        }
+
+        if self.geometry_depth == Some(depth) {
+            self.geometry_depth = None;
+            self.srs_dimension = None;
+        }
+        Ok(())
+    }
+
+    /// Split position values into coordinates of `dim` values.
+    fn positions(&self, values: &[f64], dim: usize) -> Result<Vec<Coord>> {
+        if !(2..=3).contains(&dim) || values.len() % dim != 0 {
+            return Err(GeoArrowError::General(format!(
+                "Invalid GML positions of dimension {}: {:?}",
+                dim, values
+            )));
+        }
+        Ok(values
+            .chunks_exact(dim)
+            .map(|position| {
+                let (x, y) = if self.swap_xy {
+                    (position[1], position[0])
+                } else {
+                    (position[0], position[1])
+                };
+                Coord {
+                    x,
+                    y,
+                    z: position.get(2).copied(),
+                }
+            })
+            .collect())
+    }
+
+    /// Add a geometry to the enclosing multi geometry, or else to the feature.
+    fn push_geometry(&mut self, geometry: ParsedGeometry) {
+        match self.geometry_frames.last_mut() {
+            Some(GeometryFrame::Multi(_, geometries)) => geometries.push(geometry),
+            _ => {
+                if let Some(feature) = &mut self.feature {
+                    feature.geometry = Some(geometry);
+                }
+            }
+        }
+    }
+}
+
+/// Whether an element is a GML geometry that can be read.
+fn is_geometry(name: &[u8]) -> bool {
+    matches!(
+        name,
+        b"Point"
+            | b"LineString"
+            | b"Curve"
+            | b"LinearRing"
+            | b"Polygon"
+            | b"Surface"
+            | b"Envelope"
+            | b"MultiPoint"
+            | b"MultiCurve"
+            | b"MultiLineString"
+            | b"MultiSurface"
+            | b"MultiPolygon"
+            | b"MultiGeometry"
+    )
+}
+
+/// Combine the members of a multi geometry element.
+fn multi_geometry(name: &[u8], mut geometries: Vec<ParsedGeometry>) -> Result<ParsedGeometry> {
+    let invalid = || {
+        GeoArrowError::General(format!(
+            "Invalid member of GML {}",
+            String::from_utf8_lossy(name)
+        ))
+    };
+    let geometry = match name {
+        b"MultiPoint" => ParsedGeometry::MultiPoint(
+            geometries
+                .into_iter()
+                .map(|geometry| match geometry {
+                    ParsedGeometry::Point(coord) => Ok(coord),
+                    _ => Err(invalid()),
+                })
+                .collect::<Result<_>>()?,
+        ),
+        b"MultiCurve" | b"MultiLineString" => ParsedGeometry::MultiLineString(
+            geometries
+                .into_iter()
+                .map(|geometry| match geometry {
+                    ParsedGeometry::LineString(coords) => Ok(coords),
+                    _ => Err(invalid()),
+                })
+                .collect::<Result<_>>()?,
+        ),
+        b"Surface" if geometries.len() == 1 => geometries.remove(0),
+        b"MultiSurface" | b"MultiPolygon" | b"Surface" => ParsedGeometry::MultiPolygon(
+            geometries
+                .into_iter()
+                .map(|geometry| match geometry {
+                    ParsedGeometry::Polygon(rings) => Ok(vec![rings]),
+                    ParsedGeometry::MultiPolygon(polygons) => Ok(polygons),
+                    _ => Err(invalid()),
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .collect(),
+        ),
+        _ => ParsedGeometry::GeometryCollection(geometries),
+    };
+    Ok(geometry)
+}
+
+/// The narrowest Arrow type that all property values can be read as.
+///
+/// Date-times without an offset are read as UTC.
+fn inferred_type(values: &StringArray) -> DataType {
+    let values = values.iter().flatten().collect::<Vec<_>>();
+    let all = |f: fn(&str) -> bool| values.iter().all(|value| f(value));
+    if values.is_empty() {
+        DataType::Utf8
+    } else if all(|value| value.parse::<i64>().is_ok()) {
+        DataType::Int64
+    } else if all(|value| value.parse::<f64>().is_ok()) {
+        DataType::Float64
+    } else if all(|value| matches!(value, "true" | "false")) {
+        DataType::Boolean
+    } else if all(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()) {
+        DataType::Date32
+    } else if all(|value| {
+        DateTime::parse_from_rfc3339(value).is_ok()
+            || NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").is_ok()
+    }) {
+        DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+    } else {
+        DataType::Utf8
+    }
+}
+
+fn parse_values(text: &str) -> Result<Vec<f64>> {
+    text.split_whitespace()
+        .map(|value| {
+            value
+                .parse::<f64>()
+                .map_err(|_| GeoArrowError::General(format!("Invalid GML position: '{}'", text)))
+        })
+        .collect()
+}
+
+/// The CRS of a GML `srsName`, such as `EPSG:4326`, `urn:ogc:def:crs:EPSG::4326` or
+/// `http://www.opengis.net/def/crs/EPSG/0/4326`.
+fn srs_name_crs(srs_name: &str) -> Crs {
+    if srs_name.to_ascii_uppercase().ends_with("CRS84") {
+        return Crs::from_authority_code("OGC:CRS84".to_string());
+    }
+    let authority_code = if let Some(rest) = srs_name.strip_prefix("urn:ogc:def:crs:") {
+        rest.split_once(':')
+            .map(|(authority, rest)| (authority, rest.rsplit(':').next().unwrap_or_default()))
+    } else if let Some(rest) = srs_name
+        .strip_prefix("http://www.opengis.net/def/crs/")
+        .or_else(|| srs_name.strip_prefix("https://www.opengis.net/def/crs/"))
+    {
+        rest.split_once('/')
+            .map(|(authority, rest)| (authority, rest.rsplit('/').next().unwrap_or_default()))
+    } else if let Some((_, code)) = srs_name.split_once("epsg.xml#") {
+        Some(("EPSG", code))
+    } else {
+        srs_name.split_once(':')
+    };
+    match authority_code {
+        Some((authority, code))
+            if !authority.is_empty()
+                && !code.is_empty()
+                && code.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            Crs::from_authority_code(format!("{}:{}", authority.to_ascii_uppercase(), code))
+        }
+        _ => Crs::from_unknown_crs_type(srs_name.to_string()),
+    }
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use arrow_array::Array;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Date32Type, Float64Type, Int64Type};
+    use geo_traits::{
+        CoordTrait, GeometryTrait, GeometryType, LineStringTrait, MultiPolygonTrait, PolygonTrait,
+    };
+
+    use super::*;
+    use crate::array::NativeArrayDyn;
+    use crate::io::wkb::to_wkb;
+    use crate::table::Table;
+    use crate::trait_::ArrayAccessor;
+
+    pub(in crate::io::gml) const GML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<wfs:FeatureCollection xmlns:wfs="http://www.opengis.net/wfs/2.0"
+    xmlns:gml="http://www.opengis.net/gml/3.2" xmlns:ps="http://example.com/parks"
+    xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <wfs:member>
+    <ps:park gml:id="park.1">
+      <gml:boundedBy><gml:Envelope srsName="EPSG:4326"><gml:lowerCorner>0 0</gml:lowerCorner>
+        <gml:upperCorner>1 1</gml:upperCorner></gml:Envelope></gml:boundedBy>
+      <ps:name>North &amp; South</ps:name>
+      <ps:visitors>1200</ps:visitors>
+      <ps:area>4.5</ps:area>
+      <ps:opened>1999-05-01</ps:opened>
+      <ps:shape>
+        <gml:Polygon gml:id="park.1.geom" srsName="urn:ogc:def:crs:EPSG::3857" srsDimension="2">
+          <gml:exterior><gml:LinearRing><gml:posList>
+            0 0 10 0 10 10 0 10 0 0
+          </gml:posList></gml:LinearRing></gml:exterior>
+          <gml:interior><gml:LinearRing><gml:posList>2 2 2 4 4 4 2 2</gml:posList></gml:LinearRing></gml:interior>
+        </gml:Polygon>
+      </ps:shape>
+    </ps:park>
+  </wfs:member>
+  <wfs:member>
+    <ps:park gml:id="park.2">
+      <ps:name xsi:nil="true"/>
+      <ps:visitors>30</ps:visitors>
+      <ps:area>7</ps:area>
+      <ps:shape>
+        <gml:MultiSurface gml:id="park.2.geom">
+          <gml:surfaceMember><gml:Polygon gml:id="park.2.geom.0"><gml:exterior><gml:LinearRing>
+            <gml:pos>20 20</gml:pos><gml:pos>30 20</gml:pos><gml:pos>30 30</gml:pos><gml:pos>20 20</gml:pos>
+          </gml:LinearRing></gml:exterior></gml:Polygon></gml:surfaceMember>
+          <gml:surfaceMember><gml:Polygon gml:id="park.2.geom.1"><gml:exterior><gml:LinearRing>
+            <gml:posList srsDimension="3">40 40 1 50 40 1 50 50 1 40 40 1</gml:posList>
+          </gml:LinearRing></gml:exterior></gml:Polygon></gml:surfaceMember>
+        </gml:MultiSurface>
+      </ps:shape>
+    </ps:park>
+  </wfs:member>
+</wfs:FeatureCollection>"#;
+
+    #[test]
+    fn read() {
+        let table: Table = read_gml(GML.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table.batches()[0];
+        let schema = batch.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["gml_id", "name", "visitors", "area", "opened", "geometry"]
+        );
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.column(0).as_string::<i32>().value(1), "park.2");
+        assert_eq!(batch.column(1).as_string::<i32>().value(0), "North & South");
+        assert!(batch.column(1).is_null(1));
+        assert_eq!(batch.column(2).as_primitive::<Int64Type>().value(1), 30);
+        assert_eq!(batch.column(3).as_primitive::<Float64Type>().value(1), 7.0);
+        let opened = batch.column(4).as_primitive::<Date32Type>();
+        assert!(opened.is_valid(0) && opened.is_null(1));
+
+        let geometry_type = NativeType::try_from(schema.field(5)).unwrap();
+        assert!(matches!(geometry_type, NativeType::MultiPolygon(_)));
+        assert_eq!(
+            geometry_type.metadata().crs(),
+            &Crs::from_authority_code("EPSG:3857".to_string())
+        );
+
+        let geometry = NativeArrayDyn::from_arrow_array(batch.column(5).as_ref(), schema.field(5))
+            .unwrap()
+            .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry.as_ref());
+        let GeometryType::MultiPolygon(multi_polygon) =
+            wkb_array.value(0).parse().unwrap().as_type()
+        else {
+            panic!("expected a multi polygon");
+        };
+        let polygon = multi_polygon.polygon(0).unwrap();
+        assert_eq!(polygon.exterior().unwrap().num_coords(), 5);
+        assert_eq!(polygon.num_interiors(), 1);
+
+        let GeometryType::MultiPolygon(multi_polygon) =
+            wkb_array.value(1).parse().unwrap().as_type()
+        else {
+            panic!("expected a multi polygon");
+        };
+        assert_eq!(multi_polygon.num_polygons(), 2);
+        let coord = multi_polygon
+            .polygon(1)
+            .unwrap()
+            .exterior()
+            .unwrap()
+            .coord(1)
+            .unwrap();
+        assert_eq!(
+            (coord.x(), coord.y(), coord.nth_or_panic(2)),
+            (50.0, 40.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn gml2() {
+        let input = r#"<ogr:FeatureCollection xmlns:ogr="http://ogr.maptools.org/"
+    xmlns:gml="http://www.opengis.net/gml">
+  <gml:featureMember>
+    <ogr:roads fid="roads.0">
+      <ogr:geometryProperty><gml:LineString srsName="http://www.opengis.net/gml/srs/epsg.xml#4326">
+        <gml:coordinates>45.5,-122.5 45.6,-122.6</gml:coordinates>
+      </gml:LineString></ogr:geometryProperty>
+      <ogr:lanes>2</ogr:lanes>
+    </ogr:roads>
+  </gml:featureMember>
+</ogr:FeatureCollection>"#;
+        let options = GmlReaderOptions {
+            swap_xy: true,
+            ..Default::default()
+        };
+        let table: Table = read_gml(input.as_bytes(), options)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table.batches()[0];
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name(), "lanes");
+        let geometry_type = NativeType::try_from(schema.field(1)).unwrap();
+        assert!(matches!(geometry_type, NativeType::LineString(_)));
+        assert_eq!(
+            geometry_type.metadata().crs(),
+            &Crs::from_authority_code("EPSG:4326".to_string())
+        );
+
+        let geometry = NativeArrayDyn::from_arrow_array(batch.column(1).as_ref(), schema.field(1))
+            .unwrap()
+            .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry.as_ref());
+        let GeometryType::LineString(line_string) = wkb_array.value(0).parse().unwrap().as_type()
+        else {
+            panic!("expected a line string");
+        };
+        let coord = line_string.coord(0).unwrap();
+        assert_eq!((coord.x(), coord.y()), (-122.5, 45.5));
+    }
+
+    const STOPS: &str = r#"<ogr:FeatureCollection xmlns:ogr="http://ogr.maptools.org/"
+    xmlns:gml="http://www.opengis.net/gml">
+  <gml:featureMember>
+    <ogr:stops><ogr:geometryProperty><gml:Point><gml:pos>1 2</gml:pos></gml:Point></ogr:geometryProperty>
+      <ogr:code>7</ogr:code></ogr:stops>
+  </gml:featureMember>
+  <gml:featureMember>
+    <ogr:stops><ogr:geometryProperty><gml:Point><gml:pos>3 4 5</gml:pos></gml:Point></ogr:geometryProperty>
+      <ogr:code>A</ogr:code><ogr:name>Depot</ogr:name></ogr:stops>
+  </gml:featureMember>
+  <gml:featureMember>
+    <ogr:stops><ogr:geometryProperty><gml:LineString><gml:posList>0 0 1 1</gml:posList></gml:LineString></ogr:geometryProperty>
+      <ogr:code>9</ogr:code></ogr:stops>
+  </gml:featureMember>
+</ogr:FeatureCollection>"#;
+
+    #[test]
+    fn batches() {
+        let options = GmlReaderOptions {
+            batch_size: Some(1),
+            ..Default::default()
+        };
+        let table: Table = read_gml(STOPS.as_bytes(), options)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(table.batches().len(), 3);
+        // The schema comes from all features, not only those of the first batch
+        let schema = table.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["code", "name", "geometry"]);
+        assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+        let geometry_type = NativeType::try_from(schema.field(2)).unwrap();
+        assert!(matches!(geometry_type, NativeType::Geometry(_)));
+
+        let codes = table
+            .batches()
+            .iter()
+            .map(|batch| batch.column(0).as_string::<i32>().value(0).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(codes, ["7", "A", "9"]);
+        assert_eq!(
+            table.batches()[1].column(1).as_string::<i32>().value(0),
+            "Depot"
+        );
+    }
+
+    #[test]
+    fn beyond_inference() {
+        // Each later feature differs from the first in one way
+        for (idx, feature) in [
+            "<ogr:code>8</ogr:code><ogr:name>Depot</ogr:name>",
+            r#"<ogr:code>8</ogr:code><ogr:geometryProperty><gml:Point><gml:pos>3 4 5</gml:pos></gml:Point></ogr:geometryProperty>"#,
+            r#"<ogr:code>8</ogr:code><ogr:geometryProperty><gml:LineString><gml:posList>0 0 1 1</gml:posList></gml:LineString></ogr:geometryProperty>"#,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let input = format!(
+                r#"<ogr:FeatureCollection xmlns:ogr="http://ogr.maptools.org/"
+    xmlns:gml="http://www.opengis.net/gml">
+  <gml:featureMember>
+    <ogr:stops><ogr:code>7</ogr:code><ogr:geometryProperty><gml:Point><gml:pos>1 2</gml:pos></gml:Point></ogr:geometryProperty></ogr:stops>
+  </gml:featureMember>
+  <gml:featureMember>
+    <ogr:stops gml:id="stops.{}">{}</ogr:stops>
+  </gml:featureMember>
+</ogr:FeatureCollection>"#,
+                idx, feature
+            );
+            let options = GmlReaderOptions {
+                batch_size: Some(1),
+                infer_schema_length: 1,
+                ..Default::default()
+            };
+            let mut reader = read_gml(std::io::Cursor::new(input), options)
+                .unwrap()
+                .into_inner();
+            assert!(reader.next().unwrap().is_ok());
+            assert!(reader.next().unwrap().is_err());
+        }
+    }
+
+    #[test]
+    fn srs_names() {
+        for (srs_name, code) in [
+            ("EPSG:27700", "EPSG:27700"),
+            ("urn:ogc:def:crs:EPSG:6.6:27700", "EPSG:27700"),
+            ("http://www.opengis.net/def/crs/EPSG/0/27700", "EPSG:27700"),
+            ("urn:ogc:def:crs:OGC:1.3:CRS84", "OGC:CRS84"),
+        ] {
+            assert_eq!(
+                srs_name_crs(srs_name),
+                Crs::from_authority_code(code.to_string())
+            );
+        }
+    }
+}
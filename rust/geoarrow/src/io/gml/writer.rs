@@ -0,0 +1,491 @@
+use std::io::Write;
+
+use arrow_array::{Array, RecordBatch};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait,
+    MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+};
+use geoarrow_schema::{Crs, CrsType};
+use quick_xml::escape::escape;
+use serde_json::Value;
+
+use crate::array::NativeArrayDyn;
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::stream::RecordBatchReader;
+use crate::io::wkb::to_wkb;
+use crate::schema::GeoSchemaExt;
+use crate::trait_::ArrayAccessor;
+
+/// Options for the GML writer.
+#[derive(Debug, Clone)]
+pub struct GmlWriterOptions {
+    /// The namespace prefix of the feature collection, feature and property elements.
+    pub prefix: String,
+
+    /// The namespace URI bound to `prefix`.
+    pub namespace: String,
+
+    /// The local name of each feature element.
+    pub feature_name: String,
+
+    /// The `srsName` of the geometries.
+    ///
+    /// If `None`, the `srsName` is an OGC URN of the geometry column's CRS, if it has an authority
+    /// code.
+    pub srs_name: Option<String>,
+
+    /// Whether to swap the first two values of each position, such as to write latitude first.
+    pub swap_xy: bool,
+}
+
+impl Default for GmlWriterOptions {
+    fn default() -> Self {
+        Self {
+            prefix: "geoarrow".to_string(),
+            namespace: "http://geoarrow.org/gml".to_string(),
+            feature_name: "feature".to_string(),
+            srs_name: None,
+            swap_xy: false,
+        }
+    }
+}
+
+/// Write a table to a GML 3.2 simple features collection.
+///
+/// The table must contain exactly one geometry column. Each row is written as a feature in a
+/// `featureMember`, with the non-null values of the other columns as properties named after the
+/// columns, and the geometry as a `geometry` property. A `gml_id` column is written as the
+/// features' `gml:id`, and otherwise features are numbered.
+///
+/// The prefix, feature name and column names must be valid XML names without a colon, and are
+/// an error otherwise. Ids that aren't valid XML ids, such as numbers, are prefixed with the
+/// feature name.
+///
+/// Multi line strings are written as `MultiCurve`, multi polygons as `MultiSurface` and geometry
+/// collections as `MultiGeometry` elements. Z values are written with `srsDimension="3"`.
+pub fn write_gml<W: Write, S: Into<RecordBatchReader>>(
+    stream: S,
+    mut writer: W,
+    options: GmlWriterOptions,
+) -> Result<()> {
+    let reader = stream.into().into_inner();
+    let schema = reader.schema();
+    let geometry_columns = schema.geometry_columns();
+    let geometry_column_idx = match geometry_columns.as_slice() {
+        [idx] => *idx,
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Writing GML requires exactly one geometry column, found {}",
+                geometry_columns.len()
+            )));
+        }
+    };
+    let srs_name = match &options.srs_name {
+        Some(srs_name) => Some(srs_name.clone()),
+        None => crs_srs_name(
+            NativeType::try_from(schema.field(geometry_column_idx))?
+                .metadata()
+                .crs(),
+        ),
+    };
+    let prefix = options.prefix.as_str();
+    for name in [prefix, options.feature_name.as_str()] {
+        check_name(name)?;
+    }
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<{prefix}:FeatureCollection gml:id="{prefix}.collection" xmlns:{prefix}="{}" xmlns:gml="http://www.opengis.net/gml/3.2">"#,
+        escape(options.namespace.as_str())
+    )?;
+
+    let mut num_features = 0;
+    for batch in reader {
+        let batch = batch?;
+        let geometry_array = NativeArrayDyn::from_arrow_array(
+            batch.column(geometry_column_idx).as_ref(),
+            schema.field(geometry_column_idx),
+        )?
+        .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry_array.as_ref());
+        let properties = PropertyFormatters::try_new(&batch, geometry_column_idx)?;
+
+        let mut feature = vec![];
+        for row in 0..batch.num_rows() {
+            let id = match properties.id(row) {
+                Some(id) => feature_id(id, &options.feature_name)?,
+                None => format!("{}.{}", options.feature_name, num_features),
+            };
+            num_features += 1;
+
+            feature.clear();
+            write!(
+                feature,
+                r#"<{prefix}:featureMember><{prefix}:{} gml:id="{}">"#,
+                options.feature_name,
+                escape(id.as_str())
+            )?;
+            properties.write(&mut feature, prefix, row)?;
+            if let Some(wkb) = wkb_array.get(row) {
+                let geometry = wkb.parse()?;
+                let mut attributes = format!(r#" gml:id="{}.geometry""#, escape(id.as_str()));
+                if let Some(srs_name) = &srs_name {
+                    attributes.push_str(&format!(r#" srsName="{}""#, escape(srs_name.as_str())));
+                }
+                if matches!(geometry.dim(), Dimensions::Xyz | Dimensions::Xyzm) {
+                    attributes.push_str(r#" srsDimension="3""#);
+                }
+                write!(feature, "<{prefix}:geometry>")?;
+                GeometryWriter {
+                    swap_xy: options.swap_xy,
+                }
+                .write(&mut feature, &geometry, &attributes)?;
+                write!(feature, "</{prefix}:geometry>")?;
+            }
+            writeln!(
+                feature,
+                "</{prefix}:{}></{prefix}:featureMember>",
+                options.feature_name
+            )?;
+            writer.write_all(&feature)?;
+        }
+    }
+
+    writeln!(writer, "</{prefix}:FeatureCollection>")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// The OGC URN of a CRS with an authority code.
+fn crs_srs_name(crs: &Crs) -> Option<String> {
+    let (authority, code) = match (crs.crs_type(), crs.crs_value()) {
+        (Some(CrsType::Projjson), Some(Value::Object(projjson))) => {
+            let id = projjson.get("id")?;
+            let code = match id.get("code")? {
+                Value::String(code) => code.clone(),
+                code => code.to_string(),
+            };
+            (id.get("authority")?.as_str()?.to_string(), code)
+        }
+        (Some(CrsType::Srid), Some(Value::String(value))) => ("EPSG".to_string(), value.clone()),
+        (_, Some(Value::String(value))) if value.eq_ignore_ascii_case("OGC:CRS84") => {
+            return Some("urn:ogc:def:crs:OGC:1.3:CRS84".to_string());
+        }
+        (_, Some(Value::String(value))) => {
+            let (authority, code) = value.split_once(':')?;
+            (authority.to_string(), code.to_string())
+        }
+        _ => return None,
+    };
+    Some(format!("urn:ogc:def:crs:{}::{}", authority, code))
+}
+
+/// Whether a name is an XML name without a colon, as required of element names without their
+/// prefix and of ids.
+fn is_ncname(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+fn check_name(name: &str) -> Result<()> {
+    if is_ncname(name) {
+        Ok(())
+    } else {
+        Err(GeoArrowError::General(format!(
+            "'{}' is not a valid GML element name",
+            name
+        )))
+    }
+}
+
+/// The `gml:id` of a feature, prefixed with the feature name if the id isn't a valid XML id.
+fn feature_id(id: String, feature_name: &str) -> Result<String> {
+    if is_ncname(&id) {
+        return Ok(id);
+    }
+    let prefixed = format!("{}.{}", feature_name, id);
+    if is_ncname(&prefixed) {
+        Ok(prefixed)
+    } else {
+        Err(GeoArrowError::General(format!(
+            "'{}' is not a valid GML id",
+            id
+        )))
+    }
+}
+
+/// Formats the non-geometry columns of a batch as feature properties.
+struct PropertyFormatters<'a> {
+    id: Option<(&'a dyn Array, ArrayFormatter<'a>)>,
+    properties: Vec<(&'a str, &'a dyn Array, ArrayFormatter<'a>)>,
+}
+
+impl<'a> PropertyFormatters<'a> {
+    fn try_new(batch: &'a RecordBatch, geometry_column_idx: usize) -> Result<Self> {
+        let mut formatters = Self {
+            id: None,
+            properties: vec![],
+        };
+        for (idx, field) in batch.schema_ref().fields().iter().enumerate() {
+            if idx == geometry_column_idx {
+                continue;
+            }
+            let column = batch.column(idx).as_ref();
+            let formatter = ArrayFormatter::try_new(column, &FormatOptions::default())?;
+            match field.name().as_str() {
+                "gml_id" => formatters.id = Some((column, formatter)),
+                name => {
+                    check_name(name)?;
+                    formatters.properties.push((name, column, formatter))
+                }
+            }
+        }
+        Ok(formatters)
+    }
+
+    fn id(&self, row: usize) -> Option<String> {
+        let (column, formatter) = self.id.as_ref()?;
+        column
+            .is_valid(row)
+            .then(|| formatter.value(row).to_string())
+    }
+
+    fn write(&self, out: &mut Vec<u8>, prefix: &str, row: usize) -> Result<()> {
+        for (name, column, formatter) in &self.properties {
+            if column.is_valid(row) {
+                let value = formatter.value(row).to_string();
+                write!(
+                    out,
+                    "<{prefix}:{name}>{}</{prefix}:{name}>",
+                    escape(value.as_str())
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes geometries as GML 3.2 elements.
+struct GeometryWriter {
+    swap_xy: bool,
+}
+
+impl GeometryWriter {
+    /// Write a geometry element, with `attributes` on its outermost element.
+    fn write(
+        &self,
+        out: &mut Vec<u8>,
+        geometry: &impl GeometryTrait<T = f64>,
+        attributes: &str,
+    ) -> Result<()> {
+        match geometry.as_type() {
+            GeometryType::Point(point) => self.write_point(out, &point, attributes)?,
+            GeometryType::LineString(line_string) => {
+                self.write_line_string(out, &line_string, attributes)?
+            }
+            GeometryType::Polygon(polygon) => self.write_polygon(out, &polygon, attributes)?,
+            GeometryType::MultiPoint(multi_point) => {
+                write!(out, "<gml:MultiPoint{attributes}>")?;
+                for point in multi_point.points() {
+                    out.extend_from_slice(b"<gml:pointMember>");
+                    self.write_point(out, &point, "")?;
+                    out.extend_from_slice(b"</gml:pointMember>");
+                }
+                out.extend_from_slice(b"</gml:MultiPoint>");
+            }
+            GeometryType::MultiLineString(multi_line_string) => {
+                write!(out, "<gml:MultiCurve{attributes}>")?;
+                for line_string in multi_line_string.line_strings() {
+                    out.extend_from_slice(b"<gml:curveMember>");
+                    self.write_line_string(out, &line_string, "")?;
+                    out.extend_from_slice(b"</gml:curveMember>");
+                }
+                out.extend_from_slice(b"</gml:MultiCurve>");
+            }
+            GeometryType::MultiPolygon(multi_polygon) => {
+                write!(out, "<gml:MultiSurface{attributes}>")?;
+                for polygon in multi_polygon.polygons() {
+                    out.extend_from_slice(b"<gml:surfaceMember>");
+                    self.write_polygon(out, &polygon, "")?;
+                    out.extend_from_slice(b"</gml:surfaceMember>");
+                }
+                out.extend_from_slice(b"</gml:MultiSurface>");
+            }
+            GeometryType::GeometryCollection(collection) => {
+                write!(out, "<gml:MultiGeometry{attributes}>")?;
+                for geometry in collection.geometries() {
+                    out.extend_from_slice(b"<gml:geometryMember>");
+                    self.write(out, &geometry, "")?;
+                    out.extend_from_slice(b"</gml:geometryMember>");
+                }
+                out.extend_from_slice(b"</gml:MultiGeometry>");
+            }
+            _ => {
+                return Err(GeoArrowError::General(
+                    "GML simple features can't represent rect, triangle or line geometries"
+                        .to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn write_point(
+        &self,
+        out: &mut Vec<u8>,
+        point: &impl PointTrait<T = f64>,
+        attributes: &str,
+    ) -> Result<()> {
+        match point.coord() {
+            Some(coord) => {
+                write!(out, "<gml:Point{attributes}><gml:pos>")?;
+                self.write_positions(out, [coord])?;
+                out.extend_from_slice(b"</gml:pos></gml:Point>");
+            }
+            None => write!(out, "<gml:Point{attributes}/>")?,
+        }
+        Ok(())
+    }
+
+    fn write_line_string(
+        &self,
+        out: &mut Vec<u8>,
+        line_string: &impl LineStringTrait<T = f64>,
+        attributes: &str,
+    ) -> Result<()> {
+        write!(out, "<gml:LineString{attributes}><gml:posList>")?;
+        self.write_positions(out, line_string.coords())?;
+        out.extend_from_slice(b"</gml:posList></gml:LineString>");
+        Ok(())
+    }
+
+    fn write_polygon(
+        &self,
+        out: &mut Vec<u8>,
+        polygon: &impl PolygonTrait<T = f64>,
+        attributes: &str,
+    ) -> Result<()> {
+        write!(out, "<gml:Polygon{attributes}>")?;
+        if let Some(exterior) = polygon.exterior() {
+            out.extend_from_slice(b"<gml:exterior><gml:LinearRing><gml:posList>");
+            self.write_positions(out, exterior.coords())?;
+            out.extend_from_slice(b"</gml:posList></gml:LinearRing></gml:exterior>");
+        }
+        for interior in polygon.interiors() {
+            out.extend_from_slice(b"<gml:interior><gml:LinearRing><gml:posList>");
+            self.write_positions(out, interior.coords())?;
+            out.extend_from_slice(b"</gml:posList></gml:LinearRing></gml:interior>");
+        }
+        out.extend_from_slice(b"</gml:Polygon>");
+        Ok(())
+    }
+
+    /// Write space-separated position values, with a Z value for 3D coordinates.
+    fn write_positions<C: CoordTrait<T = f64>>(
+        &self,
+        out: &mut Vec<u8>,
+        coords: impl IntoIterator<Item = C>,
+    ) -> Result<()> {
+        for (idx, coord) in coords.into_iter().enumerate() {
+            if idx > 0 {
+                out.push(b' ');
+            }
+            if self.swap_xy {
+                write!(out, "{} {}", coord.y(), coord.x())?;
+            } else {
+                write!(out, "{} {}", coord.x(), coord.y())?;
+            }
+            if matches!(coord.dim(), Dimensions::Xyz | Dimensions::Xyzm) {
+                write!(out, " {}", coord.nth_or_panic(2))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    use arrow_schema::Schema;
+
+    use super::*;
+    use crate::io::gml::read_gml;
+    use crate::io::gml::reader::test::GML;
+    use crate::table::Table;
+    use crate::test::point;
+
+    #[test]
+    fn round_trip() {
+        let table: Table = read_gml(GML.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let mut output = vec![];
+        write_gml(&table, &mut output, Default::default()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(
+            r#"<geoarrow:feature gml:id="park.1"><geoarrow:name>North &amp; South</geoarrow:name>"#
+        ));
+        assert!(output.contains(r#"srsName="urn:ogc:def:crs:EPSG::3857" srsDimension="3""#));
+
+        let table2: Table = read_gml(Cursor::new(output), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let (batch, batch2) = (&table.batches()[0], &table2.batches()[0]);
+        assert_eq!(batch.schema(), batch2.schema());
+        // The geometries hold NaN Z values, which aren't equal
+        assert_eq!(batch.columns()[..5], batch2.columns()[..5]);
+    }
+
+    #[test]
+    fn points() {
+        let options = GmlWriterOptions {
+            srs_name: Some("urn:ogc:def:crs:EPSG::4326".to_string()),
+            swap_xy: true,
+            ..Default::default()
+        };
+        let mut output = vec![];
+        write_gml(&point::table(), &mut output, options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(
+            r#"<geoarrow:geometry><gml:Point gml:id="feature.1.geometry" srsName="urn:ogc:def:crs:EPSG::4326"><gml:pos>2 1</gml:pos></gml:Point></geoarrow:geometry>"#
+        ));
+    }
+
+    fn rename_column(table: &Table, idx: usize, name: &str) -> Table {
+        let mut fields = table.schema().fields().to_vec();
+        fields[idx] = Arc::new(fields[idx].as_ref().clone().with_name(name));
+        let schema = Arc::new(Schema::new(fields));
+        let batches = table
+            .batches()
+            .iter()
+            .map(|batch| batch.clone().with_schema(schema.clone()).unwrap())
+            .collect();
+        Table::try_new(batches, schema).unwrap()
+    }
+
+    #[test]
+    fn names() {
+        let mut output = vec![];
+        let table = rename_column(&point::table(), 1, "my col");
+        assert!(write_gml(&table, &mut output, Default::default()).is_err());
+        let options = GmlWriterOptions {
+            prefix: "a<b".to_string(),
+            ..Default::default()
+        };
+        assert!(write_gml(&point::table(), &mut output, options).is_err());
+
+        let table = rename_column(&point::table(), 0, "gml_id");
+        let mut output = vec![];
+        write_gml(&table, &mut output, Default::default()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(r#"<geoarrow:feature gml:id="feature.1">"#));
+    }
+}
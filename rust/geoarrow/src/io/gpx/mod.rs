@@ -0,0 +1,7 @@
+//! Read from and write to [GPX](https://www.topografix.com/gpx.asp) files.
+
+mod reader;
+mod writer;
+
+pub use reader::{GpxLayers, GpxReaderOptions, read_gpx};
+pub use writer::{GpxWriterOptions, write_gpx};
@@ -0,0 +1,446 @@
+use std::io::BufRead;
+use std::sync::Arc;
+
+use arrow_array::builder::{ListBuilder, StringBuilder, TimestampMillisecondBuilder};
+use arrow_array::{ArrayRef, RecordBatch, TimestampMillisecondArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, NaiveDateTime};
+use geoarrow_schema::{
+    CoordType, Crs, Dimension, LineStringType, Metadata, MultiLineStringType, PointType,
+};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::parsed::{Coord, ParsedGeometry, geometry_array};
+use crate::io::stream::{RecordBatchReader, record_batches};
+use crate::io::xml::attribute;
+
+/// Options for the GPX reader.
+#[derive(Debug, Clone)]
+pub struct GpxReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+}
+
+impl Default for GpxReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+        }
+    }
+}
+
+/// The waypoints, routes and tracks of a GPX file.
+///
+/// Geometries have a Z dimension holding each point's elevation, which is NaN for points
+/// without one, and a WGS84 CRS.
+pub struct GpxLayers {
+    /// The `wpt` elements, with `name`, `comment`, `description`, `symbol` and `time` columns and
+    /// a `Point` geometry column.
+    pub waypoints: RecordBatchReader,
+
+    /// The `rte` elements, with `name` and `description` columns and a `LineString` geometry
+    /// column.
+    pub routes: RecordBatchReader,
+
+    /// The `trk` elements, with `name` and `description` columns, a `MultiLineString` geometry
+    /// column with one line string per track segment, and a `time` list column holding the time
+    /// of each point of the track, in order.
+    pub tracks: RecordBatchReader,
+}
+
+/// Read a GPX file.
+pub fn read_gpx<R: BufRead>(reader: R, options: GpxReaderOptions) -> Result<GpxLayers> {
+    let mut parser = GpxParser::default();
+    let mut reader = Reader::from_reader(reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = vec![];
+    let mut path: Vec<Vec<u8>> = vec![];
+    let mut text = String::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(element) => {
+                let name = element.local_name().as_ref().to_vec();
+                parser.start(
+                    &name,
+                    &attribute(&element, "lon")?,
+                    &attribute(&element, "lat")?,
+                )?;
+                path.push(name);
+                text.clear();
+            }
+            Event::Empty(element) => {
+                let name = element.local_name().as_ref().to_vec();
+                parser.start(
+                    &name,
+                    &attribute(&element, "lon")?,
+                    &attribute(&element, "lat")?,
+                )?;
+                parser.end(&name, path.last().map(Vec::as_slice), "")?;
+            }
+            Event::Text(value) => text.push_str(&value.unescape()?),
+            Event::CData(value) => text.push_str(&String::from_utf8_lossy(&value)),
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                parser.end(&name, path.last().map(Vec::as_slice), text.trim())?;
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let batch_size = options.batch_size.unwrap_or(65_536);
+    let metadata = Arc::new(Metadata::new(
+        Crs::from_authority_code("OGC:CRS84".to_string()),
+        None,
+    ));
+    Ok(GpxLayers {
+        waypoints: record_batches(
+            parser.waypoints_batch(options.coord_type, metadata.clone())?,
+            batch_size,
+        ),
+        routes: record_batches(
+            parser.routes_batch(options.coord_type, metadata.clone())?,
+            batch_size,
+        ),
+        tracks: record_batches(
+            parser.tracks_batch(options.coord_type, metadata)?,
+            batch_size,
+        ),
+    })
+}
+
+#[derive(Debug, Default)]
+struct Waypoint {
+    coord: Option<Coord>,
+    name: Option<String>,
+    comment: Option<String>,
+    description: Option<String>,
+    symbol: Option<String>,
+    time: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct Route {
+    name: Option<String>,
+    description: Option<String>,
+    points: Vec<Coord>,
+}
+
+#[derive(Debug, Default)]
+struct Track {
+    name: Option<String>,
+    description: Option<String>,
+    segments: Vec<Vec<Coord>>,
+    times: Vec<Option<i64>>,
+}
+
+/// Collects the waypoints, routes and tracks of a GPX document from its elements.
+#[derive(Debug, Default)]
+struct GpxParser {
+    waypoints: Vec<Waypoint>,
+    routes: Vec<Route>,
+    tracks: Vec<Track>,
+    /// The `wpt`, `rtept` or `trkpt` element being read.
+    point: Option<Waypoint>,
+}
+
+impl GpxParser {
+    fn start(&mut self, name: &[u8], lon: &Option<String>, lat: &Option<String>) -> Result<()> {
+        match name {
+            b"wpt" | b"rtept" | b"trkpt" => {
+                let (Some(lon), Some(lat)) = (lon, lat) else {
+                    return Err(GeoArrowError::General(
+                        "GPX point is missing its lat or lon attribute".to_string(),
+                    ));
+                };
+                self.point = Some(Waypoint {
+                    coord: Some(Coord {
+                        x: parse_number(lon)?,
+                        y: parse_number(lat)?,
+                        z: None,
+                    }),
+                    ..Default::default()
+                });
+            }
+            b"rte" => self.routes.push(Route::default()),
+            b"trk" => self.tracks.push(Track::default()),
+            b"trkseg" => {
+                if let Some(track) = self.tracks.last_mut() {
+                    track.segments.push(vec![]);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, name: &[u8], parent: Option<&[u8]>, text: &str) -> Result<()> {
+        let text = (!text.is_empty()).then(|| text.to_string());
+        if let (Some(b"wpt" | b"rtept" | b"trkpt"), Some(point)) = (parent, &mut self.point) {
+            match name {
+                b"ele" => {
+                    if let (Some(coord), Some(text)) = (&mut point.coord, &text) {
+                        coord.z = Some(parse_number(text)?);
+                    }
+                }
+                b"time" => point.time = text.as_deref().map(parse_time).transpose()?,
+                b"name" => point.name = text,
+                b"cmt" => point.comment = text,
+                b"desc" => point.description = text,
+                b"sym" => point.symbol = text,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match name {
+            b"name" | b"desc" => {
+                let (name_field, description_field) = match parent {
+                    Some(b"rte") => match self.routes.last_mut() {
+                        Some(route) => (&mut route.name, &mut route.description),
+                        None => return Ok(()),
+                    },
+                    Some(b"trk") => match self.tracks.last_mut() {
+                        Some(track) => (&mut track.name, &mut track.description),
+                        None => return Ok(()),
+                    },
+                    _ => return Ok(()),
+                };
+                if name == b"name" {
+                    *name_field = text;
+                } else {
+                    *description_field = text;
+                }
+            }
+            b"wpt" => self.waypoints.extend(self.point.take()),
+            b"rtept" => {
+                let coord = self.point.take().and_then(|point| point.coord);
+                if let (Some(route), Some(coord)) = (self.routes.last_mut(), coord) {
+                    route.points.push(coord);
+                }
+            }
+            b"trkpt" => {
+                let point = self.point.take();
+                let track = self.tracks.last_mut();
+                if let (Some(track), Some(point)) = (track, point) {
+                    // Points outside of a track segment are ignored
+                    if let (Some(segment), Some(coord)) = (track.segments.last_mut(), point.coord) {
+                        segment.push(coord);
+                        track.times.push(point.time);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn waypoints_batch(
+        &self,
+        coord_type: CoordType,
+        metadata: Arc<Metadata>,
+    ) -> Result<RecordBatch> {
+        let geometry_type = NativeType::Point(PointType::new(coord_type, Dimension::XYZ, metadata));
+        let geometries = self
+            .waypoints
+            .iter()
+            .map(|waypoint| waypoint.coord.map(ParsedGeometry::Point))
+            .collect::<Vec<_>>();
+        let columns: Vec<ArrayRef> = vec![
+            string_array(self.waypoints.iter().map(|waypoint| &waypoint.name)),
+            string_array(self.waypoints.iter().map(|waypoint| &waypoint.comment)),
+            string_array(self.waypoints.iter().map(|waypoint| &waypoint.description)),
+            string_array(self.waypoints.iter().map(|waypoint| &waypoint.symbol)),
+            Arc::new(
+                TimestampMillisecondArray::from_iter(
+                    self.waypoints.iter().map(|waypoint| waypoint.time),
+                )
+                .with_timezone("UTC"),
+            ),
+            geometry_array(&geometries, geometry_type.clone())?,
+        ];
+        let schema = Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("comment", DataType::Utf8, true),
+            Field::new("description", DataType::Utf8, true),
+            Field::new("symbol", DataType::Utf8, true),
+            Field::new("time", time_type(), true),
+            geometry_type.to_field("geometry", true),
+        ]);
+        Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+    }
+
+    fn routes_batch(&self, coord_type: CoordType, metadata: Arc<Metadata>) -> Result<RecordBatch> {
+        let geometry_type =
+            NativeType::LineString(LineStringType::new(coord_type, Dimension::XYZ, metadata));
+        let geometries = self
+            .routes
+            .iter()
+            .map(|route| Some(ParsedGeometry::LineString(route.points.clone())))
+            .collect::<Vec<_>>();
+        let columns: Vec<ArrayRef> = vec![
+            string_array(self.routes.iter().map(|route| &route.name)),
+            string_array(self.routes.iter().map(|route| &route.description)),
+            geometry_array(&geometries, geometry_type.clone())?,
+        ];
+        let schema = Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("description", DataType::Utf8, true),
+            geometry_type.to_field("geometry", true),
+        ]);
+        Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+    }
+
+    fn tracks_batch(&self, coord_type: CoordType, metadata: Arc<Metadata>) -> Result<RecordBatch> {
+        let geometry_type = NativeType::MultiLineString(MultiLineStringType::new(
+            coord_type,
+            Dimension::XYZ,
+            metadata,
+        ));
+        let geometries = self
+            .tracks
+            .iter()
+            .map(|track| Some(ParsedGeometry::MultiLineString(track.segments.clone())))
+            .collect::<Vec<_>>();
+        let mut times = ListBuilder::new(TimestampMillisecondBuilder::new().with_timezone("UTC"))
+            .with_field(Field::new("item", time_type(), true));
+        for track in &self.tracks {
+            times.values().extend(track.times.iter().copied());
+            times.append(true);
+        }
+        let columns: Vec<ArrayRef> = vec![
+            string_array(self.tracks.iter().map(|track| &track.name)),
+            string_array(self.tracks.iter().map(|track| &track.description)),
+            Arc::new(times.finish()),
+            geometry_array(&geometries, geometry_type.clone())?,
+        ];
+        let schema: SchemaRef = Arc::new(Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("description", DataType::Utf8, true),
+            Field::new_list("time", Field::new("item", time_type(), true), true),
+            geometry_type.to_field("geometry", true),
+        ]));
+        Ok(RecordBatch::try_new(schema, columns)?)
+    }
+}
+
+fn time_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+fn string_array<'a>(values: impl Iterator<Item = &'a Option<String>>) -> ArrayRef {
+    let mut builder = StringBuilder::new();
+    for value in values {
+        builder.append_option(value.as_deref());
+    }
+    Arc::new(builder.finish())
+}
+
+fn parse_number(value: &str) -> Result<f64> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| GeoArrowError::General(format!("Invalid GPX number: '{}'", value)))
+}
+
+/// Parse an XML Schema `dateTime` to milliseconds since the epoch, assuming UTC if it has no
+/// time zone.
+fn parse_time(value: &str) -> Result<i64> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp_millis());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .map(|time| time.and_utc().timestamp_millis())
+        .map_err(|_| GeoArrowError::General(format!("Invalid GPX time: '{}'", value)))
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::Array;
+    use arrow_array::cast::AsArray;
+
+    use super::*;
+    use crate::table::Table;
+
+    const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <metadata><name>Field trip</name></metadata>
+  <wpt lat="46.5" lon="7.25">
+    <ele>1500.5</ele>
+    <time>2024-06-01T08:00:00Z</time>
+    <name>Camp &amp; hut</name>
+    <sym>Flag</sym>
+  </wpt>
+  <wpt lat="46.6" lon="7.3"/>
+  <rte>
+    <name>Approach</name>
+    <rtept lat="46.5" lon="7.25"/>
+    <rtept lat="46.55" lon="7.27"/>
+  </rte>
+  <trk>
+    <name>Day 1</name>
+    <trkseg>
+      <trkpt lat="46.5" lon="7.25"><ele>1500</ele><time>2024-06-01T08:00:00Z</time></trkpt>
+      <trkpt lat="46.51" lon="7.26"><ele>1520</ele><time>2024-06-01T08:05:00Z</time></trkpt>
+    </trkseg>
+    <trkseg>
+      <trkpt lat="46.52" lon="7.27"><ele>1550</ele></trkpt>
+    </trkseg>
+  </trk>
+</gpx>"#;
+
+    #[test]
+    fn read() {
+        let layers = read_gpx(GPX.as_bytes(), Default::default()).unwrap();
+
+        let waypoints: Table = layers.waypoints.try_into().unwrap();
+        let batch = &waypoints.batches()[0];
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.column(0).as_string::<i32>().value(0), "Camp & hut");
+        assert_eq!(batch.column(3).as_string::<i32>().value(0), "Flag");
+        assert!(batch.column(4).is_null(1));
+
+        let routes: Table = layers.routes.try_into().unwrap();
+        assert_eq!(routes.batches()[0].num_rows(), 1);
+
+        let tracks: Table = layers.tracks.try_into().unwrap();
+        let batch = &tracks.batches()[0];
+        assert_eq!(batch.column(0).as_string::<i32>().value(0), "Day 1");
+        let times = batch.column(2).as_list::<i32>().value(0);
+        assert_eq!(times.len(), 3);
+        assert_eq!(times.null_count(), 1);
+        assert_eq!(
+            NativeType::try_from(batch.schema().field(3).as_ref()).unwrap(),
+            NativeType::MultiLineString(MultiLineStringType::new(
+                CoordType::Interleaved,
+                Dimension::XYZ,
+                Default::default(),
+            ))
+            .with_metadata(Arc::new(Metadata::new(
+                Crs::from_authority_code("OGC:CRS84".to_string()),
+                None,
+            )))
+        );
+    }
+
+    #[test]
+    fn batch_size() {
+        let options = GpxReaderOptions {
+            batch_size: Some(1),
+            ..Default::default()
+        };
+        let layers = read_gpx(GPX.as_bytes(), options).unwrap();
+        let waypoints: Table = layers.waypoints.try_into().unwrap();
+        assert_eq!(waypoints.batches().len(), 2);
+    }
+}
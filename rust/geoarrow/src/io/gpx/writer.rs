@@ -0,0 +1,326 @@
+use std::io::Write;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::TimestampMillisecondType;
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, TimeUnit};
+use chrono::{DateTime, SecondsFormat};
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryTrait, GeometryType, LineStringTrait, MultiLineStringTrait,
+    PointTrait,
+};
+use quick_xml::escape::escape;
+
+use crate::array::NativeArrayDyn;
+use crate::error::{GeoArrowError, Result};
+use crate::io::stream::RecordBatchReader;
+use crate::io::wkb::to_wkb;
+use crate::schema::GeoSchemaExt;
+use crate::trait_::ArrayAccessor;
+
+/// Options for the GPX writer.
+#[derive(Debug, Clone)]
+pub struct GpxWriterOptions {
+    /// The `creator` attribute of the `gpx` element.
+    pub creator: String,
+}
+
+impl Default for GpxWriterOptions {
+    fn default() -> Self {
+        Self {
+            creator: "geoarrow".to_string(),
+        }
+    }
+}
+
+/// Write a table to a GPX file.
+///
+/// The table must contain exactly one geometry column. Points are written as waypoints, line
+/// strings as routes, and multi line strings as tracks with one segment per line string. Other
+/// geometry types are an error, and rows with null geometries are skipped. Z values are written
+/// as elevations.
+///
+/// Columns named `name`, `comment`, `description` and `symbol` are written as the matching GPX
+/// elements. A timestamp `time` column is written as the time of each waypoint, and a list `time`
+/// column as the time of each point of a track. Other columns are not written.
+pub fn write_gpx<W: Write, S: Into<RecordBatchReader>>(
+    stream: S,
+    mut writer: W,
+    options: GpxWriterOptions,
+) -> Result<()> {
+    let reader = stream.into().into_inner();
+    let schema = reader.schema();
+    let geometry_columns = schema.geometry_columns();
+    let geometry_column_idx = match geometry_columns.as_slice() {
+        [idx] => *idx,
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Writing GPX requires exactly one geometry column, found {}",
+                geometry_columns.len()
+            )));
+        }
+    };
+
+    // GPX requires waypoints, then routes, then tracks
+    let mut waypoints = vec![];
+    let mut routes = vec![];
+    let mut tracks = vec![];
+    for batch in reader {
+        let batch = batch?;
+        let columns = GpxColumns::try_new(&batch)?;
+        let geometry_array = NativeArrayDyn::from_arrow_array(
+            batch.column(geometry_column_idx).as_ref(),
+            schema.field(geometry_column_idx),
+        )?
+        .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry_array.as_ref());
+
+        for row in 0..batch.num_rows() {
+            let Some(wkb) = wkb_array.get(row) else {
+                continue;
+            };
+            let geometry = wkb.parse()?;
+            match geometry.as_type() {
+                GeometryType::Point(point) => {
+                    let Some(coord) = point.coord() else {
+                        continue;
+                    };
+                    write_point(
+                        &mut waypoints,
+                        "wpt",
+                        &coord,
+                        columns.point_time(row),
+                        &columns.texts(row),
+                    )?;
+                    waypoints.push(b'\n');
+                }
+                GeometryType::LineString(line_string) => {
+                    routes.extend_from_slice(b"<rte>");
+                    write_texts(&mut routes, &columns.texts(row)[..3])?;
+                    for coord in line_string.coords() {
+                        routes.extend_from_slice(b"\n  ");
+                        write_point(&mut routes, "rtept", &coord, None, &[])?;
+                    }
+                    routes.extend_from_slice(b"\n</rte>\n");
+                }
+                GeometryType::MultiLineString(multi_line_string) => {
+                    let times = columns.track_times(row);
+                    let mut times = times.iter().copied();
+                    tracks.extend_from_slice(b"<trk>");
+                    write_texts(&mut tracks, &columns.texts(row)[..3])?;
+                    for line_string in multi_line_string.line_strings() {
+                        tracks.extend_from_slice(b"\n  <trkseg>");
+                        for coord in line_string.coords() {
+                            tracks.extend_from_slice(b"\n    ");
+                            write_point(&mut tracks, "trkpt", &coord, times.next().flatten(), &[])?;
+                        }
+                        tracks.extend_from_slice(b"\n  </trkseg>");
+                    }
+                    tracks.extend_from_slice(b"\n</trk>\n");
+                }
+                _ => {
+                    return Err(GeoArrowError::General(
+                        "GPX can only represent point, line string and multi line string geometries"
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<gpx version="1.1" creator="{}" xmlns="http://www.topografix.com/GPX/1/1">"#,
+        escape(options.creator.as_str())
+    )?;
+    writer.write_all(&waypoints)?;
+    writer.write_all(&routes)?;
+    writer.write_all(&tracks)?;
+    writeln!(writer, "</gpx>")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// The non-geometry columns written to GPX.
+struct GpxColumns {
+    name: Option<ArrayRef>,
+    comment: Option<ArrayRef>,
+    description: Option<ArrayRef>,
+    symbol: Option<ArrayRef>,
+    time: Option<ArrayRef>,
+}
+
+impl GpxColumns {
+    fn try_new(batch: &RecordBatch) -> Result<Self> {
+        let string_column = |name: &str| -> Result<Option<ArrayRef>> {
+            batch
+                .column_by_name(name)
+                .map(|column| Ok(arrow_cast::cast(column, &DataType::Utf8)?))
+                .transpose()
+        };
+        let time_type = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+        let time = match batch.column_by_name("time") {
+            Some(column) => match column.data_type() {
+                DataType::List(_) | DataType::LargeList(_) => {
+                    let list_type = DataType::new_list(time_type, true);
+                    Some(arrow_cast::cast(column, &list_type)?)
+                }
+                DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {
+                    Some(arrow_cast::cast(column, &time_type)?)
+                }
+                _ => None,
+            },
+            None => None,
+        };
+        Ok(Self {
+            name: string_column("name")?,
+            comment: string_column("comment")?,
+            description: string_column("description")?,
+            symbol: string_column("symbol")?,
+            time,
+        })
+    }
+
+    /// The `name`, `cmt`, `desc` and `sym` elements of a row, in the order GPX requires.
+    fn texts(&self, row: usize) -> [(&'static str, Option<&str>); 4] {
+        let value = |column: &Option<ArrayRef>| {
+            column
+                .as_ref()
+                .filter(|column| column.is_valid(row))
+                .map(|column| column.as_string::<i32>().value(row))
+        };
+        [
+            ("name", value(&self.name)),
+            ("cmt", value(&self.comment)),
+            ("desc", value(&self.description)),
+            ("sym", value(&self.symbol)),
+        ]
+    }
+
+    fn point_time(&self, row: usize) -> Option<i64> {
+        let time = self.time.as_ref()?;
+        let time = time.as_primitive_opt::<TimestampMillisecondType>()?;
+        time.is_valid(row).then(|| time.value(row))
+    }
+
+    fn track_times(&self, row: usize) -> Vec<Option<i64>> {
+        let Some(list) = self
+            .time
+            .as_ref()
+            .and_then(|time| time.as_list_opt::<i32>())
+        else {
+            return vec![];
+        };
+        if list.is_null(row) {
+            return vec![];
+        }
+        list.value(row)
+            .as_primitive::<TimestampMillisecondType>()
+            .iter()
+            .collect()
+    }
+}
+
+fn write_point(
+    out: &mut Vec<u8>,
+    element: &str,
+    coord: &impl CoordTrait<T = f64>,
+    time: Option<i64>,
+    texts: &[(&str, Option<&str>)],
+) -> Result<()> {
+    write!(
+        out,
+        r#"<{element} lat="{}" lon="{}">"#,
+        coord.y(),
+        coord.x()
+    )?;
+    if matches!(coord.dim(), Dimensions::Xyz | Dimensions::Xyzm) {
+        let elevation = coord.nth_or_panic(2);
+        if !elevation.is_nan() {
+            write!(out, "<ele>{elevation}</ele>")?;
+        }
+    }
+    if let Some(time) = time.and_then(DateTime::from_timestamp_millis) {
+        write!(
+            out,
+            "<time>{}</time>",
+            time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+        )?;
+    }
+    write_texts(out, texts)?;
+    write!(out, "</{element}>")?;
+    Ok(())
+}
+
+fn write_texts(out: &mut Vec<u8>, texts: &[(&str, Option<&str>)]) -> Result<()> {
+    for (element, value) in texts {
+        if let Some(value) = value {
+            write!(out, "<{element}>{}</{element}>", escape(*value))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow_array::cast::AsArray;
+    use arrow_schema::Schema;
+
+    use super::*;
+    use crate::ArrayBase;
+    use crate::io::gpx::read_gpx;
+    use crate::table::Table;
+    use crate::test::polygon;
+
+    const GPX: &str = r#"<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <wpt lat="46.5" lon="7.25"><ele>1500.5</ele><name>Camp &lt;1&gt;</name></wpt>
+  <trk>
+    <name>Day 1</name>
+    <trkseg>
+      <trkpt lat="46.5" lon="7.25"><ele>1500</ele><time>2024-06-01T08:00:00Z</time></trkpt>
+      <trkpt lat="46.51" lon="7.26"><ele>1520</ele><time>2024-06-01T08:05:00.5Z</time></trkpt>
+    </trkseg>
+  </trk>
+</gpx>"#;
+
+    fn round_trip(table: &Table) -> String {
+        let mut output = vec![];
+        write_gpx(table, &mut output, Default::default()).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn write() {
+        let layers = read_gpx(GPX.as_bytes(), Default::default()).unwrap();
+        let waypoints: Table = layers.waypoints.try_into().unwrap();
+        let tracks: Table = layers.tracks.try_into().unwrap();
+
+        let output = round_trip(&waypoints);
+        assert!(output.contains(
+            r#"<wpt lat="46.5" lon="7.25"><ele>1500.5</ele><name>Camp &lt;1&gt;</name></wpt>"#
+        ));
+
+        let output = round_trip(&tracks);
+        assert!(output.contains("<time>2024-06-01T08:05:00.500Z</time>"));
+        let layers = read_gpx(output.as_bytes(), Default::default()).unwrap();
+        let tracks2: Table = layers.tracks.try_into().unwrap();
+        assert_eq!(tracks.batches(), tracks2.batches());
+        assert_eq!(
+            tracks2.batches()[0].column(0).as_string::<i32>().value(0),
+            "Day 1"
+        );
+    }
+
+    #[test]
+    fn unsupported_geometry() {
+        let geometry = polygon::p_array();
+        let schema = Arc::new(Schema::new(vec![geometry.extension_field()]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![geometry.into_array_ref()]).unwrap();
+        let table = Table::try_new(vec![batch], schema).unwrap();
+        let mut output = vec![];
+        assert!(write_gpx(&table, &mut output, Default::default()).is_err());
+    }
+}
@@ -0,0 +1,7 @@
+//! Read from and write to [KML](https://www.ogc.org/standard/kml/) files.
+
+mod reader;
+mod writer;
+
+pub use reader::{KmlReaderOptions, read_kml};
+pub use writer::{KmlWriterOptions, write_kml};
@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use geoarrow_schema::{CoordType, Crs, GeometryType, Metadata};
+use indexmap::IndexSet;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::parsed::{Coord, ParsedGeometry, geometry_array};
+use crate::io::stream::{RecordBatchReader, record_batches};
+use crate::io::xml::attribute;
+
+/// Options for the KML reader.
+#[derive(Debug, Clone)]
+pub struct KmlReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+}
+
+impl Default for KmlReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+        }
+    }
+}
+
+/// Read the Placemarks of a KML file, at any depth of Documents and Folders.
+///
+/// The output schema has `name` and `description` columns, followed by one column for each
+/// `Data` or `SimpleData` name in the Placemarks' `ExtendedData`, and a `geometry` column with a
+/// WGS84 CRS.
+///
+/// `ExtendedData` columns declared by a `Schema` element take the declared type. Other columns
+/// are `Int64` if all of their values are integers, `Float64` if all are numbers, `Boolean` if
+/// all are `true` or `false`, and `Utf8` otherwise. Values that can't be read as the column's
+/// type are null.
+///
+/// A `MultiGeometry` whose members all have the same type is read as the matching multi
+/// geometry type, and otherwise as a geometry collection.
+pub fn read_kml<R: BufRead>(reader: R, options: KmlReaderOptions) -> Result<RecordBatchReader> {
+    let mut parser = KmlParser::default();
+    let mut reader = Reader::from_reader(reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = vec![];
+    let mut path: Vec<Vec<u8>> = vec![];
+    let mut text = String::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(element) => {
+                let name = element.local_name().as_ref().to_vec();
+                parser.start(&name, &element)?;
+                path.push(name);
+                text.clear();
+            }
+            Event::Empty(element) => {
+                let name = element.local_name().as_ref().to_vec();
+                parser.start(&name, &element)?;
+                parser.end(&name, path.last().map(Vec::as_slice), "")?;
+            }
+            Event::Text(value) => text.push_str(&value.unescape()?),
+            Event::CData(value) => text.push_str(&String::from_utf8_lossy(&value)),
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                parser.end(&name, path.last().map(Vec::as_slice), text.trim())?;
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let batch = parser.finish(options.coord_type)?;
+    Ok(record_batches(batch, options.batch_size.unwrap_or(65_536)))
+}
+
+#[derive(Debug, Default)]
+struct Placemark {
+    name: Option<String>,
+    description: Option<String>,
+    data: HashMap<String, String>,
+    geometry: Option<ParsedGeometry>,
+}
+
+/// A geometry whose members are still being read.
+#[derive(Debug)]
+enum GeometryFrame {
+    MultiGeometry(Vec<ParsedGeometry>),
+    Polygon(Vec<Vec<Coord>>),
+}
+
+/// Collects the Placemarks of a KML document from its elements.
+#[derive(Debug, Default)]
+struct KmlParser {
+    placemarks: Vec<Placemark>,
+    placemark: Option<Placemark>,
+    /// The declared types of `SimpleField`s, by name.
+    field_types: HashMap<String, String>,
+    /// The names of `ExtendedData` values, in order of first appearance.
+    data_names: IndexSet<String>,
+    /// The name of the `Data` or `SimpleData` element being read.
+    data_name: Option<String>,
+    geometry_frames: Vec<GeometryFrame>,
+    coords: Vec<Coord>,
+}
+
+impl KmlParser {
+    fn start(&mut self, name: &[u8], element: &BytesStart) -> Result<()> {
+        match name {
+            b"Placemark" => self.placemark = Some(Placemark::default()),
+            b"SimpleField" => {
+                if let (Some(name), Some(field_type)) =
+                    (attribute(element, "name")?, attribute(element, "type")?)
+                {
+                    self.field_types.insert(name, field_type);
+                }
+            }
+            b"Data" | b"SimpleData" => self.data_name = attribute(element, "name")?,
+            b"MultiGeometry" => self
+                .geometry_frames
+                .push(GeometryFrame::MultiGeometry(vec![])),
+            b"Polygon" => self.geometry_frames.push(GeometryFrame::Polygon(vec![])),
+            b"Point" | b"LineString" | b"LinearRing" => self.coords.clear(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, name: &[u8], parent: Option<&[u8]>, text: &str) -> Result<()> {
+        let Some(placemark) = &mut self.placemark else {
+            return Ok(());
+        };
+        let text = (!text.is_empty()).then(|| text.to_string());
+        match name {
+            b"name" if matches!(parent, Some(b"Placemark")) => placemark.name = text,
+            b"description" if matches!(parent, Some(b"Placemark")) => placemark.description = text,
+            b"value" | b"SimpleData"
+                if name == b"SimpleData" || matches!(parent, Some(b"Data")) =>
+            {
+                if let (Some(data_name), Some(text)) = (self.data_name.take(), text) {
+                    self.data_names.insert(data_name.clone());
+                    placemark.data.insert(data_name, text);
+                }
+            }
+            b"coordinates" => self.coords = parse_coordinates(text.as_deref().unwrap_or(""))?,
+            b"Point" => {
+                if let Some(coord) = self.coords.first().copied() {
+                    self.push_geometry(ParsedGeometry::Point(coord));
+                }
+            }
+            b"LineString" => {
+                let coords = std::mem::take(&mut self.coords);
+                self.push_geometry(ParsedGeometry::LineString(coords))
+            }
+            b"LinearRing" => {
+                let coords = std::mem::take(&mut self.coords);
+                match self.geometry_frames.last_mut() {
+                    Some(GeometryFrame::Polygon(rings))
+                        if matches!(parent, Some(b"outerBoundaryIs" | b"innerBoundaryIs")) =>
+                    {
+                        // The exterior ring comes first, whatever order the boundaries are in
+                        if matches!(parent, Some(b"outerBoundaryIs")) {
+                            rings.insert(0, coords);
+                        } else {
+                            rings.push(coords);
+                        }
+                    }
+                    _ => self.push_geometry(ParsedGeometry::LineString(coords)),
+                }
+            }
+            b"Polygon" => {
+                if let Some(GeometryFrame::Polygon(rings)) = self.geometry_frames.pop() {
+                    self.push_geometry(ParsedGeometry::Polygon(rings));
+                }
+            }
+            b"MultiGeometry" => {
+                if let Some(GeometryFrame::MultiGeometry(geometries)) = self.geometry_frames.pop() {
+                    self.push_geometry(multi_geometry(geometries));
+                }
+            }
+            b"Placemark" => self.placemarks.extend(self.placemark.take()),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Add a geometry to the enclosing MultiGeometry, or else to the Placemark.
+    fn push_geometry(&mut self, geometry: ParsedGeometry) {
+        match self.geometry_frames.last_mut() {
+            Some(GeometryFrame::MultiGeometry(geometries)) => geometries.push(geometry),
+            _ => {
+                if let Some(placemark) = &mut self.placemark {
+                    placemark.geometry = Some(geometry);
+                }
+            }
+        }
+    }
+
+    fn finish(self, coord_type: CoordType) -> Result<RecordBatch> {
+        let mut fields = vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("description", DataType::Utf8, true),
+        ];
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter(
+                self.placemarks
+                    .iter()
+                    .map(|placemark| placemark.name.as_deref()),
+            )),
+            Arc::new(StringArray::from_iter(
+                self.placemarks
+                    .iter()
+                    .map(|placemark| placemark.description.as_deref()),
+            )),
+        ];
+
+        for data_name in &self.data_names {
+            let values = StringArray::from_iter(
+                self.placemarks
+                    .iter()
+                    .map(|placemark| placemark.data.get(data_name)),
+            );
+            let data_type = match self.field_types.get(data_name) {
+                Some(field_type) => declared_type(field_type),
+                None => inferred_type(&values),
+            };
+            fields.push(Field::new(data_name, data_type.clone(), true));
+            columns.push(arrow_cast::cast(&values, &data_type)?);
+        }
+
+        let metadata = Arc::new(Metadata::new(
+            Crs::from_authority_code("OGC:CRS84".to_string()),
+            None,
+        ));
+        let geometry_type = NativeType::Geometry(GeometryType::new(coord_type, metadata));
+        let geometries = self
+            .placemarks
+            .into_iter()
+            .map(|placemark| placemark.geometry)
+            .collect::<Vec<_>>();
+        fields.push(geometry_type.to_field("geometry", true));
+        columns.push(geometry_array(&geometries, geometry_type)?);
+
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+}
+
+/// The Arrow type of a KML `SimpleField` type.
+fn declared_type(field_type: &str) -> DataType {
+    match field_type {
+        "int" | "uint" | "short" | "ushort" => DataType::Int64,
+        "float" | "double" => DataType::Float64,
+        "bool" => DataType::Boolean,
+        _ => DataType::Utf8,
+    }
+}
+
+/// The narrowest Arrow type that all values can be read as.
+fn inferred_type(values: &StringArray) -> DataType {
+    let values = values.iter().flatten().collect::<Vec<_>>();
+    if values.is_empty() {
+        DataType::Utf8
+    } else if values.iter().all(|value| value.parse::<i64>().is_ok()) {
+        DataType::Int64
+    } else if values.iter().all(|value| value.parse::<f64>().is_ok()) {
+        DataType::Float64
+    } else if values
+        .iter()
+        .all(|value| matches!(*value, "true" | "false"))
+    {
+        DataType::Boolean
+    } else {
+        DataType::Utf8
+    }
+}
+
+/// Combine the members of a MultiGeometry into a multi geometry if they have the same type.
+fn multi_geometry(geometries: Vec<ParsedGeometry>) -> ParsedGeometry {
+    if geometries.is_empty() {
+        return ParsedGeometry::GeometryCollection(geometries);
+    }
+    if geometries
+        .iter()
+        .all(|geometry| matches!(geometry, ParsedGeometry::Point(_)))
+    {
+        return ParsedGeometry::MultiPoint(
+            geometries
+                .into_iter()
+                .filter_map(|geometry| match geometry {
+                    ParsedGeometry::Point(coord) => Some(coord),
+                    _ => None,
+                })
+                .collect(),
+        );
+    }
+    if geometries
+        .iter()
+        .all(|geometry| matches!(geometry, ParsedGeometry::LineString(_)))
+    {
+        return ParsedGeometry::MultiLineString(
+            geometries
+                .into_iter()
+                .filter_map(|geometry| match geometry {
+                    ParsedGeometry::LineString(coords) => Some(coords),
+                    _ => None,
+                })
+                .collect(),
+        );
+    }
+    if geometries
+        .iter()
+        .all(|geometry| matches!(geometry, ParsedGeometry::Polygon(_)))
+    {
+        return ParsedGeometry::MultiPolygon(
+            geometries
+                .into_iter()
+                .filter_map(|geometry| match geometry {
+                    ParsedGeometry::Polygon(rings) => Some(rings),
+                    _ => None,
+                })
+                .collect(),
+        );
+    }
+    ParsedGeometry::GeometryCollection(geometries)
+}
+
+/// Parse the whitespace-separated `lon,lat[,alt]` tuples of a `coordinates` element.
+fn parse_coordinates(text: &str) -> Result<Vec<Coord>> {
+    text.split_whitespace()
+        .map(|tuple| {
+            let invalid =
+                || GeoArrowError::General(format!("Invalid KML coordinates: '{}'", tuple));
+            let mut values = tuple
+                .split(',')
+                .map(|value| value.parse::<f64>().map_err(|_| invalid()));
+            let x = values.next().ok_or_else(invalid)??;
+            let y = values.next().ok_or_else(invalid)??;
+            let z = values.next().transpose()?;
+            Ok(Coord { x, y, z })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::Array;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, Int64Type};
+
+    use super::*;
+    use crate::table::Table;
+
+    const KML: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2">
+  <Document>
+    <Schema name="sites" id="sites">
+      <SimpleField name="code" type="string"/>
+    </Schema>
+    <Folder>
+      <Placemark>
+        <name>Summit</name>
+        <description><![CDATA[<b>High</b> point]]></description>
+        <ExtendedData>
+          <Data name="height"><value>4478</value></Data>
+          <Data name="open"><value>true</value></Data>
+          <SchemaData schemaUrl="#sites"><SimpleData name="code">0042</SimpleData></SchemaData>
+        </ExtendedData>
+        <Point><coordinates>7.6586,45.9763,4478</coordinates></Point>
+      </Placemark>
+      <Placemark>
+        <name>Lake</name>
+        <ExtendedData>
+          <Data name="height"><value>372.5</value></Data>
+        </ExtendedData>
+        <Polygon>
+          <outerBoundaryIs><LinearRing><coordinates>
+            0,0 1,0 1,1 0,0
+          </coordinates></LinearRing></outerBoundaryIs>
+        </Polygon>
+      </Placemark>
+      <Placemark>
+        <name>Trails</name>
+        <MultiGeometry>
+          <LineString><coordinates>0,0 1,1</coordinates></LineString>
+          <LineString><coordinates>2,2 3,3</coordinates></LineString>
+        </MultiGeometry>
+      </Placemark>
+    </Folder>
+  </Document>
+</kml>"##;
+
+    #[test]
+    fn read() {
+        let reader = read_kml(KML.as_bytes(), Default::default()).unwrap();
+        let table: Table = reader.try_into().unwrap();
+        let batch = &table.batches()[0];
+        let schema = batch.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["name", "description", "height", "open", "code", "geometry"]
+        );
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(
+            batch.column(1).as_string::<i32>().value(0),
+            "<b>High</b> point"
+        );
+        let height = batch.column(2).as_primitive::<Float64Type>();
+        assert_eq!(height.value(0), 4478.0);
+        assert_eq!(height.value(1), 372.5);
+        assert!(height.is_null(2));
+        assert!(batch.column(3).as_boolean().value(0));
+        assert_eq!(batch.column(4).as_string::<i32>().value(0), "0042");
+    }
+
+    #[test]
+    fn inferred_types() {
+        let values = StringArray::from(vec![Some("1"), None, Some("-2")]);
+        assert_eq!(inferred_type(&values), DataType::Int64);
+        let values = StringArray::from(vec!["1", "x"]);
+        assert_eq!(inferred_type(&values), DataType::Utf8);
+        assert!(
+            arrow_cast::cast(&values, &DataType::Int64)
+                .unwrap()
+                .as_primitive::<Int64Type>()
+                .is_null(1)
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use std::io::Write;
+
+use arrow_array::{Array, RecordBatch};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait,
+    MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+};
+use quick_xml::escape::escape;
+
+use crate::array::NativeArrayDyn;
+use crate::error::{GeoArrowError, Result};
+use crate::io::stream::RecordBatchReader;
+use crate::io::wkb::to_wkb;
+use crate::schema::GeoSchemaExt;
+use crate::trait_::ArrayAccessor;
+
+/// Options for the KML writer.
+#[derive(Debug, Clone, Default)]
+pub struct KmlWriterOptions {
+    /// The `name` of the KML `Document`.
+    pub document_name: Option<String>,
+}
+
+/// Write a table to a KML file.
+///
+/// The table must contain exactly one geometry column, and each row is written as a Placemark.
+/// Columns named `name` and `description` are written as the matching Placemark elements, and
+/// the non-null values of all other columns as `ExtendedData`. Multi geometries and geometry
+/// collections are written as `MultiGeometry` elements.
+///
+/// KML coordinates are always longitude and latitude on WGS84, but the geometries are not
+/// reprojected.
+pub fn write_kml<W: Write, S: Into<RecordBatchReader>>(
+    stream: S,
+    mut writer: W,
+    options: KmlWriterOptions,
+) -> Result<()> {
+    let reader = stream.into().into_inner();
+    let schema = reader.schema();
+    let geometry_columns = schema.geometry_columns();
+    let geometry_column_idx = match geometry_columns.as_slice() {
+        [idx] => *idx,
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Writing KML requires exactly one geometry column, found {}",
+                geometry_columns.len()
+            )));
+        }
+    };
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+    writeln!(writer, "<Document>")?;
+    if let Some(document_name) = &options.document_name {
+        writeln!(writer, "<name>{}</name>", escape(document_name.as_str()))?;
+    }
+
+    for batch in reader {
+        let batch = batch?;
+        let geometry_array = NativeArrayDyn::from_arrow_array(
+            batch.column(geometry_column_idx).as_ref(),
+            schema.field(geometry_column_idx),
+        )?
+        .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry_array.as_ref());
+        let formatters = PlacemarkFormatters::try_new(&batch, geometry_column_idx)?;
+
+        let mut placemark = vec![];
+        for row in 0..batch.num_rows() {
+            placemark.clear();
+            placemark.extend_from_slice(b"<Placemark>");
+            formatters.write(&mut placemark, row)?;
+            if let Some(wkb) = wkb_array.get(row) {
+                write_geometry(&mut placemark, &wkb.parse()?)?;
+            }
+            placemark.extend_from_slice(b"</Placemark>\n");
+            writer.write_all(&placemark)?;
+        }
+    }
+
+    writeln!(writer, "</Document>")?;
+    writeln!(writer, "</kml>")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Formats the non-geometry columns of a batch as Placemark elements.
+struct PlacemarkFormatters<'a> {
+    name: Option<(&'a dyn Array, ArrayFormatter<'a>)>,
+    description: Option<(&'a dyn Array, ArrayFormatter<'a>)>,
+    data: Vec<(&'a str, &'a dyn Array, ArrayFormatter<'a>)>,
+}
+
+impl<'a> PlacemarkFormatters<'a> {
+    fn try_new(batch: &'a RecordBatch, geometry_column_idx: usize) -> Result<Self> {
+        let mut formatters = Self {
+            name: None,
+            description: None,
+            data: vec![],
+        };
+        for (idx, field) in batch.schema_ref().fields().iter().enumerate() {
+            if idx == geometry_column_idx {
+                continue;
+            }
+            let column = batch.column(idx).as_ref();
+            let formatter = ArrayFormatter::try_new(column, &FormatOptions::default())?;
+            match field.name().as_str() {
+                "name" => formatters.name = Some((column, formatter)),
+                "description" => formatters.description = Some((column, formatter)),
+                name => formatters.data.push((name, column, formatter)),
+            }
+        }
+        Ok(formatters)
+    }
+
+    fn write(&self, out: &mut Vec<u8>, row: usize) -> Result<()> {
+        for (element, text) in [("name", &self.name), ("description", &self.description)] {
+            if let Some((column, formatter)) = text {
+                if column.is_valid(row) {
+                    let value = formatter.value(row).to_string();
+                    write!(out, "<{element}>{}</{element}>", escape(value.as_str()))?;
+                }
+            }
+        }
+
+        let mut values = self
+            .data
+            .iter()
+            .filter(|(_, column, _)| column.is_valid(row))
+            .peekable();
+        if values.peek().is_none() {
+            return Ok(());
+        }
+        out.extend_from_slice(b"<ExtendedData>");
+        for (name, _, formatter) in values {
+            let value = formatter.value(row).to_string();
+            write!(
+                out,
+                r#"<Data name="{}"><value>{}</value></Data>"#,
+                escape(*name),
+                escape(value.as_str())
+            )?;
+        }
+        out.extend_from_slice(b"</ExtendedData>");
+        Ok(())
+    }
+}
+
+fn write_geometry(out: &mut Vec<u8>, geometry: &impl GeometryTrait<T = f64>) -> Result<()> {
+    match geometry.as_type() {
+        GeometryType::Point(point) => {
+            if let Some(coord) = point.coord() {
+                out.extend_from_slice(b"<Point>");
+                write_coordinates(out, [coord])?;
+                out.extend_from_slice(b"</Point>");
+            }
+        }
+        GeometryType::LineString(line_string) => write_line_string(out, &line_string)?,
+        GeometryType::Polygon(polygon) => write_polygon(out, &polygon)?,
+        GeometryType::MultiPoint(multi_point) => {
+            out.extend_from_slice(b"<MultiGeometry>");
+            for point in multi_point.points() {
+                if let Some(coord) = point.coord() {
+                    out.extend_from_slice(b"<Point>");
+                    write_coordinates(out, [coord])?;
+                    out.extend_from_slice(b"</Point>");
+                }
+            }
+            out.extend_from_slice(b"</MultiGeometry>");
+        }
+        GeometryType::MultiLineString(multi_line_string) => {
+            out.extend_from_slice(b"<MultiGeometry>");
+            for line_string in multi_line_string.line_strings() {
+                write_line_string(out, &line_string)?;
+            }
+            out.extend_from_slice(b"</MultiGeometry>");
+        }
+        GeometryType::MultiPolygon(multi_polygon) => {
+            out.extend_from_slice(b"<MultiGeometry>");
+            for polygon in multi_polygon.polygons() {
+                write_polygon(out, &polygon)?;
+            }
+            out.extend_from_slice(b"</MultiGeometry>");
+        }
+        GeometryType::GeometryCollection(collection) => {
+            out.extend_from_slice(b"<MultiGeometry>");
+            for geometry in collection.geometries() {
+                write_geometry(out, &geometry)?;
+            }
+            out.extend_from_slice(b"</MultiGeometry>");
+        }
+        _ => {
+            return Err(GeoArrowError::General(
+                "KML can't represent rect, triangle or line geometries".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn write_line_string(out: &mut Vec<u8>, line_string: &impl LineStringTrait<T = f64>) -> Result<()> {
+    out.extend_from_slice(b"<LineString>");
+    write_coordinates(out, line_string.coords())?;
+    out.extend_from_slice(b"</LineString>");
+    Ok(())
+}
+
+fn write_polygon(out: &mut Vec<u8>, polygon: &impl PolygonTrait<T = f64>) -> Result<()> {
+    out.extend_from_slice(b"<Polygon>");
+    if let Some(exterior) = polygon.exterior() {
+        out.extend_from_slice(b"<outerBoundaryIs><LinearRing>");
+        write_coordinates(out, exterior.coords())?;
+        out.extend_from_slice(b"</LinearRing></outerBoundaryIs>");
+    }
+    for interior in polygon.interiors() {
+        out.extend_from_slice(b"<innerBoundaryIs><LinearRing>");
+        write_coordinates(out, interior.coords())?;
+        out.extend_from_slice(b"</LinearRing></innerBoundaryIs>");
+    }
+    out.extend_from_slice(b"</Polygon>");
+    Ok(())
+}
+
+/// Write a `coordinates` element of `lon,lat[,alt]` tuples, skipping NaN altitudes.
+fn write_coordinates<C: CoordTrait<T = f64>>(
+    out: &mut Vec<u8>,
+    coords: impl IntoIterator<Item = C>,
+) -> Result<()> {
+    out.extend_from_slice(b"<coordinates>");
+    for (idx, coord) in coords.into_iter().enumerate() {
+        if idx > 0 {
+            out.push(b' ');
+        }
+        write!(out, "{},{}", coord.x(), coord.y())?;
+        if matches!(coord.dim(), Dimensions::Xyz | Dimensions::Xyzm) {
+            let altitude = coord.nth_or_panic(2);
+            if !altitude.is_nan() {
+                write!(out, ",{altitude}")?;
+            }
+        }
+    }
+    out.extend_from_slice(b"</coordinates>");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::cast::AsArray;
+
+    use super::*;
+    use crate::io::kml::read_kml;
+    use crate::table::Table;
+    use crate::test::point;
+
+    const KML: &str = r#"<kml xmlns="http://www.opengis.net/kml/2.2"><Document>
+  <Placemark>
+    <name>Summit</name>
+    <description><![CDATA[<b>High</b> point]]></description>
+    <ExtendedData><Data name="height"><value>4478.5</value></Data></ExtendedData>
+    <Point><coordinates>7.6586,45.9763,4478</coordinates></Point>
+  </Placemark>
+  <Placemark>
+    <Polygon>
+      <outerBoundaryIs><LinearRing><coordinates>0,0 10,0 10,10 0,0</coordinates></LinearRing></outerBoundaryIs>
+      <innerBoundaryIs><LinearRing><coordinates>1,1 2,1 2,2 1,1</coordinates></LinearRing></innerBoundaryIs>
+    </Polygon>
+  </Placemark>
+  <Placemark>
+    <MultiGeometry>
+      <Point><coordinates>0,0</coordinates></Point>
+      <LineString><coordinates>0,0 1,1</coordinates></LineString>
+    </MultiGeometry>
+  </Placemark>
+</Document></kml>"#;
+
+    #[test]
+    fn round_trip() {
+        let table: Table = read_kml(KML.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let mut output = vec![];
+        write_kml(&table, &mut output, Default::default()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("<description>&lt;b&gt;High&lt;/b&gt; point</description>"));
+        assert!(output.contains("<coordinates>7.6586,45.9763,4478</coordinates>"));
+        assert!(
+            output.contains(
+                "<innerBoundaryIs><LinearRing><coordinates>1,1 2,1 2,2 1,1</coordinates>"
+            )
+        );
+
+        let table2: Table = read_kml(output.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(table.batches(), table2.batches());
+    }
+
+    #[test]
+    fn document_name() {
+        let table = point::table();
+        let mut output = vec![];
+        let options = KmlWriterOptions {
+            document_name: Some("Points & more".to_string()),
+        };
+        write_kml(&table, &mut output, options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("<name>Points &amp; more</name>"));
+        assert!(output.contains(r#"<Data name="string"><value>foo</value></Data>"#));
+        assert_eq!(output.matches("<Placemark>").count(), 3);
+
+        let table2: Table = read_kml(output.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert!(table2.batches()[0].column(0).as_string::<i32>().is_null(0));
+    }
+}
@@ -5,22 +5,39 @@ pub mod crs;
 #[cfg(feature = "csv")]
 pub mod csv;
 pub(crate) mod display;
+pub mod esri_json;
 #[cfg(feature = "flatgeobuf")]
 pub mod flatgeobuf;
 #[cfg(feature = "gdal")]
 pub mod gdal;
 pub(crate) mod geo;
+#[cfg(feature = "geobuf")]
+pub mod geobuf;
 pub mod geojson;
 pub mod geojson_lines;
+#[cfg(feature = "geopackage")]
+pub mod geopackage;
 #[cfg(feature = "geos")]
 pub(crate) mod geos;
 pub mod geozero;
+#[cfg(feature = "gml")]
+pub mod gml;
+#[cfg(feature = "gpx")]
+pub mod gpx;
 pub mod ipc;
+#[cfg(feature = "kml")]
+pub mod kml;
+#[cfg(feature = "osm")]
+pub mod osm;
+pub(crate) mod parsed;
 #[cfg(feature = "postgis")]
 pub mod postgis;
 pub mod shapefile;
 mod stream;
+pub mod topojson;
 pub mod wkb;
 pub mod wkt;
+#[cfg(any(feature = "gml", feature = "gpx", feature = "kml"))]
+pub(crate) mod xml;
 
 pub use stream::RecordBatchReader;
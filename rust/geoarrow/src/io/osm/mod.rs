@@ -0,0 +1,6 @@
+//! Read from [OpenStreetMap PBF](https://wiki.openstreetmap.org/wiki/PBF_Format) files.
+
+mod proto;
+mod reader;
+
+pub use reader::{OsmLayers, OsmReaderOptions, TagFilter, read_osm_pbf};
@@ -0,0 +1,138 @@
+//! Protobuf messages of the [OSM PBF format](https://wiki.openstreetmap.org/wiki/PBF_Format).
+//!
+//! Only the fields needed to read element ids, tags, locations and members are declared.
+
+/// The header preceding each blob, itself preceded by its length as a big-endian `u32`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct BlobHeader {
+    /// Either `OSMHeader` or `OSMData`.
+    #[prost(string, required, tag = "1")]
+    pub r#type: String,
+    #[prost(int32, required, tag = "3")]
+    pub datasize: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct Blob {
+    #[prost(int32, optional, tag = "2")]
+    pub raw_size: Option<i32>,
+    #[prost(oneof = "BlobData", tags = "1, 3, 4, 6, 7")]
+    pub data: Option<BlobData>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub(super) enum BlobData {
+    #[prost(bytes, tag = "1")]
+    Raw(Vec<u8>),
+    #[prost(bytes, tag = "3")]
+    ZlibData(Vec<u8>),
+    #[prost(bytes, tag = "4")]
+    LzmaData(Vec<u8>),
+    #[prost(bytes, tag = "6")]
+    Lz4Data(Vec<u8>),
+    #[prost(bytes, tag = "7")]
+    ZstdData(Vec<u8>),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct HeaderBlock {
+    /// Features a reader must support to read the file.
+    #[prost(string, repeated, tag = "4")]
+    pub required_features: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct PrimitiveBlock {
+    #[prost(message, required, tag = "1")]
+    pub stringtable: StringTable,
+    #[prost(message, repeated, tag = "2")]
+    pub primitivegroup: Vec<PrimitiveGroup>,
+    /// The size of a location unit in nanodegrees, 100 if unset.
+    #[prost(int32, optional, tag = "17")]
+    pub granularity: Option<i32>,
+    #[prost(int64, optional, tag = "19")]
+    pub lat_offset: Option<i64>,
+    #[prost(int64, optional, tag = "20")]
+    pub lon_offset: Option<i64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct StringTable {
+    #[prost(bytes, repeated, tag = "1")]
+    pub s: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct PrimitiveGroup {
+    #[prost(message, repeated, tag = "1")]
+    pub nodes: Vec<Node>,
+    #[prost(message, optional, tag = "2")]
+    pub dense: Option<DenseNodes>,
+    #[prost(message, repeated, tag = "3")]
+    pub ways: Vec<Way>,
+    #[prost(message, repeated, tag = "4")]
+    pub relations: Vec<Relation>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct Node {
+    #[prost(sint64, required, tag = "1")]
+    pub id: i64,
+    /// Indices into the string table.
+    #[prost(uint32, repeated, packed = "true", tag = "2")]
+    pub keys: Vec<u32>,
+    #[prost(uint32, repeated, packed = "true", tag = "3")]
+    pub vals: Vec<u32>,
+    #[prost(sint64, required, tag = "8")]
+    pub lat: i64,
+    #[prost(sint64, required, tag = "9")]
+    pub lon: i64,
+}
+
+/// Nodes stored as parallel, delta-encoded columns.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct DenseNodes {
+    #[prost(sint64, repeated, packed = "true", tag = "1")]
+    pub id: Vec<i64>,
+    #[prost(sint64, repeated, packed = "true", tag = "8")]
+    pub lat: Vec<i64>,
+    #[prost(sint64, repeated, packed = "true", tag = "9")]
+    pub lon: Vec<i64>,
+    /// The key and value string indices of each node's tags, each node's terminated by a 0.
+    #[prost(int32, repeated, packed = "true", tag = "10")]
+    pub keys_vals: Vec<i32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct Way {
+    #[prost(int64, required, tag = "1")]
+    pub id: i64,
+    #[prost(uint32, repeated, packed = "true", tag = "2")]
+    pub keys: Vec<u32>,
+    #[prost(uint32, repeated, packed = "true", tag = "3")]
+    pub vals: Vec<u32>,
+    /// Delta-encoded node ids.
+    #[prost(sint64, repeated, packed = "true", tag = "8")]
+    pub refs: Vec<i64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(super) struct Relation {
+    #[prost(int64, required, tag = "1")]
+    pub id: i64,
+    #[prost(uint32, repeated, packed = "true", tag = "2")]
+    pub keys: Vec<u32>,
+    #[prost(uint32, repeated, packed = "true", tag = "3")]
+    pub vals: Vec<u32>,
+    /// The string table index of each member's role.
+    #[prost(int32, repeated, packed = "true", tag = "8")]
+    pub roles_sid: Vec<i32>,
+    /// Delta-encoded member ids.
+    #[prost(sint64, repeated, packed = "true", tag = "9")]
+    pub memids: Vec<i64>,
+    /// The type of each member, [WAY_MEMBER] for ways.
+    #[prost(int32, repeated, packed = "true", tag = "10")]
+    pub types: Vec<i32>,
+}
+
+pub(super) const WAY_MEMBER: i32 = 1;
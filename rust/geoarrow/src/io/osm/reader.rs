@@ -0,0 +1,975 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use arrow_array::builder::{MapBuilder, StringBuilder};
+use arrow_array::{Array, ArrayRef, Int64Array, RecordBatch};
+use arrow_schema::{DataType, Field, FieldRef, Schema};
+use flate2::read::ZlibDecoder;
+use geo::{Area, Contains, Coord, LineString, MultiPolygon, Point, Polygon};
+use geoarrow_schema::{CoordType, Crs, Dimension, Metadata};
+use prost::Message;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::ArrayBase;
+use crate::array::{LineStringBuilder, MultiPolygonBuilder, PointBuilder, PolygonBuilder};
+use crate::error::{GeoArrowError, Result};
+use crate::io::osm::proto;
+use crate::io::stream::{RecordBatchReader, record_batches};
+
+/// The maximum sizes of a blob header and a blob allowed by the format.
+const MAX_BLOB_HEADER_SIZE: usize = 64 * 1024;
+const MAX_BLOB_SIZE: usize = 32 * 1024 * 1024;
+
+/// The number of blobs read into memory before being decoded in parallel.
+const BLOBS_PER_CHUNK: usize = 64;
+
+/// The `required_features` of a file header that this reader supports.
+const SUPPORTED_FEATURES: [&str; 2] = ["OsmSchema-V0.6", "DenseNodes"];
+
+/// Keys whose presence makes a closed way an area rather than a line.
+const AREA_KEYS: [&str; 14] = [
+    "aeroway",
+    "amenity",
+    "building",
+    "building:part",
+    "craft",
+    "historic",
+    "landuse",
+    "leisure",
+    "man_made",
+    "military",
+    "natural",
+    "office",
+    "shop",
+    "tourism",
+];
+
+/// Tags with an area key that are nevertheless lines.
+const LINEAR_TAGS: [(&str, &str); 6] = [
+    ("man_made", "embankment"),
+    ("man_made", "pipeline"),
+    ("natural", "cliff"),
+    ("natural", "coastline"),
+    ("natural", "ridge"),
+    ("natural", "tree_row"),
+];
+
+/// Selects OSM elements by their tags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TagFilter {
+    /// Keep all elements.
+    #[default]
+    All,
+
+    /// Keep elements with at least one tag.
+    Tagged,
+
+    /// Keep elements with any of these keys.
+    Keys(Vec<String>),
+
+    /// Keep elements with any of these key and value pairs.
+    Tags(Vec<(String, String)>),
+
+    /// Keep no elements.
+    None,
+}
+
+impl TagFilter {
+    fn matches(&self, tags: &[(String, String)]) -> bool {
+        match self {
+            Self::All => true,
+            Self::Tagged => !tags.is_empty(),
+            Self::Keys(keys) => tags.iter().any(|(key, _)| keys.contains(key)),
+            Self::Tags(filter) => tags.iter().any(|tag| filter.contains(tag)),
+            Self::None => false,
+        }
+    }
+}
+
+/// Options for the OSM PBF reader.
+#[derive(Debug, Clone)]
+pub struct OsmReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+
+    /// The nodes to read as points. Defaults to tagged nodes, since untagged nodes are usually
+    /// only the vertices of ways.
+    pub nodes: TagFilter,
+
+    /// The ways to read as lines and areas. Defaults to tagged ways.
+    pub ways: TagFilter,
+
+    /// The multipolygon and boundary relations to read as multi polygons. Defaults to all of
+    /// them.
+    pub relations: TagFilter,
+}
+
+impl Default for OsmReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+            nodes: TagFilter::Tagged,
+            ways: TagFilter::Tagged,
+            relations: TagFilter::All,
+        }
+    }
+}
+
+/// The layers of an OSM PBF file.
+///
+/// Each layer has an `id` column, a `tags` column of string to string maps, and a `geometry`
+/// column with a WGS84 CRS. The layers are held in memory, see
+/// [`read_osm_pbf`].
+pub struct OsmLayers {
+    /// Nodes, as points.
+    pub nodes: RecordBatchReader,
+
+    /// Open ways and closed ways that aren't areas, as line strings.
+    pub lines: RecordBatchReader,
+
+    /// Closed ways that are areas, as polygons.
+    pub areas: RecordBatchReader,
+
+    /// Multipolygon and boundary relations, as multi polygons.
+    pub multipolygons: RecordBatchReader,
+}
+
+/// Read the nodes, ways and multipolygon relations of an OSM PBF file.
+///
+/// A closed way is an area if it is tagged `area=yes`, or if it has a key such as `building` or
+/// `landuse` and isn't tagged `area=no`. The outer and inner member ways of a relation are
+/// joined into rings, and each inner ring is assigned to the smallest outer ring containing it.
+/// Ways and relations that reference elements missing from the file, and relations whose rings
+/// can't be closed or that have an inner ring outside all of their outer rings, are skipped.
+///
+/// The file is read in three passes, for relations, ways and then nodes, so that only the member
+/// ways and node locations that are needed are held in memory. If the `rayon` feature is
+/// enabled, blocks are decoded in parallel.
+///
+/// The layers are built in full once the last pass is done, since a way can't be assembled until
+/// the locations of all of its nodes have been read. The selected elements of all four layers are
+/// therefore held in memory together, and the returned readers only split them into batches.
+pub fn read_osm_pbf<R: Read + Seek>(mut reader: R, options: OsmReaderOptions) -> Result<OsmLayers> {
+    // Relations come last in a file, but they determine which ways need to be kept
+    let relations: Vec<OsmRelation> = if options.relations == TagFilter::None {
+        vec![]
+    } else {
+        map_blocks(&mut reader, |block| block.relations(&options.relations))?
+            .into_iter()
+            .flatten()
+            .collect()
+    };
+    let member_way_ids = relations
+        .iter()
+        .flat_map(|relation| relation.outer.iter().chain(&relation.inner))
+        .copied()
+        .collect::<HashSet<_>>();
+
+    let ways: Vec<OsmWay> = if options.ways == TagFilter::None && member_way_ids.is_empty() {
+        vec![]
+    } else {
+        map_blocks(&mut reader, |block| {
+            block.ways(&options.ways, &member_way_ids)
+        })?
+        .into_iter()
+        .flatten()
+        .collect()
+    };
+    let way_node_ids = ways
+        .iter()
+        .flat_map(|way| &way.refs)
+        .copied()
+        .collect::<HashSet<_>>();
+
+    let mut nodes = vec![];
+    let mut locations = HashMap::new();
+    if options.nodes != TagFilter::None || !way_node_ids.is_empty() {
+        for (block_nodes, block_locations) in map_blocks(&mut reader, |block| {
+            block.nodes(&options.nodes, &way_node_ids)
+        })? {
+            nodes.extend(block_nodes);
+            locations.extend(block_locations);
+        }
+    }
+
+    let metadata = Arc::new(Metadata::new(
+        Crs::from_authority_code("OGC:CRS84".to_string()),
+        None,
+    ));
+    let coord_type = options.coord_type;
+    let batch_size = options.batch_size.unwrap_or(65_536);
+
+    let points = nodes
+        .iter()
+        .map(|node| Point(node.coord))
+        .collect::<Vec<_>>();
+    let points =
+        PointBuilder::from_points(points.iter(), Dimension::XY, coord_type, metadata.clone())
+            .finish();
+    let nodes = layer(
+        nodes.into_iter().map(|node| (node.id, node.tags)),
+        points.extension_field(),
+        points.into_array_ref(),
+    )?;
+
+    let mut member_ways = HashMap::new();
+    let mut lines = vec![];
+    let mut areas = vec![];
+    for way in ways {
+        if member_way_ids.contains(&way.id) {
+            member_ways.insert(way.id, way.refs.clone());
+        }
+        if !way.selected {
+            continue;
+        }
+        let Some(coords) = coords(&way.refs, &locations) else {
+            continue;
+        };
+        if coords.len() >= 4 && way.refs.first() == way.refs.last() && is_area(&way.tags) {
+            areas.push((way.id, way.tags, Polygon::new(LineString(coords), vec![])));
+        } else if coords.len() >= 2 {
+            lines.push((way.id, way.tags, LineString(coords)));
+        }
+    }
+
+    let (line_rows, line_strings): (Vec<_>, Vec<_>) = lines
+        .into_iter()
+        .map(|(id, tags, line_string)| ((id, tags), line_string))
+        .unzip();
+    let line_strings = LineStringBuilder::from_line_strings(
+        &line_strings,
+        Dimension::XY,
+        coord_type,
+        metadata.clone(),
+    )
+    .finish();
+    let lines = layer(
+        line_rows,
+        line_strings.extension_field(),
+        line_strings.into_array_ref(),
+    )?;
+
+    let (area_rows, polygons): (Vec<_>, Vec<_>) = areas
+        .into_iter()
+        .map(|(id, tags, polygon)| ((id, tags), polygon))
+        .unzip();
+    let polygons =
+        PolygonBuilder::from_polygons(&polygons, Dimension::XY, coord_type, metadata.clone())
+            .finish();
+    let areas = layer(
+        area_rows,
+        polygons.extension_field(),
+        polygons.into_array_ref(),
+    )?;
+
+    let (relation_rows, multi_polygons): (Vec<_>, Vec<_>) = relations
+        .into_iter()
+        .filter_map(|relation| {
+            let multi_polygon = multi_polygon(&relation, &member_ways, &locations)?;
+            Some(((relation.id, relation.tags), multi_polygon))
+        })
+        .unzip();
+    let multi_polygons = MultiPolygonBuilder::from_multi_polygons(
+        &multi_polygons,
+        Dimension::XY,
+        coord_type,
+        metadata,
+    )
+    .finish();
+    let multipolygons = layer(
+        relation_rows,
+        multi_polygons.extension_field(),
+        multi_polygons.into_array_ref(),
+    )?;
+
+    Ok(OsmLayers {
+        nodes: record_batches(nodes, batch_size),
+        lines: record_batches(lines, batch_size),
+        areas: record_batches(areas, batch_size),
+        multipolygons: record_batches(multipolygons, batch_size),
+    })
+}
+
+type Tags = Vec<(String, String)>;
+
+struct OsmNode {
+    id: i64,
+    tags: Tags,
+    coord: Coord,
+}
+
+struct OsmWay {
+    id: i64,
+    tags: Tags,
+    /// The ids of the way's nodes.
+    refs: Vec<i64>,
+    /// Whether the way passed the ways filter, rather than only being a relation member.
+    selected: bool,
+}
+
+struct OsmRelation {
+    id: i64,
+    tags: Tags,
+    outer: Vec<i64>,
+    inner: Vec<i64>,
+}
+
+/// A decoded primitive block.
+struct Block(proto::PrimitiveBlock);
+
+impl Block {
+    fn string(&self, idx: usize) -> Result<String> {
+        let bytes = self.0.stringtable.s.get(idx).ok_or_else(|| {
+            GeoArrowError::General(format!("Invalid OSM PBF string table index {}", idx))
+        })?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn tags(&self, keys: &[u32], vals: &[u32]) -> Result<Tags> {
+        if keys.len() != vals.len() {
+            return Err(GeoArrowError::General(
+                "OSM PBF element has different numbers of tag keys and values".to_string(),
+            ));
+        }
+        keys.iter()
+            .zip(vals)
+            .map(|(key, val)| Ok((self.string(*key as usize)?, self.string(*val as usize)?)))
+            .collect()
+    }
+
+    fn coord(&self, lat: i64, lon: i64) -> Coord {
+        let granularity = self.0.granularity.unwrap_or(100) as i64;
+        let lat_offset = self.0.lat_offset.unwrap_or(0);
+        let lon_offset = self.0.lon_offset.unwrap_or(0);
+        Coord {
+            x: 1e-9 * (lon_offset + granularity * lon) as f64,
+            y: 1e-9 * (lat_offset + granularity * lat) as f64,
+        }
+    }
+
+    /// The nodes that pass `filter`, and the locations of the nodes in `way_node_ids`.
+    fn nodes(
+        &self,
+        filter: &TagFilter,
+        way_node_ids: &HashSet<i64>,
+    ) -> Result<(Vec<OsmNode>, Vec<(i64, Coord)>)> {
+        let mut nodes = vec![];
+        let mut locations = vec![];
+        let mut visit = |id: i64, tags: Tags, coord: Coord| {
+            if way_node_ids.contains(&id) {
+                locations.push((id, coord));
+            }
+            if filter.matches(&tags) {
+                nodes.push(OsmNode { id, tags, coord });
+            }
+        };
+
+        for group in &self.0.primitivegroup {
+            for node in &group.nodes {
+                let tags = self.tags(&node.keys, &node.vals)?;
+                visit(node.id, tags, self.coord(node.lat, node.lon));
+            }
+
+            let Some(dense) = &group.dense else {
+                continue;
+            };
+            if dense.lat.len() != dense.id.len() || dense.lon.len() != dense.id.len() {
+                return Err(GeoArrowError::General(
+                    "OSM PBF dense nodes have different numbers of ids and locations".to_string(),
+                ));
+            }
+            let (mut id, mut lat, mut lon) = (0, 0, 0);
+            let mut keys_vals = dense.keys_vals.iter();
+            for idx in 0..dense.id.len() {
+                id += dense.id[idx];
+                lat += dense.lat[idx];
+                lon += dense.lon[idx];
+                let mut tags = vec![];
+                // Each node's tags are terminated by a 0
+                while let Some(&key) = keys_vals.next() {
+                    if key == 0 {
+                        break;
+                    }
+                    let val = keys_vals.next().ok_or_else(|| {
+                        GeoArrowError::General("OSM PBF dense node tag without value".to_string())
+                    })?;
+                    tags.push((self.string(key as usize)?, self.string(*val as usize)?));
+                }
+                visit(id, tags, self.coord(lat, lon));
+            }
+        }
+        Ok((nodes, locations))
+    }
+
+    /// The ways that pass `filter` or are in `member_way_ids`.
+    fn ways(&self, filter: &TagFilter, member_way_ids: &HashSet<i64>) -> Result<Vec<OsmWay>> {
+        let mut ways = vec![];
+        for way in self.0.primitivegroup.iter().flat_map(|group| &group.ways) {
+            let tags = self.tags(&way.keys, &way.vals)?;
+            let selected = filter.matches(&tags);
+            if !selected && !member_way_ids.contains(&way.id) {
+                continue;
+            }
+            let refs = way
+                .refs
+                .iter()
+                .scan(0, |id, delta| {
+                    *id += delta;
+                    Some(*id)
+                })
+                .collect();
+            ways.push(OsmWay {
+                id: way.id,
+                tags,
+                refs,
+                selected,
+            });
+        }
+        Ok(ways)
+    }
+
+    /// The multipolygon and boundary relations that pass `filter`.
+    fn relations(&self, filter: &TagFilter) -> Result<Vec<OsmRelation>> {
+        let mut relations = vec![];
+        for relation in self
+            .0
+            .primitivegroup
+            .iter()
+            .flat_map(|group| &group.relations)
+        {
+            let tags = self.tags(&relation.keys, &relation.vals)?;
+            let relation_type = tags
+                .iter()
+                .find(|(key, _)| key == "type")
+                .map(|(_, value)| value.as_str());
+            if !matches!(relation_type, Some("multipolygon" | "boundary")) || !filter.matches(&tags)
+            {
+                continue;
+            }
+            if relation.roles_sid.len() != relation.memids.len()
+                || relation.types.len() != relation.memids.len()
+            {
+                return Err(GeoArrowError::General(
+                    "OSM PBF relation has different numbers of member ids, roles and types"
+                        .to_string(),
+                ));
+            }
+
+            let mut outer = vec![];
+            let mut inner = vec![];
+            let mut member_id = 0;
+            for ((delta, role), member_type) in relation
+                .memids
+                .iter()
+                .zip(&relation.roles_sid)
+                .zip(&relation.types)
+            {
+                member_id += delta;
+                if *member_type != proto::WAY_MEMBER {
+                    continue;
+                }
+                match self.string(*role as usize)?.as_str() {
+                    "outer" | "" => outer.push(member_id),
+                    "inner" => inner.push(member_id),
+                    _ => {}
+                }
+            }
+            relations.push(OsmRelation {
+                id: relation.id,
+                tags,
+                outer,
+                inner,
+            });
+        }
+        Ok(relations)
+    }
+}
+
+/// Decode the data blocks of a file, `BLOBS_PER_CHUNK` at a time, and map each with `f`.
+fn map_blocks<R: Read + Seek, T: Send>(
+    reader: &mut R,
+    f: impl Fn(&Block) -> Result<T> + Sync,
+) -> Result<Vec<T>> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut output = vec![];
+    let mut chunk = vec![];
+    while let Some((blob_type, blob)) = read_blob(reader)? {
+        match blob_type.as_str() {
+            "OSMHeader" => check_header(&proto::HeaderBlock::decode(decompress(&blob)?.as_ref())?)?,
+            "OSMData" => {
+                chunk.push(blob);
+                if chunk.len() == BLOBS_PER_CHUNK {
+                    output.extend(map_chunk(&chunk, &f)?);
+                    chunk.clear();
+                }
+            }
+            // Readers should skip unknown blob types
+            _ => {}
+        }
+    }
+    output.extend(map_chunk(&chunk, &f)?);
+    Ok(output)
+}
+
+fn map_chunk<T: Send>(
+    chunk: &[proto::Blob],
+    f: &(impl Fn(&Block) -> Result<T> + Sync),
+) -> Result<Vec<T>> {
+    let decode = |blob: &proto::Blob| {
+        let block = proto::PrimitiveBlock::decode(decompress(blob)?.as_ref())?;
+        f(&Block(block))
+    };
+
+    #[cfg(feature = "rayon")]
+    {
+        chunk.par_iter().map(decode).collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        chunk.iter().map(decode).collect()
+    }
+}
+
+/// Read the next blob and its type, or `None` at the end of the file.
+fn read_blob<R: Read>(reader: &mut R) -> Result<Option<(String, proto::Blob)>> {
+    let mut header_size = [0; 4];
+    match reader.read_exact(&mut header_size) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+    let header_size = u32::from_be_bytes(header_size) as usize;
+    if header_size > MAX_BLOB_HEADER_SIZE {
+        return Err(GeoArrowError::General(format!(
+            "OSM PBF blob header size {} is larger than the maximum of {}",
+            header_size, MAX_BLOB_HEADER_SIZE
+        )));
+    }
+    let mut header = vec![0; header_size];
+    reader.read_exact(&mut header)?;
+    let header = proto::BlobHeader::decode(header.as_slice())?;
+
+    let blob_size = usize::try_from(header.datasize)
+        .ok()
+        .filter(|size| *size <= MAX_BLOB_SIZE)
+        .ok_or_else(|| {
+            GeoArrowError::General(format!("Invalid OSM PBF blob size {}", header.datasize))
+        })?;
+    let mut blob = vec![0; blob_size];
+    reader.read_exact(&mut blob)?;
+    Ok(Some((header.r#type, proto::Blob::decode(blob.as_slice())?)))
+}
+
+fn decompress(blob: &proto::Blob) -> Result<Cow<'_, [u8]>> {
+    match &blob.data {
+        Some(proto::BlobData::Raw(data)) => Ok(Cow::Borrowed(data)),
+        Some(proto::BlobData::ZlibData(data)) => {
+            let raw_size = blob.raw_size.unwrap_or(0).clamp(0, MAX_BLOB_SIZE as i32) as usize;
+            let mut raw = Vec::with_capacity(raw_size);
+            ZlibDecoder::new(data.as_slice())
+                .take(MAX_BLOB_SIZE as u64 + 1)
+                .read_to_end(&mut raw)?;
+            if raw.len() > MAX_BLOB_SIZE {
+                return Err(GeoArrowError::General(format!(
+                    "OSM PBF blob decompresses to more than the maximum of {} bytes",
+                    MAX_BLOB_SIZE
+                )));
+            }
+            Ok(Cow::Owned(raw))
+        }
+        Some(_) => Err(GeoArrowError::General(
+            "Unsupported OSM PBF blob compression, only zlib is supported".to_string(),
+        )),
+        None => Ok(Cow::Borrowed(&[])),
+    }
+}
+
+fn check_header(header: &proto::HeaderBlock) -> Result<()> {
+    for feature in &header.required_features {
+        if !SUPPORTED_FEATURES.contains(&feature.as_str()) {
+            return Err(GeoArrowError::General(format!(
+                "Unsupported OSM PBF feature '{}'",
+                feature
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn is_area(tags: &[(String, String)]) -> bool {
+    let mut is_area = false;
+    for (key, value) in tags {
+        match (key.as_str(), value.as_str()) {
+            ("area", "yes") => return true,
+            ("area", "no") => return false,
+            (key, value) if AREA_KEYS.contains(&key) && !LINEAR_TAGS.contains(&(key, value)) => {
+                is_area = true
+            }
+            _ => {}
+        }
+    }
+    is_area
+}
+
+/// The locations of nodes, or `None` if any is missing.
+fn coords(node_ids: &[i64], locations: &HashMap<i64, Coord>) -> Option<Vec<Coord>> {
+    node_ids
+        .iter()
+        .map(|id| locations.get(id).copied())
+        .collect()
+}
+
+/// Assemble the member ways of a relation into a multi polygon.
+fn multi_polygon(
+    relation: &OsmRelation,
+    member_ways: &HashMap<i64, Vec<i64>>,
+    locations: &HashMap<i64, Coord>,
+) -> Option<MultiPolygon> {
+    let rings = |way_ids: &[i64]| -> Option<Vec<LineString>> {
+        rings(way_ids, member_ways)?
+            .iter()
+            .map(|ring| coords(ring, locations).map(LineString))
+            .collect()
+    };
+    let mut polygons = rings(&relation.outer)?
+        .into_iter()
+        .map(|exterior| Polygon::new(exterior, vec![]))
+        .collect::<Vec<_>>();
+    if polygons.is_empty() {
+        return None;
+    }
+
+    // Areas of the exteriors, to find the smallest one containing each inner ring
+    let areas = polygons
+        .iter()
+        .map(|polygon| polygon.unsigned_area())
+        .collect::<Vec<_>>();
+    for interior in rings(&relation.inner)? {
+        let outer = (0..polygons.len())
+            .filter(|idx| polygons[*idx].contains(&interior))
+            .min_by(|a, b| areas[*a].total_cmp(&areas[*b]));
+        // An inner ring outside all outer rings makes the relation invalid
+        polygons[outer?].interiors_push(interior);
+    }
+    Some(MultiPolygon(polygons))
+}
+
+/// Join ways end to end into closed rings of node ids, or `None` if a ring can't be closed.
+///
+/// Rings with fewer than four nodes are dropped.
+fn rings(way_ids: &[i64], member_ways: &HashMap<i64, Vec<i64>>) -> Option<Vec<Vec<i64>>> {
+    let mut segments = way_ids
+        .iter()
+        .map(|id| member_ways.get(id).map(Vec::as_slice))
+        .collect::<Option<Vec<_>>>()?;
+    let mut rings = vec![];
+    while let Some(segment) = segments.pop() {
+        let mut ring = segment.to_vec();
+        while ring.first() != ring.last() {
+            let end = *ring.last()?;
+            let idx = segments.iter().position(|segment| {
+                segment.first() == Some(&end) || segment.last() == Some(&end)
+            })?;
+            let segment = segments.swap_remove(idx);
+            if segment.first() == Some(&end) {
+                ring.extend_from_slice(&segment[1..]);
+            } else {
+                ring.extend(segment.iter().rev().skip(1));
+            }
+        }
+        if ring.len() >= 4 {
+            rings.push(ring);
+        }
+    }
+    Some(rings)
+}
+
+/// Build the batch of a layer from the id and tags of each row and the geometry column.
+fn layer(
+    rows: impl IntoIterator<Item = (i64, Tags)>,
+    geometry_field: FieldRef,
+    geometry: ArrayRef,
+) -> Result<RecordBatch> {
+    let mut ids = vec![];
+    let mut tags = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+    for (id, row_tags) in rows {
+        ids.push(id);
+        for (key, value) in row_tags {
+            tags.keys().append_value(key);
+            tags.values().append_value(value);
+        }
+        tags.append(true)?;
+    }
+    let tags = tags.finish();
+
+    let schema = Schema::new(vec![
+        Arc::new(Field::new("id", DataType::Int64, false)),
+        Arc::new(Field::new("tags", tags.data_type().clone(), false)),
+        geometry_field,
+    ]);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(Int64Array::from(ids)), Arc::new(tags), geometry],
+    )?)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Write};
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int64Type;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use geo_traits::{
+        GeometryTrait, GeometryType, LineStringTrait, MultiPolygonTrait, PolygonTrait,
+    };
+
+    use super::*;
+    use crate::array::NativeArrayDyn;
+    use crate::io::wkb::to_wkb;
+    use crate::table::Table;
+    use crate::trait_::ArrayAccessor;
+
+    const STRINGS: [&str; 13] = [
+        "",
+        "amenity",
+        "cafe",
+        "building",
+        "yes",
+        "highway",
+        "path",
+        "type",
+        "multipolygon",
+        "landuse",
+        "forest",
+        "outer",
+        "inner",
+    ];
+
+    fn string(value: &str) -> u32 {
+        STRINGS.iter().position(|s| *s == value).unwrap() as u32
+    }
+
+    fn delta(values: &[i64]) -> Vec<i64> {
+        let mut previous = 0;
+        values
+            .iter()
+            .map(|value| {
+                let delta = value - previous;
+                previous = *value;
+                delta
+            })
+            .collect()
+    }
+
+    fn way(id: i64, refs: &[i64], tags: &[(&str, &str)]) -> proto::Way {
+        proto::Way {
+            id,
+            keys: tags.iter().map(|(key, _)| string(key)).collect(),
+            vals: tags.iter().map(|(_, val)| string(val)).collect(),
+            refs: delta(refs),
+        }
+    }
+
+    fn write_blob(out: &mut Vec<u8>, blob_type: &str, data: Vec<u8>, compress: bool) {
+        let blob = if compress {
+            let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+            encoder.write_all(&data).unwrap();
+            proto::Blob {
+                raw_size: Some(data.len() as i32),
+                data: Some(proto::BlobData::ZlibData(encoder.finish().unwrap())),
+            }
+        } else {
+            proto::Blob {
+                raw_size: None,
+                data: Some(proto::BlobData::Raw(data)),
+            }
+        }
+        .encode_to_vec();
+        let header = proto::BlobHeader {
+            r#type: blob_type.to_string(),
+            datasize: blob.len() as i32,
+        }
+        .encode_to_vec();
+        out.extend_from_slice(&(header.len() as u32).to_be_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&blob);
+    }
+
+    /// A square with a square hole, as both a building way and a multipolygon relation, a path,
+    /// and a cafe, and an invalid relation with its inner ring outside its outer ring.
+    fn pbf() -> Vec<u8> {
+        let stringtable = proto::StringTable {
+            s: STRINGS.iter().map(|s| s.as_bytes().to_vec()).collect(),
+        };
+        // Locations are in units of 100 nanodegrees
+        let locations: [(i64, i64); 8] = [
+            (0, 0),
+            (0, 40_000_000),
+            (40_000_000, 40_000_000),
+            (40_000_000, 0),
+            (10_000_000, 10_000_000),
+            (10_000_000, 20_000_000),
+            (20_000_000, 20_000_000),
+            (20_000_000, 10_000_000),
+        ];
+        let nodes = proto::PrimitiveBlock {
+            stringtable: stringtable.clone(),
+            primitivegroup: vec![proto::PrimitiveGroup {
+                nodes: vec![proto::Node {
+                    id: 9,
+                    keys: vec![string("amenity")],
+                    vals: vec![string("cafe")],
+                    lat: 100_000_000,
+                    lon: 100_000_000,
+                }],
+                dense: Some(proto::DenseNodes {
+                    id: delta(&[1, 2, 3, 4, 5, 6, 7, 8]),
+                    lat: delta(&locations.map(|(lat, _)| lat)),
+                    lon: delta(&locations.map(|(_, lon)| lon)),
+                    keys_vals: vec![],
+                }),
+                ways: vec![],
+                relations: vec![],
+            }],
+            granularity: None,
+            lat_offset: None,
+            lon_offset: None,
+        };
+        let ways = proto::PrimitiveBlock {
+            stringtable,
+            primitivegroup: vec![proto::PrimitiveGroup {
+                nodes: vec![],
+                dense: None,
+                ways: vec![
+                    way(10, &[1, 2, 3], &[]),
+                    way(11, &[1, 4, 3], &[]),
+                    way(12, &[5, 6, 7, 8, 5], &[("building", "yes")]),
+                    way(13, &[1, 3], &[("highway", "path")]),
+                ],
+                relations: vec![
+                    proto::Relation {
+                        id: 20,
+                        keys: vec![string("type"), string("landuse")],
+                        vals: vec![string("multipolygon"), string("forest")],
+                        roles_sid: vec![
+                            string("outer") as i32,
+                            string("outer") as i32,
+                            string("inner") as i32,
+                        ],
+                        memids: delta(&[10, 11, 12]),
+                        types: vec![proto::WAY_MEMBER; 3],
+                    },
+                    proto::Relation {
+                        id: 21,
+                        keys: vec![string("type")],
+                        vals: vec![string("multipolygon")],
+                        roles_sid: vec![
+                            string("outer") as i32,
+                            string("inner") as i32,
+                            string("inner") as i32,
+                        ],
+                        memids: delta(&[12, 10, 11]),
+                        types: vec![proto::WAY_MEMBER; 3],
+                    },
+                ],
+            }],
+            granularity: Some(100),
+            lat_offset: None,
+            lon_offset: None,
+        };
+
+        let mut out = vec![];
+        let header = proto::HeaderBlock {
+            required_features: vec!["OsmSchema-V0.6".to_string(), "DenseNodes".to_string()],
+        };
+        write_blob(&mut out, "OSMHeader", header.encode_to_vec(), false);
+        write_blob(&mut out, "OSMData", nodes.encode_to_vec(), true);
+        write_blob(&mut out, "OSMData", ways.encode_to_vec(), false);
+        out
+    }
+
+    fn ids(reader: RecordBatchReader) -> Vec<i64> {
+        let table: Table = reader.try_into().unwrap();
+        table
+            .batches()
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn read() {
+        let layers = read_osm_pbf(Cursor::new(pbf()), Default::default()).unwrap();
+        assert_eq!(ids(layers.nodes), [9]);
+        assert_eq!(ids(layers.lines), [13]);
+        assert_eq!(ids(layers.areas), [12]);
+
+        let table: Table = layers.multipolygons.try_into().unwrap();
+        let batch = &table.batches()[0];
+        assert_eq!(batch.column(0).as_primitive::<Int64Type>().values(), &[20]);
+        let tags = batch.column(1).as_map();
+        assert_eq!(tags.value(0).len(), 2);
+
+        let geometry =
+            NativeArrayDyn::from_arrow_array(batch.column(2).as_ref(), batch.schema().field(2))
+                .unwrap()
+                .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry.as_ref());
+        let multi_polygon = wkb_array.value(0).parse().unwrap();
+        let GeometryType::MultiPolygon(multi_polygon) = multi_polygon.as_type() else {
+            panic!("expected a multi polygon");
+        };
+        assert_eq!(multi_polygon.num_polygons(), 1);
+        let polygon = multi_polygon.polygon(0).unwrap();
+        assert_eq!(polygon.num_interiors(), 1);
+        assert_eq!(polygon.exterior().unwrap().num_coords(), 5);
+    }
+
+    #[test]
+    fn filters() {
+        let options = OsmReaderOptions {
+            nodes: TagFilter::None,
+            ways: TagFilter::Tags(vec![("highway".to_string(), "path".to_string())]),
+            ..Default::default()
+        };
+        let layers = read_osm_pbf(Cursor::new(pbf()), options).unwrap();
+        assert!(ids(layers.nodes).is_empty());
+        assert_eq!(ids(layers.lines), [13]);
+        assert!(ids(layers.areas).is_empty());
+        // Member ways are read whatever the ways filter
+        assert_eq!(ids(layers.multipolygons), [20]);
+    }
+
+    #[test]
+    fn unsupported_feature() {
+        let mut out = vec![];
+        let header = proto::HeaderBlock {
+            required_features: vec!["HistoricalInformation".to_string()],
+        };
+        write_blob(&mut out, "OSMHeader", header.encode_to_vec(), false);
+        assert!(read_osm_pbf(Cursor::new(out), Default::default()).is_err());
+    }
+
+    #[test]
+    fn oversized_blob() {
+        let mut out = vec![];
+        write_blob(&mut out, "OSMData", vec![0; MAX_BLOB_SIZE + 1], true);
+        assert!(read_osm_pbf(Cursor::new(out), Default::default()).is_err());
+    }
+}
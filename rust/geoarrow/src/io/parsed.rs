@@ -0,0 +1,731 @@
+//! Geometries and properties parsed from text formats, and their conversion to Arrow arrays.
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_json::ReaderBuilder;
+use arrow_json::reader::infer_json_schema_from_iterator;
+use arrow_schema::{DataType, Fields, Schema, SchemaRef};
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, LineStringTrait,
+    MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+    UnimplementedLine, UnimplementedRect, UnimplementedTriangle,
+};
+use geoarrow_array::GeoArrowArray;
+use geoarrow_array::builder::{
+    GeometryBuilder, GeometryCollectionBuilder, LineStringBuilder, MultiLineStringBuilder,
+    MultiPointBuilder, MultiPolygonBuilder, PointBuilder, PolygonBuilder,
+};
+use geoarrow_schema::{
+    CoordType, Dimension, GeometryType, LineStringType, Metadata, MultiLineStringType,
+    MultiPointType, MultiPolygonType, PointType, PolygonType,
+};
+use indexmap::IndexMap;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::Value;
+
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+
+/// A position with an optional Z value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Coord {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) z: Option<f64>,
+}
+
+/// A geometry parsed from a text format.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ParsedGeometry {
+    Point(Coord),
+    LineString(Vec<Coord>),
+    Polygon(Vec<Vec<Coord>>),
+    MultiPoint(Vec<Coord>),
+    MultiLineString(Vec<Vec<Coord>>),
+    MultiPolygon(Vec<Vec<Vec<Coord>>>),
+    GeometryCollection(Vec<ParsedGeometry>),
+}
+
+impl ParsedGeometry {
+    /// Whether any position of the geometry has a Z value.
+    pub(crate) fn has_z(&self) -> bool {
+        let any_z = |coords: &[Coord]| coords.iter().any(|coord| coord.z.is_some());
+        match self {
+            Self::Point(coord) => coord.z.is_some(),
+            Self::LineString(coords) | Self::MultiPoint(coords) => any_z(coords),
+            Self::Polygon(lines) | Self::MultiLineString(lines) => {
+                lines.iter().any(|line| any_z(line))
+            }
+            Self::MultiPolygon(polygons) => polygons.iter().flatten().any(|ring| any_z(ring)),
+            Self::GeometryCollection(geometries) => geometries.iter().any(Self::has_z),
+        }
+    }
+
+    /// A view of the geometry implementing [geo_traits], with XYZ positions if `has_z`.
+    fn view(&self, has_z: bool) -> GeometryView<'_> {
+        match self {
+            Self::Point(coord) => GeometryView::Point(ParsedPoint(ParsedCoord {
+                coord: *coord,
+                has_z,
+            })),
+            Self::LineString(coords) => GeometryView::LineString(ParsedCoords { coords, has_z }),
+            Self::Polygon(lines) => GeometryView::Polygon(ParsedLines { lines, has_z }),
+            Self::MultiPoint(coords) => GeometryView::MultiPoint(ParsedCoords { coords, has_z }),
+            Self::MultiLineString(lines) => {
+                GeometryView::MultiLineString(ParsedLines { lines, has_z })
+            }
+            Self::MultiPolygon(polygons) => {
+                GeometryView::MultiPolygon(ParsedPolygons { polygons, has_z })
+            }
+            Self::GeometryCollection(geometries) => {
+                GeometryView::GeometryCollection(ParsedCollection { geometries, has_z })
+            }
+        }
+    }
+}
+
+/// Build a geometry array of `geometry_type` from parsed geometries.
+pub(crate) fn geometry_array(
+    geometries: &[Option<ParsedGeometry>],
+    geometry_type: NativeType,
+) -> Result<ArrayRef> {
+    let mut builder = ParsedGeometryBuilder::try_new(geometry_type)?;
+    for geometry in geometries {
+        builder.push(geometry.as_ref())?;
+    }
+    Ok(builder.finish())
+}
+
+/// Builds a geometry array of a given type from parsed geometries, one geometry at a time.
+///
+/// For the mixed geometry type, each geometry keeps its own dimension. Otherwise, positions
+/// without a Z value get a NaN Z value if the type has a Z dimension.
+pub(crate) struct ParsedGeometryBuilder {
+    /// Whether positions are XYZ, or `None` if each geometry keeps its own dimension.
+    has_z: Option<bool>,
+    builder: TypedBuilder,
+}
+
+enum TypedBuilder {
+    Point(PointBuilder),
+    LineString(LineStringBuilder),
+    Polygon(PolygonBuilder),
+    MultiPoint(MultiPointBuilder),
+    MultiLineString(MultiLineStringBuilder),
+    MultiPolygon(MultiPolygonBuilder),
+    GeometryCollection(GeometryCollectionBuilder),
+    Geometry(GeometryBuilder),
+}
+
+impl ParsedGeometryBuilder {
+    pub(crate) fn try_new(geometry_type: NativeType) -> Result<Self> {
+        let has_z = geometry_type.dimension().map(|dim| dim == Dimension::XYZ);
+        let builder = match geometry_type {
+            NativeType::Point(typ) => TypedBuilder::Point(PointBuilder::new(typ)),
+            NativeType::LineString(typ) => TypedBuilder::LineString(LineStringBuilder::new(typ)),
+            NativeType::Polygon(typ) => TypedBuilder::Polygon(PolygonBuilder::new(typ)),
+            NativeType::MultiPoint(typ) => TypedBuilder::MultiPoint(MultiPointBuilder::new(typ)),
+            NativeType::MultiLineString(typ) => {
+                TypedBuilder::MultiLineString(MultiLineStringBuilder::new(typ))
+            }
+            NativeType::MultiPolygon(typ) => {
+                TypedBuilder::MultiPolygon(MultiPolygonBuilder::new(typ))
+            }
+            NativeType::GeometryCollection(typ) => {
+                TypedBuilder::GeometryCollection(GeometryCollectionBuilder::new(typ))
+            }
+            NativeType::Geometry(typ) => TypedBuilder::Geometry(GeometryBuilder::new(typ)),
+            NativeType::Rect(_) => {
+                return Err(GeoArrowError::General(
+                    "Parsed geometries can't be read as rects".to_string(),
+                ));
+            }
+        };
+        Ok(Self { has_z, builder })
+    }
+
+    /// Add a geometry, or a null if `None`.
+    pub(crate) fn push(&mut self, geometry: Option<&ParsedGeometry>) -> Result<()> {
+        let view =
+            geometry.map(|geometry| geometry.view(self.has_z.unwrap_or_else(|| geometry.has_z())));
+        let view = view.as_ref();
+        match &mut self.builder {
+            TypedBuilder::Point(builder) => builder.push_geometry(view),
+            TypedBuilder::LineString(builder) => builder.push_geometry(view),
+            TypedBuilder::Polygon(builder) => builder.push_geometry(view),
+            TypedBuilder::MultiPoint(builder) => builder.push_geometry(view),
+            TypedBuilder::MultiLineString(builder) => builder.push_geometry(view),
+            TypedBuilder::MultiPolygon(builder) => builder.push_geometry(view),
+            TypedBuilder::GeometryCollection(builder) => builder.push_geometry(view),
+            TypedBuilder::Geometry(builder) => builder.push_geometry(view),
+        }
+        .map_err(|err| GeoArrowError::General(err.to_string()))
+    }
+
+    pub(crate) fn finish(self) -> ArrayRef {
+        match self.builder {
+            TypedBuilder::Point(builder) => builder.finish().into_array_ref(),
+            TypedBuilder::LineString(builder) => builder.finish().into_array_ref(),
+            TypedBuilder::Polygon(builder) => builder.finish().into_array_ref(),
+            TypedBuilder::MultiPoint(builder) => builder.finish().into_array_ref(),
+            TypedBuilder::MultiLineString(builder) => builder.finish().into_array_ref(),
+            TypedBuilder::MultiPolygon(builder) => builder.finish().into_array_ref(),
+            TypedBuilder::GeometryCollection(builder) => builder.finish().into_array_ref(),
+            TypedBuilder::Geometry(builder) => builder.finish().into_array_ref(),
+        }
+    }
+}
+
+/// A position of a [ParsedGeometry], with a NaN Z value if it has none but its geometry does.
+#[derive(Debug, Clone, Copy)]
+struct ParsedCoord {
+    coord: Coord,
+    has_z: bool,
+}
+
+fn dimensions(has_z: bool) -> Dimensions {
+    if has_z {
+        Dimensions::Xyz
+    } else {
+        Dimensions::Xy
+    }
+}
+
+impl CoordTrait for ParsedCoord {
+    type T = f64;
+
+    fn dim(&self) -> Dimensions {
+        dimensions(self.has_z)
+    }
+
+    fn nth_or_panic(&self, n: usize) -> Self::T {
+        match n {
+            0 => self.coord.x,
+            1 => self.coord.y,
+            2 if self.has_z => self.coord.z.unwrap_or(f64::NAN),
+            _ => panic!("Coordinate dimension {n} out of range"),
+        }
+    }
+
+    fn x(&self) -> Self::T {
+        self.coord.x
+    }
+
+    fn y(&self) -> Self::T {
+        self.coord.y
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ParsedPoint(ParsedCoord);
+
+impl PointTrait for ParsedPoint {
+    type T = f64;
+    type CoordType<'b> = ParsedCoord;
+
+    fn dim(&self) -> Dimensions {
+        self.0.dim()
+    }
+
+    fn coord(&self) -> Option<Self::CoordType<'_>> {
+        Some(self.0)
+    }
+}
+
+/// The positions of a line string, a ring or a multi point.
+#[derive(Debug, Clone, Copy)]
+struct ParsedCoords<'a> {
+    coords: &'a [Coord],
+    has_z: bool,
+}
+
+impl LineStringTrait for ParsedCoords<'_> {
+    type T = f64;
+    type CoordType<'b>
+        = ParsedCoord
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        dimensions(self.has_z)
+    }
+
+    fn num_coords(&self) -> usize {
+        self.coords.len()
+    }
+
+    unsafe fn coord_unchecked(&self, i: usize) -> Self::CoordType<'_> {
+        ParsedCoord {
+            coord: self.coords[i],
+            has_z: self.has_z,
+        }
+    }
+}
+
+impl MultiPointTrait for ParsedCoords<'_> {
+    type T = f64;
+    type PointType<'b>
+        = ParsedPoint
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        dimensions(self.has_z)
+    }
+
+    fn num_points(&self) -> usize {
+        self.coords.len()
+    }
+
+    unsafe fn point_unchecked(&self, i: usize) -> Self::PointType<'_> {
+        ParsedPoint(ParsedCoord {
+            coord: self.coords[i],
+            has_z: self.has_z,
+        })
+    }
+}
+
+/// The rings of a polygon or the line strings of a multi line string.
+#[derive(Debug, Clone, Copy)]
+struct ParsedLines<'a> {
+    lines: &'a [Vec<Coord>],
+    has_z: bool,
+}
+
+impl<'a> ParsedLines<'a> {
+    fn line(&self, i: usize) -> ParsedCoords<'a> {
+        ParsedCoords {
+            coords: &self.lines[i],
+            has_z: self.has_z,
+        }
+    }
+}
+
+impl<'a> PolygonTrait for ParsedLines<'a> {
+    type T = f64;
+    type RingType<'b>
+        = ParsedCoords<'a>
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        dimensions(self.has_z)
+    }
+
+    fn exterior(&self) -> Option<Self::RingType<'_>> {
+        (!self.lines.is_empty()).then(|| self.line(0))
+    }
+
+    fn num_interiors(&self) -> usize {
+        self.lines.len().saturating_sub(1)
+    }
+
+    unsafe fn interior_unchecked(&self, i: usize) -> Self::RingType<'_> {
+        self.line(i + 1)
+    }
+}
+
+impl<'a> MultiLineStringTrait for ParsedLines<'a> {
+    type T = f64;
+    type LineStringType<'b>
+        = ParsedCoords<'a>
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        dimensions(self.has_z)
+    }
+
+    fn num_line_strings(&self) -> usize {
+        self.lines.len()
+    }
+
+    unsafe fn line_string_unchecked(&self, i: usize) -> Self::LineStringType<'_> {
+        self.line(i)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ParsedPolygons<'a> {
+    polygons: &'a [Vec<Vec<Coord>>],
+    has_z: bool,
+}
+
+impl<'a> MultiPolygonTrait for ParsedPolygons<'a> {
+    type T = f64;
+    type PolygonType<'b>
+        = ParsedLines<'a>
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        dimensions(self.has_z)
+    }
+
+    fn num_polygons(&self) -> usize {
+        self.polygons.len()
+    }
+
+    unsafe fn polygon_unchecked(&self, i: usize) -> Self::PolygonType<'_> {
+        ParsedLines {
+            lines: &self.polygons[i],
+            has_z: self.has_z,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ParsedCollection<'a> {
+    geometries: &'a [ParsedGeometry],
+    has_z: bool,
+}
+
+impl<'a> GeometryCollectionTrait for ParsedCollection<'a> {
+    type T = f64;
+    type GeometryType<'b>
+        = GeometryView<'a>
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        dimensions(self.has_z)
+    }
+
+    fn num_geometries(&self) -> usize {
+        self.geometries.len()
+    }
+
+    unsafe fn geometry_unchecked(&self, i: usize) -> Self::GeometryType<'_> {
+        self.geometries[i].view(self.has_z)
+    }
+}
+
+/// A [ParsedGeometry] with a fixed dimension, which is pushed into the geometry builders.
+#[derive(Debug, Clone, Copy)]
+enum GeometryView<'a> {
+    Point(ParsedPoint),
+    LineString(ParsedCoords<'a>),
+    Polygon(ParsedLines<'a>),
+    MultiPoint(ParsedCoords<'a>),
+    MultiLineString(ParsedLines<'a>),
+    MultiPolygon(ParsedPolygons<'a>),
+    GeometryCollection(ParsedCollection<'a>),
+}
+
+impl<'a> GeometryTrait for GeometryView<'a> {
+    type T = f64;
+    type PointType<'b>
+        = ParsedPoint
+    where
+        Self: 'b;
+    type LineStringType<'b>
+        = ParsedCoords<'a>
+    where
+        Self: 'b;
+    type PolygonType<'b>
+        = ParsedLines<'a>
+    where
+        Self: 'b;
+    type MultiPointType<'b>
+        = ParsedCoords<'a>
+    where
+        Self: 'b;
+    type MultiLineStringType<'b>
+        = ParsedLines<'a>
+    where
+        Self: 'b;
+    type MultiPolygonType<'b>
+        = ParsedPolygons<'a>
+    where
+        Self: 'b;
+    type GeometryCollectionType<'b>
+        = ParsedCollection<'a>
+    where
+        Self: 'b;
+    type RectType<'b>
+        = UnimplementedRect<f64>
+    where
+        Self: 'b;
+    type TriangleType<'b>
+        = UnimplementedTriangle<f64>
+    where
+        Self: 'b;
+    type LineType<'b>
+        = UnimplementedLine<f64>
+    where
+        Self: 'b;
+
+    fn dim(&self) -> Dimensions {
+        match self {
+            Self::Point(point) => point.dim(),
+            Self::LineString(coords) | Self::MultiPoint(coords) => dimensions(coords.has_z),
+            Self::Polygon(lines) | Self::MultiLineString(lines) => dimensions(lines.has_z),
+            Self::MultiPolygon(polygons) => dimensions(polygons.has_z),
+            Self::GeometryCollection(collection) => dimensions(collection.has_z),
+        }
+    }
+
+    fn as_type(
+        &self,
+    ) -> geo_traits::GeometryType<
+        '_,
+        Self::PointType<'_>,
+        Self::LineStringType<'_>,
+        Self::PolygonType<'_>,
+        Self::MultiPointType<'_>,
+        Self::MultiLineStringType<'_>,
+        Self::MultiPolygonType<'_>,
+        Self::GeometryCollectionType<'_>,
+        Self::RectType<'_>,
+        Self::TriangleType<'_>,
+        Self::LineType<'_>,
+    > {
+        match self {
+            Self::Point(point) => geo_traits::GeometryType::Point(point),
+            Self::LineString(coords) => geo_traits::GeometryType::LineString(coords),
+            Self::Polygon(lines) => geo_traits::GeometryType::Polygon(lines),
+            Self::MultiPoint(coords) => geo_traits::GeometryType::MultiPoint(coords),
+            Self::MultiLineString(lines) => geo_traits::GeometryType::MultiLineString(lines),
+            Self::MultiPolygon(polygons) => geo_traits::GeometryType::MultiPolygon(polygons),
+            Self::GeometryCollection(collection) => {
+                geo_traits::GeometryType::GeometryCollection(collection)
+            }
+        }
+    }
+}
+
+/// The narrowest geometry type that holds all of `geometries`.
+///
+/// Single and multi geometries of the same kind are read as the multi geometry type, and
+/// geometries of different kinds as the mixed geometry type. The dimension is XYZ if any
+/// position has a Z value.
+pub(crate) fn infer_geometry_type<'a>(
+    geometries: impl IntoIterator<Item = &'a ParsedGeometry>,
+    coord_type: CoordType,
+    metadata: Arc<Metadata>,
+) -> NativeType {
+    let geometries = geometries.into_iter().collect::<Vec<_>>();
+    let dim = if geometries.iter().any(|geometry| geometry.has_z()) {
+        Dimension::XYZ
+    } else {
+        Dimension::XY
+    };
+    let all = |f: fn(&ParsedGeometry) -> bool| {
+        !geometries.is_empty() && geometries.iter().all(|geometry| f(geometry))
+    };
+
+    if all(|geometry| matches!(geometry, ParsedGeometry::Point(_))) {
+        NativeType::Point(PointType::new(coord_type, dim, metadata))
+    } else if all(|geometry| matches!(geometry, ParsedGeometry::LineString(_))) {
+        NativeType::LineString(LineStringType::new(coord_type, dim, metadata))
+    } else if all(|geometry| matches!(geometry, ParsedGeometry::Polygon(_))) {
+        NativeType::Polygon(PolygonType::new(coord_type, dim, metadata))
+    } else if all(|geometry| {
+        matches!(
+            geometry,
+            ParsedGeometry::Point(_) | ParsedGeometry::MultiPoint(_)
+        )
+    }) {
+        NativeType::MultiPoint(MultiPointType::new(coord_type, dim, metadata))
+    } else if all(|geometry| {
+        matches!(
+            geometry,
+            ParsedGeometry::LineString(_) | ParsedGeometry::MultiLineString(_)
+        )
+    }) {
+        NativeType::MultiLineString(MultiLineStringType::new(coord_type, dim, metadata))
+    } else if all(|geometry| {
+        matches!(
+            geometry,
+            ParsedGeometry::Polygon(_) | ParsedGeometry::MultiPolygon(_)
+        )
+    }) {
+        NativeType::MultiPolygon(MultiPolygonType::new(coord_type, dim, metadata))
+    } else {
+        NativeType::Geometry(GeometryType::new(coord_type, metadata))
+    }
+}
+
+/// Infer the schema of JSON property objects, as the GeoJSON reader does.
+///
+/// The columns follow the order of `key_order`, which holds the keys of the property objects.
+pub(crate) fn infer_properties_schema(rows: &[Value], key_order: &KeyOrder) -> Result<SchemaRef> {
+    let schema = infer_json_schema_from_iterator(rows.iter().map(Ok))?;
+    Ok(Arc::new(key_order.sort_schema(&schema)))
+}
+
+/// The order in which the keys of JSON objects first appear in a document.
+///
+/// The keys of [`serde_json::Map`] are sorted, so this keeps the columns inferred from JSON
+/// objects in the order of the input. The keys of nested objects are tracked under their parent
+/// key, and the objects in an array are merged.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KeyOrder(IndexMap<String, KeyOrder>);
+
+impl KeyOrder {
+    /// The order of the keys of the objects under `key`.
+    pub(crate) fn get(&self, key: &str) -> Option<&KeyOrder> {
+        self.0.get(key)
+    }
+
+    pub(crate) fn first_key(&self) -> Option<&str> {
+        self.0.keys().next().map(String::as_str)
+    }
+
+    /// Add a key after the keys seen so far, unless it was already seen.
+    pub(crate) fn insert(&mut self, key: &str) {
+        self.0.entry(key.to_string()).or_default();
+    }
+
+    /// Add a key after the keys seen so far, with the keys of its value in the order of `order`.
+    pub(crate) fn insert_nested(&mut self, key: &str, order: KeyOrder) {
+        self.0.entry(key.to_string()).or_default().merge(order);
+    }
+
+    /// Add the keys of `other` after the keys seen so far.
+    pub(crate) fn merge(&mut self, other: KeyOrder) {
+        for (key, order) in other.0 {
+            self.0.entry(key).or_default().merge(order);
+        }
+    }
+
+    /// Sort the fields of a schema, and of its nested structs, in the order of their keys.
+    ///
+    /// Fields without a key are placed last.
+    pub(crate) fn sort_schema(&self, schema: &Schema) -> Schema {
+        Schema::new_with_metadata(self.sort_fields(schema.fields()), schema.metadata().clone())
+    }
+
+    fn sort_fields(&self, fields: &Fields) -> Fields {
+        let mut fields = fields
+            .iter()
+            .map(|field| match self.get(field.name()) {
+                Some(order) => Arc::new(
+                    field
+                        .as_ref()
+                        .clone()
+                        .with_data_type(order.sort_type(field.data_type())),
+                ),
+                None => field.clone(),
+            })
+            .collect::<Vec<_>>();
+        fields.sort_by_key(|field| self.0.get_index_of(field.name()).unwrap_or(usize::MAX));
+        fields.into()
+    }
+
+    fn sort_type(&self, data_type: &DataType) -> DataType {
+        match data_type {
+            DataType::Struct(fields) => DataType::Struct(self.sort_fields(fields)),
+            DataType::List(field) => DataType::List(Arc::new(
+                field
+                    .as_ref()
+                    .clone()
+                    .with_data_type(self.sort_type(field.data_type())),
+            )),
+            data_type => data_type.clone(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyOrder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(KeyOrderVisitor)
+    }
+}
+
+struct KeyOrderVisitor;
+
+impl<'de> Visitor<'de> for KeyOrderVisitor {
+    type Value = KeyOrder;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> std::result::Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> std::result::Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> std::result::Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> std::result::Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> std::result::Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<KeyOrder, D::Error> {
+        KeyOrder::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<KeyOrder, A::Error> {
+        let mut order = KeyOrder::default();
+        while let Some(element) = seq.next_element()? {
+            order.merge(element);
+        }
+        Ok(order)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<KeyOrder, A::Error> {
+        let mut order = KeyOrder::default();
+        while let Some((key, value)) = map.next_entry::<String, KeyOrder>()? {
+            order.0.entry(key).or_default().merge(value);
+        }
+        Ok(order)
+    }
+}
+
+/// Decode JSON property objects into columns of `schema`.
+///
+/// Values are coerced to the column types where possible, so numbers can be read into string
+/// columns.
+pub(crate) fn property_columns(schema: SchemaRef, rows: &[Value]) -> Result<Vec<ArrayRef>> {
+    if schema.fields().is_empty() {
+        return Ok(vec![]);
+    }
+    let mut decoder = ReaderBuilder::new(schema.clone())
+        .with_batch_size(rows.len().max(1))
+        .with_coerce_primitive(true)
+        .build_decoder()?;
+    decoder.serialize(rows)?;
+    let batch = decoder
+        .flush()?
+        .unwrap_or_else(|| RecordBatch::new_empty(schema));
+    Ok(batch.columns().to_vec())
+}
+
+/// Build a batch from property columns followed by a geometry column.
+pub(crate) fn feature_batch(
+    properties_schema: &Schema,
+    mut columns: Vec<ArrayRef>,
+    geometries: &[Option<ParsedGeometry>],
+    geometry_type: NativeType,
+) -> Result<RecordBatch> {
+    let mut fields = properties_schema.fields().to_vec();
+    fields.push(Arc::new(geometry_type.to_field("geometry", true)));
+    columns.push(geometry_array(geometries, geometry_type)?);
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
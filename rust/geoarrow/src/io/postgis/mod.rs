@@ -1,6 +1,13 @@
-//! Read from PostGIS databases.
+//! Read from and write to PostGIS databases.
 
 mod reader;
 mod type_info;
+mod writer;
 
-pub use reader::read_postgis;
+pub use reader::{PostgisReaderOptions, read_postgis, read_postgis_stream};
+pub use writer::{PostgisWriteMode, PostgisWriterOptions, write_postgis};
+
+/// Quote a Postgres identifier such as a table or column name.
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -1,8 +1,261 @@
-//! Read from [Shapefile](https://www.esri.com/content/dam/esrisites/sitecore-archive/Files/Pdfs/library/whitepapers/pdfs/shapefile.pdf) datasets.
+//! Read from and write to [Shapefile](https://www.esri.com/content/dam/esrisites/sitecore-archive/Files/Pdfs/library/whitepapers/pdfs/shapefile.pdf) datasets.
 //!
 //! This wraps the [shapefile] crate.
 
 mod reader;
 mod scalar;
+mod writer;
 
-pub use reader::{ShapefileReaderOptions, read_shapefile};
+pub use reader::{
+    ShapefileReader, ShapefileReaderOptions, ShapefileRecordBatchReader, read_shapefile,
+};
+pub use writer::{ShapefileWriterOptions, write_shapefile};
+
+/// The size of the `.shp` and `.shx` file headers.
+const SHP_HEADER_SIZE: u64 = 100;
+
+/// The size of the header preceding each record in the `.shp` file.
+const SHP_RECORD_HEADER_SIZE: u64 = 8;
+
+/// The coordinates of a point as `[x, y, z, m]`, with a Z of 0 and an M of "no data" if they
+/// don't exist.
+type Coord = [f64; 4];
+
+/// Twice the signed area of a closed ring, which is positive for counterclockwise rings.
+fn signed_area(ring: &[Coord]) -> f64 {
+    ring.windows(2)
+        .map(|pair| pair[0][0] * pair[1][1] - pair[1][0] * pair[0][1])
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Float64Type;
+    use arrow_array::{Array, Int64Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use geoarrow_schema::{CoordType, Crs, Dimension, Metadata};
+
+    use super::*;
+    use crate::ArrayBase;
+    use crate::array::{PointArray, PointBuilder};
+    use crate::datatypes::NativeType;
+    use crate::table::Table;
+    use crate::test::point;
+
+    /// The points table, with its geometry column replaced by `point_array`.
+    fn table_with_geometry(point_array: PointArray) -> Table {
+        let table = point::table();
+        let schema = Arc::new(Schema::new(vec![
+            table.schema().field(0).clone(),
+            table.schema().field(1).clone(),
+            point_array.extension_field().as_ref().clone(),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                table.batches()[0].column(0).clone(),
+                table.batches()[0].column(1).clone(),
+                point_array.into_array_ref(),
+            ],
+        )
+        .unwrap();
+        Table::try_new(vec![batch], schema).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.shp");
+        write_shapefile(point::table(), &path, Default::default()).unwrap();
+        for extension in ["shp", "shx", "dbf", "cpg"] {
+            assert!(path.with_extension(extension).exists());
+        }
+
+        let reader = ShapefileReader::open(&path, Default::default()).unwrap();
+        let batches = reader
+            .batches()
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(
+            batch.column(0).as_primitive::<Float64Type>().values(),
+            &[1., 2., 3.]
+        );
+        assert_eq!(
+            batch
+                .column(1)
+                .as_string::<i32>()
+                .iter()
+                .collect::<Vec<_>>(),
+            [Some("foo"), Some("bar"), Some("baz")]
+        );
+        assert_eq!(batch.schema().field(2).name(), "geometry");
+    }
+
+    #[test]
+    fn batch_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.shp");
+        write_shapefile(point::table(), &path, Default::default()).unwrap();
+
+        let options = ShapefileReaderOptions {
+            batch_size: Some(2),
+            ..Default::default()
+        };
+        let reader = ShapefileReader::open(&path, options).unwrap();
+        let num_rows = reader
+            .batches()
+            .map(|batch| batch.unwrap().num_rows())
+            .collect::<Vec<_>>();
+        assert_eq!(num_rows, [2, 1]);
+    }
+
+    #[test]
+    fn split_at_max_file_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.shp");
+        // Each .dbf record takes 266 bytes, after a 97 byte header
+        let options = ShapefileWriterOptions { max_file_size: 700 };
+        write_shapefile(point::table(), &path, options).unwrap();
+
+        let num_rows = |path| {
+            let reader = ShapefileReader::open(path, Default::default()).unwrap();
+            reader
+                .batches()
+                .map(|batch| batch.unwrap().num_rows())
+                .sum::<usize>()
+        };
+        assert_eq!(num_rows(path.clone()), 2);
+        assert_eq!(num_rows(dir.path().join("points_1.shp")), 1);
+    }
+
+    #[test]
+    fn null_geometries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.shp");
+        let points = [Some(point::p0()), None, Some(point::p2())];
+        let point_array = PointBuilder::from_nullable_points(
+            points.iter().map(Option::as_ref),
+            Dimension::XY,
+            CoordType::default_interleaved(),
+            Default::default(),
+        )
+        .finish();
+        write_shapefile(table_with_geometry(point_array), &path, Default::default()).unwrap();
+
+        let reader = ShapefileReader::open(&path, Default::default()).unwrap();
+        let batches = reader
+            .batches()
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 3);
+        let geometry = batch.column(2);
+        assert!(geometry.is_valid(0));
+        assert!(geometry.is_null(1));
+        assert!(geometry.is_valid(2));
+        assert_eq!(batch.column(1).as_string::<i32>().value(2), "baz");
+    }
+
+    #[test]
+    fn prj_crs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.shp");
+        let wkt = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563]],PRIMEM["Greenwich",0],UNIT["degree",0.0174532925199433]]"#;
+        let crs = Crs::from_unknown_crs_type(wkt.to_string());
+        let point_array = PointBuilder::from_points(
+            [point::p0(), point::p1(), point::p2()].iter(),
+            Dimension::XY,
+            CoordType::default_interleaved(),
+            Arc::new(Metadata::new(crs.clone(), None)),
+        )
+        .finish();
+        write_shapefile(table_with_geometry(point_array), &path, Default::default()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path.with_extension("prj")).unwrap(),
+            wkt
+        );
+
+        let reader = ShapefileReader::open(&path, Default::default()).unwrap();
+        let geometry_type = NativeType::try_from(reader.schema().field(2).as_ref()).unwrap();
+        assert_eq!(geometry_type.metadata().crs(), &crs);
+    }
+
+    #[test]
+    fn cpg_encoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.shp");
+        write_shapefile(point::table(), &path, Default::default()).unwrap();
+
+        // Replace "bar" with "bär" encoded as windows-1252, which keeps the record length
+        let dbf_path = path.with_extension("dbf");
+        let mut dbf = std::fs::read(&dbf_path).unwrap();
+        let start = dbf.windows(3).position(|window| window == b"bar").unwrap();
+        dbf[start + 1] = 0xe4;
+        std::fs::write(&dbf_path, dbf).unwrap();
+        std::fs::write(path.with_extension("cpg"), "1252").unwrap();
+
+        let reader = ShapefileReader::open(&path, Default::default()).unwrap();
+        let batches = reader
+            .batches()
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            batches[0]
+                .column(1)
+                .as_string::<i32>()
+                .iter()
+                .collect::<Vec<_>>(),
+            [Some("foo"), Some("bär"), Some("baz")]
+        );
+    }
+
+    #[test]
+    fn int64_digits() {
+        let table = point::table();
+        let point_array = point::point_array();
+        let values = Arc::new(Int64Array::from(vec![i64::MAX, -9_007_199_254_740_993, 0]));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("big", DataType::Int64, true),
+            point_array.extension_field().as_ref().clone(),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![values, table.batches()[0].column(2).clone()],
+        )
+        .unwrap();
+        let table = Table::try_new(vec![batch], schema).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.shp");
+        write_shapefile(table, &path, Default::default()).unwrap();
+
+        // Integers are written with all of their digits, which an f64 can't hold
+        let dbf = std::fs::read(path.with_extension("dbf")).unwrap();
+        for digits in [b"9223372036854775807".as_slice(), b"-9007199254740993"] {
+            assert!(dbf.windows(digits.len()).any(|window| window == digits));
+        }
+    }
+
+    #[test]
+    fn truncated_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.shp");
+        write_shapefile(point::table(), &path, Default::default()).unwrap();
+
+        let mut shp = std::fs::read(&path).unwrap();
+        // Claim that the first record's content is far longer than the file
+        shp[SHP_HEADER_SIZE as usize + 4..SHP_HEADER_SIZE as usize + 8]
+            .copy_from_slice(&i32::MAX.to_be_bytes());
+        std::fs::write(&path, shp).unwrap();
+
+        let reader = ShapefileReader::open(&path, Default::default()).unwrap();
+        let err = reader.batches().next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("extends past the end of the file"));
+    }
+}
@@ -1,19 +1,32 @@
-use std::io::{Read, Seek};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
 use std::sync::Arc;
 
-use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use arrow_array::{RecordBatch, RecordBatchReader};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
 use dbase::{FieldInfo, FieldType, FieldValue, Record};
-use geoarrow_schema::{CoordType, Crs, Dimension, Metadata};
-use geozero::FeatureProcessor;
-use shapefile::{Reader, ShapeReader, ShapeType};
-
+use geoarrow_schema::{
+    CoordType, Crs, Dimension, Metadata, MultiLineStringType, MultiPointType, MultiPolygonType,
+    PointType,
+};
+use shapefile::{
+    Multipoint, MultipointZ, NO_DATA, Point, PointZ, Polygon, PolygonRing, PolygonZ, Polyline,
+    PolylineZ, Shape,
+};
+
+use super::{Coord, SHP_HEADER_SIZE, SHP_RECORD_HEADER_SIZE, signed_area};
+use crate::ArrayBase;
 use crate::array::{MultiLineStringBuilder, MultiPointBuilder, MultiPolygonBuilder, PointBuilder};
+use crate::datatypes::NativeType;
 use crate::error::{GeoArrowError, Result};
 use crate::io::geozero::table::builder::anyvalue::AnyBuilder;
 use crate::io::geozero::table::builder::properties::PropertiesBatchBuilder;
-use crate::io::geozero::table::{GeoTableBuilder, GeoTableBuilderOptions};
 use crate::table::Table;
 
+/// The number of rows in each batch when [ShapefileReaderOptions::batch_size] is not set.
+const DEFAULT_BATCH_SIZE: usize = 65_536;
+
 /// Options for the Shapefile reader
 #[derive(Debug, Clone)]
 pub struct ShapefileReaderOptions {
@@ -23,277 +36,546 @@ pub struct ShapefileReaderOptions {
     /// The number of rows in each batch.
     pub batch_size: Option<usize>,
 
-    /// The CRS to assign to the file. Read this from the `.prj` file in the same directory with
-    /// the same name.
+    /// The CRS to assign to the file.
+    ///
+    /// When opening a Shapefile with [ShapefileReader::open], this defaults to the contents of
+    /// the `.prj` file in the same directory with the same name.
     pub crs: Option<String>,
-}
-
-// TODO:
-// stretch goal: return a record batch reader.
-/// Read a Shapefile into a [Table].
-pub fn read_shapefile<T: Read + Seek>(
-    shp_reader: T,
-    dbf_reader: T,
-    options: ShapefileReaderOptions,
-) -> Result<Table> {
-    let dbf_reader = dbase::Reader::new(dbf_reader).unwrap();
-    let shp_reader = ShapeReader::new(shp_reader).unwrap();
-
-    let header = shp_reader.header();
 
-    let dbf_fields = dbf_reader.fields().to_vec();
-    let schema = infer_schema(&dbf_fields);
-    let geometry_type = header.shape_type;
-
-    let features_count = dbf_reader.header().num_records as usize;
-    let features_count = if features_count > 0 {
-        Some(features_count)
-    } else {
-        None
-    };
+    /// The label of the character encoding of the `.dbf` file, such as `"UTF-8"` or
+    /// `"windows-1252"`. Bare code page numbers such as `"1252"` are also accepted.
+    ///
+    /// When opening a Shapefile with [ShapefileReader::open], this defaults to the contents of
+    /// the `.cpg` file in the same directory with the same name. Otherwise the `.dbf` is decoded
+    /// as UTF-8.
+    pub encoding: Option<String>,
+}
 
-    let crs = options
-        .crs
-        .map(Crs::from_unknown_crs_type)
-        .unwrap_or_default();
+impl Default for ShapefileReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: None,
+            crs: None,
+            encoding: None,
+        }
+    }
+}
 
-    let table_builder_options = GeoTableBuilderOptions::new(
-        options.coord_type,
-        true,
-        options.batch_size,
-        Some(schema),
-        features_count,
-        Arc::new(Metadata::new(crs, None)),
-    );
+/// A reader for [Shapefile](https://en.wikipedia.org/wiki/Shapefile) datasets.
+///
+/// Call [`batches`][Self::batches] to iterate over the file as Arrow record batches.
+pub struct ShapefileReader<T: Read + Seek, D: Read + Seek> {
+    batches: ShapefileRecordBatchReader<T, D>,
+}
 
-    let mut reader = Reader::new(shp_reader, dbf_reader);
+impl ShapefileReader<BufReader<File>, BufReader<File>> {
+    /// Open the Shapefile at the given path to its `.shp` file.
+    ///
+    /// The `.dbf` file, and the `.prj` and `.cpg` files if they exist, are read from the same
+    /// directory with the same name.
+    pub fn open(path: impl AsRef<Path>, mut options: ShapefileReaderOptions) -> Result<Self> {
+        let path = path.as_ref();
+        if options.crs.is_none() {
+            options.crs = read_sidecar(path, "prj")?;
+        }
+        if options.encoding.is_none() {
+            options.encoding = read_sidecar(path, "cpg")?;
+        }
 
-    // TODO: these might work in a macro
+        let shp_reader = BufReader::new(File::open(path)?);
+        let dbf_reader = BufReader::new(File::open(path.with_extension("dbf"))?);
+        Self::try_new(shp_reader, dbf_reader, options)
+    }
+}
 
-    match geometry_type {
-        ShapeType::Point => {
-            let mut builder = GeoTableBuilder::<PointBuilder>::new_with_options(
-                Dimension::XY,
-                table_builder_options,
-            );
+impl<T: Read + Seek, D: Read + Seek> ShapefileReader<T, D> {
+    /// Create a reader from the contents of the `.shp` and `.dbf` files.
+    pub fn try_new(
+        mut shp_reader: T,
+        dbf_reader: D,
+        options: ShapefileReaderOptions,
+    ) -> Result<Self> {
+        let mut header = [0; SHP_HEADER_SIZE as usize];
+        shp_reader.read_exact(&mut header)?;
+        // The file code and length are big endian, and the length is given in 16-bit words
+        if read_i32_be(&header, 0) != 9994 {
+            return Err(GeoArrowError::General(
+                "Invalid Shapefile header".to_string(),
+            ));
+        }
+        let shp_size = 2 * u64::from(read_i32_be(&header, 24) as u32);
+        // A truncated file is read up to its actual end
+        let file_size = shp_reader.seek(SeekFrom::End(0))?;
+        shp_reader.seek(SeekFrom::Start(SHP_HEADER_SIZE))?;
+        let shp_size = shp_size.min(file_size);
+        let shape_type = read_i32_le(&header, 32);
+
+        let dbf_reader = dbase_reader(dbf_reader, options.encoding.as_deref())?;
+        let dbf_fields = dbf_reader.fields().to_vec();
+        let properties_schema = infer_schema(&dbf_fields);
+
+        let crs = options
+            .crs
+            .map(Crs::from_unknown_crs_type)
+            .unwrap_or_default();
+        let metadata = Arc::new(Metadata::new(crs, None));
+        let geometry_type = geometry_type(shape_type, options.coord_type, metadata)?;
+
+        let mut fields = properties_schema.fields().to_vec();
+        fields.push(geometry_type.to_field("geometry", true).into());
+        let schema = Arc::new(Schema::new(fields));
+
+        Ok(Self {
+            batches: ShapefileRecordBatchReader {
+                shp_reader,
+                dbf_reader,
+                shp_position: SHP_HEADER_SIZE,
+                shp_size,
+                dbf_fields,
+                properties_schema,
+                schema,
+                geometry_type,
+                batch_size: options.batch_size.unwrap_or(DEFAULT_BATCH_SIZE),
+            },
+        })
+    }
 
-            for geom_and_record in
-                reader.iter_shapes_and_records_as::<shapefile::Point, dbase::Record>()
-            {
-                let (geom, record) = geom_and_record.unwrap();
+    /// The Arrow schema of the record batches emitted by this reader.
+    pub fn schema(&self) -> SchemaRef {
+        self.batches.schema.clone()
+    }
 
-                // Process properties
-                let prop_builder = builder.properties_builder_mut();
-                prop_builder.add_record(record, &dbf_fields)?;
+    /// Iterate over the features of this Shapefile as record batches.
+    pub fn batches(self) -> ShapefileRecordBatchReader<T, D> {
+        self.batches
+    }
+}
 
-                // Hack to advance internal row number
-                builder.properties_end()?;
+/// An iterator over the record batches of a [ShapefileReader].
+///
+/// The reader owns the `.shp` and `.dbf` sources, and reads their records in order.
+pub struct ShapefileRecordBatchReader<T: Read + Seek, D: Read + Seek> {
+    shp_reader: T,
+    dbf_reader: dbase::Reader<D>,
+    shp_position: u64,
+    shp_size: u64,
+    dbf_fields: Vec<FieldInfo>,
+    properties_schema: SchemaRef,
+    schema: SchemaRef,
+    geometry_type: NativeType,
+    batch_size: usize,
+}
 
-                let geom = super::scalar::Point::new(&geom);
-                builder.geom_builder().push_point(Some(&geom));
+impl<T: Read + Seek, D: Read + Seek> ShapefileRecordBatchReader<T, D> {
+    /// Read the next record of the `.shp` file, if any.
+    fn next_shape(&mut self) -> Result<Option<Shape>> {
+        if self.shp_position + SHP_RECORD_HEADER_SIZE > self.shp_size {
+            return Ok(None);
+        }
+        // The record number and the content length in 16-bit words are big endian
+        let mut record_header = [0; SHP_RECORD_HEADER_SIZE as usize];
+        self.shp_reader.read_exact(&mut record_header)?;
+        let content_length = 2 * u64::from(read_i32_be(&record_header, 4) as u32);
+        // Check the length before allocating, so that a corrupt header can't request more memory
+        // than the file holds
+        if self.shp_position + SHP_RECORD_HEADER_SIZE + content_length > self.shp_size {
+            return Err(GeoArrowError::General(format!(
+                "Shapefile record at byte {} with {} bytes of content extends past the end of the file",
+                self.shp_position, content_length
+            )));
+        }
+        let mut content = vec![0; content_length as usize];
+        self.shp_reader.read_exact(&mut content)?;
+        self.shp_position += SHP_RECORD_HEADER_SIZE + content_length;
+        decode_shape(&content).map(Some)
+    }
 
-                // Hack to advance internal row number
-                builder.feature_end(0)?;
-            }
-            builder.finish()
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let mut shapes = vec![];
+        while shapes.len() < self.batch_size {
+            let Some(shape) = self.next_shape()? else {
+                break;
+            };
+            shapes.push(shape);
         }
-        ShapeType::PointZ => {
-            let mut builder = GeoTableBuilder::<PointBuilder>::new_with_options(
-                Dimension::XYZ,
-                table_builder_options,
-            );
 
-            for geom_and_record in
-                reader.iter_shapes_and_records_as::<shapefile::PointZ, dbase::Record>()
-            {
-                let (geom, record) = geom_and_record.unwrap();
+        if shapes.is_empty() {
+            return Ok(None);
+        }
 
-                // Process properties
-                let prop_builder = builder.properties_builder_mut();
-                prop_builder.add_record(record, &dbf_fields)?;
+        let mut properties =
+            PropertiesBatchBuilder::from_schema_with_capacity(&self.properties_schema, 0);
+        let mut geometries = GeometryBuilder::new(&self.geometry_type);
+        // Each record iterator continues from where the previous batch stopped
+        let mut records = self.dbf_reader.iter_records();
+        for shape in shapes {
+            let record = records
+                .next()
+                .ok_or_else(|| {
+                    GeoArrowError::General(
+                        "The .dbf file has fewer records than the .shp file".to_string(),
+                    )
+                })?
+                .map_err(|err| GeoArrowError::General(format!("Invalid .dbf record: {err}")))?;
+            properties.add_record(record, &self.dbf_fields)?;
+            geometries.push_shape(shape)?;
+        }
 
-                // Hack to advance internal row number
-                builder.properties_end()?;
+        let mut columns = properties.finish()?.columns().to_vec();
+        columns.push(geometries.finish());
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), columns)?))
+    }
+}
 
-                let geom = super::scalar::PointZ::new(&geom);
-                builder.geom_builder().push_point(Some(&geom));
+impl<T: Read + Seek, D: Read + Seek> Iterator for ShapefileRecordBatchReader<T, D> {
+    type Item = std::result::Result<RecordBatch, ArrowError>;
 
-                // Hack to advance internal row number
-                builder.feature_end(0)?;
-            }
-            builder.finish()
-        }
-        ShapeType::Multipoint => {
-            let mut builder = GeoTableBuilder::<MultiPointBuilder>::new_with_options(
-                Dimension::XY,
-                table_builder_options,
-            );
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch().map_err(ArrowError::from).transpose()
+    }
+}
 
-            for geom_and_record in
-                reader.iter_shapes_and_records_as::<shapefile::Multipoint, dbase::Record>()
-            {
-                let (geom, record) = geom_and_record.unwrap();
+impl<T: Read + Seek, D: Read + Seek> RecordBatchReader for ShapefileRecordBatchReader<T, D> {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
 
-                // Process properties
-                let prop_builder = builder.properties_builder_mut();
-                prop_builder.add_record(record, &dbf_fields)?;
+/// Read a Shapefile into a [Table].
+pub fn read_shapefile<T: Read + Seek>(
+    shp_reader: T,
+    dbf_reader: T,
+    options: ShapefileReaderOptions,
+) -> Result<Table> {
+    let reader = ShapefileReader::try_new(shp_reader, dbf_reader, options)?;
+    let schema = reader.schema();
+    let batches = reader
+        .batches()
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Table::try_new(batches, schema)
+}
 
-                // Hack to advance internal row number
-                builder.properties_end()?;
+/// Read the contents of a file next to the `.shp`, such as the `.prj`, if it exists.
+fn read_sidecar(path: &Path, extension: &str) -> Result<Option<String>> {
+    match std::fs::read_to_string(path.with_extension(extension)) {
+        Ok(content) => Ok(Some(content.trim().to_string())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
 
-                let geom = super::scalar::MultiPoint::new(&geom);
-                builder.geom_builder().push_multi_point(Some(&geom))?;
+/// Create a `.dbf` reader decoding text with the encoding of the given label.
+fn dbase_reader<D: Read + Seek>(source: D, encoding: Option<&str>) -> Result<dbase::Reader<D>> {
+    let Some(label) = encoding.map(str::trim) else {
+        return Ok(dbase::Reader::new(source)?);
+    };
 
-                // Hack to advance internal row number
-                builder.feature_end(0)?;
-            }
-            builder.finish()
-        }
-        ShapeType::MultipointZ => {
-            let mut builder = GeoTableBuilder::<MultiPointBuilder>::new_with_options(
-                Dimension::XYZ,
-                table_builder_options,
-            );
+    // .cpg files often contain a bare code page number
+    let encoding = if label.chars().all(|c| c.is_ascii_digit()) {
+        encoding_rs::Encoding::for_label(format!("windows-{label}").as_bytes())
+            .or_else(|| encoding_rs::Encoding::for_label(format!("cp{label}").as_bytes()))
+    } else {
+        encoding_rs::Encoding::for_label(label.as_bytes())
+    }
+    .ok_or_else(|| GeoArrowError::General(format!("Unknown Shapefile encoding {}", label)))?;
 
-            for geom_and_record in
-                reader.iter_shapes_and_records_as::<shapefile::MultipointZ, dbase::Record>()
-            {
-                let (geom, record) = geom_and_record.unwrap();
+    if encoding == encoding_rs::UTF_8 {
+        Ok(dbase::Reader::new(source)?)
+    } else {
+        Ok(dbase::Reader::new_with_encoding(
+            source,
+            dbase::encoding::EncodingRs::from(encoding),
+        )?)
+    }
+}
 
-                // Process properties
-                let prop_builder = builder.properties_builder_mut();
-                prop_builder.add_record(record, &dbf_fields)?;
+fn read_i32_be(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_be_bytes(std::array::from_fn(|i| bytes[offset + i]))
+}
 
-                // Hack to advance internal row number
-                builder.properties_end()?;
+fn read_i32_le(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(std::array::from_fn(|i| bytes[offset + i]))
+}
 
-                let geom = super::scalar::MultiPointZ::new(&geom);
-                builder.geom_builder().push_multi_point(Some(&geom))?;
+/// A cursor over the contents of a `.shp` record.
+struct ShapeContent<'a>(&'a [u8]);
 
-                // Hack to advance internal row number
-                builder.feature_end(0)?;
-            }
-            builder.finish()
+impl<'a> ShapeContent<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(GeoArrowError::General(
+                "Truncated Shapefile record".to_string(),
+            ));
         }
-        ShapeType::Polyline => {
-            let mut builder = GeoTableBuilder::<MultiLineStringBuilder>::new_with_options(
-                Dimension::XY,
-                table_builder_options,
-            );
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
 
-            for geom_and_record in
-                reader.iter_shapes_and_records_as::<shapefile::Polyline, dbase::Record>()
-            {
-                let (geom, record) = geom_and_record.unwrap();
+    fn i32(&mut self) -> Result<i32> {
+        Ok(read_i32_le(self.take(4)?, 0))
+    }
 
-                // Process properties
-                let prop_builder = builder.properties_builder_mut();
-                prop_builder.add_record(record, &dbf_fields)?;
+    fn count(&mut self) -> Result<usize> {
+        let count = self.i32()?;
+        usize::try_from(count).map_err(|_| {
+            GeoArrowError::General(format!("Invalid count {count} in Shapefile record"))
+        })
+    }
 
-                // Hack to advance internal row number
-                builder.properties_end()?;
+    fn f64s(&mut self, len: usize) -> Result<Vec<f64>> {
+        let bytes = self.take(len.saturating_mul(8))?;
+        Ok(bytes
+            .chunks_exact(8)
+            .map(|value| f64::from_le_bytes(std::array::from_fn(|i| value[i])))
+            .collect())
+    }
 
-                let geom = super::scalar::Polyline::new(&geom);
-                builder.geom_builder().push_multi_line_string(Some(&geom))?;
+    fn f64(&mut self) -> Result<f64> {
+        Ok(self.f64s(1)?[0])
+    }
 
-                // Hack to advance internal row number
-                builder.feature_end(0)?;
+    /// Read the coordinates of multi points and multi-part shapes.
+    ///
+    /// The X and Y values are followed by the Z range and values for Z shapes, and then by the
+    /// optional M range and values.
+    fn coords(&mut self, len: usize, z: bool) -> Result<Vec<Coord>> {
+        let xy = self.f64s(len.saturating_mul(2))?;
+        let mut coords = xy
+            .chunks_exact(2)
+            .map(|xy| [xy[0], xy[1], 0., NO_DATA])
+            .collect::<Vec<_>>();
+        if z {
+            for dim in [2, 3] {
+                if dim == 3 && self.0.is_empty() {
+                    break;
+                }
+                self.take(16)?;
+                for (coord, value) in coords.iter_mut().zip(self.f64s(len)?) {
+                    coord[dim] = value;
+                }
             }
-            builder.finish()
         }
-        ShapeType::PolylineZ => {
-            let mut builder = GeoTableBuilder::<MultiLineStringBuilder>::new_with_options(
-                Dimension::XYZ,
-                table_builder_options,
-            );
-
-            for geom_and_record in
-                reader.iter_shapes_and_records_as::<shapefile::PolylineZ, dbase::Record>()
-            {
-                let (geom, record) = geom_and_record.unwrap();
-
-                // Process properties
-                let prop_builder = builder.properties_builder_mut();
-                prop_builder.add_record(record, &dbf_fields)?;
-
-                // Hack to advance internal row number
-                builder.properties_end()?;
-
-                let geom = super::scalar::PolylineZ::new(&geom);
-                builder.geom_builder().push_multi_line_string(Some(&geom))?;
+        Ok(coords)
+    }
+}
 
-                // Hack to advance internal row number
-                builder.feature_end(0)?;
+/// Decode the contents of a `.shp` record.
+fn decode_shape(content: &[u8]) -> Result<Shape> {
+    let mut content = ShapeContent(content);
+    let shape_type = content.i32()?;
+    let z = shape_type > 10;
+    let shape = match shape_type {
+        0 => Shape::NullShape,
+        1 => Shape::Point(Point::new(content.f64()?, content.f64()?)),
+        11 => {
+            let [x, y, z] = [content.f64()?, content.f64()?, content.f64()?];
+            // The M value is optional
+            let m = if content.0.is_empty() {
+                NO_DATA
+            } else {
+                content.f64()?
+            };
+            Shape::PointZ(PointZ::new(x, y, z, m))
+        }
+        8 | 18 => {
+            // Skip the bounding box
+            content.take(32)?;
+            let num_points = content.count()?;
+            let coords = content.coords(num_points, z)?;
+            if z {
+                Shape::MultipointZ(MultipointZ::new(
+                    coords.into_iter().map(to_point_z).collect(),
+                ))
+            } else {
+                Shape::Multipoint(Multipoint::new(coords.into_iter().map(to_point).collect()))
             }
-            builder.finish()
         }
-        ShapeType::Polygon => {
-            let mut builder = GeoTableBuilder::<MultiPolygonBuilder>::new_with_options(
-                Dimension::XY,
-                table_builder_options,
-            );
-
-            for geom_and_record in
-                reader.iter_shapes_and_records_as::<shapefile::Polygon, dbase::Record>()
-            {
-                let (geom, record) = geom_and_record.unwrap();
+        3 | 5 | 13 | 15 => {
+            content.take(32)?;
+            let num_parts = content.count()?;
+            let num_points = content.count()?;
+            let mut offsets = (0..num_parts)
+                .map(|_| content.count())
+                .collect::<Result<Vec<_>>>()?;
+            offsets.push(num_points);
+            let coords = content.coords(num_points, z)?;
+            let parts = offsets
+                .windows(2)
+                .map(|range| {
+                    coords.get(range[0]..range[1]).ok_or_else(|| {
+                        GeoArrowError::General("Invalid part offsets in Shapefile".to_string())
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            match shape_type {
+                3 => Shape::Polyline(Polyline::with_parts(polyline_parts(&parts, to_point)?)),
+                13 => Shape::PolylineZ(PolylineZ::with_parts(polyline_parts(&parts, to_point_z)?)),
+                5 => Shape::Polygon(Polygon::with_rings(polygon_rings(&parts, to_point))),
+                _ => Shape::PolygonZ(PolygonZ::with_rings(polygon_rings(&parts, to_point_z))),
+            }
+        }
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Unsupported Shapefile shape type {shape_type}"
+            )));
+        }
+    };
+    Ok(shape)
+}
 
-                // Process properties
-                let prop_builder = builder.properties_builder_mut();
-                prop_builder.add_record(record, &dbf_fields)?;
+fn to_point([x, y, _, _]: Coord) -> Point {
+    Point::new(x, y)
+}
 
-                // Hack to advance internal row number
-                builder.properties_end()?;
+fn to_point_z([x, y, z, m]: Coord) -> PointZ {
+    PointZ::new(x, y, z, m)
+}
 
-                let geom = super::scalar::MultiPolygon::new(geom);
-                builder.geom_builder().push_multi_polygon(Some(&geom))?;
+fn polyline_parts<P>(parts: &[&[Coord]], to_point: fn(Coord) -> P) -> Result<Vec<Vec<P>>> {
+    if parts.iter().any(|part| part.len() < 2) {
+        return Err(GeoArrowError::General(
+            "Shapefile polyline parts must have two or more points".to_string(),
+        ));
+    }
+    Ok(parts
+        .iter()
+        .map(|part| part.iter().copied().map(to_point).collect())
+        .collect())
+}
 
-                // Hack to advance internal row number
-                builder.feature_end(0)?;
+/// The rings of a polygon, where clockwise rings are exterior rings.
+fn polygon_rings<P>(rings: &[&[Coord]], to_point: fn(Coord) -> P) -> Vec<PolygonRing<P>> {
+    rings
+        .iter()
+        .map(|ring| {
+            let points = ring.iter().copied().map(to_point).collect();
+            if signed_area(ring) > 0. {
+                PolygonRing::Inner(points)
+            } else {
+                PolygonRing::Outer(points)
             }
-            builder.finish()
-        }
-        ShapeType::PolygonZ => {
-            let mut builder = GeoTableBuilder::<MultiPolygonBuilder>::new_with_options(
-                Dimension::XYZ,
-                table_builder_options,
-            );
+        })
+        .collect()
+}
 
-            for geom_and_record in
-                reader.iter_shapes_and_records_as::<shapefile::PolygonZ, dbase::Record>()
-            {
-                let (geom, record) = geom_and_record.unwrap();
+/// The GeoArrow type used to read a Shapefile shape type.
+///
+/// Polylines and polygons are always read as multi geometries, because a single shape may contain
+/// several parts.
+fn geometry_type(
+    shape_type: i32,
+    coord_type: CoordType,
+    metadata: Arc<Metadata>,
+) -> Result<NativeType> {
+    let geometry_type = match shape_type {
+        1 => NativeType::Point(PointType::new(coord_type, Dimension::XY, metadata)),
+        11 => NativeType::Point(PointType::new(coord_type, Dimension::XYZ, metadata)),
+        8 => NativeType::MultiPoint(MultiPointType::new(coord_type, Dimension::XY, metadata)),
+        18 => NativeType::MultiPoint(MultiPointType::new(coord_type, Dimension::XYZ, metadata)),
+        3 => NativeType::MultiLineString(MultiLineStringType::new(
+            coord_type,
+            Dimension::XY,
+            metadata,
+        )),
+        13 => NativeType::MultiLineString(MultiLineStringType::new(
+            coord_type,
+            Dimension::XYZ,
+            metadata,
+        )),
+        5 => NativeType::MultiPolygon(MultiPolygonType::new(coord_type, Dimension::XY, metadata)),
+        15 => NativeType::MultiPolygon(MultiPolygonType::new(coord_type, Dimension::XYZ, metadata)),
+        t => {
+            return Err(GeoArrowError::General(format!(
+                "Unsupported shapefile geometry type: {}",
+                t
+            )));
+        }
+    };
+    Ok(geometry_type)
+}
 
-                // Process properties
-                let prop_builder = builder.properties_builder_mut();
-                prop_builder.add_record(record, &dbf_fields)?;
+/// A builder for the geometries of a single batch.
+enum GeometryBuilder {
+    Point(PointBuilder),
+    MultiPoint(MultiPointBuilder),
+    MultiLineString(MultiLineStringBuilder),
+    MultiPolygon(MultiPolygonBuilder),
+}
 
-                // Hack to advance internal row number
-                builder.properties_end()?;
+impl GeometryBuilder {
+    fn new(geometry_type: &NativeType) -> Self {
+        let coord_type = geometry_type.coord_type();
+        let dim = geometry_type.dimension().unwrap_or(Dimension::XY);
+        let metadata = geometry_type.metadata().clone();
+        match geometry_type {
+            NativeType::Point(_) => {
+                Self::Point(PointBuilder::new_with_options(dim, coord_type, metadata))
+            }
+            NativeType::MultiPoint(_) => Self::MultiPoint(MultiPointBuilder::new_with_options(
+                dim, coord_type, metadata,
+            )),
+            NativeType::MultiLineString(_) => Self::MultiLineString(
+                MultiLineStringBuilder::new_with_options(dim, coord_type, metadata),
+            ),
+            // geometry_type only returns the above types and multi polygons
+            _ => Self::MultiPolygon(MultiPolygonBuilder::new_with_options(
+                dim, coord_type, metadata,
+            )),
+        }
+    }
 
-                let geom = super::scalar::MultiPolygonZ::new(geom);
-                builder.geom_builder().push_multi_polygon(Some(&geom))?;
+    fn push_shape(&mut self, shape: Shape) -> Result<()> {
+        use super::scalar;
 
-                // Hack to advance internal row number
-                builder.feature_end(0)?;
+        match (self, shape) {
+            (Self::Point(builder), Shape::NullShape) => builder.push_null(),
+            (Self::MultiPoint(builder), Shape::NullShape) => builder.push_null(),
+            (Self::MultiLineString(builder), Shape::NullShape) => builder.push_null(),
+            (Self::MultiPolygon(builder), Shape::NullShape) => builder.push_null(),
+            (Self::Point(builder), Shape::Point(geom)) => {
+                builder.push_point(Some(&scalar::Point::new(&geom)))
+            }
+            (Self::Point(builder), Shape::PointZ(geom)) => {
+                builder.push_point(Some(&scalar::PointZ::new(&geom)))
+            }
+            (Self::MultiPoint(builder), Shape::Multipoint(geom)) => {
+                builder.push_multi_point(Some(&scalar::MultiPoint::new(&geom)))?
+            }
+            (Self::MultiPoint(builder), Shape::MultipointZ(geom)) => {
+                builder.push_multi_point(Some(&scalar::MultiPointZ::new(&geom)))?
+            }
+            (Self::MultiLineString(builder), Shape::Polyline(geom)) => {
+                builder.push_multi_line_string(Some(&scalar::Polyline::new(&geom)))?
+            }
+            (Self::MultiLineString(builder), Shape::PolylineZ(geom)) => {
+                builder.push_multi_line_string(Some(&scalar::PolylineZ::new(&geom)))?
+            }
+            (Self::MultiPolygon(builder), Shape::Polygon(geom)) => {
+                builder.push_multi_polygon(Some(&scalar::MultiPolygon::try_new(geom)?))?
+            }
+            (Self::MultiPolygon(builder), Shape::PolygonZ(geom)) => {
+                builder.push_multi_polygon(Some(&scalar::MultiPolygonZ::try_new(geom)?))?
+            }
+            (_, shape) => {
+                return Err(GeoArrowError::General(format!(
+                    "Unexpected shape type {} in Shapefile",
+                    shape.shapetype()
+                )));
             }
-            builder.finish()
         }
-        t => Err(GeoArrowError::General(format!(
-            "Unsupported shapefile geometry type: {}",
-            t
-        ))),
+        Ok(())
     }
-    // ?;
-
-    // // Assign CRS onto the table
-    // let geom_col_idx = table.default_geometry_column_idx()?;
-    // let col = table.geometry_column(Some(geom_col_idx))?;
-    // let field = col.data_type().to_field_with_metadata("geometry", true, &array_metadata);
 
-    // table.remove_column(geom_col_idx);
-    // table.append_column(field.into(), col.array_refs())?;
-    // Ok(table)
+    fn finish(self) -> arrow_array::ArrayRef {
+        match self {
+            Self::Point(builder) => builder.finish().into_array_ref(),
+            Self::MultiPoint(builder) => builder.finish().into_array_ref(),
+            Self::MultiLineString(builder) => builder.finish().into_array_ref(),
+            Self::MultiPolygon(builder) => builder.finish().into_array_ref(),
+        }
+    }
 }
 
 impl PropertiesBatchBuilder {
@@ -322,21 +604,30 @@ impl PropertiesBatchBuilder {
 
 impl AnyBuilder {
     fn add_field_value(&mut self, value: &FieldValue) -> Result<()> {
+        let unexpected_type =
+            || GeoArrowError::General(format!("Unexpected dBase value {:?}", value));
+
         match value {
             FieldValue::Character(v) => {
                 if let Some(v) = v {
-                    self.as_string_mut().unwrap().append_value(v);
+                    self.as_string_mut()
+                        .ok_or_else(unexpected_type)?
+                        .append_value(v);
                 } else {
                     self.append_null();
                 }
             }
             FieldValue::Currency(v) | FieldValue::Double(v) => {
-                self.as_float64_mut().unwrap().append_value(*v);
+                self.as_float64_mut()
+                    .ok_or_else(unexpected_type)?
+                    .append_value(*v);
             }
             FieldValue::Date(v) => {
                 if let Some(v) = v {
                     let unix_days = v.to_unix_days();
-                    self.as_date32_mut().unwrap().append_value(unix_days);
+                    self.as_date32_mut()
+                        .ok_or_else(unexpected_type)?
+                        .append_value(unix_days);
                 } else {
                     self.append_null();
                 }
@@ -346,33 +637,43 @@ impl AnyBuilder {
                 // seconds to microseconds
                 let unix_timestamp_us = unix_timestamp_s * 1000 * 1000;
                 self.as_date_time_mut()
-                    .unwrap()
+                    .ok_or_else(unexpected_type)?
                     .0
                     .append_value(unix_timestamp_us);
             }
             FieldValue::Float(v) => {
                 if let Some(v) = v {
-                    self.as_float32_mut().unwrap().append_value(*v);
+                    self.as_float32_mut()
+                        .ok_or_else(unexpected_type)?
+                        .append_value(*v);
                 } else {
                     self.append_null();
                 }
             }
             FieldValue::Integer(v) => {
-                self.as_int32_mut().unwrap().append_value(*v);
+                self.as_int32_mut()
+                    .ok_or_else(unexpected_type)?
+                    .append_value(*v);
             }
             FieldValue::Logical(v) => {
                 if let Some(v) = v {
-                    self.as_bool_mut().unwrap().append_value(*v);
+                    self.as_bool_mut()
+                        .ok_or_else(unexpected_type)?
+                        .append_value(*v);
                 } else {
                     self.append_null();
                 }
             }
             FieldValue::Memo(v) => {
-                self.as_string_mut().unwrap().append_value(v);
+                self.as_string_mut()
+                    .ok_or_else(unexpected_type)?
+                    .append_value(v);
             }
             FieldValue::Numeric(v) => {
                 if let Some(v) = v {
-                    self.as_float64_mut().unwrap().append_value(*v);
+                    self.as_float64_mut()
+                        .ok_or_else(unexpected_type)?
+                        .append_value(*v);
                 } else {
                     self.append_null();
                 }
@@ -398,9 +699,9 @@ fn infer_schema(fields: &[FieldInfo]) -> SchemaRef {
             FieldType::Date => Field::new(name, DataType::Date32, true),
             FieldType::DateTime => Field::new(
                 name,
-                // The dbase DateTime only stores data at second precision, but we currently build
-                // millisecond arrays, because that's our existing code path
-                DataType::Timestamp(arrow_schema::TimeUnit::Millisecond, None),
+                // The dbase DateTime only stores data at second precision, but the properties
+                // builder always creates microsecond arrays
+                DataType::Timestamp(TimeUnit::Microsecond, None),
                 true,
             ),
         };
@@ -5,6 +5,8 @@ use geo_traits::{
     PointTrait, PolygonTrait,
 };
 
+use crate::error::{GeoArrowError, Result};
+
 pub(super) struct Point<'a>(&'a shapefile::Point);
 
 impl<'a> Point<'a> {
@@ -334,7 +336,7 @@ pub(super) struct MultiPolygon(Vec<Polygon>);
 impl MultiPolygon {
     /// This is ported from the geo-types From impl
     /// https://github.com/tmontaigu/shapefile-rs/blob/a27a93ec721d954661620d7f451db53e4bf4e5e9/src/record/polygon.rs#L564
-    pub(super) fn new(geom: shapefile::Polygon) -> Self {
+    pub(super) fn try_new(geom: shapefile::Polygon) -> Result<Self> {
         let mut last_poly = None;
         let mut polygons = Vec::new();
         for ring in geom.into_inner() {
@@ -352,12 +354,10 @@ impl MultiPolygon {
                     if let Some(poly) = last_poly.as_mut() {
                         poly.inner.push(points);
                     } else {
-                        panic!("inner ring without a previous outer ring");
-                        // This is the strange (?) case: inner ring without a previous outer ring
-                        // polygons.push(geo_types::Polygon::<f64>::new(
-                        //     LineString::<f64>::from(Vec::<Coordinate<f64>>::new()),
-                        //     vec![LineString::from(interior)],
-                        // ));
+                        return Err(GeoArrowError::General(
+                            "Shapefile polygon has an inner ring without a previous outer ring"
+                                .to_string(),
+                        ));
                     }
                 }
             }
@@ -367,7 +367,7 @@ impl MultiPolygon {
             polygons.push(poly);
         }
 
-        Self(polygons)
+        Ok(Self(polygons))
     }
 }
 
@@ -393,7 +393,7 @@ pub(super) struct MultiPolygonZ(Vec<PolygonZ>);
 impl MultiPolygonZ {
     /// This is ported from the geo-types From impl
     /// https://github.com/tmontaigu/shapefile-rs/blob/a27a93ec721d954661620d7f451db53e4bf4e5e9/src/record/polygon.rs#L564
-    pub(super) fn new(geom: shapefile::PolygonZ) -> Self {
+    pub(super) fn try_new(geom: shapefile::PolygonZ) -> Result<Self> {
         let mut last_poly = None;
         let mut polygons = Vec::new();
         for ring in geom.into_inner() {
@@ -411,12 +411,10 @@ impl MultiPolygonZ {
                     if let Some(poly) = last_poly.as_mut() {
                         poly.inner.push(points);
                     } else {
-                        panic!("inner ring without a previous outer ring");
-                        // This is the strange (?) case: inner ring without a previous outer ring
-                        // polygons.push(geo_types::Polygon::<f64>::new(
-                        //     LineString::<f64>::from(Vec::<Coordinate<f64>>::new()),
-                        //     vec![LineString::from(interior)],
-                        // ));
+                        return Err(GeoArrowError::General(
+                            "Shapefile polygon has an inner ring without a previous outer ring"
+                                .to_string(),
+                        ));
                     }
                 }
             }
@@ -426,7 +424,7 @@ impl MultiPolygonZ {
             polygons.push(poly);
         }
 
-        Self(polygons)
+        Ok(Self(polygons))
     }
 }
 
@@ -0,0 +1,921 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::{Array, RecordBatch};
+use arrow_schema::{DataType, Field, TimeUnit};
+use chrono::Datelike;
+use dbase::{
+    Encoding, FieldIOError, FieldInfo, FieldName, FieldValue, FieldWriter, TableWriter,
+    TableWriterBuilder, WritableAsDbaseField, WritableRecord,
+};
+use geo_traits::{
+    CoordTrait, GeometryTrait, GeometryType, LineStringTrait, MultiLineStringTrait,
+    MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait, RectTrait,
+};
+use geoarrow_schema::{Crs, CrsType, Dimension};
+use serde_json::Value;
+use shapefile::NO_DATA;
+
+use super::{Coord, SHP_HEADER_SIZE, SHP_RECORD_HEADER_SIZE, signed_area};
+use crate::array::NativeArrayDyn;
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::RecordBatchReader;
+use crate::io::wkb::to_wkb;
+use crate::schema::GeoSchemaExt;
+use crate::trait_::ArrayAccessor;
+
+/// The maximum size of a `.shp` or `.dbf` file supported by most readers.
+const MAX_FILE_SIZE: u64 = i32::MAX as u64;
+
+/// The size of each record in the `.shx` file.
+const SHX_RECORD_SIZE: u64 = 8;
+
+/// The maximum length of a dBase character field.
+const MAX_CHARACTER_LENGTH: u8 = 254;
+
+/// The maximum length in bytes of a dBase field name.
+const MAX_FIELD_NAME_LENGTH: usize = 10;
+
+/// Options for the Shapefile writer
+#[derive(Debug, Clone)]
+pub struct ShapefileWriterOptions {
+    /// The maximum size in bytes of each `.shp` and `.dbf` file.
+    ///
+    /// When writing another feature would exceed this size, the following features are written
+    /// to a new Shapefile whose name has a numeric suffix, such as `roads_1.shp`. Defaults to the
+    /// 2 GB limit of the format.
+    pub max_file_size: u64,
+}
+
+impl Default for ShapefileWriterOptions {
+    fn default() -> Self {
+        Self {
+            max_file_size: MAX_FILE_SIZE,
+        }
+    }
+}
+
+/// Write a table to a Shapefile.
+///
+/// The `.shp`, `.shx`, `.dbf` and `.cpg` files are written next to each other, using the name of
+/// the `.shp` file given by `path`. The `.prj` file is written if the geometry column has a WKT
+/// CRS.
+///
+/// The table must contain exactly one geometry column, with points, multi points, (multi) line
+/// strings or (multi) polygons with XY or XYZ coordinates. Null geometries are written as null
+/// shapes. Property columns are written to the `.dbf` file as follows:
+///
+/// | Arrow type                          | dBase field type              |
+/// |-------------------------------------|-------------------------------|
+/// | `Boolean`                           | Logical                       |
+/// | Integers up to 32 bits              | Numeric, 11 digits            |
+/// | `Int64`, `UInt32`, `UInt64`         | Numeric, 20 digits            |
+/// | Floats                              | Numeric, 24 digits, 15 decimals |
+/// | `Utf8`, `LargeUtf8`, `Utf8View`     | Character, 254 bytes          |
+/// | `Date32`, `Date64`                  | Date                          |
+/// | `Timestamp`                         | Character, as an ISO 8601 string in UTC |
+///
+/// Column names are truncated to the 10 bytes allowed by dBase.
+pub fn write_shapefile<S: Into<RecordBatchReader>>(
+    stream: S,
+    path: impl AsRef<Path>,
+    options: ShapefileWriterOptions,
+) -> Result<()> {
+    let reader = stream.into().into_inner();
+    let schema = reader.schema();
+
+    let geometry_columns = schema.as_ref().geometry_columns();
+    let geometry_column_idx = match geometry_columns.as_slice() {
+        [idx] => *idx,
+        _ => {
+            return Err(GeoArrowError::General(format!(
+                "Writing Shapefile requires exactly one geometry column, found {}",
+                geometry_columns.len()
+            )));
+        }
+    };
+    let geometry_field = schema.field(geometry_column_idx);
+    let geometry_type = NativeType::try_from(geometry_field)?;
+    let shape_kind = ShapeKind::try_new(&geometry_type)?;
+
+    let mut dbf_fields = vec![];
+    let mut dbf_names = HashSet::new();
+    for (column_idx, field) in schema.fields().iter().enumerate() {
+        if column_idx != geometry_column_idx {
+            let name = dbf_field_name(field.name(), &dbf_names);
+            dbf_names.insert(name.clone());
+            dbf_fields.push(DbfField::try_new(column_idx, name, field)?);
+        }
+    }
+
+    let mut files = ShapefileParts {
+        path: path.as_ref().to_path_buf(),
+        prj: prj(geometry_type.metadata().crs()),
+        shape_kind,
+        dbf_fields: &dbf_fields,
+        max_file_size: options.max_file_size,
+        num_parts: 0,
+        current: None,
+    };
+    for batch in reader {
+        let batch = batch?;
+        let geometry_array = NativeArrayDyn::from_arrow_array(
+            batch.column(geometry_column_idx).as_ref(),
+            schema.field(geometry_column_idx),
+        )?
+        .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry_array.as_ref());
+
+        for row in 0..batch.num_rows() {
+            let shape = match wkb_array.get(row) {
+                Some(geometry) => Shape::try_new(shape_kind, &geometry.parse()?)?,
+                None => Shape::Null,
+            };
+            let record = record(&batch, &dbf_fields, row)?;
+            files.write(&shape, &record)?;
+        }
+    }
+
+    files.finish()
+}
+
+/// The Shapefiles written so far, and the writer for the current one.
+struct ShapefileParts<'a> {
+    path: PathBuf,
+    prj: Option<String>,
+    shape_kind: ShapeKind,
+    dbf_fields: &'a [DbfField],
+    max_file_size: u64,
+    num_parts: usize,
+    current: Option<ShapefilePart>,
+}
+
+struct ShapefilePart {
+    shp: BufWriter<File>,
+    shx: BufWriter<File>,
+    dbf: TableWriter<BufWriter<File>>,
+    bounds: Bounds,
+    shp_size: u64,
+    dbf_size: u64,
+    num_records: usize,
+}
+
+impl ShapefileParts<'_> {
+    fn write(&mut self, shape: &Shape, record: &DbfRecord) -> Result<()> {
+        let content = shape.encode(self.shape_kind);
+        let shp_record_size = SHP_RECORD_HEADER_SIZE + content.len() as u64;
+        let dbf_record_size = dbf_record_size(self.dbf_fields);
+        let max_file_size = self.max_file_size;
+        let fits = |part: &ShapefilePart| {
+            part.num_records == 0
+                || (part.shp_size + shp_record_size <= max_file_size
+                    && part.dbf_size + dbf_record_size <= max_file_size)
+        };
+        let mut part = match self.current.take() {
+            Some(part) if fits(&part) => part,
+            Some(part) => {
+                part.finish(self.shape_kind)?;
+                self.next_part()?
+            }
+            None => self.next_part()?,
+        };
+
+        part.write(&content, shape, record)?;
+        part.dbf_size += dbf_record_size;
+        self.current = Some(part);
+        Ok(())
+    }
+
+    /// Create the next Shapefile.
+    fn next_part(&mut self) -> Result<ShapefilePart> {
+        let path = if self.num_parts == 0 {
+            self.path.clone()
+        } else {
+            let stem = self
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy())
+                .unwrap_or_default();
+            self.path
+                .with_file_name(format!("{}_{}.shp", stem, self.num_parts))
+        };
+        self.num_parts += 1;
+
+        // The headers are written once the number of records and the bounds are known
+        let mut shp = BufWriter::new(File::create(&path)?);
+        shp.write_all(&[0; SHP_HEADER_SIZE as usize])?;
+        let mut shx = BufWriter::new(File::create(path.with_extension("shx"))?);
+        shx.write_all(&[0; SHP_HEADER_SIZE as usize])?;
+        let dbf =
+            table_builder(self.dbf_fields)?.build_with_file_dest(path.with_extension("dbf"))?;
+        std::fs::write(path.with_extension("cpg"), "UTF-8")?;
+        if let Some(prj) = &self.prj {
+            std::fs::write(path.with_extension("prj"), prj)?;
+        }
+
+        // The dBase header has 32 bytes, plus 32 per field and a terminator byte
+        let dbf_header_size = 32 + 32 * self.dbf_fields.len() as u64 + 1;
+        Ok(ShapefilePart {
+            shp,
+            shx,
+            dbf,
+            bounds: Bounds::new(),
+            shp_size: SHP_HEADER_SIZE,
+            dbf_size: dbf_header_size,
+            num_records: 0,
+        })
+    }
+
+    /// Complete the headers of the current Shapefile, creating an empty one if nothing was
+    /// written.
+    fn finish(mut self) -> Result<()> {
+        let part = match self.current.take() {
+            Some(part) => part,
+            None => self.next_part()?,
+        };
+        part.finish(self.shape_kind)
+    }
+}
+
+impl ShapefilePart {
+    /// Write the encoded contents of a shape to the `.shp` file, along with its `.shx` index
+    /// entry and `.dbf` record.
+    fn write(&mut self, content: &[u8], shape: &Shape, record: &DbfRecord) -> Result<()> {
+        // Offsets and lengths are given in 16-bit words, and record numbers start at 1
+        let content_length = (content.len() / 2) as i32;
+        self.shx
+            .write_all(&((self.shp_size / 2) as i32).to_be_bytes())?;
+        self.shx.write_all(&content_length.to_be_bytes())?;
+        self.shp
+            .write_all(&(self.num_records as i32 + 1).to_be_bytes())?;
+        self.shp.write_all(&content_length.to_be_bytes())?;
+        self.shp.write_all(content)?;
+        self.dbf.write_record(record)?;
+
+        for coord in shape.coords() {
+            self.bounds.add(coord);
+        }
+        self.shp_size += SHP_RECORD_HEADER_SIZE + content.len() as u64;
+        self.num_records += 1;
+        Ok(())
+    }
+
+    /// Write the `.shp` and `.shx` headers.
+    ///
+    /// The header of the `.dbf` file is completed when its writer is dropped.
+    fn finish(self, shape_kind: ShapeKind) -> Result<()> {
+        let shx_size = SHP_HEADER_SIZE + SHX_RECORD_SIZE * self.num_records as u64;
+        for (mut file, size) in [(self.shp, self.shp_size), (self.shx, shx_size)] {
+            file.seek(SeekFrom::Start(0))?;
+            file.write_all(&shp_header(shape_kind, &self.bounds, size))?;
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// The 100 byte header shared by the `.shp` and `.shx` files.
+fn shp_header(shape_kind: ShapeKind, bounds: &Bounds, file_size: u64) -> Vec<u8> {
+    let mut header = Vec::with_capacity(SHP_HEADER_SIZE as usize);
+    // The file code, five unused integers and the file length in 16-bit words are big endian
+    header.extend_from_slice(&9994_i32.to_be_bytes());
+    header.extend_from_slice(&[0; 20]);
+    header.extend_from_slice(&((file_size / 2) as i32).to_be_bytes());
+    // The version, shape type and bounding box are little endian
+    header.extend_from_slice(&1000_i32.to_le_bytes());
+    header.extend_from_slice(&shape_kind.code().to_le_bytes());
+    let [x_range, y_range, z_range, m_range] = [0, 1, 2, 3].map(|dim| bounds.range(dim));
+    for value in [x_range[0], y_range[0], x_range[1], y_range[1]] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    let [z_range, m_range] = if shape_kind.z {
+        [z_range, m_range]
+    } else {
+        [[0.; 2]; 2]
+    };
+    for value in z_range.into_iter().chain(m_range) {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    header
+}
+
+/// The contents of the `.prj` file for a CRS.
+///
+/// Only WKT can be written, so CRSes given as PROJJSON or an authority code are skipped.
+fn prj(crs: &Crs) -> Option<String> {
+    match (crs.crs_type(), crs.crs_value()) {
+        (None | Some(CrsType::Wkt2_2019), Some(Value::String(wkt))) => Some(wkt.clone()),
+        _ => None,
+    }
+}
+
+/// The kind of shape stored in a Shapefile for a GeoArrow type.
+#[derive(Debug, Clone, Copy)]
+struct ShapeKind {
+    shape_type: ShapeType,
+    z: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ShapeType {
+    Point,
+    Multipoint,
+    Polyline,
+    Polygon,
+}
+
+impl ShapeKind {
+    fn try_new(geometry_type: &NativeType) -> Result<Self> {
+        let shape_type = match geometry_type {
+            NativeType::Point(_) => ShapeType::Point,
+            NativeType::MultiPoint(_) => ShapeType::Multipoint,
+            NativeType::LineString(_) | NativeType::MultiLineString(_) => ShapeType::Polyline,
+            NativeType::Polygon(_) | NativeType::MultiPolygon(_) | NativeType::Rect(_) => {
+                ShapeType::Polygon
+            }
+            _ => {
+                return Err(GeoArrowError::NotYetImplemented(format!(
+                    "Writing {} geometries to Shapefile",
+                    geometry_type.extension_name()
+                )));
+            }
+        };
+        let z = match geometry_type.dimension() {
+            Some(Dimension::XY) => false,
+            Some(Dimension::XYZ) | Some(Dimension::XYZM) => true,
+            dim => {
+                return Err(GeoArrowError::NotYetImplemented(format!(
+                    "Writing geometries with dimension {:?} to Shapefile",
+                    dim
+                )));
+            }
+        };
+        Ok(Self { shape_type, z })
+    }
+
+    /// The shape type code written to the file.
+    fn code(&self) -> i32 {
+        let code = match self.shape_type {
+            ShapeType::Point => 1,
+            ShapeType::Polyline => 3,
+            ShapeType::Polygon => 5,
+            ShapeType::Multipoint => 8,
+        };
+        // Z shapes, which also have M values, use the code of the XY shape plus 10
+        if self.z { code + 10 } else { code }
+    }
+}
+
+/// A shape to write to a Shapefile.
+enum Shape {
+    Null,
+    Point(Coord),
+    Multipoint(Vec<Coord>),
+    /// The parts of a polyline, or the rings of a polygon.
+    Parts(Vec<Vec<Coord>>),
+}
+
+impl Shape {
+    fn try_new(kind: ShapeKind, geometry: &impl GeometryTrait<T = f64>) -> Result<Self> {
+        let shape = match kind.shape_type {
+            ShapeType::Point => Self::Point(single_point(geometry)?),
+            ShapeType::Multipoint => Self::Multipoint(multi_point(geometry)?),
+            ShapeType::Polyline => Self::Parts(polyline_parts(geometry)?),
+            ShapeType::Polygon => Self::Parts(polygon_rings(geometry)?),
+        };
+        Ok(shape)
+    }
+
+    /// The coordinates of all points of the shape.
+    fn coords(&self) -> Vec<&Coord> {
+        match self {
+            Self::Null => vec![],
+            Self::Point(coord) => vec![coord],
+            Self::Multipoint(coords) => coords.iter().collect(),
+            Self::Parts(parts) => parts.iter().flatten().collect(),
+        }
+    }
+
+    /// Encode the contents of the shape's record in the `.shp` file, after the record header.
+    ///
+    /// Shapes start with their shape type. Null shapes end there and points only add their
+    /// coordinates. Other shapes add their bounding box and point count, then multi-part shapes
+    /// add their part count and the offset of each part. Z shapes follow the X and Y values with
+    /// the Z range and values, then the M range and values.
+    fn encode(&self, kind: ShapeKind) -> Vec<u8> {
+        let mut content = vec![];
+        match self {
+            Self::Null => put_i32(&mut content, 0),
+            Self::Point([x, y, z, m]) => {
+                put_i32(&mut content, kind.code());
+                let values = if kind.z {
+                    vec![*x, *y, *z, *m]
+                } else {
+                    vec![*x, *y]
+                };
+                for value in values {
+                    put_f64(&mut content, value);
+                }
+            }
+            Self::Multipoint(_) | Self::Parts(_) => {
+                put_i32(&mut content, kind.code());
+                let coords = self.coords();
+                let mut bounds = Bounds::new();
+                for coord in &coords {
+                    bounds.add(coord);
+                }
+                let [x_range, y_range] = [0, 1].map(|dim| bounds.range(dim));
+                for value in [x_range[0], y_range[0], x_range[1], y_range[1]] {
+                    put_f64(&mut content, value);
+                }
+                if let Self::Parts(parts) = self {
+                    put_i32(&mut content, parts.len() as i32);
+                    put_i32(&mut content, coords.len() as i32);
+                    let mut offset = 0;
+                    for part in parts {
+                        put_i32(&mut content, offset as i32);
+                        offset += part.len();
+                    }
+                } else {
+                    put_i32(&mut content, coords.len() as i32);
+                }
+                for coord in &coords {
+                    put_f64(&mut content, coord[0]);
+                    put_f64(&mut content, coord[1]);
+                }
+                if kind.z {
+                    for dim in [2, 3] {
+                        let [min, max] = bounds.range(dim);
+                        put_f64(&mut content, min);
+                        put_f64(&mut content, max);
+                        for coord in &coords {
+                            put_f64(&mut content, coord[dim]);
+                        }
+                    }
+                }
+            }
+        }
+        content
+    }
+}
+
+fn put_i32(buf: &mut Vec<u8>, value: i32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_f64(buf: &mut Vec<u8>, value: f64) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+/// The ranges of the X, Y, Z and M values of some coordinates.
+struct Bounds {
+    min: Coord,
+    max: Coord,
+}
+
+impl Bounds {
+    fn new() -> Self {
+        Self {
+            min: [f64::INFINITY; 4],
+            max: [f64::NEG_INFINITY; 4],
+        }
+    }
+
+    fn add(&mut self, coord: &Coord) {
+        for dim in 0..4 {
+            self.min[dim] = self.min[dim].min(coord[dim]);
+            self.max[dim] = self.max[dim].max(coord[dim]);
+        }
+    }
+
+    /// The `[min, max]` range of a dimension, or zeros if there are no coordinates.
+    fn range(&self, dim: usize) -> [f64; 2] {
+        if self.min[dim] <= self.max[dim] {
+            [self.min[dim], self.max[dim]]
+        } else {
+            [0.; 2]
+        }
+    }
+}
+
+fn coord(coord: &impl CoordTrait<T = f64>) -> Coord {
+    [
+        coord.x(),
+        coord.y(),
+        coord.nth(2).unwrap_or(0.),
+        coord.nth(3).unwrap_or(NO_DATA),
+    ]
+}
+
+fn empty_geometry_error() -> GeoArrowError {
+    GeoArrowError::General("Shapefile does not support empty geometries".to_string())
+}
+
+fn unexpected_geometry_error() -> GeoArrowError {
+    GeoArrowError::General("Unexpected geometry type for Shapefile".to_string())
+}
+
+fn single_point(geometry: &impl GeometryTrait<T = f64>) -> Result<Coord> {
+    match geometry.as_type() {
+        GeometryType::Point(point) => Ok(coord(&point.coord().ok_or_else(empty_geometry_error)?)),
+        _ => Err(unexpected_geometry_error()),
+    }
+}
+
+fn multi_point(geometry: &impl GeometryTrait<T = f64>) -> Result<Vec<Coord>> {
+    let points = match geometry.as_type() {
+        GeometryType::Point(point) => point.coord().map(|c| coord(&c)).into_iter().collect(),
+        GeometryType::MultiPoint(multi_point) => multi_point
+            .points()
+            .filter_map(|point| point.coord().map(|c| coord(&c)))
+            .collect::<Vec<_>>(),
+        _ => return Err(unexpected_geometry_error()),
+    };
+    if points.is_empty() {
+        return Err(empty_geometry_error());
+    }
+    Ok(points)
+}
+
+fn line_coords(line_string: &impl LineStringTrait<T = f64>) -> Vec<Coord> {
+    line_string.coords().map(|c| coord(&c)).collect()
+}
+
+/// The parts of a polyline.
+fn polyline_parts(geometry: &impl GeometryTrait<T = f64>) -> Result<Vec<Vec<Coord>>> {
+    let parts = match geometry.as_type() {
+        GeometryType::LineString(line_string) => vec![line_coords(line_string)],
+        GeometryType::MultiLineString(multi_line_string) => multi_line_string
+            .line_strings()
+            .map(|line_string| line_coords(&line_string))
+            .collect(),
+        _ => return Err(unexpected_geometry_error()),
+    };
+    if parts.is_empty() || parts.iter().any(|part| part.len() < 2) {
+        return Err(GeoArrowError::General(
+            "Shapefile polylines must have at least one part with two or more points".to_string(),
+        ));
+    }
+    Ok(parts)
+}
+
+/// The rings of a polygon.
+///
+/// Rings are closed, and oriented clockwise for exterior rings and counterclockwise for
+/// interior rings as the format requires.
+fn polygon_rings(geometry: &impl GeometryTrait<T = f64>) -> Result<Vec<Vec<Coord>>> {
+    let mut rings = vec![];
+    match geometry.as_type() {
+        GeometryType::Polygon(polygon) => push_polygon(&mut rings, polygon),
+        GeometryType::MultiPolygon(multi_polygon) => {
+            for polygon in multi_polygon.polygons() {
+                push_polygon(&mut rings, &polygon);
+            }
+        }
+        GeometryType::Rect(rect) => {
+            let (min, max) = (coord(&rect.min()), coord(&rect.max()));
+            let ring = [
+                [min[0], min[1]],
+                [min[0], max[1]],
+                [max[0], max[1]],
+                [max[0], min[1]],
+                [min[0], min[1]],
+            ]
+            .into_iter()
+            .map(|[x, y]| [x, y, min[2], NO_DATA])
+            .collect();
+            rings.push((true, ring));
+        }
+        _ => return Err(unexpected_geometry_error()),
+    }
+
+    for (_, ring) in rings.iter_mut() {
+        if let (Some(first), Some(last)) = (ring.first(), ring.last()) {
+            if first[..3] != last[..3] {
+                ring.push(*first);
+            }
+        }
+    }
+    if rings.is_empty() || rings.iter().any(|(_, ring)| ring.len() < 4) {
+        return Err(GeoArrowError::General(
+            "Shapefile polygons must have at least one ring with four or more points".to_string(),
+        ));
+    }
+
+    Ok(rings
+        .into_iter()
+        .map(|(exterior, mut ring)| {
+            if exterior == (signed_area(&ring) > 0.) {
+                ring.reverse();
+            }
+            ring
+        })
+        .collect())
+}
+
+/// Add the exterior and interior rings of a polygon, flagging the exterior.
+fn push_polygon(rings: &mut Vec<(bool, Vec<Coord>)>, polygon: &impl PolygonTrait<T = f64>) {
+    if let Some(exterior) = polygon.exterior() {
+        rings.push((true, line_coords(&exterior)));
+    }
+    for interior in polygon.interiors() {
+        rings.push((false, line_coords(&interior)));
+    }
+}
+
+/// A property column written to the `.dbf` file.
+struct DbfField {
+    column_idx: usize,
+    name: String,
+    field_type: DbfFieldType,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DbfFieldType {
+    Logical,
+    Numeric { length: u8, decimals: u8 },
+    Character { length: u8 },
+    Date,
+}
+
+impl DbfField {
+    fn try_new(column_idx: usize, name: String, field: &Field) -> Result<Self> {
+        let field_type = match field.data_type() {
+            DataType::Boolean => DbfFieldType::Logical,
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::UInt8
+            | DataType::UInt16 => DbfFieldType::Numeric {
+                length: 11,
+                decimals: 0,
+            },
+            DataType::Int64 | DataType::UInt32 | DataType::UInt64 => DbfFieldType::Numeric {
+                length: 20,
+                decimals: 0,
+            },
+            DataType::Float16 | DataType::Float32 | DataType::Float64 => DbfFieldType::Numeric {
+                length: 24,
+                decimals: 15,
+            },
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => DbfFieldType::Character {
+                length: MAX_CHARACTER_LENGTH,
+            },
+            DataType::Date32 | DataType::Date64 => DbfFieldType::Date,
+            DataType::Timestamp(_, _) => DbfFieldType::Character { length: 24 },
+            data_type => {
+                return Err(GeoArrowError::NotYetImplemented(format!(
+                    "Writing {} column {} to Shapefile",
+                    data_type,
+                    field.name()
+                )));
+            }
+        };
+        Ok(Self {
+            column_idx,
+            name,
+            field_type,
+        })
+    }
+
+    fn length(&self) -> u64 {
+        match self.field_type {
+            DbfFieldType::Logical => 1,
+            DbfFieldType::Numeric { length, .. } | DbfFieldType::Character { length } => {
+                length.into()
+            }
+            DbfFieldType::Date => 8,
+        }
+    }
+}
+
+/// Truncate a column name to the length allowed by dBase, replacing its end with a numeric
+/// suffix if the truncated name is already taken.
+fn dbf_field_name(name: &str, existing: &HashSet<String>) -> String {
+    let truncate = |name: &str, max_length: usize| {
+        let mut end = name.len().min(max_length);
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name[..end].to_string()
+    };
+
+    let truncated = truncate(name, MAX_FIELD_NAME_LENGTH);
+    let mut candidate = truncated.clone();
+    let mut suffix = 1;
+    while existing.contains(&candidate) {
+        let suffix_str = format!("_{suffix}");
+        candidate = truncate(&truncated, MAX_FIELD_NAME_LENGTH - suffix_str.len()) + &suffix_str;
+        suffix += 1;
+    }
+    candidate
+}
+
+/// The size in bytes of each record in the `.dbf` file, including the deletion flag.
+fn dbf_record_size(fields: &[DbfField]) -> u64 {
+    1 + fields.iter().map(DbfField::length).sum::<u64>()
+}
+
+fn table_builder(fields: &[DbfField]) -> Result<TableWriterBuilder> {
+    let mut builder = TableWriterBuilder::new();
+    for field in fields {
+        let name = FieldName::try_from(field.name.as_str()).map_err(|_| {
+            GeoArrowError::General(format!("Invalid dBase field name {}", field.name))
+        })?;
+        builder = match field.field_type {
+            DbfFieldType::Logical => builder.add_logical_field(name),
+            DbfFieldType::Numeric { length, decimals } => {
+                builder.add_numeric_field(name, length, decimals)
+            }
+            DbfFieldType::Character { length } => builder.add_character_field(name, length),
+            DbfFieldType::Date => builder.add_date_field(name),
+        };
+    }
+    Ok(builder)
+}
+
+/// A `.dbf` record, with a value for each field in order.
+struct DbfRecord(Vec<DbfValue>);
+
+impl WritableRecord for DbfRecord {
+    fn write_using<'a, W: Write>(
+        &self,
+        field_writer: &mut FieldWriter<'a, W>,
+    ) -> std::result::Result<(), FieldIOError> {
+        for value in &self.0 {
+            field_writer.write_next_field_value(value)?;
+        }
+        Ok(())
+    }
+}
+
+/// A value of a `.dbf` record.
+enum DbfValue {
+    Field(FieldValue),
+    /// A value of a numeric field from an integer column.
+    ///
+    /// This is written with all of its digits, as a numeric [FieldValue] holds an `f64` that can't
+    /// represent every 64-bit integer.
+    Integer(Option<i128>),
+}
+
+impl WritableAsDbaseField for DbfValue {
+    fn write_as<E: Encoding, W: Write>(
+        &self,
+        field_info: &FieldInfo,
+        encoding: &E,
+        dst: &mut W,
+    ) -> std::result::Result<(), FieldIOError> {
+        match self {
+            Self::Field(value) => value.write_as(field_info, encoding, dst),
+            Self::Integer(Some(value)) => {
+                dst.write_all(value.to_string().as_bytes())?;
+                Ok(())
+            }
+            Self::Integer(None) => FieldValue::Numeric(None).write_as(field_info, encoding, dst),
+        }
+    }
+}
+
+/// Create the `.dbf` record for a row of a batch.
+fn record(batch: &RecordBatch, fields: &[DbfField], row: usize) -> Result<DbfRecord> {
+    let mut values = Vec::with_capacity(fields.len());
+    for field in fields {
+        let array = batch.column(field.column_idx);
+        let value = match field.field_type {
+            DbfFieldType::Logical => {
+                FieldValue::Logical(array.is_valid(row).then(|| array.as_boolean().value(row)))
+            }
+            DbfFieldType::Numeric { decimals: 0, .. } => {
+                values.push(DbfValue::Integer(integer_value(array, row)?));
+                continue;
+            }
+            DbfFieldType::Numeric { .. } => FieldValue::Numeric(numeric_value(array, row)?),
+            DbfFieldType::Character { length } => {
+                let value = character_value(array, row)?;
+                if let Some(value) = &value {
+                    if value.len() > length.into() {
+                        return Err(GeoArrowError::General(format!(
+                            "Value of column {} is longer than the {} bytes allowed by dBase",
+                            field.name, length
+                        )));
+                    }
+                }
+                FieldValue::Character(value)
+            }
+            DbfFieldType::Date => FieldValue::Date(date_value(array, row)?),
+        };
+        values.push(DbfValue::Field(value));
+    }
+    Ok(DbfRecord(values))
+}
+
+fn integer_value(array: &dyn Array, row: usize) -> Result<Option<i128>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    let value = match array.data_type() {
+        DataType::Int8 => array.as_primitive::<Int8Type>().value(row).into(),
+        DataType::Int16 => array.as_primitive::<Int16Type>().value(row).into(),
+        DataType::Int32 => array.as_primitive::<Int32Type>().value(row).into(),
+        DataType::Int64 => array.as_primitive::<Int64Type>().value(row).into(),
+        DataType::UInt8 => array.as_primitive::<UInt8Type>().value(row).into(),
+        DataType::UInt16 => array.as_primitive::<UInt16Type>().value(row).into(),
+        DataType::UInt32 => array.as_primitive::<UInt32Type>().value(row).into(),
+        DataType::UInt64 => array.as_primitive::<UInt64Type>().value(row).into(),
+        data_type => {
+            return Err(GeoArrowError::General(format!(
+                "Unexpected integer type {}",
+                data_type
+            )));
+        }
+    };
+    Ok(Some(value))
+}
+
+fn numeric_value(array: &dyn Array, row: usize) -> Result<Option<f64>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    let value = match array.data_type() {
+        DataType::Float16 => array.as_primitive::<Float16Type>().value(row).to_f64(),
+        DataType::Float32 => array.as_primitive::<Float32Type>().value(row).into(),
+        DataType::Float64 => array.as_primitive::<Float64Type>().value(row),
+        data_type => {
+            return Err(GeoArrowError::General(format!(
+                "Unexpected numeric type {}",
+                data_type
+            )));
+        }
+    };
+    // dBase has no representation of NaN or infinity
+    Ok(value.is_finite().then_some(value))
+}
+
+fn character_value(array: &dyn Array, row: usize) -> Result<Option<String>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    let value = match array.data_type() {
+        DataType::Utf8 => array.as_string::<i32>().value(row).to_string(),
+        DataType::LargeUtf8 => array.as_string::<i64>().value(row).to_string(),
+        DataType::Utf8View => array.as_string_view().value(row).to_string(),
+        DataType::Timestamp(unit, _) => {
+            // Timestamps are written relative to UTC regardless of their timezone
+            let datetime = match unit {
+                TimeUnit::Second => array
+                    .as_primitive::<TimestampSecondType>()
+                    .value_as_datetime(row),
+                TimeUnit::Millisecond => array
+                    .as_primitive::<TimestampMillisecondType>()
+                    .value_as_datetime(row),
+                TimeUnit::Microsecond => array
+                    .as_primitive::<TimestampMicrosecondType>()
+                    .value_as_datetime(row),
+                TimeUnit::Nanosecond => array
+                    .as_primitive::<TimestampNanosecondType>()
+                    .value_as_datetime(row),
+            }
+            .ok_or_else(|| GeoArrowError::General("Timestamp out of range".to_string()))?;
+            datetime.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+        }
+        data_type => {
+            return Err(GeoArrowError::General(format!(
+                "Unexpected character type {}",
+                data_type
+            )));
+        }
+    };
+    Ok(Some(value))
+}
+
+fn date_value(array: &dyn Array, row: usize) -> Result<Option<dbase::Date>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    let date = match array.data_type() {
+        DataType::Date32 => array.as_primitive::<Date32Type>().value_as_date(row),
+        DataType::Date64 => array.as_primitive::<Date64Type>().value_as_date(row),
+        data_type => {
+            return Err(GeoArrowError::General(format!(
+                "Unexpected date type {}",
+                data_type
+            )));
+        }
+    };
+    match date {
+        Some(date) if (0..=9999).contains(&date.year()) => Ok(Some(dbase::Date::new(
+            date.day(),
+            date.month(),
+            date.year() as u32,
+        ))),
+        _ => Err(GeoArrowError::General(
+            "Date is out of the range supported by dBase".to_string(),
+        )),
+    }
+}
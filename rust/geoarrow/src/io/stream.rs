@@ -1,6 +1,6 @@
 use crate::error::GeoArrowError;
 use crate::table::Table;
-use arrow_array::{RecordBatchIterator, RecordBatchReader as _RecordBatchReader};
+use arrow_array::{RecordBatch, RecordBatchIterator, RecordBatchReader as _RecordBatchReader};
 use arrow_schema::SchemaRef;
 
 /// A newtype wrapper around an [`arrow_array::RecordBatchReader`] so that we can implement the
@@ -69,3 +69,14 @@ impl From<Box<dyn _RecordBatchReader + Send>> for RecordBatchReader {
         Self(value)
     }
 }
+
+/// Split a batch into a stream of batches of at most `batch_size` rows.
+pub(crate) fn record_batches(batch: RecordBatch, batch_size: usize) -> RecordBatchReader {
+    let schema = batch.schema();
+    let batch_size = batch_size.max(1);
+    let batches = (0..batch.num_rows())
+        .step_by(batch_size)
+        .map(|offset| Ok(batch.slice(offset, batch_size.min(batch.num_rows() - offset))))
+        .collect::<Vec<_>>();
+    RecordBatchReader::new(Box::new(RecordBatchIterator::new(batches, schema)))
+}
@@ -0,0 +1,8 @@
+//! Read from and write to [TopoJSON](https://github.com/topojson/topojson-specification)
+//! topologies.
+
+mod reader;
+mod writer;
+
+pub use reader::{TopoJsonReaderOptions, read_topojson};
+pub use writer::{TopoJsonWriterOptions, write_topojson};
@@ -0,0 +1,409 @@
+use std::io::Read;
+use std::sync::Arc;
+
+use geoarrow_schema::{CoordType, Metadata};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::error::{GeoArrowError, Result};
+use crate::io::parsed::{
+    Coord, KeyOrder, ParsedGeometry, feature_batch, infer_geometry_type, infer_properties_schema,
+    property_columns,
+};
+use crate::io::stream::{RecordBatchReader, record_batches};
+
+/// Options for the TopoJSON reader.
+#[derive(Debug, Clone)]
+pub struct TopoJsonReaderOptions {
+    /// The GeoArrow coordinate type to use in the geometry arrays.
+    pub coord_type: CoordType,
+
+    /// The number of rows in each batch.
+    pub batch_size: Option<usize>,
+
+    /// The name of the object of the topology to read.
+    ///
+    /// If `None`, the first object is read.
+    pub object: Option<String>,
+
+    /// The name of the column that each geometry object's `id` member is stored in.
+    ///
+    /// If `None`, ids are not read.
+    pub id_column: Option<String>,
+}
+
+impl Default for TopoJsonReaderOptions {
+    fn default() -> Self {
+        Self {
+            coord_type: CoordType::Interleaved,
+            batch_size: Some(65_536),
+            object: None,
+            id_column: Some("id".to_string()),
+        }
+    }
+}
+
+/// The members of a topology that are read.
+#[derive(Deserialize)]
+struct Topology {
+    #[serde(default)]
+    transform: Option<Transform>,
+    #[serde(default)]
+    arcs: Vec<Vec<Vec<f64>>>,
+    objects: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct Transform {
+    scale: [f64; 2],
+    translate: [f64; 2],
+}
+
+impl Transform {
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            x * self.scale[0] + self.translate[0],
+            y * self.scale[1] + self.translate[1],
+        )
+    }
+}
+
+/// Read an object of a TopoJSON topology.
+///
+/// If the object is a geometry collection, each of its geometries is a row, and otherwise the
+/// object is the only row. The output schema has the properties of the geometries, inferred as by
+/// the GeoJSON reader, followed by a `geometry` column. The geometry type is the narrowest that
+/// holds all geometries, such as polygon for a collection of polygons.
+///
+/// Quantized topologies are decoded with their transform. Positions with more than two values
+/// keep the third as a Z value.
+pub fn read_topojson<R: Read>(
+    mut reader: R,
+    options: TopoJsonReaderOptions,
+) -> Result<RecordBatchReader> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let topology: Topology = serde_json::from_str(&text)?;
+    let key_order: KeyOrder = serde_json::from_str(&text)?;
+    let objects_order = key_order.get("objects").cloned().unwrap_or_default();
+
+    let name = match &options.object {
+        Some(name) => name.as_str(),
+        None => objects_order
+            .first_key()
+            .ok_or_else(|| GeoArrowError::General("TopoJSON has no objects".to_string()))?,
+    };
+    let object = topology
+        .objects
+        .get(name)
+        .ok_or_else(|| GeoArrowError::General(format!("TopoJSON object '{}' not found", name)))?;
+    let object_order = objects_order.get(name).cloned().unwrap_or_default();
+    let decoder = ArcDecoder::new(&topology);
+
+    let (objects, geometry_order) = match object.get("type").and_then(Value::as_str) {
+        Some("GeometryCollection") => (
+            object
+                .get("geometries")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+            object_order.get("geometries").cloned().unwrap_or_default(),
+        ),
+        _ => (std::slice::from_ref(object), object_order),
+    };
+    let mut properties_order = KeyOrder::default();
+    if let Some(id_column) = &options.id_column {
+        properties_order.insert(id_column);
+    }
+    if let Some(order) = geometry_order.get("properties") {
+        properties_order.merge(order.clone());
+    }
+    let mut geometries = vec![];
+    let mut rows = vec![];
+    for object in objects {
+        geometries.push(decoder.geometry(object)?);
+        let mut properties = match object.get("properties") {
+            Some(Value::Object(properties)) => properties.clone(),
+            _ => Map::new(),
+        };
+        if let (Some(id_column), Some(id)) = (&options.id_column, object.get("id")) {
+            if properties.contains_key(id_column) {
+                return Err(GeoArrowError::General(format!(
+                    "Geometry property {id_column:?} conflicts with the id column"
+                )));
+            }
+            properties.insert(id_column.clone(), id.clone());
+        }
+        rows.push(Value::Object(properties));
+    }
+
+    let properties_schema = infer_properties_schema(&rows, &properties_order)?;
+    let columns = property_columns(properties_schema.clone(), &rows)?;
+    let geometry_type = infer_geometry_type(
+        geometries.iter().flatten(),
+        options.coord_type,
+        Arc::new(Metadata::default()),
+    );
+    let batch = feature_batch(&properties_schema, columns, &geometries, geometry_type)?;
+    Ok(record_batches(batch, options.batch_size.unwrap_or(65_536)))
+}
+
+/// Decodes the arcs and positions of a topology's geometry objects.
+struct ArcDecoder<'a> {
+    transform: Option<&'a Transform>,
+    /// The decoded positions of each arc.
+    arcs: Vec<Vec<Coord>>,
+}
+
+impl<'a> ArcDecoder<'a> {
+    fn new(topology: &'a Topology) -> Self {
+        let transform = topology.transform.as_ref();
+        let arcs = topology
+            .arcs
+            .iter()
+            .map(|arc| {
+                // Quantized arcs are delta-encoded
+                let (mut x, mut y) = (0.0, 0.0);
+                arc.iter()
+                    .map(|position| {
+                        let (px, py) = (
+                            position.first().copied().unwrap_or(f64::NAN),
+                            position.get(1).copied().unwrap_or(f64::NAN),
+                        );
+                        let (px, py) = match transform {
+                            Some(transform) => {
+                                x += px;
+                                y += py;
+                                transform.apply(x, y)
+                            }
+                            None => (px, py),
+                        };
+                        Coord {
+                            x: px,
+                            y: py,
+                            z: position.get(2).copied(),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { transform, arcs }
+    }
+
+    fn position(&self, value: &Value) -> Result<Coord> {
+        let invalid = || GeoArrowError::General(format!("Invalid TopoJSON position: {}", value));
+        let values = value.as_array().ok_or_else(invalid)?;
+        let number = |idx: usize| values.get(idx).and_then(Value::as_f64);
+        let (x, y) = (
+            number(0).ok_or_else(invalid)?,
+            number(1).ok_or_else(invalid)?,
+        );
+        let (x, y) = match self.transform {
+            Some(transform) => transform.apply(x, y),
+            None => (x, y),
+        };
+        Ok(Coord { x, y, z: number(2) })
+    }
+
+    /// Join arcs into a line, where a negative index `i` is arc `!i` reversed.
+    fn line(&self, value: &Value) -> Result<Vec<Coord>> {
+        let invalid = || GeoArrowError::General(format!("Invalid TopoJSON arcs: {}", value));
+        let mut line: Vec<Coord> = vec![];
+        for idx in value.as_array().ok_or_else(invalid)? {
+            let idx = idx.as_i64().ok_or_else(invalid)?;
+            let (arc_idx, reversed) = if idx < 0 { (!idx, true) } else { (idx, false) };
+            let arc = self
+                .arcs
+                .get(arc_idx as usize)
+                .ok_or_else(|| GeoArrowError::General(format!("Invalid TopoJSON arc {}", idx)))?;
+            // Each arc starts where the previous one ended
+            let skip = usize::from(!line.is_empty());
+            if reversed {
+                line.extend(arc.iter().rev().skip(skip));
+            } else {
+                line.extend(arc.iter().skip(skip));
+            }
+        }
+        Ok(line)
+    }
+
+    fn lines(&self, value: &Value) -> Result<Vec<Vec<Coord>>> {
+        value
+            .as_array()
+            .ok_or_else(|| GeoArrowError::General(format!("Invalid TopoJSON arcs: {}", value)))?
+            .iter()
+            .map(|line| self.line(line))
+            .collect()
+    }
+
+    /// Decode a geometry object, or `None` if it has a null type.
+    fn geometry(&self, object: &Value) -> Result<Option<ParsedGeometry>> {
+        let invalid = || GeoArrowError::General(format!("Invalid TopoJSON geometry: {}", object));
+        let member = |name: &str| object.get(name).ok_or_else(invalid);
+        let geometry = match object.get("type").and_then(Value::as_str) {
+            None => return Ok(None),
+            Some("Point") => ParsedGeometry::Point(self.position(member("coordinates")?)?),
+            Some("MultiPoint") => ParsedGeometry::MultiPoint(
+                member("coordinates")?
+                    .as_array()
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .map(|position| self.position(position))
+                    .collect::<Result<_>>()?,
+            ),
+            Some("LineString") => ParsedGeometry::LineString(self.line(member("arcs")?)?),
+            Some("MultiLineString") => {
+                ParsedGeometry::MultiLineString(self.lines(member("arcs")?)?)
+            }
+            Some("Polygon") => ParsedGeometry::Polygon(self.lines(member("arcs")?)?),
+            Some("MultiPolygon") => ParsedGeometry::MultiPolygon(
+                member("arcs")?
+                    .as_array()
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .map(|polygon| self.lines(polygon))
+                    .collect::<Result<_>>()?,
+            ),
+            Some("GeometryCollection") => ParsedGeometry::GeometryCollection(
+                member("geometries")?
+                    .as_array()
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .filter_map(|geometry| self.geometry(geometry).transpose())
+                    .collect::<Result<_>>()?,
+            ),
+            Some(_) => return Err(invalid()),
+        };
+        Ok(Some(geometry))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::cast::AsArray;
+    use geo_traits::{
+        CoordTrait, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait,
+        PolygonTrait,
+    };
+
+    use super::*;
+    use crate::array::NativeArrayDyn;
+    use crate::io::wkb::to_wkb;
+    use crate::table::Table;
+    use crate::trait_::ArrayAccessor;
+
+    #[test]
+    fn read_quantized() {
+        // The example from the TopoJSON specification
+        let input = r#"{
+  "type": "Topology",
+  "transform": {"scale": [0.0005, 0.0001], "translate": [100, 0]},
+  "objects": {
+    "example": {
+      "type": "GeometryCollection",
+      "geometries": [
+        {"type": "Point", "properties": {"prop0": "value0"}, "coordinates": [4000, 5000]},
+        {"type": "LineString", "properties": {"prop0": "value1"}, "arcs": [0]},
+        {"type": "Polygon", "id": 3, "arcs": [[-2]]}
+      ]
+    }
+  },
+  "arcs": [
+    [[4000, 0], [1999, 9999], [2000, -9999], [2000, 9999]],
+    [[0, 0], [0, 9999], [2000, 0], [0, -9999], [-2000, 0]]
+  ]
+}"#;
+        let table: Table = read_topojson(input.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table.batches()[0];
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name(), "prop0");
+        assert_eq!(schema.field(1).name(), "id");
+        assert_eq!(batch.column(0).as_string::<i32>().value(1), "value1");
+
+        let geometry = NativeArrayDyn::from_arrow_array(batch.column(2).as_ref(), schema.field(2))
+            .unwrap()
+            .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry.as_ref());
+        let point = wkb_array.value(0);
+        let GeometryType::Point(point) = point.parse().unwrap().as_type() else {
+            panic!("expected a point");
+        };
+        let coord = geo_traits::PointTrait::coord(point).unwrap();
+        assert_eq!((coord.x(), coord.y()), (102.0, 0.5));
+
+        let polygon = wkb_array.value(2);
+        let GeometryType::Polygon(polygon) = polygon.parse().unwrap().as_type() else {
+            panic!("expected a polygon");
+        };
+        let exterior = polygon
+            .exterior()
+            .unwrap()
+            .coords()
+            .map(|coord| (coord.x(), coord.y()))
+            .collect::<Vec<_>>();
+        assert_eq!(exterior.len(), 5);
+        assert_eq!(exterior[1], (101.0, 0.0));
+        assert!((exterior[2].1 - 0.9999).abs() < 1e-12);
+    }
+
+    #[test]
+    fn read_object() {
+        let input = r#"{
+  "type": "Topology",
+  "objects": {
+    "first": {"type": "LineString", "arcs": [0, 1]},
+    "second": {"type": "GeometryCollection", "geometries": [
+      {"type": "GeometryCollection", "geometries": [{"type": "Point", "coordinates": [1, 2]}]},
+      {"type": null}
+    ]}
+  },
+  "arcs": [[[0, 0], [1, 1]], [[1, 1], [2, 0]]]
+}"#;
+        let table: Table = read_topojson(input.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table.batches()[0];
+        let geometry =
+            NativeArrayDyn::from_arrow_array(batch.column(0).as_ref(), batch.schema().field(0))
+                .unwrap()
+                .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry.as_ref());
+        let GeometryType::LineString(line_string) = wkb_array.value(0).parse().unwrap().as_type()
+        else {
+            panic!("expected a line string");
+        };
+        assert_eq!(line_string.num_coords(), 3);
+
+        let options = TopoJsonReaderOptions {
+            object: Some("second".to_string()),
+            ..Default::default()
+        };
+        let table: Table = read_topojson(input.as_bytes(), options)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table.batches()[0];
+        let geometry =
+            NativeArrayDyn::from_arrow_array(batch.column(0).as_ref(), batch.schema().field(0))
+                .unwrap()
+                .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry.as_ref());
+        let GeometryType::GeometryCollection(collection) =
+            wkb_array.value(0).parse().unwrap().as_type()
+        else {
+            panic!("expected a geometry collection");
+        };
+        assert_eq!(collection.num_geometries(), 1);
+        assert!(wkb_array.get(1).is_none());
+
+        let options = TopoJsonReaderOptions {
+            object: Some("missing".to_string()),
+            ..Default::default()
+        };
+        assert!(read_topojson(input.as_bytes(), options).is_err());
+    }
+}
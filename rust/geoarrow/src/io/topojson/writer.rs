@@ -0,0 +1,716 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+use geo_traits::{
+    CoordTrait, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait,
+    MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait, RectTrait,
+};
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::value::RawValue;
+use serde_json::{Map, Value, json};
+
+use crate::NativeArray;
+use crate::array::{AsNativeArray, NativeArrayDyn};
+use crate::datatypes::NativeType;
+use crate::error::{GeoArrowError, Result};
+use crate::io::geojson::{FeatureEncoder, GeoJsonWriterOptions};
+use crate::io::stream::RecordBatchReader;
+use crate::trait_::ArrayAccessor;
+
+/// Options for the TopoJSON writer.
+#[derive(Debug, Clone)]
+pub struct TopoJsonWriterOptions {
+    /// The name of the topology object that holds the geometries.
+    ///
+    /// Defaults to `"features"`.
+    pub object_name: String,
+
+    /// The number of distinct values along each axis that coordinates are quantized to.
+    ///
+    /// If `None`, coordinates are not quantized.
+    pub quantization: Option<u32>,
+
+    /// The name of the column to write as each geometry's id, rather than as a property.
+    pub id_column: Option<String>,
+}
+
+impl Default for TopoJsonWriterOptions {
+    fn default() -> Self {
+        Self {
+            object_name: "features".to_string(),
+            quantization: None,
+            id_column: None,
+        }
+    }
+}
+
+/// Write a table to a TopoJSON topology with a single geometry collection object.
+///
+/// The table must contain exactly one geometry column. Properties are encoded as by the GeoJSON
+/// writer. Lines and polygon rings are split where they meet, and each shared part is stored as
+/// one arc, so that the common boundaries of a polygon coverage are only written once. Z values
+/// are not written. Since arcs are shared across rows, all features are held in memory until the
+/// file is written.
+pub fn write_topojson<W: Write, S: Into<RecordBatchReader>>(
+    stream: S,
+    mut writer: W,
+    options: TopoJsonWriterOptions,
+) -> Result<()> {
+    if options
+        .quantization
+        .is_some_and(|quantization| quantization < 2)
+    {
+        return Err(GeoArrowError::General(
+            "TopoJSON quantization must be at least 2".to_string(),
+        ));
+    }
+
+    let reader = stream.into().into_inner();
+    let geojson_options = GeoJsonWriterOptions {
+        id_column: options.id_column.clone(),
+        ..Default::default()
+    };
+    let encoder = FeatureEncoder::try_new(&reader.schema(), &geojson_options)?;
+    let mut shapes = vec![];
+    let mut attributes = vec![];
+    for batch in reader {
+        let batch = batch?;
+        let schema = batch.schema();
+        let geometry_array = NativeArrayDyn::from_arrow_array(
+            batch.column(encoder.geometry_column_idx).as_ref(),
+            schema.field(encoder.geometry_column_idx),
+        )?
+        .into_inner();
+        shapes.extend(Shape::from_array(geometry_array.as_ref())?);
+
+        let properties = encoder.encode_properties(&batch)?;
+        let mut properties = properties
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty());
+        for id in encoder.encode_ids(&batch)? {
+            // Properties are kept as raw JSON so that keys stay in their original order
+            let properties = properties
+                .next()
+                .map(serde_json::from_slice::<Box<RawValue>>)
+                .transpose()?;
+            attributes.push((id, properties));
+        }
+    }
+
+    let mut bbox = [
+        f64::INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NEG_INFINITY,
+    ];
+    for shape in shapes.iter_mut() {
+        shape.for_each_position(&mut |position| {
+            bbox[0] = bbox[0].min(position[0]);
+            bbox[1] = bbox[1].min(position[1]);
+            bbox[2] = bbox[2].max(position[0]);
+            bbox[3] = bbox[3].max(position[1]);
+        });
+    }
+    let has_bbox = bbox[0] <= bbox[2] && bbox[1] <= bbox[3];
+
+    let mut transform = None;
+    let quantized = match options.quantization {
+        Some(quantization) if has_bbox => {
+            let scale = |min: f64, max: f64| {
+                if max > min {
+                    (max - min) / (quantization - 1) as f64
+                } else {
+                    1.0
+                }
+            };
+            let scale = [scale(bbox[0], bbox[2]), scale(bbox[1], bbox[3])];
+            for shape in shapes.iter_mut() {
+                shape.for_each_position(&mut |position| {
+                    position[0] = ((position[0] - bbox[0]) / scale[0]).round();
+                    position[1] = ((position[1] - bbox[1]) / scale[1]).round();
+                });
+                shape.dedup();
+            }
+            transform = Some(json!({"scale": scale, "translate": [bbox[0], bbox[1]]}));
+            true
+        }
+        _ => false,
+    };
+
+    let mut arcs = ArcBuilder::new(&shapes, quantized);
+    let geometries = attributes
+        .into_iter()
+        .zip(&shapes)
+        .map(|((id, properties), shape)| GeometryObject {
+            geometry: arcs.object(shape),
+            id,
+            properties,
+        })
+        .collect();
+    let topology = Topology {
+        type_name: "Topology",
+        bbox: has_bbox.then_some(bbox),
+        transform,
+        objects: IndexMap::from([(
+            options.object_name,
+            GeometryCollectionObject {
+                type_name: "GeometryCollection",
+                geometries,
+            },
+        )]),
+        arcs: arcs.finish(),
+    };
+
+    serde_json::to_writer(&mut writer, &topology)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// A topology, serialized with its members in the order of the specification.
+#[derive(Serialize)]
+struct Topology {
+    #[serde(rename = "type")]
+    type_name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bbox: Option<[f64; 4]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transform: Option<Value>,
+    objects: IndexMap<String, GeometryCollectionObject>,
+    arcs: Value,
+}
+
+#[derive(Serialize)]
+struct GeometryCollectionObject {
+    #[serde(rename = "type")]
+    type_name: &'static str,
+    geometries: Vec<GeometryObject>,
+}
+
+/// A geometry object of the collection, with the id and properties of its row.
+#[derive(Serialize)]
+struct GeometryObject {
+    #[serde(flatten)]
+    geometry: Map<String, Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<Box<RawValue>>,
+}
+
+type Position = [f64; 2];
+
+/// The positions of a geometry, before they are split into arcs.
+enum Shape {
+    Null,
+    Point(Position),
+    MultiPoint(Vec<Position>),
+    LineString(Vec<Position>),
+    MultiLineString(Vec<Vec<Position>>),
+    Polygon(Vec<Vec<Position>>),
+    MultiPolygon(Vec<Vec<Vec<Position>>>),
+    GeometryCollection(Vec<Shape>),
+}
+
+impl Shape {
+    /// The shape of each geometry of a native array, walking its coordinates directly.
+    fn from_array(array: &dyn NativeArray) -> Result<Vec<Self>> {
+        use NativeType::*;
+
+        match array.data_type() {
+            Point(_) => shapes(array.as_point(), |point| Ok(Self::point(&point))),
+            LineString(_) => shapes(array.as_line_string(), |line_string| {
+                Ok(Self::LineString(positions(&line_string)))
+            }),
+            Polygon(_) => shapes(array.as_polygon(), |polygon| {
+                Ok(Self::Polygon(rings(&polygon)))
+            }),
+            MultiPoint(_) => shapes(array.as_multi_point(), |multi_point| {
+                Ok(Self::multi_point(&multi_point))
+            }),
+            MultiLineString(_) => shapes(array.as_multi_line_string(), |multi_line_string| {
+                Ok(Self::multi_line_string(&multi_line_string))
+            }),
+            MultiPolygon(_) => shapes(array.as_multi_polygon(), |multi_polygon| {
+                Ok(Self::multi_polygon(&multi_polygon))
+            }),
+            GeometryCollection(_) => shapes(array.as_geometry_collection(), |collection| {
+                Self::geometry_collection(&collection)
+            }),
+            Geometry(_) => shapes(array.as_geometry(), |geometry| {
+                Self::from_geometry(&geometry)
+            }),
+            Rect(_) => shapes(array.as_rect(), |rect| Ok(Self::rect(&rect))),
+        }
+    }
+
+    fn point(point: &impl PointTrait<T = f64>) -> Self {
+        match point.coord() {
+            Some(coord) if !(coord.x().is_nan() && coord.y().is_nan()) => {
+                Self::Point(position(&coord))
+            }
+            _ => Self::Null,
+        }
+    }
+
+    fn multi_point(multi_point: &impl MultiPointTrait<T = f64>) -> Self {
+        Self::MultiPoint(
+            multi_point
+                .points()
+                .filter_map(|point| point.coord())
+                .map(|coord| position(&coord))
+                .collect(),
+        )
+    }
+
+    fn multi_line_string(multi_line_string: &impl MultiLineStringTrait<T = f64>) -> Self {
+        Self::MultiLineString(
+            multi_line_string
+                .line_strings()
+                .map(|line_string| positions(&line_string))
+                .collect(),
+        )
+    }
+
+    fn multi_polygon(multi_polygon: &impl MultiPolygonTrait<T = f64>) -> Self {
+        Self::MultiPolygon(
+            multi_polygon
+                .polygons()
+                .map(|polygon| rings(&polygon))
+                .collect(),
+        )
+    }
+
+    fn rect(rect: &impl RectTrait<T = f64>) -> Self {
+        let (min, max) = (position(&rect.min()), position(&rect.max()));
+        Self::Polygon(vec![vec![
+            min,
+            [max[0], min[1]],
+            max,
+            [min[0], max[1]],
+            min,
+        ]])
+    }
+
+    fn geometry_collection(collection: &impl GeometryCollectionTrait<T = f64>) -> Result<Self> {
+        Ok(Self::GeometryCollection(
+            collection
+                .geometries()
+                .map(|geometry| Self::from_geometry(&geometry))
+                .collect::<Result<_>>()?,
+        ))
+    }
+
+    fn from_geometry(geometry: &impl GeometryTrait<T = f64>) -> Result<Self> {
+        let shape = match geometry.as_type() {
+            GeometryType::Point(point) => Self::point(point),
+            GeometryType::MultiPoint(multi_point) => Self::multi_point(multi_point),
+            GeometryType::LineString(line_string) => Self::LineString(positions(line_string)),
+            GeometryType::MultiLineString(multi_line_string) => {
+                Self::multi_line_string(multi_line_string)
+            }
+            GeometryType::Polygon(polygon) => Self::Polygon(rings(polygon)),
+            GeometryType::MultiPolygon(multi_polygon) => Self::multi_polygon(multi_polygon),
+            GeometryType::GeometryCollection(collection) => Self::geometry_collection(collection)?,
+            GeometryType::Rect(rect) => Self::rect(rect),
+            _ => {
+                return Err(GeoArrowError::General(
+                    "TopoJSON can't represent triangles or lines".to_string(),
+                ));
+            }
+        };
+        Ok(shape)
+    }
+
+    fn for_each_position(&mut self, f: &mut impl FnMut(&mut Position)) {
+        match self {
+            Self::Null => {}
+            Self::Point(position) => f(position),
+            Self::MultiPoint(positions) | Self::LineString(positions) => {
+                positions.iter_mut().for_each(f)
+            }
+            Self::MultiLineString(lines) | Self::Polygon(lines) => {
+                lines.iter_mut().flatten().for_each(f)
+            }
+            Self::MultiPolygon(polygons) => polygons.iter_mut().flatten().flatten().for_each(f),
+            Self::GeometryCollection(shapes) => shapes
+                .iter_mut()
+                .for_each(|shape| shape.for_each_position(f)),
+        }
+    }
+
+    /// Call `f` with each line and whether it is a polygon ring.
+    fn for_each_line(&self, f: &mut impl FnMut(&[Position], bool)) {
+        match self {
+            Self::Null | Self::Point(_) | Self::MultiPoint(_) => {}
+            Self::LineString(line) => f(line, false),
+            Self::MultiLineString(lines) => lines.iter().for_each(|line| f(line, false)),
+            Self::Polygon(rings) => rings.iter().for_each(|ring| f(ring, true)),
+            Self::MultiPolygon(polygons) => {
+                polygons.iter().flatten().for_each(|ring| f(ring, true))
+            }
+            Self::GeometryCollection(shapes) => {
+                shapes.iter().for_each(|shape| shape.for_each_line(f))
+            }
+        }
+    }
+
+    /// Remove repeated positions from lines and rings, such as after quantization.
+    fn dedup(&mut self) {
+        fn dedup_line(line: &mut Vec<Position>) {
+            line.dedup();
+            // Arcs need at least two positions
+            if line.len() == 1 {
+                line.push(line[0]);
+            }
+        }
+
+        match self {
+            Self::Null | Self::Point(_) | Self::MultiPoint(_) => {}
+            Self::LineString(line) => dedup_line(line),
+            Self::MultiLineString(lines) | Self::Polygon(lines) => {
+                lines.iter_mut().for_each(dedup_line)
+            }
+            Self::MultiPolygon(polygons) => polygons.iter_mut().flatten().for_each(dedup_line),
+            Self::GeometryCollection(shapes) => shapes.iter_mut().for_each(Self::dedup),
+        }
+    }
+}
+
+/// The shape of each geometry of an array, or [`Shape::Null`] for nulls.
+fn shapes<'a, A: ArrayAccessor<'a>>(
+    array: &'a A,
+    shape: impl Fn(A::Item) -> Result<Shape>,
+) -> Result<Vec<Shape>> {
+    (0..array.len())
+        .map(|row| array.get(row).map_or(Ok(Shape::Null), &shape))
+        .collect()
+}
+
+fn position(coord: &impl CoordTrait<T = f64>) -> Position {
+    [coord.x(), coord.y()]
+}
+
+fn positions(line_string: &impl LineStringTrait<T = f64>) -> Vec<Position> {
+    line_string.coords().map(|coord| position(&coord)).collect()
+}
+
+fn rings(polygon: &impl PolygonTrait<T = f64>) -> Vec<Vec<Position>> {
+    polygon
+        .exterior()
+        .into_iter()
+        .map(|ring| positions(&ring))
+        .chain(polygon.interiors().map(|ring| positions(&ring)))
+        .collect()
+}
+
+/// A position compared by value, where `-0.0` equals `0.0`.
+type Key = (u64, u64);
+
+fn key(position: &Position) -> Key {
+    ((position[0] + 0.0).to_bits(), (position[1] + 0.0).to_bits())
+}
+
+/// The number of distinct positions of a ring, without its closing position.
+fn ring_len(ring: &[Position]) -> usize {
+    match (ring.first(), ring.last()) {
+        (Some(first), Some(last)) if ring.len() > 1 && key(first) == key(last) => ring.len() - 1,
+        _ => ring.len(),
+    }
+}
+
+/// Splits lines and rings into shared arcs.
+struct ArcBuilder {
+    /// The positions where lines and rings are split: the ends of lines, and positions that have
+    /// different neighbours in different lines or rings.
+    junctions: HashSet<Key>,
+    arcs: Vec<Vec<Position>>,
+    arc_indices: HashMap<Vec<Key>, i64>,
+    quantized: bool,
+}
+
+impl ArcBuilder {
+    fn new(shapes: &[Shape], quantized: bool) -> Self {
+        let mut junctions = HashSet::new();
+        let mut neighbours = HashMap::<Key, (Key, Key)>::new();
+        let mut visit = |junctions: &mut HashSet<Key>, position: Key, previous: Key, next: Key| {
+            let pair = if previous <= next {
+                (previous, next)
+            } else {
+                (next, previous)
+            };
+            if *neighbours.entry(position).or_insert(pair) != pair {
+                junctions.insert(position);
+            }
+        };
+        for shape in shapes {
+            shape.for_each_line(&mut |line, is_ring| {
+                if is_ring {
+                    let len = ring_len(line);
+                    for idx in 0..len {
+                        let previous = key(&line[(idx + len - 1) % len]);
+                        let next = key(&line[(idx + 1) % len]);
+                        visit(&mut junctions, key(&line[idx]), previous, next);
+                    }
+                } else if let (Some(first), Some(last)) = (line.first(), line.last()) {
+                    junctions.insert(key(first));
+                    junctions.insert(key(last));
+                    for window in line.windows(3) {
+                        visit(
+                            &mut junctions,
+                            key(&window[1]),
+                            key(&window[0]),
+                            key(&window[2]),
+                        );
+                    }
+                }
+            });
+        }
+        Self {
+            junctions,
+            arcs: vec![],
+            arc_indices: HashMap::new(),
+            quantized,
+        }
+    }
+
+    /// The index of an arc, where `!i` refers to arc `i` reversed.
+    fn arc(&mut self, positions: &[Position]) -> i64 {
+        let keys = positions.iter().map(key).collect::<Vec<_>>();
+        if let Some(idx) = self.arc_indices.get(&keys) {
+            return *idx;
+        }
+        let reversed = keys.iter().rev().copied().collect::<Vec<_>>();
+        if let Some(idx) = self.arc_indices.get(&reversed) {
+            return !*idx;
+        }
+        let idx = self.arcs.len() as i64;
+        self.arcs.push(positions.to_vec());
+        self.arc_indices.insert(keys, idx);
+        idx
+    }
+
+    /// Split a line at its junctions.
+    fn line(&mut self, line: &[Position]) -> Value {
+        let mut indices = vec![];
+        let mut start = 0;
+        for idx in 1..line.len().saturating_sub(1) {
+            if self.junctions.contains(&key(&line[idx])) {
+                indices.push(self.arc(&line[start..=idx]));
+                start = idx;
+            }
+        }
+        if !line.is_empty() {
+            indices.push(self.arc(&line[start..]));
+        }
+        json!(indices)
+    }
+
+    /// Split a ring at its junctions, starting from the first junction so that no arc wraps
+    /// around the ring's start.
+    fn ring(&mut self, ring: &[Position]) -> Value {
+        let ring = &ring[..ring_len(ring)];
+        if ring.is_empty() {
+            return json!([]);
+        }
+        // A ring without junctions starts from its least position, so that identical rings
+        // share an arc
+        let start = ring
+            .iter()
+            .position(|position| self.junctions.contains(&key(position)))
+            .or_else(|| (0..ring.len()).min_by_key(|idx| key(&ring[*idx])))
+            .unwrap_or_default();
+        let rotated = ring[start..]
+            .iter()
+            .chain(&ring[..=start])
+            .copied()
+            .collect::<Vec<_>>();
+        self.line(&rotated)
+    }
+
+    fn rings(&mut self, rings: &[Vec<Position>]) -> Value {
+        Value::Array(rings.iter().map(|ring| self.ring(ring)).collect())
+    }
+
+    fn position(&self, position: &Position) -> Value {
+        if self.quantized {
+            json!([position[0] as i64, position[1] as i64])
+        } else {
+            json!(position)
+        }
+    }
+
+    /// The TopoJSON geometry object of a shape.
+    fn object(&mut self, shape: &Shape) -> Map<String, Value> {
+        let (type_name, member, value) = match shape {
+            Shape::Null => {
+                let mut object = Map::new();
+                object.insert("type".to_string(), Value::Null);
+                return object;
+            }
+            Shape::Point(position) => ("Point", "coordinates", self.position(position)),
+            Shape::MultiPoint(positions) => (
+                "MultiPoint",
+                "coordinates",
+                positions
+                    .iter()
+                    .map(|position| self.position(position))
+                    .collect(),
+            ),
+            Shape::LineString(line) => ("LineString", "arcs", self.line(line)),
+            Shape::MultiLineString(lines) => (
+                "MultiLineString",
+                "arcs",
+                lines.iter().map(|line| self.line(line)).collect(),
+            ),
+            Shape::Polygon(rings) => ("Polygon", "arcs", self.rings(rings)),
+            Shape::MultiPolygon(polygons) => (
+                "MultiPolygon",
+                "arcs",
+                polygons.iter().map(|rings| self.rings(rings)).collect(),
+            ),
+            Shape::GeometryCollection(shapes) => (
+                "GeometryCollection",
+                "geometries",
+                shapes
+                    .iter()
+                    .map(|shape| Value::Object(self.object(shape)))
+                    .collect(),
+            ),
+        };
+        let mut object = Map::new();
+        object.insert("type".to_string(), type_name.into());
+        object.insert(member.to_string(), value);
+        object
+    }
+
+    /// The arcs of the topology, delta-encoded if quantized.
+    fn finish(self) -> Value {
+        self.arcs
+            .iter()
+            .map(|arc| {
+                if self.quantized {
+                    let mut previous = [0.0, 0.0];
+                    arc.iter()
+                        .map(|position| {
+                            let delta = [position[0] - previous[0], position[1] - previous[1]];
+                            previous = *position;
+                            json!([delta[0] as i64, delta[1] as i64])
+                        })
+                        .collect()
+                } else {
+                    json!(arc)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow_array::RecordBatch;
+    use arrow_array::cast::AsArray;
+    use arrow_schema::Schema;
+    use geo::polygon;
+    use geoarrow_schema::{CoordType, Dimension};
+
+    use super::*;
+    use crate::ArrayBase;
+    use crate::array::PolygonBuilder;
+    use crate::io::topojson::read_topojson;
+    use crate::io::wkb::to_wkb;
+    use crate::table::Table;
+    use crate::test::point;
+
+    fn write(table: &Table, options: TopoJsonWriterOptions) -> String {
+        let mut output = vec![];
+        write_topojson(table, &mut output, options).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn shared_arcs() {
+        let left = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let right = polygon![(x: 1., y: 0.), (x: 2., y: 0.), (x: 2., y: 1.), (x: 1., y: 1.)];
+        let geometry = PolygonBuilder::from_polygons(
+            &[left, right],
+            Dimension::XY,
+            CoordType::Interleaved,
+            Default::default(),
+        )
+        .finish();
+        let schema = Arc::new(Schema::new(vec![geometry.extension_field()]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![geometry.into_array_ref()]).unwrap();
+        let table = Table::try_new(vec![batch], schema).unwrap();
+
+        let output = write(&table, Default::default());
+        let topology: Value = serde_json::from_str(&output).unwrap();
+        // The shared edge is a single arc
+        assert_eq!(topology["arcs"].as_array().unwrap().len(), 3);
+        assert_eq!(topology["bbox"], json!([0.0, 0.0, 2.0, 1.0]));
+
+        let table: Table = read_topojson(output.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table.batches()[0];
+        let geometry =
+            NativeArrayDyn::from_arrow_array(batch.column(0).as_ref(), batch.schema().field(0))
+                .unwrap()
+                .into_inner();
+        let wkb_array = to_wkb::<i32>(geometry.as_ref());
+        for (row, expected) in [(0.0, 0.0), (1.0, 0.0)].into_iter().enumerate() {
+            let GeometryType::Polygon(polygon) = wkb_array.value(row).parse().unwrap().as_type()
+            else {
+                panic!("expected a polygon");
+            };
+            let mut coords = polygon
+                .exterior()
+                .unwrap()
+                .coords()
+                .map(|coord| (coord.x(), coord.y()))
+                .collect::<Vec<_>>();
+            assert_eq!(coords.len(), 5);
+            assert_eq!(coords.first(), coords.last());
+            coords.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!(coords[0], expected);
+        }
+    }
+
+    #[test]
+    fn quantized() {
+        let table = point::table();
+        let options = TopoJsonWriterOptions {
+            quantization: Some(3),
+            ..Default::default()
+        };
+        let output = write(&table, options);
+        let topology: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            topology["transform"],
+            json!({"scale": [1.0, 1.0], "translate": [0.0, 1.0]})
+        );
+        let geometries = &topology["objects"]["features"]["geometries"];
+        assert_eq!(geometries[2]["coordinates"], json!([2, 2]));
+
+        let table2: Table = read_topojson(output.as_bytes(), Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let batch = &table2.batches()[0];
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(
+            batch.column(1).as_string::<i32>().value(0),
+            table.batches()[0].column(1).as_string::<i32>().value(0)
+        );
+    }
+
+    #[test]
+    fn property_order() {
+        let output = write(&point::table(), Default::default());
+        // Properties keep the order of their columns rather than being sorted
+        assert!(output.contains(r#""properties":{"u8":"#));
+    }
+}
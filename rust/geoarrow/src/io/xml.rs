@@ -0,0 +1,16 @@
+//! Helpers shared by the XML-based readers and writers.
+
+use quick_xml::events::BytesStart;
+
+use crate::error::Result;
+
+/// The unescaped value of the attribute of an element with the given local name.
+pub(crate) fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        if attribute.key.local_name().as_ref() == name.as_bytes() {
+            return Ok(Some(attribute.unescape_value()?.into_owned()));
+        }
+    }
+    Ok(None)
+}
@@ -24,7 +24,7 @@
 //!
 //! // Writes that table to a cursor as JSON, then reads it back into a `serde_json::Value`.
 //! let mut cursor = Cursor::new(Vec::new());
-//! geoarrow::io::geojson::write_geojson(table, &mut cursor);
+//! geoarrow::io::geojson::write_geojson(table, &mut cursor, Default::default());
 //! let value: serde_json::Value = serde_json::from_slice(&cursor.into_inner()).unwrap();
 //! # }
 //! ```